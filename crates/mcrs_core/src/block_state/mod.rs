@@ -6,7 +6,7 @@ use std::marker::PhantomData;
 /// property names and property value names).
 ///
 /// Wraps `&'static str` for zero-cost static usage.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct PropertyStr(&'static str);
 
 impl PropertyStr {
@@ -5,7 +5,7 @@ pub mod state;
 pub mod tag;
 pub mod voxel_shape;
 
-pub use registry::{PackSource, RegistryAccess, RegistrySnapshot, RegistrySnapshotErased, ResourceKey, SnapshotEntry, StaticId, StaticRegistry};
+pub use registry::{FrozenRegistry, PackSource, RegistryAccess, RegistrySnapshot, RegistrySnapshotErased, ResourceKey, SnapshotEntry, StaticId, StaticRegistry};
 pub use resource_location::ResourceLocation;
 pub use state::AppState;
 pub use tag::{DynRegistryIndex, DynTagRegistry, IdBitSet, RawBitSet, TagEntry, TagFile, TagFileLoader, TagFileSettings, TagKey, TagRef, TagRegistry, TaggedRegistry};
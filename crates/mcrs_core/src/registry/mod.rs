@@ -6,4 +6,4 @@ pub mod static_registry;
 pub use access::{ErasedEntry, ErasedRegistrySnapshot, PackSource, RegistryAccess, RegistrySnapshotErased};
 pub use resource_key::ResourceKey;
 pub use snapshot::{RegistrySnapshot, SnapshotEntry};
-pub use static_registry::{StaticId, StaticRegistry};
+pub use static_registry::{FrozenRegistry, StaticId, StaticRegistry};
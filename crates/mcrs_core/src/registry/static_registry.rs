@@ -2,6 +2,7 @@ use crate::resource_location::ResourceLocation;
 use bevy_ecs::resource::Resource;
 use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::ops::Deref;
 use std::sync::Arc;
 
 /// A typed index into a `StaticRegistry<T>`.
@@ -143,6 +144,15 @@ impl<T: 'static> StaticRegistry<T> {
         tracing::info!(count = self.entries.len(), "frozen StaticRegistry");
     }
 
+    /// Consume the registry, freezing it first if needed. The result has no
+    /// way to register anything, so the ids handed to clients can't shift.
+    pub fn into_frozen(mut self) -> FrozenRegistry<T> {
+        if !self.frozen {
+            self.freeze();
+        }
+        FrozenRegistry(Arc::new(self))
+    }
+
     pub fn id_of_value(&self, value: &'static T) -> Option<StaticId<T>> {
         self.reverse
             .get(&(value as *const T as usize))
@@ -175,6 +185,28 @@ impl<T: 'static> Default for StaticRegistry<T> {
     }
 }
 
+/// A [`StaticRegistry`] after [`into_frozen`](StaticRegistry::into_frozen).
+///
+/// Read-only: it derefs to the registry, but only `&self` lookups are
+/// reachable, so every [`StaticId`] stays valid for good. Clones share one
+/// allocation.
+#[derive(Resource)]
+pub struct FrozenRegistry<T: 'static>(Arc<StaticRegistry<T>>);
+
+impl<T: 'static> Clone for FrozenRegistry<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: 'static> Deref for FrozenRegistry<T> {
+    type Target = StaticRegistry<T>;
+
+    fn deref(&self) -> &StaticRegistry<T> {
+        &self.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,6 +297,33 @@ mod tests {
         assert_eq!(items[2].2.0, 3);
     }
 
+    #[test]
+    fn test_into_frozen_resolves_by_id_and_loc() {
+        let reg = make_registry();
+        let id_b = reg.id_of("minecraft:b").unwrap();
+        let frozen = reg.into_frozen();
+        assert!(frozen.frozen());
+        assert_eq!(frozen.len(), 3);
+        assert_eq!(frozen.get_by_id(id_b).unwrap().0, 2);
+        assert_eq!(frozen.get_by_loc("minecraft:c").unwrap().0, 3);
+        assert_eq!(frozen.id_of("minecraft:a").unwrap().raw(), 0);
+        assert_eq!(frozen.id_of_value(&DUMMY_C).unwrap().raw(), 2);
+        assert!(frozen.get_by_loc("minecraft:unknown").is_none());
+    }
+
+    #[test]
+    fn test_frozen_clone_shares_storage() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FrozenRegistry<Dummy>>();
+
+        let mut reg = make_registry();
+        reg.freeze();
+        let frozen = reg.into_frozen();
+        let copy = frozen.clone();
+        assert!(Arc::ptr_eq(&frozen.0, &copy.0));
+        assert_eq!(copy.get_by_loc("minecraft:a").unwrap().0, 1);
+    }
+
     #[test]
     fn test_frozen_returns_false_before_freeze() {
         let reg = StaticRegistry::<Dummy>::new();
@@ -1,5 +1,5 @@
 use crate::resource_location::ResourceLocation;
-use crate::tag::file::{TagEntry, TagFile, TagFileSettings};
+use crate::tag::file::{TagFile, TagFileSettings};
 use crate::tag::key::{TagKey, TaggedRegistry};
 use bevy_asset::{AssetServer, Assets, Handle};
 use bevy_ecs::resource::Resource;
@@ -314,34 +314,23 @@ impl<T: TaggedRegistry + 'static> DynTagRegistry<T> {
 }
 
 /// Recursively expand a `TagFile` into a set of `u32` IDs using a
-/// `DynRegistryIndex` for element resolution.
+/// `DynRegistryIndex` for element resolution. Cyclic tag references are
+/// broken; see [`TagFile::visit_elements`].
 pub fn resolve_dyn_tag_file<T: TaggedRegistry>(
     tag_file: &TagFile,
     all_files: &Assets<TagFile>,
     index: &DynRegistryIndex<T>,
 ) -> HashSet<u32> {
     let mut out = HashSet::new();
-    for entry in &tag_file.values {
-        match entry {
-            TagEntry::Element(loc) => {
-                if let Some(id) = index.get(loc.as_str()) {
-                    out.insert(id);
-                } else {
-                    tracing::warn!("tag references unknown dynamic registry entry: {}", loc.as_str());
-                }
-            }
-            TagEntry::OptionalElement(loc) => {
-                if let Some(id) = index.get(loc.as_str()) {
-                    out.insert(id);
-                }
-            }
-            TagEntry::Tag(h) | TagEntry::OptionalTag(h) => {
-                if let Some(nested) = all_files.get(h) {
-                    out.extend(resolve_dyn_tag_file::<T>(nested, all_files, index));
-                }
-            }
+    tag_file.visit_elements(all_files, |loc, required| match index.get(loc.as_str()) {
+        Some(id) => {
+            out.insert(id);
         }
-    }
+        None if required => {
+            tracing::warn!("tag references unknown dynamic registry entry: {loc}");
+        }
+        None => {}
+    });
     out
 }
 
@@ -349,6 +338,7 @@ pub fn resolve_dyn_tag_file<T: TaggedRegistry>(
 mod tests {
     use super::*;
     use crate::resource_location::ResourceLocation;
+    use crate::tag::file::TagEntry;
     use crate::tag::key::TaggedRegistry;
 
     struct TestBiome;
@@ -1,6 +1,9 @@
 use crate::resource_location::ResourceLocation;
 use bevy_asset::io::Reader;
-use bevy_asset::{Asset, AssetLoader, Handle, LoadContext, UntypedAssetId, VisitAssetDependencies};
+use bevy_asset::{
+    Asset, AssetId, AssetLoader, Assets, Handle, LoadContext, UntypedAssetId,
+    VisitAssetDependencies,
+};
 use bevy_reflect::TypePath;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::str::FromStr;
@@ -30,6 +33,52 @@ pub struct TagFile {
 
 impl Asset for TagFile {}
 
+impl TagFile {
+    /// Visit every element of this tag with whether it is required, expanding
+    /// nested `#tag` references through `all_files`. Nested tags that are not
+    /// loaded are skipped.
+    ///
+    /// A reference back to a tag already being expanded would recurse
+    /// forever; it is skipped with a warning, so a cycle contributes each of
+    /// its members' elements once.
+    pub fn visit_elements(
+        &self,
+        all_files: &Assets<TagFile>,
+        mut visit: impl FnMut(&ResourceLocation, bool),
+    ) {
+        self.visit_elements_inner(all_files, &mut Vec::new(), &mut visit);
+    }
+
+    fn visit_elements_inner(
+        &self,
+        all_files: &Assets<TagFile>,
+        expanding: &mut Vec<AssetId<TagFile>>,
+        visit: &mut dyn FnMut(&ResourceLocation, bool),
+    ) {
+        for entry in &self.values {
+            match entry {
+                TagEntry::Element(loc) => visit(loc, true),
+                TagEntry::OptionalElement(loc) => visit(loc, false),
+                TagEntry::Tag(h) | TagEntry::OptionalTag(h) => {
+                    let id = h.id();
+                    if expanding.contains(&id) {
+                        tracing::warn!(
+                            path = ?h.path(),
+                            "tag includes itself; skipping the cyclic reference"
+                        );
+                        continue;
+                    }
+                    if let Some(nested) = all_files.get(id) {
+                        expanding.push(id);
+                        nested.visit_elements_inner(all_files, expanding, visit);
+                        expanding.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
 impl VisitAssetDependencies for TagFile {
     fn visit_dependencies(&self, visit: &mut impl FnMut(UntypedAssetId)) {
         for entry in &self.values {
@@ -1,7 +1,7 @@
 use crate::registry::{StaticId, StaticRegistry};
 use crate::resource_location::ResourceLocation;
 use crate::tag::bitset::IdBitSet;
-use crate::tag::file::{TagEntry, TagFile, TagFileSettings};
+use crate::tag::file::{TagFile, TagFileSettings};
 use crate::tag::key::{TagKey, TaggedRegistry};
 use bevy_asset::{AssetServer, Assets, Handle};
 use bevy_ecs::resource::Resource;
@@ -223,33 +223,24 @@ impl<T: TaggedRegistry + 'static> TagRegistry<T> {
     ///
     /// Resolves `#tag` references by following nested tag file handles,
     /// and plain element references by looking up the static registry.
+    /// Cyclic tag references are broken; see [`TagFile::visit_elements`].
     pub fn resolve_tag_file(
         tag_file: &TagFile,
         all_files: &Assets<TagFile>,
         registry: &StaticRegistry<T>,
     ) -> HashSet<StaticId<T>> {
         let mut out = HashSet::new();
-        for entry in &tag_file.values {
-            match entry {
-                TagEntry::Element(loc) => {
-                    if let Some(id) = registry.id_of(loc.as_str()) {
-                        out.insert(id);
-                    } else {
-                        tracing::warn!("tag references unknown registry entry: {loc}");
-                    }
+        tag_file.visit_elements(all_files, |loc, required| {
+            match registry.id_of(loc.as_str()) {
+                Some(id) => {
+                    out.insert(id);
                 }
-                TagEntry::OptionalElement(loc) => {
-                    if let Some(id) = registry.id_of(loc.as_str()) {
-                        out.insert(id);
-                    }
-                }
-                TagEntry::Tag(h) | TagEntry::OptionalTag(h) => {
-                    if let Some(nested) = all_files.get(h) {
-                        out.extend(Self::resolve_tag_file(nested, all_files, registry));
-                    }
+                None if required => {
+                    tracing::warn!("tag references unknown registry entry: {loc}");
                 }
+                None => {}
             }
-        }
+        });
         out
     }
 
@@ -275,6 +266,7 @@ impl<T: TaggedRegistry + 'static> TagRegistry<T> {
 mod tests {
     use super::*;
     use crate::registry::StaticId;
+    use crate::tag::file::TagEntry;
     use crate::tag::key::TaggedRegistry;
 
     /// Dummy registry element type for testing.
@@ -539,4 +531,58 @@ mod tests {
             );
         }
     }
+
+    // ── resolve_tag_file ──
+
+    static OAK_LOG: TestBlock = TestBlock;
+    static BIRCH_LOG: TestBlock = TestBlock;
+    static STONE: TestBlock = TestBlock;
+
+    fn block_registry() -> StaticRegistry<TestBlock> {
+        let mut registry = StaticRegistry::new();
+        registry.register(rl_arc("minecraft:oak_log"), &OAK_LOG);
+        registry.register(rl_arc("minecraft:birch_log"), &BIRCH_LOG);
+        registry.register(rl_arc("minecraft:stone"), &STONE);
+        registry
+    }
+
+    fn element(s: &str) -> TagEntry {
+        TagEntry::Element(rl_arc(s))
+    }
+
+    fn tag_file(values: Vec<TagEntry>) -> TagFile {
+        TagFile {
+            replace: false,
+            values,
+        }
+    }
+
+    #[test]
+    fn resolve_tag_file_expands_nested_tags() {
+        let registry = block_registry();
+        let mut files = Assets::<TagFile>::default();
+        let oak_logs = files.add(tag_file(vec![element("minecraft:oak_log")]));
+        let logs = tag_file(vec![
+            TagEntry::Tag(oak_logs),
+            element("minecraft:birch_log"),
+        ]);
+
+        let ids = TagRegistry::resolve_tag_file(&logs, &files, &registry);
+        assert_eq!(ids, HashSet::from([id(0), id(1)]));
+    }
+
+    #[test]
+    fn resolve_tag_file_breaks_cycles() {
+        let registry = block_registry();
+        let mut files = Assets::<TagFile>::default();
+        let a = files.add(tag_file(vec![element("minecraft:oak_log")]));
+        let b = files.add(tag_file(vec![
+            TagEntry::Tag(a.clone()),
+            element("minecraft:stone"),
+        ]));
+        files.get_mut(&a).unwrap().values.push(TagEntry::Tag(b));
+
+        let ids = TagRegistry::resolve_tag_file(files.get(&a).unwrap(), &files, &registry);
+        assert_eq!(ids, HashSet::from([id(0), id(2)]));
+    }
 }
@@ -1,5 +1,7 @@
 use crate::entity::physics::{OldTransform, Transform};
 use crate::entity::player::Player;
+use crate::entity::player::chunk_view::PlayerViewDistance;
+use crate::geometry::ColumnPos;
 use crate::world::chunk::{Chunk, ChunkIndex, ChunkPos};
 use crate::world::dimension::{Dimension, DimensionPlayers, InDimension, OldInDimension};
 use bevy_app::{App, FixedPostUpdate, FixedPreUpdate, FixedUpdate, Plugin};
@@ -10,6 +12,8 @@ use bevy_ecs::prelude::{
     IntoScheduleConfigs, Local, On, ParallelCommands, Query, Ref, With, Without,
 };
 use bevy_ecs::relationship::RelationshipSourceCollection;
+use bevy_ecs::schedule::SystemSet;
+use bevy_math::DVec3;
 use std::time::Instant;
 
 pub mod despawn;
@@ -24,7 +28,10 @@ impl Plugin for EntityPlugin {
             FixedPreUpdate,
             (add_entity_to_chunk, add_player_synced_entities),
         );
-        app.add_systems(FixedUpdate, (tick_chunk_entities, sync_entities));
+        app.add_systems(
+            FixedUpdate,
+            (tick_chunk_entities, sync_entities.in_set(VisibilitySystem)),
+        );
         app.add_systems(
             FixedPostUpdate,
             (
@@ -44,8 +51,16 @@ impl Plugin for EntityPlugin {
 #[component(storage = "SparseSet")]
 pub struct EntityNetworkSync;
 
+/// Keeps every player's [`PlayerSynchronizedEntities`] in step with the
+/// entities in its view distance, triggering [`EntityNetworkAddEvent`] and
+/// [`EntityNetworkRemoveEvent`] as they come and go and
+/// [`EntityNetworkSyncEvent`] when a visible one moves.
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VisibilitySystem;
+
+/// The entities a player's client has been told to spawn.
 #[derive(Component, Default, Deref)]
-struct PlayerSynchronizedEntities(EntityHashSet);
+pub struct PlayerSynchronizedEntities(EntityHashSet);
 
 #[allow(dead_code)]
 #[derive(EntityEvent, Debug)]
@@ -321,7 +336,13 @@ fn add_player_synced_entities(
 fn sync_entities(
     dim_players: Query<&DimensionPlayers>,
     player_data: Query<
-        (&Transform, &PlayerSynchronizedEntities),
+        (
+            Entity,
+            &InDimension,
+            &Transform,
+            &PlayerViewDistance,
+            &PlayerSynchronizedEntities,
+        ),
         (With<Player>, Without<Despawned>),
     >,
     entities: Query<(
@@ -352,12 +373,19 @@ fn sync_entities(
                 if player == entity {
                     return;
                 }
-                let Ok((player_transform, synced_entities)) = player_data.get(player) else {
+                let Ok((_, _, player_transform, view_distance, synced_entities)) =
+                    player_data.get(player)
+                else {
                     return;
                 };
                 let has_spawned = synced_entities.contains(&entity);
-                let too_far =
-                    || (transform.translation - player_transform.translation).length() > 16.0;
+                let too_far = || {
+                    !in_view_distance(
+                        player_transform.translation,
+                        transform.translation,
+                        view_distance,
+                    )
+                };
 
                 if has_spawned {
                     if is_removed || too_far() {
@@ -369,7 +397,7 @@ fn sync_entities(
                             cmds.trigger(EntityNetworkSyncEvent { entity, player });
                         });
                     }
-                } else if !too_far() {
+                } else if !is_removed && !too_far() {
                     commands.command_scope(|mut cmds| {
                         cmds.trigger(EntityNetworkAddEvent { entity, player });
                     });
@@ -381,6 +409,32 @@ fn sync_entities(
                 });
             }
         });
+
+    // Entities despawned outright or moved to another dimension are never
+    // visited above, so drop them from every set still holding them.
+    player_data
+        .par_iter()
+        .for_each(|(player, player_dimension, _, _, synced_entities)| {
+            for &entity in synced_entities.iter() {
+                let same_dimension = entities
+                    .get(entity)
+                    .is_ok_and(|(_, in_dimension, ..)| in_dimension == player_dimension);
+                if !same_dimension {
+                    commands.command_scope(|mut cmds| {
+                        cmds.trigger(EntityNetworkRemoveEvent { entity, player });
+                    });
+                }
+            }
+        });
+}
+
+/// Whether `pos` is in a chunk column within `view_distance` of `viewer`,
+/// the same square the client keeps chunks loaded in.
+fn in_view_distance(viewer: DVec3, pos: DVec3, view_distance: &PlayerViewDistance) -> bool {
+    let viewer = ColumnPos::from(viewer);
+    let column = ColumnPos::from(pos);
+    let distance = (column.x - viewer.x).abs().max((column.z - viewer.z).abs());
+    distance <= view_distance.distance as i32
 }
 
 fn synced_entity_added(
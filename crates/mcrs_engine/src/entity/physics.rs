@@ -57,7 +57,8 @@ impl Default for Transform {
 #[derive(Copy, Clone, Debug, Component, Deref)]
 pub struct OldTransform(pub Transform);
 
-#[derive(Copy, Clone, Debug, Deref, DerefMut, Component)]
+/// Movement in blocks per tick.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deref, DerefMut, Component)]
 pub struct Velocity(pub DVec3);
 
 #[derive(Copy, Clone, Debug, Deref, DerefMut, Component)]
@@ -55,7 +55,11 @@ fn update_view(
             &PlayerViewDistance,
             &InDimension,
         ),
-        Or<(Changed<Transform>, Added<PlayerChunkObserver>)>,
+        Or<(
+            Changed<Transform>,
+            Changed<PlayerViewDistance>,
+            Added<PlayerChunkObserver>,
+        )>,
     >,
     dimensions: Query<&DimensionTypeConfig>,
     commands: ParallelCommands,
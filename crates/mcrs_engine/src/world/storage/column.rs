@@ -217,6 +217,43 @@ impl Heightmaps {
         self.motion_blocking.set(Self::index(x, z), rel as u32);
     }
 
+    /// Build both heightmaps by scanning each column top-down. `classify`
+    /// receives `(x, world_y, z)` and reports how that block counts; each
+    /// entry stores the Y just above the topmost matching block, and columns
+    /// with no match keep the `min_y` sentinel.
+    pub fn from_blocks(
+        height: u32,
+        min_y: i32,
+        mut classify: impl FnMut(usize, i32, usize) -> HeightmapBlock,
+    ) -> Self {
+        let mut heightmaps = Self::with_min_y(height, min_y);
+        let top = min_y + height as i32;
+        for z in 0..16 {
+            for x in 0..16 {
+                let mut surface_found = false;
+                for y in (min_y..top).rev() {
+                    match classify(x, y, z) {
+                        HeightmapBlock::Air => continue,
+                        HeightmapBlock::NonBlocking => {
+                            if !surface_found {
+                                heightmaps.surface_set(x, z, y + 1);
+                                surface_found = true;
+                            }
+                        }
+                        HeightmapBlock::MotionBlocking => {
+                            if !surface_found {
+                                heightmaps.surface_set(x, z, y + 1);
+                            }
+                            heightmaps.motion_blocking_set(x, z, y + 1);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        heightmaps
+    }
+
     pub fn to_long_array_surface(&self) -> &[u64] {
         self.world_surface.raw_longs()
     }
@@ -226,6 +263,16 @@ impl Heightmaps {
     }
 }
 
+/// How a block state counts toward [`Heightmaps::from_blocks`]: any non-air
+/// block raises `WORLD_SURFACE`, only motion-blocking ones raise
+/// `MOTION_BLOCKING`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeightmapBlock {
+    Air,
+    NonBlocking,
+    MotionBlocking,
+}
+
 // No `Default for Heightmaps`: every column ships with a dimension-shape
 // derived size via `Heightmaps::with_min_y`, and a 384-tall hardcoded default
 // would silently mis-size storage for the nether (height 256) or end (height
@@ -621,6 +668,40 @@ mod tests {
         assert_eq!(h.motion_blocking_get(15, 15), -64);
     }
 
+    #[test]
+    fn heightmap_from_blocks_flat_chunk() {
+        // Superflat layout: bedrock at -64, dirt -63..=-62, grass at -61, and
+        // a tall-grass plant at -60 over the (0, 0) column only.
+        let h = Heightmaps::from_blocks(384, -64, |x, y, z| match y {
+            -64..=-61 => HeightmapBlock::MotionBlocking,
+            -60 if x == 0 && z == 0 => HeightmapBlock::NonBlocking,
+            _ => HeightmapBlock::Air,
+        });
+        assert_eq!(h.surface_get(0, 0), -59);
+        assert_eq!(h.motion_blocking_get(0, 0), -60);
+        for (x, z) in [(1, 0), (15, 15), (7, 3)] {
+            assert_eq!(h.surface_get(x, z), -60);
+            assert_eq!(h.motion_blocking_get(x, z), -60);
+        }
+
+        // 9-bit entries, 7 per long, no entry spans a long boundary.
+        let motion = h.to_long_array_motion_blocking();
+        assert_eq!(motion.len(), 256usize.div_ceil(7));
+        let row = (0..7).fold(0u64, |acc, i| acc | (4u64 << (9 * i)));
+        assert_eq!(motion[0], row);
+        let tail = (0..4).fold(0u64, |acc, i| acc | (4u64 << (9 * i)));
+        assert_eq!(motion[36], tail);
+        let surface = h.to_long_array_surface();
+        assert_eq!(surface[0], (row & !0x1ff) | 5);
+    }
+
+    #[test]
+    fn heightmap_from_blocks_empty_column_keeps_sentinel() {
+        let h = Heightmaps::from_blocks(256, 0, |_, _, _| HeightmapBlock::Air);
+        assert_eq!(h.surface_get(3, 9), 0);
+        assert_eq!(h.motion_blocking_get(3, 9), 0);
+    }
+
     #[test]
     fn section_lookup_loaded() {
         let mut si = ColumnChunks::new(-4, 24);
@@ -37,6 +37,7 @@ use mcrs_minecraft_lighting::table::BlockStateLightTable;
 use mcrs_vanilla::biome::Biome;
 use mcrs_vanilla::block::Block;
 use mcrs_vanilla::enchantment::EnchantmentData;
+use mcrs_vanilla::item::Item;
 
 #[test]
 fn aoi_state_does_not_leak_across_dim_boundary() {
@@ -219,7 +220,9 @@ fn build_host_app() -> App {
     app.insert_resource(make_stub_block_light_table());
     app.insert_resource(StaticRegistry::<Block>::new());
     app.insert_resource(StaticRegistry::<EnchantmentData>::default());
+    app.insert_resource(StaticRegistry::<Item>::new());
     app.insert_resource(TagRegistry::<Block>::default());
+    app.insert_resource(TagRegistry::<Item>::default());
     app.insert_resource(RegistrySnapshot::<Biome>::default());
     app
 }
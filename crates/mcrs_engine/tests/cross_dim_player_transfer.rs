@@ -29,10 +29,11 @@ use bytes::Bytes;
 use mcrs_minecraft::world::bus::{
     InboundPlayerDespawn, InboundPlayerPacket, InboundPlayerSpawn, OutboundPlayerAttached,
     OutboundPlayerDisconnect, OutboundPlayerPacket, OutboundPlayerTransfer,
-    PendingInboundLifecycle, PendingInboundPartition, PlayerTransferSnapshot,
+    PendingInboundLifecycle, PendingInboundPartition, PlayerTransferSnapshot, SpawnReason,
 };
 use mcrs_minecraft::world::player_index::{PlayerIndex, PlayerLocation};
 use mcrs_minecraft::world::sub_app_builder::DimSubAppHandle;
+use mcrs_protocol::GameMode;
 use mcrs_protocol::uuid::Uuid;
 use smallvec::SmallVec;
 
@@ -112,6 +113,7 @@ fn synthetic_snapshot() -> PlayerTransferSnapshot {
         username: "transfer-test".into(),
         position: DVec3::new(1.0, 2.0, 3.0),
         rotation: Vec2::ZERO,
+        game_mode: GameMode::Survival,
     }
 }
 
@@ -400,6 +402,7 @@ fn arm_transfer(app: &mut App, host_anchor: Entity, dest_label: Entity) {
             host_anchor,
             dest_dim: dest_label,
             snapshot: synthetic_snapshot(),
+            reason: SpawnReason::DimensionChange,
         });
 }
 
@@ -49,6 +49,7 @@ mod harness {
     use mcrs_vanilla::biome::Biome;
     use mcrs_vanilla::block::Block;
     use mcrs_vanilla::enchantment::EnchantmentData;
+    use mcrs_vanilla::item::Item;
 
     pub fn make_stub_block_light_table() -> BlockStateLightTable {
         let state_count = 2usize;
@@ -83,7 +84,9 @@ mod harness {
         app.insert_resource(make_stub_block_light_table());
         app.insert_resource(StaticRegistry::<Block>::new());
         app.insert_resource(StaticRegistry::<EnchantmentData>::default());
+        app.insert_resource(StaticRegistry::<Item>::new());
         app.insert_resource(TagRegistry::<Block>::default());
+        app.insert_resource(TagRegistry::<Item>::default());
         app.insert_resource(RegistrySnapshot::<Biome>::default());
         app
     }
@@ -414,5 +417,6 @@ fn _synthetic_snapshot_compile_only() {
         username: "x".into(),
         position: DVec3::ZERO,
         rotation: Vec2::ZERO,
+        game_mode: mcrs_protocol::GameMode::Survival,
     };
 }
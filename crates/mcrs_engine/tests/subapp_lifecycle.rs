@@ -16,6 +16,7 @@ use mcrs_core::tag::TagRegistry;
 use mcrs_core::voxel_shape::VoxelShape;
 use mcrs_core::AppState;
 use mcrs_vanilla::enchantment::EnchantmentData;
+use mcrs_vanilla::item::Item;
 use mcrs_engine::world::dimension::{Dimension, DimensionId, DimensionTypeConfig};
 use mcrs_engine::world::sub_app::{
     DimAppLabel, DimDespawnQueue, DimSpawnQueue, DimSpawnRequest,
@@ -89,7 +90,9 @@ mod harness {
         app.insert_resource(make_stub_block_light_table());
         app.insert_resource(StaticRegistry::<Block>::new());
         app.insert_resource(StaticRegistry::<EnchantmentData>::default());
+        app.insert_resource(StaticRegistry::<Item>::new());
         app.insert_resource(TagRegistry::<Block>::default());
+        app.insert_resource(TagRegistry::<Item>::default());
         app.insert_resource(RegistrySnapshot::<Biome>::default());
 
         // The production extract closure in `spawn_dim_subapp` shuttles
@@ -2,6 +2,15 @@ use crate::sound::{Music, SoundEvent};
 use crate::weight::Weighted;
 use crate::sound::Holder;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Vanilla `BiomeManager.obfuscateSeed`: the first eight bytes of the
+/// SHA-256 of the world seed, both little-endian. Login and Respawn carry
+/// it so the client can blend biome colors without learning the seed.
+pub fn obfuscate_seed(seed: u64) -> i64 {
+    let digest = Sha256::digest(seed.to_le_bytes());
+    i64::from_le_bytes(digest[..8].try_into().unwrap())
+}
 
 #[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Biome {
@@ -0,0 +1,306 @@
+//! Boss bars shown at the top of the screen, for raids, events and the like.
+//! Each bar is sent only to its viewers: an Add when one starts watching, a
+//! Remove when one stops, and otherwise just the aspects that changed.
+
+use bevy_app::{App, FixedUpdate, Plugin};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::prelude::{Query, ResMut, With};
+use bevy_ecs::resource::Resource;
+use indexmap::{IndexMap, IndexSet};
+use mcrs_network::{InGameConnectionState, ServerSideConnection};
+use mcrs_protocol::boss_event::{BossBarColor, BossBarDivision, BossBarFlags, BossEventOperation};
+use mcrs_protocol::packets::game::clientbound::ClientboundBossEvent;
+use mcrs_protocol::uuid::Uuid;
+use mcrs_protocol::{Text, WritePacket};
+
+pub struct BossBarPlugin;
+
+impl Plugin for BossBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BossBars>();
+        app.add_systems(FixedUpdate, broadcast_boss_bars);
+    }
+}
+
+/// Every boss bar on the server, by id.
+#[derive(Resource, Debug, Default)]
+pub struct BossBars {
+    bars: IndexMap<Uuid, BossBar>,
+    /// Bars removed since the last broadcast, with the viewers still showing
+    /// them.
+    removed: Vec<(Uuid, IndexSet<Entity>)>,
+}
+
+impl BossBars {
+    /// Add `bar` under a fresh id.
+    pub fn insert(&mut self, bar: BossBar) -> Uuid {
+        let id = Uuid::new_v4();
+        self.bars.insert(id, bar);
+        id
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<&BossBar> {
+        self.bars.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: Uuid) -> Option<&mut BossBar> {
+        self.bars.get_mut(&id)
+    }
+
+    /// Drop the bar, hiding it from everyone who was shown it.
+    pub fn remove(&mut self, id: Uuid) -> Option<BossBar> {
+        let bar = self.bars.shift_remove(&id)?;
+        let shown: IndexSet<Entity> = bar
+            .viewers
+            .difference(&bar.added)
+            .chain(&bar.removed)
+            .copied()
+            .collect();
+        if !shown.is_empty() {
+            self.removed.push((id, shown));
+        }
+        Some(bar)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Uuid, &BossBar)> {
+        self.bars.iter().map(|(&id, bar)| (id, bar))
+    }
+}
+
+/// One bar and the connections it is shown to. A setter that changes the
+/// title, progress, style or flags has only that aspect resent to the
+/// viewers on the next broadcast.
+#[derive(Clone, Debug)]
+pub struct BossBar {
+    title: Text,
+    progress: f32,
+    color: BossBarColor,
+    division: BossBarDivision,
+    flags: BossBarFlags,
+    viewers: IndexSet<Entity>,
+    /// Viewers not sent the Add yet.
+    added: IndexSet<Entity>,
+    /// Viewers to send a Remove to.
+    removed: IndexSet<Entity>,
+    pending: Vec<BossBarUpdate>,
+}
+
+/// An aspect of the bar its viewers have not been told about yet. The packet
+/// carries the value at broadcast time, so repeated changes within a tick go
+/// out once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BossBarUpdate {
+    Progress,
+    Title,
+    Style,
+    Properties,
+}
+
+impl BossBar {
+    pub fn new(title: impl Into<Text>) -> Self {
+        Self {
+            title: title.into(),
+            progress: 1.0,
+            color: BossBarColor::default(),
+            division: BossBarDivision::default(),
+            flags: BossBarFlags::new(),
+            viewers: IndexSet::new(),
+            added: IndexSet::new(),
+            removed: IndexSet::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn with_progress(mut self, progress: f32) -> Self {
+        self.progress = progress.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_style(mut self, color: BossBarColor, division: BossBarDivision) -> Self {
+        self.color = color;
+        self.division = division;
+        self
+    }
+
+    pub fn with_flags(mut self, flags: BossBarFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// How full the bar is, from 0.0 to 1.0.
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+
+    /// Change how full the bar is, clamped to 0.0..=1.0.
+    pub fn set_progress(&mut self, progress: f32) {
+        let progress = progress.clamp(0.0, 1.0);
+        if self.progress != progress {
+            self.progress = progress;
+            self.mark(BossBarUpdate::Progress);
+        }
+    }
+
+    pub fn title(&self) -> &Text {
+        &self.title
+    }
+
+    pub fn set_title(&mut self, title: impl Into<Text>) {
+        let title = title.into();
+        if self.title != title {
+            self.title = title;
+            self.mark(BossBarUpdate::Title);
+        }
+    }
+
+    pub fn color(&self) -> BossBarColor {
+        self.color
+    }
+
+    pub fn division(&self) -> BossBarDivision {
+        self.division
+    }
+
+    pub fn set_style(&mut self, color: BossBarColor, division: BossBarDivision) {
+        if (self.color, self.division) != (color, division) {
+            self.color = color;
+            self.division = division;
+            self.mark(BossBarUpdate::Style);
+        }
+    }
+
+    pub fn flags(&self) -> BossBarFlags {
+        self.flags
+    }
+
+    pub fn set_flags(&mut self, flags: BossBarFlags) {
+        if self.flags != flags {
+            self.flags = flags;
+            self.mark(BossBarUpdate::Properties);
+        }
+    }
+
+    /// Show the bar to the connection `viewer`. Returns `false` if it already
+    /// sees it.
+    pub fn add_viewer(&mut self, viewer: Entity) -> bool {
+        if !self.viewers.insert(viewer) {
+            return false;
+        }
+        // Still on screen from before: no Add needed, just cancel the Remove.
+        if !self.removed.shift_remove(&viewer) {
+            self.added.insert(viewer);
+        }
+        true
+    }
+
+    /// Hide the bar from `viewer`. Returns `false` if it did not see it.
+    pub fn remove_viewer(&mut self, viewer: Entity) -> bool {
+        if !self.viewers.shift_remove(&viewer) {
+            return false;
+        }
+        if !self.added.shift_remove(&viewer) {
+            self.removed.insert(viewer);
+        }
+        true
+    }
+
+    pub fn viewers(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.viewers.iter().copied()
+    }
+
+    fn mark(&mut self, update: BossBarUpdate) {
+        if !self.pending.contains(&update) {
+            self.pending.push(update);
+        }
+    }
+
+    /// The Add operation for the current state, for viewers who just started
+    /// watching.
+    pub fn add_operation(&self) -> BossEventOperation {
+        BossEventOperation::Add {
+            title: self.title.clone(),
+            progress: self.progress,
+            color: self.color,
+            division: self.division,
+            flags: self.flags,
+        }
+    }
+
+    fn update_operation(&self, update: BossBarUpdate) -> BossEventOperation {
+        match update {
+            BossBarUpdate::Progress => BossEventOperation::UpdateProgress(self.progress),
+            BossBarUpdate::Title => BossEventOperation::UpdateTitle(self.title.clone()),
+            BossBarUpdate::Style => BossEventOperation::UpdateStyle {
+                color: self.color,
+                division: self.division,
+            },
+            BossBarUpdate::Properties => BossEventOperation::UpdateProperties(self.flags),
+        }
+    }
+}
+
+/// Send each bar's changes since the last tick to its viewers: Removes first,
+/// then the changed aspects to viewers who already show it, then the full
+/// bar to new viewers. Viewers whose connection closed are dropped.
+pub fn broadcast_boss_bars(
+    mut bars: ResMut<BossBars>,
+    mut connections: Query<&mut ServerSideConnection, With<InGameConnectionState>>,
+    connected: Query<(), With<ServerSideConnection>>,
+) {
+    let bars = &mut *bars;
+    for (id, viewers) in bars.removed.drain(..) {
+        let remove = ClientboundBossEvent {
+            id,
+            operation: BossEventOperation::Remove,
+        };
+        for viewer in viewers {
+            if let Ok(mut con) = connections.get_mut(viewer) {
+                con.write_packet(&remove);
+            }
+        }
+    }
+
+    for (&id, bar) in &mut bars.bars {
+        bar.viewers.retain(|&viewer| connected.contains(viewer));
+
+        let remove = ClientboundBossEvent {
+            id,
+            operation: BossEventOperation::Remove,
+        };
+        for viewer in std::mem::take(&mut bar.removed) {
+            if let Ok(mut con) = connections.get_mut(viewer) {
+                con.write_packet(&remove);
+            }
+        }
+
+        let added = std::mem::take(&mut bar.added);
+        let updates: Vec<ClientboundBossEvent> = std::mem::take(&mut bar.pending)
+            .into_iter()
+            .map(|update| ClientboundBossEvent {
+                id,
+                operation: bar.update_operation(update),
+            })
+            .collect();
+        if !updates.is_empty() {
+            for &viewer in bar.viewers.difference(&added) {
+                if let Ok(mut con) = connections.get_mut(viewer) {
+                    for update in &updates {
+                        con.write_packet(update);
+                    }
+                }
+            }
+        }
+
+        if !added.is_empty() {
+            let add = ClientboundBossEvent {
+                id,
+                operation: bar.add_operation(),
+            };
+            for viewer in added {
+                if let Ok(mut con) = connections.get_mut(viewer) {
+                    con.write_packet(&add);
+                }
+            }
+        }
+    }
+}
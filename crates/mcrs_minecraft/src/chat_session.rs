@@ -0,0 +1,619 @@
+//! Secure chat: whether the server insists on signed chat, the chat session a
+//! client announces with Chat Session Update, and the last-seen message window
+//! a signed client acknowledges with every message.
+
+use crate::login::GameProfile;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use bevy_app::{App, Plugin};
+use bevy_ecs::prelude::{Commands, Component, On, Query, Res, Resource};
+use mcrs_network::event::ReceivedPacketEvent;
+use mcrs_network::{ConnectionState, ServerSideConnection};
+use mcrs_protocol::Text;
+use mcrs_protocol::entity::player::MessageSignature;
+use mcrs_protocol::packets::game::serverbound::ServerboundChatSessionUpdate;
+use mcrs_protocol::uuid::Uuid;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::{Pkcs1v15Sign, RsaPublicKey};
+use serde::Deserialize;
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use tracing::warn;
+
+/// How many messages a client tracks as "last seen", vanilla
+/// `LastSeenMessages.LAST_SEEN_MESSAGES_MAX_LENGTH`.
+pub const LAST_SEEN_MESSAGES: usize = 20;
+
+/// Where Mojang publishes the keys profile keys are signed with.
+pub const SERVICES_PUBLIC_KEYS_URL: &str = "https://api.minecraftservices.com/publickeys";
+
+/// The DER `DigestInfo` a PKCS#1 v1.5 signature wraps a SHA-1 hash in.
+const SHA1_DIGEST_INFO: [u8; 15] = [
+    0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14,
+];
+/// The DER `DigestInfo` a PKCS#1 v1.5 signature wraps a SHA-256 hash in.
+const SHA256_DIGEST_INFO: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+
+pub struct ChatSessionPlugin;
+
+impl Plugin for ChatSessionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChatConfig>();
+        // Like vanilla, the keys are fetched once at startup.
+        if app.world().resource::<ChatConfig>().signing == ChatSigning::RequireSigned
+            && !app.world().contains_resource::<ServicesKeys>()
+        {
+            match ServicesKeys::fetch(SERVICES_PUBLIC_KEYS_URL) {
+                Ok(keys) => {
+                    app.insert_resource(keys);
+                }
+                Err(e) => warn!("chat sessions will be ignored: {e}"),
+            }
+        }
+        app.add_observer(update_chat_session);
+    }
+}
+
+/// Insert before [`ChatSessionPlugin`] to override the defaults.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ChatConfig {
+    pub signing: ChatSigning,
+}
+
+/// Whether chat must come from a client with a valid chat session, like
+/// vanilla's `enforce-secure-profile`. Not to be confused with the client's
+/// own chat visibility setting, `mcrs_protocol::setting::ChatMode`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChatSigning {
+    /// Only messages signed with the key of an unexpired session Mojang
+    /// vouched for are accepted, and the client is told so at login.
+    RequireSigned,
+    /// Any message is accepted and rebroadcast as system chat, so it is never
+    /// reportable.
+    #[default]
+    AllowUnsigned,
+}
+
+impl ChatSigning {
+    /// The `enforces_secure_chat` flag sent with the play Login packet.
+    pub fn enforces_secure_chat(self) -> bool {
+        self == Self::RequireSigned
+    }
+}
+
+/// Mojang's keys that sign player profile keys, vanilla `ServicesKeySet` for
+/// `ServicesKeyType.PROFILE_KEY`. Fetched at startup when chat has to be
+/// signed; insert before [`ChatSessionPlugin`] to supply them instead.
+/// Without it no chat session is trusted, so no chat is accepted as signed.
+#[derive(Resource, Debug, Clone)]
+pub struct ServicesKeys {
+    profile_keys: Vec<RsaPublicKey>,
+}
+
+#[derive(Debug, Error)]
+pub enum ServicesKeyError {
+    #[error("services key request failed: {0}")]
+    Request(#[from] ureq::Error),
+    #[error("malformed services key response: {0}")]
+    Response(#[from] serde_json::Error),
+    #[error("services key is not base64: {0}")]
+    Base64(#[from] base64::DecodeError),
+    #[error("services key is not an X.509 RSA key: {0}")]
+    Key(#[from] rsa::pkcs8::spki::Error),
+}
+
+/// A `publickeys` response body; only the keys profile keys are signed with
+/// are kept.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicKeysResponse {
+    player_certificate_keys: Vec<ServicesKeyInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServicesKeyInfo {
+    /// Base64 of the X.509 DER key.
+    public_key: String,
+}
+
+impl ServicesKeys {
+    pub fn new(profile_keys: Vec<RsaPublicKey>) -> Self {
+        Self { profile_keys }
+    }
+
+    /// Ask `url` for Mojang's current keys.
+    pub fn fetch(url: &str) -> Result<Self, ServicesKeyError> {
+        let body = ureq::get(url).call()?.body_mut().read_to_string()?;
+        Self::from_json(&body)
+    }
+
+    /// The keys in a `publickeys` response body.
+    pub fn from_json(body: &str) -> Result<Self, ServicesKeyError> {
+        let response: PublicKeysResponse = serde_json::from_str(body)?;
+        let profile_keys = response
+            .player_certificate_keys
+            .iter()
+            .map(|info| {
+                let der = BASE64_STANDARD.decode(&info.public_key)?;
+                Ok(RsaPublicKey::from_public_key_der(&der)?)
+            })
+            .collect::<Result<_, ServicesKeyError>>()?;
+        Ok(Self::new(profile_keys))
+    }
+
+    /// Whether any of the keys made `signature` over `payload`, a
+    /// `SHA1withRSA` signature like vanilla's `ServicesKeyInfo.signature`.
+    pub fn verify(&self, payload: &[u8], signature: &[u8]) -> bool {
+        let hash = Sha1::digest(payload);
+        self.profile_keys.iter().any(|key| {
+            key.verify(pkcs1v15(&SHA1_DIGEST_INFO, 20), &hash, signature)
+                .is_ok()
+        })
+    }
+}
+
+fn pkcs1v15(digest_info: &[u8], hash_len: usize) -> Pkcs1v15Sign {
+    Pkcs1v15Sign {
+        hash_len: Some(hash_len),
+        prefix: digest_info.into(),
+    }
+}
+
+/// The profile key a client chats with, from a Chat Session Update whose key
+/// Mojang signed.
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct ChatSession {
+    pub session_id: Uuid,
+    /// Key expiry, in milliseconds since the Unix epoch.
+    pub expires_at: i64,
+    pub public_key: RsaPublicKey,
+}
+
+/// Why a Chat Session Update was refused.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ChatSessionError {
+    #[error("profile key expired")]
+    Expired,
+    #[error("profile key is not an X.509 RSA key")]
+    InvalidKey,
+    #[error("profile key is not signed by Mojang")]
+    InvalidKeySignature,
+}
+
+impl ChatSessionError {
+    pub fn translation_key(&self) -> &'static str {
+        match self {
+            Self::Expired => "multiplayer.disconnect.expired_public_key",
+            Self::InvalidKey | Self::InvalidKeySignature => {
+                "multiplayer.disconnect.invalid_public_key_signature.new"
+            }
+        }
+    }
+}
+
+impl ChatSession {
+    /// The session `pkt` announces for the player `profile_id`, once its key
+    /// is checked to be unexpired and signed by Mojang, like vanilla
+    /// `RemoteChatSession.Data.validate`.
+    pub fn validate(
+        profile_id: Uuid,
+        pkt: &ServerboundChatSessionUpdate,
+        services_keys: &ServicesKeys,
+        now_millis: i64,
+    ) -> Result<Self, ChatSessionError> {
+        if pkt.expires_at < now_millis {
+            return Err(ChatSessionError::Expired);
+        }
+        let payload = profile_key_payload(profile_id, pkt.expires_at, pkt.public_key.0);
+        if !services_keys.verify(&payload, pkt.key_signature.0) {
+            return Err(ChatSessionError::InvalidKeySignature);
+        }
+        let public_key = RsaPublicKey::from_public_key_der(pkt.public_key.0)
+            .map_err(|_| ChatSessionError::InvalidKey)?;
+        Ok(Self {
+            session_id: pkt.session_id,
+            expires_at: pkt.expires_at,
+            public_key,
+        })
+    }
+
+    pub fn is_expired_at(&self, now_millis: i64) -> bool {
+        self.expires_at < now_millis
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(now_millis())
+    }
+
+    /// Whether the session key made `signature` over `payload`, a
+    /// `SHA256withRSA` signature like every signed chat message carries.
+    pub fn verify(&self, payload: &[u8], signature: &[u8]) -> bool {
+        let hash = Sha256::digest(payload);
+        self.public_key
+            .verify(pkcs1v15(&SHA256_DIGEST_INFO, 32), &hash, signature)
+            .is_ok()
+    }
+}
+
+/// What Mojang signs a profile key with, vanilla
+/// `ProfilePublicKey.Data.signedPayload`: the player id, the expiry and the
+/// X.509 key, all big-endian.
+fn profile_key_payload(profile_id: Uuid, expires_at: i64, public_key_der: &[u8]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(24 + public_key_der.len());
+    payload.extend_from_slice(profile_id.as_bytes());
+    payload.extend_from_slice(&expires_at.to_be_bytes());
+    payload.extend_from_slice(public_key_der);
+    payload
+}
+
+/// Milliseconds since the Unix epoch, the clock chat timestamps and key
+/// expiries use.
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as i64)
+}
+
+/// Chat Session Update: trust the announced session once Mojang's signature
+/// on its key checks out, and disconnect a client whose key is expired or
+/// forged. Without [`ServicesKeys`] the update is ignored, like vanilla.
+pub fn update_chat_session(
+    on: On<ReceivedPacketEvent>,
+    mut query: Query<(
+        &ConnectionState,
+        &GameProfile,
+        &mut ServerSideConnection,
+        Option<&ChatSession>,
+    )>,
+    services_keys: Option<Res<ServicesKeys>>,
+    mut commands: Commands,
+) {
+    let Ok((state, profile, mut con, current)) = query.get_mut(on.entity) else {
+        return;
+    };
+    if *state != ConnectionState::Game {
+        return;
+    }
+    let Some(pkt) = on.decode::<ServerboundChatSessionUpdate>() else {
+        return;
+    };
+    let Some(services_keys) = services_keys else {
+        warn!(
+            "ignoring chat session from {}: Mojang's services keys are missing",
+            profile.username
+        );
+        return;
+    };
+    // A key may only be replaced by one that lasts at least as long.
+    let session = match current {
+        Some(current) if pkt.expires_at < current.expires_at => Err(ChatSessionError::Expired),
+        _ => ChatSession::validate(profile.id, &pkt, &services_keys, now_millis()),
+    };
+    match session {
+        Ok(session) => {
+            commands.entity(on.entity).insert(session);
+        }
+        Err(e) => {
+            warn!("chat session from {} refused: {e}", profile.username);
+            con.disconnect(*state, Text::translate(e.translation_key(), vec![]));
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LastSeenError {
+    #[error("advanced last seen window by {offset}, but only {pending} messages were pending")]
+    InvalidOffset { offset: i32, pending: usize },
+    #[error("last seen update acknowledged unknown or previously ignored message at index {0}")]
+    UnknownMessage(usize),
+    #[error("last seen update ignored previously acknowledged message at index {0}")]
+    IgnoredAcknowledged(usize),
+    #[error("last seen update checksum {sent} does not match {expected}")]
+    ChecksumMismatch { sent: u8, expected: u8 },
+}
+
+/// Server side of the last-seen window, vanilla `LastSeenMessagesValidator`.
+/// The client acknowledges the signed messages it has displayed, and every
+/// update has to agree with what the server sent it.
+#[derive(Clone, Debug)]
+pub struct LastSeenMessagesValidator {
+    /// Oldest first; the first [`LAST_SEEN_MESSAGES`] slots are the window,
+    /// `None` where the client ignored a message or nothing was sent yet.
+    tracked: Vec<Option<TrackedMessage>>,
+    last_pending: Option<Box<[u8; 256]>>,
+}
+
+#[derive(Clone, Debug)]
+struct TrackedMessage {
+    signature: Box<[u8; 256]>,
+    /// Sent to the client but not acknowledged yet.
+    pending: bool,
+}
+
+impl Default for LastSeenMessagesValidator {
+    fn default() -> Self {
+        Self {
+            tracked: vec![None; LAST_SEEN_MESSAGES],
+            last_pending: None,
+        }
+    }
+}
+
+impl LastSeenMessagesValidator {
+    /// Track a signed message sent to the client.
+    pub fn add_pending(&mut self, signature: &[u8; 256]) {
+        if self.last_pending.as_deref() == Some(signature) {
+            return;
+        }
+        let signature = Box::new(*signature);
+        self.tracked.push(Some(TrackedMessage {
+            signature: signature.clone(),
+            pending: true,
+        }));
+        self.last_pending = Some(signature);
+    }
+
+    pub fn tracked_count(&self) -> usize {
+        self.tracked.len()
+    }
+
+    /// Drop the `offset` oldest messages, as the client does when its window
+    /// moves on (Chat Ack and the start of every chat packet).
+    pub fn apply_offset(&mut self, offset: i32) -> Result<(), LastSeenError> {
+        let pending = self.tracked.len() - LAST_SEEN_MESSAGES;
+        if offset < 0 || offset as usize > pending {
+            return Err(LastSeenError::InvalidOffset { offset, pending });
+        }
+        self.tracked.drain(..offset as usize);
+        Ok(())
+    }
+
+    /// Apply the last-seen part of a chat packet and check its checksum.
+    /// Returns the signatures it acknowledges, oldest first, which the
+    /// message's own signature covers.
+    pub fn apply_update(
+        &mut self,
+        update: &MessageSignature,
+    ) -> Result<Vec<[u8; 256]>, LastSeenError> {
+        self.apply_offset(update.offset.0)?;
+        let mut acknowledged = Vec::new();
+        let mut checksum = 1i32;
+        let window = self.tracked.iter_mut().take(LAST_SEEN_MESSAGES);
+        for (index, slot) in window.enumerate() {
+            if update.acknowledged.bit(index) {
+                let Some(message) = slot else {
+                    return Err(LastSeenError::UnknownMessage(index));
+                };
+                message.pending = false;
+                acknowledged.push(*message.signature);
+                checksum = checksum
+                    .wrapping_mul(31)
+                    .wrapping_add(signature_hash(&message.signature));
+            } else {
+                if slot.as_ref().is_some_and(|message| !message.pending) {
+                    return Err(LastSeenError::IgnoredAcknowledged(index));
+                }
+                *slot = None;
+            }
+        }
+        let expected = match checksum as u8 {
+            0 => 1,
+            checksum => checksum,
+        };
+        // Either side may opt out of the checksum by sending 0.
+        if update.checksum != 0 && update.checksum != expected {
+            return Err(LastSeenError::ChecksumMismatch {
+                sent: update.checksum,
+                expected,
+            });
+        }
+        Ok(acknowledged)
+    }
+}
+
+/// Java's `Arrays.hashCode(byte[])`, which the checksum is built from.
+fn signature_hash(signature: &[u8; 256]) -> i32 {
+    signature.iter().fold(1i32, |hash, &byte| {
+        hash.wrapping_mul(31).wrapping_add(byte as i8 as i32)
+    })
+}
+
+/// Throwaway keys standing in for Mojang's services key and a player's
+/// profile key, and signatures made with them.
+#[cfg(test)]
+pub(crate) mod test_keys {
+    use super::*;
+    use rsa::RsaPrivateKey;
+    use rsa::pkcs8::{DecodePrivateKey, EncodePublicKey};
+
+    pub fn services_key() -> RsaPrivateKey {
+        RsaPrivateKey::from_pkcs8_pem(include_str!("../tests/fixtures/chat/services_key.pem"))
+            .unwrap()
+    }
+
+    pub fn profile_key() -> RsaPrivateKey {
+        RsaPrivateKey::from_pkcs8_pem(include_str!("../tests/fixtures/chat/profile_key.pem"))
+            .unwrap()
+    }
+
+    pub fn public_key_der(key: &RsaPrivateKey) -> Vec<u8> {
+        key.to_public_key()
+            .to_public_key_der()
+            .unwrap()
+            .as_bytes()
+            .to_vec()
+    }
+
+    /// `key`'s signature on the profile key `public_key_der`, as Mojang's
+    /// would be.
+    pub fn sign_profile_key(
+        key: &RsaPrivateKey,
+        profile_id: Uuid,
+        expires_at: i64,
+        public_key_der: &[u8],
+    ) -> Vec<u8> {
+        let payload = profile_key_payload(profile_id, expires_at, public_key_der);
+        key.sign(pkcs1v15(&SHA1_DIGEST_INFO, 20), &Sha1::digest(payload))
+            .unwrap()
+    }
+
+    /// `key`'s `SHA256withRSA` signature on `payload`.
+    pub fn sign_message(key: &RsaPrivateKey, payload: &[u8]) -> [u8; 256] {
+        key.sign(pkcs1v15(&SHA256_DIGEST_INFO, 32), &Sha256::digest(payload))
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_keys::*;
+    use super::*;
+    use mcrs_protocol::{Bounded, FixedBitSet, VarInt};
+    use rsa::RsaPrivateKey;
+
+    fn session_update<'a>(
+        public_key_der: &'a [u8],
+        expires_at: i64,
+        key_signature: &'a [u8],
+    ) -> ServerboundChatSessionUpdate<'a> {
+        ServerboundChatSessionUpdate {
+            session_id: Uuid::from_u128(7),
+            expires_at,
+            public_key: Bounded(public_key_der),
+            key_signature: Bounded(key_signature),
+        }
+    }
+
+    #[test]
+    fn session_key_has_to_be_signed_by_mojang() {
+        let services_keys = ServicesKeys::new(vec![services_key().to_public_key()]);
+        let profile_id = Uuid::from_u128(0x069a79f444e94726a5befca90e38aaf5);
+        let expires_at = 1_700_000_000_000;
+        let der = public_key_der(&profile_key());
+        let signature = sign_profile_key(&services_key(), profile_id, expires_at, &der);
+        let update = session_update(&der, expires_at, &signature);
+        let now = expires_at - 1;
+
+        let session = ChatSession::validate(profile_id, &update, &services_keys, now).unwrap();
+        assert_eq!(session.session_id, Uuid::from_u128(7));
+        assert_eq!(session.public_key, profile_key().to_public_key());
+        assert_eq!(
+            ChatSession::validate(profile_id, &update, &services_keys, expires_at + 1),
+            Err(ChatSessionError::Expired)
+        );
+
+        // Signed by the player's own key, for another player, or for another
+        // expiry.
+        let self_signed = sign_profile_key(&profile_key(), profile_id, expires_at, &der);
+        let forged = [
+            (profile_id, session_update(&der, expires_at, &self_signed)),
+            (Uuid::from_u128(1), update.clone()),
+            (profile_id, session_update(&der, expires_at + 1, &signature)),
+            (profile_id, session_update(&der, expires_at, &[0; 256])),
+        ];
+        for (profile_id, update) in forged {
+            assert_eq!(
+                ChatSession::validate(profile_id, &update, &services_keys, now),
+                Err(ChatSessionError::InvalidKeySignature)
+            );
+        }
+    }
+
+    #[test]
+    fn services_keys_are_read_from_the_publickeys_response() {
+        let encode = |key: &RsaPrivateKey| BASE64_STANDARD.encode(public_key_der(key));
+        let body = format!(
+            r#"{{
+                "profilePropertyKeys": [{{"publicKey": "{}"}}],
+                "playerCertificateKeys": [{{"publicKey": "{}"}}]
+            }}"#,
+            encode(&profile_key()),
+            encode(&services_key()),
+        );
+        let services_keys = ServicesKeys::from_json(&body).unwrap();
+        let payload = b"profile key";
+        let signature = services_key()
+            .sign(pkcs1v15(&SHA1_DIGEST_INFO, 20), &Sha1::digest(payload))
+            .unwrap();
+        assert!(services_keys.verify(payload, &signature));
+        // Property keys sign skins, not profile keys.
+        let signature = profile_key()
+            .sign(pkcs1v15(&SHA1_DIGEST_INFO, 20), &Sha1::digest(payload))
+            .unwrap();
+        assert!(!services_keys.verify(payload, &signature));
+
+        assert!(matches!(
+            ServicesKeys::from_json(r#"{"playerCertificateKeys": [{"publicKey": "!"}]}"#),
+            Err(ServicesKeyError::Base64(_))
+        ));
+    }
+
+    fn update(offset: i32, acknowledged: &[usize], checksum: u8) -> MessageSignature {
+        let mut bits = FixedBitSet([0; 3]);
+        for &index in acknowledged {
+            bits.set_bit(index, true);
+        }
+        MessageSignature {
+            offset: VarInt(offset),
+            acknowledged: bits,
+            checksum,
+        }
+    }
+
+    #[test]
+    fn empty_window_accepts_unsigned_clients() {
+        let mut validator = LastSeenMessagesValidator::default();
+        assert_eq!(validator.apply_update(&update(0, &[], 1)), Ok(vec![]));
+        assert_eq!(validator.apply_update(&update(0, &[], 0)), Ok(vec![]));
+        assert_eq!(
+            validator.apply_update(&update(0, &[0], 0)),
+            Err(LastSeenError::UnknownMessage(0))
+        );
+        assert_eq!(
+            validator.apply_offset(1),
+            Err(LastSeenError::InvalidOffset {
+                offset: 1,
+                pending: 0
+            })
+        );
+    }
+
+    #[test]
+    fn acknowledged_messages_slide_through_the_window() {
+        let mut validator = LastSeenMessagesValidator::default();
+        let signature = [7u8; 256];
+        validator.add_pending(&signature);
+        validator.add_pending(&signature);
+        assert_eq!(validator.tracked_count(), LAST_SEEN_MESSAGES + 1);
+
+        // The client shifts the new message into the last slot and sees it.
+        let expected = (31 + signature_hash(&signature)) as u8;
+        assert_eq!(
+            validator.apply_update(&update(1, &[19], expected.wrapping_add(1))),
+            Err(LastSeenError::ChecksumMismatch {
+                sent: expected.wrapping_add(1),
+                expected
+            })
+        );
+
+        let mut validator = LastSeenMessagesValidator::default();
+        validator.add_pending(&signature);
+        assert_eq!(
+            validator.apply_update(&update(1, &[19], expected)),
+            Ok(vec![signature])
+        );
+        // Once acknowledged it can not be silently dropped again.
+        assert_eq!(
+            validator.apply_update(&update(0, &[], 0)),
+            Err(LastSeenError::IgnoredAcknowledged(19))
+        );
+    }
+}
@@ -1,28 +1,85 @@
 use bevy_app::{App, Plugin};
-use bevy_ecs::prelude::{Commands, Component, On, Query};
-use derive_more::Deref;
+use bevy_ecs::prelude::{Commands, Component, On, Query, Res, Resource};
+use bevy_math::DVec3;
+use mcrs_engine::geometry::ColumnPos;
 use mcrs_network::ConnectionState;
 use mcrs_network::event::ReceivedPacketEvent;
 use mcrs_protocol::packets::configuration::serverbound::ServerboundClientInformation as ConfigurationPacket;
 use mcrs_protocol::packets::game::serverbound::ServerboundClientInformation as GamePacket;
+use mcrs_protocol::setting::{ChatMode, DisplayedSkinParts, MainArm};
 
 pub struct ClientInfoPlugin;
 
 impl Plugin for ClientInfoPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<ServerViewConfig>();
         app.add_observer(update_client_info);
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Component, Deref)]
-pub struct ClientLocale(String);
+/// The server's view and simulation distances, in chunks. Both are sent in
+/// Login; a client's own view distance is clamped to `view_distance`.
+#[derive(Resource, Debug, Clone)]
+pub struct ServerViewConfig {
+    /// Upper bound for a client's view distance.
+    pub view_distance: u8,
+    /// How far from a player entities are ticked.
+    pub simulation_distance: u8,
+}
+
+impl Default for ServerViewConfig {
+    fn default() -> Self {
+        Self {
+            view_distance: 12,
+            simulation_distance: 12,
+        }
+    }
+}
+
+impl ServerViewConfig {
+    /// The distance chunks are streamed to a client that asked for
+    /// `requested`: the smaller of the two, but never below vanilla's
+    /// minimum.
+    pub fn effective_view_distance(&self, requested: u8) -> u8 {
+        requested.clamp(MIN_VIEW_DISTANCE, self.view_distance.max(MIN_VIEW_DISTANCE))
+    }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Component, Deref)]
-pub struct ClientViewDistance(u8);
+    /// Whether something at `position` is in a column within
+    /// `simulation_distance` of one of `players`, and so gets ticked.
+    pub fn in_simulation_distance(
+        &self,
+        position: DVec3,
+        mut players: impl Iterator<Item = DVec3>,
+    ) -> bool {
+        let column = ColumnPos::from(position);
+        let distance = self.simulation_distance as i32;
+        players.any(|player| {
+            let player = ColumnPos::from(player);
+            (player.x - column.x).abs() <= distance && (player.z - column.z).abs() <= distance
+        })
+    }
+}
+
+/// Vanilla never streams fewer chunks than this, whatever the client asks.
+const MIN_VIEW_DISTANCE: u8 = 2;
+
+/// The settings a client last sent in Client Information. Replaced on every
+/// resend, in Configuration and Game alike.
+#[derive(Clone, Debug, PartialEq, Eq, Component)]
+pub struct ClientInfo {
+    pub locale: String,
+    /// Requested view distance, already clamped to the server's maximum.
+    pub view_distance: u8,
+    pub chat_mode: ChatMode,
+    pub main_hand: MainArm,
+    pub displayed_skin_parts: DisplayedSkinParts,
+    pub allow_server_listings: bool,
+}
 
 pub fn update_client_info(
     on: On<ReceivedPacketEvent>,
     query: Query<&ConnectionState>,
+    config: Res<ServerViewConfig>,
     mut commands: Commands,
 ) {
     let Ok(state) = query.get(on.entity) else {
@@ -36,8 +93,16 @@ pub fn update_client_info(
     let Some(info) = info else {
         return;
     };
-    let mut entity = commands.entity(on.entity);
-    entity.insert(ClientLocale(info.locale.to_string()));
-    entity.insert(ClientViewDistance(info.view_distance));
-    entity.insert(info.chat_mode);
+    commands.entity(on.entity).insert((
+        ClientInfo {
+            locale: info.locale.to_string(),
+            view_distance: config.effective_view_distance(info.view_distance),
+            chat_mode: info.chat_mode,
+            main_hand: info.main_arm,
+            displayed_skin_parts: info.displayed_skin_parts,
+            allow_server_listings: info.allow_server_listings,
+        },
+        info.chat_mode,
+    ));
 }
+
@@ -0,0 +1,193 @@
+//! The commands every dimension starts with.
+
+use crate::command::dispatcher::{
+    CommandContext, CommandDispatcher, EntitySelector, argument, literal,
+};
+use crate::command::send_feedback;
+use crate::login::GameProfile;
+use crate::world::bus::{
+    OutboundPlayerPacket, OutboundPlayerTransferRequest, PacketPayload, PacketPriority,
+    PacketTarget, PlayerTransferSnapshot, SpawnReason,
+};
+use crate::world::entity::player::HostAnchor;
+use crate::world::entity::player::ability::PlayerGameMode;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::Messages;
+use bevy_ecs::query::With;
+use bevy_ecs::world::World;
+use bevy_math::{DVec3, Vec2};
+use mcrs_engine::entity::physics::Transform;
+use mcrs_engine::entity::player::Player;
+use mcrs_protocol::Text;
+use mcrs_protocol::command::{ArgumentParser, StringKind};
+use mcrs_protocol::text::{Color, IntoText};
+use tracing::info;
+
+const INTEGER: ArgumentParser = ArgumentParser::Integer {
+    min: None,
+    max: None,
+};
+const PLAYER: ArgumentParser = ArgumentParser::Entity {
+    single: true,
+    players_only: true,
+};
+const GREEDY: ArgumentParser = ArgumentParser::String(StringKind::GreedyPhrase);
+
+/// The op level vanilla asks of `/teleport`, which `/dim` shares.
+const REQUIRED_OP_LEVEL: u8 = 2;
+
+pub(super) fn register(dispatcher: &mut CommandDispatcher) {
+    dispatcher.register(literal("say").then(argument("message", GREEDY).executes(say)));
+    dispatcher.register(
+        literal("teleport")
+            .requires(REQUIRED_OP_LEVEL)
+            .then(
+                argument("x", INTEGER).then(
+                    argument("y", INTEGER)
+                        .then(argument("z", INTEGER).executes(teleport_to_position)),
+                ),
+            )
+            .then(argument("destination", PLAYER).executes(teleport_to_player)),
+    );
+    dispatcher.register(
+        literal("tp")
+            .requires(REQUIRED_OP_LEVEL)
+            .redirect(&["teleport"]),
+    );
+    dispatcher.register(
+        literal("dim")
+            .requires(REQUIRED_OP_LEVEL)
+            .then(argument("dimension", GREEDY).executes(dim)),
+    );
+}
+
+fn name(world: &World, entity: Entity) -> String {
+    world
+        .get::<GameProfile>(entity)
+        .map_or_else(|| "Server".to_owned(), |profile| profile.username.clone())
+}
+
+/// `/say <message>`: announce `message` to every player as the sender.
+fn say(world: &mut World, context: &CommandContext) {
+    let Some(message) = context.string("message") else {
+        return;
+    };
+    let sender = name(world, context.sender);
+    info!("[{}] {}", sender, message);
+    world
+        .resource_mut::<Messages<OutboundPlayerPacket>>()
+        .write(OutboundPlayerPacket {
+            target: PacketTarget::AllPlayers,
+            priority: PacketPriority::Normal,
+            data: PacketPayload::SystemChat {
+                content: Text::translate(
+                    "chat.type.announcement",
+                    vec![sender.into_text(), message.to_owned().into_text()],
+                ),
+                overlay: false,
+            },
+        });
+}
+
+/// `/teleport <x> <y> <z>`: move the sender to the center of that block.
+fn teleport_to_position(world: &mut World, context: &CommandContext) {
+    let (Some(x), Some(y), Some(z)) = (
+        context.integer("x"),
+        context.integer("y"),
+        context.integer("z"),
+    ) else {
+        return;
+    };
+    let position = DVec3::new(x as f64 + 0.5, y as f64, z as f64 + 0.5);
+    let Some(mut transform) = world.get_mut::<Transform>(context.sender) else {
+        return;
+    };
+    // The movement `teleport` system sends the new position.
+    transform.translation = position;
+    let feedback = Text::translate(
+        "commands.teleport.success.location.single",
+        vec![
+            name(world, context.sender).into_text(),
+            format!("{:.6}", position.x).into_text(),
+            format!("{:.6}", position.y).into_text(),
+            format!("{:.6}", position.z).into_text(),
+        ],
+    );
+    send_feedback(world, context.sender, feedback);
+}
+
+/// `/teleport <destination>`: move the sender to another player in the same
+/// dimension.
+fn teleport_to_player(world: &mut World, context: &CommandContext) {
+    let Some(selector) = context.entity("destination") else {
+        return;
+    };
+    let destination = match selector {
+        EntitySelector::Sender => Some(context.sender),
+        EntitySelector::Player(username) => world
+            .query_filtered::<(Entity, &GameProfile), With<Player>>()
+            .iter(world)
+            .find(|(_, profile)| profile.username.eq_ignore_ascii_case(username))
+            .map(|(entity, _)| entity),
+    };
+    let Some(position) =
+        destination.and_then(|entity| Some(world.get::<Transform>(entity)?.translation))
+    else {
+        let feedback =
+            Text::translate("argument.entity.notfound.player", vec![]).color(Color::RED);
+        send_feedback(world, context.sender, feedback);
+        return;
+    };
+    let Some(mut transform) = world.get_mut::<Transform>(context.sender) else {
+        return;
+    };
+    transform.translation = position;
+    let feedback = Text::translate(
+        "commands.teleport.success.entity.single",
+        vec![
+            name(world, context.sender).into_text(),
+            destination.map_or_else(String::new, |entity| name(world, entity)).into_text(),
+        ],
+    );
+    send_feedback(world, context.sender, feedback);
+}
+
+/// `/dim <dimension>`: move the sender into another dimension, e.g.
+/// `nether` or `minecraft:the_end`.
+fn dim(world: &mut World, context: &CommandContext) {
+    let Some(raw) = context.string("dimension") else {
+        return;
+    };
+    let dim_name = match raw {
+        "nether" | "the_nether" => "minecraft:the_nether".to_string(),
+        "overworld" | "over" => "minecraft:overworld".to_string(),
+        "end" | "the_end" => "minecraft:the_end".to_string(),
+        other if other.contains(':') => other.to_string(),
+        other => format!("minecraft:{other}"),
+    };
+    let Some(&HostAnchor(host_anchor)) = world.get::<HostAnchor>(context.sender) else {
+        return;
+    };
+    let Some(profile) = world.get::<GameProfile>(context.sender) else {
+        return;
+    };
+    let Some(game_mode) = world.get::<PlayerGameMode>(context.sender) else {
+        return;
+    };
+    let snapshot = PlayerTransferSnapshot {
+        uuid: profile.id,
+        username: profile.username.clone(),
+        position: DVec3::new(0.0, 100.0, 0.0),
+        rotation: Vec2::ZERO,
+        game_mode: game_mode.0,
+    };
+    info!("dim transfer {:?} -> {}", context.sender, dim_name);
+    world
+        .resource_mut::<Messages<OutboundPlayerTransferRequest>>()
+        .write(OutboundPlayerTransferRequest {
+            host_anchor,
+            dim_name,
+            snapshot,
+            reason: SpawnReason::DimensionChange,
+        });
+}
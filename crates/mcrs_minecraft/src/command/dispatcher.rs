@@ -0,0 +1,631 @@
+//! The command tree: registration, the node graph sent to clients, and
+//! parsing chat command input against it.
+
+use bevy_ecs::entity::Entity;
+use bevy_ecs::resource::Resource;
+use bevy_ecs::world::World;
+use mcrs_protocol::VarInt;
+use mcrs_protocol::command::{ArgumentParser, CommandNode, CommandNodeKind, StringKind};
+use mcrs_protocol::packets::game::clientbound::ClientboundCommands;
+use thiserror::Error;
+
+/// Runs a parsed command. Handlers get the whole world, so they can read the
+/// sender and write packets the same way systems do.
+pub type CommandHandler = fn(&mut World, &CommandContext);
+
+/// Index of the root node, in the dispatcher and in the graph.
+const ROOT: usize = 0;
+
+/// Usernames are at most 16 characters, so longer input can not name one.
+const MAX_PLAYER_NAME_LENGTH: usize = 16;
+
+/// Every registered command, as a tree of literal and argument nodes under
+/// one root. Each client is sent the part of the tree its op level may use,
+/// so what it completes is what the server accepts from it.
+#[derive(Resource, Debug)]
+pub struct CommandDispatcher {
+    nodes: Vec<Node>,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    kind: CommandNodeKind,
+    children: Vec<usize>,
+    redirect: Option<usize>,
+    handler: Option<CommandHandler>,
+    required_op_level: u8,
+}
+
+impl Default for CommandDispatcher {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node {
+                kind: CommandNodeKind::Root,
+                children: Vec::new(),
+                redirect: None,
+                handler: None,
+                required_op_level: 0,
+            }],
+        }
+    }
+}
+
+/// A literal or argument node and the nodes that follow it, built with
+/// [`literal`] and [`argument`].
+#[derive(Clone, Debug)]
+pub struct CommandBuilder {
+    kind: CommandNodeKind,
+    children: Vec<CommandBuilder>,
+    redirect: Option<Vec<String>>,
+    handler: Option<CommandHandler>,
+    required_op_level: u8,
+}
+
+/// A node matching `name` exactly.
+pub fn literal(name: impl Into<String>) -> CommandBuilder {
+    CommandBuilder::new(CommandNodeKind::Literal(name.into()))
+}
+
+/// A node parsing a value with `parser`, readable by `name` from the
+/// [`CommandContext`].
+pub fn argument(name: impl Into<String>, parser: ArgumentParser) -> CommandBuilder {
+    CommandBuilder::new(CommandNodeKind::Argument {
+        name: name.into(),
+        parser,
+        suggestions: None,
+    })
+}
+
+impl CommandBuilder {
+    fn new(kind: CommandNodeKind) -> Self {
+        Self {
+            kind,
+            children: Vec::new(),
+            redirect: None,
+            handler: None,
+            required_op_level: 0,
+        }
+    }
+
+    pub fn then(mut self, child: CommandBuilder) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Make the input ending at this node a whole command, run by `handler`.
+    pub fn executes(mut self, handler: CommandHandler) -> Self {
+        self.handler = Some(handler);
+        self
+    }
+
+    /// Continue parsing past this node at the literal node reached by `path`
+    /// from the root, or at the root itself for an empty path. The target has
+    /// to be registered first.
+    pub fn redirect(mut self, path: &[&str]) -> Self {
+        self.redirect = Some(path.iter().map(|&name| name.to_owned()).collect());
+        self
+    }
+
+    /// Only let players of at least `op_level` use this node and the ones
+    /// after it. Others neither see it in their graph nor can run it.
+    pub fn requires(mut self, op_level: u8) -> Self {
+        self.required_op_level = op_level;
+        self
+    }
+}
+
+/// A parsed command, ready to run.
+#[derive(Clone, Debug)]
+pub struct ParsedCommand {
+    pub handler: CommandHandler,
+    pub context: CommandContext,
+}
+
+/// What a handler is given: who ran the command and the arguments parsed
+/// along the way.
+#[derive(Clone, Debug)]
+pub struct CommandContext {
+    pub sender: Entity,
+    pub input: String,
+    arguments: Vec<(String, ArgumentValue)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArgumentValue {
+    Integer(i32),
+    String(String),
+    Entity(EntitySelector),
+}
+
+/// A parsed player selector. Resolving it against the world is up to the
+/// handler.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EntitySelector {
+    /// `@s`, whoever ran the command.
+    Sender,
+    Player(String),
+}
+
+impl CommandContext {
+    pub fn argument(&self, name: &str) -> Option<&ArgumentValue> {
+        self.arguments
+            .iter()
+            .find(|(argument, _)| argument == name)
+            .map(|(_, value)| value)
+    }
+
+    pub fn integer(&self, name: &str) -> Option<i32> {
+        match self.argument(name)? {
+            &ArgumentValue::Integer(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn string(&self, name: &str) -> Option<&str> {
+        match self.argument(name)? {
+            ArgumentValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn entity(&self, name: &str) -> Option<&EntitySelector> {
+        match self.argument(name)? {
+            ArgumentValue::Entity(selector) => Some(selector),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum CommandError {
+    #[error("unknown command")]
+    Unknown,
+    #[error("incomplete command")]
+    Incomplete,
+    #[error("invalid value for argument {0}")]
+    InvalidArgument(String),
+}
+
+impl CommandError {
+    /// The vanilla message for the error.
+    pub fn translation_key(&self) -> &'static str {
+        match self {
+            CommandError::Unknown | CommandError::Incomplete => "command.unknown.command",
+            CommandError::InvalidArgument(_) => "command.unknown.argument",
+        }
+    }
+}
+
+impl CommandDispatcher {
+    /// Add `command` under the root. A literal already registered there is
+    /// merged with it rather than replaced.
+    ///
+    /// # Panics
+    ///
+    /// If a redirect in `command` names a node that is not registered.
+    pub fn register(&mut self, command: CommandBuilder) {
+        self.insert(ROOT, command);
+    }
+
+    fn insert(&mut self, parent: usize, builder: CommandBuilder) {
+        let redirect = builder.redirect.as_ref().map(|path| {
+            self.find(path)
+                .unwrap_or_else(|| panic!("redirect target /{} is not registered", path.join(" ")))
+        });
+        let existing = self.nodes[parent]
+            .children
+            .iter()
+            .copied()
+            .find(|&child| same_node(&self.nodes[child].kind, &builder.kind));
+        let index = match existing {
+            Some(index) => {
+                let node = &mut self.nodes[index];
+                node.handler = builder.handler.or(node.handler);
+                node.redirect = redirect.or(node.redirect);
+                node.required_op_level = node.required_op_level.max(builder.required_op_level);
+                index
+            }
+            None => {
+                self.nodes.push(Node {
+                    kind: builder.kind,
+                    children: Vec::new(),
+                    redirect,
+                    handler: builder.handler,
+                    required_op_level: builder.required_op_level,
+                });
+                let index = self.nodes.len() - 1;
+                self.nodes[parent].children.push(index);
+                index
+            }
+        };
+        for child in builder.children {
+            self.insert(index, child);
+        }
+    }
+
+    /// The node reached from the root by following literal `path`.
+    fn find(&self, path: &[String]) -> Option<usize> {
+        path.iter().try_fold(ROOT, |node, name| {
+            let is_named = |child: &usize| match &self.nodes[*child].kind {
+                CommandNodeKind::Literal(literal) => literal == name,
+                _ => false,
+            };
+            self.nodes[node].children.iter().copied().find(is_named)
+        })
+    }
+
+    /// The command graph for the Commands packet, holding only the nodes a
+    /// player of `op_level` may use. A redirect to a node left out is
+    /// dropped.
+    pub fn commands_packet(&self, op_level: u8) -> ClientboundCommands {
+        // Children are always registered after their parent, so one pass in
+        // index order reaches every parent before its children.
+        let mut usable = vec![false; self.nodes.len()];
+        usable[ROOT] = true;
+        for (index, node) in self.nodes.iter().enumerate() {
+            if usable[index] {
+                for &child in &node.children {
+                    usable[child] = self.nodes[child].required_op_level <= op_level;
+                }
+            }
+        }
+        // Nodes keep their order, so the root stays at `ROOT`.
+        let mut wire_index = vec![None; self.nodes.len()];
+        let mut next = 0;
+        for (index, _) in usable.iter().enumerate().filter(|(_, usable)| **usable) {
+            wire_index[index] = Some(VarInt(next));
+            next += 1;
+        }
+        let nodes = self
+            .nodes
+            .iter()
+            .zip(&usable)
+            .filter(|(_, usable)| **usable)
+            .map(|(node, _)| CommandNode {
+                kind: node.kind.clone(),
+                children: node
+                    .children
+                    .iter()
+                    .filter_map(|&child| wire_index[child])
+                    .collect(),
+                redirect: node.redirect.and_then(|target| wire_index[target]),
+                executable: node.handler.is_some(),
+                restricted: false,
+            })
+            .collect();
+        ClientboundCommands {
+            nodes,
+            root_index: VarInt(ROOT as i32),
+        }
+    }
+
+    /// Parse `input`, a command without its leading slash, as run by
+    /// `sender` with `op_level`. Commands above that level are unknown.
+    pub fn parse(
+        &self,
+        sender: Entity,
+        op_level: u8,
+        input: &str,
+    ) -> Result<ParsedCommand, CommandError> {
+        let mut arguments = Vec::new();
+        let handler = self.parse_children(ROOT, op_level, input, &mut arguments)?;
+        Ok(ParsedCommand {
+            handler,
+            context: CommandContext {
+                sender,
+                input: input.to_owned(),
+                arguments,
+            },
+        })
+    }
+
+    /// Match `input` against the children of `node`, or of its redirect
+    /// target, trying each in registration order until one parses the rest.
+    fn parse_children(
+        &self,
+        node: usize,
+        op_level: u8,
+        input: &str,
+        arguments: &mut Vec<(String, ArgumentValue)>,
+    ) -> Result<CommandHandler, CommandError> {
+        let node = self.nodes[node].redirect.unwrap_or(node);
+        let mut error = CommandError::Unknown;
+        for &child in &self.nodes[node].children {
+            let child_node = &self.nodes[child];
+            if child_node.required_op_level > op_level {
+                continue;
+            }
+            let (value, rest) = match read_node(&child_node.kind, input) {
+                Ok(read) => read,
+                Err(Some(err)) => {
+                    error = err;
+                    continue;
+                }
+                Err(None) => continue,
+            };
+            let mark = arguments.len();
+            if let (Some(value), CommandNodeKind::Argument { name, .. }) = (value, &child_node.kind)
+            {
+                arguments.push((name.clone(), value));
+            }
+            let result = match rest {
+                "" => child_node.handler.ok_or(CommandError::Incomplete),
+                rest => match rest.strip_prefix(' ') {
+                    Some(rest) => self.parse_children(child, op_level, rest, arguments),
+                    None => Err(CommandError::Unknown),
+                },
+            };
+            match result {
+                Ok(handler) => return Ok(handler),
+                Err(err) => error = err,
+            }
+            arguments.truncate(mark);
+        }
+        Err(error)
+    }
+}
+
+fn same_node(a: &CommandNodeKind, b: &CommandNodeKind) -> bool {
+    match (a, b) {
+        (CommandNodeKind::Literal(a), CommandNodeKind::Literal(b)) => a == b,
+        (CommandNodeKind::Argument { name: a, .. }, CommandNodeKind::Argument { name: b, .. }) => {
+            a == b
+        }
+        _ => false,
+    }
+}
+
+/// Read one node's worth of `input`, returning the argument value (none for
+/// literals) and what is left. A literal that does not match is `Err(None)`;
+/// an argument that can not be parsed names itself.
+fn read_node(
+    kind: &CommandNodeKind,
+    input: &str,
+) -> Result<(Option<ArgumentValue>, &str), Option<CommandError>> {
+    let (name, parser) = match kind {
+        CommandNodeKind::Root => return Err(None),
+        CommandNodeKind::Literal(literal) => {
+            let (word, rest) = split_word(input);
+            return if word == literal {
+                Ok((None, rest))
+            } else {
+                Err(None)
+            };
+        }
+        CommandNodeKind::Argument { name, parser, .. } => (name, parser),
+    };
+    let invalid = || Some(CommandError::InvalidArgument(name.clone()));
+    let (value, rest) = match *parser {
+        ArgumentParser::Integer { min, max } => {
+            let (word, rest) = split_word(input);
+            let value: i32 = word.parse().map_err(|_| invalid())?;
+            if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) {
+                return Err(invalid());
+            }
+            (ArgumentValue::Integer(value), rest)
+        }
+        ArgumentParser::String(StringKind::SingleWord) => {
+            let (word, rest) = split_word(input);
+            if word.is_empty() {
+                return Err(invalid());
+            }
+            (ArgumentValue::String(word.to_owned()), rest)
+        }
+        ArgumentParser::String(StringKind::QuotablePhrase) => {
+            let (value, rest) = read_quotable(input).ok_or_else(invalid)?;
+            (ArgumentValue::String(value), rest)
+        }
+        ArgumentParser::String(StringKind::GreedyPhrase) => {
+            if input.is_empty() {
+                return Err(invalid());
+            }
+            (ArgumentValue::String(input.to_owned()), "")
+        }
+        ArgumentParser::Entity { .. } => {
+            let (word, rest) = split_word(input);
+            let selector = match word {
+                "@s" => EntitySelector::Sender,
+                name if !name.is_empty()
+                    && name.len() <= MAX_PLAYER_NAME_LENGTH
+                    && !name.starts_with('@') =>
+                {
+                    EntitySelector::Player(name.to_owned())
+                }
+                _ => return Err(invalid()),
+            };
+            (ArgumentValue::Entity(selector), rest)
+        }
+    };
+    Ok((Some(value), rest))
+}
+
+/// Split off everything up to the next space.
+fn split_word(input: &str) -> (&str, &str) {
+    input.split_at(input.find(' ').unwrap_or(input.len()))
+}
+
+/// A single word, or a double-quoted phrase with `\"` and `\\` escapes.
+fn read_quotable(input: &str) -> Option<(String, &str)> {
+    let Some(quoted) = input.strip_prefix('"') else {
+        let (word, rest) = split_word(input);
+        return (!word.is_empty()).then(|| (word.to_owned(), rest));
+    };
+    let mut value = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => value.push(chars.next()?.1),
+            '"' => return Some((value, &quoted[i + 1..])),
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcrs_protocol::{Decode, Encode};
+
+    #[derive(Resource, Default)]
+    struct Said(Vec<String>);
+
+    fn say(world: &mut World, context: &CommandContext) {
+        let message = context.string("message").unwrap().to_owned();
+        world.resource_mut::<Said>().0.push(message);
+    }
+
+    fn teleport(_: &mut World, _: &CommandContext) {}
+
+    fn say_dispatcher() -> CommandDispatcher {
+        let mut dispatcher = CommandDispatcher::default();
+        dispatcher.register(literal("say").then(
+            argument("message", ArgumentParser::String(StringKind::GreedyPhrase)).executes(say),
+        ));
+        dispatcher
+    }
+
+    #[test]
+    fn say_builds_a_graph_and_dispatches_its_message() {
+        let dispatcher = say_dispatcher();
+
+        let mut data = Vec::new();
+        dispatcher.commands_packet(0).encode(&mut data).unwrap();
+        let packet = ClientboundCommands::decode(&mut data.as_slice()).unwrap();
+        let nodes = &packet.nodes;
+        assert_eq!(nodes.len(), 3);
+        let root = &nodes[packet.root_index.0 as usize];
+        assert_eq!(root.kind, CommandNodeKind::Root);
+        assert!(!root.executable);
+        assert_eq!(root.children.len(), 1);
+        let say_node = &nodes[root.children[0].0 as usize];
+        assert_eq!(say_node.kind, CommandNodeKind::Literal("say".to_owned()));
+        assert!(!say_node.executable);
+        let message = &nodes[say_node.children[0].0 as usize];
+        assert_eq!(
+            message.kind,
+            CommandNodeKind::Argument {
+                name: "message".to_owned(),
+                parser: ArgumentParser::String(StringKind::GreedyPhrase),
+                suggestions: None,
+            }
+        );
+        assert!(message.executable);
+        assert!(message.children.is_empty());
+
+        let mut world = World::new();
+        world.init_resource::<Said>();
+        let parsed = dispatcher
+            .parse(Entity::PLACEHOLDER, 0, "say hello  world")
+            .unwrap();
+        (parsed.handler)(&mut world, &parsed.context);
+        assert_eq!(world.resource::<Said>().0, ["hello  world"]);
+
+        assert_eq!(
+            dispatcher.parse(Entity::PLACEHOLDER, 0, "say").unwrap_err(),
+            CommandError::Incomplete
+        );
+        assert_eq!(
+            dispatcher
+                .parse(Entity::PLACEHOLDER, 0, "shout hi")
+                .unwrap_err(),
+            CommandError::Unknown
+        );
+    }
+
+    #[test]
+    fn redirects_continue_at_the_target() {
+        let mut dispatcher = say_dispatcher();
+        let integer = || ArgumentParser::Integer {
+            min: None,
+            max: None,
+        };
+        dispatcher.register(
+            literal("teleport").then(
+                argument("x", integer()).then(
+                    argument("y", integer()).then(argument("z", integer()).executes(teleport)),
+                ),
+            ),
+        );
+        dispatcher.register(literal("tp").redirect(&["teleport"]));
+        dispatcher.register(literal("run").redirect(&[]));
+
+        let packet = dispatcher.commands_packet(0);
+        let tp = packet
+            .nodes
+            .iter()
+            .find(|node| node.kind == CommandNodeKind::Literal("tp".to_owned()))
+            .unwrap();
+        assert!(tp.children.is_empty());
+        let target = &packet.nodes[tp.redirect.unwrap().0 as usize];
+        assert_eq!(target.kind, CommandNodeKind::Literal("teleport".to_owned()));
+
+        let parsed = dispatcher
+            .parse(Entity::PLACEHOLDER, 0, "tp 1 -2 3")
+            .unwrap();
+        assert_eq!(parsed.context.integer("x"), Some(1));
+        assert_eq!(parsed.context.integer("y"), Some(-2));
+        assert_eq!(parsed.context.integer("z"), Some(3));
+        assert_eq!(
+            dispatcher
+                .parse(Entity::PLACEHOLDER, 0, "tp 1 two 3")
+                .unwrap_err(),
+            CommandError::InvalidArgument("y".to_owned())
+        );
+
+        let parsed = dispatcher
+            .parse(Entity::PLACEHOLDER, 0, "run run say hi")
+            .unwrap();
+        assert_eq!(parsed.context.string("message"), Some("hi"));
+    }
+
+    #[test]
+    fn commands_above_the_op_level_are_hidden_and_unknown() {
+        let mut dispatcher = say_dispatcher();
+        dispatcher.register(
+            literal("teleport").requires(2).then(
+                argument(
+                    "destination",
+                    ArgumentParser::Entity {
+                        single: true,
+                        players_only: true,
+                    },
+                )
+                .executes(teleport),
+            ),
+        );
+        dispatcher.register(literal("tp").requires(2).redirect(&["teleport"]));
+        dispatcher.register(literal("run").redirect(&[]));
+
+        let literals = |op_level| {
+            let packet = dispatcher.commands_packet(op_level);
+            let root = &packet.nodes[packet.root_index.0 as usize];
+            root.children
+                .iter()
+                .map(|child| match &packet.nodes[child.0 as usize].kind {
+                    CommandNodeKind::Literal(literal) => literal.clone(),
+                    kind => panic!("{kind:?} under the root"),
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(literals(0), ["say", "run"]);
+        assert_eq!(literals(2), ["say", "teleport", "tp", "run"]);
+        let packet = dispatcher.commands_packet(0);
+        assert_eq!(packet.nodes.len(), 4);
+        let run = packet
+            .nodes
+            .iter()
+            .find(|node| node.kind == CommandNodeKind::Literal("run".to_owned()))
+            .unwrap();
+        assert_eq!(run.redirect, Some(packet.root_index));
+
+        for input in ["teleport Alice", "tp Alice", "run tp Alice"] {
+            assert_eq!(
+                dispatcher.parse(Entity::PLACEHOLDER, 0, input).unwrap_err(),
+                CommandError::Unknown
+            );
+            assert!(dispatcher.parse(Entity::PLACEHOLDER, 2, input).is_ok());
+        }
+        assert!(dispatcher.parse(Entity::PLACEHOLDER, 0, "say hi").is_ok());
+    }
+}
@@ -0,0 +1,91 @@
+//! Chat commands. Each dimension sub-app holds a [`CommandDispatcher`]: the
+//! part of its graph a player's op level allows is sent to them as they join,
+//! and the Chat Command packets they send are parsed against it and run
+//! inside the dimension.
+
+mod builtin;
+pub mod dispatcher;
+
+use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
+use crate::world::entity::player::HostAnchor;
+use crate::world::entity::player::ability::PlayerOpLevel;
+use bevy_app::{App, Plugin};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::{MessageWriter, Messages};
+use bevy_ecs::observer::On;
+use bevy_ecs::prelude::{Commands, Query, Res};
+use bevy_ecs::world::World;
+pub use dispatcher::{
+    ArgumentValue, CommandBuilder, CommandContext, CommandDispatcher, CommandError, CommandHandler,
+    EntitySelector, argument, literal,
+};
+use mcrs_network::event::ReceivedPacketEvent;
+use mcrs_protocol::Text;
+use mcrs_protocol::packets::game::serverbound::ServerboundChatCommand;
+use mcrs_protocol::text::Color;
+use tracing::info;
+
+pub struct CommandPlugin;
+
+impl Plugin for CommandPlugin {
+    fn build(&self, app: &mut App) {
+        let mut dispatcher = CommandDispatcher::default();
+        builtin::register(&mut dispatcher);
+        app.insert_resource(dispatcher);
+        app.add_observer(handle_command);
+    }
+}
+
+/// Parse a Chat Command and queue its handler to run with the whole world.
+/// Input that does not parse, or names a command above the sender's op
+/// level, is answered with vanilla's error message.
+fn handle_command(
+    event: On<ReceivedPacketEvent>,
+    dispatcher: Res<CommandDispatcher>,
+    senders: Query<(&HostAnchor, &PlayerOpLevel)>,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
+    mut commands: Commands,
+) {
+    let Some(pkt) = event.decode::<ServerboundChatCommand>() else {
+        return;
+    };
+    let Ok((host_anchor, op_level)) = senders.get(event.entity) else {
+        return;
+    };
+    let input: &str = pkt.command.0;
+    info!("command from {:?}: /{}", event.entity, input);
+    match dispatcher.parse(event.entity, op_level.clamped(), input) {
+        Ok(parsed) => {
+            commands.queue(move |world: &mut World| {
+                (parsed.handler)(world, &parsed.context);
+            });
+        }
+        Err(err) => {
+            packet_writer.write(OutboundPlayerPacket {
+                target: PacketTarget::SinglePlayer(host_anchor.0),
+                priority: PacketPriority::Normal,
+                data: PacketPayload::SystemChat {
+                    content: Text::translate(err.translation_key(), vec![]).color(Color::RED),
+                    overlay: false,
+                },
+            });
+        }
+    }
+}
+
+/// Send `content` to the player `entity` as a system message.
+pub fn send_feedback(world: &mut World, entity: Entity, content: Text) {
+    let Some(&HostAnchor(host)) = world.get::<HostAnchor>(entity) else {
+        return;
+    };
+    world
+        .resource_mut::<Messages<OutboundPlayerPacket>>()
+        .write(OutboundPlayerPacket {
+            target: PacketTarget::SinglePlayer(host),
+            priority: PacketPriority::Normal,
+            data: PacketPayload::SystemChat {
+                content,
+                overlay: false,
+            },
+        });
+}
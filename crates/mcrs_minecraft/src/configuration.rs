@@ -1,10 +1,14 @@
 use crate::dimension_type::DimensionType;
 use crate::login::GameProfile;
+use crate::spawn_point::SpawnPoint;
 use crate::version::VERSION_ID;
-use crate::world::bus::{InboundPlayerSpawn, PendingInboundLifecycle, PlayerTransferSnapshot};
-use crate::world::entity::player::column_view::ColumnView;
+use crate::world::bus::{
+    InboundPlayerDespawn, InboundPlayerSpawn, PendingInboundLifecycle, PlayerTransferSnapshot,
+    SpawnReason,
+};
+use crate::world::entity::player::default_game_mode;
 use crate::world::player_index::{HostAnchorRef, PlayerIndex};
-use crate::world::sub_app_builder::DimSubAppHandle;
+use crate::world::sub_app_builder::{DimLabel, DimSubAppHandle};
 use crate::world_preset_loader::{
     DimensionTypeAsset, DimensionTypeLoader, WorldPresetAsset, WorldPresetLoader,
     resolve_preset_asset_path,
@@ -15,12 +19,12 @@ use bevy_ecs::component::Component;
 use bevy_ecs::message::MessageReader;
 use bevy_ecs::prelude::{Changed, Commands, Entity, On, Query, ResMut, With, Without};
 use bevy_ecs::resource::Resource;
-use bevy_ecs::system::Res;
-use bevy_math::{DVec3, Vec2};
+use bevy_ecs::system::{EntityCommand, Res};
+use bevy_ecs::world::{EntityWorldMut, World};
 use mcrs_core::RegistryAccess;
 use mcrs_core::registry::access::ErasedRegistrySnapshot;
+use mcrs_core::tag::TaggedRegistry;
 use mcrs_core::tag::registry::TagRegistry;
-use mcrs_engine::entity::player::chunk_view::PlayerChunkObserver;
 use mcrs_network::event::ReceivedPacketEvent;
 use mcrs_network::{ConnectionState, InGameConnectionState, ServerSideConnection};
 use mcrs_protocol::packets::configuration::clientbound::{
@@ -196,6 +200,21 @@ fn load_dynamic_registry_tags(
     groups
 }
 
+/// Serialize a static registry's tags for `ClientboundUpdateTags`: one group
+/// per tag listing its members' registry ids, sorted by tag name.
+fn static_tag_groups<T: TaggedRegistry>(tags: &TagRegistry<T>) -> Vec<TagGroup<'static>> {
+    let mut groups: Vec<TagGroup> = tags
+        .iter()
+        .map(|(tag_loc, bitset)| TagGroup {
+            name: Ident::new(Cow::Owned(tag_loc.as_str().to_string()))
+                .unwrap_or_else(|_| Ident::new(Cow::Borrowed("minecraft:unknown")).unwrap()),
+            entries: bitset.iter().map(|id| VarInt(id.raw() as i32)).collect(),
+        })
+        .collect();
+    groups.sort_by(|a, b| a.name.as_str().cmp(b.name.as_str()));
+    groups
+}
+
 /// Marker for a connection that has been sent `ClientboundSelectKnownPacks`
 /// and is awaiting the client's `ServerboundSelectKnownPacks` response
 /// before the rest of the Configuration data is sent.
@@ -203,6 +222,21 @@ fn load_dynamic_registry_tags(
 #[component(storage = "SparseSet")]
 struct AwaitingKnownPacks;
 
+/// Marker for a connection that has been sent `ClientboundFinishConfiguration`
+/// and is awaiting the client's `ServerboundFinishConfiguration` acknowledgement.
+/// Only a connection carrying it may transition to `ConnectionState::Game`.
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+struct AwaitingFinishAck;
+
+/// Marker for an in-game connection that has been sent
+/// `ClientboundStartConfiguration` and is awaiting the client's
+/// `ServerboundConfigurationAcknowledged`. Only a connection carrying it may
+/// go back to `ConnectionState::Configuration`.
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+struct AwaitingReconfiguration;
+
 /// True iff the entry's NBT body should be omitted from `ClientboundRegistryData`
 /// because the client already has the pack that sourced it.
 ///
@@ -231,6 +265,7 @@ impl Plugin for ConfigurationStatePlugin {
 
         app.init_resource::<LoadedWorldPreset>();
         app.init_resource::<LoadedDimensionTypes>();
+        app.init_resource::<SpawnPoint>();
 
         app.add_systems(Startup, start_loading_world_preset);
         app.add_systems(Update, (process_loaded_world_preset, sync_dimension_type_changes));
@@ -344,7 +379,7 @@ fn sync_dimension_type_changes(
     mut dim_type_events: MessageReader<AssetEvent<DimensionTypeAsset>>,
     dim_type_assets: Res<Assets<DimensionTypeAsset>>,
     mut loaded_dim_types: ResMut<LoadedDimensionTypes>,
-    mut players: Query<(Entity, &mut ServerSideConnection), With<InGameConnectionState>>,
+    players: Query<Entity, With<InGameConnectionState>>,
     mut commands: Commands,
 ) {
     let mut changed = false;
@@ -383,14 +418,73 @@ fn sync_dimension_type_changes(
     }
 
     if changed {
-        for (entity, mut con) in players.iter_mut() {
-            info!("Sending reconfiguration to connected player");
-            con.write_packet(&ClientboundStartConfiguration);
-            commands
-                .entity(entity)
-                .remove::<ColumnView>()
-                .remove::<PlayerChunkObserver>();
+        for entity in players.iter() {
+            commands.entity(entity).queue(begin_reconfiguration());
+        }
+    }
+}
+
+/// Send an in-game connection back to Configuration, e.g. after a datapack
+/// reload or to switch its resource packs.
+///
+/// Writes `ClientboundStartConfiguration`; once the client acknowledges,
+/// [`on_game_configuration_ack`] moves the connection to
+/// `ConnectionState::Configuration` and the regular handshake re-sends the
+/// registries and ends with `ClientboundFinishConfiguration`, which brings it
+/// back into Game. Does nothing for a connection that is not in Game or is
+/// already reconfiguring.
+///
+/// The client drops its level, so the player also leaves its dimension: the
+/// in-dim entity is despawned and [`emit_initial_player_spawn`] places the
+/// player again, with a fresh Login and chunk stream, once the connection is
+/// back in Game.
+pub fn begin_reconfiguration() -> impl EntityCommand {
+    |mut entity: EntityWorldMut| {
+        if entity.get::<ConnectionState>() != Some(&ConnectionState::Game)
+            || entity.contains::<AwaitingReconfiguration>()
+        {
+            return;
+        }
+        let Some(mut con) = entity.get_mut::<ServerSideConnection>() else {
+            return;
+        };
+        info!("Sending reconfiguration to {}", con.remote_addr());
+        con.write_packet(&ClientboundStartConfiguration);
+        entity.insert(AwaitingReconfiguration);
+        if let Some(&HostAnchorRef(host_anchor)) = entity.get::<HostAnchorRef>() {
+            entity.world_scope(|world| leave_dimension(world, host_anchor));
+        }
+    }
+}
+
+/// Despawn `host_anchor`'s in-dim entity, in the dimension it is leaving if
+/// it is mid-transfer too, and mark it as not yet placed so
+/// [`emit_initial_player_spawn`] treats its next Game entry as a join.
+fn leave_dimension(world: &mut World, host_anchor: Entity) {
+    let Some(mut player_index) = world.get_resource_mut::<PlayerIndex>() else {
+        return;
+    };
+    let Some(location) = player_index.get_mut(&host_anchor) else {
+        return;
+    };
+    let current_dim = std::mem::replace(&mut location.current_dim, Entity::PLACEHOLDER);
+    let previous_dim = location
+        .previous_dim
+        .take()
+        .filter(|dim| *dim != current_dim);
+    location.in_dim_entity = None;
+
+    let mut lifecycle = world.resource_mut::<PendingInboundLifecycle>();
+    for dim in [Some(current_dim), previous_dim].into_iter().flatten() {
+        if dim == Entity::PLACEHOLDER {
+            continue;
         }
+        lifecycle
+            .per_dim
+            .entry(dim)
+            .or_default()
+            .despawns
+            .push(InboundPlayerDespawn { host_anchor });
     }
 }
 
@@ -401,7 +495,7 @@ fn sync_dimension_type_changes(
 /// re-trigger the negotiation while a previous negotiation is still in
 /// flight (e.g. a stray `Changed<ConnectionState>` event from a separate
 /// system mutating other connection components).
-fn on_configuration_enter(
+pub fn on_configuration_enter(
     mut query: Query<
         (Entity, &mut ServerSideConnection, &ConnectionState),
         (Changed<ConnectionState>, Without<AwaitingKnownPacks>),
@@ -428,10 +522,10 @@ fn on_configuration_enter(
 /// `ServerboundSelectKnownPacks`. Sends `ClientboundRegistryData` for the
 /// 23 synced registries (alphabetical order), the `environment_attribute`
 /// special case, `ClientboundUpdateTags` for the 7 tag-capable registries,
-/// and finally `ClientboundFinishConfiguration`. Removes the
-/// `AwaitingKnownPacks` marker so the connection is eligible for future
-/// reconfiguration.
-fn on_known_packs_response(
+/// and finally `ClientboundFinishConfiguration`. Swaps the
+/// `AwaitingKnownPacks` marker for `AwaitingFinishAck`, so the connection is
+/// eligible for future reconfiguration once the client acknowledges.
+pub fn on_known_packs_response(
     event: On<ReceivedPacketEvent>,
     mut query: Query<(Entity, &mut ServerSideConnection), With<AwaitingKnownPacks>>,
     access: Res<RegistryAccess>,
@@ -532,59 +626,27 @@ fn on_known_packs_response(
     let mut tag_registries = Vec::new();
 
     if !block_tags.is_empty() {
-        let groups: Vec<TagGroup> = block_tags
-            .iter()
-            .map(|(tag_loc, bitset)| TagGroup {
-                name: Ident::new(Cow::Owned(tag_loc.as_str().to_string()))
-                    .unwrap_or_else(|_| Ident::new(Cow::Borrowed("minecraft:unknown")).unwrap()),
-                entries: bitset.iter().map(|id| VarInt(id.raw() as i32)).collect(),
-            })
-            .collect();
         tag_registries.push(RegistryTags {
             registry: ident!("minecraft:block").into(),
-            tags: groups,
+            tags: static_tag_groups(&block_tags),
         });
     }
     if !item_tags.is_empty() {
-        let groups: Vec<TagGroup> = item_tags
-            .iter()
-            .map(|(tag_loc, bitset)| TagGroup {
-                name: Ident::new(Cow::Owned(tag_loc.as_str().to_string()))
-                    .unwrap_or_else(|_| Ident::new(Cow::Borrowed("minecraft:unknown")).unwrap()),
-                entries: bitset.iter().map(|id| VarInt(id.raw() as i32)).collect(),
-            })
-            .collect();
         tag_registries.push(RegistryTags {
             registry: ident!("minecraft:item").into(),
-            tags: groups,
+            tags: static_tag_groups(&item_tags),
         });
     }
     if !enchantment_tags.is_empty() {
-        let groups: Vec<TagGroup> = enchantment_tags
-            .iter()
-            .map(|(tag_loc, bitset)| TagGroup {
-                name: Ident::new(Cow::Owned(tag_loc.as_str().to_string()))
-                    .unwrap_or_else(|_| Ident::new(Cow::Borrowed("minecraft:unknown")).unwrap()),
-                entries: bitset.iter().map(|id| VarInt(id.raw() as i32)).collect(),
-            })
-            .collect();
         tag_registries.push(RegistryTags {
             registry: ident!("minecraft:enchantment").into(),
-            tags: groups,
+            tags: static_tag_groups(&enchantment_tags),
         });
     }
     if !entity_type_tags.is_empty() {
-        let groups: Vec<TagGroup> = entity_type_tags
-            .iter()
-            .map(|(tag_loc, bitset)| TagGroup {
-                name: Ident::new(Cow::Owned(tag_loc.as_str().to_string()))
-                    .unwrap_or_else(|_| Ident::new(Cow::Borrowed("minecraft:unknown")).unwrap()),
-                entries: bitset.iter().map(|id| VarInt(id.raw() as i32)).collect(),
-            })
-            .collect();
         tag_registries.push(RegistryTags {
             registry: ident!("minecraft:entity_type").into(),
-            tags: groups,
+            tags: static_tag_groups(&entity_type_tags),
         });
     }
 
@@ -649,12 +711,19 @@ fn on_known_packs_response(
 
     con.write_packet(&ClientboundFinishConfiguration);
 
-    commands.entity(entity).remove::<AwaitingKnownPacks>();
+    commands
+        .entity(entity)
+        .remove::<AwaitingKnownPacks>()
+        .insert(AwaitingFinishAck);
 }
 
-fn on_configuration_ack(
+/// Step 3 of the Configuration handshake: triggered by
+/// `ServerboundFinishConfiguration`. Transitions to `ConnectionState::Game`
+/// only after the server has sent `ClientboundFinishConfiguration`; an early
+/// acknowledgement (before the registries went out) is ignored.
+pub fn on_configuration_ack(
     event: On<ReceivedPacketEvent>,
-    mut query: Query<(Entity, &mut ConnectionState)>,
+    mut query: Query<(Entity, &mut ConnectionState), With<AwaitingFinishAck>>,
     mut commands: Commands,
 ) {
     let Ok((entity, mut state)) = query.get_mut(event.entity) else {
@@ -666,16 +735,22 @@ fn on_configuration_ack(
     let Some(_) = event.decode::<ServerboundFinishConfiguration>() else {
         return;
     };
-    *state = ConnectionState::Game;
-    commands.entity(entity).insert(InGameConnectionState);
+    if !state.transition_to(ConnectionState::Game) {
+        return;
+    }
+    commands
+        .entity(entity)
+        .remove::<AwaitingFinishAck>()
+        .insert(InGameConnectionState);
 }
 
-/// Handles `ServerboundConfigurationAcknowledged` (packet 0x0F) sent during Game state.
+/// Handles `ServerboundConfigurationAcknowledged` (packet 0x10) sent during Game state.
 /// This is the client's response to `ClientboundStartConfiguration` during reconfiguration.
-/// Transitions the connection back to Configuration so registries can be re-sent.
-fn on_game_configuration_ack(
+/// Transitions the connection back to Configuration so registries can be re-sent; an
+/// acknowledgement the server never asked for is ignored.
+pub fn on_game_configuration_ack(
     event: On<ReceivedPacketEvent>,
-    mut query: Query<(Entity, &mut ConnectionState)>,
+    mut query: Query<(Entity, &mut ConnectionState), With<AwaitingReconfiguration>>,
     mut commands: Commands,
 ) {
     let Ok((entity, mut state)) = query.get_mut(event.entity) else {
@@ -688,16 +763,21 @@ fn on_game_configuration_ack(
         return;
     };
     info!("Player {:?} acknowledged reconfiguration", entity);
-    *state = ConnectionState::Configuration;
-    commands.entity(entity).remove::<InGameConnectionState>();
+    if !state.transition_to(ConnectionState::Configuration) {
+        return;
+    }
+    commands
+        .entity(entity)
+        .remove::<(InGameConnectionState, AwaitingReconfiguration)>();
 }
 
 /// Runs each Update tick. For every connection in `InGameConnectionState` whose
 /// host-anchor still has `current_dim == Entity::PLACEHOLDER` (initial join not yet
-/// emitted), picks the first live `DimSubAppHandle` label entity (keyed by insertion
-/// order, which is consistent within a tick) and buffers one `InboundPlayerSpawn`
-/// into `PendingInboundLifecycle.per_dim[dim_label].spawns`, then sets
-/// `PlayerLocation.current_dim` to that label.
+/// emitted), picks the live `DimSubAppHandle` label entity whose [`DimLabel`] is the
+/// [`SpawnPoint`]'s dimension (the first live one, keyed by insertion order, if
+/// none is) and buffers one `InboundPlayerSpawn`
+/// at the [`SpawnPoint`] into `PendingInboundLifecycle.per_dim[dim_label].spawns`,
+/// then sets `PlayerLocation.current_dim` to that label.
 ///
 /// `current_dim` is set to the DimSubAppHandle LABEL entity (the key used by
 /// `PendingInboundLifecycle` and the extract closure), NOT a sub-app-internal
@@ -705,16 +785,31 @@ fn on_game_configuration_ack(
 ///
 /// If no live label entity exists yet (dims still loading), the emit is deferred:
 /// no spawn is pushed and `current_dim` stays `PLACEHOLDER`. The idempotent guard
-/// (`current_dim != PLACEHOLDER`) ensures at most one initial-join spawn per player.
+/// (`current_dim != PLACEHOLDER`) ensures at most one join spawn per entry into
+/// Game; [`begin_reconfiguration`] resets it so the player is placed again
+/// once the client has finished reconfiguring.
 pub fn emit_initial_player_spawn(
-    connections: Query<&HostAnchorRef, With<InGameConnectionState>>,
+    connections: Query<
+        &HostAnchorRef,
+        (
+            With<InGameConnectionState>,
+            Without<AwaitingReconfiguration>,
+        ),
+    >,
     mut player_index: ResMut<PlayerIndex>,
-    live_dims: Query<Entity, With<DimSubAppHandle>>,
+    live_dims: Query<(Entity, &DimLabel), With<DimSubAppHandle>>,
     profiles: Query<&GameProfile>,
+    spawn_point: Res<SpawnPoint>,
     mut lifecycle: ResMut<PendingInboundLifecycle>,
 ) {
-    let dim_label = match live_dims.iter().next() {
-        Some(e) => e,
+    // Players join in the spawn point's dimension. A spawn point naming a
+    // dimension with no sub-app falls back to the first live one, the way
+    // vanilla falls back to the overworld.
+    let spawn_dim = live_dims
+        .iter()
+        .find(|(_, label)| label.0 == spawn_point.dimension.as_str());
+    let (dim_label, label) = match spawn_dim.or_else(|| live_dims.iter().next()) {
+        Some(dim) => dim,
         None => return,
     };
 
@@ -732,21 +827,33 @@ pub fn emit_initial_player_spawn(
         let snapshot = PlayerTransferSnapshot {
             uuid: profile.id,
             username: profile.username.clone(),
-            position: DVec3::new(0.0, 100.0, 0.0),
-            rotation: Vec2::ZERO,
+            position: spawn_point.player_position(),
+            rotation: spawn_point.rotation(),
+            game_mode: default_game_mode(),
         };
+        if label.0 != spawn_point.dimension.as_str() {
+            warn!(
+                spawn_dimension = %spawn_point.dimension,
+                dimension = %label.0,
+                "spawn point names a dimension with no live sub-app; joining another"
+            );
+        }
         location.current_dim = dim_label;
         lifecycle
             .per_dim
             .entry(dim_label)
             .or_default()
             .spawns
-            .push(InboundPlayerSpawn { host_anchor, snapshot });
+            .push(InboundPlayerSpawn {
+                host_anchor,
+                snapshot,
+                reason: SpawnReason::Join,
+            });
     }
 }
 
 #[derive(Default, Resource)]
-pub(crate) struct LoadedDimensionTypes(pub Vec<(Ident<String>, DimensionType)>);
+pub struct LoadedDimensionTypes(pub Vec<(Ident<String>, DimensionType)>);
 
 /// Resource containing the loaded world preset with ordered dimensions.
 /// The dimensions are sorted alphabetically by dimension key for deterministic ordering.
@@ -1,5 +1,9 @@
 use mcrs_nbt::compound::NbtCompound;
-use mcrs_protocol::{Ident, Text};
+use mcrs_nbt::tag::NbtTag;
+use mcrs_protocol::dialog::DialogHolder;
+use mcrs_protocol::packets::common::serverbound::CustomClickAction;
+use mcrs_protocol::packets::game::clientbound::ClientboundShowDialog;
+use mcrs_protocol::{Ident, Text, WritePacket};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -45,6 +49,49 @@ pub enum Dialog {
     },
 }
 
+impl Dialog {
+    /// The dialog as the NBT compound the Show Dialog packets carry.
+    pub fn to_nbt(&self) -> Result<NbtCompound, mcrs_nbt::Error> {
+        mcrs_nbt::to_nbt_compound(self)
+    }
+}
+
+/// Open `dialog` on a client in the Game state. The definition is sent inline,
+/// so it does not have to be part of the synced `minecraft:dialog` registry.
+pub fn show_dialog(conn: &mut impl WritePacket, dialog: &Dialog) -> Result<(), mcrs_nbt::Error> {
+    conn.write_packet(&ClientboundShowDialog {
+        dialog: DialogHolder::Direct(dialog.to_nbt()?),
+    });
+    Ok(())
+}
+
+/// A submitted dialog, read back from the serverbound Custom Click Action sent
+/// by a `dynamic/custom` button. The payload holds the button's `additions`
+/// plus one entry per input, keyed by the input's `key`.
+#[derive(Clone, Debug)]
+pub struct DialogResponse<'a> {
+    pub id: &'a str,
+    pub fields: &'a NbtCompound,
+}
+
+impl<'a> DialogResponse<'a> {
+    pub fn new(action: &'a CustomClickAction<'_>) -> Self {
+        Self {
+            id: action.id.as_str(),
+            fields: &action.payload,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&'a NbtTag> {
+        self.fields.get(key)
+    }
+
+    /// The value of a text input.
+    pub fn text(&self, key: &str) -> Option<&'a str> {
+        self.fields.get_string(key)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CommonDialogData {
     pub title: Text,
@@ -130,6 +177,7 @@ pub enum DialogAction {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
 pub enum DialogBody {
     #[serde(rename = "minecraft:plain_text")]
     PlainMessage { contents: Text, width: Option<i32> },
@@ -143,6 +191,7 @@ pub struct Input {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
 pub enum InputControl {
     #[serde(rename = "minecraft:text")]
     Text {
@@ -160,3 +209,112 @@ pub struct MultilineOptions {
     max_lines: Option<u32>,
     height: Option<u32>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcrs_protocol::packets::game::serverbound::ServerboundCustomClickAction;
+    use mcrs_protocol::{Decode, Encode};
+    use std::borrow::Cow;
+
+    fn confirmation() -> Dialog {
+        Dialog::Confirmation {
+            common: CommonDialogData {
+                title: Text::text("Leave?"),
+                body: Some(vec![DialogBody::PlainMessage {
+                    contents: Text::text("Unsaved changes will be lost."),
+                    width: None,
+                }]),
+                inputs: Some(vec![Input {
+                    key: "reason".to_string(),
+                    control: InputControl::Text {
+                        width: None,
+                        label: Text::text("Reason"),
+                        label_visible: None,
+                        initial: None,
+                        max_length: Some(32),
+                        multiline: None,
+                    },
+                }]),
+                ..Default::default()
+            },
+            yes: ActionButton {
+                button: CommonButtonData {
+                    label: Text::text("Yes"),
+                    ..Default::default()
+                },
+                action: Some(Action::DynamicCustom {
+                    id: Ident::new("mcrs:leave").unwrap().into(),
+                    additions: NbtCompound::default(),
+                }),
+            },
+            no: ActionButton::default(),
+        }
+    }
+
+    #[test]
+    fn confirmation_dialog_serializes_to_nbt() {
+        let nbt = confirmation().to_nbt().unwrap();
+        assert_eq!(nbt.get_string("type"), Some("minecraft:confirmation"));
+        let title = nbt.get_compound("title").unwrap();
+        assert_eq!(title.get_string("text"), Some("Leave?"));
+
+        let body = nbt.get_list("body").unwrap();
+        let NbtTag::Compound(body) = &body[0] else {
+            panic!("body entry must be a compound");
+        };
+        assert_eq!(body.get_string("type"), Some("minecraft:plain_text"));
+
+        let inputs = nbt.get_list("inputs").unwrap();
+        let NbtTag::Compound(input) = &inputs[0] else {
+            panic!("input entry must be a compound");
+        };
+        assert_eq!(input.get_string("type"), Some("minecraft:text"));
+        assert_eq!(input.get_string("key"), Some("reason"));
+        assert_eq!(input.get_int("max_length"), Some(32));
+
+        let yes = nbt.get_compound("yes").unwrap();
+        let action = yes.get_compound("action").unwrap();
+        assert_eq!(action.get_string("type"), Some("dynamic/custom"));
+        assert_eq!(action.get_string("id"), Some("mcrs:leave"));
+        // Unset optional fields are left out rather than written as empty tags.
+        assert!(nbt.get("external_title").is_none());
+        assert!(nbt.get_compound("no").unwrap().get("action").is_none());
+    }
+
+    #[test]
+    fn show_dialog_sends_inline_definition() {
+        let mut data = Vec::new();
+        ClientboundShowDialog {
+            dialog: DialogHolder::Direct(confirmation().to_nbt().unwrap()),
+        }
+        .encode(&mut data)
+        .unwrap();
+        let decoded = ClientboundShowDialog::decode(&mut data.as_slice()).unwrap();
+        assert_eq!(
+            decoded.dialog,
+            DialogHolder::Direct(confirmation().to_nbt().unwrap())
+        );
+        // Holder id 0 marks an inline dialog.
+        assert_eq!(data[0], 0);
+    }
+
+    #[test]
+    fn custom_click_payload_round_trips_form_fields() {
+        let mut payload = NbtCompound::default();
+        payload.put_string("reason", "afk".to_string());
+        let mut data = Vec::new();
+        ServerboundCustomClickAction(CustomClickAction {
+            id: Ident::new(Cow::Borrowed("mcrs:leave")).unwrap(),
+            payload,
+        })
+        .encode(&mut data)
+        .unwrap();
+
+        let decoded = ServerboundCustomClickAction::decode(&mut data.as_slice()).unwrap();
+        let response = DialogResponse::new(&decoded.0);
+        assert_eq!(response.id, "mcrs:leave");
+        assert_eq!(response.text("reason"), Some("afk"));
+        assert!(response.get("missing").is_none());
+    }
+}
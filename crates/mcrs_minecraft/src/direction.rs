@@ -1,4 +1,5 @@
 use bevy_math::IVec3;
+use mcrs_vanilla::block::state_properties::Axis;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::ops::BitAndAssign;
@@ -17,7 +18,16 @@ pub enum Direction {
 }
 
 impl Direction {
-    pub fn normal(&self) -> IVec3 {
+    /// Horizontal directions in clockwise order, starting north.
+    pub const HORIZONTAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// Unit vector pointing one block towards this direction.
+    pub fn offset(&self) -> IVec3 {
         match self {
             Direction::Down => IVec3::NEG_Y,
             Direction::Up => IVec3::Y,
@@ -50,6 +60,60 @@ impl Direction {
         }
     }
 
+    pub fn axis(&self) -> Axis {
+        match self {
+            Direction::Down | Direction::Up => Axis::Y,
+            Direction::North | Direction::South => Axis::Z,
+            Direction::West | Direction::East => Axis::X,
+        }
+    }
+
+    /// Rotate a quarter turn clockwise seen from above. `Up` and `Down` are
+    /// returned unchanged.
+    pub fn rotate_y_cw(&self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+            vertical => *vertical,
+        }
+    }
+
+    /// Rotate a quarter turn counter-clockwise seen from above. `Up` and
+    /// `Down` are returned unchanged.
+    pub fn rotate_y_ccw(&self) -> Direction {
+        match self {
+            Direction::North => Direction::West,
+            Direction::West => Direction::South,
+            Direction::South => Direction::East,
+            Direction::East => Direction::North,
+            vertical => *vertical,
+        }
+    }
+
+    /// Horizontal direction an entity with this yaw (in degrees) is facing,
+    /// like vanilla `Direction.fromYRot`: 0 is south, 90 is west.
+    pub fn from_yaw(yaw: f32) -> Direction {
+        match (yaw as f64 / 90.0 + 0.5).floor() as i32 & 3 {
+            0 => Direction::South,
+            1 => Direction::West,
+            2 => Direction::North,
+            _ => Direction::East,
+        }
+    }
+
+    /// The direction whose offset is exactly `normal`, if any.
+    pub fn from_normal(normal: IVec3) -> Option<Direction> {
+        Direction::all()
+            .into_iter()
+            .find(|direction| direction.offset() == normal)
+    }
+
+    pub fn horizontal() -> impl Iterator<Item = Direction> {
+        Direction::HORIZONTAL.into_iter()
+    }
+
     pub fn all() -> [Direction; 6] {
         [
             Direction::Down,
@@ -85,22 +149,27 @@ impl From<Direction> for usize {
 
 impl From<IVec3> for Direction {
     fn from(vec: IVec3) -> Self {
-        match vec {
-            IVec3::NEG_Y => Direction::Down,
-            IVec3::Y => Direction::Up,
-            IVec3::NEG_Z => Direction::North,
-            IVec3::Z => Direction::South,
-            IVec3::NEG_X => Direction::West,
-            IVec3::X => Direction::East,
-            _ => panic!("Invalid block direction vector"),
-        }
+        Direction::from_normal(vec).expect("Invalid block direction vector")
     }
 }
 
 impl From<Direction> for IVec3 {
     #[inline]
     fn from(dir: Direction) -> Self {
-        dir.normal()
+        dir.offset()
+    }
+}
+
+impl From<mcrs_protocol::Direction> for Direction {
+    fn from(direction: mcrs_protocol::Direction) -> Self {
+        match direction {
+            mcrs_protocol::Direction::Down => Direction::Down,
+            mcrs_protocol::Direction::Up => Direction::Up,
+            mcrs_protocol::Direction::North => Direction::North,
+            mcrs_protocol::Direction::South => Direction::South,
+            mcrs_protocol::Direction::West => Direction::West,
+            mcrs_protocol::Direction::East => Direction::East,
+        }
     }
 }
 
@@ -195,3 +264,69 @@ impl BitAndAssign for DirectionSet {
         self.0 &= rhs.0;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opposite_is_an_involution_on_the_same_axis() {
+        for direction in Direction::all() {
+            let opposite = direction.opposite();
+            assert_ne!(opposite, direction);
+            assert_eq!(opposite.opposite(), direction);
+            assert_eq!(opposite.axis(), direction.axis());
+            assert_eq!(opposite.offset(), -direction.offset());
+        }
+    }
+
+    #[test]
+    fn offsets_are_unit_vectors() {
+        let expected = [
+            (Direction::Down, IVec3::new(0, -1, 0)),
+            (Direction::Up, IVec3::new(0, 1, 0)),
+            (Direction::North, IVec3::new(0, 0, -1)),
+            (Direction::South, IVec3::new(0, 0, 1)),
+            (Direction::West, IVec3::new(-1, 0, 0)),
+            (Direction::East, IVec3::new(1, 0, 0)),
+        ];
+        for (direction, offset) in expected {
+            assert_eq!(direction.offset(), offset);
+            assert_eq!(Direction::from_normal(offset), Some(direction));
+        }
+        assert_eq!(Direction::from_normal(IVec3::new(1, 1, 0)), None);
+        assert_eq!(Direction::from_normal(IVec3::ZERO), None);
+    }
+
+    #[test]
+    fn horizontal_rotations_cycle() {
+        for direction in Direction::horizontal() {
+            assert_eq!(direction.rotate_y_cw().rotate_y_ccw(), direction);
+            assert_eq!(direction.rotate_y_cw().rotate_y_cw(), direction.opposite());
+        }
+        assert_eq!(Direction::North.rotate_y_cw(), Direction::East);
+        assert_eq!(Direction::Up.rotate_y_cw(), Direction::Up);
+    }
+
+    #[test]
+    fn from_yaw_quadrant_edges() {
+        let cases = [
+            (0.0, Direction::South),
+            (44.9, Direction::South),
+            (45.0, Direction::West),
+            (134.9, Direction::West),
+            (135.0, Direction::North),
+            (180.0, Direction::North),
+            (-135.0, Direction::East),
+            (-135.1, Direction::North),
+            (-45.1, Direction::East),
+            (-45.0, Direction::South),
+            (225.0, Direction::East),
+            (315.0, Direction::South),
+            (360.0, Direction::South),
+        ];
+        for (yaw, expected) in cases {
+            assert_eq!(Direction::from_yaw(yaw), expected, "yaw {yaw}");
+        }
+    }
+}
@@ -2,24 +2,26 @@ use bevy_app::{App, Plugin};
 use bevy_ecs::component::Component;
 use bevy_ecs::entity::Entity;
 use bevy_ecs::observer::On;
-use bevy_ecs::prelude::{Commands, Query};
+use bevy_ecs::prelude::{Commands, Query, Res};
 use bevy_ecs::query::Changed;
+use bevy_ecs::resource::Resource;
 use mcrs_network::event::ReceivedPacketEvent;
-use mcrs_network::{ConnectionState, ServerSideConnection};
-use mcrs_protocol::WritePacket;
+use mcrs_network::{ConnectionState, SendPriority, ServerSideConnection};
+use mcrs_protocol::Text;
 use mcrs_protocol::packets::configuration::clientbound::ClientboundKeepAlive as ConfigurationRequest;
 use mcrs_protocol::packets::configuration::serverbound::ServerboundKeepAlive as ConfigurationResponse;
 use mcrs_protocol::packets::game::clientbound::ClientboundKeepAlive as GameRequest;
 use mcrs_protocol::packets::game::serverbound::{
     ServerboundAcceptTeleportation, ServerboundKeepAlive as GameResponse,
 };
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tracing::{debug, warn};
 
 pub struct KeepAlivePlugin;
 
 impl Plugin for KeepAlivePlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<KeepAliveConfig>();
         app.add_systems(bevy_app::FixedPreUpdate, handle_keepalive);
         app.add_systems(bevy_app::FixedPreUpdate, new_connection);
         app.add_observer(handle_keepalive_response);
@@ -27,13 +29,41 @@ impl Plugin for KeepAlivePlugin {
     }
 }
 
+/// How often keep-alives are sent and how long a client may take to answer.
+#[derive(Resource, Debug, Clone)]
+pub struct KeepAliveConfig {
+    pub interval: Duration,
+    /// Time after a keep-alive is sent before an unanswered client is kicked.
+    pub timeout: Duration,
+}
+
+impl Default for KeepAliveConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(15),
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
 #[derive(Component, Debug)]
 pub struct KeepaliveState {
     pending: bool,
+    /// When the last keep-alive was sent, or when the connection changed state.
     time: Instant,
     challenge: i64,
 }
 
+/// Smoothed keep-alive round-trip time, i.e. the player's ping. Updated like
+/// vanilla: `latency = (latency * 3 + rtt) / 4`. This is what the player list
+/// should show; it doesn't jitter with single slow responses.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Latency(pub Duration);
+
+/// Raw round-trip time of the most recent keep-alive, before smoothing.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LatencySample(pub Duration);
+
 pub fn new_connection(
     query: Query<(Entity, &ConnectionState), Changed<ConnectionState>>,
     mut commands: Commands,
@@ -44,36 +74,46 @@ pub fn new_connection(
             continue;
         }
 
-        commands.entity(entity).insert(KeepaliveState {
-            pending: false,
-            time: Instant::now(),
-            challenge: 0,
-        });
+        commands
+            .entity(entity)
+            .insert(KeepaliveState {
+                pending: false,
+                time: Instant::now(),
+                challenge: 0,
+            })
+            .insert_if_new((Latency::default(), LatencySample::default()));
     }
 }
 
 pub fn handle_keepalive(
     mut query: Query<(
-        Entity,
         &mut ServerSideConnection,
         &ConnectionState,
         &mut KeepaliveState,
     )>,
-    mut commands: Commands,
+    config: Res<KeepAliveConfig>,
 ) {
     let now = Instant::now();
-    for (entity, mut con, conn_state, mut state) in query.iter_mut() {
-        if *conn_state == ConnectionState::Login {
+    for (mut con, conn_state, mut state) in query.iter_mut() {
+        if *conn_state == ConnectionState::Login || con.is_closing() {
             continue;
         }
 
-        if now.duration_since(state.time).as_secs() >= 15 {
-            if state.pending {
-                warn!("Keepalive timeout for {}", con.remote_addr());
-                commands.entity(entity).remove::<ServerSideConnection>();
-                continue;
+        let elapsed = now.duration_since(state.time);
+        if state.pending {
+            if elapsed >= config.timeout {
+                warn!(
+                    "Keepalive timeout for {}: no response to {} after {:?}",
+                    con.remote_addr(),
+                    state.challenge,
+                    elapsed
+                );
+                con.disconnect(*conn_state, Text::translate("disconnect.timeout", vec![]));
             }
+            continue;
+        }
 
+        if elapsed >= config.interval {
             state.challenge = rand::random();
             state.time = now;
             state.pending = true;
@@ -90,11 +130,11 @@ pub fn handle_keepalive(
             match conn_state {
                 ConnectionState::Configuration => {
                     let pkt = ConfigurationRequest(request);
-                    con.write_packet(&pkt);
+                    con.write_packet_with_priority(&pkt, SendPriority::High);
                 }
                 ConnectionState::Game => {
                     let pkt = GameRequest(request);
-                    con.write_packet(&pkt);
+                    con.write_packet_with_priority(&pkt, SendPriority::High);
                 }
                 ConnectionState::Login => unreachable!(),
             }
@@ -104,10 +144,16 @@ pub fn handle_keepalive(
 
 pub fn handle_keepalive_response(
     event: On<ReceivedPacketEvent>,
-    mut query: Query<(&ServerSideConnection, &ConnectionState, &mut KeepaliveState)>,
+    mut query: Query<(
+        &ServerSideConnection,
+        &ConnectionState,
+        &mut KeepaliveState,
+        Option<&mut Latency>,
+        Option<&mut LatencySample>,
+    )>,
     mut commands: Commands,
 ) {
-    let Ok((con, conn_state, mut state) ) = query.get_mut(event.entity) else {
+    let Ok((con, conn_state, mut state, latency, sample)) = query.get_mut(event.entity) else {
         return;
     };
     let keep_alive = match conn_state {
@@ -139,6 +185,13 @@ pub fn handle_keepalive_response(
         return;
     }
 
+    let rtt = event.timestamp.saturating_duration_since(state.time);
+    if let Some(mut latency) = latency {
+        latency.0 = (latency.0 * 3 + rtt) / 4;
+    }
+    if let Some(mut sample) = sample {
+        sample.0 = rtt;
+    }
     state.pending = false;
 }
 
@@ -8,31 +8,54 @@
 
 extern crate core;
 
-mod biome;
-mod client_info;
+pub mod biome;
+pub mod boss_bar;
+pub mod chat_session;
+pub mod client_info;
+pub mod command;
 pub mod runner;
 pub use runner::run_server_loop;
 pub mod configuration;
 pub mod dialog;
 mod dimension_type;
-mod direction;
+pub mod direction;
 pub mod disconnect;
 pub mod enchantment;
-mod keep_alive;
+pub mod keep_alive;
 pub mod login;
+pub mod player_list;
+pub mod resource_pack;
+pub mod scoreboard;
+pub mod server_config;
 pub mod sound;
+pub mod spawn_point;
+pub mod system_chat;
 mod tag;
+pub mod tick_rate;
 mod value;
 mod version;
-mod weight;
+pub mod weather;
+pub mod weight;
 pub mod world;
+pub mod world_border;
 pub mod world_preset_loader;
+pub mod world_time;
 
+use crate::boss_bar::BossBarPlugin;
+use crate::chat_session::ChatSessionPlugin;
 use crate::client_info::ClientInfoPlugin;
 use crate::configuration::ConfigurationStatePlugin;
 use crate::keep_alive::KeepAlivePlugin;
 use crate::login::LoginPlugin;
+use crate::player_list::PlayerListPlugin;
+use crate::resource_pack::ResourcePackPlugin;
+use crate::scoreboard::ScoreboardPlugin;
+use crate::server_config::apply_server_config;
+use crate::tick_rate::TickRatePlugin;
+use crate::weather::WeatherPlugin;
 use crate::world::WorldPlugin;
+use crate::world_border::WorldBorderPlugin;
+use crate::world_time::WorldTimePlugin;
 use bevy_app::prelude::*;
 use bevy_app::{App, Plugin, TaskPoolOptions, TaskPoolPlugin};
 use bevy_asset::AssetPlugin;
@@ -55,6 +78,8 @@ pub struct ServerPlugin;
 
 impl Plugin for ServerPlugin {
     fn build(&self, app: &mut App) {
+        apply_server_config(app);
+
         #[cfg(debug_assertions)]
         app.add_plugins(TaskPoolPlugin {
             task_pool_options: TaskPoolOptions::with_num_threads(1),
@@ -72,6 +97,7 @@ impl Plugin for ServerPlugin {
             app.add_plugins(TimePlugin);
         }
         app.insert_resource(Time::<Fixed>::from_hz(DEFAULT_TPS.get() as f64));
+        app.add_plugins(TickRatePlugin);
         app.add_plugins(AssetPlugin::default());
         app.add_plugins(mcrs_core::MinecraftEnginePlugin);
         app.add_plugins(mcrs_vanilla::MinecraftCorePlugin);
@@ -79,7 +105,14 @@ impl Plugin for ServerPlugin {
         app.add_plugins(LoginPlugin);
         app.add_plugins(ConfigurationStatePlugin);
         app.add_plugins(KeepAlivePlugin);
+        app.add_plugins(PlayerListPlugin);
         app.add_plugins(WorldPlugin);
+        app.add_plugins(WorldTimePlugin);
+        app.add_plugins(WeatherPlugin);
+        app.add_plugins(WorldBorderPlugin);
+        app.add_plugins(BossBarPlugin);
+        app.add_plugins(ScoreboardPlugin);
+        app.add_plugins(ResourcePackPlugin);
         app.init_resource::<BlockStateLightTable>();
         app.add_systems(
             OnEnter(AppState::WorldgenFreeze),
@@ -88,6 +121,7 @@ impl Plugin for ServerPlugin {
                 .before(transition_to_playing),
         );
         app.add_plugins(ClientInfoPlugin);
+        app.add_plugins(ChatSessionPlugin);
     }
 }
 
@@ -1,27 +1,52 @@
 use bevy_ecs::component::Component;
 use bevy_ecs::entity::Entity;
-use bevy_ecs::lifecycle::Add;
+use bevy_ecs::lifecycle::Insert;
 use bevy_ecs::prelude::{On, Query};
 use bevy_ecs::query::{With, Without};
-use bevy_ecs::system::{Commands, ResMut};
+use bevy_ecs::resource::Resource;
+use bevy_ecs::system::{Commands, EntityCommands, Res, ResMut};
+use bevy_tasks::futures_lite::future;
+use bevy_tasks::{IoTaskPool, Task, block_on};
 use mcrs_network::event::ReceivedPacketEvent;
-use mcrs_network::{ConnectionState, ServerSideConnection};
-use mcrs_protocol::packets::login::clientbound::ClientboundLoginFinished;
-use mcrs_protocol::packets::login::serverbound::{ServerboundHello, ServerboundLoginAcknowledged};
+use mcrs_network::{ConnectionState, ServerSideConnection, transition_connection_state};
+use mcrs_protocol::packets::login::clientbound::{
+    ClientboundHello, ClientboundLoginFinished, LoginCompression,
+};
+use mcrs_protocol::packets::login::serverbound::{
+    ServerboundHello, ServerboundKey, ServerboundLoginAcknowledged,
+};
 use mcrs_protocol::profile::Property;
-use mcrs_protocol::{Bounded, WritePacket, uuid};
+use mcrs_protocol::{Bounded, CompressionThreshold, Text, VarInt, WritePacket, uuid};
+use md5::{Digest, Md5};
+use rsa::pkcs8::EncodePublicKey;
+use rsa::rand_core::OsRng;
+use rsa::{Pkcs1v15Encrypt, RsaPrivateKey, RsaPublicKey};
+use serde::Deserialize;
+use sha1::Sha1;
 use smallvec::SmallVec;
 use std::borrow::Cow;
+use thiserror::Error;
+use tracing::warn;
 
+use crate::server_config::ServerConfig;
 use crate::world::player_index::{HostAnchorRef, PlayerIndex, PlayerLocation};
 
 pub struct LoginPlugin;
 
 impl bevy_app::Plugin for LoginPlugin {
     fn build(&self, app: &mut bevy_app::App) {
+        app.init_resource::<ServerConfig>();
+        app.init_resource::<SessionServer>();
+        if app.world().resource::<ServerConfig>().online_mode
+            && !app.world().contains_resource::<ServerKeyPair>()
+        {
+            app.insert_resource(ServerKeyPair::generate());
+        }
         app.add_observer(handle_hello_packet);
+        app.add_observer(handle_key_packet);
         app.add_observer(handle_login_acknowledged);
         app.add_observer(on_login_accepted);
+        app.add_systems(bevy_app::FixedPreUpdate, finish_authentication);
     }
 }
 
@@ -68,9 +93,169 @@ impl<'a> From<&'a GameProfile> for mcrs_protocol::profile::GameProfile<'a> {
     }
 }
 
+/// The RSA key pair online-mode logins exchange the shared secret with,
+/// generated once at startup like vanilla's `MinecraftServer.keyPair`.
+#[derive(Resource)]
+pub struct ServerKeyPair {
+    private_key: RsaPrivateKey,
+    public_key_der: Vec<u8>,
+}
+
+impl ServerKeyPair {
+    /// A fresh 1024-bit key pair, the size vanilla uses.
+    pub fn generate() -> Self {
+        let private_key =
+            RsaPrivateKey::new(&mut OsRng, 1024).expect("failed to generate the server key pair");
+        let public_key_der = RsaPublicKey::from(&private_key)
+            .to_public_key_der()
+            .expect("failed to encode the server public key")
+            .as_bytes()
+            .to_vec();
+        Self {
+            private_key,
+            public_key_der,
+        }
+    }
+
+    /// The public key as X.509 `SubjectPublicKeyInfo` DER, the form sent in
+    /// Encryption Request and hashed into the server id.
+    pub fn public_key_der(&self) -> &[u8] {
+        &self.public_key_der
+    }
+
+    /// Decrypt something the client encrypted with the public key.
+    pub fn decrypt(&self, data: &[u8]) -> Option<Vec<u8>> {
+        self.private_key.decrypt(Pkcs1v15Encrypt, data).ok()
+    }
+}
+
+/// Where online-mode logins are checked, vanilla's
+/// `YggdrasilMinecraftSessionService`. Insert before [`LoginPlugin`] to point
+/// it elsewhere.
+#[derive(Resource, Debug, Clone)]
+pub struct SessionServer {
+    /// The `hasJoined` endpoint, queried with the username and server hash.
+    pub has_joined_url: String,
+}
+
+impl Default for SessionServer {
+    fn default() -> Self {
+        Self {
+            has_joined_url: "https://sessionserver.mojang.com/session/minecraft/hasJoined"
+                .to_string(),
+        }
+    }
+}
+
+/// Sent Encryption Request and waiting for the client's Encryption Response.
+#[derive(Component, Debug)]
+pub struct PendingEncryption {
+    username: String,
+    verify_token: [u8; 4],
+}
+
+/// Asking the session server whether the client joined this server.
+#[derive(Component)]
+pub struct SessionLookup(Task<Result<Option<GameProfile>, SessionError>>);
+
+#[derive(Debug, Error)]
+pub enum SessionError {
+    #[error("session server request failed: {0}")]
+    Request(#[from] ureq::Error),
+    #[error("malformed session server response: {0}")]
+    Response(#[from] serde_json::Error),
+    #[error("session server returned the invalid profile id {0:?}")]
+    ProfileId(String),
+}
+
+/// A `hasJoined` response body.
+#[derive(Deserialize)]
+struct SessionProfile {
+    id: String,
+    name: String,
+    #[serde(default)]
+    properties: Vec<Property<String>>,
+}
+
+/// Vanilla's offline-mode player UUID: the version 3 UUID of the MD5 of
+/// `OfflinePlayer:<name>`, as in `UUIDUtil.createOfflinePlayerUUID`.
+pub fn offline_uuid(username: &str) -> uuid::Uuid {
+    let mut hasher = Md5::new();
+    hasher.update(b"OfflinePlayer:");
+    hasher.update(username.as_bytes());
+    let hash: [u8; 16] = hasher.finalize()[..].try_into().unwrap();
+    uuid::Builder::from_md5_bytes(hash).into_uuid()
+}
+
+/// The server id the client and the session server agree on: the SHA-1 of
+/// `server_id`, the shared secret and the public key, printed like Java's
+/// `new BigInteger(digest).toString(16)` (signed, no leading zeros).
+pub fn server_hash(server_id: &str, shared_secret: &[u8], public_key: &[u8]) -> String {
+    let mut digest: [u8; 20] = Sha1::new()
+        .chain_update(server_id)
+        .chain_update(shared_secret)
+        .chain_update(public_key)
+        .finalize()
+        .into();
+    let negative = digest[0] & 0x80 != 0;
+    if negative {
+        // Two's complement, so the magnitude can be printed after a `-`.
+        let mut carry = true;
+        for byte in digest.iter_mut().rev() {
+            let (sum, overflow) = (!*byte).overflowing_add(carry as u8);
+            *byte = sum;
+            carry = overflow;
+        }
+    }
+    let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    let digits = match hex.trim_start_matches('0') {
+        "" => "0",
+        digits => digits,
+    };
+    if negative {
+        format!("-{digits}")
+    } else {
+        digits.to_string()
+    }
+}
+
+/// Ask the session server whether `username` joined the server with
+/// `server_hash`, vanilla `hasJoinedServer`. `None` when it did not.
+fn has_joined(
+    url: &str,
+    username: &str,
+    server_hash: &str,
+) -> Result<Option<GameProfile>, SessionError> {
+    let mut response = ureq::get(url)
+        .query("username", username)
+        .query("serverId", server_hash)
+        .call()?;
+    if response.status() == ureq::http::StatusCode::NO_CONTENT {
+        return Ok(None);
+    }
+    let body = response.body_mut().read_to_string()?;
+    let profile: SessionProfile = serde_json::from_str(&body)?;
+    let id = uuid::Uuid::parse_str(&profile.id).map_err(|_| SessionError::ProfileId(profile.id))?;
+    Ok(Some(GameProfile {
+        id,
+        username: profile.name,
+        properties: profile.properties,
+    }))
+}
+
+/// Usernames vanilla accepts: 3 to 16 ASCII letters, digits and underscores.
+pub fn is_valid_username(username: &str) -> bool {
+    (3..=16).contains(&username.len())
+        && username
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
 pub fn handle_hello_packet(
     event: On<ReceivedPacketEvent>,
     mut query: Query<(&mut ServerSideConnection, &ConnectionState), Without<LoginState>>,
+    config: Res<ServerConfig>,
+    key_pair: Option<Res<ServerKeyPair>>,
     mut commands: Commands,
 ) {
     let Ok((mut con, state)) = query.get_mut(event.entity) else {
@@ -83,19 +268,151 @@ pub fn handle_hello_packet(
     let Some(pkt) = event.decode::<ServerboundHello>() else {
         return;
     };
+    if !is_valid_username(pkt.username.0) {
+        con.disconnect(*state, Text::text("Invalid characters in username"));
+        return;
+    }
+    if config.online_mode {
+        let Some(key_pair) = key_pair else {
+            warn!("online mode is on but there is no ServerKeyPair");
+            con.disconnect(
+                *state,
+                Text::translate("multiplayer.disconnect.authservers_down", vec![]),
+            );
+            return;
+        };
+        let verify_token: [u8; 4] = rand::random();
+        con.write_packet(&ClientboundHello {
+            server_id: Bounded(""),
+            public_key: key_pair.public_key_der(),
+            verify_token: &verify_token,
+            should_authenticate: true,
+        });
+        con.expect_encryption();
+        commands.entity(event.entity).insert((
+            PendingEncryption {
+                username: pkt.username.to_string(),
+                verify_token,
+            },
+            LoginState::Key,
+        ));
+        return;
+    }
+    // Offline mode: the client's own profile id is ignored.
     let profile = GameProfile {
-        id: pkt.profile_id,
+        id: offline_uuid(pkt.username.0),
         username: pkt.username.to_string(),
         properties: Vec::new(),
     };
     println!("new profile: {profile:?}");
+    accept_login(&mut con, &config, profile, commands.entity(event.entity));
+}
+
+/// Finish the login with `profile`: compression, if configured, then Login
+/// Success.
+fn accept_login(
+    con: &mut ServerSideConnection,
+    config: &ServerConfig,
+    profile: GameProfile,
+    mut entity: EntityCommands,
+) {
+    if let Some(threshold) = config.compression_threshold {
+        con.write_packet(&LoginCompression {
+            threshold: VarInt(threshold),
+        });
+        con.set_compression(CompressionThreshold(threshold));
+    }
     let response = ClientboundLoginFinished {
         profile: (&profile).into(),
     };
     con.write_packet(&response);
+    entity.insert((profile, LoginState::Accepted));
+}
+
+/// Encryption Response: check the verify token, encrypt the connection with
+/// the shared secret and ask the session server about the client.
+pub fn handle_key_packet(
+    event: On<ReceivedPacketEvent>,
+    mut query: Query<(
+        &mut ServerSideConnection,
+        &ConnectionState,
+        &LoginState,
+        &PendingEncryption,
+    )>,
+    key_pair: Option<Res<ServerKeyPair>>,
+    session_server: Res<SessionServer>,
+    mut commands: Commands,
+) {
+    let Ok((mut con, state, login_state, pending)) = query.get_mut(event.entity) else {
+        return;
+    };
+    if ConnectionState::Login != *state || LoginState::Key != *login_state {
+        return;
+    }
+    let Some(pkt) = event.decode::<ServerboundKey>() else {
+        return;
+    };
+    let Some(key_pair) = key_pair else {
+        return;
+    };
+    let verified = key_pair
+        .decrypt(pkt.verify_token)
+        .is_some_and(|token| token == pending.verify_token);
+    let shared_secret = key_pair
+        .decrypt(pkt.shared_secret)
+        .and_then(|secret| <[u8; 16]>::try_from(secret).ok());
+    let shared_secret = match shared_secret {
+        Some(shared_secret) if verified => shared_secret,
+        _ => {
+            con.disconnect(*state, Text::text("Protocol error"));
+            return;
+        }
+    };
+    con.enable_encryption(&shared_secret);
+
+    let url = session_server.has_joined_url.clone();
+    let username = pending.username.clone();
+    let server_hash = server_hash("", &shared_secret, key_pair.public_key_der());
+    let task = IoTaskPool::get().spawn(async move { has_joined(&url, &username, &server_hash) });
     commands
         .entity(event.entity)
-        .insert((profile, LoginState::Accepted));
+        .remove::<PendingEncryption>()
+        .insert((SessionLookup(task), LoginState::Authenticating));
+}
+
+/// Accept or turn away clients whose session server lookup has finished.
+pub fn finish_authentication(
+    mut query: Query<(
+        Entity,
+        &mut ServerSideConnection,
+        &ConnectionState,
+        &mut SessionLookup,
+    )>,
+    config: Res<ServerConfig>,
+    mut commands: Commands,
+) {
+    for (entity, mut con, state, mut lookup) in &mut query {
+        let Some(result) = block_on(future::poll_once(&mut lookup.0)) else {
+            continue;
+        };
+        commands.entity(entity).remove::<SessionLookup>();
+        match result {
+            Ok(Some(profile)) => {
+                accept_login(&mut con, &config, profile, commands.entity(entity));
+            }
+            Ok(None) => con.disconnect(
+                *state,
+                Text::translate("multiplayer.disconnect.unverified_username", vec![]),
+            ),
+            Err(e) => {
+                warn!("could not verify {}: {e}", con.remote_addr());
+                con.disconnect(
+                    *state,
+                    Text::translate("multiplayer.disconnect.authservers_down", vec![]),
+                );
+            }
+        }
+    }
 }
 
 pub fn handle_login_acknowledged(
@@ -114,11 +431,11 @@ pub fn handle_login_acknowledged(
     };
     commands
         .entity(event.entity)
-        .insert(ConnectionState::Configuration);
+        .queue(transition_connection_state(ConnectionState::Configuration));
 }
 
 pub fn on_login_accepted(
-    trigger: On<Add, LoginState>,
+    trigger: On<Insert, LoginState>,
     login_state: Query<(&LoginState, &GameProfile)>,
     mut player_index: ResMut<PlayerIndex>,
     mut commands: Commands,
@@ -149,3 +466,59 @@ pub fn on_login_accepted(
         },
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_uuid_matches_vanilla() {
+        assert_eq!(
+            offline_uuid("Notch").to_string(),
+            "b50ad385-829d-3141-a216-7e7d7539ba7f"
+        );
+        assert_eq!(
+            offline_uuid("jeb_").to_string(),
+            "a762f560-4fce-3236-812a-b80efff0b62b"
+        );
+        assert_eq!(offline_uuid("Notch").get_version_num(), 3);
+    }
+
+    #[test]
+    fn server_hash_matches_java_biginteger_hex() {
+        assert_eq!(
+            server_hash("Notch", &[], &[]),
+            "4ed1f46bbe04bc756bcb17c0c7ce3e4632f06a48"
+        );
+        assert_eq!(
+            server_hash("jeb_", &[], &[]),
+            "-7c9d5b0044c130109a5d7b5fb5c317c02b4e28c1"
+        );
+        assert_eq!(
+            server_hash("simon", &[], &[]),
+            "88e16a1019277b15d58faf0541e11910eb756f6"
+        );
+        // The parts are hashed as one stream.
+        assert_eq!(
+            server_hash("No", b"t", b"ch"),
+            server_hash("Notch", &[], &[])
+        );
+    }
+
+    #[test]
+    fn username_validation() {
+        for name in ["abc", "Notch", "jeb_", "a_very_long_name"] {
+            assert!(is_valid_username(name), "{name}");
+        }
+        for name in [
+            "ab",
+            "seventeen_chars__",
+            "has space",
+            "dash-name",
+            "ñame",
+            "",
+        ] {
+            assert!(!is_valid_username(name), "{name}");
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! The tab list: who is online, with their ping and display name, kept in
+//! sync with every in-game connection through Player Info Update and Player
+//! Info Remove. Changes made during a tick go out together on the next one.
+
+use crate::keep_alive::Latency;
+use crate::login::GameProfile;
+use bevy_app::{App, FixedUpdate, Plugin};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::prelude::{Added, Changed, IntoScheduleConfigs, Query, ResMut, With};
+use bevy_ecs::resource::Resource;
+use indexmap::IndexMap;
+use mcrs_network::{InGameConnectionState, ServerSideConnection};
+use mcrs_protocol::packets::game::clientbound::{
+    ClientboundPlayerInfoRemove, ClientboundPlayerInfoUpdate,
+};
+use mcrs_protocol::profile::{PlayerListActions, PlayerListEntry};
+use mcrs_protocol::uuid::Uuid;
+use mcrs_protocol::{Text, WritePacket};
+use std::borrow::Cow;
+
+pub struct PlayerListPlugin;
+
+impl Plugin for PlayerListPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerList>();
+        app.add_systems(
+            FixedUpdate,
+            (track_player_list, broadcast_player_list).chain(),
+        );
+    }
+}
+
+/// Everyone on the tab list, in join order, keyed by connection entity.
+#[derive(Resource, Debug, Default)]
+pub struct PlayerList {
+    players: IndexMap<Entity, ListedPlayer>,
+    /// Connections that entered Game since the last broadcast, including
+    /// ones back from reconfiguration; each gets the whole roster.
+    joined: Vec<Entity>,
+    /// Players the rest of the server has not been told about yet.
+    added: Vec<Entity>,
+    /// Players whose listed flag, latency or display name changed.
+    updated: Vec<Entity>,
+    removed: Vec<Uuid>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ListedPlayer {
+    pub profile: GameProfile,
+    /// Whether the client shows the player in its tab list at all.
+    pub listed: bool,
+    /// Smoothed ping from [`Latency`], in milliseconds.
+    pub latency: i32,
+    /// Shown instead of the username when set.
+    pub display_name: Option<Text>,
+}
+
+impl PlayerList {
+    pub fn get(&self, entity: Entity) -> Option<&ListedPlayer> {
+        self.players.get(&entity)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &ListedPlayer)> {
+        self.players
+            .iter()
+            .map(|(&entity, player)| (entity, player))
+    }
+
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    pub fn set_display_name(&mut self, entity: Entity, display_name: Option<Text>) {
+        if let Some(player) = self.players.get_mut(&entity) {
+            player.display_name = display_name;
+            self.mark_updated(entity);
+        }
+    }
+
+    pub fn set_listed(&mut self, entity: Entity, listed: bool) {
+        if let Some(player) = self.players.get_mut(&entity) {
+            player.listed = listed;
+            self.mark_updated(entity);
+        }
+    }
+
+    fn entries(&self, entities: &[Entity]) -> Vec<PlayerListEntry<'_>> {
+        entities
+            .iter()
+            .filter_map(|entity| self.players.get(entity))
+            .map(ListedPlayer::entry)
+            .collect()
+    }
+
+    fn mark_updated(&mut self, entity: Entity) {
+        if !self.updated.contains(&entity) {
+            self.updated.push(entity);
+        }
+    }
+}
+
+/// Add players entering Game, drop closed connections and pick up latency
+/// changes.
+pub fn track_player_list(
+    mut list: ResMut<PlayerList>,
+    joined: Query<(Entity, &GameProfile, Option<&Latency>), Added<InGameConnectionState>>,
+    latencies: Query<(Entity, &Latency), Changed<Latency>>,
+    connected: Query<(), With<ServerSideConnection>>,
+) {
+    let list = &mut *list;
+    list.players.retain(|&entity, player| {
+        let connected = connected.contains(entity);
+        if !connected {
+            list.removed.push(player.profile.id);
+        }
+        connected
+    });
+    list.joined
+        .retain(|entity| list.players.contains_key(entity));
+    list.added
+        .retain(|entity| list.players.contains_key(entity));
+    list.updated
+        .retain(|entity| list.players.contains_key(entity));
+
+    for (entity, profile, latency) in &joined {
+        list.joined.push(entity);
+        // Back from reconfiguration: everyone else still lists the player.
+        if list.players.contains_key(&entity) {
+            continue;
+        }
+        list.players.insert(
+            entity,
+            ListedPlayer {
+                profile: profile.clone(),
+                listed: true,
+                latency: latency.map_or(0, latency_millis),
+                display_name: None,
+            },
+        );
+        list.added.push(entity);
+    }
+
+    for (entity, latency) in &latencies {
+        let millis = latency_millis(latency);
+        if let Some(player) = list.players.get_mut(&entity)
+            && player.latency != millis
+        {
+            player.latency = millis;
+            list.mark_updated(entity);
+        }
+    }
+}
+
+/// Send this tick's changes: one Player Info Remove, one Player Info Update
+/// for new players and one for changed ones. Connections that just joined
+/// get the whole roster in a single update instead.
+pub fn broadcast_player_list(
+    mut list: ResMut<PlayerList>,
+    mut connections: Query<(Entity, &mut ServerSideConnection), With<InGameConnectionState>>,
+) {
+    let joined = std::mem::take(&mut list.joined);
+    let added = std::mem::take(&mut list.added);
+    let updated = std::mem::take(&mut list.updated);
+    let removed = std::mem::take(&mut list.removed);
+    if joined.is_empty() && added.is_empty() && updated.is_empty() && removed.is_empty() {
+        return;
+    }
+
+    let list = &*list;
+    let roster: Vec<PlayerListEntry> = if joined.is_empty() {
+        Vec::new()
+    } else {
+        list.players.values().map(ListedPlayer::entry).collect()
+    };
+    let added = list.entries(&added);
+    let updated = list.entries(&updated);
+    let remove = ClientboundPlayerInfoRemove { uuids: removed };
+
+    for (entity, mut con) in &mut connections {
+        if joined.contains(&entity) {
+            // The roster already reflects this tick's changes.
+            con.write_packet(&ClientboundPlayerInfoUpdate {
+                actions: add_actions(),
+                entries: Cow::Borrowed(&roster),
+            });
+            continue;
+        }
+        if !remove.uuids.is_empty() {
+            con.write_packet(&remove);
+        }
+        if !added.is_empty() {
+            con.write_packet(&ClientboundPlayerInfoUpdate {
+                actions: add_actions(),
+                entries: Cow::Borrowed(&added),
+            });
+        }
+        if !updated.is_empty() {
+            con.write_packet(&ClientboundPlayerInfoUpdate {
+                actions: update_actions(),
+                entries: Cow::Borrowed(&updated),
+            });
+        }
+    }
+}
+
+impl ListedPlayer {
+    fn entry(&self) -> PlayerListEntry<'_> {
+        PlayerListEntry {
+            player_uuid: self.profile.id,
+            username: &self.profile.username,
+            properties: Cow::Borrowed(&self.profile.properties),
+            listed: self.listed,
+            ping: self.latency,
+            display_name: self.display_name.as_ref().map(Cow::Borrowed),
+            ..Default::default()
+        }
+    }
+}
+
+/// Game modes are per-dimension state and are sent by the dimension the
+/// player is in, so they are left out here.
+fn add_actions() -> PlayerListActions {
+    update_actions().with_add_player(true)
+}
+
+fn update_actions() -> PlayerListActions {
+    PlayerListActions::new()
+        .with_update_listed(true)
+        .with_update_latency(true)
+        .with_update_display_name(true)
+}
+
+fn latency_millis(latency: &Latency) -> i32 {
+    latency.0.as_millis().min(i32::MAX as u128) as i32
+}
@@ -0,0 +1,228 @@
+//! Server resource packs: pushing and popping them on a client, and tracking
+//! what the client reports back in its Resource Pack Response.
+
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::component::Component;
+use bevy_ecs::message::MessageReader;
+use bevy_ecs::prelude::{Query, Res};
+use bevy_ecs::resource::Resource;
+use bevy_ecs::system::EntityCommand;
+use bevy_ecs::world::EntityWorldMut;
+use indexmap::IndexMap;
+use mcrs_network::event::{ClientPacket, ServerboundPacket};
+use mcrs_network::{ConnectionState, ServerSideConnection};
+use mcrs_protocol::packets::common::clientbound::{ResourcePackPop, ResourcePackPush};
+use mcrs_protocol::packets::configuration::clientbound::{
+    ClientboundResourcePackPop as ConfigurationPop,
+    ClientboundResourcePackPush as ConfigurationPush,
+};
+use mcrs_protocol::packets::configuration::serverbound::Packet as ConfigurationPacket;
+use mcrs_protocol::packets::game::clientbound::{
+    ClientboundResourcePackPop as GamePop, ClientboundResourcePackPush as GamePush,
+};
+use mcrs_protocol::packets::game::serverbound::Packet as GamePacket;
+use mcrs_protocol::resource_pack::Status;
+use mcrs_protocol::uuid::Uuid;
+use mcrs_protocol::{Text, WritePacket};
+use std::borrow::Cow;
+use tracing::info;
+
+pub struct ResourcePackPlugin;
+
+impl Plugin for ResourcePackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ResourcePackConfig>();
+        app.add_systems(Update, track_resource_pack_responses);
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct ResourcePackConfig {
+    /// Sent to a client that declines a forced pack.
+    pub declined_forced_reason: Text,
+}
+
+impl Default for ResourcePackConfig {
+    fn default() -> Self {
+        Self {
+            declined_forced_reason: Text::translate(
+                "multiplayer.requiredTexturePrompt.disconnect",
+                vec![],
+            ),
+        }
+    }
+}
+
+/// A pack to offer a client.
+#[derive(Clone, Debug)]
+pub struct ResourcePack {
+    pub id: Uuid,
+    pub url: String,
+    /// Hex SHA-1 of the pack file. The client re-downloads when it does not
+    /// match its cached copy; empty skips the check.
+    pub hash: String,
+    /// The client can only decline by disconnecting.
+    pub forced: bool,
+    /// Shown in the client's accept prompt.
+    pub prompt: Option<Text>,
+}
+
+/// Where a client is with a pack, as far as its responses tell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourcePackStatus {
+    /// Pushed, with no response yet.
+    Pending,
+    Accepted,
+    Downloaded,
+    Loaded,
+    Declined,
+    /// The download, the URL or applying the pack failed, or the client
+    /// dropped it.
+    Failed,
+}
+
+impl From<Status> for ResourcePackStatus {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::SuccessfullyLoaded => ResourcePackStatus::Loaded,
+            Status::Declined => ResourcePackStatus::Declined,
+            Status::Accepted => ResourcePackStatus::Accepted,
+            Status::Downloaded => ResourcePackStatus::Downloaded,
+            Status::FailedDownload
+            | Status::InvalidUrl
+            | Status::FailedReload
+            | Status::Discarded => ResourcePackStatus::Failed,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct TrackedPack {
+    forced: bool,
+    status: ResourcePackStatus,
+}
+
+/// The packs pushed to a connection and their status, in push order.
+#[derive(Component, Debug, Default, Clone)]
+pub struct ResourcePacks {
+    packs: IndexMap<Uuid, TrackedPack>,
+}
+
+impl ResourcePacks {
+    pub fn status(&self, id: Uuid) -> Option<ResourcePackStatus> {
+        self.packs.get(&id).map(|pack| pack.status)
+    }
+
+    /// Whether every pack pushed so far has loaded, for gating features
+    /// behind a required pack.
+    pub fn all_loaded(&self) -> bool {
+        self.packs
+            .values()
+            .all(|pack| pack.status == ResourcePackStatus::Loaded)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Uuid, ResourcePackStatus)> {
+        self.packs.iter().map(|(&id, pack)| (id, pack.status))
+    }
+}
+
+/// Send Add Resource Pack for `pack` and start tracking it as
+/// [`ResourcePackStatus::Pending`]. Works in Configuration and Game; a
+/// connection still in Login is left alone.
+pub fn push_resource_pack(pack: ResourcePack) -> impl EntityCommand {
+    move |mut entity: EntityWorldMut| {
+        let Some(&state) = entity.get::<ConnectionState>() else {
+            return;
+        };
+        let Some(mut con) = entity.get_mut::<ServerSideConnection>() else {
+            return;
+        };
+        let push = ResourcePackPush {
+            id: pack.id,
+            url: &pack.url,
+            hash: &pack.hash,
+            required: pack.forced,
+            prompt: pack.prompt.as_ref().map(Cow::Borrowed),
+        };
+        match state {
+            ConnectionState::Login => return,
+            ConnectionState::Configuration => con.write_packet(&ConfigurationPush(push)),
+            ConnectionState::Game => con.write_packet(&GamePush(push)),
+        }
+        let tracked = TrackedPack {
+            forced: pack.forced,
+            status: ResourcePackStatus::Pending,
+        };
+        match entity.get_mut::<ResourcePacks>() {
+            Some(mut packs) => {
+                packs.packs.insert(pack.id, tracked);
+            }
+            None => {
+                let mut packs = ResourcePacks::default();
+                packs.packs.insert(pack.id, tracked);
+                entity.insert(packs);
+            }
+        }
+    }
+}
+
+/// Send Remove Resource Pack for `id`, or for every pack if `None`, and
+/// stop tracking it.
+pub fn pop_resource_pack(id: Option<Uuid>) -> impl EntityCommand {
+    move |mut entity: EntityWorldMut| {
+        let Some(&state) = entity.get::<ConnectionState>() else {
+            return;
+        };
+        let Some(mut con) = entity.get_mut::<ServerSideConnection>() else {
+            return;
+        };
+        let pop = ResourcePackPop { id };
+        match state {
+            ConnectionState::Login => return,
+            ConnectionState::Configuration => con.write_packet(&ConfigurationPop(pop)),
+            ConnectionState::Game => con.write_packet(&GamePop(pop)),
+        }
+        if let Some(mut packs) = entity.get_mut::<ResourcePacks>() {
+            match id {
+                Some(id) => {
+                    packs.packs.shift_remove(&id);
+                }
+                None => packs.packs.clear(),
+            }
+        }
+    }
+}
+
+/// Record each Resource Pack Response on the connection's [`ResourcePacks`].
+/// Declining a forced pack disconnects the client, like vanilla. Responses
+/// for packs that were never pushed are ignored.
+pub fn track_resource_pack_responses(
+    mut packets: MessageReader<ClientPacket>,
+    mut connections: Query<(&mut ServerSideConnection, &mut ResourcePacks)>,
+    config: Res<ResourcePackConfig>,
+) {
+    for packet in packets.read() {
+        let response = match packet.packet() {
+            ServerboundPacket::Configuration(ConfigurationPacket::ResourcePack(response)) => {
+                response.0
+            }
+            ServerboundPacket::Game(GamePacket::ResourcePack(response)) => response.0,
+            _ => continue,
+        };
+        let Ok((mut con, mut packs)) = connections.get_mut(packet.entity) else {
+            continue;
+        };
+        let Some(pack) = packs.packs.get_mut(&response.id) else {
+            continue;
+        };
+        pack.status = response.status.into();
+        if pack.forced && pack.status == ResourcePackStatus::Declined {
+            info!(
+                "{} declined the forced resource pack {}",
+                con.remote_addr(),
+                response.id
+            );
+            con.disconnect(packet.state, config.declined_forced_reason.clone());
+        }
+    }
+}
@@ -1,10 +1,9 @@
+use crate::tick_rate::TickRate;
 use crate::world::sub_app_builder::{drain_dim_despawn_queue, drain_dim_spawn_queue};
-use crate::DEFAULT_TPS;
 use bevy_app::App;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
 pub fn run_server_loop(mut app: App) {
-    let tick = Duration::from_secs_f64(1.0 / DEFAULT_TPS.get() as f64);
     app.finish();
     app.cleanup();
     loop {
@@ -15,6 +14,13 @@ pub fn run_server_loop(mut app: App) {
         if app.should_exit().is_some() {
             break;
         }
+        // Re-read every tick so `TickRate` changes take effect right away.
+        let tick = app
+            .world()
+            .get_resource::<TickRate>()
+            .copied()
+            .unwrap_or_default()
+            .timestep();
         let elapsed = start.elapsed();
         if elapsed < tick {
             std::thread::sleep(tick - elapsed);
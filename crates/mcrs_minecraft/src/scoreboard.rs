@@ -0,0 +1,454 @@
+//! The server scoreboard: objectives, the scores listed under them and the
+//! display slots they are shown in. Connections entering Game are sent the
+//! whole board; after that only what changed since the last tick goes out.
+
+use bevy_app::{App, FixedUpdate, Plugin};
+use bevy_ecs::prelude::{Query, Ref, ResMut};
+use bevy_ecs::resource::Resource;
+use indexmap::IndexMap;
+use mcrs_network::{InGameConnectionState, ServerSideConnection};
+use mcrs_protocol::packets::game::clientbound::{
+    ClientboundResetScore, ClientboundSetDisplayObjective, ClientboundSetObjective,
+    ClientboundSetScore,
+};
+use mcrs_protocol::scoreboard::{DisplaySlot, NumberFormat, ObjectiveInfo, ObjectiveUpdate};
+use mcrs_protocol::{Text, VarInt, WritePacket};
+
+pub struct ScoreboardPlugin;
+
+impl Plugin for ScoreboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Scoreboard>();
+        app.add_systems(FixedUpdate, broadcast_scoreboard);
+    }
+}
+
+/// Objectives by name, and the objective shown in each display slot.
+#[derive(Resource, Debug, Default)]
+pub struct Scoreboard {
+    objectives: IndexMap<String, Objective>,
+    display_slots: IndexMap<DisplaySlot, String>,
+    pending: Vec<ScoreboardUpdate>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Objective {
+    pub info: ObjectiveInfo,
+    scores: IndexMap<String, Score>,
+}
+
+impl Objective {
+    pub fn score(&self, owner: &str) -> Option<&Score> {
+        self.scores.get(owner)
+    }
+
+    pub fn scores(&self) -> impl Iterator<Item = (&str, &Score)> {
+        self.scores
+            .iter()
+            .map(|(owner, score)| (owner.as_str(), score))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Score {
+    pub value: i32,
+    /// Shown instead of the owner's name.
+    pub display_name: Option<Text>,
+    /// Overrides the objective's number format.
+    pub number_format: Option<NumberFormat>,
+}
+
+/// Something clients have not been told about yet. The packet carries the
+/// state at broadcast time, so repeated changes within a tick go out once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ScoreboardUpdate {
+    AddObjective(String),
+    ChangeObjective(String),
+    RemoveObjective(String),
+    Score { objective: String, owner: String },
+    ResetScore { objective: String, owner: String },
+    Display(DisplaySlot),
+}
+
+impl ScoreboardUpdate {
+    fn objective(&self) -> Option<&str> {
+        match self {
+            ScoreboardUpdate::AddObjective(name)
+            | ScoreboardUpdate::ChangeObjective(name)
+            | ScoreboardUpdate::RemoveObjective(name)
+            | ScoreboardUpdate::Score {
+                objective: name, ..
+            }
+            | ScoreboardUpdate::ResetScore {
+                objective: name, ..
+            } => Some(name),
+            ScoreboardUpdate::Display(_) => None,
+        }
+    }
+}
+
+impl Scoreboard {
+    /// Add an objective with no scores. Returns `false` if `name` is taken.
+    pub fn add_objective(&mut self, name: impl Into<String>, info: ObjectiveInfo) -> bool {
+        let name = name.into();
+        if self.objectives.contains_key(&name) {
+            return false;
+        }
+        self.pending
+            .push(ScoreboardUpdate::AddObjective(name.clone()));
+        self.objectives.insert(
+            name,
+            Objective {
+                info,
+                scores: IndexMap::new(),
+            },
+        );
+        true
+    }
+
+    pub fn objective(&self, name: &str) -> Option<&Objective> {
+        self.objectives.get(name)
+    }
+
+    pub fn objectives(&self) -> impl Iterator<Item = (&str, &Objective)> {
+        self.objectives
+            .iter()
+            .map(|(name, objective)| (name.as_str(), objective))
+    }
+
+    /// Change the objective's title, render type or default number format.
+    pub fn set_objective_info(&mut self, name: &str, info: ObjectiveInfo) {
+        let Some(objective) = self.objectives.get_mut(name) else {
+            return;
+        };
+        if objective.info != info {
+            objective.info = info;
+            // Not sent yet: the Add will carry the new info.
+            if !self
+                .pending
+                .contains(&ScoreboardUpdate::AddObjective(name.to_owned()))
+            {
+                self.mark(ScoreboardUpdate::ChangeObjective(name.to_owned()));
+            }
+        }
+    }
+
+    /// Drop the objective and its scores. Clients clear any display slot
+    /// showing it themselves.
+    pub fn remove_objective(&mut self, name: &str) -> Option<Objective> {
+        let objective = self.objectives.shift_remove(name)?;
+        self.display_slots.retain(|_, shown| shown != name);
+        let was_sent = !self
+            .pending
+            .contains(&ScoreboardUpdate::AddObjective(name.to_owned()));
+        self.pending.retain(|update| match update {
+            ScoreboardUpdate::RemoveObjective(_) => true,
+            update => update.objective() != Some(name),
+        });
+        if was_sent {
+            self.pending
+                .push(ScoreboardUpdate::RemoveObjective(name.to_owned()));
+        }
+        Some(objective)
+    }
+
+    pub fn score(&self, objective: &str, owner: &str) -> Option<&Score> {
+        self.objectives.get(objective)?.score(owner)
+    }
+
+    /// Set `owner`'s score for `objective`, creating it if needed. Nothing is
+    /// sent if the value is unchanged. Returns `false` if there is no such
+    /// objective.
+    pub fn set_score(&mut self, objective: &str, owner: &str, value: i32) -> bool {
+        let Some(scores) = self.objectives.get_mut(objective).map(|o| &mut o.scores) else {
+            return false;
+        };
+        match scores.get_mut(owner) {
+            Some(score) if score.value == value => return true,
+            Some(score) => score.value = value,
+            None => {
+                scores.insert(
+                    owner.to_owned(),
+                    Score {
+                        value,
+                        display_name: None,
+                        number_format: None,
+                    },
+                );
+            }
+        }
+        self.mark_score(objective, owner);
+        true
+    }
+
+    /// Set how `owner`'s score for `objective` is shown, creating it at zero
+    /// if needed. Returns `false` if there is no such objective.
+    pub fn set_score_format(
+        &mut self,
+        objective: &str,
+        owner: &str,
+        display_name: Option<Text>,
+        number_format: Option<NumberFormat>,
+    ) -> bool {
+        let Some(scores) = self.objectives.get_mut(objective).map(|o| &mut o.scores) else {
+            return false;
+        };
+        let created = !scores.contains_key(owner);
+        let score = scores.entry(owner.to_owned()).or_insert(Score {
+            value: 0,
+            display_name: None,
+            number_format: None,
+        });
+        if created || score.display_name != display_name || score.number_format != number_format {
+            score.display_name = display_name;
+            score.number_format = number_format;
+            self.mark_score(objective, owner);
+        }
+        true
+    }
+
+    /// Remove `owner`'s score for `objective`.
+    pub fn reset_score(&mut self, objective: &str, owner: &str) -> Option<Score> {
+        let score = self
+            .objectives
+            .get_mut(objective)?
+            .scores
+            .shift_remove(owner)?;
+        let update = ScoreboardUpdate::Score {
+            objective: objective.to_owned(),
+            owner: owner.to_owned(),
+        };
+        self.pending.retain(|pending| *pending != update);
+        self.mark(ScoreboardUpdate::ResetScore {
+            objective: objective.to_owned(),
+            owner: owner.to_owned(),
+        });
+        Some(score)
+    }
+
+    /// Show `objective` in `slot`, or clear the slot with `None`. Returns
+    /// `false` if there is no such objective.
+    pub fn set_display(&mut self, slot: DisplaySlot, objective: Option<&str>) -> bool {
+        let changed = match objective {
+            Some(name) if !self.objectives.contains_key(name) => return false,
+            Some(name) => self.display_slots.insert(slot, name.to_owned()).as_deref() != Some(name),
+            None => self.display_slots.shift_remove(&slot).is_some(),
+        };
+        if changed {
+            self.mark(ScoreboardUpdate::Display(slot));
+        }
+        true
+    }
+
+    /// The objective shown in `slot`.
+    pub fn display(&self, slot: DisplaySlot) -> Option<&str> {
+        self.display_slots.get(&slot).map(String::as_str)
+    }
+
+    fn mark_score(&mut self, objective: &str, owner: &str) {
+        self.mark(ScoreboardUpdate::Score {
+            objective: objective.to_owned(),
+            owner: owner.to_owned(),
+        });
+    }
+
+    fn mark(&mut self, update: ScoreboardUpdate) {
+        if !self.pending.contains(&update) {
+            self.pending.push(update);
+        }
+    }
+
+    fn set_score_packet(&self, objective: &str, owner: &str) -> Option<ClientboundSetScore> {
+        let score = self.score(objective, owner)?;
+        Some(ClientboundSetScore {
+            owner: owner.to_owned(),
+            objective: objective.to_owned(),
+            score: VarInt(score.value),
+            display_name: score.display_name.clone(),
+            number_format: score.number_format.clone(),
+        })
+    }
+
+    fn write_update(&self, update: &ScoreboardUpdate, out: &mut impl WritePacket) {
+        match update {
+            ScoreboardUpdate::AddObjective(name) | ScoreboardUpdate::ChangeObjective(name) => {
+                let Some(objective) = self.objectives.get(name) else {
+                    return;
+                };
+                let info = objective.info.clone();
+                out.write_packet(&ClientboundSetObjective {
+                    objective: name.clone(),
+                    update: match update {
+                        ScoreboardUpdate::AddObjective(_) => ObjectiveUpdate::Add(info),
+                        _ => ObjectiveUpdate::Change(info),
+                    },
+                });
+            }
+            ScoreboardUpdate::RemoveObjective(name) => out.write_packet(&ClientboundSetObjective {
+                objective: name.clone(),
+                update: ObjectiveUpdate::Remove,
+            }),
+            ScoreboardUpdate::Score { objective, owner } => {
+                if let Some(packet) = self.set_score_packet(objective, owner) {
+                    out.write_packet(&packet);
+                }
+            }
+            ScoreboardUpdate::ResetScore { objective, owner } => {
+                out.write_packet(&ClientboundResetScore {
+                    owner: owner.clone(),
+                    objective: Some(objective.clone()),
+                })
+            }
+            &ScoreboardUpdate::Display(slot) => out.write_packet(&ClientboundSetDisplayObjective {
+                slot,
+                objective: self.display(slot).unwrap_or_default().to_owned(),
+            }),
+        }
+    }
+
+    /// Every objective with its scores, then the display slots.
+    fn write_all(&self, out: &mut impl WritePacket) {
+        for (name, objective) in &self.objectives {
+            out.write_packet(&ClientboundSetObjective {
+                objective: name.clone(),
+                update: ObjectiveUpdate::Add(objective.info.clone()),
+            });
+            for owner in objective.scores.keys() {
+                if let Some(packet) = self.set_score_packet(name, owner) {
+                    out.write_packet(&packet);
+                }
+            }
+        }
+        for (&slot, objective) in &self.display_slots {
+            out.write_packet(&ClientboundSetDisplayObjective {
+                slot,
+                objective: objective.clone(),
+            });
+        }
+    }
+}
+
+/// Send the whole board to connections that entered Game this tick, and the
+/// changes made since the last tick to everyone else.
+pub fn broadcast_scoreboard(
+    mut scoreboard: ResMut<Scoreboard>,
+    mut connections: Query<(&mut ServerSideConnection, Ref<InGameConnectionState>)>,
+) {
+    let updates = std::mem::take(&mut scoreboard.pending);
+    for (mut con, in_game) in &mut connections {
+        if in_game.is_added() {
+            scoreboard.write_all(&mut *con);
+            continue;
+        }
+        for update in &updates {
+            scoreboard.write_update(update, &mut *con);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcrs_protocol::decode::PacketFrame;
+    use mcrs_protocol::{Packet, PacketDecoder, PacketEncoder};
+
+    /// Encode the pending updates the way the broadcast does and decode them
+    /// back into frames.
+    fn sent_frames(scoreboard: &mut Scoreboard) -> Vec<PacketFrame> {
+        let mut encoder = PacketEncoder::new();
+        for update in std::mem::take(&mut scoreboard.pending) {
+            scoreboard.write_update(&update, &mut encoder);
+        }
+        decode(encoder)
+    }
+
+    fn decode(mut encoder: PacketEncoder) -> Vec<PacketFrame> {
+        let mut decoder = PacketDecoder::new();
+        decoder.queue_bytes(encoder.take());
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.try_next_packet().unwrap() {
+            frames.push(frame);
+        }
+        frames
+    }
+
+    fn sidebar() -> Scoreboard {
+        let mut scoreboard = Scoreboard::default();
+        scoreboard.add_objective("kills", ObjectiveInfo::new("Kills"));
+        scoreboard.set_display(DisplaySlot::Sidebar, Some("kills"));
+        scoreboard
+    }
+
+    #[test]
+    fn only_changed_scores_are_sent() {
+        let mut scoreboard = sidebar();
+        scoreboard.set_score("kills", "Alice", 1);
+        scoreboard.set_score("kills", "Bob", 2);
+        let frames = sent_frames(&mut scoreboard);
+        let ids: Vec<i32> = frames.iter().map(|frame| frame.id).collect();
+        assert_eq!(
+            ids,
+            [
+                ClientboundSetObjective::ID,
+                ClientboundSetDisplayObjective::ID,
+                ClientboundSetScore::ID,
+                ClientboundSetScore::ID,
+            ]
+        );
+
+        scoreboard.set_score("kills", "Alice", 1);
+        scoreboard.set_score("kills", "Bob", 3);
+        let frames = sent_frames(&mut scoreboard);
+        assert_eq!(frames.len(), 1);
+        let score = frames[0].decode::<ClientboundSetScore>().unwrap();
+        assert_eq!(score.owner, "Bob");
+        assert_eq!(score.objective, "kills");
+        assert_eq!(score.score, VarInt(3));
+    }
+
+    #[test]
+    fn removing_an_unsent_objective_sends_nothing() {
+        let mut scoreboard = sidebar();
+        scoreboard.set_score("kills", "Alice", 1);
+        scoreboard.remove_objective("kills");
+        assert!(sent_frames(&mut scoreboard).is_empty());
+        assert_eq!(scoreboard.display(DisplaySlot::Sidebar), None);
+
+        // Once sent, removal is one packet and the scores go with it.
+        let mut scoreboard = sidebar();
+        scoreboard.set_score("kills", "Alice", 1);
+        sent_frames(&mut scoreboard);
+        scoreboard.set_score("kills", "Alice", 2);
+        scoreboard.remove_objective("kills");
+        let frames = sent_frames(&mut scoreboard);
+        assert_eq!(frames.len(), 1);
+        let remove = frames[0].decode::<ClientboundSetObjective>().unwrap();
+        assert_eq!(remove.update, ObjectiveUpdate::Remove);
+    }
+
+    #[test]
+    fn joining_connections_get_the_whole_board() {
+        let mut scoreboard = sidebar();
+        scoreboard.set_score("kills", "Alice", 4);
+        scoreboard.reset_score("kills", "Alice");
+        scoreboard.set_score("kills", "Bob", 5);
+        sent_frames(&mut scoreboard);
+
+        let mut encoder = PacketEncoder::new();
+        scoreboard.write_all(&mut encoder);
+        let frames = decode(encoder);
+        assert_eq!(frames.len(), 3);
+        let add = frames[0].decode::<ClientboundSetObjective>().unwrap();
+        assert_eq!(
+            add.update,
+            ObjectiveUpdate::Add(ObjectiveInfo::new("Kills"))
+        );
+        let score = frames[1].decode::<ClientboundSetScore>().unwrap();
+        assert_eq!((score.owner.as_str(), score.score), ("Bob", VarInt(5)));
+        let display = frames[2]
+            .decode::<ClientboundSetDisplayObjective>()
+            .unwrap();
+        assert_eq!(display.slot, DisplaySlot::Sidebar);
+        assert_eq!(display.objective, "kills");
+    }
+}
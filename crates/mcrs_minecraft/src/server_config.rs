@@ -0,0 +1,69 @@
+//! Server-wide settings. [`ServerPlugin`](crate::ServerPlugin) reads
+//! [`ServerConfig`] once while it is built and hands each part to the plugin
+//! that needs it, so the config must be inserted before the plugin is added.
+
+use crate::client_info::ServerViewConfig;
+use bevy_app::App;
+use bevy_ecs::resource::Resource;
+use mcrs_network::NetworkConfig;
+use mcrs_protocol::Text;
+
+/// The settings of a `server.properties`, for the parts this server supports.
+#[derive(Resource, Debug, Clone)]
+pub struct ServerConfig {
+    /// Authenticate players with Mojang's session servers. Offline mode (the
+    /// default) trusts the username and derives the UUID from it.
+    pub online_mode: bool,
+    /// Packets at least this many bytes long are compressed once the client
+    /// has been sent Set Compression at login. `None` (the default) leaves
+    /// the connection uncompressed.
+    pub compression_threshold: Option<i32>,
+    /// Player limit shown in the server list.
+    pub max_players: u32,
+    /// Description shown in the server list.
+    pub motd: Text,
+    /// Upper bound for a client's view distance, in chunks.
+    pub view_distance: u8,
+    /// Accept clients transferred here from another server.
+    pub accepts_transfers: bool,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            online_mode: false,
+            compression_threshold: None,
+            max_players: 20,
+            motd: Text::text("mcrs Server"),
+            view_distance: ServerViewConfig::default().view_distance,
+            accepts_transfers: false,
+        }
+    }
+}
+
+/// Insert [`ServerConfig`], or its default, and copy its fields into the
+/// network and view configs, keeping whatever else those were given.
+pub(crate) fn apply_server_config(app: &mut App) {
+    let world = app.world();
+    let config = world
+        .get_resource::<ServerConfig>()
+        .cloned()
+        .unwrap_or_default();
+    let mut network = world
+        .get_resource::<NetworkConfig>()
+        .cloned()
+        .unwrap_or_default();
+    let mut view = world
+        .get_resource::<ServerViewConfig>()
+        .cloned()
+        .unwrap_or_default();
+
+    network.motd = config.motd.clone();
+    network.max_players = config.max_players;
+    network.accepts_transfers = config.accepts_transfers;
+    view.view_distance = config.view_distance;
+
+    app.insert_resource(network);
+    app.insert_resource(view);
+    app.insert_resource(config);
+}
@@ -1,7 +1,18 @@
 mod minecraft;
 
+use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::MessageWriter;
+use bevy_ecs::query::With;
+use bevy_ecs::system::{Query, SystemParam};
+use bevy_math::DVec3;
+use mcrs_core::StaticRegistry;
+use mcrs_engine::entity::physics::Transform;
+use mcrs_engine::entity::player::Player;
 use mcrs_protocol::Ident;
+use mcrs_protocol::sound::{SoundCategory, SoundId};
 use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
 use std::borrow::Cow;
 use std::fmt::Debug;
 
@@ -12,6 +23,22 @@ pub enum Holder<E: Debug + Clone + PartialEq> {
     Direct(E),
 }
 
+impl<E: Debug + Clone + PartialEq + 'static> Holder<E> {
+    /// The inline value for `Direct`, or the entry registered under the
+    /// referenced id. Idents are always namespaced, so the key lookup is a
+    /// plain string comparison against the registry's resource locations.
+    pub fn resolve<'r>(&'r self, registry: &'r StaticRegistry<E>) -> Option<&'r E> {
+        match self {
+            Holder::Reference(id) => registry.get_by_loc(id.as_str()),
+            Holder::Direct(value) => Some(value),
+        }
+    }
+
+    pub fn resolve_owned(&self, registry: &StaticRegistry<E>) -> Option<E> {
+        self.resolve(registry).cloned()
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SoundEvent {
     sound_id: Ident<String>,
@@ -110,3 +137,158 @@ impl SoundType {
         }
     }
 }
+
+/// Hearing range used when a sound event has no fixed range, matching
+/// vanilla `SoundEvent.getRange`: 16 blocks, scaled up by louder volumes.
+pub fn sound_range(event: &mcrs_vanilla::sound::SoundEvent, volume: f32) -> f32 {
+    event
+        .range
+        .unwrap_or(if volume > 1.0 { 16.0 * volume } else { 16.0 })
+}
+
+/// The wire position of a sound: each axis times 8, truncated toward zero.
+pub fn sound_fixed_position(pos: DVec3) -> [i32; 3] {
+    [
+        (pos.x * 8.0) as i32,
+        (pos.y * 8.0) as i32,
+        (pos.z * 8.0) as i32,
+    ]
+}
+
+/// Per-dim sound emitter. Sends a sound to every player of the dimension
+/// within its hearing range through the outbound bus.
+#[derive(SystemParam)]
+pub struct PlaySound<'w, 's> {
+    players: Query<'w, 's, (Entity, &'static Transform), With<Player>>,
+    packet_writer: MessageWriter<'w, OutboundPlayerPacket>,
+}
+
+impl PlaySound<'_, '_> {
+    pub fn play_sound(
+        &mut self,
+        pos: DVec3,
+        event: &mcrs_vanilla::sound::SoundEvent,
+        category: SoundCategory,
+        volume: f32,
+        pitch: f32,
+    ) {
+        let range = sound_range(event, volume) as f64;
+        let listeners: SmallVec<[Entity; 8]> = self
+            .players
+            .iter()
+            .filter(|(_, transform)| transform.translation.distance_squared(pos) < range * range)
+            .map(|(player, _)| player)
+            .collect();
+        if listeners.is_empty() {
+            return;
+        }
+        self.packet_writer.write(OutboundPlayerPacket {
+            target: PacketTarget::PlayerSet(listeners),
+            priority: PacketPriority::Low,
+            data: PacketPayload::Sound {
+                sound: SoundId::Direct {
+                    id: Ident::new_unchecked(Cow::Borrowed(event.identifier.as_static_str())),
+                    range: event.range,
+                },
+                category,
+                position: pos,
+                volume,
+                pitch,
+                seed: rand::random(),
+            },
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcrs_core::ResourceLocation;
+
+    fn registry() -> StaticRegistry<SoundEvent> {
+        let mut registry = StaticRegistry::new();
+        let id = Ident::new("ui.button.click").unwrap();
+        let click: &'static SoundEvent = Box::leak(Box::new(SoundEvent::new(id.into(), None)));
+        registry.register(
+            ResourceLocation::from_str_const("minecraft:ui.button.click"),
+            click,
+        );
+        registry
+    }
+
+    #[test]
+    fn resolve_reference_looks_up_registry() {
+        let registry = registry();
+        let holder = Holder::Reference(Ident::new("ui.button.click").unwrap());
+        assert_eq!(
+            holder.resolve(&registry),
+            registry.get_by_loc("minecraft:ui.button.click")
+        );
+        assert!(holder.resolve_owned(&registry).is_some());
+    }
+
+    #[test]
+    fn resolve_unknown_reference_is_none() {
+        let registry = registry();
+        let holder = Holder::<SoundEvent>::Reference(Ident::new("missing").unwrap());
+        assert!(holder.resolve(&registry).is_none());
+    }
+
+    #[test]
+    fn resolve_direct_ignores_registry() {
+        let registry = StaticRegistry::new();
+        let event = SoundEvent::new(Ident::new("custom:sound").unwrap().into(), Some(8.0));
+        let holder = Holder::Direct(event.clone());
+        assert_eq!(holder.resolve(&registry), Some(&event));
+        assert_eq!(holder.resolve_owned(&registry), Some(event));
+    }
+
+    #[test]
+    fn sound_position_is_eight_times_block_coordinates() {
+        assert_eq!(
+            sound_fixed_position(DVec3::new(0.5, 64.0, -3.25)),
+            [4, 512, -26]
+        );
+        // Truncation toward zero, as vanilla's `(int)` cast does.
+        assert_eq!(
+            sound_fixed_position(DVec3::new(1.99, -0.06, -1.99)),
+            [15, 0, -15]
+        );
+    }
+
+    #[test]
+    fn sound_packet_encodes_fixed_point_position() {
+        use mcrs_protocol::Encode;
+        use mcrs_protocol::packets::game::clientbound::ClientboundSound;
+
+        let [x, y, z] = sound_fixed_position(DVec3::new(10.5, 70.0, -2.5));
+        let packet = ClientboundSound {
+            sound: SoundId::Reference {
+                id: mcrs_protocol::VarInt(3),
+            },
+            category: SoundCategory::Block,
+            x,
+            y,
+            z,
+            volume: 1.0,
+            pitch: 1.0,
+            seed: 0,
+        };
+        let mut data = Vec::new();
+        packet.encode(&mut data).unwrap();
+        // Registry reference id + 1, then the category ordinal.
+        assert_eq!(data[..2], [4, 4]);
+        assert_eq!(data[2..6], 84i32.to_be_bytes());
+        assert_eq!(data[6..10], 560i32.to_be_bytes());
+        assert_eq!(data[10..14], (-20i32).to_be_bytes());
+    }
+
+    #[test]
+    fn sound_range_scales_with_loud_volumes() {
+        let event = mcrs_vanilla::sound::SoundEvent::new(mcrs_core::rl!("block.stone.break"), None);
+        assert_eq!(sound_range(&event, 0.5), 16.0);
+        assert_eq!(sound_range(&event, 4.0), 64.0);
+        let fixed = mcrs_vanilla::sound::SoundEvent::new(event.identifier, Some(8.0));
+        assert_eq!(sound_range(&fixed, 4.0), 8.0);
+    }
+}
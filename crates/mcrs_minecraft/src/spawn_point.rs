@@ -0,0 +1,44 @@
+//! The world spawn: where players join, and the compass target and respawn
+//! fallback the client is told about with Set Default Spawn Position.
+
+use bevy_ecs::resource::Resource;
+use bevy_math::{DVec3, Vec2};
+use mcrs_engine::geometry::BlockPos;
+use mcrs_protocol::{Ident, ident};
+
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct SpawnPoint {
+    pub dimension: Ident<String>,
+    pub position: BlockPos,
+    /// Rotation players face on joining, in degrees.
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Default for SpawnPoint {
+    fn default() -> Self {
+        Self {
+            dimension: ident!("overworld").into(),
+            position: BlockPos::new(0, 100, 0),
+            yaw: 0.0,
+            pitch: 0.0,
+        }
+    }
+}
+
+impl SpawnPoint {
+    /// Where a player spawning here stands: on the bottom face of the spawn
+    /// block, centered on it.
+    pub fn player_position(&self) -> DVec3 {
+        DVec3::new(
+            self.position.x as f64 + 0.5,
+            self.position.y as f64,
+            self.position.z as f64 + 0.5,
+        )
+    }
+
+    /// Yaw and pitch, as carried by the transfer snapshot.
+    pub fn rotation(&self) -> Vec2 {
+        Vec2::new(self.yaw, self.pitch)
+    }
+}
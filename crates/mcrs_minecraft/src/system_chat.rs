@@ -0,0 +1,32 @@
+//! Server messages written straight to connections: System Chat in the chat
+//! box, or above the hotbar when sent as an overlay. Per-dimension code goes
+//! through the bus with `PacketPayload::SystemChat` instead.
+
+use bevy_ecs::prelude::{Query, With};
+use mcrs_network::{InGameConnectionState, ServerSideConnection};
+use mcrs_protocol::packets::game::clientbound::ClientboundSystemChatPacket;
+use mcrs_protocol::{Text, WritePacket};
+
+/// Show `text` to one player, in the action bar when `overlay` is set.
+pub fn send_system_message(connection: &mut impl WritePacket, text: &Text, overlay: bool) {
+    connection.write_packet(&ClientboundSystemChatPacket {
+        content: text.clone(),
+        overlay,
+    });
+}
+
+/// Show `text` to everyone in Game state, in the action bar when `overlay`
+/// is set.
+pub fn broadcast_system_message(
+    connections: &mut Query<&mut ServerSideConnection, With<InGameConnectionState>>,
+    text: &Text,
+    overlay: bool,
+) {
+    let packet = ClientboundSystemChatPacket {
+        content: text.clone(),
+        overlay,
+    };
+    for mut connection in connections {
+        connection.write_packet(&packet);
+    }
+}
@@ -0,0 +1,209 @@
+//! Runtime control of the server tick rate, like vanilla's `/tick`: change
+//! the rate, freeze ticking, and step a frozen server a given number of
+//! ticks.
+//!
+//! The host's fixed timestep follows [`TickRate`]. While frozen,
+//! `Time<Virtual>` is paused so no fixed ticks accumulate, and each pending
+//! step adds exactly one timestep of overstep, so `FixedMain` runs once per
+//! host update until the steps are used up. Dimension sub-apps get a copy of
+//! `TickRate` and skip their fixed schedules whenever the host does.
+
+use crate::DEFAULT_TPS;
+use bevy_app::{App, First, Plugin};
+use bevy_ecs::prelude::{IntoScheduleConfigs, ResMut};
+use bevy_ecs::resource::Resource;
+use bevy_time::{Fixed, Time, TimeSystems, Virtual};
+use std::time::Duration;
+
+pub struct TickRatePlugin;
+
+impl Plugin for TickRatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TickRate>();
+        // Before the clock advances, so a freeze applies to this update.
+        app.add_systems(First, apply_tick_rate.before(TimeSystems));
+    }
+}
+
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct TickRate {
+    tps: f32,
+    frozen: bool,
+    /// Ticks still to run while frozen.
+    pending_steps: u32,
+    /// Whether fixed ticks run during the current host update.
+    ticking: bool,
+}
+
+impl TickRate {
+    /// Bounds of vanilla's `/tick rate`.
+    pub const MIN_TPS: f32 = 1.0;
+    pub const MAX_TPS: f32 = 10000.0;
+
+    pub fn new(tps: f32) -> Self {
+        Self {
+            tps: tps.clamp(Self::MIN_TPS, Self::MAX_TPS),
+            frozen: false,
+            pending_steps: 0,
+            ticking: true,
+        }
+    }
+
+    /// Ticks per second.
+    pub fn tps(&self) -> f32 {
+        self.tps
+    }
+
+    /// Change the tick rate, clamped to vanilla's bounds.
+    pub fn set_tps(&mut self, tps: f32) {
+        self.tps = tps.clamp(Self::MIN_TPS, Self::MAX_TPS);
+    }
+
+    /// Length of one tick.
+    pub fn timestep(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.tps as f64)
+    }
+
+    /// Stop fixed ticks until [`unfreeze`](Self::unfreeze), apart from
+    /// explicit [`step`](Self::step)s.
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    /// Resume ticking and drop any steps not yet taken.
+    pub fn unfreeze(&mut self) {
+        self.frozen = false;
+        self.pending_steps = 0;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Run `ticks` more ticks, one per host update. Like vanilla, stepping
+    /// only works while frozen; returns `false` otherwise.
+    pub fn step(&mut self, ticks: u32) -> bool {
+        if !self.frozen {
+            return false;
+        }
+        self.pending_steps = self.pending_steps.saturating_add(ticks);
+        true
+    }
+
+    /// Steps requested but not yet run.
+    pub fn pending_steps(&self) -> u32 {
+        self.pending_steps
+    }
+
+    /// Whether fixed ticks run during the current host update: always when
+    /// not frozen, otherwise only while a step is being taken.
+    pub fn is_ticking(&self) -> bool {
+        self.ticking
+    }
+}
+
+impl Default for TickRate {
+    fn default() -> Self {
+        Self::new(DEFAULT_TPS.get() as f32)
+    }
+}
+
+/// Retime `Time<Fixed>` when the rate changes, and pause virtual time while
+/// frozen, feeding it one timestep per pending step.
+fn apply_tick_rate(
+    mut tick_rate: ResMut<TickRate>,
+    mut fixed: ResMut<Time<Fixed>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    if fixed.timestep() != tick_rate.timestep() {
+        fixed.set_timestep(tick_rate.timestep());
+    }
+    if tick_rate.frozen != virtual_time.is_paused() {
+        if tick_rate.frozen {
+            virtual_time.pause();
+        } else {
+            virtual_time.unpause();
+        }
+    }
+
+    let stepping = tick_rate.frozen && tick_rate.pending_steps > 0;
+    if stepping {
+        fixed.accumulate_overstep(tick_rate.timestep());
+        tick_rate.pending_steps -= 1;
+    }
+    // Only written when it flips, so a steady state doesn't mark the
+    // resource changed every frame.
+    let ticking = !tick_rate.frozen || stepping;
+    if tick_rate.ticking != ticking {
+        tick_rate.ticking = ticking;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::FixedUpdate;
+    use bevy_time::{TimePlugin, TimeUpdateStrategy};
+
+    #[derive(Resource, Default)]
+    struct FixedTicks(u32);
+
+    fn count_ticks(mut ticks: ResMut<FixedTicks>) {
+        ticks.0 += 1;
+    }
+
+    /// An app whose every update advances real time by exactly one tick.
+    fn make_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, TickRatePlugin));
+        app.init_resource::<FixedTicks>();
+        app.add_systems(FixedUpdate, count_ticks);
+        let timestep = TickRate::default().timestep();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+        // The first update only starts the clock.
+        app.update();
+        app.world_mut().resource_mut::<FixedTicks>().0 = 0;
+        app
+    }
+
+    fn run(app: &mut App, updates: u32) -> u32 {
+        let before = app.world().resource::<FixedTicks>().0;
+        for _ in 0..updates {
+            app.update();
+        }
+        app.world().resource::<FixedTicks>().0 - before
+    }
+
+    #[test]
+    fn tick_rate_sets_the_fixed_timestep() {
+        let mut app = make_app();
+        let default_timestep = app.world().resource::<Time<Fixed>>().timestep();
+        assert_eq!(default_timestep, Duration::from_millis(50));
+
+        app.world_mut().resource_mut::<TickRate>().set_tps(10.0);
+        app.update();
+        let timestep = app.world().resource::<Time<Fixed>>().timestep();
+        assert_eq!(timestep, default_timestep * 2);
+
+        // Updates still advance 50 ms each, so a tick now takes two.
+        assert_eq!(run(&mut app, 4), 2);
+    }
+
+    #[test]
+    fn freeze_stops_fixed_ticks_until_stepped() {
+        let mut app = make_app();
+        assert_eq!(run(&mut app, 3), 3);
+
+        app.world_mut().resource_mut::<TickRate>().freeze();
+        assert_eq!(run(&mut app, 3), 0);
+        assert!(!app.world().resource::<TickRate>().is_ticking());
+
+        assert!(app.world_mut().resource_mut::<TickRate>().step(2));
+        assert_eq!(run(&mut app, 4), 2);
+        assert_eq!(app.world().resource::<TickRate>().pending_steps(), 0);
+
+        app.world_mut().resource_mut::<TickRate>().unfreeze();
+        assert_eq!(run(&mut app, 3), 3);
+        assert!(!app.world_mut().resource_mut::<TickRate>().step(1));
+    }
+}
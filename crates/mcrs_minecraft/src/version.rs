@@ -1 +1,2 @@
+/// Version of the `minecraft:core` known pack offered during Configuration.
 pub const VERSION_ID: &str = "1.21.11";
@@ -0,0 +1,405 @@
+//! Rain and thunder: the weather cycle, the fade of the rain and thunder
+//! levels, and the Game Event packets that keep players in a dimension in
+//! sync.
+
+use crate::world::bridge::PlayerAttached;
+use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
+use crate::world::player_index::PlayerIndex;
+use bevy_app::{App, FixedUpdate, Plugin};
+use bevy_ecs::message::MessageWriter;
+use bevy_ecs::prelude::{IntoScheduleConfigs, On, Query, Res, ResMut};
+use bevy_ecs::resource::Resource;
+use mcrs_minecraft_worldgen::bevy::WorldGenConfig;
+use mcrs_network::ServerSideConnection;
+use mcrs_protocol::packets::game::clientbound::ClientboundGameEvent;
+use mcrs_protocol::{GameEventKind, WritePacket};
+use mcrs_random::Random;
+use mcrs_random::xoroshiro::XoroshiroRandom;
+
+/// Mean ticks between weather changes: one in-game day.
+pub const DEFAULT_MEAN_INTERVAL: u32 = 24_000;
+
+/// How far the rain and thunder levels move toward their target each tick,
+/// like vanilla.
+const FADE_PER_TICK: f32 = 0.01;
+
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world().contains_resource::<Weather>() {
+            let seed = match app.world().get_resource::<WorldGenConfig>() {
+                Some(config) => config.seed,
+                None => WorldGenConfig::from_env().seed,
+            };
+            app.insert_resource(Weather::new(seed));
+        }
+        app.add_systems(FixedUpdate, (tick_weather, broadcast_weather).chain());
+        app.add_observer(send_weather_on_attach);
+    }
+}
+
+/// The weather of the world, shared by its dimensions. Starting the rain
+/// and moving a level queue the Game Event players need, sent on the next
+/// tick.
+#[derive(Resource, Debug, Clone)]
+pub struct Weather {
+    raining: bool,
+    rain_level: f32,
+    thunder_level: f32,
+    thundering: bool,
+    cycle: bool,
+    mean_interval: u32,
+    rain_countdown: u32,
+    thunder_countdown: u32,
+    rng: XoroshiroRandom,
+    pending: Vec<WeatherUpdate>,
+}
+
+/// A change clients have not been told about yet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum WeatherUpdate {
+    BeginRaining,
+    EndRaining,
+    RainLevel(f32),
+    ThunderLevel(f32),
+}
+
+impl Weather {
+    /// Clear weather with the cycle driven by a generator seeded with
+    /// `seed`, so the same seed gives the same weather. The plugin passes
+    /// the world seed.
+    pub fn new(seed: u64) -> Self {
+        let mut weather = Self {
+            raining: false,
+            rain_level: 0.0,
+            thunder_level: 0.0,
+            thundering: false,
+            cycle: true,
+            mean_interval: DEFAULT_MEAN_INTERVAL,
+            rain_countdown: 0,
+            thunder_countdown: 0,
+            rng: XoroshiroRandom::new(seed),
+            pending: Vec::new(),
+        };
+        weather.rain_countdown = weather.next_interval();
+        weather.thunder_countdown = weather.next_interval();
+        weather
+    }
+
+    pub fn raining(&self) -> bool {
+        self.raining
+    }
+
+    /// Start or stop the rain. The level then fades over the next ticks.
+    pub fn set_raining(&mut self, raining: bool) {
+        if self.raining == raining {
+            return;
+        }
+        self.raining = raining;
+        self.pending.push(if raining {
+            WeatherUpdate::BeginRaining
+        } else {
+            WeatherUpdate::EndRaining
+        });
+    }
+
+    pub fn thundering(&self) -> bool {
+        self.thundering
+    }
+
+    /// Start or stop the thunderstorm. The level then fades over the next
+    /// ticks.
+    pub fn set_thundering(&mut self, thundering: bool) {
+        self.thundering = thundering;
+    }
+
+    /// Strength of the rain the client draws, in `0.0..=1.0`. Fades toward 1
+    /// while raining and toward 0 otherwise.
+    pub fn rain_level(&self) -> f32 {
+        self.rain_level
+    }
+
+    /// Jump to `level` at once instead of fading to it.
+    pub fn set_rain_level(&mut self, level: f32) {
+        let level = level.clamp(0.0, 1.0);
+        if self.rain_level != level {
+            self.rain_level = level;
+            self.pending.push(WeatherUpdate::RainLevel(level));
+        }
+    }
+
+    /// Strength of the thunderstorm, in `0.0..=1.0`. The client only shows
+    /// it scaled by the rain level.
+    pub fn thunder_level(&self) -> f32 {
+        self.thunder_level
+    }
+
+    /// Jump to `level` at once instead of fading to it.
+    pub fn set_thunder_level(&mut self, level: f32) {
+        let level = level.clamp(0.0, 1.0);
+        if self.thunder_level != level {
+            self.thunder_level = level;
+            self.pending.push(WeatherUpdate::ThunderLevel(level));
+        }
+    }
+
+    /// `doWeatherCycle`: whether rain and thunder start and stop on their
+    /// own.
+    pub fn cycle(&self) -> bool {
+        self.cycle
+    }
+
+    pub fn set_cycle(&mut self, cycle: bool) {
+        self.cycle = cycle;
+    }
+
+    /// Mean ticks until the cycle flips the rain, and separately the
+    /// thunder.
+    pub fn mean_interval(&self) -> u32 {
+        self.mean_interval
+    }
+
+    /// Change the mean interval, from the next change on.
+    pub fn set_mean_interval(&mut self, mean_interval: u32) {
+        self.mean_interval = mean_interval;
+    }
+
+    /// Run the cycle one tick, then fade the levels toward their targets.
+    pub fn tick(&mut self) {
+        if self.cycle {
+            self.rain_countdown = self.rain_countdown.saturating_sub(1);
+            if self.rain_countdown == 0 {
+                self.set_raining(!self.raining);
+                self.rain_countdown = self.next_interval();
+            }
+            self.thunder_countdown = self.thunder_countdown.saturating_sub(1);
+            if self.thunder_countdown == 0 {
+                self.set_thundering(!self.thundering);
+                self.thunder_countdown = self.next_interval();
+            }
+        }
+        self.set_rain_level(fade(self.rain_level, self.raining));
+        self.set_thunder_level(fade(self.thunder_level, self.thundering));
+    }
+
+    /// Ticks until the next change, uniform in half to one and a half times
+    /// `mean_interval`.
+    fn next_interval(&mut self) -> u32 {
+        let mean = self.mean_interval.max(1);
+        mean / 2 + self.rng.next_u32_bound(mean) + 1
+    }
+
+    /// The Game Events a player entering the world needs, like vanilla
+    /// `PlayerList.sendLevelInfo`: nothing in clear weather.
+    fn join_updates(&self) -> Vec<WeatherUpdate> {
+        if !self.raining {
+            return Vec::new();
+        }
+        vec![
+            WeatherUpdate::BeginRaining,
+            WeatherUpdate::RainLevel(self.rain_level),
+            WeatherUpdate::ThunderLevel(self.thunder_level),
+        ]
+    }
+}
+
+impl WeatherUpdate {
+    fn packet(self) -> ClientboundGameEvent {
+        let game_event = match self {
+            WeatherUpdate::BeginRaining => GameEventKind::BeginRaining,
+            WeatherUpdate::EndRaining => GameEventKind::EndRaining,
+            WeatherUpdate::RainLevel(level) => GameEventKind::RainLevelChange(level),
+            WeatherUpdate::ThunderLevel(level) => GameEventKind::ThunderLevelChange(level),
+        };
+        ClientboundGameEvent { game_event }
+    }
+
+    fn write(self, out: &mut impl WritePacket) {
+        out.write_packet(&self.packet());
+    }
+}
+
+fn fade(level: f32, rising: bool) -> f32 {
+    if rising {
+        (level + FADE_PER_TICK).min(1.0)
+    } else {
+        (level - FADE_PER_TICK).max(0.0)
+    }
+}
+
+pub fn tick_weather(mut weather: ResMut<Weather>) {
+    weather.tick();
+}
+
+/// Send the changes made since the last tick to every player in a
+/// dimension. Players still waiting for their Login get the current weather
+/// from [`send_weather_on_attach`] instead.
+pub fn broadcast_weather(
+    mut weather: ResMut<Weather>,
+    player_index: Res<PlayerIndex>,
+    mut connections: Query<&mut ServerSideConnection>,
+) {
+    let updates = std::mem::take(&mut weather.pending);
+    if updates.is_empty() {
+        return;
+    }
+    for socket in player_index.attached_sockets() {
+        let Ok(mut con) = connections.get_mut(socket) else {
+            continue;
+        };
+        for &update in &updates {
+            update.write(&mut *con);
+        }
+    }
+}
+
+/// Send the current weather to a player entering a dimension, through the
+/// bridge so it lands after the Login or Respawn already queued for them.
+fn send_weather_on_attach(
+    attached: On<PlayerAttached>,
+    weather: Res<Weather>,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
+) {
+    let updates = weather.join_updates();
+    for &update in &updates {
+        packet_writer.write(OutboundPlayerPacket {
+            target: PacketTarget::SinglePlayer(attached.host_anchor),
+            priority: PacketPriority::High,
+            data: PacketPayload::GameEvent(update.packet()),
+        });
+    }
+    mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+        .fetch_add(updates.len() as u64, std::sync::atomic::Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcrs_protocol::{Packet, PacketDecoder, PacketEncoder};
+
+    /// Encode `updates` the way the broadcast does and decode the Game
+    /// Events back.
+    fn game_events(updates: Vec<WeatherUpdate>) -> Vec<GameEventKind> {
+        let mut encoder = PacketEncoder::new();
+        for update in updates {
+            update.write(&mut encoder);
+        }
+        let mut decoder = PacketDecoder::new();
+        decoder.queue_bytes(encoder.take());
+        let mut events = Vec::new();
+        while let Some(frame) = decoder.try_next_packet().unwrap() {
+            assert_eq!(frame.id, ClientboundGameEvent::ID);
+            events.push(frame.decode::<ClientboundGameEvent>().unwrap().game_event);
+        }
+        events
+    }
+
+    fn sent(weather: &mut Weather) -> Vec<GameEventKind> {
+        game_events(std::mem::take(&mut weather.pending))
+    }
+
+    fn manual() -> Weather {
+        Weather {
+            cycle: false,
+            ..Weather::new(0)
+        }
+    }
+
+    #[test]
+    fn toggling_rain_sends_begin_and_end() {
+        let mut weather = manual();
+        weather.set_raining(true);
+        weather.set_raining(true);
+        assert!(matches!(
+            sent(&mut weather)[..],
+            [GameEventKind::BeginRaining]
+        ));
+
+        weather.set_raining(false);
+        assert!(matches!(
+            sent(&mut weather)[..],
+            [GameEventKind::EndRaining]
+        ));
+    }
+
+    #[test]
+    fn levels_fade_one_packet_per_tick() {
+        let mut weather = manual();
+        weather.set_raining(true);
+        weather.set_thundering(true);
+        weather.tick();
+        let events = sent(&mut weather);
+        assert!(matches!(
+            events[..],
+            [
+                GameEventKind::BeginRaining,
+                GameEventKind::RainLevelChange(0.01),
+                GameEventKind::ThunderLevelChange(0.01),
+            ]
+        ));
+
+        for _ in 0..200 {
+            weather.tick();
+        }
+        assert_eq!((weather.rain_level, weather.thunder_level), (1.0, 1.0));
+        let levels = sent(&mut weather);
+        assert!(levels.iter().all(|event| matches!(
+            event,
+            GameEventKind::RainLevelChange(_) | GameEventKind::ThunderLevelChange(_)
+        )));
+        assert!(matches!(
+            levels.last(),
+            Some(GameEventKind::ThunderLevelChange(1.0))
+        ));
+
+        // Settled levels send nothing further.
+        weather.tick();
+        assert!(sent(&mut weather).is_empty());
+
+        weather.set_rain_level(0.5);
+        assert!(matches!(
+            sent(&mut weather)[..],
+            [GameEventKind::RainLevelChange(0.5)]
+        ));
+    }
+
+    #[test]
+    fn joining_during_rain_gets_the_current_levels() {
+        let mut weather = manual();
+        assert!(weather.join_updates().is_empty());
+
+        weather.set_raining(true);
+        weather.set_rain_level(0.75);
+        weather.set_thunder_level(0.25);
+        assert!(matches!(
+            game_events(weather.join_updates())[..],
+            [
+                GameEventKind::BeginRaining,
+                GameEventKind::RainLevelChange(0.75),
+                GameEventKind::ThunderLevelChange(0.25),
+            ]
+        ));
+    }
+
+    #[test]
+    fn the_cycle_is_deterministic_under_a_seed() {
+        let starts = |seed| {
+            let mut weather = Weather {
+                mean_interval: 100,
+                ..Weather::new(seed)
+            };
+            weather.rain_countdown = weather.next_interval();
+            (0..1_000)
+                .filter(|_| {
+                    let was_raining = weather.raining;
+                    weather.tick();
+                    !was_raining && weather.raining
+                })
+                .count()
+        };
+        assert_eq!(starts(7), starts(7));
+        // A change every 50 to 150 ticks gives several spells of rain.
+        assert!((3..=10).contains(&starts(7)));
+    }
+}
@@ -1,5 +1,164 @@
+use mcrs_random::Random;
+
 #[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct Weighted<T: PartialEq> {
     data: T,
     weight: f32,
 }
+
+/// Values picked with probability proportional to an integer weight.
+///
+/// Keeps the running weight totals next to the values, so a pick is one
+/// `next_u32_bound(total)` draw and a binary search, like vanilla
+/// `WeightedList.getRandom`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeightedList<T> {
+    values: Vec<T>,
+    /// `cumulative[i]` is the sum of the weights of `values[..=i]`.
+    cumulative: Vec<u32>,
+}
+
+impl<T> Default for WeightedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> WeightedList<T> {
+    pub const fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            cumulative: Vec::new(),
+        }
+    }
+
+    /// Append `value`. Zero-weight values are kept but never picked.
+    ///
+    /// Panics if the total weight overflows `u32`.
+    pub fn add(&mut self, weight: u32, value: T) {
+        let total = self
+            .total_weight()
+            .checked_add(weight)
+            .expect("total weight overflows u32");
+        self.values.push(value);
+        self.cumulative.push(total);
+    }
+
+    pub fn total_weight(&self) -> u32 {
+        self.cumulative.last().copied().unwrap_or(0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &T)> {
+        let mut previous = 0;
+        self.cumulative
+            .iter()
+            .zip(&self.values)
+            .map(move |(&total, value)| {
+                let weight = total - previous;
+                previous = total;
+                (weight, value)
+            })
+    }
+
+    /// Pick a value, or `None` when the total weight is zero.
+    pub fn sample(&self, rng: &mut impl Random) -> Option<&T> {
+        self.sample_index(rng).map(|index| &self.values[index])
+    }
+
+    /// Pick a value and take it out of the list, for draws without replacement.
+    pub fn sample_and_remove(&mut self, rng: &mut impl Random) -> Option<T> {
+        let index = self.sample_index(rng)?;
+        let previous = index.checked_sub(1).map_or(0, |i| self.cumulative[i]);
+        let weight = self.cumulative[index] - previous;
+        self.cumulative.remove(index);
+        for total in &mut self.cumulative[index..] {
+            *total -= weight;
+        }
+        Some(self.values.remove(index))
+    }
+
+    fn sample_index(&self, rng: &mut impl Random) -> Option<usize> {
+        let total = self.total_weight();
+        if total == 0 {
+            return None;
+        }
+        let roll = rng.next_u32_bound(total);
+        // First value whose running total passes the roll; zero-weight values
+        // share their predecessor's total and are skipped.
+        Some(self.cumulative.partition_point(|&total| total <= roll))
+    }
+}
+
+impl<T> FromIterator<(u32, T)> for WeightedList<T> {
+    fn from_iter<I: IntoIterator<Item = (u32, T)>>(iter: I) -> Self {
+        let mut list = Self::new();
+        for (weight, value) in iter {
+            list.add(weight, value);
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcrs_random::xoroshiro::XoroshiroRandom;
+
+    #[test]
+    fn sampled_frequencies_match_weights() {
+        let weights = [1u32, 0, 4, 10, 25];
+        let list: WeightedList<usize> = weights.iter().copied().zip(0..).collect();
+        let mut rng = XoroshiroRandom::new(7);
+
+        const SAMPLES: usize = 100_000;
+        let mut hits = [0usize; 5];
+        for _ in 0..SAMPLES {
+            hits[*list.sample(&mut rng).unwrap()] += 1;
+        }
+
+        assert_eq!(hits[1], 0, "zero-weight values are never picked");
+        let total = list.total_weight() as f64;
+        for (hits, weight) in hits.into_iter().zip(weights) {
+            let observed = hits as f64 / SAMPLES as f64;
+            let expected = weight as f64 / total;
+            assert!(
+                (observed - expected).abs() < 0.01,
+                "weight {weight}: observed {observed:.4}, expected {expected:.4}"
+            );
+        }
+    }
+
+    #[test]
+    fn sample_and_remove_draws_each_value_once() {
+        let mut list: WeightedList<char> = [(3, 'a'), (0, 'b'), (1, 'c'), (6, 'd')]
+            .into_iter()
+            .collect();
+        let mut rng = XoroshiroRandom::new(3);
+
+        let mut drawn = Vec::new();
+        while let Some(value) = list.sample_and_remove(&mut rng) {
+            drawn.push(value);
+        }
+        drawn.sort();
+        assert_eq!(drawn, ['a', 'c', 'd']);
+        // Only the zero-weight value is left, and it can't be drawn.
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.total_weight(), 0);
+        assert_eq!(list.iter().collect::<Vec<_>>(), [(0, &'b')]);
+    }
+
+    #[test]
+    fn empty_list_samples_nothing() {
+        let list = WeightedList::<()>::new();
+        let mut rng = XoroshiroRandom::new(1);
+        assert_eq!(list.sample(&mut rng), None);
+    }
+}
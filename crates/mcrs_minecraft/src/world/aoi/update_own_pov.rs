@@ -47,7 +47,11 @@ pub fn update_own_pov(
         ),
         (
             With<Player>,
-            Or<(Changed<Transform>, Added<ChunkSubscriptionSet>)>,
+            Or<(
+                Changed<Transform>,
+                Changed<PlayerViewDistance>,
+                Added<ChunkSubscriptionSet>,
+            )>,
         ),
     >,
     mut observers: Query<&mut PlayerObservers, (With<Column>, Without<Player>)>,
@@ -8,6 +8,7 @@ use std::sync::atomic::Ordering;
 
 use bevy_ecs::message::MessageWriter;
 use bevy_ecs::prelude::{Changed, Entity, Query, ResMut, With, Without};
+use bevy_math::DVec3;
 use mcrs_engine::aoi::PlayerObservers;
 use mcrs_engine::entity::physics::Transform;
 use mcrs_engine::entity::player::Player;
@@ -15,6 +16,7 @@ use mcrs_engine::geometry::ColumnPos;
 use mcrs_engine::world::dimension::InDimension;
 use mcrs_engine::world::storage::column::{Column, ColumnIndex};
 use smallvec::SmallVec;
+use mcrs_protocol::entity::EntityMetadata;
 use mcrs_protocol::uuid::Uuid;
 
 use crate::login::GameProfile;
@@ -117,8 +119,10 @@ pub fn update_tracked_by(
                         uuid,
                         kind: MinecraftEntityType::Player as i32,
                         position: pos,
+                        velocity: DVec3::ZERO,
                         yaw: transform.rotation.y,
                         pitch: transform.rotation.x,
+                        metadata: EntityMetadata::new(),
                     },
                 });
                 mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
@@ -66,6 +66,7 @@ macro_rules! generate_block_states {
                 properties: &PROPERTIES,
                 default_state: &DEFAULT_STATE,
                 states: ALL_BLOCK_STATES,
+                state_properties: &[],
             };
 
             // DEFAULT_STATE is the only state
@@ -102,6 +103,17 @@ macro_rules! generate_block_states {
                 $($prop_name: [$($prop_value),+]),+
             });
 
+            // Generate one property definition per state property
+            $(
+                pub static [<$prop_name:upper _PROPERTY>]: mcrs_core::block_state::PropertyDef =
+                    mcrs_core::block_state::PropertyDef {
+                        name: mcrs_core::block_state::PropertyStr::new(stringify!($prop_name)),
+                        values: &[$(
+                            mcrs_core::block_state::PropertyStr::new(stringify!($prop_value))
+                        ),+],
+                    };
+            )+
+
             // Generate BLOCK constant
             pub const BLOCK: Block = Block {
                 identifier: ident!($block_name),
@@ -109,6 +121,7 @@ macro_rules! generate_block_states {
                 properties: &PROPERTIES,
                 default_state: &DEFAULT_STATE,
                 states: ALL_BLOCK_STATES,
+                state_properties: &[$(&[<$prop_name:upper _PROPERTY>]),+],
             };
 
             // Generate DEFAULT_STATE
@@ -1,4 +1,4 @@
-use crate::world::block::Block;
+use crate::world::block::{Block, BlockStateRegistry};
 use bevy_app::{App, Plugin};
 use mcrs_protocol::{BlockStateId, Ident};
 
@@ -23,6 +23,8 @@ macro_rules! declare_blocks {
 
         impl Plugin for MinecraftBlockPlugin {
             fn build(&self, app: &mut App) {
+                app.insert_resource(BlockStateRegistry::vanilla());
+
                 // Add block-specific plugins
                 $(
                     $($(
@@ -32,6 +34,9 @@ macro_rules! declare_blocks {
             }
         }
 
+        /// Every implemented block, in `minecraft:block` registry order.
+        pub static BLOCKS: &[&Block] = &[$(&$const_name),*];
+
         const STATE_TABLE_LEN: usize = 1 << 16;
 
         static STATE_TO_BLOCK: [Option<&'static Block>; STATE_TABLE_LEN] = {
@@ -88,6 +93,7 @@ declare_blocks! {
     pale_oak_sapling => PALE_OAK_SAPLING,
     mangrove_propagule => MANGROVE_PROPAGULE,
     bedrock => BEDROCK,
+    oak_log => OAK_LOG,
     note_block => NOTE_BLOCK,
     tnt => TNT [TntBlockPlugin],
     diamond_ore => DIAMOND_ORE,
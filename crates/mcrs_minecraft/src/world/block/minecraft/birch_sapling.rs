@@ -3,6 +3,7 @@ use crate::world::block::{Block, BlockState};
 use mcrs_minecraft_block::material::PushReaction;
 use mcrs_minecraft_block::material::map::MapColor;
 use mcrs_protocol::{BlockStateId, ident};
+use mcrs_vanilla::block::state_properties;
 
 pub const BLOCK: Block = Block {
     identifier: ident!("birch_sapling"),
@@ -10,6 +11,7 @@ pub const BLOCK: Block = Block {
     properties: &PROPERTIES,
     default_state: DEFAULT_STATE,
     states: &[STAGE_0_STATE, STAGE_1_STATE],
+    state_properties: &[&state_properties::STAGE],
 };
 
 pub const STAGE_0_STATE: BlockState = BlockState {
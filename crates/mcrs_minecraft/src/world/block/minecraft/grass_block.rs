@@ -2,6 +2,7 @@ use crate::world::block::behaviour::Properties;
 use crate::world::block::{Block, BlockState};
 use mcrs_minecraft_block::material::map::MapColor;
 use mcrs_protocol::{BlockStateId, ident};
+use mcrs_vanilla::block::state_properties;
 
 pub const BLOCK: Block = Block {
     identifier: ident!("grass_block"),
@@ -9,6 +10,7 @@ pub const BLOCK: Block = Block {
     properties: &PROPERTIES,
     default_state: &DEFAULT_STATE,
     states: &[SNOWY_STATE, DEFAULT_STATE],
+    state_properties: &[&state_properties::SNOWY],
 };
 
 pub const SNOWY_STATE: BlockState = BlockState {
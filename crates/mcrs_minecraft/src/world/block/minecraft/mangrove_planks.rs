@@ -10,6 +10,7 @@ pub const BLOCK: Block = Block {
     properties: &PROPERTIES,
     default_state: &DEFAULT_STATE,
     states: &[DEFAULT_STATE],
+    state_properties: &[],
 };
 
 pub const DEFAULT_STATE: BlockState = BlockState {
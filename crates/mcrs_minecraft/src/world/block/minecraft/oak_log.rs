@@ -0,0 +1,39 @@
+use crate::sound::SoundType;
+use crate::world::block::behaviour::Properties;
+use crate::world::block::minecraft::note_block::NoteBlockInstrument;
+use crate::world::block::{Block, BlockState};
+use mcrs_minecraft_block::material::map::MapColor;
+use mcrs_protocol::{BlockStateId, ident};
+use mcrs_vanilla::block::state_properties;
+
+pub const BLOCK: Block = Block {
+    identifier: ident!("oak_log"),
+    protocol_id: 49,
+    properties: &PROPERTIES,
+    default_state: DEFAULT_STATE,
+    states: &[X_STATE, Y_STATE, Z_STATE],
+    state_properties: &[&state_properties::AXIS],
+};
+
+pub const X_STATE: BlockState = BlockState {
+    id: BlockStateId(136),
+};
+
+pub const Y_STATE: BlockState = BlockState {
+    id: BlockStateId(137),
+};
+
+pub const Z_STATE: BlockState = BlockState {
+    id: BlockStateId(138),
+};
+
+pub const DEFAULT_STATE: &BlockState = &Y_STATE;
+
+// Block type: RotatedPillarBlock - not fully implemented yet
+// Vanilla colours the bark side PODZOL; a single map colour is all we model.
+pub const PROPERTIES: Properties = Properties::new()
+    .with_map_color(MapColor::WOOD)
+    .with_note_block_instrument(NoteBlockInstrument::BASS)
+    .with_strength(2.0)
+    .with_sound(&SoundType::WOOD)
+    .ignited_by_lava();
@@ -3,6 +3,7 @@ use crate::world::block::minecraft::note_block::NoteBlockInstrument;
 use crate::world::block::{Block, BlockState};
 use mcrs_minecraft_block::material::map::MapColor;
 use mcrs_protocol::{BlockStateId, ident};
+use mcrs_vanilla::block::state_properties;
 
 pub const BLOCK: Block = Block {
     identifier: ident!("pale_oak_wood"),
@@ -10,6 +11,7 @@ pub const BLOCK: Block = Block {
     properties: &PROPERTIES,
     default_state: DEFAULT_STATE,
     states: &[X_STATE, Y_STATE, Z_STATE],
+    state_properties: &[&state_properties::AXIS],
 };
 
 pub const X_STATE: BlockState = BlockState {
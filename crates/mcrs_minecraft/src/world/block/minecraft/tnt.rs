@@ -18,6 +18,7 @@ use mcrs_engine::entity::physics::Transform;
 use mcrs_engine::entity::player::Player;
 use mcrs_engine::world::dimension::InDimension;
 use mcrs_protocol::BlockStateId;
+use mcrs_vanilla::block::state_properties;
 use rand::{RngExt, rng};
 
 pub const BLOCK: Block = Block {
@@ -26,6 +27,7 @@ pub const BLOCK: Block = Block {
     properties: &PROPERTIES,
     default_state: &DEFAULT_STATE,
     states: &[UNSTABLE_STATE, DEFAULT_STATE],
+    state_properties: &[&state_properties::UNSTABLE],
 };
 
 pub const UNSTABLE_STATE: BlockState = BlockState {
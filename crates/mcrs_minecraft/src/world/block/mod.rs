@@ -1,10 +1,14 @@
 use crate::world::block::behaviour::Properties;
+use mcrs_core::block_state::PropertyDef;
 use mcrs_protocol::{BlockStateId, Ident};
 use std::hash::{Hash, Hasher};
 
 pub mod behaviour;
 mod macros;
 pub mod minecraft;
+mod registry;
+
+pub use registry::{BlockStateRegistry, StateProperties};
 
 #[derive(Debug)]
 pub struct Block {
@@ -14,7 +18,12 @@ pub struct Block {
     pub protocol_id: u16,
     pub properties: &'static Properties,
     pub default_state: &'static BlockState,
+    /// Every state of the block, in ascending id order.
     pub states: &'static [BlockState],
+    /// The block's properties sorted by name, as vanilla's
+    /// `StateDefinition` orders them. State ids enumerate their values with
+    /// the last property varying fastest.
+    pub state_properties: &'static [&'static PropertyDef],
 }
 
 impl PartialEq for Block {
@@ -43,9 +52,125 @@ impl Block {
         self.properties.requires_correct_tool_for_drops
     }
 
+    /// Whether entities collide with the block rather than pass through.
+    pub fn has_collision(&self) -> bool {
+        self.properties.has_collision
+    }
+
     pub fn xp_range(&self) -> Option<(u32, u32)> {
         self.properties.xp_range
     }
+
+    /// Whether the given state ID belongs to this block.
+    pub fn owns_state(&self, state_id: BlockStateId) -> bool {
+        self.state_offset(state_id).is_some()
+    }
+
+    /// Start building a state from this block's default state.
+    pub fn default_state(&'static self) -> StateBuilder {
+        StateBuilder {
+            block: self,
+            state_id: self.default_state.id,
+        }
+    }
+
+    /// The state with the given property values. Properties missing from
+    /// `properties` keep their default value; an unknown property or value
+    /// gives `None`.
+    pub fn state_id(&self, properties: &StateProperties) -> Option<BlockStateId> {
+        properties
+            .iter()
+            .try_fold(self.default_state.id, |state_id, (property, value)| {
+                self.with_property(state_id, property.as_str(), value.as_str())
+            })
+    }
+
+    /// The property values of `state_id`, or `None` if this block doesn't
+    /// own it.
+    pub fn properties_of(&self, state_id: BlockStateId) -> Option<StateProperties> {
+        let offset = self.state_offset(state_id)?;
+        let mut stride = 1;
+        let mut properties = StateProperties::new();
+        for def in self.state_properties.iter().rev() {
+            let count = def.count() as u16;
+            properties.insert(def.name, def.values[(offset / stride % count) as usize]);
+            stride *= count;
+        }
+        Some(properties)
+    }
+
+    /// Return a new state ID with a property set by string name and value.
+    pub fn with_property(
+        &self,
+        state_id: BlockStateId,
+        property: &str,
+        value: &str,
+    ) -> Option<BlockStateId> {
+        let offset = self.state_offset(state_id)?;
+        let mut stride = 1;
+        for def in self.state_properties.iter().rev() {
+            let count = def.count() as u16;
+            if def.name.as_str() == property {
+                let old = offset / stride % count;
+                let new = def.index_of(value)? as u16;
+                return Some(BlockStateId(state_id.0 - old * stride + new * stride));
+            }
+            stride *= count;
+        }
+        None
+    }
+
+    /// Offset of `state_id` from the block's first state.
+    fn state_offset(&self, state_id: BlockStateId) -> Option<u16> {
+        let base = self.states.first()?.id.0;
+        let offset = state_id.0.checked_sub(base)?;
+        ((offset as usize) < self.states.len()).then_some(offset)
+    }
+}
+
+/// Fluent builder for a [`BlockStateId`], started by [`Block::default_state`].
+///
+/// ```rust,ignore
+/// use crate::world::block::minecraft::OAK_LOG;
+///
+/// let state_id = OAK_LOG.default_state().with("axis", "x").id();
+/// ```
+pub struct StateBuilder {
+    block: &'static Block,
+    state_id: BlockStateId,
+}
+
+impl StateBuilder {
+    /// Set a property value. Panics if the property doesn't belong to this
+    /// block or the value is not one of its values.
+    pub fn with(self, property: &str, value: &str) -> Self {
+        let block = self.block;
+        self.try_with(property, value)
+            .unwrap_or_else(|| panic!("{} has no state {property}={value}", block.identifier))
+    }
+
+    /// Set a property value. Returns `None` if the property doesn't belong to
+    /// this block or the value is not one of its values.
+    pub fn try_with(mut self, property: &str, value: &str) -> Option<Self> {
+        self.state_id = self.block.with_property(self.state_id, property, value)?;
+        Some(self)
+    }
+
+    /// The block the state belongs to.
+    pub fn block(&self) -> &'static Block {
+        self.block
+    }
+
+    /// Get the resulting state ID.
+    pub fn id(self) -> BlockStateId {
+        self.state_id
+    }
+}
+
+impl From<StateBuilder> for BlockStateId {
+    fn from(builder: StateBuilder) -> Self {
+        builder.state_id
+    }
 }
 
 impl From<&'static Block> for BlockStateId {
@@ -0,0 +1,146 @@
+use crate::world::block::Block;
+use bevy_ecs::resource::Resource;
+use mcrs_core::block_state::PropertyStr;
+use mcrs_protocol::BlockStateId;
+use std::collections::{BTreeMap, HashMap};
+
+/// Property values of one block state, keyed by property name.
+pub type StateProperties = BTreeMap<PropertyStr, PropertyStr>;
+
+/// Global block state ids for every `(block, property values)` pair, in the
+/// order the client derives from the `minecraft:block` registry.
+///
+/// Lookups need the block's complete property set; [`Block::state_id`]
+/// fills in defaults for a partial one.
+#[derive(Resource)]
+pub struct BlockStateRegistry {
+    ids: HashMap<(u16, StateProperties), BlockStateId>,
+    states: HashMap<BlockStateId, (&'static Block, StateProperties)>,
+}
+
+impl BlockStateRegistry {
+    /// Enumerate every state of `blocks`.
+    pub fn new(blocks: impl IntoIterator<Item = &'static Block>) -> Self {
+        let mut ids = HashMap::new();
+        let mut states = HashMap::new();
+        for block in blocks {
+            let combinations: usize = block
+                .state_properties
+                .iter()
+                .map(|def| def.count() as usize)
+                .product();
+            debug_assert_eq!(
+                combinations,
+                block.states.len(),
+                "{} properties don't match its states",
+                block.identifier
+            );
+            for state in block.states {
+                let properties = block
+                    .properties_of(state.id)
+                    .expect("a block owns its states");
+                ids.insert((block.protocol_id, properties.clone()), state.id);
+                let previous = states.insert(state.id, (block, properties));
+                assert!(previous.is_none(), "state {} registered twice", state.id.0);
+            }
+        }
+        Self { ids, states }
+    }
+
+    /// The registry of every implemented vanilla block.
+    pub fn vanilla() -> Self {
+        Self::new(super::minecraft::BLOCKS.iter().copied())
+    }
+
+    /// The state of `block` with exactly these property values.
+    pub fn state_id(&self, block: &Block, properties: &StateProperties) -> Option<BlockStateId> {
+        self.ids
+            .get(&(block.protocol_id, properties.clone()))
+            .copied()
+    }
+
+    /// The block and property values of `state_id`.
+    pub fn state(&self, state_id: BlockStateId) -> Option<(&'static Block, &StateProperties)> {
+        self.states
+            .get(&state_id)
+            .map(|(block, properties)| (*block, properties))
+    }
+
+    /// Number of registered states.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::block::StateBuilder;
+    use crate::world::block::minecraft::{GRASS_BLOCK, OAK_LOG, STONE};
+
+    fn properties(pairs: &[(&'static str, &'static str)]) -> StateProperties {
+        pairs
+            .iter()
+            .map(|&(name, value)| (PropertyStr::new(name), PropertyStr::new(value)))
+            .collect()
+    }
+
+    #[test]
+    fn vanilla_state_ids() {
+        let registry = BlockStateRegistry::vanilla();
+
+        assert_eq!(STONE.default_state().id(), BlockStateId(1));
+        assert_eq!(
+            registry.state_id(&STONE, &StateProperties::new()),
+            Some(BlockStateId(1))
+        );
+
+        assert_eq!(OAK_LOG.default_state().id(), BlockStateId(137));
+        for (axis, id) in [("x", 136), ("y", 137), ("z", 138)] {
+            assert_eq!(
+                OAK_LOG.default_state().with("axis", axis).id(),
+                BlockStateId(id)
+            );
+            let properties = properties(&[("axis", axis)]);
+            assert_eq!(
+                registry.state_id(&OAK_LOG, &properties),
+                Some(BlockStateId(id))
+            );
+            let (block, found) = registry.state(BlockStateId(id)).unwrap();
+            assert_eq!(block, &OAK_LOG);
+            assert_eq!(found, &properties);
+        }
+
+        assert_eq!(
+            GRASS_BLOCK.state_id(&properties(&[("snowy", "true")])),
+            Some(BlockStateId(8))
+        );
+    }
+
+    #[test]
+    fn unknown_properties_have_no_state() {
+        let registry = BlockStateRegistry::vanilla();
+        assert_eq!(
+            OAK_LOG
+                .default_state()
+                .try_with("axis", "w")
+                .map(StateBuilder::id),
+            None
+        );
+        assert_eq!(OAK_LOG.state_id(&properties(&[("snowy", "true")])), None);
+        assert_eq!(
+            registry.state_id(&STONE, &properties(&[("axis", "x")])),
+            None
+        );
+        // The registry wants every property; `Block::state_id` fills defaults.
+        assert_eq!(registry.state_id(&OAK_LOG, &StateProperties::new()), None);
+        assert_eq!(
+            OAK_LOG.state_id(&StateProperties::new()),
+            Some(BlockStateId(137))
+        );
+    }
+}
@@ -1,8 +1,9 @@
 //! Per-dim block-update wire emit. Replaces the old host-side
 //! `update_client_blocks` body (which queried `&mut ServerSideConnection`
 //! directly) with a per-dim system that resolves recipients through
-//! `Column.PlayerObservers` and emits one `OutboundPlayerPacket` per block
-//! change. Single message hop — no two-frame buffer rotation across the
+//! `Column.PlayerObservers` and emits one `OutboundPlayerPacket` per changed
+//! section: a Block Update for a lone change, a Section Blocks Update for
+//! several. Single message hop — no two-frame buffer rotation across the
 //! `World` boundary that caused the TNT silent-drop regression.
 //!
 //! Lives in the minecraft crate (not in `mcrs_minecraft_block`) to avoid
@@ -17,12 +18,13 @@ use bevy_ecs::message::MessageWriter;
 use bevy_ecs::prelude::{Changed, Entity, IntoScheduleConfigs, Query, With};
 use mcrs_engine::aoi::PlayerObservers;
 use mcrs_engine::entity::player::Player;
-use mcrs_engine::geometry::ColumnPos;
+use mcrs_engine::geometry::{BlockPos, ColumnPos};
 use mcrs_engine::world::chunk::ChunkPos;
 use mcrs_engine::world::dimension::InDimension;
 use mcrs_engine::world::storage::column::ColumnIndex;
 use mcrs_minecraft_block::block_update::{BlockUpdateSet, ChunkNetworkSyncBlockChangesSet};
 use mcrs_minecraft_block::palette::BlockPalette;
+use mcrs_protocol::chunk::ChunkBlockUpdateEntry;
 use smallvec::SmallVec;
 
 pub use mcrs_minecraft_block::block_update::BlockUpdatePlugin;
@@ -35,15 +37,16 @@ use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, Pac
 /// `ChunkNetworkSyncBlockChangesSet` changed this tick, resolves the
 /// observer set through the chunk's column (`ColumnPos::from(chunk_pos)`
 /// -> `ColumnIndex.0.get` -> column entity -> `PlayerObservers`), and
-/// emits one `OutboundPlayerPacket { target: PlayerSet, priority: Normal,
-/// data: PacketPayload::BlockUpdate { position, new_state } }` per
-/// changed block, then clears the changes set.
+/// emits one `OutboundPlayerPacket { target: PlayerSet, priority: Normal }`
+/// per chunk, then clears the changes set. Like vanilla, a single changed
+/// block is sent as `PacketPayload::BlockUpdate` and more as one
+/// `PacketPayload::SectionBlocksUpdate`.
 ///
 /// Recipients are resolved at emit time by reading `PlayerObservers` on
 /// the chunk's column entity rather than at consume time on the host.
 /// The change set is drained every tick — same lifecycle as the previous
 /// host-side body, so repeated changes on the same block within a tick
-/// coalesce into a single packet emit.
+/// coalesce into a single entry.
 #[cfg_attr(
     feature = "telemetry-tracy",
     tracing::instrument(name = "block_update::update_client_blocks_per_dim", skip_all)
@@ -82,28 +85,48 @@ pub fn update_client_blocks_per_dim(
         // Drain regardless of whether there are recipients — leaving stale
         // entries in the change set would re-fire `Changed<...>` next tick
         // and keep emitting empty packets, or accumulate unbounded.
-        let positions: Vec<_> = changes.changes.drain().collect();
+        let mut positions: Vec<_> = changes.changes.drain().collect();
 
         if observer_entities.is_empty() {
             continue;
         }
 
-        for position in positions {
-            let new_state = palette.get(position);
-            packet_writer.write(OutboundPlayerPacket {
-                target: PacketTarget::PlayerSet(observer_entities.clone()),
-                priority: PacketPriority::Normal,
-                data: PacketPayload::BlockUpdate {
-                    position,
-                    new_state,
-                },
-            });
-            mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
-                .fetch_add(1, Ordering::Relaxed);
-        }
+        let data = if let [position] = positions[..] {
+            PacketPayload::BlockUpdate {
+                position,
+                new_state: palette.get(position),
+            }
+        } else {
+            // Hash order would make the packet differ run to run.
+            positions.sort_unstable_by_key(|pos| (pos.y, pos.z, pos.x));
+            PacketPayload::SectionBlocksUpdate {
+                section: *chunk_pos,
+                blocks: positions
+                    .into_iter()
+                    .map(|pos| section_block_entry(pos, palette.get(pos).0))
+                    .collect(),
+            }
+        };
+        packet_writer.write(OutboundPlayerPacket {
+            target: PacketTarget::PlayerSet(observer_entities),
+            priority: PacketPriority::Normal,
+            data,
+        });
+        mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+            .fetch_add(1, Ordering::Relaxed);
     }
 }
 
+/// Pack a block's position within its section with its state id, as a
+/// Section Blocks Update entry.
+fn section_block_entry(pos: BlockPos, state: u16) -> ChunkBlockUpdateEntry {
+    ChunkBlockUpdateEntry::new()
+        .with_off_x((pos.x & 15) as u8)
+        .with_off_y((pos.y & 15) as u8)
+        .with_off_z((pos.z & 15) as u8)
+        .with_block_state(state)
+}
+
 /// Per-dim wire-emit plugin. Pairs with `BlockUpdatePlugin` (which
 /// remains in the block crate and supplies the message types + reader +
 /// change-tracker seeder); both are registered into each `DimSubApp`.
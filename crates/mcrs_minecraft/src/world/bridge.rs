@@ -1,6 +1,7 @@
 use std::sync::atomic::Ordering;
 
 use bevy_ecs::entity::Entity;
+use bevy_ecs::event::Event;
 use bevy_math::DVec3;
 use bevy_ecs::message::{MessageReader, MessageWriter, Messages};
 use bevy_ecs::prelude::Commands;
@@ -10,10 +11,11 @@ use bevy_ecs::system::{Query, Res, ResMut};
 
 /// FixedPostUpdate ordering for the three bridge stages.
 ///
-/// `Outbound` fills per-connection `OutboundQueue` from the message bus.
-/// `Dispatch` encodes + coalesces + sends each queue to the socket.
 /// `Inbound` reads serverbound packets from sockets and routes them to
-/// `PendingInboundPartition` or `inbound_pending`.
+/// `PendingInboundPartition` or `inbound_pending` (in `NetworkSet::Receive`).
+/// `Outbound` fills per-connection `OutboundQueue` from the message bus.
+/// `Dispatch` encodes + coalesces + sends each queue to the socket. Both run
+/// in `NetworkSet::Flush`, in that order.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, SystemSet)]
 pub enum BridgeSet {
     Outbound,
@@ -21,31 +23,39 @@ pub enum BridgeSet {
     Inbound,
 }
 use mcrs_network::event::ReceivedPacketEvent;
-use mcrs_network::{EngineConnection, InGameConnectionState, ServerSideConnection};
+use mcrs_network::{EngineConnection, InGameConnectionState, SendError, ServerSideConnection};
 use mcrs_protocol::chunk::ChunkData;
 use mcrs_protocol::packets::game::clientbound::{
-    ClientboundAddEntity, ClientboundBlockUpdate, ClientboundChunkCacheRadius,
+    ClientboundAddEntity, ClientboundAnimate, ClientboundBlockUpdate, ClientboundChunkCacheRadius,
+    ClientboundCommands, ClientboundContainerSetContent, ClientboundContainerSetSlot,
     ClientboundDisconnect, ClientboundEntityEvent, ClientboundEntityPositionSync,
     ClientboundForgetLevelChunk, ClientboundGameEvent, ClientboundLevelChunkWithLight,
-    ClientboundLightUpdate, ClientboundLogin, ClientboundPlayerInfoUpdate,
-    ClientboundPlayerPosition, ClientboundRemoveEntities, ClientboundSetChunkCacheCenter,
-    ClientboundSystemChatPacket,
+    ClientboundLightUpdate, ClientboundLogin, ClientboundMoveEntityPos,
+    ClientboundMoveEntityPosRot, ClientboundPlayerInfoUpdate, ClientboundPlayerPosition,
+    ClientboundRemoveEntities, ClientboundRespawn, ClientboundRotateHead,
+    ClientboundSectionBlocksUpdate, ClientboundSetChunkCacheCenter,
+    ClientboundSetDefaultSpawnPosition, ClientboundSetEntityData, ClientboundSetEntityMotion,
+    ClientboundSetSimulationDistance, ClientboundSound, ClientboundSystemChatPacket,
+    ClientboundUpdateAttributes,
 };
 use mcrs_protocol::entity::player::PlayerSpawnInfo;
+use mcrs_protocol::game_mode::OptGameMode;
 use mcrs_protocol::profile::{PlayerListActions, PlayerListEntry};
-use mcrs_protocol::{ByteAngle, GameEventKind, Ident, Look, PositionFlag, Text, VarInt};
+use mcrs_protocol::{ByteAngle, GameEventKind, GlobalPos, Ident, LpVec3, Text, VarInt};
 use rustc_hash::FxHashSet;
 use tracing::{debug, trace, warn};
 
+use crate::sound::sound_fixed_position;
 use crate::world::bridge_queue::{
     InboundRateBucket, OutboundQueue, DEPTH_DRAIN_TARGET, DEPTH_LIMIT, HIGH_OVERFLOW_LIMIT,
     KICK_AFTER_OVERFLOW_TICKS,
 };
 use crate::world::bus::{
     InboundPlayerDespawn, InboundPlayerPacket, InboundPlayerSpawn, OutboundPlayerAttached,
-    OutboundPlayerTransfer, OutboundPlayerTransferRequest, PacketPayload, PacketTarget,
-    PendingInboundLifecycle, PendingInboundPartition,
+    OutboundPlayerDisconnect, OutboundPlayerTransfer, OutboundPlayerTransferRequest, PacketPayload,
+    PacketTarget, PendingInboundLifecycle, PendingInboundPartition, SpawnReason,
 };
+use crate::world::entity::player::DisconnectReason;
 use crate::world::player_index::{HostAnchorRef, PlayerIndex};
 use crate::world::sub_app_builder::{DimLabel, DimSubAppHandle};
 
@@ -171,7 +181,7 @@ pub fn bridge_outbound(
 /// tick.
 ///
 /// Execution order: runs in `BridgeSet::Dispatch` (FixedPostUpdate), after
-/// `bridge_outbound` filled queues and before `bridge_inbound` reads.
+/// `bridge_inbound` read the sockets and `bridge_outbound` filled queues.
 ///
 /// SEQUENTIAL `iter_mut()` — do NOT use `par_iter_mut`. Kicking a connection
 /// issues `commands.entity(e).remove::<ServerSideConnection>()`, which
@@ -200,7 +210,7 @@ pub fn dispatch_encode(
                 })
                 .ok();
             let blob = conn.raw.take_encoded();
-            conn.raw.try_send_blob(blob);
+            let _ = conn.raw.try_send_blob(blob);
             commands.entity(entity).remove::<ServerSideConnection>();
             BRIDGE_KICK_OVERFLOW_TOTAL.fetch_add(1, Ordering::Relaxed);
             continue;
@@ -220,7 +230,7 @@ pub fn dispatch_encode(
                 })
                 .ok();
             let blob = conn.raw.take_encoded();
-            conn.raw.try_send_blob(blob);
+            let _ = conn.raw.try_send_blob(blob);
             commands.entity(entity).remove::<ServerSideConnection>();
             BRIDGE_KICK_OVERFLOW_TOTAL.fetch_add(1, Ordering::Relaxed);
             continue;
@@ -268,7 +278,7 @@ pub fn dispatch_encode(
                                 z: VarInt(column.z),
                                 light_data,
                             })
-                            .ok();
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
                     }
                     PacketPayload::BlockUpdate {
                         position,
@@ -279,7 +289,22 @@ pub fn dispatch_encode(
                                 block_pos: position,
                                 block_state_id: new_state,
                             })
-                            .ok();
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::SectionBlocksUpdate { section, blocks } => {
+                        trace!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            ?section,
+                            blocks = blocks.len(),
+                            "dispatch_encode: SectionBlocksUpdate"
+                        );
+                        conn.raw
+                            .append(&ClientboundSectionBlocksUpdate {
+                                chunk_pos: section,
+                                blocks: blocks.into(),
+                            })
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
                     }
                     PacketPayload::ChunkUnload { column } => {
                         conn.raw
@@ -287,7 +312,7 @@ pub fn dispatch_encode(
                                 x: column.x,
                                 z: column.z,
                             })
-                            .ok();
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
                     }
                     PacketPayload::EntityPosSync {
                         entity_id,
@@ -310,15 +335,108 @@ pub fn dispatch_encode(
                                 look,
                                 on_ground,
                             })
-                            .ok();
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::EntityMove {
+                        entity_id,
+                        delta,
+                        look,
+                        on_ground,
+                    } => {
+                        trace!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            entity_id,
+                            "dispatch_encode: EntityMove"
+                        );
+                        match look {
+                            Some(look) => {
+                                conn.raw
+                                    .append(&ClientboundMoveEntityPosRot {
+                                        entity_id: VarInt(entity_id),
+                                        delta,
+                                        y_rot: ByteAngle::from_degrees(look.yaw),
+                                        x_rot: ByteAngle::from_degrees(look.pitch),
+                                        on_ground,
+                                    })
+                                    .unwrap_or_else(|e| skip_unencodable(entity, e));
+                                conn.raw
+                                    .append(&ClientboundRotateHead {
+                                        entity_id: VarInt(entity_id),
+                                        y_head_rot: ByteAngle::from_degrees(look.yaw),
+                                    })
+                                    .unwrap_or_else(|e| skip_unencodable(entity, e));
+                            }
+                            None => {
+                                conn.raw
+                                    .append(&ClientboundMoveEntityPos {
+                                        entity_id: VarInt(entity_id),
+                                        delta,
+                                        on_ground,
+                                    })
+                                    .unwrap_or_else(|e| skip_unencodable(entity, e));
+                            }
+                        }
+                    }
+                    PacketPayload::EntityAnimation { entity_id, action } => {
+                        trace!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            entity_id,
+                            action,
+                            "dispatch_encode: EntityAnimation"
+                        );
+                        conn.raw
+                            .append(&ClientboundAnimate {
+                                entity_id: VarInt(entity_id),
+                                action,
+                            })
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::UpdateAttributes {
+                        entity_id,
+                        attributes,
+                    } => {
+                        trace!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            entity_id,
+                            count = attributes.len(),
+                            "dispatch_encode: UpdateAttributes"
+                        );
+                        conn.raw
+                            .append(&ClientboundUpdateAttributes {
+                                entity_id: VarInt(entity_id),
+                                attributes,
+                            })
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::EntityVelocity {
+                        entity_id,
+                        velocity,
+                    } => {
+                        trace!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            entity_id,
+                            "dispatch_encode: EntityVelocity"
+                        );
+                        conn.raw
+                            .append(&ClientboundSetEntityMotion {
+                                entity_id: VarInt(entity_id),
+                                velocity: LpVec3(velocity),
+                            })
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
                     }
                     PacketPayload::PlayerEnteredView {
                         entity_id,
                         uuid,
                         kind,
                         position,
+                        velocity,
                         yaw,
                         pitch,
+                        metadata,
                     } => {
                         debug!(
                             target: "mcrs_minecraft::bridge",
@@ -326,19 +444,29 @@ pub fn dispatch_encode(
                             entity_id,
                             "dispatch_encode: PlayerEnteredView"
                         );
-                        conn.raw
-                            .append(&ClientboundAddEntity {
-                                id: VarInt(entity_id),
-                                uuid,
-                                kind: VarInt(kind),
-                                pos: position,
-                                velocity: VarInt(0),
-                                yaw: ByteAngle::from_degrees(yaw),
-                                pitch: ByteAngle::from_degrees(pitch),
-                                head_yaw: ByteAngle::from_degrees(yaw),
-                                data: VarInt(0),
-                            })
-                            .ok();
+                        conn.bundle(|conn| {
+                            conn.raw
+                                .append(&ClientboundAddEntity {
+                                    id: VarInt(entity_id),
+                                    uuid,
+                                    kind: VarInt(kind),
+                                    pos: position,
+                                    velocity: LpVec3(velocity),
+                                    yaw: ByteAngle::from_degrees(yaw),
+                                    pitch: ByteAngle::from_degrees(pitch),
+                                    head_yaw: ByteAngle::from_degrees(yaw),
+                                    data: VarInt(0),
+                                })
+                                .unwrap_or_else(|e| skip_unencodable(entity, e));
+                            if !metadata.is_empty() {
+                                conn.raw
+                                    .append(&ClientboundSetEntityData {
+                                        entity_id: VarInt(entity_id),
+                                        metadata,
+                                    })
+                                    .unwrap_or_else(|e| skip_unencodable(entity, e));
+                            }
+                        });
                     }
                     PacketPayload::ChunkLoad {
                         column,
@@ -363,7 +491,7 @@ pub fn dispatch_encode(
                                 chunk_data,
                                 light_data,
                             })
-                            .ok();
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
                     }
                     PacketPayload::PlayerLeftView { entity_ids } => {
                         debug!(
@@ -376,7 +504,24 @@ pub fn dispatch_encode(
                             .append(&ClientboundRemoveEntities {
                                 entity_ids: entity_ids.iter().map(|id| VarInt(*id)).collect(),
                             })
-                            .ok();
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::EntityData {
+                        entity_id,
+                        metadata,
+                    } => {
+                        trace!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            entity_id,
+                            "dispatch_encode: EntityData"
+                        );
+                        conn.raw
+                            .append(&ClientboundSetEntityData {
+                                entity_id: VarInt(entity_id),
+                                metadata,
+                            })
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
                     }
                     PacketPayload::PlayerLogin {
                         player_id,
@@ -391,6 +536,13 @@ pub fn dispatch_encode(
                         reduced_debug_info,
                         show_death_screen,
                         do_limited_crafting,
+                        previous_game_mode,
+                        hashed_seed,
+                        is_debug,
+                        is_flat,
+                        last_death_location,
+                        portal_cooldown,
+                        sea_level,
                         enforces_secure_chat,
                     } => {
                         debug!(
@@ -422,12 +574,25 @@ pub fn dispatch_encode(
                                         dimension.as_str(),
                                     )
                                     .expect("dimension id is a valid resource location"),
+                                    seed: hashed_seed as u64,
                                     game_mode,
-                                    ..Default::default()
+                                    prev_game_mode: OptGameMode(previous_game_mode),
+                                    is_debug,
+                                    is_flat,
+                                    last_depth_location: last_death_location.and_then(
+                                        |(death_dimension, position)| {
+                                            Some(GlobalPos {
+                                                dimension_name: Ident::new(death_dimension).ok()?,
+                                                position,
+                                            })
+                                        },
+                                    ),
+                                    portal_cooldown: VarInt(portal_cooldown),
+                                    sea_level: VarInt(sea_level),
                                 },
                                 enforces_secure_chat,
                             })
-                            .ok();
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
                     }
                     PacketPayload::LevelChunksLoadStart => {
                         debug!(
@@ -439,7 +604,7 @@ pub fn dispatch_encode(
                             .append(&ClientboundGameEvent {
                                 game_event: GameEventKind::LevelChunksLoadStart,
                             })
-                            .ok();
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
                     }
                     PacketPayload::PlayerLoginEntityEvent {
                         entity_id,
@@ -457,7 +622,7 @@ pub fn dispatch_encode(
                                 entity_id,
                                 entity_status,
                             })
-                            .ok();
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
                     }
                     PacketPayload::SetChunkCacheCenter { x, z } => {
                         debug!(
@@ -472,7 +637,7 @@ pub fn dispatch_encode(
                                 x: VarInt(x),
                                 z: VarInt(z),
                             })
-                            .ok();
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
                     }
                     PacketPayload::SetChunkCacheRadius { radius } => {
                         debug!(
@@ -485,7 +650,47 @@ pub fn dispatch_encode(
                             .append(&ClientboundChunkCacheRadius {
                                 radius: VarInt(radius),
                             })
-                            .ok();
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::SetSimulationDistance {
+                        simulation_distance,
+                    } => {
+                        debug!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            simulation_distance,
+                            "dispatch_encode: SetSimulationDistance"
+                        );
+                        conn.raw
+                            .append(&ClientboundSetSimulationDistance {
+                                simulation_distance: VarInt(simulation_distance),
+                            })
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::SetDefaultSpawnPosition {
+                        dimension,
+                        position,
+                        yaw,
+                        pitch,
+                    } => {
+                        debug!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            %dimension,
+                            %position,
+                            "dispatch_encode: SetDefaultSpawnPosition"
+                        );
+                        conn.raw
+                            .append(&ClientboundSetDefaultSpawnPosition {
+                                pos: GlobalPos {
+                                    dimension_name: Ident::new(dimension)
+                                        .expect("spawn dimension is a valid resource location"),
+                                    position,
+                                },
+                                yaw,
+                                pitch,
+                            })
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
                     }
                     PacketPayload::PlayerInfoUpdate { entries } => {
                         debug!(
@@ -498,25 +703,56 @@ pub fn dispatch_encode(
                             .iter()
                             .map(|e| PlayerListEntry {
                                 player_uuid: e.player_uuid,
-                                username: e.username.as_str(),
                                 game_mode: e.game_mode,
-                                listed: e.listed,
                                 ..Default::default()
                             })
                             .collect();
                         conn.raw
                             .append(&ClientboundPlayerInfoUpdate {
-                                actions: PlayerListActions::new()
-                                    .with_add_player(true)
-                                    .with_update_game_mode(true)
-                                    .with_update_listed(true),
+                                actions: PlayerListActions::new().with_update_game_mode(true),
                                 entries: std::borrow::Cow::Borrowed(&wire_entries),
                             })
-                            .ok();
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::Respawn {
+                        dimension,
+                        dimension_type_id,
+                        game_mode,
+                        hashed_seed,
+                        sea_level,
+                        portal_cooldown,
+                        data_to_keep,
+                    } => {
+                        debug!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            %dimension,
+                            data_to_keep,
+                            "dispatch_encode: Respawn"
+                        );
+                        conn.raw
+                            .append(&ClientboundRespawn {
+                                player_spawn_info: PlayerSpawnInfo {
+                                    dimension_type_id: VarInt(dimension_type_id),
+                                    dimension: Ident::<std::borrow::Cow<str>>::new(
+                                        dimension.as_str(),
+                                    )
+                                    .expect("dimension id is a valid resource location"),
+                                    seed: hashed_seed as u64,
+                                    game_mode,
+                                    portal_cooldown: VarInt(portal_cooldown),
+                                    sea_level: VarInt(sea_level),
+                                    ..Default::default()
+                                },
+                                data_to_keep,
+                            })
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
                     }
                     PacketPayload::PlayerPosition {
                         teleport_id,
                         position,
+                        look,
+                        flags,
                     } => {
                         debug!(
                             target: "mcrs_minecraft::bridge",
@@ -530,10 +766,24 @@ pub fn dispatch_encode(
                                 teleport_id: VarInt(teleport_id),
                                 position,
                                 velocity: DVec3::ZERO,
-                                look: Look::default(),
-                                flags: Vec::<PositionFlag>::new(),
+                                look,
+                                flags,
                             })
-                            .ok();
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::Commands { nodes, root_index } => {
+                        debug!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            nodes = nodes.len(),
+                            "dispatch_encode: Commands"
+                        );
+                        conn.raw
+                            .append(&ClientboundCommands {
+                                nodes,
+                                root_index: VarInt(root_index),
+                            })
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
                     }
                     PacketPayload::SystemChat { content, overlay } => {
                         debug!(
@@ -543,7 +793,119 @@ pub fn dispatch_encode(
                         );
                         conn.raw
                             .append(&ClientboundSystemChatPacket { content, overlay })
-                            .ok();
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::Sound {
+                        sound,
+                        category,
+                        position,
+                        volume,
+                        pitch,
+                        seed,
+                    } => {
+                        let [x, y, z] = sound_fixed_position(position);
+                        conn.raw
+                            .append(&ClientboundSound {
+                                sound,
+                                category,
+                                x,
+                                y,
+                                z,
+                                volume,
+                                pitch,
+                                seed,
+                            })
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::ContainerSetContent {
+                        container_id,
+                        state_seqno,
+                        slots,
+                        carried,
+                    } => {
+                        trace!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            container_id,
+                            state_seqno,
+                            slots = slots.len(),
+                            "dispatch_encode: ContainerSetContent"
+                        );
+                        conn.raw
+                            .append(&ClientboundContainerSetContent {
+                                container_id: VarInt(container_id),
+                                state_seqno: VarInt(state_seqno),
+                                slot_data: slots,
+                                carried_item: carried,
+                            })
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::ContainerSetSlot {
+                        container_id,
+                        state_seqno,
+                        slot,
+                        item,
+                    } => {
+                        trace!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            container_id,
+                            state_seqno,
+                            slot,
+                            "dispatch_encode: ContainerSetSlot"
+                        );
+                        conn.raw
+                            .append(&ClientboundContainerSetSlot {
+                                container_id: VarInt(container_id),
+                                state_seqno: VarInt(state_seqno),
+                                slot,
+                                item,
+                            })
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::SetTime(packet) => {
+                        trace!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            game_time = packet.game_time,
+                            "dispatch_encode: SetTime"
+                        );
+                        conn.raw
+                            .append(&packet)
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::InitializeBorder(packet) => {
+                        trace!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            size = packet.new_size,
+                            "dispatch_encode: InitializeBorder"
+                        );
+                        conn.raw
+                            .append(&packet)
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::PlayerAbilities(packet) => {
+                        trace!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            flags = packet.flags.into_bits(),
+                            "dispatch_encode: PlayerAbilities"
+                        );
+                        conn.raw
+                            .append(&packet)
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
+                    }
+                    PacketPayload::GameEvent(packet) => {
+                        trace!(
+                            target: "mcrs_minecraft::bridge",
+                            conn = ?entity,
+                            game_event = ?packet.game_event,
+                            "dispatch_encode: GameEvent"
+                        );
+                        conn.raw
+                            .append(&packet)
+                            .unwrap_or_else(|e| skip_unencodable(entity, e));
                     }
                     PacketPayload::Test(_) => {
                         // Test-only payload; no wire packet. Counted-drop so
@@ -556,20 +918,35 @@ pub fn dispatch_encode(
 
         // --- (4) Coalesce + send ---
         let blob = conn.raw.take_encoded();
-        if blob.len() > MAX_QUEUED_BYTES_PER_SOCKET {
-            // Byte-cap backstop: oversized blob is never sent; kick the connection.
+        let queued = blob.len() + conn.raw.queued_bytes();
+        if queued > MAX_QUEUED_BYTES_PER_SOCKET {
+            // Byte-cap backstop: a client this far behind never catches up;
+            // kick the connection.
             warn!(
                 entity = ?entity,
-                blob_len = blob.len(),
+                queued,
                 max = MAX_QUEUED_BYTES_PER_SOCKET,
-                "dispatch_encode: blob exceeds MAX_QUEUED_BYTES_PER_SOCKET; closing connection"
+                "dispatch_encode: queued bytes exceed MAX_QUEUED_BYTES_PER_SOCKET; closing connection"
             );
             commands.entity(entity).remove::<ServerSideConnection>();
             continue;
         }
-        if !blob.is_empty() && !conn.raw.try_send_blob(blob) {
-            // Channel full = backpressure; feeds kick path next tick.
-            queue.overflow_ticks = queue.overflow_ticks.saturating_add(1);
+        match conn.raw.try_send_blob(blob) {
+            Ok(()) | Err(SendError::Encode(_)) => {}
+            Err(SendError::Full) => {
+                // Backpressure: the bytes stay queued and go out ahead of
+                // next tick's blob.
+                queue.overflow_ticks = queue.overflow_ticks.saturating_add(1);
+            }
+            Err(SendError::Disconnected) => {
+                debug!(
+                    target: "mcrs_minecraft::bridge",
+                    conn = ?entity,
+                    "dispatch_encode: writer gone; closing connection"
+                );
+                commands.entity(entity).remove::<ServerSideConnection>();
+                continue;
+            }
         }
 
         // --- (5) Update depth gauges (monotone totals, consistent with metrics.rs) ---
@@ -580,6 +957,16 @@ pub fn dispatch_encode(
     }
 }
 
+/// A packet that failed to encode left nothing in the encoder, so it is
+/// dropped and the rest of the tick's packets still go out.
+fn skip_unencodable(entity: Entity, error: SendError) {
+    warn!(
+        target: "mcrs_minecraft::bridge",
+        conn = ?entity,
+        "dispatch_encode: dropping packet: {error}"
+    );
+}
+
 pub fn partition_main_inbound(
     mut msgs: ResMut<Messages<InboundPlayerPacket>>,
     mut partition: ResMut<PendingInboundPartition>,
@@ -627,6 +1014,7 @@ pub fn resolve_transfer_requests(
             host_anchor: req.host_anchor,
             dest_dim,
             snapshot: req.snapshot,
+            reason: req.reason,
         });
     }
 }
@@ -662,8 +1050,10 @@ pub fn bridge_player_transfer(
         // Despawn the player's entity in the dimension it is leaving, otherwise
         // that entity keeps its AoI subscription and streams the old
         // dimension's chunks (wrong section count for the new dimension) to the
-        // now-relocated connection, crashing the client.
-        if old_current_dim != Entity::PLACEHOLDER && old_current_dim != msg.dest_dim {
+        // now-relocated connection, crashing the client. A death respawn
+        // replaces the entity even within the same dimension.
+        let replaced = old_current_dim != msg.dest_dim || msg.reason == SpawnReason::Death;
+        if old_current_dim != Entity::PLACEHOLDER && replaced {
             lifecycle
                 .per_dim
                 .entry(old_current_dim)
@@ -676,6 +1066,7 @@ pub fn bridge_player_transfer(
         let spawn = InboundPlayerSpawn {
             host_anchor: msg.host_anchor,
             snapshot: msg.snapshot.clone(),
+            reason: msg.reason,
         };
         lifecycle
             .per_dim
@@ -686,10 +1077,21 @@ pub fn bridge_player_transfer(
     }
 }
 
+/// Triggered host-side each time a player is bound to its new in-dim entity,
+/// on join and after every dimension change. The dimension's Login or
+/// Respawn is already queued by then, so host-resident world state (time,
+/// border, weather) sent from here reaches the client after it, like vanilla
+/// `PlayerList.sendLevelInfo`.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlayerAttached {
+    pub host_anchor: Entity,
+}
+
 pub fn bridge_player_attach(
     mut attach_msgs: ResMut<Messages<OutboundPlayerAttached>>,
     mut player_index: ResMut<PlayerIndex>,
     mut partition: ResMut<PendingInboundPartition>,
+    mut commands: Commands,
 ) {
     for msg in attach_msgs.drain() {
         let drained_and_dim = {
@@ -703,6 +1105,9 @@ pub fn bridge_player_attach(
             (drained, current_dim)
         };
         let (drained, current_dim) = drained_and_dim;
+        commands.trigger(PlayerAttached {
+            host_anchor: msg.host_anchor,
+        });
         if !drained.is_empty() {
             let bucket = partition.per_dim.entry(current_dim).or_default();
             for packet in drained {
@@ -712,6 +1117,23 @@ pub fn bridge_player_attach(
     }
 }
 
+/// Put the reason of each per-dim disconnect request on the player's host
+/// connection, where `disconnect_player` sends it.
+pub fn bridge_player_disconnect(
+    mut disconnect_msgs: ResMut<Messages<OutboundPlayerDisconnect>>,
+    player_index: Res<PlayerIndex>,
+    mut commands: Commands,
+) {
+    for msg in disconnect_msgs.drain() {
+        let Some(location) = player_index.get(&msg.host_anchor) else {
+            continue;
+        };
+        commands
+            .entity(location.socket)
+            .insert(DisconnectReason(msg.reason));
+    }
+}
+
 pub fn bridge_inbound(
     mut conns: Query<
         (
@@ -743,7 +1165,7 @@ pub fn bridge_inbound(
                             })
                             .ok();
                         let blob = conn.raw.take_encoded();
-                        conn.raw.try_send_blob(blob);
+                        let _ = conn.raw.try_send_blob(blob);
                         commands.entity(entity).remove::<ServerSideConnection>();
                         BRIDGE_KICK_FLOOD_TOTAL.fetch_add(1, Ordering::Relaxed);
                         break;
@@ -927,6 +1349,7 @@ mod tests {
 
     use crate::world::bus::PlayerTransferSnapshot;
     use bevy_math::{DVec3, Vec2};
+    use mcrs_protocol::GameMode;
     use mcrs_protocol::uuid::Uuid;
 
     fn synthetic_snapshot() -> PlayerTransferSnapshot {
@@ -935,6 +1358,7 @@ mod tests {
             username: "test".into(),
             position: DVec3::ZERO,
             rotation: Vec2::ZERO,
+            game_mode: GameMode::Survival,
         }
     }
 
@@ -982,6 +1406,7 @@ mod tests {
                 host_anchor,
                 dest_dim,
                 snapshot: synthetic_snapshot(),
+                reason: SpawnReason::DimensionChange,
             });
 
         run_transfer(&mut world);
@@ -1000,6 +1425,46 @@ mod tests {
         assert_eq!(bundle.spawns[0].host_anchor, host_anchor);
     }
 
+    #[test]
+    fn death_respawn_in_the_same_dim_replaces_the_entity() {
+        let mut world = World::new();
+        world.init_resource::<Messages<OutboundPlayerTransfer>>();
+        world.init_resource::<PlayerIndex>();
+        world.init_resource::<PendingInboundLifecycle>();
+
+        let host_anchor = world.spawn_empty().id();
+        let dim = world.spawn(DimSubAppHandle).id();
+        let in_dim = world.spawn_empty().id();
+
+        world.resource_mut::<PlayerIndex>().insert(
+            host_anchor,
+            PlayerLocation {
+                socket: Entity::PLACEHOLDER,
+                current_dim: dim,
+                previous_dim: None,
+                in_dim_entity: Some(in_dim),
+                inbound_pending: SmallVec::new(),
+            },
+        );
+
+        world
+            .resource_mut::<Messages<OutboundPlayerTransfer>>()
+            .write(OutboundPlayerTransfer {
+                host_anchor,
+                dest_dim: dim,
+                snapshot: synthetic_snapshot(),
+                reason: SpawnReason::Death,
+            });
+
+        run_transfer(&mut world);
+
+        let lifecycle = world.resource::<PendingInboundLifecycle>();
+        let bundle = lifecycle.per_dim.get(&dim).expect("dim bundle present");
+        assert_eq!(bundle.despawns.len(), 1);
+        assert_eq!(bundle.spawns.len(), 1);
+        assert_eq!(bundle.spawns[0].reason, SpawnReason::Death);
+    }
+
     #[test]
     fn bridge_player_transfer_drops_transfer_to_unregistered_dim() {
         let mut world = World::new();
@@ -1032,6 +1497,7 @@ mod tests {
                 host_anchor,
                 dest_dim: bogus_dim,
                 snapshot: synthetic_snapshot(),
+                reason: SpawnReason::DimensionChange,
             });
 
         run_transfer(&mut world);
@@ -1123,4 +1589,41 @@ mod tests {
         let partition = world.resource::<PendingInboundPartition>();
         assert!(partition.per_dim.is_empty());
     }
+
+    #[test]
+    fn bridge_player_disconnect_puts_the_reason_on_the_socket() {
+        let mut world = World::new();
+        world.init_resource::<Messages<OutboundPlayerDisconnect>>();
+        world.init_resource::<PlayerIndex>();
+
+        let host_anchor = Entity::from_raw_u32(42).expect("nonzero");
+        let socket = world.spawn_empty().id();
+        world.resource_mut::<PlayerIndex>().insert(
+            host_anchor,
+            PlayerLocation {
+                socket,
+                current_dim: Entity::PLACEHOLDER,
+                previous_dim: None,
+                in_dim_entity: Some(Entity::PLACEHOLDER),
+                inbound_pending: SmallVec::new(),
+            },
+        );
+        let reason = Text::translate("multiplayer.disconnect.unsigned_chat", vec![]);
+        world
+            .resource_mut::<Messages<OutboundPlayerDisconnect>>()
+            .write(OutboundPlayerDisconnect {
+                host_anchor,
+                reason: reason.clone(),
+            });
+
+        let mut sys = IntoSystem::into_system(bridge_player_disconnect);
+        sys.initialize(&mut world);
+        let _ = sys.run((), &mut world);
+        sys.apply_deferred(&mut world);
+
+        assert_eq!(
+            world.get::<DisconnectReason>(socket).map(|r| &r.0),
+            Some(&reason)
+        );
+    }
 }
@@ -3,11 +3,19 @@ use bevy_ecs::message::Message;
 use bevy_ecs::resource::Resource;
 use bevy_math::{DVec3, Vec2};
 use bytes::Bytes;
-use mcrs_engine::geometry::{BlockPos, ColumnPos};
+use mcrs_engine::geometry::{BlockPos, ChunkPos, ColumnPos};
 use mcrs_protocol::BlockStateId;
-use mcrs_protocol::chunk::LightData;
+use mcrs_protocol::chunk::{ChunkBlockUpdateEntry, LightData};
+use mcrs_protocol::command::CommandNode;
+use mcrs_protocol::entity::EntityMetadata;
+use mcrs_protocol::entity::attribute::AttributeSnapshot;
+use mcrs_protocol::packets::game::clientbound::{
+    ClientboundGameEvent, ClientboundInitializeBorder, ClientboundPlayerAbilities,
+    ClientboundSetTime,
+};
+use mcrs_protocol::sound::{SoundCategory, SoundId};
 use mcrs_protocol::uuid::Uuid;
-use mcrs_protocol::{GameMode, Look, Text};
+use mcrs_protocol::{GameMode, Look, PositionFlag, Slot, Text};
 use rustc_hash::FxHashMap;
 use smallvec::SmallVec;
 use std::time::Instant;
@@ -34,6 +42,8 @@ pub struct OutboundPlayerTransfer {
     pub host_anchor: Entity,
     pub dest_dim: Entity,
     pub snapshot: PlayerTransferSnapshot,
+    /// `DimensionChange` or `Death`; passed on to the destination's spawn.
+    pub reason: SpawnReason,
 }
 
 /// A request to transfer a player to a dimension identified by name. Emitted
@@ -45,12 +55,27 @@ pub struct OutboundPlayerTransferRequest {
     pub host_anchor: Entity,
     pub dim_name: String,
     pub snapshot: PlayerTransferSnapshot,
+    pub reason: SpawnReason,
 }
 
 #[derive(Message, Clone, Debug)]
 pub struct InboundPlayerSpawn {
     pub host_anchor: Entity,
     pub snapshot: PlayerTransferSnapshot,
+    pub reason: SpawnReason,
+}
+
+/// Why a player entity is being materialised in a dimension, which decides
+/// whether the client is sent a Login or a Respawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpawnReason {
+    /// First placement after configuration; the client has no level yet.
+    Join,
+    /// Moved from another dimension; the client already has a level.
+    DimensionChange,
+    /// Respawned at the spawn point after dying, possibly into the same
+    /// dimension; the client already has a level.
+    Death,
 }
 
 #[derive(Message, Clone, Debug)]
@@ -59,9 +84,12 @@ pub struct OutboundPlayerAttached {
     pub new_in_dim_entity: Entity,
 }
 
+/// A per-dim request to disconnect a player. The host puts `reason` on the
+/// player's connection as a `DisconnectReason`, which sends it to the client.
 #[derive(Message, Clone, Debug)]
 pub struct OutboundPlayerDisconnect {
     pub host_anchor: Entity,
+    pub reason: Text,
 }
 
 #[derive(Message, Clone, Debug)]
@@ -98,6 +126,13 @@ pub enum PacketPayload {
         position: BlockPos,
         new_state: BlockStateId,
     },
+    /// Several changes to one section in a single tick, sent as one
+    /// ClientboundSectionBlocksUpdate. Each entry packs a block's position
+    /// within `section` with its new state.
+    SectionBlocksUpdate {
+        section: ChunkPos,
+        blocks: Vec<ChunkBlockUpdateEntry>,
+    },
     /// Carries owned chunk bytes and light data so dispatch_encode can build
     /// ClientboundLevelChunkWithLight without World access. The per-dim chunk
     /// producer encodes sections into `chunk_bytes`; dispatch constructs the
@@ -112,14 +147,25 @@ pub enum PacketPayload {
     },
     /// Carries the wire numeric entity id and all fields ClientboundAddEntity
     /// needs so dispatch_encode needs no World access. The producer resolves
-    /// `entity.index_u32() as i32` before emitting this variant.
+    /// `entity.index_u32() as i32` before emitting this variant. Non-empty
+    /// `metadata` goes out as ClientboundSetEntityData in the same bundle, so
+    /// the client never draws the entity without it. `velocity` is in blocks
+    /// per tick, like `EntityVelocity`.
     PlayerEnteredView {
         entity_id: i32,
         uuid: Uuid,
         kind: i32,
         position: DVec3,
+        velocity: DVec3,
         yaw: f32,
         pitch: f32,
+        metadata: EntityMetadata<'static>,
+    },
+    /// Carries owned metadata entries so dispatch_encode can build
+    /// ClientboundSetEntityData without World access.
+    EntityData {
+        entity_id: i32,
+        metadata: EntityMetadata<'static>,
     },
     /// Carries the wire numeric entity id list so dispatch_encode can build
     /// ClientboundRemoveEntities without World access.
@@ -136,6 +182,34 @@ pub enum PacketPayload {
         look: Look,
         on_ground: bool,
     },
+    /// Carries a relative move in 1/4096 block units so dispatch_encode can
+    /// build ClientboundMoveEntityPos, or ClientboundMoveEntityPosRot plus
+    /// ClientboundRotateHead when `look` is set. Moves that do not fit an
+    /// `i16` go out as `EntityPosSync` instead.
+    EntityMove {
+        entity_id: i32,
+        delta: [i16; 3],
+        look: Option<Look>,
+        on_ground: bool,
+    },
+    /// Carries the wire numeric entity id and one of the
+    /// `ClientboundAnimate::SWING_*` actions for ClientboundAnimate.
+    EntityAnimation {
+        entity_id: i32,
+        action: u8,
+    },
+    /// Carries the velocity, in blocks per tick, ClientboundSetEntityMotion
+    /// sets on the entity.
+    EntityVelocity {
+        entity_id: i32,
+        velocity: DVec3,
+    },
+    /// Carries the attributes ClientboundUpdateAttributes lists, owned so
+    /// dispatch_encode needs no World access.
+    UpdateAttributes {
+        entity_id: i32,
+        attributes: Vec<AttributeSnapshot>,
+    },
     /// Carries all fields ClientboundLogin requires as self-contained owned
     /// wire data so dispatch_encode needs no World access. The per-dim play-
     /// login emitter fills these from the InboundPlayerSpawn snapshot and the
@@ -160,6 +234,20 @@ pub enum PacketPayload {
         reduced_debug_info: bool,
         show_death_screen: bool,
         do_limited_crafting: bool,
+        /// The game mode the player was in before the current one; `None` on
+        /// a first join.
+        previous_game_mode: Option<GameMode>,
+        /// The first eight bytes of the SHA-256 of the world seed, which the
+        /// client only uses to jitter biome blending.
+        hashed_seed: i64,
+        is_debug: bool,
+        /// Superflat world: the client draws the horizon at the bottom of the
+        /// world instead of at sea level.
+        is_flat: bool,
+        /// Where the player last died, for recovery compasses.
+        last_death_location: Option<(String, BlockPos)>,
+        portal_cooldown: i32,
+        sea_level: i32,
         enforces_secure_chat: bool,
     },
     /// Carries the `ClientboundGameEvent { LevelChunksLoadStart }` wire data.
@@ -181,17 +269,52 @@ pub enum PacketPayload {
     SetChunkCacheRadius {
         radius: i32,
     },
+    /// Sets the distance, in chunks, the client simulates around itself.
+    SetSimulationDistance {
+        simulation_distance: i32,
+    },
+    /// Carries the `ClientboundSetDefaultSpawnPosition` wire data: the world
+    /// spawn compasses point at.
+    SetDefaultSpawnPosition {
+        dimension: String,
+        position: BlockPos,
+        yaw: f32,
+        pitch: f32,
+    },
     /// Carries owned per-entry data for ClientboundPlayerInfoUpdate so
     /// dispatch_encode needs no World access.
     PlayerInfoUpdate {
         entries: Vec<PlayerInfoEntry>,
     },
+    /// Carries the fields ClientboundRespawn requires. Emitted instead of
+    /// `PlayerLogin` when a player already in play changes dimension or
+    /// respawns after death; `data_to_keep` is a mask of
+    /// `ClientboundRespawn::KEEP_*`.
+    Respawn {
+        dimension: String,
+        dimension_type_id: i32,
+        game_mode: GameMode,
+        hashed_seed: i64,
+        sea_level: i32,
+        portal_cooldown: i32,
+        data_to_keep: u8,
+    },
+    /// Carries the command graph for ClientboundCommands. Emitted once per
+    /// join from the dimension's `CommandDispatcher`.
+    Commands {
+        nodes: Vec<CommandNode>,
+        root_index: i32,
+    },
     /// Carries the fields ClientboundPlayerPosition (teleport-sync) requires.
     /// Emitted once per join immediately after `PlayerLogin` so the client
-    /// renders at the correct spawn position rather than (0,0,0).
+    /// renders at the correct spawn position rather than (0,0,0), and again
+    /// whenever the server moves the player or rejects a client move. Fields
+    /// named in `flags` are relative to the client's current values.
     PlayerPosition {
         teleport_id: i32,
         position: DVec3,
+        look: Look,
+        flags: Vec<PositionFlag>,
     },
     /// Carries an owned system-chat message so dispatch_encode builds
     /// ClientboundSystemChatPacket without World access. The per-dim chat
@@ -201,17 +324,55 @@ pub enum PacketPayload {
         content: Text,
         overlay: bool,
     },
+    /// Carries the fields ClientboundSound requires. `position` is in block
+    /// coordinates; dispatch_encode applies the fixed-point wire scaling.
+    Sound {
+        sound: SoundId<'static>,
+        category: SoundCategory,
+        position: DVec3,
+        volume: f32,
+        pitch: f32,
+        seed: i64,
+    },
+    /// Carries ClientboundContainerSetContent: every slot of the container in
+    /// protocol order, plus the stack on the cursor.
+    ContainerSetContent {
+        container_id: i32,
+        state_seqno: i32,
+        slots: Vec<Slot>,
+        carried: Slot,
+    },
+    /// Carries ClientboundContainerSetSlot, correcting one slot the client
+    /// predicted differently.
+    ContainerSetSlot {
+        container_id: i32,
+        state_seqno: i32,
+        slot: i16,
+        item: Slot,
+    },
+    /// Carries ClientboundSetTime: the world age and the world clocks. The
+    /// host sends it this way to a player that just entered a dimension, so
+    /// it follows the Login.
+    SetTime(ClientboundSetTime),
+    /// Carries ClientboundInitializeBorder: the whole world border, sent the
+    /// same way as `SetTime` to a player that just entered a dimension.
+    InitializeBorder(ClientboundInitializeBorder),
+    /// Carries ClientboundPlayerAbilities: sent when a player enters a
+    /// dimension and whenever its abilities or game mode change.
+    PlayerAbilities(ClientboundPlayerAbilities),
+    /// Carries ClientboundGameEvent for a dimension-side event, such as a
+    /// player's game mode changing.
+    GameEvent(ClientboundGameEvent),
 }
 
 /// Owned player-list entry for use inside `PacketPayload::PlayerInfoUpdate`.
-/// Carries the fields needed for the AddPlayer + UpdateGameMode + UpdateListed
-/// action combination used during join.
+/// Dimensions only own game modes, so it is sent as an UpdateGameMode for a
+/// player the host `PlayerList` has already added; adding and listing are
+/// left to the host.
 #[derive(Clone, Debug)]
 pub struct PlayerInfoEntry {
     pub player_uuid: Uuid,
-    pub username: String,
     pub game_mode: GameMode,
-    pub listed: bool,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -227,8 +388,8 @@ pub struct TestInboundPayload {
 /// Persistent-only player state snapshot used by cross-dim transfer.
 ///
 /// Current shape carries the minimal viable fields (uuid + username +
-/// position + rotation). The full transfer contract (advancements,
-/// statistics, inventory, health, game_mode, experience) requires types
+/// position + rotation + game mode). The full transfer contract
+/// (advancements, statistics, inventory, health, experience) requires types
 /// owned by `MinecraftEntityPlugin`, which remains host-side; pulling
 /// those types into this module is out of scope for now.
 #[derive(Clone, Debug)]
@@ -237,6 +398,7 @@ pub struct PlayerTransferSnapshot {
     pub username: String,
     pub position: DVec3,
     pub rotation: Vec2,
+    pub game_mode: GameMode,
 }
 
 /// Per-dim partition of inbound player packets awaiting shuttle into a
@@ -297,6 +459,7 @@ mod tests {
             username: "test".to_string(),
             position: DVec3::ZERO,
             rotation: Vec2::ZERO,
+            game_mode: GameMode::Survival,
         };
 
         let outbound = OutboundPlayerPacket {
@@ -318,6 +481,7 @@ mod tests {
             host_anchor: e,
             dest_dim: e,
             snapshot: snapshot.clone(),
+            reason: SpawnReason::DimensionChange,
         };
         assert_eq!(
             format!("{:?}", transfer.clone()),
@@ -327,6 +491,7 @@ mod tests {
         let spawn = InboundPlayerSpawn {
             host_anchor: e,
             snapshot: snapshot.clone(),
+            reason: SpawnReason::DimensionChange,
         };
         assert_eq!(format!("{:?}", spawn.clone()), format!("{:?}", spawn));
 
@@ -339,7 +504,10 @@ mod tests {
             format!("{:?}", attached)
         );
 
-        let disconnect = OutboundPlayerDisconnect { host_anchor: e };
+        let disconnect = OutboundPlayerDisconnect {
+            host_anchor: e,
+            reason: Text::default(),
+        };
         assert_eq!(
             format!("{:?}", disconnect.clone()),
             format!("{:?}", disconnect)
@@ -420,11 +588,13 @@ mod tests {
             username: "x".into(),
             position: DVec3::ZERO,
             rotation: Vec2::ZERO,
+            game_mode: GameMode::Survival,
         };
         let mut b = LifecycleBundle::default();
         b.spawns.push(InboundPlayerSpawn {
             host_anchor: e,
             snapshot,
+            reason: SpawnReason::Join,
         });
         b.despawns.push(InboundPlayerDespawn { host_anchor: e });
         b.block_events.push(PlayerWillDestroyBlock {
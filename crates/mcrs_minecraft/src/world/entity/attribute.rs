@@ -1,40 +1,313 @@
-use bevy_ecs::bundle::Bundle;
+use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
+use crate::world::entity::player::HostAnchor;
+use bevy_app::{App, FixedPostUpdate, Plugin};
+use bevy_ecs::change_detection::{DetectChanges, DetectChangesMut};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::MessageWriter;
+use bevy_ecs::prelude::{Changed, Component, Query, With};
+use mcrs_engine::entity::PlayerSynchronizedEntities;
+use mcrs_engine::entity::player::Player;
+use mcrs_protocol::VarInt;
+use mcrs_protocol::entity::attribute::{AttributeModifier, AttributeOperation, AttributeSnapshot};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::Ordering;
 
-// The Bundle derive expands to a struct-update expression which clippy
-// flags as needless on an empty bundle. Wrap in a module so the allow
-// reaches the macro expansion site.
-#[allow(clippy::needless_update)]
-mod living_attributes_bundle {
-    use super::Bundle;
+/// The attributes this server knows, by network id in vanilla's
+/// `minecraft:attribute` registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AttributeKind {
+    Armor = 1,
+    ArmorToughness = 2,
+    AttackDamage = 3,
+    AttackKnockback = 4,
+    AttackSpeed = 5,
+    BlockBreakSpeed = 6,
+    KnockbackResistance = 19,
+    Luck = 20,
+    MaxHealth = 22,
+    MiningEfficiency = 23,
+    MovementSpeed = 25,
+}
+
+impl AttributeKind {
+    pub const ALL: [Self; 11] = [
+        Self::Armor,
+        Self::ArmorToughness,
+        Self::AttackDamage,
+        Self::AttackKnockback,
+        Self::AttackSpeed,
+        Self::BlockBreakSpeed,
+        Self::KnockbackResistance,
+        Self::Luck,
+        Self::MaxHealth,
+        Self::MiningEfficiency,
+        Self::MovementSpeed,
+    ];
+
+    /// The registry key, without the `minecraft:` namespace.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Armor => "armor",
+            Self::ArmorToughness => "armor_toughness",
+            Self::AttackDamage => "attack_damage",
+            Self::AttackKnockback => "attack_knockback",
+            Self::AttackSpeed => "attack_speed",
+            Self::BlockBreakSpeed => "block_break_speed",
+            Self::KnockbackResistance => "knockback_resistance",
+            Self::Luck => "luck",
+            Self::MaxHealth => "max_health",
+            Self::MiningEfficiency => "mining_efficiency",
+            Self::MovementSpeed => "movement_speed",
+        }
+    }
+
+    /// Base value of an entity that doesn't set its own.
+    pub fn default_value(self) -> f64 {
+        match self {
+            Self::Armor
+            | Self::ArmorToughness
+            | Self::AttackKnockback
+            | Self::KnockbackResistance
+            | Self::Luck
+            | Self::MiningEfficiency => 0.0,
+            Self::AttackDamage => 2.0,
+            Self::BlockBreakSpeed => 1.0,
+            Self::AttackSpeed => 4.0,
+            Self::MaxHealth => 20.0,
+            Self::MovementSpeed => 0.7,
+        }
+    }
+
+    /// The range a computed value is clamped to.
+    pub fn range(self) -> (f64, f64) {
+        match self {
+            Self::Armor => (0.0, 30.0),
+            Self::ArmorToughness => (0.0, 20.0),
+            Self::AttackDamage => (0.0, 2048.0),
+            Self::AttackKnockback => (0.0, 5.0),
+            Self::AttackSpeed => (0.0, 1024.0),
+            Self::BlockBreakSpeed => (0.0, 1024.0),
+            Self::KnockbackResistance => (0.0, 1.0),
+            Self::Luck => (-1024.0, 1024.0),
+            Self::MaxHealth => (1.0, 1024.0),
+            Self::MiningEfficiency => (0.0, 1024.0),
+            Self::MovementSpeed => (0.0, 1024.0),
+        }
+    }
+
+    fn sanitize_value(self, value: f64) -> f64 {
+        let (min, max) = self.range();
+        if value.is_nan() {
+            min
+        } else {
+            value.clamp(min, max)
+        }
+    }
+}
 
-    #[derive(Bundle, Default)]
-    pub struct LivingAttributesBundle {}
+/// One attribute of an entity: its base value and the modifiers on it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeInstance {
+    base_value: f64,
+    modifiers: Vec<AttributeModifier>,
 }
 
-pub use living_attributes_bundle::LivingAttributesBundle;
+impl AttributeInstance {
+    pub fn new(base_value: f64) -> Self {
+        Self {
+            base_value,
+            modifiers: Vec::new(),
+        }
+    }
 
-pub trait Attribute: Default {
-    fn base_value(&self) -> f32;
+    pub fn base_value(&self) -> f64 {
+        self.base_value
+    }
 
-    fn value(&self) -> f32 {
-        let base = self.base_value();
-        Self::sanitize_value(base)
+    pub fn modifiers(&self) -> &[AttributeModifier] {
+        &self.modifiers
     }
 
-    fn sanitize_value(value: f32) -> f32 {
-        value
+    /// Vanilla `AttributeInstance.calculateValue`: every `AddValue` is added
+    /// to the base, every `AddMultipliedBase` adds a multiple of that sum,
+    /// and every `AddMultipliedTotal` then scales the result.
+    pub fn value(&self, kind: AttributeKind) -> f64 {
+        let amounts = |operation: AttributeOperation| {
+            self.modifiers
+                .iter()
+                .filter(move |modifier| modifier.operation == operation)
+                .map(|modifier| modifier.amount)
+        };
+        let base = self.base_value + amounts(AttributeOperation::AddValue).sum::<f64>();
+        let mut value = base;
+        for amount in amounts(AttributeOperation::AddMultipliedBase) {
+            value += base * amount;
+        }
+        for amount in amounts(AttributeOperation::AddMultipliedTotal) {
+            value *= 1.0 + amount;
+        }
+        kind.sanitize_value(value)
+    }
+
+    fn snapshot(&self, kind: AttributeKind) -> AttributeSnapshot {
+        AttributeSnapshot {
+            attribute: VarInt(kind as i32),
+            base: self.base_value,
+            modifiers: self.modifiers.clone(),
+        }
     }
 }
 
-pub trait RangedAttribute: Attribute {
-    fn min_value() -> f32;
-    fn max_value() -> f32;
+/// An entity's attributes. Any change is sent as Update Attributes to the
+/// entity's own client and the players it is visible to; see
+/// [`AttributeSyncPlugin`].
+#[derive(Component, Debug, Clone, Default)]
+pub struct Attributes {
+    instances: BTreeMap<AttributeKind, AttributeInstance>,
+    /// Attributes changed since they were last sent.
+    dirty: BTreeSet<AttributeKind>,
+}
 
-    fn sanitize_value(value: f32) -> f32 {
-        if value.is_nan() {
-            Self::min_value()
-        } else {
-            value.clamp(Self::min_value(), Self::max_value())
+impl Attributes {
+    /// Vanilla `Player.createAttributes`, for the attributes that differ from
+    /// their defaults.
+    pub fn player() -> Self {
+        Self::default()
+            .with_base_value(AttributeKind::AttackDamage, 1.0)
+            .with_base_value(AttributeKind::MovementSpeed, 0.1)
+    }
+
+    pub fn with_base_value(mut self, kind: AttributeKind, base_value: f64) -> Self {
+        self.set_base_value(kind, base_value);
+        self
+    }
+
+    pub fn get(&self, kind: AttributeKind) -> Option<&AttributeInstance> {
+        self.instances.get(&kind)
+    }
+
+    pub fn base_value(&self, kind: AttributeKind) -> f64 {
+        self.get(kind)
+            .map_or(kind.default_value(), AttributeInstance::base_value)
+    }
+
+    /// The attribute with every modifier applied.
+    pub fn value(&self, kind: AttributeKind) -> f64 {
+        match self.get(kind) {
+            Some(instance) => instance.value(kind),
+            None => kind.sanitize_value(kind.default_value()),
+        }
+    }
+
+    pub fn set_base_value(&mut self, kind: AttributeKind, base_value: f64) {
+        let instance = self.instance_mut(kind);
+        if instance.base_value != base_value {
+            instance.base_value = base_value;
+            self.dirty.insert(kind);
+        }
+    }
+
+    /// Add `modifier`, replacing the one with the same id if there is one.
+    /// Returns the replaced modifier, or `None` if the attribute already had
+    /// this exact modifier and nothing changed.
+    pub fn add_modifier(
+        &mut self,
+        kind: AttributeKind,
+        modifier: AttributeModifier,
+    ) -> Option<AttributeModifier> {
+        let modifiers = &mut self.instance_mut(kind).modifiers;
+        let replaced = match modifiers.iter_mut().find(|old| old.id == modifier.id) {
+            Some(old) if *old == modifier => return None,
+            Some(old) => Some(std::mem::replace(old, modifier)),
+            None => {
+                modifiers.push(modifier);
+                None
+            }
+        };
+        self.dirty.insert(kind);
+        replaced
+    }
+
+    /// Remove the modifier with `id`, if the attribute has one.
+    pub fn remove_modifier(&mut self, kind: AttributeKind, id: &str) -> Option<AttributeModifier> {
+        let modifiers = &mut self.instances.get_mut(&kind)?.modifiers;
+        let index = modifiers
+            .iter()
+            .position(|modifier| modifier.id.as_str() == id)?;
+        self.dirty.insert(kind);
+        Some(modifiers.remove(index))
+    }
+
+    fn instance_mut(&mut self, kind: AttributeKind) -> &mut AttributeInstance {
+        self.instances
+            .entry(kind)
+            .or_insert_with(|| AttributeInstance::new(kind.default_value()))
+    }
+
+    /// Every attribute, as Update Attributes lists them.
+    pub fn snapshots(&self) -> Vec<AttributeSnapshot> {
+        self.instances
+            .iter()
+            .map(|(&kind, instance)| instance.snapshot(kind))
+            .collect()
+    }
+
+    /// The attributes changed since the last call.
+    fn take_dirty_snapshots(&mut self) -> Vec<AttributeSnapshot> {
+        let dirty = std::mem::take(&mut self.dirty);
+        dirty
+            .into_iter()
+            .filter_map(|kind| Some(self.instances.get(&kind)?.snapshot(kind)))
+            .collect()
+    }
+}
+
+pub struct AttributeSyncPlugin;
+
+impl Plugin for AttributeSyncPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedPostUpdate, sync_attributes);
+    }
+}
+
+/// Send Update Attributes for the attributes that changed to the entity's
+/// own client and every player it is visible to. A newly added
+/// [`Attributes`] is sent whole; players that see the entity later get it
+/// with the entity's spawn.
+fn sync_attributes(
+    mut entities: Query<(Entity, &mut Attributes, Option<&HostAnchor>), Changed<Attributes>>,
+    viewers: Query<(&HostAnchor, &PlayerSynchronizedEntities), With<Player>>,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
+) {
+    for (entity, mut attributes, own_anchor) in &mut entities {
+        let added = attributes.is_added();
+        let attributes = attributes.bypass_change_detection();
+        let dirty = attributes.take_dirty_snapshots();
+        let snapshots = if added { attributes.snapshots() } else { dirty };
+        if snapshots.is_empty() {
+            continue;
+        }
+
+        let entity_id = entity.index_u32() as i32;
+        let targets = own_anchor.into_iter().chain(
+            viewers
+                .iter()
+                .filter(|(_, synced_entities)| synced_entities.contains(&entity))
+                .map(|(host_anchor, _)| host_anchor),
+        );
+        let mut sent = 0;
+        for host_anchor in targets {
+            packet_writer.write(OutboundPlayerPacket {
+                target: PacketTarget::SinglePlayer(host_anchor.0),
+                priority: PacketPriority::Normal,
+                data: PacketPayload::UpdateAttributes {
+                    entity_id,
+                    attributes: snapshots.clone(),
+                },
+            });
+            sent += 1;
         }
+        mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+            .fetch_add(sent, Ordering::Relaxed);
     }
 }
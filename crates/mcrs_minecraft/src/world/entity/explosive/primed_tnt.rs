@@ -1,12 +1,17 @@
+use crate::client_info::ServerViewConfig;
+use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
 use crate::world::entity::explosive::ExplosiveBundle;
+use crate::world::entity::player::HostAnchor;
 use crate::world::entity::{EntityUuid, MinecraftEntity, MinecraftEntityType};
 use crate::world::explosion::{Explosion, ExplosionRadius};
 use bevy_app::{App, FixedUpdate, Plugin};
 use bevy_ecs::bundle::Bundle;
 use bevy_ecs::component::Component;
 use bevy_ecs::entity::Entity;
-use bevy_ecs::prelude::{Commands, ContainsEntity, On, Query};
+use bevy_ecs::message::MessageWriter;
+use bevy_ecs::prelude::{Commands, ContainsEntity, On, Query, Res};
 use bevy_ecs::query::{QueryData, With, Without};
+use bevy_math::DVec3;
 use derive_more::{Deref, DerefMut};
 use mcrs_engine::entity::EntityNetworkAddEvent;
 use mcrs_engine::entity::physics::Transform;
@@ -15,9 +20,9 @@ use mcrs_engine::entity::player::chunk_view::PlayerChunkObserver;
 use mcrs_engine::entity::player::reposition::Reposition;
 use mcrs_engine::world::dimension::InDimension;
 use mcrs_network::ServerSideConnection;
-use mcrs_protocol::packets::game::clientbound::ClientboundAddEntity;
+use mcrs_protocol::entity::EntityMetadata;
 use mcrs_protocol::uuid::Uuid;
-use mcrs_protocol::{ByteAngle, VarInt, WritePacket};
+use std::sync::atomic::Ordering;
 
 pub struct PrimedTntPlugin;
 
@@ -104,11 +109,18 @@ struct PrimedTntQuery {
     uuid: &'static EntityUuid,
 }
 
+/// Count fuses down, only for TNT within simulation distance of a player.
 fn update_fuse_durations(
-    mut query: Query<(Entity, &mut Fuse), (With<PrimedTnt>, Without<Explosion>)>,
+    mut query: Query<(Entity, &mut Fuse, &Transform), (With<PrimedTnt>, Without<Explosion>)>,
+    players: Query<&Transform, With<Player>>,
+    view_config: Res<ServerViewConfig>,
     mut commands: Commands,
 ) {
-    query.iter_mut().for_each(|(e, mut fuse)| {
+    query.iter_mut().for_each(|(e, mut fuse, transform)| {
+        let players = players.iter().map(|player| player.translation);
+        if !view_config.in_simulation_distance(transform.translation, players) {
+            return;
+        }
         let f = **fuse;
         if f > 0 {
             **fuse -= 1;
@@ -123,25 +135,29 @@ fn update_fuse_durations(
 fn network_add(
     event: On<EntityNetworkAddEvent>,
     tnt: Query<(Entity, &EntityUuid, &Transform), With<PrimedTnt>>,
-    mut player: Query<(&mut ServerSideConnection, &Reposition), With<Player>>,
+    viewer: Query<(&Reposition, &HostAnchor), With<Player>>,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
 ) {
     let Ok((entity, uuid, transform)) = tnt.get(event.entity) else {
         return;
     };
-    let Ok((mut connection, reposition)) = player.get_mut(event.player) else {
+    let Ok((reposition, host_anchor)) = viewer.get(event.player) else {
         return;
     };
 
-    let pkt = ClientboundAddEntity {
-        id: VarInt(entity.index_u32() as i32),
-        uuid: uuid.0,
-        kind: VarInt(MinecraftEntityType::PrimedTnt as i32),
-        pos: reposition.convert_dvec3(transform.translation),
-        velocity: VarInt(0),
-        yaw: ByteAngle::from_degrees(transform.rotation.y),
-        pitch: ByteAngle::from_degrees(transform.rotation.x),
-        head_yaw: ByteAngle::from_degrees(transform.rotation.y),
-        data: VarInt(0),
-    };
-    connection.write_packet(&pkt);
+    packet_writer.write(OutboundPlayerPacket {
+        target: PacketTarget::SinglePlayer(host_anchor.0),
+        priority: PacketPriority::Normal,
+        data: PacketPayload::PlayerEnteredView {
+            entity_id: entity.index_u32() as i32,
+            uuid: uuid.0,
+            kind: MinecraftEntityType::PrimedTnt as i32,
+            position: reposition.convert_dvec3(transform.translation),
+            velocity: DVec3::ZERO,
+            yaw: transform.rotation.y,
+            pitch: transform.rotation.x,
+            metadata: EntityMetadata::new(),
+        },
+    });
+    mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL.fetch_add(1, Ordering::Relaxed);
 }
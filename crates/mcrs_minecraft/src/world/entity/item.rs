@@ -0,0 +1,304 @@
+use crate::client_info::ServerViewConfig;
+use crate::world::block::Block;
+use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
+use crate::world::entity::player::HostAnchor;
+use crate::world::entity::player::movement::OnGround;
+use crate::world::entity::velocity::UpdateInterval;
+use crate::world::entity::{EntityUuid, MinecraftEntity, MinecraftEntityType};
+use crate::world::item::ItemStack;
+use bevy_app::{App, FixedPostUpdate, FixedUpdate, Plugin};
+use bevy_ecs::bundle::Bundle;
+use bevy_ecs::change_detection::DetectChangesMut;
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::MessageWriter;
+use bevy_ecs::prelude::{Commands, ContainsEntity, On, Query, Res};
+use bevy_ecs::query::{With, Without};
+use bevy_math::DVec3;
+use mcrs_engine::entity::physics::{Transform, Velocity};
+use mcrs_engine::entity::player::Player;
+use mcrs_engine::entity::player::reposition::Reposition;
+use mcrs_engine::entity::{Despawned, EntityNetworkAddEvent};
+use mcrs_engine::world::block::BlockPos;
+use mcrs_engine::world::chunk::ChunkIndex;
+use mcrs_engine::world::dimension::InDimension;
+use mcrs_minecraft_block::palette::BlockPalette;
+use mcrs_protocol::entity::EntityMetadata;
+use mcrs_protocol::uuid::Uuid;
+use mcrs_protocol::{BlockStateId, Slot};
+use rand::RngExt;
+use std::sync::atomic::Ordering;
+
+/// Metadata index of the stack an item entity shows (vanilla `DATA_ITEM`).
+pub const ITEM_ENTITY_STACK_INDEX: u8 = 8;
+
+/// Ticks an item entity lasts before it despawns (vanilla
+/// `ItemEntity.LIFETIME`, five minutes).
+pub const LIFETIME: u32 = 6000;
+
+/// Half the height of an item entity; drops spawn with their center at the
+/// middle of the broken block.
+const HALF_HEIGHT: f64 = 0.125;
+const HEIGHT: f64 = 2.0 * HALF_HEIGHT;
+
+/// Pulled down by this much velocity every tick, in blocks per tick.
+const GRAVITY: f64 = 0.04;
+/// The share of its velocity an item keeps each tick.
+const DRAG: f64 = 0.98;
+/// The slipperiness of the block under a sliding item. Every block here has
+/// vanilla's default; ice and the like would have their own.
+const BLOCK_FRICTION: f64 = 0.6;
+/// An item on the ground slower than this (squared, horizontally) rests
+/// until something moves it.
+const RESTING_SPEED_SQUARED: f64 = 1.0e-5;
+
+pub struct ItemEntityPlugin;
+
+impl Plugin for ItemEntityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, tick_item_physics);
+        app.add_systems(FixedPostUpdate, despawn_expired_items);
+        app.add_observer(network_add);
+    }
+}
+
+#[derive(Bundle)]
+pub struct ItemEntityBundle {
+    pub dimension: InDimension,
+    pub transform: Transform,
+    pub uuid: EntityUuid,
+    pub stack: ItemStack,
+    pub velocity: Velocity,
+    update_interval: UpdateInterval,
+    on_ground: OnGround,
+    age: ItemAge,
+    marker: ItemEntity,
+    mc_entity_marker: MinecraftEntity,
+}
+
+impl ItemEntityBundle {
+    pub fn new(dimension: InDimension, transform: Transform, stack: ItemStack) -> Self {
+        Self {
+            dimension,
+            transform,
+            uuid: EntityUuid(Uuid::new_v4()),
+            stack,
+            velocity: Velocity::default(),
+            update_interval: UpdateInterval::ITEM,
+            on_ground: OnGround(false),
+            age: ItemAge::default(),
+            marker: ItemEntity,
+            mc_entity_marker: MinecraftEntity,
+        }
+    }
+
+    pub fn with_velocity(mut self, velocity: DVec3) -> Self {
+        self.velocity = Velocity(velocity);
+        self
+    }
+
+    /// A stack popped out of the block at `block_pos`, like vanilla
+    /// `Block.popResource`: around the block center, up to a quarter block
+    /// off on each axis, tossed up and to a random side.
+    pub fn popped_from(dimension: InDimension, block_pos: BlockPos, stack: ItemStack) -> Self {
+        let mut rng = rand::rng();
+        let mut offset = || rng.random_range(-0.25..=0.25);
+        let position = block_pos.as_dvec3()
+            + DVec3::new(0.5 + offset(), 0.5 - HALF_HEIGHT + offset(), 0.5 + offset());
+        let velocity = DVec3::new(
+            rng.random_range(-0.1..0.1),
+            0.2,
+            rng.random_range(-0.1..0.1),
+        );
+        Self::new(dimension, Transform::from_translation(position), stack).with_velocity(velocity)
+    }
+}
+
+#[derive(Component, Debug, Default)]
+#[component(storage = "SparseSet")]
+pub struct ItemEntity;
+
+/// Ticks an item entity has been simulated for; it despawns at
+/// [`LIFETIME`]. Like vanilla, the age only counts up within simulation
+/// distance of a player.
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ItemAge(pub u32);
+
+/// Move item entities within simulation distance of a player by their
+/// velocity, like vanilla `ItemEntity.tick` out of water and lava: gravity,
+/// then the move, then drag, with ground friction while sliding. Collisions
+/// only check the column the item's center is in, not its full width. An
+/// unloaded chunk counts as solid. An item that reaches its [`LIFETIME`]
+/// is despawned instead.
+#[allow(clippy::type_complexity)]
+fn tick_item_physics(
+    mut items: Query<
+        (
+            Entity,
+            &InDimension,
+            &mut Transform,
+            &mut Velocity,
+            &mut OnGround,
+            &mut ItemAge,
+        ),
+        (With<ItemEntity>, Without<Despawned>),
+    >,
+    players: Query<&Transform, (With<Player>, Without<ItemEntity>)>,
+    view_config: Res<ServerViewConfig>,
+    dimensions: Query<&ChunkIndex>,
+    chunks: Query<&BlockPalette>,
+    mut commands: Commands,
+) {
+    for (entity, dimension, mut transform, mut velocity, mut on_ground, mut age) in &mut items {
+        let players = players.iter().map(|player| player.translation);
+        if !view_config.in_simulation_distance(transform.translation, players) {
+            continue;
+        }
+        age.0 += 1;
+        if age.0 >= LIFETIME {
+            commands.entity(entity).insert(Despawned);
+            continue;
+        }
+        let Ok(chunk_index) = dimensions.get(dimension.entity()) else {
+            continue;
+        };
+        let solid = |pos: BlockPos| {
+            chunk_index
+                .get(pos)
+                .and_then(|chunk| chunks.get(chunk).ok())
+                .is_none_or(|palette| collides(palette.get(pos)))
+        };
+
+        let position = transform.translation;
+        let resting = on_ground.0
+            && velocity.x * velocity.x + velocity.z * velocity.z <= RESTING_SPEED_SQUARED
+            && solid(BlockPos::new(
+                position.x.floor() as i32,
+                position.y.floor() as i32 - 1,
+                position.z.floor() as i32,
+            ));
+        if resting {
+            continue;
+        }
+        let (position, new_velocity, landed) = step(position, velocity.0, solid);
+        if transform.translation != position {
+            transform.translation = position;
+        }
+        velocity.set_if_neq(Velocity(new_velocity));
+        on_ground.set_if_neq(OnGround(landed));
+    }
+}
+
+/// Despawn the items [`tick_item_physics`] marked, once the engine has
+/// taken them out of their chunk. Viewers are told they left on the next
+/// entity sync.
+fn despawn_expired_items(
+    items: Query<Entity, (With<ItemEntity>, With<Despawned>)>,
+    mut commands: Commands,
+) {
+    for item in &items {
+        commands.entity(item).despawn();
+    }
+}
+
+/// One tick of an item falling from `position` at `velocity`. Returns the
+/// new position and velocity, and whether the item landed.
+fn step(
+    mut position: DVec3,
+    mut velocity: DVec3,
+    solid: impl Fn(BlockPos) -> bool,
+) -> (DVec3, DVec3, bool) {
+    velocity.y -= GRAVITY;
+
+    // Sideways, one axis at a time; running into a wall stops that axis.
+    let x = position.x + velocity.x;
+    if solid(BlockPos::from(DVec3::new(x, position.y, position.z))) {
+        velocity.x = 0.0;
+    } else {
+        position.x = x;
+    }
+    let z = position.z + velocity.z;
+    if solid(BlockPos::from(DVec3::new(position.x, position.y, z))) {
+        velocity.z = 0.0;
+    } else {
+        position.z = z;
+    }
+
+    // Then up or down, stopping at the first block crossed.
+    let (x, z) = (position.x.floor() as i32, position.z.floor() as i32);
+    let y = position.y + velocity.y;
+    let mut on_ground = false;
+    if velocity.y < 0.0 {
+        let floor = (y.floor() as i32..position.y.floor() as i32)
+            .rev()
+            .find(|&block_y| solid(BlockPos::new(x, block_y, z)));
+        match floor {
+            Some(block_y) => {
+                position.y = (block_y + 1) as f64;
+                velocity.y = 0.0;
+                on_ground = true;
+            }
+            None => position.y = y,
+        }
+    } else {
+        let head = position.y + HEIGHT;
+        let ceiling = (head.floor() as i32 + 1..=(y + HEIGHT).floor() as i32)
+            .find(|&block_y| solid(BlockPos::new(x, block_y, z)));
+        match ceiling {
+            Some(block_y) => {
+                position.y = block_y as f64 - HEIGHT;
+                velocity.y = 0.0;
+            }
+            None => position.y = y,
+        }
+    }
+
+    let friction = if on_ground {
+        BLOCK_FRICTION * DRAG
+    } else {
+        DRAG
+    };
+    velocity *= DVec3::new(friction, DRAG, friction);
+    (position, velocity, on_ground)
+}
+
+/// Whether an item stops at the block; states of blocks not known here
+/// count as solid unless they are air.
+fn collides(state: BlockStateId) -> bool {
+    match <&Block>::try_from(state) {
+        Ok(block) => block.has_collision(),
+        Err(()) => !state.is_air(),
+    }
+}
+
+fn network_add(
+    event: On<EntityNetworkAddEvent>,
+    items: Query<(Entity, &EntityUuid, &Transform, &ItemStack, &Velocity), With<ItemEntity>>,
+    viewer: Query<(&Reposition, &HostAnchor), With<Player>>,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
+) {
+    let Ok((entity, uuid, transform, stack, velocity)) = items.get(event.entity) else {
+        return;
+    };
+    let Ok((reposition, host_anchor)) = viewer.get(event.player) else {
+        return;
+    };
+
+    // The client renders an item entity with an empty stack until it gets
+    // the metadata, so it is bundled with the spawn.
+    packet_writer.write(OutboundPlayerPacket {
+        target: PacketTarget::SinglePlayer(host_anchor.0),
+        priority: PacketPriority::Normal,
+        data: PacketPayload::PlayerEnteredView {
+            entity_id: entity.index_u32() as i32,
+            uuid: uuid.0,
+            kind: MinecraftEntityType::Item as i32,
+            position: reposition.convert_dvec3(transform.translation),
+            velocity: velocity.0,
+            yaw: 0.0,
+            pitch: 0.0,
+            metadata: EntityMetadata::new().with_slot(ITEM_ENTITY_STACK_INDEX, Slot::from(*stack)),
+        },
+    });
+    mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL.fetch_add(1, Ordering::Relaxed);
+}
@@ -1,33 +1,39 @@
-use crate::world::bus::{InboundPlayerPacket, OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
+use crate::world::bus::InboundPlayerPacket;
+use crate::world::entity::attribute::AttributeSyncPlugin;
 use crate::world::entity::explosive::primed_tnt::PrimedTntPlugin;
+use crate::world::entity::item::ItemEntityPlugin;
 use crate::world::entity::player::{HostAnchor, PlayerPlugin};
+use crate::world::entity::velocity::VelocitySyncPlugin;
+use crate::world::entity::visibility::EntityVisibilityPlugin;
 use bevy_app::{App, FixedPreUpdate, Plugin};
 use bevy_ecs::bundle::Bundle;
 use bevy_ecs::component::Component;
 use bevy_ecs::entity::Entity;
-use bevy_ecs::message::{MessageReader, MessageWriter};
-use bevy_ecs::prelude::{Commands, ContainsEntity, On};
-use bevy_ecs::query::With;
+use bevy_ecs::message::MessageReader;
+use bevy_ecs::prelude::{Commands, ContainsEntity};
 use bevy_ecs::system::Query;
-use bevy_math::DVec3;
 use derive_more::{Deref, DerefMut};
-use mcrs_engine::entity::physics::{OldTransform, Transform};
-use mcrs_engine::entity::{EntityNetworkSyncEvent, EntityPlugin};
+use mcrs_engine::entity::physics::Transform;
+use mcrs_engine::entity::EntityPlugin;
 use mcrs_engine::world::dimension::InDimension;
 use mcrs_network::event::ReceivedPacketEvent;
 use mcrs_protocol::uuid::Uuid;
-use mcrs_protocol::{Look, VarInt};
+use mcrs_protocol::VarInt;
 use std::sync::atomic::AtomicI32;
 use std::sync::atomic::Ordering::Relaxed;
 
 pub mod attribute;
 pub mod explosive;
+pub mod item;
 mod meta;
 pub mod player;
+pub mod velocity;
+pub mod visibility;
 
 pub struct MinecraftEntityPlugin;
 
 pub enum MinecraftEntityType {
+    Item = 71,
     PrimedTnt = 132,
     Player = 155,
 }
@@ -37,7 +43,10 @@ impl Plugin for MinecraftEntityPlugin {
         app.add_plugins(EntityPlugin);
         app.add_plugins(PlayerPlugin);
         app.add_plugins(PrimedTntPlugin);
-        app.add_observer(entity_pos_sync);
+        app.add_plugins(ItemEntityPlugin);
+        app.add_plugins(EntityVisibilityPlugin);
+        app.add_plugins(AttributeSyncPlugin);
+        app.add_plugins(VelocitySyncPlugin);
         app.add_systems(FixedPreUpdate, dispatch_inbound_to_dim);
     }
 }
@@ -143,41 +152,3 @@ fn dispatch_inbound_to_dim(
         });
     }
 }
-
-/// Per-event observer that fans entity-position updates out to a single
-/// player via the cross-`World` bus. The downstream bridge layer consumes
-/// the typed `PacketPayload::EntityPosSync` variant and translates it to
-/// the appropriate wire packet (delta-vs-old, head-rotation, etc.) using
-/// per-player wire state it owns.
-///
-/// Observer system params include `MessageWriter<OutboundPlayerPacket>`,
-/// matching the precedent set by other observers on `ReceivedPacketEvent`
-/// in `entity/player/movement.rs` and `entity/player/player_action.rs`.
-pub fn entity_pos_sync(
-    event: On<EntityNetworkSyncEvent>,
-    // The `With<OldTransform>` filter encodes the gating invariant in
-    // the type system: this observer must only fire on entities that
-    // carry both transform components. The actual delta computation
-    // moves to the bridge tier where per-player wire state lives.
-    entity_data: Query<&Transform, With<OldTransform>>,
-    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
-) {
-    let Ok(transform) = entity_data.get(event.entity) else {
-        return;
-    };
-    packet_writer.write(OutboundPlayerPacket {
-        target: PacketTarget::SinglePlayer(event.player),
-        priority: PacketPriority::Normal,
-        data: PacketPayload::EntityPosSync {
-            entity_id: event.entity.index_u32() as i32,
-            position: transform.translation,
-            velocity: DVec3::ZERO,
-            look: Look {
-                yaw: transform.rotation.y,
-                pitch: transform.rotation.x,
-            },
-            on_ground: true,
-        },
-    });
-    mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL.fetch_add(1, Relaxed);
-}
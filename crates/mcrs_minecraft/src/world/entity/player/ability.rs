@@ -1,7 +1,17 @@
+use crate::login::GameProfile;
+use crate::world::bus::{
+    OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget, PlayerInfoEntry,
+};
+use crate::world::entity::player::{HostAnchor, ResyncPlayer};
 use bevy_ecs::bundle::Bundle;
-use bevy_ecs::prelude::Component;
+use bevy_ecs::message::MessageWriter;
+use bevy_ecs::prelude::{Added, Changed, Commands, Component, Entity, Has, Or, Query, Ref, With};
 use derive_more::{Deref, DerefMut};
-use mcrs_protocol::GameMode;
+use mcrs_engine::entity::player::Player;
+use mcrs_protocol::entity::player::PlayerAbilityFlags;
+use mcrs_protocol::packets::game::clientbound::{ClientboundGameEvent, ClientboundPlayerAbilities};
+use mcrs_protocol::{GameEventKind, GameMode};
+use std::sync::atomic::Ordering;
 
 #[derive(Component, Debug, Clone, Copy, Deref, DerefMut)]
 pub struct PlayerGameMode(pub GameMode);
@@ -126,3 +136,121 @@ pub fn update_abilities_for_game_mode(
     }
     **may_build = !game_mode.is_block_placing_restricted();
 }
+
+/// Recompute the abilities of players whose game mode changed and tell the
+/// clients about the new mode. A joining player's mode already travels in the
+/// Login packet, so only later changes update the player list and send the
+/// changed player a Game Event.
+pub fn apply_game_mode(
+    mut players: Query<
+        (
+            Entity,
+            Ref<PlayerGameMode>,
+            &GameProfile,
+            &HostAnchor,
+            &mut Invulnerable,
+            &mut Flying,
+            &mut MayFly,
+            &mut MayBuild,
+        ),
+        (With<Player>, Changed<PlayerGameMode>),
+    >,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
+    mut commands: Commands,
+) {
+    for (
+        entity,
+        game_mode,
+        profile,
+        host_anchor,
+        mut invulnerable,
+        mut flying,
+        mut may_fly,
+        mut may_build,
+    ) in players.iter_mut()
+    {
+        update_abilities_for_game_mode(
+            game_mode.0,
+            &mut invulnerable,
+            &mut flying,
+            &mut may_fly,
+            &mut may_build,
+        );
+        if game_mode.0 == GameMode::Creative {
+            commands.entity(entity).insert(InstantBuild);
+        } else {
+            commands.entity(entity).remove::<InstantBuild>();
+        }
+        if game_mode.is_added() {
+            continue;
+        }
+        packet_writer.write(OutboundPlayerPacket {
+            target: PacketTarget::AllPlayers,
+            priority: PacketPriority::High,
+            data: PacketPayload::PlayerInfoUpdate {
+                entries: vec![PlayerInfoEntry {
+                    player_uuid: profile.id,
+                    game_mode: game_mode.0,
+                }],
+            },
+        });
+        packet_writer.write(OutboundPlayerPacket {
+            target: PacketTarget::SinglePlayer(host_anchor.0),
+            priority: PacketPriority::High,
+            data: PacketPayload::GameEvent(ClientboundGameEvent {
+                game_event: GameEventKind::ChangeGameMode(game_mode.0),
+            }),
+        });
+        mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+            .fetch_add(2, Ordering::Relaxed);
+    }
+}
+
+/// Send Player Abilities when a player enters the dimension, is resynced
+/// after reconfiguration, or any of the flags or speeds change.
+pub fn send_player_abilities(
+    players: Query<
+        (
+            &HostAnchor,
+            &Invulnerable,
+            &Flying,
+            &MayFly,
+            Has<InstantBuild>,
+            &FlySpeed,
+            &WalkSpeed,
+        ),
+        (
+            With<Player>,
+            Or<(
+                Changed<PlayerGameMode>,
+                Changed<Invulnerable>,
+                Changed<Flying>,
+                Changed<MayFly>,
+                Changed<FlySpeed>,
+                Changed<WalkSpeed>,
+                Added<ResyncPlayer>,
+            )>,
+        ),
+    >,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
+) {
+    for (host_anchor, invulnerable, flying, may_fly, instabuild, fly_speed, walk_speed) in
+        players.iter()
+    {
+        packet_writer.write(OutboundPlayerPacket {
+            target: PacketTarget::SinglePlayer(host_anchor.0),
+            priority: PacketPriority::High,
+            data: PacketPayload::PlayerAbilities(ClientboundPlayerAbilities {
+                flags: PlayerAbilityFlags::new()
+                    .with_invulnerable(invulnerable.0)
+                    .with_flying(flying.0)
+                    .with_may_fly(may_fly.0)
+                    .with_instabuild(instabuild),
+                flying_speed: fly_speed.0,
+                walking_speed: walk_speed.0,
+            }),
+        });
+        mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+            .fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -1,136 +1,206 @@
+use crate::chat_session::{
+    ChatConfig, ChatSession, ChatSigning, LastSeenError, LastSeenMessagesValidator, now_millis,
+};
 use crate::login::GameProfile;
 use crate::world::bus::{
-    OutboundPlayerPacket, OutboundPlayerTransferRequest, PacketPayload, PacketPriority,
-    PacketTarget, PlayerTransferSnapshot,
+    OutboundPlayerDisconnect, OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget,
 };
-use crate::world::entity::player::{DisconnectReason, HostAnchor};
+use crate::world::entity::player::HostAnchor;
 use bevy_app::{App, Plugin};
 use bevy_ecs::message::MessageWriter;
 use bevy_ecs::prelude::*;
-use bevy_math::{DVec3, Vec2};
-use mcrs_engine::entity::physics::Transform;
 use mcrs_network::event::ReceivedPacketEvent;
-use mcrs_protocol::packets::game::serverbound::{ServerboundChat, ServerboundChatCommand};
+use mcrs_protocol::packets::game::serverbound::{ServerboundChat, ServerboundChatAck};
 use mcrs_protocol::setting::ChatMode;
 use mcrs_protocol::text::{Color, IntoText};
+use mcrs_protocol::uuid::Uuid;
 use mcrs_protocol::Text;
-use tracing::info;
+use tracing::{info, warn};
 
 pub struct ChatPlugin;
 
 impl Plugin for ChatPlugin {
     fn build(&self, app: &mut App) {
         app.add_observer(handle_chat);
-        app.add_observer(handle_command);
+        app.add_observer(handle_chat_ack);
     }
 }
 
-/// Primitive debug slash-command handler. The vanilla client sends
-/// `ServerboundChatCommand` for any `/...` input (no command graph is
-/// required for the client to transmit it). Commands run inside the
-/// dimension sub-app, so any client-facing effect must route through the
-/// bridge (`OutboundPlayerPacket`) rather than touching `ServerSideConnection`
-/// directly, which is host-resident.
-fn handle_command(
-    event: On<ReceivedPacketEvent>,
-    mut sender_query: Query<(&HostAnchor, &mut Transform, &GameProfile)>,
-    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
-    mut transfer_writer: MessageWriter<OutboundPlayerTransferRequest>,
-) {
-    let Some(pkt) = event.decode::<ServerboundChatCommand>() else {
-        return;
-    };
-    let command: &str = pkt.command.0;
-    info!("command from {:?}: /{}", event.entity, command);
-    let mut parts = command.split_whitespace();
-    match parts.next() {
-        Some("tp") => {
-            let coords: Vec<f64> = parts.filter_map(|s| s.parse::<f64>().ok()).collect();
-            if coords.len() != 3 {
-                return;
-            }
-            let pos = DVec3::new(coords[0], coords[1], coords[2]);
-            let Ok((host_anchor, mut transform, _)) = sender_query.get_mut(event.entity) else {
-                return;
-            };
-            let host = host_anchor.0;
-            transform.translation = pos;
-            packet_writer.write(OutboundPlayerPacket {
-                target: PacketTarget::SinglePlayer(host),
-                priority: PacketPriority::Critical,
-                data: PacketPayload::PlayerPosition {
-                    teleport_id: 1,
-                    position: pos,
-                },
-            });
-            packet_writer.write(OutboundPlayerPacket {
-                target: PacketTarget::SinglePlayer(host),
-                priority: PacketPriority::Normal,
-                data: PacketPayload::SystemChat {
-                    content: format!(
-                        "Teleported to {:.1}, {:.1}, {:.1}",
-                        pos.x, pos.y, pos.z
-                    )
-                    .into_text(),
-                    overlay: false,
-                },
-            });
-            info!("teleported {:?} to {:?}", event.entity, pos);
+/// Per-player secure chat state. The session is mirrored from the host
+/// connection every tick; the rest starts over when the player changes
+/// dimension, which is harmless while no signed messages are sent out.
+#[derive(Component, Clone, Debug, Default)]
+pub struct SignedChatState {
+    session: Option<ChatSession>,
+    pub last_seen: LastSeenMessagesValidator,
+    /// Index the next message has in the session's chain, vanilla
+    /// `SignedMessageLink.index`; a message signed for another index is
+    /// refused.
+    next_index: i32,
+    /// Timestamp of the last accepted message; signed messages must not go
+    /// back in time.
+    last_timestamp: u64,
+}
+
+/// Why a message was refused under [`ChatSigning::RequireSigned`].
+#[derive(Debug)]
+enum ChatRejection {
+    LastSeen(LastSeenError),
+    MissingProfileKey,
+    ExpiredProfileKey,
+    Unsigned,
+    OutOfOrder,
+    InvalidSignature,
+}
+
+impl ChatRejection {
+    fn translation_key(&self) -> &'static str {
+        match self {
+            Self::LastSeen(_) => "multiplayer.disconnect.chat_validation_failed",
+            Self::MissingProfileKey => "chat.disabled.missingProfileKey",
+            Self::ExpiredProfileKey => "chat.disabled.expiredProfileKey",
+            Self::Unsigned => "multiplayer.disconnect.unsigned_chat",
+            Self::OutOfOrder => "multiplayer.disconnect.out_of_order_chat",
+            Self::InvalidSignature => "chat.disabled.invalid_signature",
         }
-        Some("dim") => {
-            let Some(raw) = parts.next() else {
-                return;
-            };
-            let dim_name = match raw {
-                "nether" | "the_nether" => "minecraft:the_nether".to_string(),
-                "overworld" | "over" => "minecraft:overworld".to_string(),
-                "end" | "the_end" => "minecraft:the_end".to_string(),
-                other if other.contains(':') => other.to_string(),
-                other => format!("minecraft:{other}"),
-            };
-            let Ok((host_anchor, _transform, profile)) = sender_query.get(event.entity) else {
-                return;
-            };
-            let snapshot = PlayerTransferSnapshot {
-                uuid: profile.id,
-                username: profile.username.clone(),
-                position: DVec3::new(0.0, 100.0, 0.0),
-                rotation: Vec2::ZERO,
-            };
-            info!("dim transfer {:?} -> {}", event.entity, dim_name);
-            transfer_writer.write(OutboundPlayerTransferRequest {
-                host_anchor: host_anchor.0,
-                dim_name,
-                snapshot,
-            });
+    }
+
+    /// A client without a usable key is only told why its chat is disabled;
+    /// one sending something its key can not have produced is disconnected.
+    fn disconnects(&self) -> bool {
+        !matches!(self, Self::MissingProfileKey | Self::ExpiredProfileKey)
+    }
+}
+
+impl SignedChatState {
+    pub fn session(&self) -> Option<&ChatSession> {
+        self.session.as_ref()
+    }
+
+    /// Take over the host connection's session. A new session starts its
+    /// message chain over, like vanilla `resetPlayerChatState`.
+    pub fn set_session(&mut self, session: Option<ChatSession>) {
+        self.session = session;
+        self.next_index = 0;
+    }
+
+    /// Check a chat message from `sender` against the session, like vanilla
+    /// `SignedMessageChain.Decoder`, and move the chain on.
+    fn accept(
+        &mut self,
+        sender: Uuid,
+        pkt: &ServerboundChat,
+        now_millis: i64,
+    ) -> Result<(), ChatRejection> {
+        let last_seen = self
+            .last_seen
+            .apply_update(&pkt.last_seen_messages)
+            .map_err(ChatRejection::LastSeen)?;
+        let Some(session) = &self.session else {
+            return Err(ChatRejection::MissingProfileKey);
+        };
+        if session.is_expired_at(now_millis) {
+            return Err(ChatRejection::ExpiredProfileKey);
         }
-        _ => {}
+        let Some(signature) = pkt.signature else {
+            return Err(ChatRejection::Unsigned);
+        };
+        if pkt.timestamp < self.last_timestamp {
+            return Err(ChatRejection::OutOfOrder);
+        }
+        let payload =
+            signed_message_payload(sender, session.session_id, self.next_index, pkt, &last_seen);
+        if !session.verify(&payload, signature) {
+            return Err(ChatRejection::InvalidSignature);
+        }
+        self.last_timestamp = pkt.timestamp;
+        self.next_index += 1;
+        Ok(())
+    }
+}
+
+/// What a chat message's signature covers, vanilla
+/// `PlayerChatMessage.updateSignature`: a version, the message's link in the
+/// sender's chain, then its body with the timestamp in whole seconds and the
+/// signatures of the messages it acknowledges. Everything is big-endian.
+fn signed_message_payload(
+    sender: Uuid,
+    session_id: Uuid,
+    index: i32,
+    pkt: &ServerboundChat,
+    last_seen: &[[u8; 256]],
+) -> Vec<u8> {
+    let content = pkt.message.0.as_bytes();
+    let mut payload = Vec::with_capacity(68 + content.len() + 256 * last_seen.len());
+    payload.extend_from_slice(&1i32.to_be_bytes());
+    payload.extend_from_slice(sender.as_bytes());
+    payload.extend_from_slice(session_id.as_bytes());
+    payload.extend_from_slice(&index.to_be_bytes());
+    payload.extend_from_slice(&pkt.salt.to_be_bytes());
+    payload.extend_from_slice(&(pkt.timestamp / 1000).to_be_bytes());
+    payload.extend_from_slice(&(content.len() as i32).to_be_bytes());
+    payload.extend_from_slice(content);
+    payload.extend_from_slice(&(last_seen.len() as i32).to_be_bytes());
+    for signature in last_seen {
+        payload.extend_from_slice(signature);
     }
+    payload
 }
 
 fn handle_chat(
     event: On<ReceivedPacketEvent>,
-    sender_query: Query<(&GameProfile, Option<&ChatMode>, &HostAnchor)>,
+    config: Res<ChatConfig>,
+    mut sender_query: Query<(
+        &GameProfile,
+        Option<&ChatMode>,
+        &HostAnchor,
+        Option<&mut SignedChatState>,
+    )>,
     mut packet_writer: MessageWriter<OutboundPlayerPacket>,
-    mut commands: Commands,
+    mut disconnects: MessageWriter<OutboundPlayerDisconnect>,
 ) {
     let Some(pkt) = event.decode::<ServerboundChat>() else {
         return;
     };
-    let Ok((profile, chat_mode, host_anchor)) = sender_query.get(event.entity) else {
+    let Ok((profile, chat_mode, host_anchor, signed_chat)) = sender_query.get_mut(event.entity)
+    else {
         return;
     };
     let msg = pkt.message;
     if is_chat_message_illegal(&msg) {
-        commands
-            .entity(event.entity)
-            .insert(DisconnectReason(Text::translate(
-                "multiplayer.disconnect.illegal_characters",
-                vec![],
-            )));
+        disconnects.write(OutboundPlayerDisconnect {
+            host_anchor: host_anchor.0,
+            reason: Text::translate("multiplayer.disconnect.illegal_characters", vec![]),
+        });
         return;
     }
 
+    if config.signing == ChatSigning::RequireSigned {
+        let Some(mut signed_chat) = signed_chat else {
+            return;
+        };
+        if let Err(rejection) = signed_chat.accept(profile.id, &pkt, now_millis()) {
+            warn!("chat from {} refused: {:?}", profile.username, rejection);
+            let reason = Text::translate(rejection.translation_key(), vec![]);
+            if rejection.disconnects() {
+                disconnects.write(OutboundPlayerDisconnect {
+                    host_anchor: host_anchor.0,
+                    reason,
+                });
+            } else {
+                packet_writer.write(OutboundPlayerPacket {
+                    target: PacketTarget::SinglePlayer(host_anchor.0),
+                    priority: PacketPriority::Normal,
+                    data: PacketPayload::SystemChat {
+                        content: reason.color(Color::RED),
+                        overlay: false,
+                    },
+                });
+            }
+            return;
+        }
+    }
+
     // ChatMode is never inserted today, so absence means "shown". Only an
     // explicit Hidden suppresses broadcast and echoes the disabled notice
     // back to the sender's own host connection.
@@ -165,6 +235,30 @@ fn handle_chat(
     });
 }
 
+fn handle_chat_ack(
+    event: On<ReceivedPacketEvent>,
+    config: Res<ChatConfig>,
+    mut query: Query<(&HostAnchor, &mut SignedChatState)>,
+    mut disconnects: MessageWriter<OutboundPlayerDisconnect>,
+) {
+    if config.signing != ChatSigning::RequireSigned {
+        return;
+    }
+    let Some(pkt) = event.decode::<ServerboundChatAck>() else {
+        return;
+    };
+    let Ok((host_anchor, mut signed_chat)) = query.get_mut(event.entity) else {
+        return;
+    };
+    if let Err(err) = signed_chat.last_seen.apply_offset(pkt.offset.0) {
+        warn!("chat ack from {:?} refused: {}", event.entity, err);
+        disconnects.write(OutboundPlayerDisconnect {
+            host_anchor: host_anchor.0,
+            reason: Text::translate("multiplayer.disconnect.chat_validation_failed", vec![]),
+        });
+    }
+}
+
 #[inline]
 fn is_chat_message_illegal(msg: &str) -> bool {
     msg.chars().any(|c| !is_allowed_chat_character(c))
@@ -174,3 +268,245 @@ fn is_chat_message_illegal(msg: &str) -> bool {
 fn is_allowed_chat_character(ch: char) -> bool {
     !matches!(ch, '\0'..='\x1f' | '\x7f' | '§')
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat_session::test_keys::{profile_key, sign_message};
+    use bevy_ecs::message::Messages;
+    use bevy_ecs::world::World;
+    use mcrs_protocol::entity::player::MessageSignature;
+    use mcrs_protocol::{Encode, FixedBitSet, Packet, VarInt};
+    use std::time::Instant;
+
+    /// A signature no key made.
+    const FORGED: [u8; 256] = [0x5A; 256];
+
+    fn world(signing: ChatSigning) -> (World, Entity) {
+        let mut world = World::new();
+        world.insert_resource(ChatConfig { signing });
+        world.init_resource::<Messages<OutboundPlayerPacket>>();
+        world.init_resource::<Messages<OutboundPlayerDisconnect>>();
+        world.add_observer(handle_chat);
+        world.add_observer(handle_chat_ack);
+        let player = world
+            .spawn((
+                GameProfile {
+                    id: Uuid::nil(),
+                    username: "Steve".to_string(),
+                    properties: Vec::new(),
+                },
+                HostAnchor(Entity::PLACEHOLDER),
+                SignedChatState::default(),
+            ))
+            .id();
+        (world, player)
+    }
+
+    fn session(expires_at: i64) -> ChatSession {
+        ChatSession {
+            session_id: Uuid::nil(),
+            expires_at,
+            public_key: profile_key().to_public_key(),
+        }
+    }
+
+    /// A chat packet as a vanilla client sends it with nothing in its
+    /// last-seen window.
+    fn chat_packet(timestamp: u64, signature: Option<&[u8; 256]>) -> ServerboundChat<'_> {
+        ServerboundChat {
+            message: "hello".into(),
+            timestamp,
+            salt: 0x0123_4567_89AB_CDEF,
+            signature,
+            last_seen_messages: MessageSignature {
+                offset: VarInt(0),
+                acknowledged: FixedBitSet([0; 3]),
+                checksum: 1,
+            },
+        }
+    }
+
+    /// The signature the player's client makes for the `index`-th message of
+    /// its session.
+    fn signed(index: i32, timestamp: u64) -> Option<[u8; 256]> {
+        let payload = signed_message_payload(
+            Uuid::nil(),
+            Uuid::nil(),
+            index,
+            &chat_packet(timestamp, None),
+            &[],
+        );
+        Some(sign_message(&profile_key(), &payload))
+    }
+
+    fn send_chat(world: &mut World, player: Entity, timestamp: u64, signature: Option<[u8; 256]>) {
+        let mut data = Vec::new();
+        chat_packet(timestamp, signature.as_ref())
+            .encode(&mut data)
+            .unwrap();
+        world.trigger(ReceivedPacketEvent {
+            entity: player,
+            id: ServerboundChat::ID,
+            data: data.into(),
+            timestamp: Instant::now(),
+        });
+        world.flush();
+    }
+
+    fn sent(world: &mut World) -> Vec<OutboundPlayerPacket> {
+        world
+            .resource_mut::<Messages<OutboundPlayerPacket>>()
+            .drain()
+            .collect()
+    }
+
+    /// The reason of the last disconnect requested for the host connection.
+    fn disconnect_reason(world: &mut World) -> Option<Text> {
+        world
+            .resource_mut::<Messages<OutboundPlayerDisconnect>>()
+            .drain()
+            .inspect(|disconnect| assert_eq!(disconnect.host_anchor, Entity::PLACEHOLDER))
+            .map(|disconnect| disconnect.reason)
+            .last()
+    }
+
+    fn is_broadcast(packet: &OutboundPlayerPacket) -> bool {
+        matches!(packet.target, PacketTarget::AllPlayers)
+            && matches!(packet.data, PacketPayload::SystemChat { .. })
+    }
+
+    #[test]
+    fn allow_unsigned_rebroadcasts_as_system_chat() {
+        let (mut world, player) = world(ChatSigning::AllowUnsigned);
+        send_chat(&mut world, player, 1_000, None);
+        let packets = sent(&mut world);
+        assert_eq!(packets.len(), 1);
+        assert!(is_broadcast(&packets[0]));
+        assert_eq!(disconnect_reason(&mut world), None);
+    }
+
+    #[test]
+    fn require_signed_needs_an_unexpired_session() {
+        let (mut world, player) = world(ChatSigning::RequireSigned);
+        send_chat(&mut world, player, 1_000, Some(FORGED));
+        let packets = sent(&mut world);
+        assert_eq!(packets.len(), 1);
+        let PacketPayload::SystemChat { content, .. } = &packets[0].data else {
+            panic!("expected a system chat notice, got {:?}", packets[0].data);
+        };
+        assert_eq!(
+            *content,
+            Text::translate("chat.disabled.missingProfileKey", vec![]).color(Color::RED)
+        );
+
+        world
+            .get_mut::<SignedChatState>(player)
+            .unwrap()
+            .set_session(Some(session(0)));
+        send_chat(&mut world, player, 1_000, Some(FORGED));
+        let packets = sent(&mut world);
+        assert!(!packets.iter().any(is_broadcast));
+        assert_eq!(disconnect_reason(&mut world), None);
+    }
+
+    #[test]
+    fn require_signed_broadcasts_signed_chat_in_order() {
+        let (mut world, player) = world(ChatSigning::RequireSigned);
+        world
+            .get_mut::<SignedChatState>(player)
+            .unwrap()
+            .set_session(Some(session(i64::MAX)));
+
+        send_chat(&mut world, player, 1_000, signed(0, 1_000));
+        send_chat(&mut world, player, 1_000, signed(1, 1_000));
+        let packets = sent(&mut world);
+        assert_eq!(packets.len(), 2);
+        assert!(packets.iter().all(is_broadcast));
+
+        send_chat(&mut world, player, 999, signed(2, 999));
+        assert!(sent(&mut world).is_empty());
+        assert_eq!(
+            disconnect_reason(&mut world),
+            Some(Text::translate(
+                "multiplayer.disconnect.out_of_order_chat",
+                vec![]
+            ))
+        );
+    }
+
+    #[test]
+    fn require_signed_disconnects_unsigned_chat() {
+        let (mut world, player) = world(ChatSigning::RequireSigned);
+        world
+            .get_mut::<SignedChatState>(player)
+            .unwrap()
+            .set_session(Some(session(i64::MAX)));
+        send_chat(&mut world, player, 1_000, None);
+        assert!(sent(&mut world).is_empty());
+        assert_eq!(
+            disconnect_reason(&mut world),
+            Some(Text::translate(
+                "multiplayer.disconnect.unsigned_chat",
+                vec![]
+            ))
+        );
+    }
+
+    #[test]
+    fn require_signed_disconnects_a_forged_signature() {
+        let invalid_signature = Some(Text::translate("chat.disabled.invalid_signature", vec![]));
+        let (mut world, player) = world(ChatSigning::RequireSigned);
+        world
+            .get_mut::<SignedChatState>(player)
+            .unwrap()
+            .set_session(Some(session(i64::MAX)));
+        send_chat(&mut world, player, 1_000, Some(FORGED));
+        assert!(sent(&mut world).is_empty());
+        assert_eq!(disconnect_reason(&mut world), invalid_signature);
+
+        // A replayed message was signed for an earlier link of the chain.
+        let (mut world, player) = world(ChatSigning::RequireSigned);
+        world
+            .get_mut::<SignedChatState>(player)
+            .unwrap()
+            .set_session(Some(session(i64::MAX)));
+        send_chat(&mut world, player, 1_000, signed(0, 1_000));
+        assert!(matches!(sent(&mut world).as_slice(), [packet] if is_broadcast(packet)));
+        send_chat(&mut world, player, 1_000, signed(0, 1_000));
+        assert!(sent(&mut world).is_empty());
+        assert_eq!(disconnect_reason(&mut world), invalid_signature);
+
+        // A new session starts its chain over.
+        world
+            .get_mut::<SignedChatState>(player)
+            .unwrap()
+            .set_session(Some(session(i64::MAX)));
+        send_chat(&mut world, player, 2_000, signed(0, 2_000));
+        assert!(matches!(sent(&mut world).as_slice(), [packet] if is_broadcast(packet)));
+        assert_eq!(disconnect_reason(&mut world), None);
+    }
+
+    #[test]
+    fn chat_ack_past_the_window_fails_validation() {
+        let (mut world, player) = world(ChatSigning::RequireSigned);
+        let mut data = Vec::new();
+        ServerboundChatAck { offset: VarInt(1) }
+            .encode(&mut data)
+            .unwrap();
+        world.trigger(ReceivedPacketEvent {
+            entity: player,
+            id: ServerboundChatAck::ID,
+            data: data.into(),
+            timestamp: Instant::now(),
+        });
+        world.flush();
+        assert_eq!(
+            disconnect_reason(&mut world),
+            Some(Text::translate(
+                "multiplayer.disconnect.chat_validation_failed",
+                vec![]
+            ))
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::VecDeque;
 
-use mcrs_minecraft_block::palette::{BiomePalette, BlockPalette};
+use mcrs_minecraft_block::palette::{BiomePalette, BlockPalette, write_section};
 use bevy_app::{App, FixedPostUpdate, FixedUpdate, Plugin, PreUpdate};
 use bevy_ecs::entity::Entity;
 use bevy_ecs::message::MessageWriter;
@@ -22,7 +22,7 @@ use mcrs_engine::world::column::{
 use mcrs_engine::world::dimension::{DimensionTypeConfig, InDimension};
 use mcrs_minecraft_lighting::codec::{build_full_light_data, ColumnLightUpdate, LightCodecParams};
 use mcrs_minecraft_lighting::sets::LightingSet;
-use mcrs_protocol::{ColumnPos, Encode};
+use mcrs_protocol::ColumnPos;
 
 use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
 use crate::world::entity::player::HostAnchor;
@@ -88,6 +88,9 @@ pub struct ColumnView {
     loading_queue: VecDeque<ColumnPos>,
     send_queue: VecDeque<(ColumnPos, Vec<Entity>)>,
     pub sent_columns: FxHashSet<ColumnPos>,
+    /// The host connection's `send_buffer_pressure`, mirrored in by the
+    /// per-dim extract so `send_column_queue` can back off slow clients.
+    pub(crate) send_buffer_pressure: f32,
 }
 
 fn load_chunk_request(
@@ -196,11 +199,25 @@ fn loading_column_queue(
 /// Maximum chunk columns to send per player per tick.
 const MAX_COL_SENDS_PER_TICK: usize = 10;
 
+/// Columns a player may be sent this tick given how backed up their socket
+/// is: the full burst while the client keeps up, one column once half the
+/// outbound channel is in use, and none while it is full.
+fn column_send_budget(send_buffer_pressure: f32) -> usize {
+    if send_buffer_pressure >= 1.0 {
+        0
+    } else if send_buffer_pressure >= 0.5 {
+        1
+    } else {
+        MAX_COL_SENDS_PER_TICK
+    }
+}
+
 /// Chunk-load wire emit routed through the `OutboundPlayerPacket` bus.
 ///
 /// Per-column byte-backpressure is removed: the bridge tier's `OutboundQueue`
-/// byte-cap and drop-oldest policy own backpressure now. Only the count gate
-/// (`MAX_COL_SENDS_PER_TICK`) remains to throttle per-tick burst.
+/// byte-cap and drop-oldest policy own backpressure now. The count gate
+/// ([`column_send_budget`]) throttles the per-tick burst, and shrinks it
+/// while the client's socket is backed up.
 /// Chunks are sent at Critical priority so they are never dropped by the bridge.
 fn send_column_queue(
     mut players: Query<(
@@ -227,10 +244,11 @@ fn send_column_queue(
         .for_each(|(mut chunk_view, rep, in_dim, host_anchor)| {
             let host = host_anchor.0;
             let column_index = dim_column_indexes.get(in_dim.entity()).ok();
+            let budget = column_send_budget(chunk_view.send_buffer_pressure);
             let mut sends = 0usize;
 
             loop {
-                if sends >= MAX_COL_SENDS_PER_TICK {
+                if sends >= budget {
                     break;
                 }
 
@@ -254,29 +272,8 @@ fn send_column_queue(
                         ready = false;
                         break;
                     }
-                    // Section layout per vanilla LevelChunkSection.write:
-                    //   short non_empty_block_count
-                    //   short fluid_count
-                    //   PalettedContainer<BlockState>
-                    //   PalettedContainer<Biome>
-                    //
-                    // The client reads both shorts unconditionally; omitting the
-                    // fluid count desynchronises the reader by two bytes per
-                    // section and turns the rest of the column into garbage.
-                    blocks
-                        .non_air_block_count()
-                        .encode(&mut data)
-                        .expect("Failed to encode chunk block count");
-                    0u16.encode(&mut data)
-                        .expect("Failed to encode chunk fluid count");
-                    blocks
-                        .convert_network()
-                        .encode(&mut data)
-                        .expect("Failed to encode chunk block data");
-                    biomes
-                        .convert_network()
-                        .encode(&mut data)
-                        .expect("Failed to encode chunk block data");
+                    write_section(blocks, biomes, &mut data)
+                        .expect("Failed to encode chunk section");
                 }
                 if !ready {
                     // Entity data not available yet — stop processing
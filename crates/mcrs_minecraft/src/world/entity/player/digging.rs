@@ -1,9 +1,9 @@
 use crate::world::block::Block;
 use mcrs_minecraft_block::block_update::BlockSetRequest;
 use crate::world::bus::PendingInboundLifecycle;
-use crate::world::entity::attribute::Attribute;
+use crate::world::entity::attribute::{AttributeKind, Attributes};
+use crate::world::entity::item::ItemEntityBundle;
 use crate::world::entity::player::ability::InstantBuild;
-use crate::world::entity::player::attribute::{BlockBreakSpeed, MiningEfficiency};
 use crate::world::entity::player::player_action::{
     PlayerAction, PlayerActionKind, PlayerWillDestroyBlock,
 };
@@ -14,8 +14,9 @@ use crate::world::item::component::Enchantments;
 use crate::world::player_index::{HostAnchorRef, PlayerIndex};
 use crate::enchantment::EnchantmentData;
 use crate::world::loot::BlockLootTables;
-use crate::world::loot::context::BlockBreakContext;
+use crate::world::loot::context::{BlockBreakContext, LootDrop};
 use mcrs_minecraft_block::palette::BlockPalette;
+use mcrs_minecraft_worldgen::bevy::OverworldNoiseRouter;
 use bevy_app::{FixedUpdate, Plugin, Update};
 use bevy_asset::AssetServer;
 use bevy_ecs::prelude::*;
@@ -31,10 +32,12 @@ use mcrs_protocol::packets::game::clientbound::ClientboundBlockDestruction;
 use mcrs_protocol::{BlockStateId, VarInt, WritePacket};
 use mcrs_core::StaticRegistry;
 use mcrs_core::tag::registry::TagRegistry;
+use mcrs_random::RandomSource;
 use mcrs_vanilla::block::Block as VanillaBlock;
+use mcrs_vanilla::item::Item as VanillaItem;
 use rand::RngExt;
 use std::time::Duration;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
 /// Route a clone of `event` into `PendingInboundLifecycle.block_events`
 /// under the destination per-dim bucket. The destination is the
@@ -163,8 +166,7 @@ fn player_start_destroy_block(
         &Transform,
         &Reposition,
         Has<InstantBuild>,
-        &MiningEfficiency,
-        &BlockBreakSpeed,
+        &Attributes,
         &PlayerHotbarSlots,
     )>,
     items: Query<(&ItemStack, Option<&Tool>)>,
@@ -179,11 +181,10 @@ fn player_start_destroy_block(
 ) {
     reader.read().for_each(|event| {
         let player = event.player;
-        let (dim, _pos, rep, _instant_build, mining_efficiency, block_break_speed, hotbar) =
-            match players.get_mut(player) {
-                Ok(value) => value,
-                Err(_) => return,
-            };
+        let (dim, _pos, rep, instant_build, attributes, hotbar) = match players.get_mut(player) {
+            Ok(value) => value,
+            Err(_) => return,
+        };
         let PlayerActionKind::StartDestroyBlock {
             block_pos,
             direction: _,
@@ -209,13 +210,12 @@ fn player_start_destroy_block(
         };
 
         let mut damage = 1.0;
-        if block_state.0 != 0 {
+        if block_state.0 != 0 && !instant_build {
             damage = get_destroy_speed(
                 block_state,
                 hotbar,
                 &items,
-                mining_efficiency,
-                block_break_speed,
+                attributes,
                 &tag_registry,
                 &block_registry,
             );
@@ -358,8 +358,7 @@ fn get_destroy_speed<B>(
     block: B,
     hotbar: &PlayerHotbarSlots,
     items: &Query<(&ItemStack, Option<&Tool>)>,
-    mining_efficiency: &MiningEfficiency,
-    block_break_speed: &BlockBreakSpeed,
+    attributes: &Attributes,
     tag_registry: &TagRegistry<VanillaBlock>,
     block_registry: &StaticRegistry<VanillaBlock>,
 ) -> f32
@@ -373,9 +372,9 @@ where
     }
     let (has_correct_tool, mut speed) = extract_tool_data(block, hotbar, items, tag_registry, block_registry);
     if speed > 1.0 {
-        speed += mining_efficiency.value();
+        speed += attributes.value(AttributeKind::MiningEfficiency) as f32;
     }
-    speed *= block_break_speed.value();
+    speed *= attributes.value(AttributeKind::BlockBreakSpeed) as f32;
     let modifier = if has_correct_tool { 30.0 } else { 100.0 };
     speed / hardness / modifier
 }
@@ -435,14 +434,17 @@ pub fn get_tool_destroy_speed(
 fn handle_player_will_destroy_block(
     mut reader: MessageReader<PlayerWillDestroyBlock>,
     mut writer: MessageWriter<BlockSetRequest>,
-    players: Query<(&InDimension, &PlayerHotbarSlots)>,
+    players: Query<(&InDimension, &PlayerHotbarSlots, Has<InstantBuild>)>,
     items: Query<(&ItemStack, Option<&Enchantments>, Option<&Tool>)>,
     tag_registry: Res<TagRegistry<VanillaBlock>>,
     block_registry: Res<StaticRegistry<VanillaBlock>>,
     enchantment_registry: Res<StaticRegistry<EnchantmentData>>,
+    item_registry: Res<StaticRegistry<VanillaItem>>,
     mut loot_tables: ResMut<BlockLootTables>,
     asset_server: Res<AssetServer>,
     mut silk_touch_id: Local<Option<u16>>,
+    noise_router: Option<Res<OverworldNoiseRouter>>,
+    mut commands: Commands,
 ) {
     if silk_touch_id.is_none() {
         *silk_touch_id = enchantment_registry
@@ -450,12 +452,20 @@ fn handle_player_will_destroy_block(
             .map(|id| id.raw() as u16);
     }
 
+    let world_seed = noise_router.map_or(0, |router| router.0.world_seed());
+
     reader.read().for_each(|event| {
         // TODO: spawn destroy particles
         // TODO: anger piglin if block is guarded by piglins
-        let Ok((dim, hotbar)) = players.get(event.player) else {
+        let Ok((dim, hotbar, instant_build)) = players.get(event.player) else {
             return;
         };
+        // Like vanilla `Block.playerDestroy`, which creative mode never
+        // reaches: the block goes without drops or experience.
+        if instant_build {
+            writer.write(BlockSetRequest::remove_block(**dim, event.block_pos));
+            return;
+        }
 
         let block: &Block = event.block_state.as_ref();
         let block_id = block.identifier;
@@ -488,8 +498,13 @@ fn handle_player_will_destroy_block(
             if let Some(table) = loot_tables.tables.get(block_id.as_str()) {
                 let ctx = BlockBreakContext {
                     tool_enchantments,
+                    block_pos: event.block_pos,
+                    loot_tables: Some(&*loot_tables),
+                    // Players have no luck attribute yet.
+                    luck: 0.0,
                 };
-                let drops = table.evaluate(&ctx);
+                let mut rng = RandomSource::new(rand::random(), false);
+                let drops = table.evaluate(&ctx, world_seed, &mut rng);
                 for drop in &drops {
                     debug!(
                         block = %block_id,
@@ -497,6 +512,13 @@ fn handle_player_will_destroy_block(
                         count = drop.count,
                         "Loot drop"
                     );
+                    let Some(stack) = drop_stack(drop, &item_registry) else {
+                        warn!(item = %drop.item_name, "Loot drop is not a registered item");
+                        continue;
+                    };
+                    // Tables that set components are refused at load, so a
+                    // drop is only ever an item and a count.
+                    commands.spawn(ItemEntityBundle::popped_from(*dim, event.block_pos, stack));
                 }
             } else {
                 // Trigger lazy load for blocks not yet loaded
@@ -523,3 +545,12 @@ fn handle_player_will_destroy_block(
         writer.write(BlockSetRequest::remove_block(**dim, event.block_pos));
     });
 }
+
+/// The stack a loot drop spawns as, or `None` if it names no known item.
+pub fn drop_stack(
+    drop: &LootDrop,
+    item_registry: &StaticRegistry<VanillaItem>,
+) -> Option<ItemStack> {
+    let item = item_registry.get_by_loc(drop.item_name.as_str())?;
+    Some(ItemStack::new(item, drop.count))
+}
@@ -1,9 +1,13 @@
 use crate::world::inventory::PlayerHotbarSlots;
+use crate::world::inventory::click::click_container;
 use bevy_app::{App, Plugin};
 use bevy_ecs::prelude::On;
-use bevy_ecs::system::Query;
+use bevy_ecs::system::{Commands, Query};
+use bevy_ecs::world::World;
 use mcrs_network::event::ReceivedPacketEvent;
-use mcrs_protocol::packets::game::serverbound::ServerboundSetCarriedItem;
+use mcrs_protocol::packets::game::serverbound::{
+    ServerboundContainerClick, ServerboundSetCarriedItem,
+};
 use tracing::warn;
 
 pub struct PlayerInventoryPlugin;
@@ -11,6 +15,7 @@ pub struct PlayerInventoryPlugin;
 impl Plugin for PlayerInventoryPlugin {
     fn build(&self, app: &mut App) {
         app.add_observer(update_carried_item);
+        app.add_observer(container_click);
     }
 }
 
@@ -27,3 +32,13 @@ fn update_carried_item(event: On<ReceivedPacketEvent>, mut hotbar: Query<&mut Pl
     }
     hotbar.selected = pkt.slot as u8
 }
+
+/// Apply a Click Container with the whole world, which it needs to split,
+/// merge and drop item entities.
+fn container_click(event: On<ReceivedPacketEvent>, mut commands: Commands) {
+    let Some(pkt) = event.decode::<ServerboundContainerClick>() else {
+        return;
+    };
+    let player = event.entity;
+    commands.queue(move |world: &mut World| click_container(world, player, &pkt));
+}
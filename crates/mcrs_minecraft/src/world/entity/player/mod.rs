@@ -1,22 +1,33 @@
-use crate::client_info::ClientViewDistance;
+use crate::biome::obfuscate_seed;
+use crate::chat_session::ChatConfig;
+use crate::client_info::{ClientInfo, ServerViewConfig};
+use crate::command::{CommandDispatcher, CommandPlugin};
 use crate::configuration::LoadedWorldPreset;
 use crate::login::GameProfile;
+use crate::server_config::ServerConfig;
+use crate::spawn_point::SpawnPoint;
 use crate::world::bus::{
     InboundPlayerDespawn, InboundPlayerSpawn, OutboundPlayerAttached, OutboundPlayerPacket,
-    PacketPayload, PacketPriority, PacketTarget, PlayerInfoEntry,
+    PacketPayload, PacketPriority, PacketTarget, PlayerInfoEntry, SpawnReason,
 };
+use crate::world::entity::EntityBundle;
 use crate::world::entity::player::ability::{PlayerGameMode, PlayerOpLevel};
-use crate::world::entity::player::chat::ChatPlugin;
+use crate::world::entity::player::chat::{ChatPlugin, SignedChatState};
 use crate::world::entity::player::column_view::ColumnViewPlugin;
 use crate::world::entity::player::digging::DiggingPlugin;
 use crate::world::entity::player::game_mode::GameModePlugin;
 use crate::world::entity::player::inventory::PlayerInventoryPlugin;
 use crate::world::entity::player::movement::MovementPlugin;
 use crate::world::entity::player::player_action::PlayerActionPlugin;
-use crate::world::entity::{EntityBundle, MinecraftEntityType};
-use crate::world::inventory::{ContainerSeqno, PlayerInventoryBundle, PlayerInventoryQuery};
+use crate::world::entity::player::posture::{PlayerPosture, PosturePlugin};
+use crate::world::entity::player::respawn::RespawnPlugin;
+use crate::world::inventory::{
+    ContainerSeqno, PLAYER_CONTAINER_ID, PlayerInventoryBundle, PlayerInventoryQuery,
+    PlayerInventoryQueryItem, set_container_content,
+};
 use crate::world::item::minecraft::DIAMOND_PICKAXE;
 use crate::world::item::{ItemCommands, ItemStack};
+use crate::world::sub_app_builder::{DimSeaLevel, DimTypeIndex};
 use bevy_app::{FixedUpdate, Plugin, PostUpdate};
 use bevy_ecs::bundle::Bundle;
 use bevy_ecs::component::Component;
@@ -28,38 +39,40 @@ use bevy_ecs::prelude::{Changed, Commands, Has, Query, RemovedComponents, Res, W
 use bevy_ecs::query::Added;
 use bevy_math::DVec3;
 use derive_more::{Deref, DerefMut};
+use mcrs_engine::entity::Despawned;
 use mcrs_engine::entity::physics::Transform;
 use mcrs_engine::entity::player::Player;
 use mcrs_engine::entity::player::chunk_view::{PlayerChunkObserver, PlayerViewDistance};
 use mcrs_engine::entity::player::reposition::Reposition;
-use mcrs_engine::entity::{Despawned, EntityNetworkAddEvent};
 use mcrs_engine::world::dimension::{Dimension, DimensionId, InDimension};
-use crate::world::sub_app_builder::DimTypeIndex;
+use mcrs_minecraft_worldgen::bevy::WorldGenConfig;
 use mcrs_network::{ConnectionState, InGameConnectionState, ServerSideConnection};
 use mcrs_protocol::entity::player::PlayerSpawnInfo;
-use mcrs_protocol::item::ComponentPatch;
 use mcrs_protocol::packets::game::clientbound::{
-    ClientboundContainerSetContent, ClientboundDisconnect, ClientboundEntityEvent,
-    ClientboundGameEvent, ClientboundLogin, ClientboundPlayerPosition,
+    ClientboundDisconnect, ClientboundEntityEvent, ClientboundGameEvent, ClientboundLogin,
+    ClientboundPlayerPosition, ClientboundRespawn,
 };
-use mcrs_protocol::{GameEventKind, GameMode, Look, Slot, Text, VarInt, WritePacket};
-use movement::TeleportState;
+use mcrs_protocol::setting::DisplayedSkinParts;
+use mcrs_protocol::{GameEventKind, GameMode, Look, Text, VarInt, WritePacket};
+use movement::{OnGround, TeleportState};
 use tracing::{debug, info};
 
 pub mod ability;
 pub mod attribute;
-mod chat;
+pub mod chat;
 pub mod column_view;
 pub mod digging;
-mod game_mode;
+pub mod game_mode;
 mod inventory;
 pub mod movement;
 pub mod player_action;
+pub mod posture;
+pub mod respawn;
 
 /// Default game mode applied to joining players, read from `MCRS_DEFAULT_GAMEMODE`
 /// (`survival`, `creative`, `adventure`, or `spectator`). Falls back to creative
 /// when unset or unrecognized.
-fn default_game_mode() -> GameMode {
+pub(crate) fn default_game_mode() -> GameMode {
     match std::env::var("MCRS_DEFAULT_GAMEMODE") {
         Ok(value) => match value.trim().to_ascii_lowercase().as_str() {
             "survival" => GameMode::Survival,
@@ -85,23 +98,41 @@ fn default_game_mode() -> GameMode {
 #[derive(bevy_ecs::component::Component, Clone, Copy, Debug, PartialEq, Eq)]
 pub struct HostAnchor(pub Entity);
 
+/// The skin layers the player's client shows, mirrored from its host-side
+/// [`ClientInfo`] so other players are spawned with the same ones.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlayerSkinParts(pub DisplayedSkinParts);
+
+impl Default for PlayerSkinParts {
+    /// Every layer, what a vanilla client shows until told otherwise.
+    fn default() -> Self {
+        Self(DisplayedSkinParts::from_bits(0x7F))
+    }
+}
+
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut bevy_app::App) {
         app.add_plugins(DiggingPlugin);
         app.add_plugins(PlayerActionPlugin);
+        app.add_plugins(PosturePlugin);
+        app.add_plugins(RespawnPlugin);
         app.add_plugins(MovementPlugin);
         app.add_plugins(ColumnViewPlugin);
         app.add_plugins(PlayerInventoryPlugin);
         app.add_plugins(ChatPlugin);
+        app.add_plugins(CommandPlugin);
         app.add_plugins(GameModePlugin);
         app.add_systems(bevy_app::Update, spawn_player);
-        app.add_systems(bevy_app::Update, consume_inbound_player_spawn);
-        app.add_systems(bevy_app::Update, despawn_inbound_player);
+        // A death respawn despawns and respawns the player in one dimension
+        // in the same tick; the old entity has to go first.
+        app.add_systems(
+            bevy_app::Update,
+            (despawn_inbound_player, consume_inbound_player_spawn).chain(),
+        );
         app.add_systems(FixedUpdate, (disconnect_player, added_inventory, resync_player));
         app.add_systems(PostUpdate, despawn_disconnected_clients);
-        app.add_observer(network_add);
         app.add_observer(player_joined);
     }
 }
@@ -109,6 +140,7 @@ impl Plugin for PlayerPlugin {
 #[derive(Bundle, Default)]
 pub struct PlayerBundle {
     pub teleport_state: TeleportState,
+    pub on_ground: OnGround,
     pub view_distance: PlayerViewDistance,
     pub reposition: Reposition,
     pub abilities: ability::PlayerAbilitiesBundle,
@@ -117,6 +149,9 @@ pub struct PlayerBundle {
     pub container_seqno: ContainerSeqno,
     pub game_mode: PlayerGameMode,
     pub op_level: PlayerOpLevel,
+    pub signed_chat: SignedChatState,
+    pub skin_parts: PlayerSkinParts,
+    pub posture: PlayerPosture,
     pub chunk_subscription_set: crate::world::aoi::ChunkSubscriptionSet,
     pub tracked_by: crate::world::aoi::TrackedBy,
     pub marker: Player,
@@ -133,11 +168,13 @@ pub struct DisconnectReason(pub Text);
 
 fn spawn_player(
     world_preset: Res<LoadedWorldPreset>,
+    chat_config: Res<ChatConfig>,
+    view_config: Res<ServerViewConfig>,
     dimensions: Query<(Entity, &DimensionId), With<Dimension>>,
     mut query: Query<
         (
             Entity,
-            &ClientViewDistance,
+            &ClientInfo,
             &ConnectionState,
             &GameProfile,
             &mut ServerSideConnection,
@@ -156,7 +193,7 @@ fn spawn_player(
 
     query
         .iter_mut()
-        .for_each(|(entity, distance, con_state, profile, mut con, is_reconfiguration, existing_game_mode, existing_op_level)| {
+        .for_each(|(entity, client_info, con_state, profile, mut con, is_reconfiguration, existing_game_mode, existing_op_level)| {
             if *con_state != ConnectionState::Game {
                 return;
             }
@@ -195,8 +232,8 @@ fn spawn_player(
                         .map(|(dim_key, _)| dim_key.clone().into())
                         .collect(),
                     max_players: VarInt(100),
-                    chunk_radius: VarInt(12),
-                    simulation_distance: VarInt(12),
+                    chunk_radius: VarInt(view_config.view_distance as i32),
+                    simulation_distance: VarInt(view_config.simulation_distance as i32),
                     reduced_debug_info: false,
                     show_death_screen: false,
                     do_limited_crafting: false,
@@ -204,7 +241,7 @@ fn spawn_player(
                         game_mode,
                         ..Default::default()
                     },
-                    enforces_secure_chat: false,
+                    enforces_secure_chat: chat_config.signing.enforces_secure_chat(),
                 });
                 con.write_packet(&ClientboundGameEvent {
                     game_event: GameEventKind::LevelChunksLoadStart,
@@ -230,8 +267,8 @@ fn spawn_player(
                         .map(|(dim_key, _)| dim_key.clone().into())
                         .collect(),
                     max_players: VarInt(100),
-                    chunk_radius: VarInt(12),
-                    simulation_distance: VarInt(12),
+                    chunk_radius: VarInt(view_config.view_distance as i32),
+                    simulation_distance: VarInt(view_config.simulation_distance as i32),
                     reduced_debug_info: false,
                     show_death_screen: false,
                     do_limited_crafting: false,
@@ -239,7 +276,7 @@ fn spawn_player(
                         game_mode,
                         ..Default::default()
                     },
-                    enforces_secure_chat: false,
+                    enforces_secure_chat: chat_config.signing.enforces_secure_chat(),
                 });
                 con.write_packet(&ClientboundGameEvent {
                     game_event: GameEventKind::LevelChunksLoadStart,
@@ -263,7 +300,7 @@ fn spawn_player(
                         .with_transform(Transform::default().with_translation(pos)),
                     PlayerBundle {
                         view_distance: PlayerViewDistance {
-                            distance: **distance,
+                            distance: client_info.view_distance,
                             ..Default::default()
                         },
                         inventory,
@@ -286,29 +323,46 @@ fn spawn_player(
 /// are host-resident and must NOT be accessed here.
 fn consume_inbound_player_spawn(
     world_preset: Res<crate::configuration::LoadedWorldPreset>,
+    chat_config: Res<ChatConfig>,
+    view_config: Res<ServerViewConfig>,
+    server_config: Res<ServerConfig>,
+    spawn_point: Res<SpawnPoint>,
+    world_gen_config: Res<WorldGenConfig>,
+    dispatcher: Res<CommandDispatcher>,
     mut reader: MessageReader<InboundPlayerSpawn>,
     mut attached: MessageWriter<OutboundPlayerAttached>,
     mut packet_writer: MessageWriter<OutboundPlayerPacket>,
-    dims: Query<(Entity, &DimensionId, &DimTypeIndex), With<Dimension>>,
+    dims: Query<(Entity, &DimensionId, &DimTypeIndex, &DimSeaLevel), With<Dimension>>,
     mut commands: Commands,
 ) {
     use std::sync::atomic::Ordering;
+    let hashed_seed = obfuscate_seed(world_gen_config.seed);
     for spawn in reader.read() {
-        let Some((dim, dim_id, dim_type_index)) = dims.iter().next() else {
+        // Each sub-world holds the one dimension the host routed the spawn
+        // to: the spawn point's on join and death, the destination on a
+        // dimension change.
+        let Ok((dim, dim_id, dim_type_index, sea_level)) = dims.single() else {
             continue;
         };
+        let sea_level = sea_level.0;
         let dim_name = dim_id.as_str().to_string();
         let dim_type_id = dim_type_index.0;
+        let game_mode = spawn.snapshot.game_mode;
+        let op_level = PlayerOpLevel::default();
+        let transform = Transform::default().with_translation(spawn.snapshot.position);
+        // The spawn position below is the player's first teleport; moves are
+        // ignored until the client confirms it.
+        let mut teleport_state = TeleportState::synced_to(transform);
+        let teleport_id = teleport_state.begin_teleport();
         let new_entity = commands
             .spawn((
                 EntityBundle::new(InDimension(dim))
                     .with_uuid(spawn.snapshot.uuid)
-                    .with_transform(
-                        Transform::default()
-                            .with_translation(spawn.snapshot.position),
-                    ),
+                    .with_transform(transform),
                 PlayerBundle {
-                    game_mode: PlayerGameMode(default_game_mode()),
+                    teleport_state,
+                    game_mode: PlayerGameMode(game_mode),
+                    op_level,
                     ..Default::default()
                 },
                 PlayerChunkObserver::default(),
@@ -327,59 +381,132 @@ fn consume_inbound_player_spawn(
         let center_x = (spawn_pos.x / 16.0).floor() as i32;
         let center_z = (spawn_pos.z / 16.0).floor() as i32;
 
-        let dimensions: Vec<String> = if world_preset.dimensions.is_empty() {
-            vec!["minecraft:overworld".to_string()]
-        } else {
-            world_preset.dimensions
-                .iter()
-                .map(|(dim_key, _)| dim_key.as_str().to_owned())
-                .collect()
-        };
+        match spawn.reason {
+            SpawnReason::Join => {
+                let dimensions: Vec<String> = if world_preset.dimensions.is_empty() {
+                    vec!["minecraft:overworld".to_string()]
+                } else {
+                    world_preset.dimensions
+                        .iter()
+                        .map(|(dim_key, _)| dim_key.as_str().to_owned())
+                        .collect()
+                };
+
+                debug!(
+                    target: "mcrs_minecraft::player",
+                    player = wire_id,
+                    host_anchor = ?host,
+                    "emit_play_login: emitting play ClientboundLogin for newly-materialized in-dim entity"
+                );
 
-        debug!(
-            target: "mcrs_minecraft::player",
-            player = wire_id,
-            host_anchor = ?host,
-            "emit_play_login: emitting play ClientboundLogin for newly-materialized in-dim entity"
-        );
+                packet_writer.write(OutboundPlayerPacket {
+                    target: PacketTarget::SinglePlayer(host),
+                    priority: PacketPriority::Critical,
+                    data: PacketPayload::PlayerLogin {
+                        player_id: wire_id,
+                        hardcore: false,
+                        game_mode,
+                        dimension: dim_name,
+                        dimension_type_id: dim_type_id,
+                        dimensions,
+                        max_players: server_config.max_players as i32,
+                        chunk_radius: view_config.view_distance as i32,
+                        simulation_distance: view_config.simulation_distance as i32,
+                        reduced_debug_info: false,
+                        show_death_screen: false,
+                        do_limited_crafting: false,
+                        previous_game_mode: None,
+                        hashed_seed,
+                        is_debug: false,
+                        is_flat: world_preset.preset_name == "flat",
+                        last_death_location: None,
+                        portal_cooldown: 0,
+                        sea_level,
+                        enforces_secure_chat: chat_config.signing.enforces_secure_chat(),
+                    },
+                });
+                mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+                    .fetch_add(1, Ordering::Relaxed);
+
+                // The client derives the local player's game mode (and therefore
+                // spectator noclip) from its own player-list entry, not the login
+                // packet. Without this the client treats itself as non-spectator and
+                // keeps block collisions even though login set the spectator mode.
+                packet_writer.write(OutboundPlayerPacket {
+                    target: PacketTarget::SinglePlayer(host),
+                    priority: PacketPriority::Critical,
+                    data: PacketPayload::PlayerInfoUpdate {
+                        entries: vec![PlayerInfoEntry {
+                            player_uuid: spawn.snapshot.uuid,
+                            game_mode,
+                        }],
+                    },
+                });
+                mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+                    .fetch_add(1, Ordering::Relaxed);
+
+                // The client keeps its command graph across dimension changes,
+                // so it is only sent on join.
+                let graph = dispatcher.commands_packet(op_level.clamped());
+                packet_writer.write(OutboundPlayerPacket {
+                    target: PacketTarget::SinglePlayer(host),
+                    priority: PacketPriority::Critical,
+                    data: PacketPayload::Commands {
+                        nodes: graph.nodes,
+                        root_index: graph.root_index.0,
+                    },
+                });
+                mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+                    .fetch_add(1, Ordering::Relaxed);
+            }
+            SpawnReason::DimensionChange | SpawnReason::Death => {
+                debug!(
+                    target: "mcrs_minecraft::player",
+                    player = wire_id,
+                    host_anchor = ?host,
+                    dimension = %dim_name,
+                    reason = ?spawn.reason,
+                    "emitting Respawn for a player already in play"
+                );
+                // The client drops every chunk it had loaded; the new in-dim
+                // entity starts with an empty `PlayerChunkObserver`, so the
+                // chunk stream re-sends the level around the position synced
+                // below.
+                packet_writer.write(OutboundPlayerPacket {
+                    target: PacketTarget::SinglePlayer(host),
+                    priority: PacketPriority::Critical,
+                    data: PacketPayload::Respawn {
+                        dimension: dim_name,
+                        dimension_type_id: dim_type_id,
+                        game_mode,
+                        hashed_seed,
+                        sea_level,
+                        portal_cooldown: 0,
+                        data_to_keep: data_to_keep(spawn.reason),
+                    },
+                });
+                mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+                    .fetch_add(1, Ordering::Relaxed);
+            }
+        }
 
         packet_writer.write(OutboundPlayerPacket {
             target: PacketTarget::SinglePlayer(host),
             priority: PacketPriority::Critical,
-            data: PacketPayload::PlayerLogin {
-                player_id: wire_id,
-                hardcore: false,
-                game_mode: default_game_mode(),
-                dimension: dim_name,
-                dimension_type_id: dim_type_id,
-                dimensions,
-                max_players: 100,
-                chunk_radius: 12,
-                simulation_distance: 12,
-                reduced_debug_info: false,
-                show_death_screen: false,
-                do_limited_crafting: false,
-                enforces_secure_chat: false,
+            data: PacketPayload::SetDefaultSpawnPosition {
+                dimension: spawn_point.dimension.as_str().to_owned(),
+                position: spawn_point.position,
+                yaw: spawn_point.yaw,
+                pitch: spawn_point.pitch,
             },
         });
         mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
             .fetch_add(1, Ordering::Relaxed);
 
-        // The client derives the local player's game mode (and therefore
-        // spectator noclip) from its own player-list entry, not the login
-        // packet. Without this the client treats itself as non-spectator and
-        // keeps block collisions even though login set the spectator mode.
         packet_writer.write(OutboundPlayerPacket {
             target: PacketTarget::SinglePlayer(host),
             priority: PacketPriority::Critical,
-            data: PacketPayload::PlayerInfoUpdate {
-                entries: vec![PlayerInfoEntry {
-                    player_uuid: spawn.snapshot.uuid,
-                    username: spawn.snapshot.username.clone(),
-                    game_mode: default_game_mode(),
-                    listed: true,
-                }],
-            },
+            data: PacketPayload::SetChunkCacheCenter { x: center_x, z: center_z },
         });
         mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
             .fetch_add(1, Ordering::Relaxed);
@@ -387,7 +514,9 @@ fn consume_inbound_player_spawn(
         packet_writer.write(OutboundPlayerPacket {
             target: PacketTarget::SinglePlayer(host),
             priority: PacketPriority::Critical,
-            data: PacketPayload::SetChunkCacheCenter { x: center_x, z: center_z },
+            data: PacketPayload::SetChunkCacheRadius {
+                radius: view_config.view_distance as i32,
+            },
         });
         mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
             .fetch_add(1, Ordering::Relaxed);
@@ -395,7 +524,9 @@ fn consume_inbound_player_spawn(
         packet_writer.write(OutboundPlayerPacket {
             target: PacketTarget::SinglePlayer(host),
             priority: PacketPriority::Critical,
-            data: PacketPayload::SetChunkCacheRadius { radius: 12 },
+            data: PacketPayload::SetSimulationDistance {
+                simulation_distance: view_config.simulation_distance as i32,
+            },
         });
         mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
             .fetch_add(1, Ordering::Relaxed);
@@ -413,7 +544,7 @@ fn consume_inbound_player_spawn(
             priority: PacketPriority::Critical,
             data: PacketPayload::PlayerLoginEntityEvent {
                 entity_id: wire_id,
-                entity_status: 24,
+                entity_status: op_level.entity_status(),
             },
         });
         mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
@@ -423,8 +554,13 @@ fn consume_inbound_player_spawn(
             target: PacketTarget::SinglePlayer(host),
             priority: PacketPriority::Critical,
             data: PacketPayload::PlayerPosition {
-                teleport_id: 1,
+                teleport_id,
                 position: spawn_pos,
+                look: Look {
+                    yaw: spawn.snapshot.rotation.x,
+                    pitch: spawn.snapshot.rotation.y,
+                },
+                flags: Vec::new(),
             },
         });
         mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
@@ -438,6 +574,16 @@ fn consume_inbound_player_spawn(
 }
 
 
+/// What Respawn tells the client to keep of the player. Dimension travel
+/// keeps everything; like vanilla `PlayerList.respawn`, a death respawn keeps
+/// nothing. A joining player is sent a Login instead.
+fn data_to_keep(reason: SpawnReason) -> u8 {
+    match reason {
+        SpawnReason::DimensionChange => ClientboundRespawn::KEEP_ALL_DATA,
+        SpawnReason::Join | SpawnReason::Death => 0,
+    }
+}
+
 /// Per-dim consumer that despawns the in-dim player entity when an
 /// `InboundPlayerDespawn` arrives for its host anchor. Fires on both
 /// disconnect and dimension transfer (the transfer pushes a despawn into the
@@ -463,37 +609,6 @@ pub struct PlayerJoinEvent {
     pub player: Entity,
 }
 
-fn network_add(
-    event: On<EntityNetworkAddEvent>,
-    added_player: Query<(Entity, &GameProfile, &Transform), With<Player>>,
-    viewer: Query<(&Reposition, &crate::world::player_index::HostAnchorRef), With<Player>>,
-    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
-) {
-    use std::sync::atomic::Ordering;
-    let Ok((entity, profile, transform)) = added_player.get(event.entity) else {
-        return;
-    };
-    let Ok((reposition, host_anchor_ref)) = viewer.get(event.player) else {
-        return;
-    };
-
-    let host_anchor = host_anchor_ref.0;
-    packet_writer.write(OutboundPlayerPacket {
-        target: PacketTarget::SinglePlayer(host_anchor),
-        priority: PacketPriority::Normal,
-        data: PacketPayload::PlayerEnteredView {
-            entity_id: entity.index_u32() as i32,
-            uuid: profile.id,
-            kind: MinecraftEntityType::Player as i32,
-            position: reposition.convert_dvec3(transform.translation),
-            yaw: transform.rotation.y,
-            pitch: transform.rotation.x,
-        },
-    });
-    mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
-        .fetch_add(1, Ordering::Relaxed);
-}
-
 fn player_joined(
     event: On<PlayerJoinEvent>,
     players: Query<(&GameProfile, &PlayerGameMode, &crate::world::player_index::HostAnchorRef), With<Player>>,
@@ -519,13 +634,13 @@ fn player_joined(
         .iter()
         .map(|(profile, game_mode, _)| PlayerInfoEntry {
             player_uuid: profile.id,
-            username: profile.username.clone(),
             game_mode: game_mode.0,
-            listed: true,
         })
         .collect();
 
-    // Broadcast player info to every connected player (including the joining player).
+    // Game modes are per-dimension, so players sharing this dimension learn
+    // each other's here. The host `PlayerList` sends the server-wide roster
+    // and owns the listed flag, so only the game mode is updated.
     for (_, _, host_anchor_ref) in players.iter() {
         packet_writer.write(OutboundPlayerPacket {
             target: PacketTarget::SinglePlayer(host_anchor_ref.0),
@@ -571,31 +686,27 @@ fn added_inventory(
     items: Query<&ItemStack >,
 ) {
     for (mut con, inventory, seqno) in players.iter_mut() {
-        let slots = inventory
-            .all_slots()
-            .iter()
-            .map(|slot| {
-                slot.and_then(|slot| items.get(slot).ok())
-                    .map(|item| Slot::new(item.item_id(), item.count(), ComponentPatch::EMPTY))
-                    .unwrap_or(Slot::EMPTY)
-            })
-            .collect();
-        let carried_item = inventory
-            .carried_item
-            .and_then(|slot| items.get(slot).ok())
-            .map(|item| Slot::new(item.item_id(), item.count(), ComponentPatch::EMPTY))
-            .unwrap_or(Slot::EMPTY);
-
-        let pkt = ClientboundContainerSetContent {
-            container_id: VarInt(0),
-            state_seqno: VarInt((**seqno) as i32),
-            slot_data: slots,
-            carried_item,
-        };
-        con.write_packet(&pkt);
+        send_player_inventory(&mut con, &inventory, *seqno, &items);
     }
 }
 
+/// Send every slot of the player inventory and the cursor stack.
+fn send_player_inventory(
+    con: &mut ServerSideConnection,
+    inventory: &PlayerInventoryQueryItem,
+    seqno: ContainerSeqno,
+    items: &Query<&ItemStack>,
+) {
+    let stack = |slot: Option<Entity>| slot.and_then(|slot| items.get(slot).ok()).copied();
+    set_container_content(
+        con,
+        PLAYER_CONTAINER_ID,
+        seqno,
+        inventory.all_slots().into_iter().map(stack),
+        stack(inventory.carried_item.0),
+    );
+}
+
 fn resync_player(
     mut players: Query<
         (
@@ -624,26 +735,7 @@ fn resync_player(
         });
 
         // Re-send inventory
-        let slots = inventory
-            .all_slots()
-            .iter()
-            .map(|slot| {
-                slot.and_then(|slot| items.get(slot).ok())
-                    .map(|item| Slot::new(item.item_id(), item.count(), ComponentPatch::EMPTY))
-                    .unwrap_or(Slot::EMPTY)
-            })
-            .collect();
-        let carried_item = inventory
-            .carried_item
-            .and_then(|slot| items.get(slot).ok())
-            .map(|item| Slot::new(item.item_id(), item.count(), ComponentPatch::EMPTY))
-            .unwrap_or(Slot::EMPTY);
-        con.write_packet(&ClientboundContainerSetContent {
-            container_id: VarInt(0),
-            state_seqno: VarInt((**seqno) as i32),
-            slot_data: slots,
-            carried_item,
-        });
+        send_player_inventory(&mut con, &inventory, *seqno, &items);
 
         commands.entity(entity).remove::<ResyncPlayer>();
     }
@@ -1,32 +1,38 @@
+use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
+use crate::world::entity::player::HostAnchor;
+use crate::world_border::WorldBorder;
 use bevy_app::{FixedPostUpdate, FixedUpdate, Plugin};
 use bevy_ecs::component::Component;
 use bevy_ecs::prelude::{
-    Changed, DetectChangesMut, Entity, Message, MessageReader, MessageWriter, Mut, On, Query,
+    Changed, DetectChangesMut, Entity, Message, MessageReader, MessageWriter, Mut, On, Query, Res,
 };
 use bevy_math::{DVec3, Quat};
 use mcrs_engine::entity::physics::Transform;
-use mcrs_network::ServerSideConnection;
 use mcrs_network::event::ReceivedPacketEvent;
-use mcrs_protocol::packets::game::clientbound::ClientboundPlayerPosition;
 use mcrs_protocol::packets::game::serverbound::{
-    ServerboundMovePlayerPos, ServerboundMovePlayerPosRot, ServerboundMovePlayerRot,
-    ServerboundMovePlayerStatusOnly,
+    ServerboundAcceptTeleportation, ServerboundMovePlayerPos, ServerboundMovePlayerPosRot,
+    ServerboundMovePlayerRot, ServerboundMovePlayerStatusOnly,
 };
-use mcrs_protocol::{Look, MoveFlags, PositionFlag, WritePacket};
+use mcrs_protocol::{Look, MoveFlags, PositionFlag};
+use tracing::{debug, warn};
+
+/// Squared distance one move packet may cover before it is rejected as a
+/// teleport-like jump, like vanilla's "moved too quickly" check.
+const MAX_MOVE_DISTANCE_SQR: f64 = 100.0;
 
 pub struct MovementPlugin;
 
 impl Plugin for MovementPlugin {
     fn build(&self, app: &mut bevy_app::App) {
         app.add_observer(handle_move_packets);
+        app.add_observer(handle_accept_teleportation);
         app.add_message::<PlayerMovement>();
         app.add_systems(FixedUpdate, process_movement);
         app.add_systems(FixedPostUpdate, teleport);
     }
 }
 
-#[derive(Component, Debug)]
-#[derive(Default)]
+#[derive(Component, Debug, Default)]
 pub struct TeleportState {
     /// Counts up as teleports are made.
     teleport_id_counter: u32,
@@ -38,6 +44,37 @@ pub struct TeleportState {
 }
 
 impl TeleportState {
+    /// State for a player whose client is, or is about to be told it is, at
+    /// `transform`.
+    pub fn synced_to(transform: Transform) -> Self {
+        Self {
+            synced_transform: transform,
+            ..Default::default()
+        }
+    }
+
+    /// Reserve the id for a teleport about to be sent. Moves are ignored
+    /// until the client has confirmed it.
+    pub fn begin_teleport(&mut self) -> i32 {
+        let id = self.teleport_id_counter as i32;
+        self.teleport_id_counter = self.teleport_id_counter.wrapping_add(1);
+        self.pending_teleports = self.pending_teleports.wrapping_add(1);
+        id
+    }
+
+    /// Apply an Accept Teleportation from the client. Teleports are confirmed
+    /// in the order they were sent, so only the oldest pending id counts.
+    pub fn confirm_teleport(&mut self, teleport_id: i32) -> bool {
+        let oldest = self
+            .teleport_id_counter
+            .wrapping_sub(self.pending_teleports);
+        if self.pending_teleports == 0 || teleport_id as u32 != oldest {
+            return false;
+        }
+        self.pending_teleports -= 1;
+        true
+    }
+
     pub fn teleport_id_counter(&self) -> u32 {
         self.teleport_id_counter
     }
@@ -47,6 +84,9 @@ impl TeleportState {
     }
 }
 
+/// Whether the client last reported the player as standing on a block.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct OnGround(pub bool);
 
 fn handle_move_packets(on: On<ReceivedPacketEvent>, mut writer: MessageWriter<PlayerMovement>) {
     let e = on.entity;
@@ -92,9 +132,28 @@ impl PlayerMovement {
     }
 }
 
+fn handle_accept_teleportation(
+    on: On<ReceivedPacketEvent>,
+    mut players: Query<&mut TeleportState>,
+) {
+    let Some(p) = on.decode::<ServerboundAcceptTeleportation>() else {
+        return;
+    };
+    let Ok(mut state) = players.get_mut(on.entity) else {
+        return;
+    };
+    if !state.confirm_teleport(p.teleport_id.0) {
+        debug!(
+            "Player {:?} confirmed unexpected teleport id {}",
+            on.entity, p.teleport_id.0
+        );
+    }
+}
+
 fn process_movement(
     mut reader: MessageReader<PlayerMovement>,
-    mut query: Query<(Mut<TeleportState>, Mut<Transform>)>,
+    mut query: Query<(Mut<TeleportState>, Mut<Transform>, Mut<OnGround>)>,
+    border: Res<WorldBorder>,
 ) {
     const MAX_XZ: f64 = 30_000_000.0;
     const MAX_Y: f64 = 20_000_000.0;
@@ -102,23 +161,52 @@ fn process_movement(
     const MIN_POS: DVec3 = DVec3::new(-MAX_XZ, -MAX_Y, -MAX_XZ);
 
     reader.read().for_each(|m| {
-        let Ok((mut state, mut transform)) = query.get_mut(m.entity) else {
+        let Ok((mut state, mut transform, mut on_ground)) = query.get_mut(m.entity) else {
             return;
         };
-        if let Some(p) = m.position { transform.set_if_neq(transform.with_translation(p.clamp(MIN_POS, MAX_POS))); }
-        if let Some(l) = m.look { transform.set_if_neq(transform.with_rotation(l)); }
-        state.synced_transform = *transform;
+        // Moves sent before the client saw a server teleport would undo it.
+        if state.pending_teleports > 0 {
+            return;
+        }
+        let mut reported = *transform;
+        if let Some(p) = m.position {
+            reported = reported.with_translation(p.clamp(MIN_POS, MAX_POS));
+        }
+        if let Some(l) = m.look {
+            reported = reported.with_rotation(l);
+        }
+        on_ground.set_if_neq(OnGround(m.flags.on_ground()));
+
+        // Rejected or clamped moves keep the client's claim as the synced
+        // transform, so `teleport` sees the difference and corrects the client.
+        if reported.translation.distance_squared(transform.translation) > MAX_MOVE_DISTANCE_SQR {
+            warn!(
+                "Player {:?} moved too quickly to {}",
+                m.entity, reported.translation
+            );
+            transform.set_changed();
+            state.synced_transform = reported;
+            return;
+        }
+        // A position outside the world border is pulled back onto it.
+        let allowed = reported.with_translation(border.clamp(reported.translation));
+        if allowed == reported {
+            transform.set_if_neq(allowed);
+        } else {
+            *transform = allowed;
+        }
+        state.synced_transform = reported;
     })
 }
 
-#[allow(clippy::type_complexity)]
+/// Send the player's position to their client whenever the server-side
+/// transform differs from what the client was last known to have.
 fn teleport(
-    mut clients: Query<
-        (&mut ServerSideConnection, &mut TeleportState, &Transform),
-        Changed<Transform>,
-    >,
+    mut clients: Query<(&HostAnchor, &mut TeleportState, &Transform), Changed<Transform>>,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
 ) {
-    for (mut client, mut state, transform) in &mut clients {
+    use std::sync::atomic::Ordering;
+    for (host_anchor, mut state, transform) in &mut clients {
         let changed_pos = transform.translation != state.synced_transform.translation;
         let changed_y_rot = transform.rotation.y != state.synced_transform.rotation.y;
         let changed_x_rot = transform.rotation.x != state.synced_transform.rotation.x;
@@ -142,31 +230,157 @@ fn teleport(
                 f
             };
 
-            client.write_packet(&ClientboundPlayerPosition {
-                teleport_id: (state.teleport_id_counter as i32).into(),
-                position: if changed_pos {
-                    transform.translation
-                } else {
-                    DVec3::ZERO
-                },
-                velocity: Default::default(),
-                look: Look {
-                    yaw: if changed_y_rot {
-                        transform.rotation.y
+            let teleport_id = state.begin_teleport();
+            packet_writer.write(OutboundPlayerPacket {
+                target: PacketTarget::SinglePlayer(host_anchor.0),
+                priority: PacketPriority::Critical,
+                data: PacketPayload::PlayerPosition {
+                    teleport_id,
+                    position: if changed_pos {
+                        transform.translation
                     } else {
-                        0.0
+                        DVec3::ZERO
                     },
-                    pitch: if changed_x_rot {
-                        transform.rotation.x
-                    } else {
-                        0.0
+                    look: Look {
+                        yaw: if changed_y_rot {
+                            transform.rotation.y
+                        } else {
+                            0.0
+                        },
+                        pitch: if changed_x_rot {
+                            transform.rotation.x
+                        } else {
+                            0.0
+                        },
                     },
+                    flags,
                 },
-                flags,
             });
+            mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+                .fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::message::Messages;
+    use bevy_ecs::schedule::{IntoScheduleConfigs, Schedule};
+    use bevy_ecs::world::World;
+
+    const START: DVec3 = DVec3::new(0.5, 64.0, 0.5);
+
+    struct Harness {
+        world: World,
+        schedule: Schedule,
+        player: Entity,
+    }
+
+    impl Harness {
+        /// A player synced at `START` with no teleport outstanding.
+        fn new() -> Self {
+            let mut world = World::new();
+            world.init_resource::<Messages<PlayerMovement>>();
+            world.init_resource::<Messages<OutboundPlayerPacket>>();
+            world.init_resource::<WorldBorder>();
+            let transform = Transform::default().with_translation(START);
+            let player = world
+                .spawn((
+                    TeleportState::synced_to(transform),
+                    transform,
+                    OnGround::default(),
+                    HostAnchor(Entity::PLACEHOLDER),
+                ))
+                .id();
+            let mut schedule = Schedule::default();
+            schedule.add_systems((process_movement, teleport).chain());
+            Self {
+                world,
+                schedule,
+                player,
+            }
+        }
+
+        /// Feed one move and return the packets sent back to the client.
+        fn step(&mut self, position: DVec3, on_ground: bool) -> Vec<PacketPayload> {
+            let flags = MoveFlags::new().with_on_ground(on_ground);
+            self.world
+                .resource_mut::<Messages<PlayerMovement>>()
+                .write(PlayerMovement::new(
+                    self.player,
+                    Some(position),
+                    None,
+                    flags,
+                ));
+            self.schedule.run(&mut self.world);
+            self.world
+                .resource_mut::<Messages<OutboundPlayerPacket>>()
+                .drain()
+                .map(|packet| packet.data)
+                .collect()
+        }
+
+        fn position(&self) -> DVec3 {
+            self.world
+                .get::<Transform>(self.player)
+                .unwrap()
+                .translation
+        }
 
-            state.pending_teleports = state.pending_teleports.wrapping_add(1);
-            state.teleport_id_counter = state.teleport_id_counter.wrapping_add(1);
+        fn state(&mut self) -> Mut<'_, TeleportState> {
+            self.world.get_mut::<TeleportState>(self.player).unwrap()
         }
     }
+
+    #[test]
+    fn walking_updates_the_transform_without_a_resync() {
+        let mut harness = Harness::new();
+        let step = DVec3::new(0.8, 64.0, 0.5);
+        assert!(harness.step(step, true).is_empty());
+        assert_eq!(harness.position(), step);
+        assert_eq!(
+            harness.world.get::<OnGround>(harness.player),
+            Some(&OnGround(true))
+        );
+
+        let jump = DVec3::new(0.8, 65.2, 0.5);
+        assert!(harness.step(jump, false).is_empty());
+        assert_eq!(harness.position(), jump);
+        assert_eq!(
+            harness.world.get::<OnGround>(harness.player),
+            Some(&OnGround(false))
+        );
+    }
+
+    #[test]
+    fn superspeed_move_is_rejected_and_resynced() {
+        let mut harness = Harness::new();
+        let sent = harness.step(DVec3::new(40.0, 64.0, 0.5), true);
+        assert_eq!(harness.position(), START);
+        let [
+            PacketPayload::PlayerPosition {
+                teleport_id,
+                position,
+                ..
+            },
+        ] = sent.as_slice()
+        else {
+            panic!("expected one position resync, got {sent:?}");
+        };
+        assert_eq!(*position, START);
+        assert_eq!(harness.state().pending_teleports(), 1);
+
+        // A move the client sent before it saw the resync is dropped.
+        assert!(harness.step(DVec3::new(41.0, 64.0, 0.5), true).is_empty());
+        assert_eq!(harness.position(), START);
+
+        // Once the teleport is confirmed, moves from the resynced position
+        // are accepted again.
+        assert!(!harness.state().confirm_teleport(teleport_id + 1));
+        assert!(harness.state().confirm_teleport(*teleport_id));
+        let step = DVec3::new(1.0, 64.0, 0.5);
+        assert!(harness.step(step, true).is_empty());
+        assert_eq!(harness.position(), step);
+    }
 }
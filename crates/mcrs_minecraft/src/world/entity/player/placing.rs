@@ -1,6 +1,5 @@
 use crate::world::inventory::PlayerHotbarSlots;
 use crate::world::item::ItemStack;
-use mcrs_minecraft_block::block::BlockUpdateFlags;
 use mcrs_minecraft_block::block_update::BlockSetRequest;
 use bevy_app::{App, Plugin};
 use bevy_ecs::message::MessageWriter;
@@ -76,11 +75,9 @@ fn handle_use_item_on(
         Direction::Down => return,
     };
 
-    writer.write(BlockSetRequest {
-        dimension: dim.entity(),
-        pos: place_pos,
-        new_state: BlockStateId(state_id),
-        flags: BlockUpdateFlags::all(),
-        recursion_left: 512,
-    });
+    writer.write(BlockSetRequest::set_block(
+        dim.entity(),
+        place_pos,
+        BlockStateId(state_id),
+    ));
 }
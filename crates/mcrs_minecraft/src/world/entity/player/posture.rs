@@ -0,0 +1,144 @@
+//! Sneaking and sprinting. The client reports sprinting in Player Command and
+//! sneaking as the shift key of Player Input; either change is shown to the
+//! players who can see the player as Set Entity Metadata.
+
+use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
+use crate::world::entity::player::HostAnchor;
+use bevy_app::{App, Plugin};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::MessageWriter;
+use bevy_ecs::prelude::{Component, On, Query, With};
+use mcrs_engine::entity::PlayerSynchronizedEntities;
+use mcrs_engine::entity::player::Player;
+use mcrs_network::event::ReceivedPacketEvent;
+use mcrs_protocol::entity::player::PlayerCommandAction;
+use mcrs_protocol::entity::{EntityMetadata, MetaDataValue, Pose};
+use mcrs_protocol::packets::game::serverbound::{ServerboundPlayerCommand, ServerboundPlayerInput};
+use std::sync::atomic::Ordering;
+
+/// Metadata index of the entity flags byte (vanilla `Entity.DATA_SHARED_FLAGS_ID`).
+pub const SHARED_FLAGS_INDEX: u8 = 0;
+/// Metadata index of the entity pose (vanilla `Entity.DATA_POSE`).
+pub const POSE_INDEX: u8 = 6;
+
+const FLAG_CROUCHING: i8 = 0x02;
+const FLAG_SPRINTING: i8 = 0x08;
+
+pub struct PosturePlugin;
+
+impl Plugin for PosturePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(handle_player_command);
+        app.add_observer(handle_player_input);
+    }
+}
+
+/// Whether a player is sneaking or sprinting, as its client last reported.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PlayerPosture {
+    pub sneaking: bool,
+    pub sprinting: bool,
+}
+
+impl PlayerPosture {
+    pub fn pose(self) -> Pose {
+        if self.sneaking {
+            Pose::Crouching
+        } else {
+            Pose::Standing
+        }
+    }
+
+    /// The flags byte and pose, for Set Entity Metadata.
+    pub fn metadata(self) -> EntityMetadata<'static> {
+        self.append_to(EntityMetadata::new())
+    }
+
+    /// `metadata` with the crouching and sprinting bits of its flags byte and
+    /// the pose set. Other flag bits already in `metadata` are kept.
+    pub fn append_to<'a>(self, metadata: EntityMetadata<'a>) -> EntityMetadata<'a> {
+        let mut flags = match metadata.get(SHARED_FLAGS_INDEX) {
+            Some(MetaDataValue::Byte(flags)) => *flags & !(FLAG_CROUCHING | FLAG_SPRINTING),
+            _ => 0,
+        };
+        if self.sneaking {
+            flags |= FLAG_CROUCHING;
+        }
+        if self.sprinting {
+            flags |= FLAG_SPRINTING;
+        }
+        metadata
+            .with_byte(SHARED_FLAGS_INDEX, flags)
+            .with_pose(POSE_INDEX, self.pose())
+    }
+}
+
+fn handle_player_command(
+    event: On<ReceivedPacketEvent>,
+    mut players: Query<&mut PlayerPosture, With<Player>>,
+    viewers: Query<(&HostAnchor, &PlayerSynchronizedEntities), With<Player>>,
+    packet_writer: MessageWriter<OutboundPlayerPacket>,
+) {
+    let Some(pkt) = event.decode::<ServerboundPlayerCommand>() else {
+        return;
+    };
+    let sprinting = match pkt.action {
+        PlayerCommandAction::StartSprinting => true,
+        PlayerCommandAction::StopSprinting => false,
+        _ => return,
+    };
+    let Ok(mut posture) = players.get_mut(event.entity) else {
+        return;
+    };
+    if posture.sprinting != sprinting {
+        posture.sprinting = sprinting;
+        broadcast_posture(event.entity, *posture, &viewers, packet_writer);
+    }
+}
+
+fn handle_player_input(
+    event: On<ReceivedPacketEvent>,
+    mut players: Query<&mut PlayerPosture, With<Player>>,
+    viewers: Query<(&HostAnchor, &PlayerSynchronizedEntities), With<Player>>,
+    packet_writer: MessageWriter<OutboundPlayerPacket>,
+) {
+    let Some(pkt) = event.decode::<ServerboundPlayerInput>() else {
+        return;
+    };
+    let Ok(mut posture) = players.get_mut(event.entity) else {
+        return;
+    };
+    let sneaking = pkt.input.shift();
+    if posture.sneaking != sneaking {
+        posture.sneaking = sneaking;
+        broadcast_posture(event.entity, *posture, &viewers, packet_writer);
+    }
+}
+
+/// Send `posture` as Set Entity Metadata to every player `player` is visible
+/// to. The player's own client already shows its posture.
+fn broadcast_posture(
+    player: Entity,
+    posture: PlayerPosture,
+    viewers: &Query<(&HostAnchor, &PlayerSynchronizedEntities), With<Player>>,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
+) {
+    let entity_id = player.index_u32() as i32;
+    let mut sent = 0;
+    for (host_anchor, synced_entities) in viewers {
+        if !synced_entities.contains(&player) {
+            continue;
+        }
+        packet_writer.write(OutboundPlayerPacket {
+            target: PacketTarget::SinglePlayer(host_anchor.0),
+            priority: PacketPriority::Normal,
+            data: PacketPayload::EntityData {
+                entity_id,
+                metadata: posture.metadata(),
+            },
+        });
+        sent += 1;
+    }
+    mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+        .fetch_add(sent, Ordering::Relaxed);
+}
@@ -0,0 +1,69 @@
+//! Respawning after death. A dead player's client shows the death screen
+//! until the player asks to respawn with Client Command; the player is then
+//! moved to the spawn point the way a dimension change moves it, as a fresh
+//! in-dim entity the client is told about with Respawn.
+
+use crate::login::GameProfile;
+use crate::spawn_point::SpawnPoint;
+use crate::world::bus::{OutboundPlayerTransferRequest, PlayerTransferSnapshot, SpawnReason};
+use crate::world::entity::player::HostAnchor;
+use crate::world::entity::player::ability::PlayerGameMode;
+use bevy_app::{App, Plugin};
+use bevy_ecs::message::MessageWriter;
+use bevy_ecs::prelude::{Component, On, Query, Res, With};
+use mcrs_engine::entity::player::Player;
+use mcrs_network::event::ReceivedPacketEvent;
+use mcrs_protocol::entity::player::ClientCommandAction;
+use mcrs_protocol::packets::game::serverbound::ServerboundClientCommand;
+use tracing::debug;
+
+pub struct RespawnPlugin;
+
+impl Plugin for RespawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(handle_client_command);
+    }
+}
+
+/// Marks a player that has died and not yet respawned. The respawned player
+/// is a new entity, so nothing removes it.
+#[derive(Component, Clone, Copy, Debug, Default)]
+#[component(storage = "SparseSet")]
+pub struct Dead;
+
+/// Per-dim: respawn a dead player at the spawn point when its client asks.
+/// Like vanilla, a request from a living player is ignored.
+fn handle_client_command(
+    event: On<ReceivedPacketEvent>,
+    players: Query<(&HostAnchor, &GameProfile, &PlayerGameMode), (With<Player>, With<Dead>)>,
+    spawn_point: Res<SpawnPoint>,
+    mut requests: MessageWriter<OutboundPlayerTransferRequest>,
+) {
+    let Some(pkt) = event.decode::<ServerboundClientCommand>() else {
+        return;
+    };
+    if pkt.action != ClientCommandAction::PerformRespawn {
+        return;
+    }
+    let Ok((host_anchor, profile, game_mode)) = players.get(event.entity) else {
+        return;
+    };
+    debug!(
+        target: "mcrs_minecraft::player",
+        host_anchor = ?host_anchor.0,
+        dimension = %spawn_point.dimension,
+        "respawning dead player at the spawn point"
+    );
+    requests.write(OutboundPlayerTransferRequest {
+        host_anchor: host_anchor.0,
+        dim_name: spawn_point.dimension.as_str().to_owned(),
+        snapshot: PlayerTransferSnapshot {
+            uuid: profile.id,
+            username: profile.username.clone(),
+            position: spawn_point.player_position(),
+            rotation: spawn_point.rotation(),
+            game_mode: game_mode.0,
+        },
+        reason: SpawnReason::Death,
+    });
+}
@@ -0,0 +1,143 @@
+//! Set Entity Velocity: an entity's [`Velocity`] reaches its own client and
+//! every player it is visible to every [`UpdateInterval`] ticks, or at once
+//! after a [`VelocityImpulse`]. Players that see the entity later get its
+//! velocity with the spawn.
+
+use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
+use crate::world::entity::player::HostAnchor;
+use bevy_app::{App, FixedPostUpdate, Plugin};
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::MessageWriter;
+use bevy_ecs::prelude::{Commands, Has, Query, With};
+use bevy_math::DVec3;
+use mcrs_engine::entity::PlayerSynchronizedEntities;
+use mcrs_engine::entity::physics::Velocity;
+use mcrs_engine::entity::player::Player;
+use std::sync::atomic::Ordering;
+
+/// How far, squared, the velocity has to move from the one last sent before
+/// it is sent again (vanilla `ServerEntity`).
+const MIN_CHANGE_SQUARED: f64 = 1.0e-7;
+
+pub struct VelocitySyncPlugin;
+
+impl Plugin for VelocitySyncPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedPostUpdate, sync_velocity);
+    }
+}
+
+/// How many ticks apart an entity's velocity is sent (vanilla
+/// `EntityType.updateInterval`). Players without one use
+/// [`PLAYER`](Self::PLAYER), other entities the vanilla default.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpdateInterval(pub u32);
+
+impl UpdateInterval {
+    pub const PLAYER: Self = Self(2);
+    pub const ITEM: Self = Self(20);
+}
+
+impl Default for UpdateInterval {
+    fn default() -> Self {
+        Self(3)
+    }
+}
+
+/// Marks a velocity set from outside the entity's own movement, like a
+/// knockback, so it is sent the same tick (vanilla `hurtMarked`). Removed
+/// once sent.
+#[derive(Component, Debug, Default, Clone, Copy)]
+#[component(storage = "SparseSet")]
+pub struct VelocityImpulse;
+
+/// The velocity viewers were last sent, and the ticks since the entity was
+/// first synced.
+#[derive(Component, Debug, Clone, Copy)]
+struct SentVelocity {
+    velocity: DVec3,
+    ticks: u32,
+}
+
+/// Send Set Entity Velocity on an entity's update interval when its velocity
+/// moved meaningfully from the one last sent, and after every impulse.
+/// Coming to a stop is always sent, so the client does not keep drifting on
+/// a tiny leftover velocity.
+///
+/// Short of an impulse, an entity's first tick here only records its
+/// velocity: the spawn already carried it to the players seeing it.
+#[allow(clippy::type_complexity)]
+fn sync_velocity(
+    mut entities: Query<(
+        Entity,
+        &Velocity,
+        Option<&mut SentVelocity>,
+        Option<&UpdateInterval>,
+        Has<Player>,
+        Has<VelocityImpulse>,
+        Option<&HostAnchor>,
+    )>,
+    viewers: Query<(&HostAnchor, &PlayerSynchronizedEntities), With<Player>>,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
+    mut commands: Commands,
+) {
+    for (entity, velocity, mut last_sent, update_interval, is_player, impulse, own_anchor) in
+        &mut entities
+    {
+        let update_interval = match update_interval {
+            Some(update_interval) => *update_interval,
+            None if is_player => UpdateInterval::PLAYER,
+            None => UpdateInterval::default(),
+        };
+        let (due, last) = match last_sent.as_deref_mut() {
+            Some(last_sent) => {
+                let due = last_sent.ticks % update_interval.0.max(1) == 0;
+                last_sent.ticks = last_sent.ticks.wrapping_add(1);
+                (due, last_sent.velocity)
+            }
+            None => (false, velocity.0),
+        };
+        let change = velocity.distance_squared(last);
+        let stopped = change > 0.0 && velocity.length_squared() == 0.0;
+        let send = impulse || (due && (change > MIN_CHANGE_SQUARED || stopped));
+        match last_sent {
+            Some(mut last_sent) if send => last_sent.velocity = velocity.0,
+            Some(_) => {}
+            None => {
+                commands.entity(entity).insert(SentVelocity {
+                    velocity: velocity.0,
+                    ticks: 1,
+                });
+            }
+        }
+        if impulse {
+            commands.entity(entity).remove::<VelocityImpulse>();
+        }
+        if !send {
+            continue;
+        }
+
+        let entity_id = entity.index_u32() as i32;
+        let targets = own_anchor.into_iter().chain(
+            viewers
+                .iter()
+                .filter(|(_, synced_entities)| synced_entities.contains(&entity))
+                .map(|(host_anchor, _)| host_anchor),
+        );
+        let mut sent = 0;
+        for host_anchor in targets {
+            packet_writer.write(OutboundPlayerPacket {
+                target: PacketTarget::SinglePlayer(host_anchor.0),
+                priority: PacketPriority::Normal,
+                data: PacketPayload::EntityVelocity {
+                    entity_id,
+                    velocity: velocity.0,
+                },
+            });
+            sent += 1;
+        }
+        mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+            .fetch_add(sent, Ordering::Relaxed);
+    }
+}
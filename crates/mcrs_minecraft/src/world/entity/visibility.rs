@@ -0,0 +1,274 @@
+//! What a player's client is told about the entities around it. The engine's
+//! [`VisibilitySystem`] keeps each player's visible set; the observers here
+//! turn its events into Spawn Entity, Set Entity Metadata, Remove Entities and
+//! the movement packets, addressed to the viewer's host anchor. Arm swings go
+//! out as Entity Animation to every player the swinger is visible to. A
+//! spawned entity's attributes follow its metadata as Update Attributes.
+//!
+//! [`VisibilitySystem`]: mcrs_engine::entity::VisibilitySystem
+
+use crate::login::GameProfile;
+use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
+use crate::world::entity::MinecraftEntityType;
+use crate::world::entity::attribute::Attributes;
+use crate::world::entity::player::movement::OnGround;
+use crate::world::entity::player::posture::PlayerPosture;
+use crate::world::entity::player::{HostAnchor, PlayerSkinParts};
+use bevy_app::{App, Plugin};
+use bevy_ecs::message::MessageWriter;
+use bevy_ecs::prelude::{On, Query};
+use bevy_ecs::query::With;
+use bevy_math::DVec3;
+use mcrs_engine::entity::physics::{OldTransform, Transform, Velocity};
+use mcrs_engine::entity::player::Player;
+use mcrs_engine::entity::player::reposition::Reposition;
+use mcrs_engine::entity::{
+    EntityNetworkAddEvent, EntityNetworkRemoveEvent, EntityNetworkSyncEvent,
+    PlayerSynchronizedEntities,
+};
+use mcrs_network::event::ReceivedPacketEvent;
+use mcrs_protocol::entity::EntityMetadata;
+use mcrs_protocol::packets::game::clientbound::ClientboundAnimate;
+use mcrs_protocol::packets::game::serverbound::ServerboundSwing;
+use mcrs_protocol::{Hand, Look};
+use smallvec::smallvec;
+use std::sync::atomic::Ordering;
+
+/// Metadata index of the skin layers a player shows (vanilla
+/// `Avatar.DATA_PLAYER_MODE_CUSTOMISATION`).
+pub const PLAYER_SKIN_PARTS_INDEX: u8 = 16;
+
+/// Update Entity Position moves in steps of 1/4096 block.
+const DELTA_SCALE: f64 = 4096.0;
+
+pub struct EntityVisibilityPlugin;
+
+impl Plugin for EntityVisibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(spawn_player);
+        app.add_observer(remove_entity);
+        app.add_observer(sync_position);
+        app.add_observer(broadcast_swing);
+    }
+}
+
+fn spawn_player(
+    event: On<EntityNetworkAddEvent>,
+    players: Query<
+        (
+            &GameProfile,
+            &Transform,
+            &PlayerSkinParts,
+            Option<&PlayerPosture>,
+            Option<&Attributes>,
+            Option<&Velocity>,
+        ),
+        With<Player>,
+    >,
+    viewers: Query<(&HostAnchor, &Reposition), With<Player>>,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
+) {
+    let Ok((profile, transform, skin_parts, posture, attributes, velocity)) =
+        players.get(event.entity)
+    else {
+        return;
+    };
+    let Ok((host_anchor, reposition)) = viewers.get(event.player) else {
+        return;
+    };
+
+    let entity_id = event.entity.index_u32() as i32;
+    let target = PacketTarget::SinglePlayer(host_anchor.0);
+    let position = reposition.convert_dvec3(transform.translation);
+    let look = look(transform);
+    let velocity = velocity.map_or(DVec3::ZERO, |velocity| velocity.0);
+    let mut metadata =
+        EntityMetadata::new().with_byte(PLAYER_SKIN_PARTS_INDEX, skin_parts.0.into_bits() as i8);
+    // Like vanilla, only what differs from a fresh entity.
+    if let Some(&posture) = posture.filter(|&&posture| posture != PlayerPosture::default()) {
+        metadata = posture.append_to(metadata);
+    }
+    packet_writer.write(OutboundPlayerPacket {
+        target: target.clone(),
+        priority: PacketPriority::Normal,
+        data: PacketPayload::PlayerEnteredView {
+            entity_id,
+            uuid: profile.id,
+            kind: MinecraftEntityType::Player as i32,
+            position,
+            velocity,
+            yaw: look.yaw,
+            pitch: look.pitch,
+            metadata,
+        },
+    });
+    let mut sent = 2;
+    let attributes = attributes.map(Attributes::snapshots).unwrap_or_default();
+    if !attributes.is_empty() {
+        packet_writer.write(OutboundPlayerPacket {
+            target: target.clone(),
+            priority: PacketPriority::Normal,
+            data: PacketPayload::UpdateAttributes {
+                entity_id,
+                attributes,
+            },
+        });
+        sent += 1;
+    }
+    // Spawn Entity only carries byte angles; the position sync right after
+    // gives the viewer the exact look, and is what later deltas build on.
+    packet_writer.write(OutboundPlayerPacket {
+        target,
+        priority: PacketPriority::Normal,
+        data: PacketPayload::EntityPosSync {
+            entity_id,
+            position,
+            velocity,
+            look,
+            on_ground: true,
+        },
+    });
+    mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+        .fetch_add(sent, Ordering::Relaxed);
+}
+
+/// Any entity kind leaves view the same way. The entity may already be
+/// gone, so only its id is used.
+fn remove_entity(
+    event: On<EntityNetworkRemoveEvent>,
+    viewers: Query<&HostAnchor, With<Player>>,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
+) {
+    let Ok(host_anchor) = viewers.get(event.player) else {
+        return;
+    };
+    packet_writer.write(OutboundPlayerPacket {
+        target: PacketTarget::SinglePlayer(host_anchor.0),
+        priority: PacketPriority::Normal,
+        data: PacketPayload::PlayerLeftView {
+            entity_ids: smallvec![event.entity.index_u32() as i32],
+        },
+    });
+    mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Send a visible entity's move since last tick as a delta, falling back to
+/// an absolute position sync when the move is too long for one or nothing
+/// moved at all (the periodic resync). The sync carries the entity's
+/// velocity, which the client takes over.
+fn sync_position(
+    event: On<EntityNetworkSyncEvent>,
+    entities: Query<(
+        &Transform,
+        &OldTransform,
+        Option<&OnGround>,
+        Option<&Velocity>,
+    )>,
+    viewers: Query<(&HostAnchor, &Reposition), With<Player>>,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
+) {
+    let Ok((transform, old_transform, on_ground, velocity)) = entities.get(event.entity) else {
+        return;
+    };
+    let Ok((host_anchor, reposition)) = viewers.get(event.player) else {
+        return;
+    };
+
+    let entity_id = event.entity.index_u32() as i32;
+    let on_ground = on_ground.is_none_or(|on_ground| on_ground.0);
+    let turned = transform.rotation.x != old_transform.rotation.x
+        || transform.rotation.y != old_transform.rotation.y;
+    let data = match position_delta(old_transform.translation, transform.translation) {
+        Some(delta) if turned || delta != [0; 3] => PacketPayload::EntityMove {
+            entity_id,
+            delta,
+            look: turned.then(|| look(transform)),
+            on_ground,
+        },
+        _ => PacketPayload::EntityPosSync {
+            entity_id,
+            position: reposition.convert_dvec3(transform.translation),
+            velocity: velocity.map_or(DVec3::ZERO, |velocity| velocity.0),
+            look: look(transform),
+            on_ground,
+        },
+    };
+    packet_writer.write(OutboundPlayerPacket {
+        target: PacketTarget::SinglePlayer(host_anchor.0),
+        priority: PacketPriority::Normal,
+        data,
+    });
+    mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Show a player's arm swing to everyone who can see the player. The swinger
+/// animates its own arm client-side, so it is not sent back.
+fn broadcast_swing(
+    event: On<ReceivedPacketEvent>,
+    viewers: Query<(&HostAnchor, &PlayerSynchronizedEntities), With<Player>>,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
+) {
+    let Some(swing) = event.decode::<ServerboundSwing>() else {
+        return;
+    };
+    let action = match swing.hand {
+        Hand::Main => ClientboundAnimate::SWING_MAIN_HAND,
+        Hand::Off => ClientboundAnimate::SWING_OFF_HAND,
+    };
+    let entity_id = event.entity.index_u32() as i32;
+    let mut sent = 0;
+    for (host_anchor, synced_entities) in &viewers {
+        if !synced_entities.contains(&event.entity) {
+            continue;
+        }
+        packet_writer.write(OutboundPlayerPacket {
+            target: PacketTarget::SinglePlayer(host_anchor.0),
+            priority: PacketPriority::Normal,
+            data: PacketPayload::EntityAnimation { entity_id, action },
+        });
+        sent += 1;
+    }
+    mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+        .fetch_add(sent, Ordering::Relaxed);
+}
+
+/// The move from `old` to `new` in Update Entity Position units, or `None`
+/// when an axis moved more than the ~8 blocks an `i16` holds. Both ends are
+/// rounded before subtracting, as vanilla does, so rounding never drifts.
+fn position_delta(old: DVec3, new: DVec3) -> Option<[i16; 3]> {
+    let axis = |old: f64, new: f64| {
+        let delta = (new * DELTA_SCALE).round() as i64 - (old * DELTA_SCALE).round() as i64;
+        i16::try_from(delta).ok()
+    };
+    Some([
+        axis(old.x, new.x)?,
+        axis(old.y, new.y)?,
+        axis(old.z, new.z)?,
+    ])
+}
+
+fn look(transform: &Transform) -> Look {
+    Look {
+        yaw: transform.rotation.y,
+        pitch: transform.rotation.x,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_moves_fit_a_delta_and_long_ones_do_not() {
+        let old = DVec3::new(0.5, 64.0, -0.5);
+        assert_eq!(
+            position_delta(old, old + DVec3::new(1.0, -0.5, 0.25)),
+            Some([4096, -2048, 1024])
+        );
+        assert_eq!(
+            position_delta(old, old + DVec3::new(7.9, 0.0, 0.0)),
+            Some([32358, 0, 0])
+        );
+        assert_eq!(position_delta(old, old + DVec3::new(0.0, 0.0, -8.5)), None);
+    }
+}
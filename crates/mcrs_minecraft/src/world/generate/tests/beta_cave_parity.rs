@@ -178,6 +178,11 @@ impl Random for CountingRng {
         self.inner.next_u32_bound(bound)
     }
 
+    fn next_u64_bound(&mut self, bound: u64) -> u64 {
+        self.inc();
+        self.inner.next_u64_bound(bound)
+    }
+
     fn next_f32(&mut self) -> f32 {
         self.inc();
         self.inner.next_f32()
@@ -190,6 +195,11 @@ impl Random for CountingRng {
         self.inner.next_f64()
     }
 
+    fn next_gaussian(&mut self) -> f64 {
+        self.inc();
+        self.inner.next_gaussian()
+    }
+
     fn fork(&mut self) -> Self {
         self.inc();
         CountingRng {
@@ -395,6 +405,7 @@ fn build_beta_router() -> mcrs_minecraft_worldgen::density_function::NoiseRouter
         mcrs_protocol::BlockStateId(1),
         mcrs_protocol::BlockStateId(86),
     )
+    .unwrap()
 }
 
 fn build_beta_biome_source() -> (BiomeSource, RegistrySnapshot<Biome>) {
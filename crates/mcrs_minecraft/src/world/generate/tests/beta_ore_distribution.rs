@@ -60,6 +60,10 @@ impl Random for CountingRng {
         self.inc();
         self.inner.next_u32_bound(bound)
     }
+    fn next_u64_bound(&mut self, bound: u64) -> u64 {
+        self.inc();
+        self.inner.next_u64_bound(bound)
+    }
     fn next_f32(&mut self) -> f32 {
         self.inc();
         self.inner.next_f32()
@@ -69,6 +73,10 @@ impl Random for CountingRng {
         self.inc();
         self.inner.next_f64()
     }
+    fn next_gaussian(&mut self) -> f64 {
+        self.inc();
+        self.inner.next_gaussian()
+    }
     fn fork(&mut self) -> Self {
         self.inc();
         CountingRng { inner: self.inner.fork(), draws: self.draws.clone() }
@@ -363,6 +363,7 @@ fn build_beta_router() -> mcrs_minecraft_worldgen::density_function::NoiseRouter
         mcrs_protocol::BlockStateId(1),
         mcrs_protocol::BlockStateId(86),
     )
+    .unwrap()
 }
 
 fn build_beta_biome_source() -> (BiomeSource, RegistrySnapshot<Biome>) {
@@ -0,0 +1,641 @@
+//! Server side of the Click Container packet for the player's own inventory.
+//!
+//! The click is applied to the slot entities, then compared with the result
+//! the client predicted and sent along with the click. Slots the client got
+//! wrong are corrected one by one; a click made against a stale state id, or
+//! one the server does not understand, resends the whole container.
+
+use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
+use crate::world::entity::item::ItemEntityBundle;
+use crate::world::entity::player::HostAnchor;
+use crate::world::entity::player::ability::PlayerGameMode;
+use crate::world::entity::player::posture::PlayerPosture;
+use crate::world::inventory::{
+    ContainerSeqno, PLAYER_CONTAINER_ID, PLAYER_CONTAINER_SIZE, PlayerInventoryMut,
+    PlayerInventoryQuery,
+};
+use crate::world::item::{Item, ItemStack};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::Messages;
+use bevy_ecs::world::World;
+use bevy_math::DVec3;
+use mcrs_engine::entity::physics::Transform;
+use mcrs_engine::world::dimension::InDimension;
+use mcrs_protocol::item::{ContainerInput, HashedSlot};
+use mcrs_protocol::packets::game::serverbound::ServerboundContainerClick;
+use mcrs_protocol::{GameMode, ItemId, Slot};
+use std::ops::Range;
+
+const CRAFTING_RESULT: usize = 0;
+const ARMOR: Range<usize> = 5..9;
+const MAIN_INVENTORY: Range<usize> = 9..36;
+const HOTBAR: Range<usize> = 36..45;
+const OFFHAND: usize = 45;
+
+/// Slot index of a click outside the window, which drops the cursor stack.
+const OUTSIDE: i16 = -999;
+/// Swap button of the offhand key; 0-8 are the hotbar number keys.
+const OFFHAND_BUTTON: u8 = 40;
+/// Height above the player's feet that thrown stacks leave from, vanilla's
+/// eye height minus 0.3.
+const THROW_HEIGHT: f64 = 1.62 - 0.3;
+/// [`THROW_HEIGHT`] while sneaking, which lowers the eyes to 1.27.
+const SNEAKING_THROW_HEIGHT: f64 = 1.27 - 0.3;
+
+/// Apply `click` to the inventory of `player` and correct the client where
+/// its prediction differs from the result.
+pub fn click_container(world: &mut World, player: Entity, click: &ServerboundContainerClick) {
+    // No other container can be open yet.
+    if click.container_id.0 != PLAYER_CONTAINER_ID {
+        return;
+    }
+    let Some(&seqno) = world.get::<ContainerSeqno>(player) else {
+        return;
+    };
+    let Some((slots, carried)) = read_slots(world, player) else {
+        return;
+    };
+    let spectator = world
+        .get::<PlayerGameMode>(player)
+        .is_some_and(|mode| mode.0 == GameMode::Spectator);
+    if spectator || click.state_seqno.0 != seqno.0 as i32 {
+        send_content(world, player);
+        return;
+    }
+
+    let before: Vec<_> = slots.iter().map(|&slot| stack(world, slot)).collect();
+    let mut state = ClickState {
+        world: &mut *world,
+        player,
+        slots,
+        carried,
+    };
+    let applied = state.apply(click);
+    let ClickState { slots, carried, .. } = state;
+    write_slots(world, player, &slots, carried);
+    if !applied {
+        send_content(world, player);
+        return;
+    }
+
+    // What the client shows now: its own predictions, untouched slots as
+    // they were.
+    let mut predicted: Vec<_> = before.iter().map(|&stack| key(stack)).collect();
+    for (index, claim) in &click.changed_slots {
+        let Some(slot) = predicted.get_mut(*index as usize) else {
+            send_content(world, player);
+            return;
+        };
+        *slot = claim_key(claim.as_ref());
+    }
+    if key(stack(world, carried)) != claim_key(click.carried_item.as_ref()) {
+        send_content(world, player);
+        return;
+    }
+    for (index, &slot) in slots.iter().enumerate() {
+        let actual = stack(world, slot);
+        if key(actual) != predicted[index] {
+            send_slot(world, player, index as i16, actual);
+        }
+    }
+}
+
+/// The slots of the player container in protocol order, and the cursor.
+/// Entities that do not hold a stack read as empty.
+fn read_slots(world: &mut World, player: Entity) -> Option<(Vec<Option<Entity>>, Option<Entity>)> {
+    let mut query = world.query::<PlayerInventoryQuery>();
+    let inventory = query.get(world, player).ok()?;
+    let slots = inventory.all_slots();
+    let carried = inventory.carried_item.0;
+    let valid = |slot: Option<Entity>| slot.filter(|&e| world.get::<ItemStack>(e).is_some());
+    Some((slots.into_iter().map(valid).collect(), valid(carried)))
+}
+
+fn write_slots(
+    world: &mut World,
+    player: Entity,
+    slots: &[Option<Entity>],
+    carried: Option<Entity>,
+) {
+    let Some(current) = world
+        .query::<PlayerInventoryQuery>()
+        .get(world, player)
+        .ok()
+        .map(|inventory| inventory.all_slots())
+    else {
+        return;
+    };
+    let mut query = world.query::<PlayerInventoryMut>();
+    let Ok(mut inventory) = query.get_mut(world, player) else {
+        return;
+    };
+    // Only touch the slots that changed, so change detection on the other
+    // slot components stays quiet.
+    for (index, &entity) in slots.iter().enumerate() {
+        if current.get(index) != Some(&entity)
+            && let Some(slot) = inventory.slot_mut(index)
+        {
+            *slot = entity;
+        }
+    }
+    if inventory.carried_item.0 != carried {
+        inventory.carried_item.0 = carried;
+    }
+}
+
+fn stack(world: &World, slot: Option<Entity>) -> Option<ItemStack> {
+    world.get::<ItemStack>(slot?).copied()
+}
+
+/// What the client can tell apart about a stack. Components are not stored
+/// server side yet, so their hashes are not compared.
+fn key(stack: Option<ItemStack>) -> Option<(ItemId, u8)> {
+    stack.map(|stack| (stack.item_id(), stack.count()))
+}
+
+fn claim_key(claim: Option<&HashedSlot>) -> Option<(ItemId, u8)> {
+    claim
+        .filter(|slot| slot.count > 0)
+        .map(|slot| (slot.id, slot.count))
+}
+
+fn max_stack_size(stack: ItemStack) -> u8 {
+    <&'static Item>::try_from(stack.item_id()).map_or(64, |item| item.components.max_stack_size.0)
+}
+
+/// Whether a player may put a stack into `index`. The crafting result is
+/// output only, and armor slots are left to equipment handling.
+fn may_place(index: usize) -> bool {
+    index != CRAFTING_RESULT && !ARMOR.contains(&index)
+}
+
+fn send(world: &mut World, player: Entity, data: PacketPayload) {
+    let Some(&HostAnchor(host)) = world.get::<HostAnchor>(player) else {
+        return;
+    };
+    world
+        .resource_mut::<Messages<OutboundPlayerPacket>>()
+        .write(OutboundPlayerPacket {
+            target: PacketTarget::SinglePlayer(host),
+            priority: PacketPriority::Normal,
+            data,
+        });
+}
+
+fn next_seqno(world: &mut World, player: Entity) -> i32 {
+    world
+        .get_mut::<ContainerSeqno>(player)
+        .map_or(0, |mut seqno| seqno.increment().0 as i32)
+}
+
+/// Resend every slot and the cursor under a new state id.
+fn send_content(world: &mut World, player: Entity) {
+    let Some((slots, carried)) = read_slots(world, player) else {
+        return;
+    };
+    let slots = slots
+        .into_iter()
+        .map(|slot| stack(world, slot).map_or(Slot::EMPTY, Slot::from))
+        .collect();
+    let carried = stack(world, carried).map_or(Slot::EMPTY, Slot::from);
+    let state_seqno = next_seqno(world, player);
+    send(
+        world,
+        player,
+        PacketPayload::ContainerSetContent {
+            container_id: PLAYER_CONTAINER_ID,
+            state_seqno,
+            slots,
+            carried,
+        },
+    );
+}
+
+fn send_slot(world: &mut World, player: Entity, slot: i16, stack: Option<ItemStack>) {
+    let state_seqno = next_seqno(world, player);
+    send(
+        world,
+        player,
+        PacketPayload::ContainerSetSlot {
+            container_id: PLAYER_CONTAINER_ID,
+            state_seqno,
+            slot,
+            item: stack.map_or(Slot::EMPTY, Slot::from),
+        },
+    );
+}
+
+/// The player container while a click is applied: slots in protocol order,
+/// each holding an entity with an [`ItemStack`].
+struct ClickState<'w> {
+    world: &'w mut World,
+    player: Entity,
+    slots: Vec<Option<Entity>>,
+    carried: Option<Entity>,
+}
+
+impl ClickState<'_> {
+    /// Apply `click`, returning `false` for clicks that are not understood.
+    fn apply(&mut self, click: &ServerboundContainerClick) -> bool {
+        let index = usize::try_from(click.slot_index)
+            .ok()
+            .filter(|&index| index < PLAYER_CONTAINER_SIZE);
+        match (click.container_input, index, click.button) {
+            (ContainerInput::Pickup, _, 0 | 1) if click.slot_index == OUTSIDE => {
+                self.drop_carried(click.button == 1)
+            }
+            (ContainerInput::Pickup, Some(index), 0 | 1) => self.pickup(index, click.button == 1),
+            (ContainerInput::QuickMove, Some(index), 0 | 1) => self.quick_move(index),
+            (ContainerInput::Swap, Some(index), button) => return self.swap(index, button),
+            (ContainerInput::Throw, Some(index), 0 | 1) => self.throw(index, click.button == 1),
+            _ => return false,
+        }
+        true
+    }
+
+    fn stack(&self, entity: Entity) -> ItemStack {
+        *self
+            .world
+            .get::<ItemStack>(entity)
+            .expect("slot entities hold an ItemStack")
+    }
+
+    /// Set the count of `entity`, despawning it at zero. Returns the entity
+    /// while it still holds items.
+    fn set_count(&mut self, entity: Entity, count: u8) -> Option<Entity> {
+        if count == 0 {
+            self.world.despawn(entity);
+            return None;
+        }
+        if let Some(mut stack) = self.world.get_mut::<ItemStack>(entity) {
+            stack.set_count(count);
+        }
+        Some(entity)
+    }
+
+    /// Take `count` items off `entity` into a new entity with the same item
+    /// components.
+    fn split(&mut self, entity: Entity, count: u8) -> Entity {
+        let remaining = self.stack(entity).count() - count;
+        let split = self.world.entity_mut(entity).clone_and_spawn();
+        self.set_count(split, count);
+        self.set_count(entity, remaining);
+        split
+    }
+
+    /// Left click takes or places the whole stack, right click half of the
+    /// slot or one item off the cursor. Different items swap.
+    fn pickup(&mut self, index: usize, right: bool) {
+        match (self.carried, self.slots[index]) {
+            (None, None) => {}
+            (None, Some(entity)) => {
+                let count = self.stack(entity).count();
+                let take = if right { count.div_ceil(2) } else { count };
+                if take == count {
+                    self.carried = self.slots[index].take();
+                } else {
+                    self.carried = Some(self.split(entity, take));
+                }
+            }
+            (Some(carried), None) if may_place(index) => {
+                if right && self.stack(carried).count() > 1 {
+                    let one = self.split(carried, 1);
+                    self.slots[index] = Some(one);
+                } else {
+                    self.slots[index] = self.carried.take();
+                }
+            }
+            (Some(carried), Some(entity)) if may_place(index) => {
+                let (held, target) = (self.stack(carried), self.stack(entity));
+                if held.item_id() == target.item_id() {
+                    let room = max_stack_size(target).saturating_sub(target.count());
+                    let moved = (if right { 1 } else { held.count() }).min(room);
+                    self.set_count(entity, target.count() + moved);
+                    self.carried = self.set_count(carried, held.count() - moved);
+                } else {
+                    self.slots[index] = Some(carried);
+                    self.carried = Some(entity);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Shift click: move the stack between the main inventory and the
+    /// hotbar, or out of the crafting grid, armor and offhand, like vanilla
+    /// `InventoryMenu.quickMoveStack`.
+    fn quick_move(&mut self, index: usize) {
+        let Some(entity) = self.slots[index].take() else {
+            return;
+        };
+        let (targets, reverse) = match index {
+            CRAFTING_RESULT => (MAIN_INVENTORY.start..HOTBAR.end, true),
+            _ if MAIN_INVENTORY.contains(&index) => (HOTBAR, false),
+            _ if HOTBAR.contains(&index) => (MAIN_INVENTORY, false),
+            _ => (MAIN_INVENTORY.start..HOTBAR.end, false),
+        };
+        self.slots[index] = self.move_into(entity, targets, reverse);
+    }
+
+    /// Merge `entity` into matching stacks in `targets`, then put what is
+    /// left into the first empty slot. Returns the part that did not fit.
+    fn move_into(
+        &mut self,
+        entity: Entity,
+        targets: Range<usize>,
+        reverse: bool,
+    ) -> Option<Entity> {
+        let order: Vec<usize> = if reverse {
+            targets.rev().collect()
+        } else {
+            targets.collect()
+        };
+        let mut stack = self.stack(entity);
+        let max = max_stack_size(stack);
+        if max > 1 {
+            for &index in &order {
+                let Some(target) = self.slots[index] else {
+                    continue;
+                };
+                let target_stack = self.stack(target);
+                if target_stack.item_id() != stack.item_id() {
+                    continue;
+                }
+                let moved = max.saturating_sub(target_stack.count()).min(stack.count());
+                if moved == 0 {
+                    continue;
+                }
+                self.set_count(target, target_stack.count() + moved);
+                stack.set_count(stack.count() - moved);
+                self.set_count(entity, stack.count())?;
+            }
+        }
+        match order.into_iter().find(|&index| self.slots[index].is_none()) {
+            Some(index) => {
+                self.slots[index] = Some(entity);
+                None
+            }
+            None => Some(entity),
+        }
+    }
+
+    /// Number key or offhand key: exchange the slot with a hotbar slot or
+    /// the offhand.
+    fn swap(&mut self, index: usize, button: u8) -> bool {
+        let other = match button {
+            0..=8 => HOTBAR.start + button as usize,
+            OFFHAND_BUTTON => OFFHAND,
+            _ => return false,
+        };
+        if self.slots[other].is_none() || may_place(index) {
+            self.slots.swap(index, other);
+        }
+        true
+    }
+
+    /// Q over a slot: throw one item, or the whole stack with Ctrl. Only
+    /// with nothing on the cursor.
+    fn throw(&mut self, index: usize, whole: bool) {
+        if self.carried.is_some() {
+            return;
+        }
+        let Some(entity) = self.slots[index] else {
+            return;
+        };
+        let thrown = if whole || self.stack(entity).count() == 1 {
+            self.slots[index] = None;
+            entity
+        } else {
+            self.split(entity, 1)
+        };
+        self.drop(thrown);
+    }
+
+    /// Click outside the window: drop the cursor stack, or one item of it
+    /// on right click.
+    fn drop_carried(&mut self, one: bool) {
+        let Some(carried) = self.carried else {
+            return;
+        };
+        let thrown = if !one || self.stack(carried).count() == 1 {
+            self.carried = None;
+            carried
+        } else {
+            self.split(carried, 1)
+        };
+        self.drop(thrown);
+    }
+
+    /// Turn the stack in `entity` into an item entity in front of the
+    /// player's face.
+    fn drop(&mut self, entity: Entity) {
+        let stack = self.stack(entity);
+        self.world.despawn(entity);
+        let (Some(&dimension), Some(transform)) = (
+            self.world.get::<InDimension>(self.player),
+            self.world.get::<Transform>(self.player),
+        ) else {
+            return;
+        };
+        let sneaking = self
+            .world
+            .get::<PlayerPosture>(self.player)
+            .is_some_and(|posture| posture.sneaking);
+        let height = if sneaking {
+            SNEAKING_THROW_HEIGHT
+        } else {
+            THROW_HEIGHT
+        };
+        let position = transform.translation + DVec3::Y * height;
+        self.world.spawn(ItemEntityBundle::new(
+            dimension,
+            Transform::from_translation(position),
+            stack,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::inventory::{CarriedItem, PlayerInventoryBundle};
+    use crate::world::item::minecraft::{DIAMOND_PICKAXE, TORCH};
+    use mcrs_protocol::VarInt;
+
+    fn world() -> (World, Entity) {
+        let mut world = World::new();
+        world.init_resource::<Messages<OutboundPlayerPacket>>();
+        let player = world
+            .spawn((
+                PlayerInventoryBundle::default(),
+                ContainerSeqno::default(),
+                HostAnchor(Entity::PLACEHOLDER),
+            ))
+            .id();
+        (world, player)
+    }
+
+    fn give(world: &mut World, player: Entity, index: usize, stack: ItemStack) -> Entity {
+        let entity = world.spawn(stack).id();
+        let mut query = world.query::<PlayerInventoryMut>();
+        *query
+            .get_mut(world, player)
+            .unwrap()
+            .slot_mut(index)
+            .unwrap() = Some(entity);
+        entity
+    }
+
+    fn slot(world: &mut World, player: Entity, index: usize) -> Option<(ItemId, u8)> {
+        let (slots, _) = read_slots(world, player).unwrap();
+        key(stack(world, slots[index]))
+    }
+
+    fn carried(world: &World, player: Entity) -> Option<(ItemId, u8)> {
+        key(stack(world, world.get::<CarriedItem>(player).unwrap().0))
+    }
+
+    fn hashed(item: &'static Item, count: u8) -> Option<HashedSlot> {
+        Some(HashedSlot {
+            id: item.id,
+            count,
+            components: Default::default(),
+        })
+    }
+
+    /// A click as a vanilla client sends it, with its predicted result.
+    fn click(
+        seqno: i32,
+        slot_index: i16,
+        button: u8,
+        container_input: ContainerInput,
+        changed_slots: Vec<(u16, Option<HashedSlot>)>,
+        carried_item: Option<HashedSlot>,
+    ) -> ServerboundContainerClick {
+        ServerboundContainerClick {
+            container_id: VarInt(PLAYER_CONTAINER_ID),
+            state_seqno: VarInt(seqno),
+            slot_index,
+            button,
+            container_input,
+            changed_slots,
+            carried_item,
+        }
+    }
+
+    fn sent(world: &mut World) -> Vec<PacketPayload> {
+        world
+            .resource_mut::<Messages<OutboundPlayerPacket>>()
+            .drain()
+            .map(|packet| packet.data)
+            .collect()
+    }
+
+    #[test]
+    fn left_click_picks_up_the_stack() {
+        let (mut world, player) = world();
+        give(&mut world, player, 36, ItemStack::new(&TORCH, 10));
+
+        let pickup = click(
+            0,
+            36,
+            0,
+            ContainerInput::Pickup,
+            vec![(36, None)],
+            hashed(&TORCH, 10),
+        );
+        click_container(&mut world, player, &pickup);
+        assert_eq!(slot(&mut world, player, 36), None);
+        assert_eq!(carried(&world, player), Some((TORCH.id, 10)));
+        assert!(sent(&mut world).is_empty());
+
+        // A right click places one item and keeps the rest on the cursor.
+        let place = click(
+            0,
+            9,
+            1,
+            ContainerInput::Pickup,
+            vec![(9, hashed(&TORCH, 1))],
+            hashed(&TORCH, 9),
+        );
+        click_container(&mut world, player, &place);
+        assert_eq!(slot(&mut world, player, 9), Some((TORCH.id, 1)));
+        assert_eq!(carried(&world, player), Some((TORCH.id, 9)));
+        assert!(sent(&mut world).is_empty());
+    }
+
+    #[test]
+    fn shift_click_moves_between_inventory_and_hotbar() {
+        let (mut world, player) = world();
+        give(&mut world, player, 9, ItemStack::new(&TORCH, 10));
+        give(&mut world, player, 36, ItemStack::new(&DIAMOND_PICKAXE, 1));
+        give(&mut world, player, 37, ItemStack::new(&TORCH, 60));
+
+        // Tops up the torches in the hotbar, then fills its first free slot.
+        let to_hotbar = click(
+            0,
+            9,
+            0,
+            ContainerInput::QuickMove,
+            vec![(9, None), (37, hashed(&TORCH, 64)), (38, hashed(&TORCH, 6))],
+            None,
+        );
+        click_container(&mut world, player, &to_hotbar);
+        assert_eq!(slot(&mut world, player, 9), None);
+        assert_eq!(slot(&mut world, player, 36), Some((DIAMOND_PICKAXE.id, 1)));
+        assert_eq!(slot(&mut world, player, 37), Some((TORCH.id, 64)));
+        assert_eq!(slot(&mut world, player, 38), Some((TORCH.id, 6)));
+        assert!(sent(&mut world).is_empty());
+
+        // Back out of the hotbar into the first free inventory slot. The
+        // client guessed slot 10, so both slots are corrected.
+        let to_inventory = click(
+            0,
+            36,
+            0,
+            ContainerInput::QuickMove,
+            vec![(36, None), (10, hashed(&DIAMOND_PICKAXE, 1))],
+            None,
+        );
+        click_container(&mut world, player, &to_inventory);
+        assert_eq!(slot(&mut world, player, 36), None);
+        assert_eq!(slot(&mut world, player, 9), Some((DIAMOND_PICKAXE.id, 1)));
+        let packets = sent(&mut world);
+        assert_eq!(packets.len(), 2);
+        assert!(
+            packets.iter().all(|packet| matches!(
+                packet,
+                PacketPayload::ContainerSetSlot { slot: 9 | 10, .. }
+            ))
+        );
+    }
+
+    #[test]
+    fn stale_state_id_resends_the_container() {
+        let (mut world, player) = world();
+        give(&mut world, player, 36, ItemStack::new(&TORCH, 10));
+
+        let stale = click(
+            5,
+            36,
+            0,
+            ContainerInput::Pickup,
+            vec![(36, None)],
+            hashed(&TORCH, 10),
+        );
+        click_container(&mut world, player, &stale);
+        assert_eq!(slot(&mut world, player, 36), Some((TORCH.id, 10)));
+        assert_eq!(carried(&world, player), None);
+        let packets = sent(&mut world);
+        let [
+            PacketPayload::ContainerSetContent {
+                state_seqno, slots, ..
+            },
+        ] = &packets[..]
+        else {
+            panic!("expected the whole container, got {packets:?}");
+        };
+        assert_eq!(*state_seqno, 1);
+        assert_eq!(slots.len(), PLAYER_CONTAINER_SIZE);
+    }
+}
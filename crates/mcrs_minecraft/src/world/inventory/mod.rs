@@ -1,8 +1,22 @@
+pub mod click;
+
+use crate::world::item::ItemStack;
 use bevy_ecs::component::Component;
 use bevy_ecs::entity::Entity;
 use bevy_ecs::prelude::Bundle;
 use bevy_ecs_macros::QueryData;
 use derive_more::{Deref, DerefMut};
+use mcrs_protocol::packets::game::clientbound::{
+    ClientboundContainerSetContent, ClientboundContainerSetSlot,
+};
+use mcrs_protocol::{Slot, VarInt, WritePacket};
+
+/// Container id of the player's own inventory, always open on the client.
+pub const PLAYER_CONTAINER_ID: i32 = 0;
+
+/// Number of slots in the player container: crafting result and 2x2 grid,
+/// armor, main inventory, hotbar and offhand.
+pub const PLAYER_CONTAINER_SIZE: usize = 1 + 4 + 4 + 4 * 9 + 1;
 
 #[derive(Debug, Clone, Default, Component)]
 pub struct PlayerInventorySlots {
@@ -71,7 +85,7 @@ pub struct PlayerInventoryQuery {
 
 impl<'w, 's> PlayerInventoryQueryItem<'w, 's> {
     pub fn all_slots(&self) -> Vec<Option<Entity>> {
-        let mut slots = Vec::with_capacity(1 + 4 + 4 + 4 * 9 + 1);
+        let mut slots = Vec::with_capacity(PLAYER_CONTAINER_SIZE);
 
         // Crafting result slot
         slots.push(self.result.item_stack);
@@ -104,5 +118,83 @@ impl<'w, 's> PlayerInventoryQueryItem<'w, 's> {
     }
 }
 
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct PlayerInventoryMut {
+    pub result: &'static mut CraftingResultSlot,
+    pub crafting: &'static mut PlayerCraftingSlots,
+    pub armor: &'static mut ArmorSlots,
+    pub inventory: &'static mut PlayerInventorySlots,
+    pub hotbar: &'static mut PlayerHotbarSlots,
+    pub offhand: &'static mut PlayerOffhandSlot,
+    pub carried_item: &'static mut CarriedItem,
+}
+
+impl<'w, 's> PlayerInventoryMutItem<'w, 's> {
+    /// The slot at protocol index `index`, in the order of
+    /// [`PlayerInventoryQueryItem::all_slots`].
+    pub fn slot_mut(&mut self, index: usize) -> Option<&mut Option<Entity>> {
+        Some(match index {
+            0 => &mut self.result.item_stack,
+            1..5 => &mut self.crafting.input_slots[index - 1],
+            5 => &mut self.armor.head,
+            6 => &mut self.armor.chest,
+            7 => &mut self.armor.legs,
+            8 => &mut self.armor.feet,
+            9..36 => &mut self.inventory.slots[index - 9],
+            36..45 => &mut self.hotbar.slots[index - 36],
+            45 => &mut self.offhand.slot,
+            _ => return None,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Component, Deref, DerefMut)]
 pub struct ContainerSeqno(pub u32);
+
+impl ContainerSeqno {
+    /// Move to the next state id, wrapping at 15 bits like vanilla, and
+    /// return it.
+    pub fn increment(&mut self) -> Self {
+        self.0 = (self.0 + 1) & 0x7FFF;
+        *self
+    }
+}
+
+fn slot(stack: Option<ItemStack>) -> Slot {
+    stack.map_or(Slot::EMPTY, Slot::from)
+}
+
+/// Show `stack` in `slot` of container `container_id`, or clear it for
+/// `None`.
+pub fn set_slot(
+    connection: &mut impl WritePacket,
+    container_id: i32,
+    seqno: ContainerSeqno,
+    slot_index: i16,
+    stack: Option<ItemStack>,
+) {
+    connection.write_packet(&ClientboundContainerSetSlot {
+        container_id: VarInt(container_id),
+        state_seqno: VarInt(*seqno as i32),
+        slot: slot_index,
+        item: slot(stack),
+    });
+}
+
+/// Replace every slot of container `container_id`, in protocol slot order,
+/// and the stack held on the cursor.
+pub fn set_container_content(
+    connection: &mut impl WritePacket,
+    container_id: i32,
+    seqno: ContainerSeqno,
+    stacks: impl IntoIterator<Item = Option<ItemStack>>,
+    carried: Option<ItemStack>,
+) {
+    connection.write_packet(&ClientboundContainerSetContent {
+        container_id: VarInt(container_id),
+        state_seqno: VarInt(*seqno as i32),
+        slot_data: stacks.into_iter().map(slot).collect(),
+        carried_item: slot(carried),
+    });
+}
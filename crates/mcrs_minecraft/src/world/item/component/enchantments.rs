@@ -20,4 +20,12 @@ impl Enchantments {
     pub fn has_enchantment(&self, id: u16) -> bool {
         self.map.contains_key(&id)
     }
+
+    pub fn set_level(&mut self, id: u16, level: u8) {
+        if level == 0 {
+            self.map.remove(&id);
+        } else {
+            self.map.insert(id, level);
+        }
+    }
 }
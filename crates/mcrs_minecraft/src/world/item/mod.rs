@@ -25,6 +25,13 @@ pub struct ItemStack {
 }
 
 impl ItemStack {
+    pub fn new(item_id: impl Into<ItemId>, count: u8) -> Self {
+        Self {
+            item_id: item_id.into(),
+            count,
+        }
+    }
+
     pub fn item_id(&self) -> ItemId {
         self.item_id
     }
@@ -32,6 +39,10 @@ impl ItemStack {
     pub fn count(&self) -> u8 {
         self.count
     }
+
+    pub fn set_count(&mut self, count: u8) {
+        self.count = count;
+    }
 }
 
 impl From<ItemStack> for Slot {
@@ -1,9 +1,17 @@
 use mcrs_protocol::Ident;
+use mcrs_engine::world::block::BlockPos;
 use crate::world::item::component::Enchantments;
+use crate::world::loot::BlockLootTables;
 use crate::world::loot::condition::LootCondition;
 
 pub struct BlockBreakContext<'a> {
     pub tool_enchantments: Option<&'a Enchantments>,
+    /// Position of the broken block; seeds tables that declare a `random_sequence`.
+    pub block_pos: BlockPos,
+    /// Tables that `minecraft:loot_table` entries are resolved against.
+    pub loot_tables: Option<&'a BlockLootTables>,
+    /// Player luck; shifts entry weights by `quality * luck`.
+    pub luck: f32,
 }
 
 #[derive(Debug, Clone)]
@@ -12,6 +20,16 @@ pub struct LootDrop {
     pub count: u8,
 }
 
+impl LootDrop {
+    /// A single item, before any loot functions run.
+    pub fn new(item_name: Ident<String>) -> Self {
+        Self {
+            item_name,
+            count: 1,
+        }
+    }
+}
+
 impl LootCondition {
     pub fn check(&self, ctx: &BlockBreakContext) -> bool {
         match self {
@@ -1,4 +1,5 @@
 use crate::world::loot::condition::LootConditionProto;
+use crate::world::loot::function::LootFunctionProto;
 use serde::Deserialize;
 use mcrs_protocol::Ident;
 
@@ -8,10 +9,14 @@ pub enum LootEntryProto {
     #[serde(rename = "minecraft:item")]
     Item {
         name: Ident<String>,
+        #[serde(default = "default_weight")]
+        weight: u32,
+        #[serde(default)]
+        quality: i32,
         #[serde(default)]
         conditions: Vec<LootConditionProto>,
         #[serde(default)]
-        functions: Vec<serde_json::Value>,
+        functions: Vec<LootFunctionProto>,
     },
     #[serde(rename = "minecraft:alternatives")]
     Alternatives {
@@ -19,11 +24,46 @@ pub enum LootEntryProto {
         #[serde(default)]
         conditions: Vec<LootConditionProto>,
     },
+    #[serde(rename = "minecraft:tag")]
+    Tag {
+        name: Ident<String>,
+        #[serde(default)]
+        expand: bool,
+        #[serde(default = "default_weight")]
+        weight: u32,
+        #[serde(default)]
+        quality: i32,
+        #[serde(default)]
+        conditions: Vec<LootConditionProto>,
+        #[serde(default)]
+        functions: Vec<LootFunctionProto>,
+    },
+    /// `value` is a table id, or an inline table (not supported).
+    #[serde(rename = "minecraft:loot_table")]
+    LootTable {
+        value: serde_json::Value,
+        #[serde(default = "default_weight")]
+        weight: u32,
+        #[serde(default)]
+        quality: i32,
+        #[serde(default)]
+        conditions: Vec<LootConditionProto>,
+        #[serde(default)]
+        functions: Vec<LootFunctionProto>,
+    },
     #[serde(rename = "minecraft:empty")]
     Empty {
+        #[serde(default = "default_weight")]
+        weight: u32,
+        #[serde(default)]
+        quality: i32,
         #[serde(default)]
         conditions: Vec<LootConditionProto>,
     },
     #[serde(other)]
     Unknown,
 }
+
+fn default_weight() -> u32 {
+    1
+}
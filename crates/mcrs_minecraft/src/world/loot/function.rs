@@ -1,4 +1,7 @@
+use crate::world::loot::context::{BlockBreakContext, LootDrop};
+use mcrs_random::Random;
 use serde::Deserialize;
+use thiserror::Error;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "function")]
@@ -18,6 +21,189 @@ pub enum LootFunctionProto {
         #[serde(default)]
         parameters: Option<serde_json::Value>,
     },
+    #[serde(rename = "minecraft:enchant_randomly")]
+    EnchantRandomly {},
+    #[serde(rename = "minecraft:enchant_with_levels")]
+    EnchantWithLevels {},
+    #[serde(rename = "minecraft:set_enchantments")]
+    SetEnchantments {},
+    #[serde(rename = "minecraft:set_components")]
+    SetComponents {},
     #[serde(other)]
     Unknown,
 }
+
+impl LootFunctionProto {
+    /// The id of a function that writes item components. Item stacks carry
+    /// no components, so tables using one are rejected rather than dropping
+    /// what it would have set.
+    pub fn component_function(&self) -> Option<&'static str> {
+        match self {
+            LootFunctionProto::EnchantRandomly {} => Some("minecraft:enchant_randomly"),
+            LootFunctionProto::EnchantWithLevels {} => Some("minecraft:enchant_with_levels"),
+            LootFunctionProto::SetEnchantments {} => Some("minecraft:set_enchantments"),
+            LootFunctionProto::SetComponents {} => Some("minecraft:set_components"),
+            _ => None,
+        }
+    }
+}
+
+/// A loot table uses a function whose result an item stack cannot hold.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("loot function {0} sets item components, which item stacks do not carry")]
+pub struct UnsupportedLootFunction(pub &'static str);
+
+// Resolved runtime types
+
+/// Vanilla number provider: a constant, or a value drawn from the loot RNG.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumberProvider {
+    Constant(f32),
+    Uniform {
+        min: Box<NumberProvider>,
+        max: Box<NumberProvider>,
+    },
+    Binomial {
+        n: Box<NumberProvider>,
+        p: Box<NumberProvider>,
+    },
+}
+
+impl NumberProvider {
+    /// Parse a bare number or a typed provider object. An object without a `type`
+    /// but with `min`/`max` is read as uniform, like vanilla's lenient codec.
+    pub fn from_json(value: &serde_json::Value) -> Option<NumberProvider> {
+        if let Some(n) = value.as_f64() {
+            return Some(NumberProvider::Constant(n as f32));
+        }
+        let object = value.as_object()?;
+        let field = |name: &str| {
+            object
+                .get(name)
+                .and_then(NumberProvider::from_json)
+                .map(Box::new)
+        };
+        match object.get("type").and_then(|t| t.as_str()) {
+            Some("minecraft:constant") => object
+                .get("value")
+                .and_then(|v| v.as_f64())
+                .map(|v| NumberProvider::Constant(v as f32)),
+            Some("minecraft:uniform") | None => Some(NumberProvider::Uniform {
+                min: field("min")?,
+                max: field("max")?,
+            }),
+            Some("minecraft:binomial") => Some(NumberProvider::Binomial {
+                n: field("n")?,
+                p: field("p")?,
+            }),
+            Some(_) => None,
+        }
+    }
+
+    pub fn get_int<R: Random>(&self, rng: &mut R) -> i32 {
+        match self {
+            // Java Math.round(float)
+            NumberProvider::Constant(value) => (value + 0.5).floor() as i32,
+            NumberProvider::Uniform { min, max } => {
+                let min = min.get_int(rng);
+                let max = max.get_int(rng);
+                if min >= max {
+                    min
+                } else {
+                    rng.next_i32_bound(max - min + 1) + min
+                }
+            }
+            NumberProvider::Binomial { n, p } => {
+                let n = n.get_int(rng);
+                let p = p.get_float(rng);
+                (0..n).filter(|_| rng.next_f32() < p).count() as i32
+            }
+        }
+    }
+
+    pub fn get_float<R: Random>(&self, rng: &mut R) -> f32 {
+        match self {
+            NumberProvider::Constant(value) => *value,
+            NumberProvider::Uniform { min, max } => {
+                let min = min.get_float(rng);
+                let max = max.get_float(rng);
+                if min >= max {
+                    min
+                } else {
+                    rng.next_f32() * (max - min) + min
+                }
+            }
+            NumberProvider::Binomial { .. } => self.get_int(rng) as f32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BonusFormula {
+    OreDrops,
+    UniformBonusCount { bonus_multiplier: i32 },
+    BinomialWithBonusCount { extra: i32, probability: f32 },
+}
+
+impl BonusFormula {
+    fn calculate<R: Random>(&self, rng: &mut R, count: i32, level: i32) -> i32 {
+        match *self {
+            BonusFormula::OreDrops => {
+                if level > 0 {
+                    let bonus = (rng.next_i32_bound(level + 2) - 1).max(0);
+                    count * (bonus + 1)
+                } else {
+                    count
+                }
+            }
+            BonusFormula::UniformBonusCount { bonus_multiplier } => {
+                count + rng.next_i32_bound(bonus_multiplier * level + 1)
+            }
+            BonusFormula::BinomialWithBonusCount { extra, probability } => {
+                count
+                    + (0..level + extra)
+                        .filter(|_| rng.next_f32() < probability)
+                        .count() as i32
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum LootFunction {
+    SetCount {
+        count: NumberProvider,
+        add: bool,
+    },
+    ApplyBonus {
+        enchantment_registry_index: u16,
+        formula: BonusFormula,
+    },
+}
+
+/// Count limit for `set_count`. Vanilla clamps to the item's max stack size;
+/// item data isn't available during loot evaluation, so the common limit is used.
+const MAX_STACK_SIZE: i32 = 64;
+
+impl LootFunction {
+    pub fn apply<R: Random>(&self, drop: &mut LootDrop, ctx: &BlockBreakContext, rng: &mut R) {
+        match self {
+            LootFunction::SetCount { count, add } => {
+                let base = if *add { drop.count as i32 } else { 0 };
+                let value = base + count.get_int(rng);
+                drop.count = value.clamp(0, MAX_STACK_SIZE) as u8;
+            }
+            LootFunction::ApplyBonus {
+                enchantment_registry_index,
+                formula,
+            } => {
+                let level = ctx
+                    .tool_enchantments
+                    .map_or(0, |e| e.get_level_by_id(*enchantment_registry_index))
+                    as i32;
+                let count = formula.calculate(rng, drop.count as i32, level);
+                drop.count = count.clamp(0, u8::MAX as i32) as u8;
+            }
+        }
+    }
+}
@@ -4,10 +4,13 @@ pub mod entry;
 pub mod function;
 
 use crate::enchantment::EnchantmentData;
+use crate::weight::WeightedList;
 use crate::world::loot::condition::{LootCondition, LootConditionProto};
 use crate::world::loot::context::{BlockBreakContext, LootDrop};
 use crate::world::loot::entry::LootEntryProto;
-use crate::world::loot::function::LootFunctionProto;
+use crate::world::loot::function::{
+    BonusFormula, LootFunction, LootFunctionProto, NumberProvider, UnsupportedLootFunction,
+};
 use bevy_app::{App, Plugin, PostStartup, Update};
 use bevy_asset::io::Reader;
 use bevy_asset::{Asset, AssetApp, AssetEvent, AssetLoader, AssetServer, Assets, Handle, LoadContext, VisitAssetDependencies};
@@ -16,14 +19,16 @@ use bevy_ecs::prelude::ResMut;
 use bevy_ecs::resource::Resource;
 use bevy_ecs::system::Res;
 use bevy_reflect::TypePath;
-use mcrs_core::StaticRegistry;
+use mcrs_core::{ResourceLocation, StaticRegistry, TagKey, TagRegistry};
+use mcrs_random::{Random, RandomSource};
 use mcrs_vanilla::block::Block as VanillaBlock;
+use mcrs_vanilla::item::Item as VanillaItem;
 use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::str::FromStr;
 use thiserror::Error;
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
 use mcrs_protocol::Ident;
 
 // ============================================================================
@@ -58,7 +63,11 @@ pub struct LootPoolProto {
 
 #[derive(Debug, Clone)]
 pub struct LootTable {
+    /// The table's own id (e.g. "minecraft:blocks/stone"), so a table that
+    /// references itself is caught on the first hop.
+    pub id: Option<Ident<String>>,
     pub pools: Vec<LootPool>,
+    pub random_sequence: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -66,19 +75,47 @@ pub struct LootPool {
     pub rolls: u32,
     pub entries: Vec<LootEntry>,
     pub conditions: Vec<LootCondition>,
+    /// Applied to every drop of the pool, after the entry's own functions.
+    pub functions: Vec<LootFunction>,
 }
 
 #[derive(Debug, Clone)]
 pub enum LootEntry {
     Item {
         name: Ident<String>,
+        weight: u32,
+        quality: i32,
         conditions: Vec<LootCondition>,
+        functions: Vec<LootFunction>,
     },
+    /// Evaluates to its first child that drops something. Carries no weight of
+    /// its own and counts as weight 1 when its pool picks an entry.
     Alternatives {
         children: Vec<LootEntry>,
         conditions: Vec<LootCondition>,
     },
+    /// Items of an item tag, resolved when the table is loaded. With `expand`
+    /// one member is picked per roll, otherwise every member drops.
+    Tag {
+        tag: Ident<String>,
+        expand: bool,
+        items: Vec<Ident<String>>,
+        weight: u32,
+        quality: i32,
+        conditions: Vec<LootCondition>,
+        functions: Vec<LootFunction>,
+    },
+    /// Rolls another loot table, looked up when the entry is evaluated.
+    Reference {
+        table: Ident<String>,
+        weight: u32,
+        quality: i32,
+        conditions: Vec<LootCondition>,
+        functions: Vec<LootFunction>,
+    },
     Empty {
+        weight: u32,
+        quality: i32,
         conditions: Vec<LootCondition>,
     },
 }
@@ -88,49 +125,79 @@ pub enum LootEntry {
 // ============================================================================
 
 impl LootTableProto {
-    pub fn resolve(&self, enchantment_registry: &StaticRegistry<EnchantmentData>) -> LootTable {
-        LootTable {
+    /// Resolve against the registries. Fails if any function writes item
+    /// components, which item stacks cannot hold.
+    pub fn resolve(
+        &self,
+        enchantment_registry: &StaticRegistry<EnchantmentData>,
+        item_registry: &StaticRegistry<VanillaItem>,
+        item_tags: &TagRegistry<VanillaItem>,
+    ) -> Result<LootTable, UnsupportedLootFunction> {
+        Ok(LootTable {
+            id: None,
             pools: self
                 .pools
                 .iter()
-                .map(|p| p.resolve(enchantment_registry))
-                .collect(),
-        }
+                .map(|p| p.resolve(enchantment_registry, item_registry, item_tags))
+                .collect::<Result<_, _>>()?,
+            random_sequence: self.random_sequence.clone(),
+        })
     }
 }
 
 impl LootPoolProto {
-    fn resolve(&self, enchantment_registry: &StaticRegistry<EnchantmentData>) -> LootPool {
+    fn resolve(
+        &self,
+        enchantment_registry: &StaticRegistry<EnchantmentData>,
+        item_registry: &StaticRegistry<VanillaItem>,
+        item_tags: &TagRegistry<VanillaItem>,
+    ) -> Result<LootPool, UnsupportedLootFunction> {
         let rolls = match &self.rolls {
             serde_json::Value::Number(n) => n.as_u64().unwrap_or(1) as u32,
             _ => 1,
         };
-        LootPool {
+        Ok(LootPool {
             rolls,
             entries: self
                 .entries
                 .iter()
-                .map(|e| resolve_entry(e, enchantment_registry))
-                .collect(),
+                .map(|e| resolve_entry(e, enchantment_registry, item_registry, item_tags))
+                .collect::<Result<_, _>>()?,
             conditions: self
                 .conditions
                 .iter()
                 .map(|c| resolve_condition(c, enchantment_registry))
                 .collect(),
-        }
+            functions: resolve_functions(&self.functions, enchantment_registry)?,
+        })
     }
 }
 
-fn resolve_entry(entry: &LootEntryProto, enchantment_registry: &StaticRegistry<EnchantmentData>) -> LootEntry {
-    match entry {
+fn resolve_entry(
+    entry: &LootEntryProto,
+    enchantment_registry: &StaticRegistry<EnchantmentData>,
+    item_registry: &StaticRegistry<VanillaItem>,
+    item_tags: &TagRegistry<VanillaItem>,
+) -> Result<LootEntry, UnsupportedLootFunction> {
+    let resolve_conditions = |conditions: &[LootConditionProto]| -> Vec<LootCondition> {
+        conditions
+            .iter()
+            .map(|c| resolve_condition(c, enchantment_registry))
+            .collect()
+    };
+    Ok(match entry {
         LootEntryProto::Item {
-            name, conditions, ..
+            name,
+            weight,
+            quality,
+            conditions,
+            functions,
         } => LootEntry::Item {
             name: name.clone(),
-            conditions: conditions
-                .iter()
-                .map(|c| resolve_condition(c, enchantment_registry))
-                .collect(),
+            weight: *weight,
+            quality: *quality,
+            conditions: resolve_conditions(conditions),
+            functions: resolve_functions(functions, enchantment_registry)?,
         },
         LootEntryProto::Alternatives {
             children,
@@ -138,23 +205,70 @@ fn resolve_entry(entry: &LootEntryProto, enchantment_registry: &StaticRegistry<E
         } => LootEntry::Alternatives {
             children: children
                 .iter()
-                .map(|e| resolve_entry(e, enchantment_registry))
-                .collect(),
-            conditions: conditions
-                .iter()
-                .map(|c| resolve_condition(c, enchantment_registry))
-                .collect(),
+                .map(|e| resolve_entry(e, enchantment_registry, item_registry, item_tags))
+                .collect::<Result<_, _>>()?,
+            conditions: resolve_conditions(conditions),
         },
-        LootEntryProto::Empty { conditions } => LootEntry::Empty {
-            conditions: conditions
-                .iter()
-                .map(|c| resolve_condition(c, enchantment_registry))
-                .collect(),
+        LootEntryProto::Tag {
+            name,
+            expand,
+            weight,
+            quality,
+            conditions,
+            functions,
+        } => {
+            let items = tag_items(name, item_registry, item_tags);
+            if items.is_empty() {
+                warn!(tag = %name, "Item tag is empty or not loaded, entry will never drop");
+            }
+            LootEntry::Tag {
+                tag: name.clone(),
+                expand: *expand,
+                items,
+                weight: *weight,
+                quality: *quality,
+                conditions: resolve_conditions(conditions),
+                functions: resolve_functions(functions, enchantment_registry)?,
+            }
+        }
+        LootEntryProto::LootTable {
+            value,
+            weight,
+            quality,
+            conditions,
+            functions,
+        } => {
+            let Some(table) = value.as_str().and_then(|id| Ident::from_str(id).ok()) else {
+                warn!(value = %value, "Unsupported loot_table entry value, entry dropped");
+                return Ok(LootEntry::Empty {
+                    weight: *weight,
+                    quality: *quality,
+                    conditions: vec![],
+                });
+            };
+            LootEntry::Reference {
+                table,
+                weight: *weight,
+                quality: *quality,
+                conditions: resolve_conditions(conditions),
+                functions: resolve_functions(functions, enchantment_registry)?,
+            }
+        }
+        LootEntryProto::Empty {
+            weight,
+            quality,
+            conditions,
+        } => LootEntry::Empty {
+            weight: *weight,
+            quality: *quality,
+            conditions: resolve_conditions(conditions),
         },
         LootEntryProto::Unknown => LootEntry::Empty {
+            weight: 1,
+            quality: 0,
             conditions: vec![],
         },
-    }
+    })
 }
 
 fn resolve_condition(
@@ -205,21 +319,145 @@ fn resolve_condition(
     }
 }
 
+/// Identifiers of every item in `tag`, in registry order.
+fn tag_items(
+    tag: &Ident<String>,
+    item_registry: &StaticRegistry<VanillaItem>,
+    item_tags: &TagRegistry<VanillaItem>,
+) -> Vec<Ident<String>> {
+    let key =
+        TagKey::<VanillaItem, _>::from_location(ResourceLocation::new(tag.namespace(), tag.path()));
+    item_registry
+        .iter()
+        .filter(|(id, _, _)| item_tags.contains(&key, *id))
+        .filter_map(|(_, loc, _)| Ident::from_str(loc.as_str()).ok())
+        .collect()
+}
+
+fn resolve_functions(
+    functions: &[LootFunctionProto],
+    enchantment_registry: &StaticRegistry<EnchantmentData>,
+) -> Result<Vec<LootFunction>, UnsupportedLootFunction> {
+    let mut resolved = Vec::with_capacity(functions.len());
+    for function in functions {
+        if let Some(id) = function.component_function() {
+            return Err(UnsupportedLootFunction(id));
+        }
+        resolved.extend(resolve_function(function, enchantment_registry));
+    }
+    Ok(resolved)
+}
+
+/// Resolve a loot function. Functions with no effect on block drops
+/// (`explosion_decay` without an explosion) or unknown ones resolve to `None`.
+fn resolve_function(
+    function: &LootFunctionProto,
+    enchantment_registry: &StaticRegistry<EnchantmentData>,
+) -> Option<LootFunction> {
+    match function {
+        LootFunctionProto::SetCount { count, add } => {
+            let Some(count) = NumberProvider::from_json(count) else {
+                warn!(count = %count, "Unsupported number provider in set_count, function ignored");
+                return None;
+            };
+            Some(LootFunction::SetCount { count, add: *add })
+        }
+        LootFunctionProto::ApplyBonus {
+            enchantment,
+            formula,
+            parameters,
+        } => {
+            let Some(static_id) = enchantment_registry.id_of(enchantment) else {
+                warn!(
+                    enchantment = %enchantment,
+                    "Enchantment not found in registry, apply_bonus ignored"
+                );
+                return None;
+            };
+            let param = |name: &str| parameters.as_ref().and_then(|p| p.get(name));
+            let formula = match formula.as_str() {
+                "minecraft:ore_drops" => BonusFormula::OreDrops,
+                "minecraft:uniform_bonus_count" => BonusFormula::UniformBonusCount {
+                    bonus_multiplier: param("bonusMultiplier")
+                        .and_then(|v| v.as_i64())
+                        .unwrap_or(1) as i32,
+                },
+                "minecraft:binomial_with_bonus_count" => BonusFormula::BinomialWithBonusCount {
+                    extra: param("extra").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
+                    probability: param("probability").and_then(|v| v.as_f64()).unwrap_or(0.0)
+                        as f32,
+                },
+                other => {
+                    warn!(formula = %other, "Unknown apply_bonus formula, function ignored");
+                    return None;
+                }
+            };
+            Some(LootFunction::ApplyBonus {
+                enchantment_registry_index: static_id.raw() as u16,
+                formula,
+            })
+        }
+        LootFunctionProto::ExplosionDecay {}
+        | LootFunctionProto::EnchantRandomly {}
+        | LootFunctionProto::EnchantWithLevels {}
+        | LootFunctionProto::SetEnchantments {}
+        | LootFunctionProto::SetComponents {}
+        | LootFunctionProto::Unknown => None,
+    }
+}
+
 // ============================================================================
 // Evaluation
 // ============================================================================
 
 impl LootTable {
-    pub fn evaluate(&self, ctx: &BlockBreakContext) -> Vec<LootDrop> {
+    /// Roll the table. Tables with a `random_sequence` ignore `rng` and draw from
+    /// a generator derived from `world_seed`, the sequence id and the block
+    /// position, so breaking the same block in the same world drops the same loot.
+    pub fn evaluate<R: Random>(
+        &self,
+        ctx: &BlockBreakContext,
+        world_seed: u64,
+        rng: &mut R,
+    ) -> Vec<LootDrop> {
+        match &self.random_sequence {
+            Some(sequence) => {
+                let mut rng = RandomSource::new(world_seed, false)
+                    .fork_hash(sequence)
+                    .fork_at(*ctx.block_pos);
+                self.roll_pools(ctx, &mut rng, &mut self.id.iter().cloned().collect())
+            }
+            None => self.roll_pools(ctx, rng, &mut self.id.iter().cloned().collect()),
+        }
+    }
+
+    fn roll_pools<R: Random>(
+        &self,
+        ctx: &BlockBreakContext,
+        rng: &mut R,
+        visited: &mut Vec<Ident<String>>,
+    ) -> Vec<LootDrop> {
         let mut drops = Vec::new();
         for pool in &self.pools {
             if !pool.conditions.iter().all(|c| c.check(ctx)) {
                 continue;
             }
+            // Conditions don't draw from the RNG, so the candidates are the same for every roll.
+            let candidates: WeightedList<&LootEntry> = pool
+                .entries
+                .iter()
+                .filter(|entry| entry.conditions().iter().all(|c| c.check(ctx)))
+                .map(|entry| (entry.effective_weight(ctx.luck), entry))
+                .collect();
             for _ in 0..pool.rolls {
-                for entry in &pool.entries {
-                    if let Some(drop) = evaluate_entry(entry, ctx) {
-                        drops.push(drop);
+                let Some(entry) = pick_entry(&candidates, rng) else {
+                    break;
+                };
+                let start = drops.len();
+                evaluate_entry(entry, ctx, rng, visited, &mut drops);
+                for drop in &mut drops[start..] {
+                    for function in &pool.functions {
+                        function.apply(drop, ctx, rng);
                     }
                 }
             }
@@ -228,37 +466,137 @@ impl LootTable {
     }
 }
 
-fn evaluate_entry(entry: &LootEntry, ctx: &BlockBreakContext) -> Option<LootDrop> {
-    match entry {
-        LootEntry::Item { name, conditions } => {
-            if conditions.iter().all(|c| c.check(ctx)) {
-                Some(LootDrop {
-                    item_name: name.clone(),
-                    count: 1,
-                })
+impl LootEntry {
+    fn conditions(&self) -> &[LootCondition] {
+        match self {
+            LootEntry::Item { conditions, .. }
+            | LootEntry::Alternatives { conditions, .. }
+            | LootEntry::Tag { conditions, .. }
+            | LootEntry::Reference { conditions, .. }
+            | LootEntry::Empty { conditions, .. } => conditions,
+        }
+    }
+
+    /// Weight of this entry in its pool's pick: `max(floor(weight + quality * luck), 0)`.
+    fn effective_weight(&self, luck: f32) -> u32 {
+        let (weight, quality) = match self {
+            LootEntry::Item {
+                weight, quality, ..
+            }
+            | LootEntry::Tag {
+                weight, quality, ..
+            }
+            | LootEntry::Reference {
+                weight, quality, ..
+            }
+            | LootEntry::Empty {
+                weight, quality, ..
+            } => (*weight, *quality),
+            LootEntry::Alternatives { .. } => return 1,
+        };
+        (weight as f32 + quality as f32 * luck).floor().max(0.0) as u32
+    }
+}
+
+/// Pick one entry with probability proportional to its weight. A lone
+/// candidate is returned without drawing from the RNG, like vanilla.
+fn pick_entry<'a, R: Random>(
+    candidates: &WeightedList<&'a LootEntry>,
+    rng: &mut R,
+) -> Option<&'a LootEntry> {
+    if candidates.len() == 1 {
+        return candidates.iter().next().map(|(_, entry)| *entry);
+    }
+    candidates.sample(rng).copied()
+}
+
+/// Push the drops of `entry` and its functions onto `drops`. Returns whether
+/// anything was dropped, which is what `alternatives` picks its child by.
+///
+/// `visited` holds the tables currently being rolled through `loot_table`
+/// entries, so a table that (indirectly) references itself stops recursing.
+fn evaluate_entry<R: Random>(
+    entry: &LootEntry,
+    ctx: &BlockBreakContext,
+    rng: &mut R,
+    visited: &mut Vec<Ident<String>>,
+    drops: &mut Vec<LootDrop>,
+) -> bool {
+    let start = drops.len();
+    let functions = match entry {
+        LootEntry::Item {
+            name,
+            conditions,
+            functions,
+        } => {
+            if !conditions.iter().all(|c| c.check(ctx)) {
+                return false;
+            }
+            drops.push(LootDrop::new(name.clone()));
+            functions
+        }
+        LootEntry::Tag {
+            expand,
+            items,
+            conditions,
+            functions,
+            ..
+        } => {
+            if items.is_empty() || !conditions.iter().all(|c| c.check(ctx)) {
+                return false;
+            }
+            if *expand {
+                let item = &items[rng.next_i32_bound(items.len() as i32) as usize];
+                drops.push(LootDrop::new(item.clone()));
             } else {
-                None
+                drops.extend(items.iter().cloned().map(LootDrop::new));
             }
+            functions
+        }
+        LootEntry::Reference {
+            table,
+            conditions,
+            functions,
+        } => {
+            if !conditions.iter().all(|c| c.check(ctx)) {
+                return false;
+            }
+            if visited.contains(table) {
+                warn!(table = %table, "Loot table references itself, entry skipped");
+                return false;
+            }
+            let Some(referenced) = ctx.loot_tables.and_then(|tables| tables.get_table(table))
+            else {
+                warn!(table = %table, "Referenced loot table is not loaded, entry skipped");
+                return false;
+            };
+            visited.push(table.clone());
+            drops.extend(referenced.roll_pools(ctx, rng, visited));
+            visited.pop();
+            functions
         }
         LootEntry::Alternatives {
             children,
             conditions,
         } => {
             if !conditions.iter().all(|c| c.check(ctx)) {
-                return None;
+                return false;
             }
-            for child in children {
-                if let Some(drop) = evaluate_entry(child, ctx) {
-                    return Some(drop);
-                }
-            }
-            None
+            return children
+                .iter()
+                .any(|child| evaluate_entry(child, ctx, rng, visited, drops));
         }
-        LootEntry::Empty { conditions: _ } => {
+        LootEntry::Empty { .. } => {
             // Empty entry never produces a drop regardless of condition outcome.
-            None
+            return false;
+        }
+    };
+    for drop in &mut drops[start..] {
+        for function in functions {
+            function.apply(drop, ctx, rng);
         }
     }
+    drops.len() > start
 }
 
 // ============================================================================
@@ -345,6 +683,21 @@ pub struct BlockLootTables {
 }
 
 impl BlockLootTables {
+    /// The loot table id of a block's table: "minecraft:stone" maps to
+    /// "minecraft:blocks/stone".
+    pub fn table_id(block_id: &Ident<String>) -> Ident<String> {
+        let id = format!("{}:blocks/{}", block_id.namespace(), block_id.path());
+        Ident::from_str(&id).expect("a block id with a blocks/ prefix is still a valid id")
+    }
+
+    /// Look up a table by its loot table id (e.g. "minecraft:blocks/stone").
+    /// Only block tables are loaded, so any other id resolves to `None`.
+    pub fn get_table(&self, table_id: &Ident<String>) -> Option<&LootTable> {
+        let block_path = table_id.path().strip_prefix("blocks/")?;
+        self.tables
+            .get(format!("{}:{}", table_id.namespace(), block_path).as_str())
+    }
+
     /// Request loading a loot table for the given block identifier (e.g. "minecraft:stone").
     /// Returns true if the table is already loaded, false if loading was triggered or is in progress.
     pub fn request(&mut self, block_id: &Ident<String>, asset_server: &AssetServer) -> bool {
@@ -394,18 +747,31 @@ fn process_loaded_loot_tables(
     mut events: MessageReader<AssetEvent<LootTableAsset>>,
     assets: Res<Assets<LootTableAsset>>,
     enchantment_registry: Res<StaticRegistry<EnchantmentData>>,
+    item_registry: Res<StaticRegistry<VanillaItem>>,
+    item_tags: Res<TagRegistry<VanillaItem>>,
     mut block_loot_tables: ResMut<BlockLootTables>,
 ) {
     for event in events.read() {
         if let AssetEvent::LoadedWithDependencies { id } = event
             && let Some(asset) = assets.get(*id) {
-                let resolved = asset.proto.resolve(&enchantment_registry);
+                block_loot_tables.pending.remove(&asset.block_id);
+                let mut resolved = match asset.proto.resolve(
+                    &enchantment_registry,
+                    &item_registry,
+                    &item_tags,
+                ) {
+                    Ok(resolved) => resolved,
+                    Err(err) => {
+                        error!(block = %asset.block_id, error = %err, "Loot table rejected");
+                        continue;
+                    }
+                };
+                resolved.id = Some(BlockLootTables::table_id(&asset.block_id));
                 info!(
                     block = %asset.block_id,
                     pools = resolved.pools.len(),
                     "Resolved loot table"
                 );
-                block_loot_tables.pending.remove(&asset.block_id);
                 block_loot_tables
                     .tables
                     .insert(asset.block_id.clone(), resolved);
@@ -424,3 +790,471 @@ impl Plugin for LootPlugin {
         app.add_systems(Update, process_loaded_loot_tables);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enchantment::EnchantmentCost;
+    use crate::world::item::component::Enchantments;
+    use mcrs_engine::world::block::BlockPos;
+    use mcrs_protocol::ident;
+    use mcrs_random::xoroshiro::XoroshiroRandom;
+
+    fn single_item_table(functions: Vec<LootFunction>) -> LootTable {
+        LootTable {
+            id: None,
+            pools: vec![LootPool {
+                rolls: 1,
+                entries: vec![LootEntry::Item {
+                    name: ident!("coal").into(),
+                    weight: 1,
+                    quality: 0,
+                    conditions: vec![],
+                    functions,
+                }],
+                conditions: vec![],
+                functions: vec![],
+            }],
+            random_sequence: None,
+        }
+    }
+
+    fn ctx_at(block_pos: BlockPos) -> BlockBreakContext<'static> {
+        BlockBreakContext {
+            tool_enchantments: None,
+            block_pos,
+            loot_tables: None,
+            luck: 0.0,
+        }
+    }
+
+    #[test]
+    fn uniform_set_count_stays_in_range() {
+        let count = NumberProvider::from_json(&serde_json::json!({
+            "type": "minecraft:uniform",
+            "min": 2.0,
+            "max": 4.0
+        }))
+        .unwrap();
+        let table = single_item_table(vec![LootFunction::SetCount { count, add: false }]);
+        let ctx = ctx_at(BlockPos::new(0, 64, 0));
+
+        let mut seen = [false; 3];
+        for seed in 0..256 {
+            let drops = table.evaluate(&ctx, 0, &mut XoroshiroRandom::new(seed));
+            assert_eq!(drops.len(), 1);
+            let count = drops[0].count;
+            assert!((2..=4).contains(&count), "seed {seed}: count {count}");
+            seen[(count - 2) as usize] = true;
+        }
+        assert_eq!(seen, [true; 3], "every count in 2..=4 should occur");
+    }
+
+    #[test]
+    fn ore_drops_bonus_scales_with_fortune() {
+        let fortune = 7;
+        let table = single_item_table(vec![LootFunction::ApplyBonus {
+            enchantment_registry_index: fortune,
+            formula: BonusFormula::OreDrops,
+        }]);
+
+        let no_tool = ctx_at(BlockPos::new(0, 64, 0));
+        let mut enchantments = Enchantments::empty();
+        enchantments.set_level(fortune, 3);
+        let fortune_tool = BlockBreakContext {
+            tool_enchantments: Some(&enchantments),
+            block_pos: BlockPos::new(0, 64, 0),
+            loot_tables: None,
+            luck: 0.0,
+        };
+
+        let mut rng = XoroshiroRandom::new(1);
+        let mut max_count = 0;
+        for _ in 0..256 {
+            assert_eq!(table.evaluate(&no_tool, 0, &mut rng)[0].count, 1);
+            let count = table.evaluate(&fortune_tool, 0, &mut rng)[0].count;
+            assert!((1..=4).contains(&count));
+            max_count = max_count.max(count);
+        }
+        assert_eq!(max_count, 4);
+    }
+
+    /// Resolve a vanilla block loot table from the assets directory, against
+    /// an enchantment registry holding only silk touch and fortune.
+    fn vanilla_block_table(block: &str) -> (LootTable, StaticRegistry<EnchantmentData>) {
+        let path = format!(
+            "{}/../../assets/minecraft/loot_table/blocks/{block}.json",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let json = std::fs::read_to_string(&path).expect("block loot table must exist");
+        let proto: LootTableProto = serde_json::from_str(&json).unwrap();
+
+        let mut enchantments = StaticRegistry::new();
+        for name in ["minecraft:silk_touch", "minecraft:fortune"] {
+            let data: &'static EnchantmentData = Box::leak(Box::new(EnchantmentData {
+                description: serde_json::Value::Null,
+                min_cost: EnchantmentCost {
+                    base: 0,
+                    per_level_above_first: 0,
+                },
+                max_cost: EnchantmentCost {
+                    base: 0,
+                    per_level_above_first: 0,
+                },
+                anvil_cost: 0,
+                slots: vec![],
+                supported_items: TagKey::from_location(ResourceLocation::minecraft(
+                    "enchantable/mining",
+                )),
+                primary_items: None,
+                weight: 1,
+                max_level: 3,
+                exclusive_set: None,
+                effects: None,
+            }));
+            enchantments.register(ResourceLocation::parse(name).unwrap(), data);
+        }
+        let table = proto
+            .resolve(&enchantments, &StaticRegistry::new(), &TagRegistry::default())
+            .unwrap();
+        (table, enchantments)
+    }
+
+    #[test]
+    fn fortune_pickaxe_breaking_stone_and_ore() {
+        let (stone, enchantments) = vanilla_block_table("stone");
+        let (coal_ore, _) = vanilla_block_table("coal_ore");
+        let fortune = enchantments.id_of("minecraft:fortune").unwrap().raw() as u16;
+        let mut pickaxe = Enchantments::empty();
+        pickaxe.set_level(fortune, 3);
+        let ctx_at = |block_pos| BlockBreakContext {
+            tool_enchantments: Some(&pickaxe),
+            block_pos,
+            loot_tables: None,
+            luck: 0.0,
+        };
+
+        // Coal ore's drop goes through apply_bonus with fortune; stone's has no
+        // bonus, so fortune leaves its single cobblestone alone.
+        let LootEntry::Alternatives { children, .. } = &coal_ore.pools[0].entries[0] else {
+            panic!("coal ore drops one of two alternatives");
+        };
+        let LootEntry::Item { functions, .. } = &children[1] else {
+            panic!("coal ore's fallback is an item entry");
+        };
+        assert!(matches!(
+            functions[..],
+            [LootFunction::ApplyBonus {
+                enchantment_registry_index,
+                formula: BonusFormula::OreDrops,
+            }] if enchantment_registry_index == fortune
+        ));
+
+        let mut max_coal = 0;
+        for x in 0..256 {
+            let ctx = ctx_at(BlockPos::new(x, 12, 0));
+            let drops = stone.evaluate(&ctx, 0, &mut XoroshiroRandom::new(0));
+            assert_eq!(drops.len(), 1);
+            assert_eq!(drops[0].item_name.as_str(), "minecraft:cobblestone");
+            assert_eq!(drops[0].count, 1);
+
+            let drops = coal_ore.evaluate(&ctx, 0, &mut XoroshiroRandom::new(0));
+            assert_eq!(drops.len(), 1);
+            assert_eq!(drops[0].item_name.as_str(), "minecraft:coal");
+            assert!((1..=4).contains(&drops[0].count));
+            max_coal = max_coal.max(drops[0].count);
+        }
+        assert_eq!(max_coal, 4);
+    }
+
+    #[test]
+    fn random_sequence_is_deterministic_per_position() {
+        let count = NumberProvider::from_json(&serde_json::json!({
+            "type": "minecraft:uniform",
+            "min": 1.0,
+            "max": 64.0
+        }))
+        .unwrap();
+        let mut table = single_item_table(vec![LootFunction::SetCount { count, add: false }]);
+        table.pools[0].rolls = 4;
+        table.random_sequence = Some("minecraft:blocks/coal_ore".to_string());
+
+        let counts = |pos: BlockPos, world_seed: u64, rng_seed: u64| -> Vec<u8> {
+            table
+                .evaluate(
+                    &ctx_at(pos),
+                    world_seed,
+                    &mut XoroshiroRandom::new(rng_seed),
+                )
+                .iter()
+                .map(|drop| drop.count)
+                .collect()
+        };
+
+        let pos = BlockPos::new(12, 40, -7);
+        // The caller's RNG is ignored once a sequence is declared.
+        assert_eq!(counts(pos, 42, 1), counts(pos, 42, 2));
+        assert_ne!(counts(pos, 42, 1), counts(BlockPos::new(13, 40, -7), 42, 1));
+        assert_ne!(counts(pos, 42, 1), counts(pos, 43, 1));
+    }
+
+    #[test]
+    fn pool_functions_run_after_entry_functions() {
+        let fortune = 7;
+        let mut table = single_item_table(vec![LootFunction::ApplyBonus {
+            enchantment_registry_index: fortune,
+            formula: BonusFormula::UniformBonusCount {
+                bonus_multiplier: 1,
+            },
+        }]);
+        table.pools[0].functions = vec![LootFunction::SetCount {
+            count: NumberProvider::Constant(2.0),
+            add: true,
+        }];
+
+        let mut enchantments = Enchantments::empty();
+        enchantments.set_level(fortune, 3);
+        let fortune_tool = BlockBreakContext {
+            tool_enchantments: Some(&enchantments),
+            block_pos: BlockPos::new(0, 64, 0),
+            loot_tables: None,
+            luck: 0.0,
+        };
+
+        let mut rng = XoroshiroRandom::new(3);
+        let no_tool = ctx_at(BlockPos::new(0, 64, 0));
+        assert_eq!(table.evaluate(&no_tool, 0, &mut rng)[0].count, 3);
+        for _ in 0..64 {
+            // 1 base + 0..=3 fortune bonus, then +2 from the pool.
+            let count = table.evaluate(&fortune_tool, 0, &mut rng)[0].count;
+            assert!((3..=6).contains(&count), "count {count}");
+        }
+
+        // A pool whose conditions fail produces nothing, so its functions never run.
+        table.pools[0].conditions =
+            vec![LootCondition::Inverted(Box::new(LootCondition::AlwaysTrue))];
+        assert!(table.evaluate(&fortune_tool, 0, &mut rng).is_empty());
+    }
+
+    fn weighted_item(name: &str, weight: u32, quality: i32) -> LootEntry {
+        LootEntry::Item {
+            name: Ident::from_str(name).unwrap(),
+            weight,
+            quality,
+            conditions: vec![],
+            functions: vec![],
+        }
+    }
+
+    fn item(name: &str) -> LootEntry {
+        weighted_item(name, 1, 0)
+    }
+
+    fn reference(table: &str) -> LootEntry {
+        LootEntry::Reference {
+            table: Ident::from_str(table).unwrap(),
+            weight: 1,
+            quality: 0,
+            conditions: vec![],
+            functions: vec![],
+        }
+    }
+
+    /// One single-roll pool per element of `pools`.
+    fn table_with_pools(pools: Vec<Vec<LootEntry>>) -> LootTable {
+        LootTable {
+            id: None,
+            pools: pools
+                .into_iter()
+                .map(|entries| LootPool {
+                    rolls: 1,
+                    entries,
+                    conditions: vec![],
+                    functions: vec![],
+                })
+                .collect(),
+            random_sequence: None,
+        }
+    }
+
+    #[test]
+    fn tag_entry_drops_every_member_or_one_when_expanded() {
+        let items: Vec<Ident<String>> = ["minecraft:oak_log", "minecraft:birch_log"]
+            .into_iter()
+            .map(|id| Ident::from_str(id).unwrap())
+            .collect();
+        let tag_entry = |expand| LootEntry::Tag {
+            tag: Ident::from_str("minecraft:logs").unwrap(),
+            expand,
+            items: items.clone(),
+            weight: 1,
+            quality: 0,
+            conditions: vec![],
+            functions: vec![],
+        };
+        let ctx = ctx_at(BlockPos::new(0, 64, 0));
+        let mut rng = XoroshiroRandom::new(5);
+
+        let all = table_with_pools(vec![vec![tag_entry(false)]]).evaluate(&ctx, 0, &mut rng);
+        let names: Vec<_> = all.iter().map(|drop| drop.item_name.clone()).collect();
+        assert_eq!(names, items);
+
+        let expanded = table_with_pools(vec![vec![tag_entry(true)]]);
+        let mut seen = [false; 2];
+        for _ in 0..64 {
+            let drops = expanded.evaluate(&ctx, 0, &mut rng);
+            assert_eq!(drops.len(), 1);
+            let index = items.iter().position(|i| *i == drops[0].item_name).unwrap();
+            seen[index] = true;
+        }
+        assert_eq!(seen, [true; 2]);
+    }
+
+    #[test]
+    fn self_referencing_table_terminates() {
+        let block_id = Ident::from_str("minecraft:stone").unwrap();
+        let mut stone = table_with_pools(vec![
+            vec![item("minecraft:cobblestone")],
+            vec![reference("minecraft:blocks/stone")],
+        ]);
+        stone.id = Some(BlockLootTables::table_id(&block_id));
+        assert_eq!(
+            stone.id.as_ref().unwrap().as_str(),
+            "minecraft:blocks/stone"
+        );
+        let mut tables = BlockLootTables::default();
+        tables.tables.insert(block_id, stone);
+        let ctx = BlockBreakContext {
+            tool_enchantments: None,
+            block_pos: BlockPos::new(0, 64, 0),
+            loot_tables: Some(&tables),
+            luck: 0.0,
+        };
+
+        let stone = &tables.tables["minecraft:stone"];
+        let drops = stone.evaluate(&ctx, 0, &mut XoroshiroRandom::new(0));
+        // The table is on the visited list before its first roll, so its
+        // reference to itself is cut straight away.
+        assert_eq!(drops.len(), 1);
+        assert_eq!(drops[0].item_name.as_str(), "minecraft:cobblestone");
+    }
+
+    /// Item stacks carry no components, so a table that would enchant or
+    /// set components on its drops is refused instead of dropping plain items.
+    #[test]
+    fn component_functions_are_rejected() {
+        let table = |entry_function: &str, pool_function: &str| -> LootTableProto {
+            serde_json::from_value(serde_json::json!({
+                "type": "minecraft:block",
+                "pools": [{
+                    "rolls": 1.0,
+                    "entries": [{
+                        "type": "minecraft:item",
+                        "name": "minecraft:book",
+                        "functions": [{ "function": entry_function, "options": "#minecraft:on_random_loot" }]
+                    }],
+                    "functions": [{ "function": pool_function, "components": {} }]
+                }]
+            }))
+            .unwrap()
+        };
+        let resolve = |proto: LootTableProto| {
+            proto
+                .resolve(&StaticRegistry::new(), &StaticRegistry::new(), &TagRegistry::default())
+                .map(|_| ())
+        };
+
+        assert_eq!(
+            resolve(table("minecraft:enchant_randomly", "minecraft:explosion_decay")),
+            Err(UnsupportedLootFunction("minecraft:enchant_randomly"))
+        );
+        assert_eq!(
+            resolve(table("minecraft:explosion_decay", "minecraft:set_components")),
+            Err(UnsupportedLootFunction("minecraft:set_components"))
+        );
+        assert_eq!(
+            resolve(table("minecraft:explosion_decay", "minecraft:explosion_decay")),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn unresolved_reference_is_dropped() {
+        let table = table_with_pools(vec![
+            vec![item("minecraft:dirt")],
+            vec![reference("minecraft:gameplay/fishing")],
+        ]);
+        let tables = BlockLootTables::default();
+        let ctx = BlockBreakContext {
+            tool_enchantments: None,
+            block_pos: BlockPos::new(0, 64, 0),
+            loot_tables: Some(&tables),
+            luck: 0.0,
+        };
+
+        let drops = table.evaluate(&ctx, 0, &mut XoroshiroRandom::new(0));
+        assert_eq!(drops.len(), 1);
+        assert_eq!(drops[0].item_name.as_str(), "minecraft:dirt");
+    }
+
+    #[test]
+    fn entry_selection_tracks_weights() {
+        let names = ["minecraft:stick", "minecraft:coal", "minecraft:diamond"];
+        let weights = [1u32, 3, 6];
+        let table = table_with_pools(vec![
+            names
+                .iter()
+                .zip(weights)
+                .map(|(name, weight)| weighted_item(name, weight, 0))
+                .collect(),
+        ]);
+        let ctx = ctx_at(BlockPos::new(0, 64, 0));
+        let mut rng = XoroshiroRandom::new(11);
+
+        const ROLLS: usize = 20_000;
+        let mut hits = [0usize; 3];
+        for _ in 0..ROLLS {
+            let drops = table.evaluate(&ctx, 0, &mut rng);
+            assert_eq!(drops.len(), 1, "one entry is picked per roll");
+            let index = names
+                .iter()
+                .position(|name| drops[0].item_name.as_str() == *name)
+                .unwrap();
+            hits[index] += 1;
+        }
+
+        let total_weight: u32 = weights.iter().sum();
+        for (hits, weight) in hits.into_iter().zip(weights) {
+            let observed = hits as f64 / ROLLS as f64;
+            let expected = weight as f64 / total_weight as f64;
+            assert!(
+                (observed - expected).abs() < 0.02,
+                "weight {weight}: observed {observed:.3}, expected {expected:.3}"
+            );
+        }
+    }
+
+    #[test]
+    fn quality_shifts_weight_with_luck() {
+        let entry = weighted_item("minecraft:emerald", 2, 3);
+        assert_eq!(entry.effective_weight(0.0), 2);
+        assert_eq!(entry.effective_weight(1.5), 6);
+        // Negative effective weights clamp to zero and are never picked.
+        assert_eq!(
+            weighted_item("minecraft:dirt", 1, -2).effective_weight(1.0),
+            0
+        );
+
+        let table = table_with_pools(vec![vec![
+            weighted_item("minecraft:dirt", 1, -2),
+            item("minecraft:stone"),
+        ]]);
+        let mut ctx = ctx_at(BlockPos::new(0, 64, 0));
+        ctx.luck = 1.0;
+        let mut rng = XoroshiroRandom::new(2);
+        for _ in 0..64 {
+            let drops = table.evaluate(&ctx, 0, &mut rng);
+            assert_eq!(drops[0].item_name.as_str(), "minecraft:stone");
+        }
+    }
+}
@@ -61,17 +61,22 @@ impl Plugin for WorldPlugin {
                 .after(mcrs_network::NetworkSet::SpawnConnections),
         );
 
-        // BridgeSet ordering: Outbound fills queues from the bus, Dispatch
-        // encodes + sends, Inbound reads sockets and routes to partitions.
+        // BridgeSet ordering: Inbound reads sockets and routes to partitions
+        // in NetworkSet::Receive, then Outbound fills queues from the bus and
+        // Dispatch encodes + sends in NetworkSet::Flush, so host systems in
+        // NetworkSet::Process between them answer within the same tick.
         // All three run in FixedPostUpdate (after DimSubApp extracts).
         app.configure_sets(
             FixedPostUpdate,
             (
-                crate::world::bridge::BridgeSet::Outbound,
-                crate::world::bridge::BridgeSet::Dispatch,
-                crate::world::bridge::BridgeSet::Inbound,
-            )
-                .chain(),
+                crate::world::bridge::BridgeSet::Inbound.in_set(mcrs_network::NetworkSet::Receive),
+                (
+                    crate::world::bridge::BridgeSet::Outbound,
+                    crate::world::bridge::BridgeSet::Dispatch,
+                )
+                    .chain()
+                    .in_set(mcrs_network::NetworkSet::Flush),
+            ),
         );
         app.add_systems(
             FixedPostUpdate,
@@ -92,6 +97,7 @@ impl Plugin for WorldPlugin {
                 crate::world::bridge::resolve_transfer_requests,
                 crate::world::bridge::bridge_player_transfer,
                 crate::world::bridge::bridge_player_attach,
+                crate::world::bridge::bridge_player_disconnect,
             )
                 .chain(),
         );
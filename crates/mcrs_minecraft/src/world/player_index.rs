@@ -36,6 +36,15 @@ impl PlayerIndex {
         self.players.iter()
     }
 
+    /// Sockets of the players that are in a dimension: their Login or
+    /// Respawn has been sent and they are not mid-transfer.
+    pub fn attached_sockets(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.players
+            .values()
+            .filter(|location| location.in_dim_entity.is_some())
+            .map(|location| location.socket)
+    }
+
     pub fn len(&self) -> usize {
         self.players.len()
     }
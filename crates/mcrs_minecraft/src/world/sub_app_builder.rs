@@ -37,17 +37,31 @@ use mcrs_minecraft_block::block_update::{BlockPlaced, BlockSetRequest};
 ///   each pump rather than being driven by accumulated `Time<Fixed>`. The host
 ///   itself owns the fixed-timestep cadence; the sub-app mirrors it 1:1.
 /// - No `SpawnScene` (we do not depend on `bevy_scene`).
+///
+/// The Fixed* schedules are skipped while the host's mirrored [`TickRate`]
+/// is frozen and not stepping, so a frozen server stops every dimension too.
 #[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
 struct DimTick;
+use crate::chat_session::{ChatConfig, ChatSession};
+use crate::client_info::{ClientInfo, ServerViewConfig};
+use crate::server_config::ServerConfig;
+use crate::spawn_point::SpawnPoint;
+use crate::tick_rate::TickRate;
 use crate::world::aoi::PlayerTrackerPlugin;
 use crate::world::block::minecraft::MinecraftBlockPlugin;
 use crate::world::block_update::{BlockUpdatePlugin, BlockUpdateWirePlugin};
 use crate::world::entity::MinecraftEntityPlugin;
+use crate::world::entity::player::chat::SignedChatState;
+use crate::world::entity::player::column_view::ColumnView;
+use crate::world::entity::player::{HostAnchor, PlayerSkinParts};
 use crate::world::explosion::ExplosionPlugin;
 use crate::world::loot::LootPlugin;
+use crate::world::player_index::PlayerIndex;
+use crate::world_border::WorldBorder;
 use mcrs_core::registry::access::RegistryAccess;
 use mcrs_core::registry::static_registry::StaticRegistry;
 use mcrs_core::tag::TagRegistry;
+use mcrs_engine::entity::player::chunk_view::PlayerViewDistance;
 use mcrs_engine::world::dimension::{
     DimensionBundle, DimensionPlugin, HasSkyLight,
 };
@@ -56,9 +70,12 @@ use mcrs_engine::world::sub_app::{
 };
 use mcrs_minecraft_lighting::table::BlockStateLightTable;
 use mcrs_minecraft_lighting::LightingPlugin;
+use mcrs_minecraft_worldgen::bevy::{WorldGenConfig, resolve_sea_level};
+use mcrs_network::ServerSideConnection;
 use mcrs_vanilla::block::Block;
 use mcrs_vanilla::biome::Biome;
 use mcrs_vanilla::enchantment::EnchantmentData;
+use mcrs_vanilla::item::Item;
 use mcrs_core::RegistrySnapshot;
 
 #[derive(Clone)]
@@ -67,7 +84,9 @@ pub struct DimRegistryBundle {
     pub block_light_table: BlockStateLightTable,
     pub static_block_registry: StaticRegistry<Block>,
     pub static_enchantment_registry: StaticRegistry<EnchantmentData>,
+    pub static_item_registry: StaticRegistry<Item>,
     pub block_tag_registry: TagRegistry<Block>,
+    pub item_tag_registry: TagRegistry<Item>,
     pub biome_registry: RegistrySnapshot<Biome>,
 }
 
@@ -77,7 +96,9 @@ pub fn gather_dim_registries(world: &bevy_ecs::world::World) -> DimRegistryBundl
         block_light_table: world.resource::<BlockStateLightTable>().clone(),
         static_block_registry: world.resource::<StaticRegistry<Block>>().clone(),
         static_enchantment_registry: world.resource::<StaticRegistry<EnchantmentData>>().clone(),
+        static_item_registry: world.resource::<StaticRegistry<Item>>().clone(),
         block_tag_registry: world.resource::<TagRegistry<Block>>().clone(),
+        item_tag_registry: world.resource::<TagRegistry<Item>>().clone(),
         biome_registry: world.resource::<RegistrySnapshot<Biome>>().clone(),
     }
 }
@@ -196,11 +217,16 @@ pub fn spawn_dim_subapp(
             }
             world.run_schedule(First);
             world.run_schedule(PreUpdate);
-            world.run_schedule(FixedFirst);
-            world.run_schedule(FixedPreUpdate);
-            world.run_schedule(FixedUpdate);
-            world.run_schedule(FixedPostUpdate);
-            world.run_schedule(FixedLast);
+            if world
+                .get_resource::<TickRate>()
+                .is_none_or(TickRate::is_ticking)
+            {
+                world.run_schedule(FixedFirst);
+                world.run_schedule(FixedPreUpdate);
+                world.run_schedule(FixedUpdate);
+                world.run_schedule(FixedPostUpdate);
+                world.run_schedule(FixedLast);
+            }
             world.run_schedule(Update);
             world.run_schedule(PostUpdate);
             world.run_schedule(Last);
@@ -251,7 +277,9 @@ pub fn spawn_dim_subapp(
     sub_app.insert_resource(registries.block_light_table.clone());
     sub_app.insert_resource(registries.static_block_registry.clone());
     sub_app.insert_resource(registries.static_enchantment_registry.clone());
+    sub_app.insert_resource(registries.static_item_registry.clone());
     sub_app.insert_resource(registries.block_tag_registry.clone());
+    sub_app.insert_resource(registries.item_tag_registry.clone());
     sub_app.insert_resource(registries.biome_registry.clone());
 
     // Seed the time resources so an inspector that reads `Res<Time<…>>` on a
@@ -260,6 +288,18 @@ pub fn spawn_dim_subapp(
     sub_app.insert_resource(Time::<Fixed>::default());
     sub_app.insert_resource(Time::<Virtual>::default());
     sub_app.insert_resource(Time::<Real>::default());
+    // The border lives on the host, which owns the connections it is synced
+    // to; per-dim movement reads the mirror to keep players inside it.
+    sub_app.init_resource::<WorldBorder>();
+    // Chat is validated per-dim, but the session a client announces arrives
+    // on its host connection.
+    sub_app.init_resource::<ChatConfig>();
+    // Login and entity ticking happen per-dim, the config is set on the host.
+    sub_app.init_resource::<ServerViewConfig>();
+    // The play login is emitted per-dim; the server settings and world spawn
+    // it carries are set on the host.
+    sub_app.init_resource::<ServerConfig>();
+    sub_app.init_resource::<SpawnPoint>();
 
     // Merged extract closure: time-resource shuttle (existing) + bus
     // shuttle (new). `SubApp::set_extract` replaces — does not compose —
@@ -284,6 +324,70 @@ pub fn spawn_dim_subapp(
         if let Some(time) = main_world.get_resource::<Time<()>>() {
             sub_world.insert_resource(*time);
         }
+        if let Some(tick_rate) = main_world.get_resource::<TickRate>() {
+            sub_world.insert_resource(*tick_rate);
+        }
+        if let Some(border) = main_world.get_resource::<WorldBorder>() {
+            sub_world.insert_resource(border.clone());
+        }
+        if let Some(chat_config) = main_world.get_resource::<ChatConfig>() {
+            sub_world.insert_resource(chat_config.clone());
+        }
+        if let Some(view_config) = main_world.get_resource::<ServerViewConfig>() {
+            sub_world.insert_resource(view_config.clone());
+        }
+        if let Some(server_config) = main_world.get_resource::<ServerConfig>() {
+            sub_world.insert_resource(server_config.clone());
+        }
+        if let Some(spawn_point) = main_world.get_resource::<SpawnPoint>() {
+            sub_world.insert_resource(spawn_point.clone());
+        }
+        // Per-player state the host connection owns is mirrored onto the
+        // player's per-dim entities. The anchor is not the connection entity
+        // itself: `PlayerIndex` maps it to the socket.
+        let player_index = main_world.get_resource::<PlayerIndex>();
+        let connection = |host_anchor: &HostAnchor| {
+            player_index
+                .and_then(|index| index.get(&host_anchor.0))
+                .and_then(|location| main_world.get_entity(location.socket).ok())
+        };
+        // Chunk sends back off for clients whose socket is backed up.
+        let mut column_views = sub_world.query::<(&HostAnchor, &mut ColumnView)>();
+        for (host_anchor, mut view) in column_views.iter_mut(sub_world) {
+            let pressure = connection(host_anchor)
+                .and_then(|con| con.get::<ServerSideConnection>())
+                .map_or(0.0, |con| con.send_buffer_pressure());
+            if view.send_buffer_pressure != pressure {
+                view.send_buffer_pressure = pressure;
+            }
+        }
+        let mut signed_chats = sub_world.query::<(&HostAnchor, &mut SignedChatState)>();
+        for (host_anchor, mut signed_chat) in signed_chats.iter_mut(sub_world) {
+            let session = connection(host_anchor).and_then(|con| con.get::<ChatSession>());
+            if signed_chat.session() != session {
+                signed_chat.set_session(session.cloned());
+            }
+        }
+        let mut skin_parts = sub_world.query::<(&HostAnchor, &mut PlayerSkinParts)>();
+        for (host_anchor, mut skin) in skin_parts.iter_mut(sub_world) {
+            let Some(info) = connection(host_anchor).and_then(|con| con.get::<ClientInfo>()) else {
+                continue;
+            };
+            if skin.0 != info.displayed_skin_parts {
+                skin.0 = info.displayed_skin_parts;
+            }
+        }
+        // Client Information arrives on the host connection; the chunk stream
+        // it sizes runs per-dim.
+        let mut view_distances = sub_world.query::<(&HostAnchor, &mut PlayerViewDistance)>();
+        for (host_anchor, mut view_distance) in view_distances.iter_mut(sub_world) {
+            let Some(info) = connection(host_anchor).and_then(|con| con.get::<ClientInfo>()) else {
+                continue;
+            };
+            if view_distance.distance != info.view_distance {
+                view_distance.distance = info.view_distance;
+            }
+        }
 
         let drained: Vec<OutboundPlayerPacket> = sub_world
             .resource_mut::<Messages<OutboundPlayerPacket>>()
@@ -344,6 +448,17 @@ pub fn spawn_dim_subapp(
             }
         }
 
+        let drained_disconnects: Vec<OutboundPlayerDisconnect> = sub_world
+            .resource_mut::<Messages<OutboundPlayerDisconnect>>()
+            .drain()
+            .collect();
+        if !drained_disconnects.is_empty() {
+            let mut main_msgs = main_world.resource_mut::<Messages<OutboundPlayerDisconnect>>();
+            for msg in drained_disconnects {
+                main_msgs.write(msg);
+            }
+        }
+
         let (spawns, despawns, block_events) = {
             let mut bundle = main_world.resource_mut::<PendingInboundLifecycle>();
             let entry = bundle.per_dim.entry(label_entity).or_default();
@@ -390,6 +505,20 @@ pub fn spawn_dim_subapp(
         .map(|i| i as i32)
         .unwrap_or(0);
 
+    // The sea level the client is told about comes from this dimension's
+    // own generator in the world preset, not the overworld router.
+    let sea_level = sub_app
+        .world()
+        .get_resource::<WorldGenConfig>()
+        .and_then(|config| {
+            resolve_sea_level(
+                &config.preset_namespace,
+                &config.preset_path,
+                request.dimension_id.as_str(),
+            )
+        })
+        .unwrap_or(DimSeaLevel::DEFAULT);
+
     let dim_entity = sub_app
         .world_mut()
         .spawn((
@@ -399,6 +528,7 @@ pub fn spawn_dim_subapp(
                 ..Default::default()
             },
             DimTypeIndex(dim_type_index),
+            DimSeaLevel(sea_level),
         ))
         .id();
     if request.has_sky {
@@ -438,6 +568,18 @@ pub struct DimSubAppHandle;
 #[derive(bevy_ecs::component::Component, Clone, Copy)]
 pub struct DimTypeIndex(pub i32);
 
+/// The sea level of a dimension's chunk generator, resolved from the world
+/// preset when the sub-app is spawned and stored on the sub-world's
+/// `Dimension` entity. Login and Respawn carry it to the client.
+#[derive(bevy_ecs::component::Component, Clone, Copy)]
+pub struct DimSeaLevel(pub i32);
+
+impl DimSeaLevel {
+    /// Vanilla's overworld sea level, for a dimension whose generator could
+    /// not be resolved.
+    pub const DEFAULT: i32 = 63;
+}
+
 /// The dimension resource location (e.g. "minecraft:the_nether") of the
 /// sub-app anchored by this host-world label entity. Lets a name-based
 /// transfer request resolve to the destination sub-app's label entity.
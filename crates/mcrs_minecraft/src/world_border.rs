@@ -0,0 +1,398 @@
+//! The square world border: its center and size, smooth size transitions, and
+//! the packets that keep players in a dimension in sync with it.
+
+use crate::world::bridge::PlayerAttached;
+use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
+use crate::world::player_index::PlayerIndex;
+use bevy_app::{App, FixedUpdate, Plugin};
+use bevy_ecs::message::MessageWriter;
+use bevy_ecs::prelude::{IntoScheduleConfigs, On, Query, Res, ResMut};
+use bevy_ecs::resource::Resource;
+use bevy_math::{DVec2, DVec3};
+use bevy_time::{Fixed, Time};
+use mcrs_network::ServerSideConnection;
+use mcrs_protocol::packets::game::clientbound::{
+    ClientboundInitializeBorder, ClientboundSetBorderCenter, ClientboundSetBorderLerpSize,
+    ClientboundSetBorderSize, ClientboundSetBorderWarningDelay,
+    ClientboundSetBorderWarningDistance,
+};
+use mcrs_protocol::{VarInt, VarLong, WritePacket};
+
+/// Vanilla's default border size, just inside the 30 million block limit.
+pub const DEFAULT_DIAMETER: f64 = 59_999_968.0;
+
+/// Vanilla `absoluteMaxSize`: the furthest the client lets the border reach
+/// from the origin, whatever its center and size.
+pub const ABSOLUTE_MAX_SIZE: i32 = 29_999_984;
+
+pub struct WorldBorderPlugin;
+
+impl Plugin for WorldBorderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldBorder>();
+        app.add_systems(
+            FixedUpdate,
+            (tick_world_border, broadcast_world_border).chain(),
+        );
+        app.add_observer(send_border_on_attach);
+    }
+}
+
+/// The border shared by every dimension. Each setter queues the packet
+/// that tells players about the change, sent on the next tick.
+#[derive(Resource, Debug, Clone)]
+pub struct WorldBorder {
+    center: DVec2,
+    diameter: f64,
+    warning_blocks: i32,
+    warning_time: i32,
+    lerp_target: f64,
+    lerp_time: i64,
+    /// Size and length of the transition in progress, so each tick
+    /// interpolates from the start rather than accumulating rounding.
+    lerp_from: f64,
+    lerp_duration: i64,
+    pending: Vec<BorderUpdate>,
+}
+
+impl Default for WorldBorder {
+    fn default() -> Self {
+        Self {
+            center: DVec2::ZERO,
+            diameter: DEFAULT_DIAMETER,
+            warning_blocks: 5,
+            warning_time: 15,
+            lerp_target: DEFAULT_DIAMETER,
+            lerp_time: 0,
+            lerp_from: DEFAULT_DIAMETER,
+            lerp_duration: 0,
+            pending: Vec::new(),
+        }
+    }
+}
+
+/// A change clients have not been told about yet, with the values at the time
+/// it was made.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BorderUpdate {
+    Center(DVec2),
+    Size(f64),
+    LerpSize {
+        from: f64,
+        to: f64,
+        millis: i64,
+    },
+    WarningBlocks(i32),
+    WarningTime(i32),
+    /// Resend the whole state.
+    Initialize,
+}
+
+impl WorldBorder {
+    pub fn center(&self) -> DVec2 {
+        self.center
+    }
+
+    pub fn set_center(&mut self, center: DVec2) {
+        self.center = center;
+        self.pending.push(BorderUpdate::Center(center));
+    }
+
+    /// Current side length of the border, in blocks.
+    pub fn diameter(&self) -> f64 {
+        self.diameter
+    }
+
+    /// Resize at once, stopping any transition in progress.
+    pub fn set_diameter(&mut self, diameter: f64) {
+        self.diameter = diameter;
+        self.lerp_target = diameter;
+        self.lerp_time = 0;
+        self.pending.push(BorderUpdate::Size(diameter));
+    }
+
+    /// The size the border is moving toward; its diameter while still.
+    pub fn lerp_target(&self) -> f64 {
+        self.lerp_target
+    }
+
+    /// Milliseconds left until the border reaches [`Self::lerp_target`].
+    pub fn lerp_time(&self) -> i64 {
+        self.lerp_time
+    }
+
+    /// Move from the current size to `target` over `millis` milliseconds.
+    /// Clients animate the transition themselves from the one packet.
+    pub fn lerp_diameter(&mut self, target: f64, millis: i64) {
+        if millis <= 0 {
+            self.set_diameter(target);
+            return;
+        }
+        self.lerp_target = target;
+        self.lerp_time = millis;
+        self.lerp_from = self.diameter;
+        self.lerp_duration = millis;
+        self.pending.push(BorderUpdate::LerpSize {
+            from: self.diameter,
+            to: target,
+            millis,
+        });
+    }
+
+    /// Distance from the border at which the client starts tinting the
+    /// screen.
+    pub fn warning_blocks(&self) -> i32 {
+        self.warning_blocks
+    }
+
+    pub fn set_warning_blocks(&mut self, warning_blocks: i32) {
+        self.warning_blocks = warning_blocks;
+        self.pending
+            .push(BorderUpdate::WarningBlocks(warning_blocks));
+    }
+
+    /// Seconds before a moving border reaches the player at which the
+    /// client starts warning about it.
+    pub fn warning_time(&self) -> i32 {
+        self.warning_time
+    }
+
+    pub fn set_warning_time(&mut self, warning_time: i32) {
+        self.warning_time = warning_time;
+        self.pending.push(BorderUpdate::WarningTime(warning_time));
+    }
+
+    /// Resend the full border to every player in a dimension on the next
+    /// tick.
+    pub fn request_broadcast(&mut self) {
+        self.pending.push(BorderUpdate::Initialize);
+    }
+
+    /// Advance a transition in progress by `millis` milliseconds.
+    pub fn tick(&mut self, millis: i64) {
+        if self.lerp_time <= 0 {
+            return;
+        }
+        self.lerp_time = (self.lerp_time - millis).max(0);
+        if self.lerp_time == 0 || self.lerp_duration <= 0 {
+            self.lerp_time = 0;
+            self.diameter = self.lerp_target;
+            return;
+        }
+        let progress = 1.0 - self.lerp_time as f64 / self.lerp_duration as f64;
+        self.diameter = self.lerp_from + (self.lerp_target - self.lerp_from) * progress;
+    }
+
+    /// Smallest corner of the border, on the X/Z plane.
+    pub fn min(&self) -> DVec2 {
+        let limit = ABSOLUTE_MAX_SIZE as f64;
+        (self.center - self.diameter / 2.0).max(DVec2::splat(-limit))
+    }
+
+    /// Largest corner of the border, on the X/Z plane.
+    pub fn max(&self) -> DVec2 {
+        let limit = ABSOLUTE_MAX_SIZE as f64;
+        (self.center + self.diameter / 2.0).min(DVec2::splat(limit))
+    }
+
+    pub fn contains(&self, x: f64, z: f64) -> bool {
+        let (min, max) = (self.min(), self.max());
+        (min.x..=max.x).contains(&x) && (min.y..=max.y).contains(&z)
+    }
+
+    /// `position` moved onto the border if it is outside. Y is left as is.
+    pub fn clamp(&self, position: DVec3) -> DVec3 {
+        let (min, max) = (self.min(), self.max());
+        DVec3::new(
+            position.x.clamp(min.x, max.x),
+            position.y,
+            position.z.clamp(min.y, max.y),
+        )
+    }
+
+    /// The Initialize Border packet for the current state, including the rest
+    /// of a transition in progress.
+    pub fn initialize_packet(&self) -> ClientboundInitializeBorder {
+        ClientboundInitializeBorder {
+            center_x: self.center.x,
+            center_z: self.center.y,
+            old_size: self.diameter,
+            new_size: self.lerp_target,
+            lerp_time: VarLong(self.lerp_time),
+            absolute_max_size: VarInt(ABSOLUTE_MAX_SIZE),
+            warning_blocks: VarInt(self.warning_blocks),
+            warning_time: VarInt(self.warning_time),
+        }
+    }
+
+    fn write_update(&self, update: BorderUpdate, out: &mut impl WritePacket) {
+        match update {
+            BorderUpdate::Center(center) => out.write_packet(&ClientboundSetBorderCenter {
+                x: center.x,
+                z: center.y,
+            }),
+            BorderUpdate::Size(size) => out.write_packet(&ClientboundSetBorderSize { size }),
+            BorderUpdate::LerpSize { from, to, millis } => {
+                out.write_packet(&ClientboundSetBorderLerpSize {
+                    old_size: from,
+                    new_size: to,
+                    lerp_time: VarLong(millis),
+                })
+            }
+            BorderUpdate::WarningBlocks(warning_blocks) => {
+                out.write_packet(&ClientboundSetBorderWarningDistance {
+                    warning_blocks: VarInt(warning_blocks),
+                })
+            }
+            BorderUpdate::WarningTime(warning_time) => {
+                out.write_packet(&ClientboundSetBorderWarningDelay {
+                    warning_time: VarInt(warning_time),
+                })
+            }
+            BorderUpdate::Initialize => out.write_packet(&self.initialize_packet()),
+        }
+    }
+}
+
+pub fn tick_world_border(mut border: ResMut<WorldBorder>, time: Res<Time<Fixed>>) {
+    border.tick(time.delta().as_millis() as i64);
+}
+
+/// Send the changes made since the last tick to every player in a
+/// dimension. Players still waiting for their Login get the whole border
+/// from [`send_border_on_attach`] instead.
+pub fn broadcast_world_border(
+    mut border: ResMut<WorldBorder>,
+    player_index: Res<PlayerIndex>,
+    mut connections: Query<&mut ServerSideConnection>,
+) {
+    let updates = std::mem::take(&mut border.pending);
+    if updates.is_empty() {
+        return;
+    }
+    for socket in player_index.attached_sockets() {
+        let Ok(mut con) = connections.get_mut(socket) else {
+            continue;
+        };
+        for &update in &updates {
+            border.write_update(update, &mut *con);
+        }
+    }
+}
+
+/// Send the whole border to a player entering a dimension, through the
+/// bridge so it lands after the Login or Respawn already queued for them.
+fn send_border_on_attach(
+    attached: On<PlayerAttached>,
+    border: Res<WorldBorder>,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
+) {
+    packet_writer.write(OutboundPlayerPacket {
+        target: PacketTarget::SinglePlayer(attached.host_anchor),
+        priority: PacketPriority::High,
+        data: PacketPayload::InitializeBorder(border.initialize_packet()),
+    });
+    mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL
+        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcrs_protocol::decode::PacketFrame;
+    use mcrs_protocol::{Packet, PacketDecoder, PacketEncoder};
+
+    /// Encode the pending updates the way the broadcast does and decode them
+    /// back into frames.
+    fn sent_frames(border: &mut WorldBorder) -> Vec<PacketFrame> {
+        let mut encoder = PacketEncoder::new();
+        for update in std::mem::take(&mut border.pending) {
+            border.write_update(update, &mut encoder);
+        }
+        let mut decoder = PacketDecoder::new();
+        decoder.queue_bytes(encoder.take());
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.try_next_packet().unwrap() {
+            frames.push(frame);
+        }
+        frames
+    }
+
+    #[test]
+    fn shrinking_sends_lerp_size_with_the_duration_in_millis() {
+        let mut border = WorldBorder::default();
+        border.set_diameter(100.0);
+        sent_frames(&mut border);
+
+        border.lerp_diameter(50.0, 10_000);
+        let frames = sent_frames(&mut border);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].id, ClientboundSetBorderLerpSize::ID);
+        let lerp = frames[0].decode::<ClientboundSetBorderLerpSize>().unwrap();
+        assert_eq!(lerp.old_size, 100.0);
+        assert_eq!(lerp.new_size, 50.0);
+        assert_eq!(lerp.lerp_time, VarLong(10_000));
+
+        // Halfway through, a joining player gets the rest of the transition.
+        for _ in 0..100 {
+            border.tick(50);
+        }
+        assert_eq!(border.diameter, 75.0);
+        let init = border.initialize_packet();
+        assert_eq!((init.old_size, init.new_size), (75.0, 50.0));
+        assert_eq!(init.lerp_time, VarLong(5_000));
+
+        for _ in 0..101 {
+            border.tick(50);
+        }
+        assert_eq!(border.diameter, 50.0);
+        assert_eq!(border.lerp_time, 0);
+        assert!(sent_frames(&mut border).is_empty());
+    }
+
+    #[test]
+    fn instant_changes_send_one_packet_each() {
+        let mut border = WorldBorder::default();
+        border.set_center(DVec2::new(8.0, -8.0));
+        border.lerp_diameter(200.0, 0);
+        border.set_warning_blocks(10);
+        border.set_warning_time(30);
+        let ids: Vec<i32> = sent_frames(&mut border)
+            .iter()
+            .map(|frame| frame.id)
+            .collect();
+        assert_eq!(
+            ids,
+            [
+                ClientboundSetBorderCenter::ID,
+                ClientboundSetBorderSize::ID,
+                ClientboundSetBorderWarningDistance::ID,
+                ClientboundSetBorderWarningDelay::ID,
+            ]
+        );
+        assert_eq!(border.lerp_target, 200.0);
+    }
+
+    #[test]
+    fn positions_outside_are_clamped_onto_the_border() {
+        let mut border = WorldBorder::default();
+        border.set_center(DVec2::new(10.0, 0.0));
+        border.set_diameter(20.0);
+        assert!(border.contains(0.0, 10.0));
+        assert!(!border.contains(-0.5, 0.0));
+        assert_eq!(
+            border.clamp(DVec3::new(30.0, 64.0, -15.0)),
+            DVec3::new(20.0, 64.0, -10.0)
+        );
+        let inside = DVec3::new(5.5, -20.0, 3.25);
+        assert_eq!(border.clamp(inside), inside);
+
+        // The default border stops at the absolute limit.
+        let far = DVec3::new(1e9, 0.0, -1e9);
+        let limit = ABSOLUTE_MAX_SIZE as f64;
+        assert_eq!(
+            WorldBorder::default().clamp(far),
+            DVec3::new(limit, 0.0, -limit)
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! World age and time of day, advanced once per fixed tick and broadcast to
+//! players in a dimension with the Set Time packet.
+
+use crate::world::bridge::PlayerAttached;
+use crate::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget};
+use crate::world::player_index::PlayerIndex;
+use bevy_app::{App, FixedUpdate, Plugin};
+use bevy_ecs::message::MessageWriter;
+use bevy_ecs::prelude::{IntoScheduleConfigs, On, Query, Res, ResMut};
+use bevy_ecs::resource::Resource;
+use mcrs_core::RegistryAccess;
+use mcrs_network::ServerSideConnection;
+use mcrs_protocol::packets::game::clientbound::{ClientboundSetTime, ClockUpdate};
+use mcrs_protocol::{VarInt, VarLong, WritePacket};
+use std::sync::atomic::Ordering;
+
+/// Length of one day-night cycle.
+pub const TICKS_PER_DAY: i64 = 24000;
+
+/// Ticks between regular Set Time broadcasts, like vanilla's once a second.
+const BROADCAST_INTERVAL: i64 = 20;
+
+pub struct WorldTimePlugin;
+
+impl Plugin for WorldTimePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldTime>();
+        app.add_systems(FixedUpdate, (tick_world_time, broadcast_world_time).chain());
+        app.add_observer(send_time_on_attach);
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct WorldTime {
+    /// Ticks since the world was created. Always advances.
+    pub world_age: i64,
+    /// Ticks on the overworld clock: the time of day plus every full day
+    /// before it. Not wrapped, so the day count, and with it the moon phase,
+    /// carries on.
+    pub day_time: i64,
+    /// `doDaylightCycle`: while false the time of day stays put and clients
+    /// are told to stop advancing it themselves.
+    pub daylight_cycle: bool,
+    broadcast_requested: bool,
+}
+
+impl Default for WorldTime {
+    fn default() -> Self {
+        Self {
+            world_age: 0,
+            day_time: 0,
+            daylight_cycle: true,
+            broadcast_requested: false,
+        }
+    }
+}
+
+impl WorldTime {
+    pub fn tick(&mut self) {
+        self.world_age += 1;
+        if self.daylight_cycle {
+            self.day_time += 1;
+        }
+    }
+
+    /// Position in the current day, in `0..TICKS_PER_DAY`.
+    pub fn time_of_day(&self) -> i64 {
+        self.day_time.rem_euclid(TICKS_PER_DAY)
+    }
+
+    /// Full days passed on the overworld clock.
+    pub fn day(&self) -> i64 {
+        self.day_time.div_euclid(TICKS_PER_DAY)
+    }
+
+    /// Jump to `time_of_day` (wrapped into the day) within the current day
+    /// and tell clients on the next tick instead of waiting for the regular
+    /// broadcast.
+    pub fn set_time_of_day(&mut self, time_of_day: i64) {
+        self.set_day_time(self.day() * TICKS_PER_DAY + time_of_day.rem_euclid(TICKS_PER_DAY));
+    }
+
+    /// Set the overworld clock itself, day count included.
+    pub fn set_day_time(&mut self, day_time: i64) {
+        self.day_time = day_time;
+        self.broadcast_requested = true;
+    }
+
+    pub fn set_daylight_cycle(&mut self, enabled: bool) {
+        self.daylight_cycle = enabled;
+        self.broadcast_requested = true;
+    }
+
+    /// Send Set Time to every player in a dimension on the next tick.
+    pub fn request_broadcast(&mut self) {
+        self.broadcast_requested = true;
+    }
+
+    /// The Set Time packet for the current state. The time of day travels as
+    /// the overworld clock; a frozen cycle is sent with rate 0, which stops
+    /// the client from ticking the clock forward between updates (the role
+    /// negative time of day played before world clocks).
+    pub fn set_time_packet(&self, overworld_clock: Option<VarInt>) -> ClientboundSetTime {
+        ClientboundSetTime {
+            game_time: self.world_age,
+            clock_updates: overworld_clock
+                .map(|clock| ClockUpdate {
+                    clock,
+                    total_ticks: VarLong(self.day_time),
+                    partial_tick: 0.0,
+                    rate: if self.daylight_cycle { 1.0 } else { 0.0 },
+                })
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+pub fn tick_world_time(mut time: ResMut<WorldTime>) {
+    time.tick();
+}
+
+/// Broadcast Set Time every [`BROADCAST_INTERVAL`] ticks or when requested.
+/// Players still waiting for their Login are skipped; they get the time
+/// from [`send_time_on_attach`].
+pub fn broadcast_world_time(
+    mut time: ResMut<WorldTime>,
+    access: Res<RegistryAccess>,
+    player_index: Res<PlayerIndex>,
+    mut connections: Query<&mut ServerSideConnection>,
+) {
+    if !time.broadcast_requested && time.world_age % BROADCAST_INTERVAL != 0 {
+        return;
+    }
+    time.broadcast_requested = false;
+
+    let packet = time.set_time_packet(overworld_clock(&access));
+    for socket in player_index.attached_sockets() {
+        if let Ok(mut con) = connections.get_mut(socket) {
+            con.write_packet(&packet);
+        }
+    }
+}
+
+/// Send the time to a player entering a dimension, through the bridge so it
+/// lands after the Login or Respawn already queued for them.
+fn send_time_on_attach(
+    attached: On<PlayerAttached>,
+    time: Res<WorldTime>,
+    access: Res<RegistryAccess>,
+    mut packet_writer: MessageWriter<OutboundPlayerPacket>,
+) {
+    packet_writer.write(OutboundPlayerPacket {
+        target: PacketTarget::SinglePlayer(attached.host_anchor),
+        priority: PacketPriority::High,
+        data: PacketPayload::SetTime(time.set_time_packet(overworld_clock(&access))),
+    });
+    mcrs_network::metrics::BRIDGE_OUTBOUND_MESSAGES_EMITTED_TOTAL.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Network id of `minecraft:overworld` in the world clock registry.
+fn overworld_clock(access: &RegistryAccess) -> Option<VarInt> {
+    access
+        .iter()
+        .find(|registry| registry.registry_key() == "minecraft:world_clock")?
+        .iter_entries()
+        .find(|entry| entry.location.as_str() == "minecraft:overworld")
+        .map(|entry| VarInt(entry.network_id as i32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLOCK: VarInt = VarInt(0);
+
+    #[test]
+    fn day_time_keeps_counting_days() {
+        let mut time = WorldTime::default();
+        time.set_time_of_day(6000);
+        for _ in 0..TICKS_PER_DAY {
+            time.tick();
+        }
+        assert_eq!(time.world_age, TICKS_PER_DAY);
+        assert_eq!(time.time_of_day(), 6000);
+        assert_eq!(time.day(), 1);
+
+        time.set_time_of_day(TICKS_PER_DAY - 1);
+        time.tick();
+        assert_eq!(time.time_of_day(), 0);
+        assert_eq!(time.day(), 2);
+
+        // The clock goes out whole, so the client's moon phase follows the
+        // day count.
+        let packet = time.set_time_packet(Some(CLOCK));
+        assert_eq!(packet.game_time, TICKS_PER_DAY + 1);
+        assert_eq!(
+            packet.clock_updates,
+            [ClockUpdate {
+                clock: CLOCK,
+                total_ticks: VarLong(2 * TICKS_PER_DAY),
+                partial_tick: 0.0,
+                rate: 1.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn frozen_cycle_keeps_time_of_day_and_pauses_the_client_clock() {
+        let mut time = WorldTime::default();
+        time.set_time_of_day(-1000);
+        assert_eq!(time.time_of_day(), 23000);
+        time.set_daylight_cycle(false);
+        for _ in 0..100 {
+            time.tick();
+        }
+        assert_eq!(time.world_age, 100);
+        assert_eq!(time.day_time, 23000);
+
+        let packet = time.set_time_packet(Some(CLOCK));
+        assert_eq!(packet.clock_updates[0].total_ticks, VarLong(23000));
+        assert_eq!(packet.clock_updates[0].rate, 0.0);
+    }
+
+    #[test]
+    fn missing_clock_registry_sends_only_world_age() {
+        let packet = WorldTime::default().set_time_packet(None);
+        assert!(packet.clock_updates.is_empty());
+    }
+}
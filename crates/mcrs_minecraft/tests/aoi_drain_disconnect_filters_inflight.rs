@@ -25,9 +25,10 @@ use bytes::Bytes;
 use mcrs_minecraft::world::bus::{
     InboundPlayerDespawn, InboundPlayerPacket, InboundPlayerSpawn, OutboundPlayerAttached,
     OutboundPlayerDisconnect, OutboundPlayerTransfer, PendingInboundLifecycle,
-    PendingInboundPartition, PlayerTransferSnapshot,
+    PendingInboundPartition, PlayerTransferSnapshot, SpawnReason,
 };
 use mcrs_minecraft::world::player_index::{PlayerIndex, PlayerLocation};
+use mcrs_protocol::GameMode;
 use mcrs_protocol::uuid::Uuid;
 use smallvec::SmallVec;
 
@@ -61,6 +62,7 @@ fn snapshot() -> PlayerTransferSnapshot {
         username: "drained".into(),
         position: DVec3::new(0.0, 64.0, 0.0),
         rotation: Vec2::ZERO,
+        game_mode: GameMode::Survival,
     }
 }
 
@@ -105,6 +107,7 @@ fn drain_tick_filters_inflight_transfer_for_queued_anchor() {
             host_anchor,
             dest_dim,
             snapshot: snapshot(),
+            reason: SpawnReason::DimensionChange,
         });
     }
 
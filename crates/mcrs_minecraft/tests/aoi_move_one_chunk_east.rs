@@ -0,0 +1,89 @@
+//! Moving one chunk east shifts the view window by one column: the new
+//! eastern edge is subscribed (and so queued for `send_column_queue`) and the
+//! old western edge is forgotten with a `ChunkUnload`, all in the same tick.
+
+use bevy_app::App;
+use bevy_ecs::entity::Entity;
+use bevy_math::DVec3;
+use mcrs_engine::aoi::PlayerObservers;
+use mcrs_engine::entity::physics::Transform;
+use mcrs_engine::entity::player::chunk_view::PlayerViewDistance;
+use mcrs_engine::geometry::ColumnPos;
+use mcrs_engine::world::dimension::{DimensionBundle, InDimension};
+use mcrs_engine::world::storage::column::{Column, ColumnIndex, ColumnSlot};
+use mcrs_minecraft::world::aoi::ChunkSubscriptionSet;
+use mcrs_minecraft::world::bus::PacketPayload;
+
+mod harness;
+use harness::{drain_outbound, drive_aoi_tick, make_aoi_app, spawn_player_in_dim};
+
+#[test]
+fn moving_one_chunk_east_loads_the_east_edge_and_forgets_the_west_edge() {
+    let mut app = make_aoi_app();
+    let dim = app.world_mut().spawn(DimensionBundle::default()).id();
+    let player = spawn_player_in_dim(&mut app, dim, DVec3::new(8.0, 64.0, 8.0));
+    let radius = app
+        .world()
+        .get::<PlayerViewDistance>(player)
+        .unwrap()
+        .distance as i32;
+    seed_column_grid(&mut app, dim, radius + 2);
+
+    drive_aoi_tick(&mut app);
+    drain_outbound(&mut app);
+
+    app.world_mut()
+        .get_mut::<Transform>(player)
+        .unwrap()
+        .translation = DVec3::new(24.0, 64.0, 8.0);
+    drive_aoi_tick(&mut app);
+
+    let subscriptions = &app.world().get::<ChunkSubscriptionSet>(player).unwrap().0;
+    for z in -radius..=radius {
+        assert!(
+            subscriptions.contains(&ColumnPos::new(radius + 1, z)),
+            "eastern column ({}, {z}) is subscribed",
+            radius + 1
+        );
+        assert!(
+            !subscriptions.contains(&ColumnPos::new(-radius, z)),
+            "western column ({}, {z}) is dropped",
+            -radius
+        );
+    }
+
+    let mut forgotten: Vec<ColumnPos> = drain_outbound(&mut app)
+        .into_iter()
+        .filter_map(|packet| match packet.data {
+            PacketPayload::ChunkUnload { column } => Some(column),
+            _ => None,
+        })
+        .collect();
+    forgotten.sort_by_key(|column| (column.x, column.z));
+    let western_edge: Vec<ColumnPos> = (-radius..=radius)
+        .map(|z| ColumnPos::new(-radius, z))
+        .collect();
+    assert_eq!(forgotten, western_edge);
+}
+
+fn seed_column_grid(app: &mut App, dim: Entity, radius: i32) {
+    for x in -radius..=radius {
+        for z in -radius..=radius {
+            let column = app
+                .world_mut()
+                .spawn((Column, PlayerObservers::default(), InDimension(dim)))
+                .id();
+            app.world_mut()
+                .get_mut::<ColumnIndex>(dim)
+                .expect("dimension has ColumnIndex")
+                .0
+                .insert(
+                    ColumnPos::new(x, z),
+                    ColumnSlot {
+                        entity: column,
+                        section_count: 1,
+                    },
+                );
+        }
+    }
+}
@@ -28,9 +28,10 @@ use mcrs_minecraft::world::bridge::bridge_player_transfer;
 use mcrs_minecraft::world::bus::{
     InboundPlayerDespawn, InboundPlayerSpawn, OutboundPlayerAttached, OutboundPlayerDisconnect,
     OutboundPlayerPacket, OutboundPlayerTransfer, PacketPayload, PacketTarget,
-    PendingInboundLifecycle, PendingInboundPartition, PlayerTransferSnapshot,
+    PendingInboundLifecycle, PendingInboundPartition, PlayerTransferSnapshot, SpawnReason,
 };
 use mcrs_minecraft::world::player_index::{PlayerIndex, PlayerLocation};
+use mcrs_protocol::GameMode;
 use mcrs_protocol::uuid::Uuid;
 use smallvec::SmallVec;
 
@@ -60,6 +61,7 @@ fn snapshot() -> PlayerTransferSnapshot {
         username: "disco".into(),
         position: DVec3::new(1.0, 64.0, 2.0),
         rotation: Vec2::ZERO,
+        game_mode: GameMode::Survival,
     }
 }
 
@@ -140,6 +142,7 @@ fn disconnect_at_tick_n_e1_1_source_emit_pre_extract() {
             host_anchor,
             dest_dim,
             snapshot: snapshot(),
+            reason: SpawnReason::DimensionChange,
         });
 
     synthetic_disconnect(&mut app, host_anchor);
@@ -194,6 +197,7 @@ fn disconnect_at_tick_n_e1_2_after_bridge_transfer() {
             host_anchor,
             dest_dim,
             snapshot: snapshot(),
+            reason: SpawnReason::DimensionChange,
         });
 
     run_bridge_transfer(&mut app);
@@ -382,6 +386,7 @@ fn transfer_out_eviction_matches_disconnect_via_shared_drain() {
             host_anchor,
             dest_dim,
             snapshot: snapshot(),
+            reason: SpawnReason::DimensionChange,
         });
     run_bridge_transfer(&mut bridge_app);
 
@@ -0,0 +1,175 @@
+//! Boss bar sync: new viewers get the whole bar, existing viewers only the
+//! aspects that changed, and removing a bar or a viewer hides it.
+
+#[path = "common/mock_connection.rs"]
+mod mock_connection;
+
+use bevy_ecs::entity::Entity;
+use bevy_ecs::schedule::Schedule;
+use bevy_ecs::world::World;
+use bytes::Bytes;
+use mcrs_minecraft::boss_bar::{BossBar, BossBars, broadcast_boss_bars};
+use mcrs_network::{EngineConnection, InGameConnectionState, ServerSideConnection};
+use mcrs_protocol::PacketDecoder;
+use mcrs_protocol::boss_event::{BossBarColor, BossBarDivision, BossEventOperation};
+use mcrs_protocol::packets::game::clientbound::ClientboundBossEvent;
+use mcrs_protocol::uuid::Uuid;
+use tokio::sync::mpsc;
+
+struct Harness {
+    world: World,
+    schedule: Schedule,
+}
+
+struct Client {
+    entity: Entity,
+    outgoing_rx: mpsc::Receiver<Bytes>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let mut world = World::new();
+        world.init_resource::<BossBars>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(broadcast_boss_bars);
+        Self { world, schedule }
+    }
+
+    fn join(&mut self) -> Client {
+        let (raw, outgoing_rx) = mock_connection::make_mock_raw_connection();
+        let entity = self
+            .world
+            .spawn((
+                ServerSideConnection { raw: Box::new(raw) },
+                InGameConnectionState,
+            ))
+            .id();
+        Client {
+            entity,
+            outgoing_rx,
+        }
+    }
+
+    fn bars(&mut self) -> bevy_ecs::world::Mut<'_, BossBars> {
+        self.world.resource_mut::<BossBars>()
+    }
+
+    fn tick(&mut self) {
+        self.schedule.run(&mut self.world);
+    }
+
+    /// Flush the client's connection and decode every Boss Event it was sent.
+    fn sent_events(&mut self, client: &mut Client) -> Vec<ClientboundBossEvent> {
+        self.world
+            .get_mut::<ServerSideConnection>(client.entity)
+            .unwrap()
+            .flush()
+            .unwrap();
+        let mut decoder = PacketDecoder::new();
+        while let Ok(blob) = client.outgoing_rx.try_recv() {
+            decoder.queue_bytes(blob.into());
+        }
+        let mut events = Vec::new();
+        while let Some(frame) = decoder.try_next_packet().unwrap() {
+            events.push(frame.decode::<ClientboundBossEvent>().unwrap());
+        }
+        events
+    }
+}
+
+/// A half-full raid bar shown to both `viewers`.
+fn raid_bar(harness: &mut Harness, viewers: [&Client; 2]) -> Uuid {
+    let mut bar = BossBar::new("Raid")
+        .with_progress(0.5)
+        .with_style(BossBarColor::Red, BossBarDivision::Notched10);
+    for viewer in viewers {
+        bar.add_viewer(viewer.entity);
+    }
+    harness.bars().insert(bar)
+}
+
+#[test]
+fn new_viewers_get_an_add_and_progress_sends_one_update() {
+    let mut harness = Harness::new();
+    let mut alice = harness.join();
+    let mut bob = harness.join();
+    let id = raid_bar(&mut harness, [&alice, &bob]);
+    harness.tick();
+
+    for client in [&mut alice, &mut bob] {
+        let events = harness.sent_events(client);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, id);
+        let BossEventOperation::Add {
+            title,
+            progress,
+            color,
+            division,
+            ..
+        } = &events[0].operation
+        else {
+            panic!("expected an Add, got {:?}", events[0].operation);
+        };
+        assert_eq!(title, &"Raid".into());
+        assert_eq!(*progress, 0.5);
+        assert_eq!(*color, BossBarColor::Red);
+        assert_eq!(*division, BossBarDivision::Notched10);
+    }
+
+    // Changes within a tick coalesce; the viewers only hear about progress.
+    {
+        let mut bars = harness.bars();
+        let bar = bars.get_mut(id).unwrap();
+        bar.set_progress(0.4);
+        bar.set_progress(0.25);
+    }
+    harness.tick();
+    for client in [&mut alice, &mut bob] {
+        let events = harness.sent_events(client);
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].operation,
+            BossEventOperation::UpdateProgress(0.25)
+        );
+    }
+
+    // Unchanged values send nothing.
+    harness.bars().get_mut(id).unwrap().set_progress(0.25);
+    harness.tick();
+    assert!(harness.sent_events(&mut alice).is_empty());
+}
+
+#[test]
+fn removing_a_viewer_or_the_bar_sends_remove() {
+    let mut harness = Harness::new();
+    let mut alice = harness.join();
+    let mut bob = harness.join();
+    let id = raid_bar(&mut harness, [&alice, &bob]);
+    harness.tick();
+    harness.sent_events(&mut alice);
+    harness.sent_events(&mut bob);
+
+    {
+        let mut bars = harness.bars();
+        let bar = bars.get_mut(id).unwrap();
+        bar.remove_viewer(bob.entity);
+        bar.set_title("Raid - Victory");
+    }
+    harness.tick();
+    let events = harness.sent_events(&mut alice);
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].operation,
+        BossEventOperation::UpdateTitle("Raid - Victory".into())
+    );
+    let events = harness.sent_events(&mut bob);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].operation, BossEventOperation::Remove);
+
+    harness.bars().remove(id);
+    harness.tick();
+    let events = harness.sent_events(&mut alice);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].operation, BossEventOperation::Remove);
+    assert!(harness.sent_events(&mut bob).is_empty());
+}
@@ -16,7 +16,7 @@ use bevy_ecs::system::{IntoSystem, System};
 use bevy_ecs::world::World;
 use bevy_math::DVec3;
 use bytes::Bytes;
-use mcrs_engine::geometry::ColumnPos;
+use mcrs_engine::geometry::{ChunkPos, ColumnPos};
 use mcrs_minecraft::world::bridge::dispatch_encode;
 use mcrs_minecraft::world::bridge_queue::{
     OutboundQueue, DEPTH_DRAIN_TARGET, DEPTH_LIMIT, HIGH_OVERFLOW_LIMIT, KICK_AFTER_OVERFLOW_TICKS,
@@ -30,9 +30,14 @@ use mcrs_network::metrics::{
     BRIDGE_KICK_OVERFLOW_TOTAL, TELEMETRY_TEST_LOCK,
 };
 use mcrs_network::ServerSideConnection;
-use mcrs_protocol::chunk::LightData;
+use mcrs_protocol::chunk::{ChunkBlockUpdateEntry, LightData};
+use mcrs_protocol::entity::EntityMetadata;
+use mcrs_protocol::packets::game::clientbound::{
+    ClientboundAddEntity, ClientboundBundleDelimiter, ClientboundSectionBlocksUpdate,
+    ClientboundSetEntityData,
+};
 use mcrs_protocol::uuid::Uuid;
-use mcrs_protocol::Look;
+use mcrs_protocol::{Look, Packet, PacketDecoder};
 use smallvec::SmallVec;
 use tokio::sync::mpsc;
 
@@ -444,7 +449,8 @@ fn entity_pos_sync_encodes() {
     assert!(!blob.is_empty(), "blob must be non-empty");
 }
 
-/// PlayerEnteredView encodes to a real ClientboundAddEntity (non-empty blob) and
+/// PlayerEnteredView encodes to a ClientboundAddEntity carrying the
+/// entity's velocity and its ClientboundSetEntityData inside one bundle, and
 /// does not increment BRIDGE_ENCODE_UNHANDLED_TOTAL.
 #[test]
 fn player_entered_view_encodes() {
@@ -463,8 +469,10 @@ fn player_entered_view_encodes() {
                 uuid: Uuid::nil(),
                 kind: 128,
                 position: DVec3::new(0.0, 64.0, 0.0),
+                velocity: DVec3::new(0.25, 0.5, -0.125),
                 yaw: 90.0,
                 pitch: 0.0,
+                metadata: EntityMetadata::new().with_byte(17, 0x7F),
             },
         });
     }
@@ -475,7 +483,90 @@ fn player_entered_view_encodes() {
 
     assert_eq!(after - before, 0, "PlayerEnteredView must not increment BRIDGE_ENCODE_UNHANDLED_TOTAL");
     let blob = rx.try_recv().expect("dispatch must produce a blob for PlayerEnteredView");
-    assert!(!blob.is_empty(), "blob must be non-empty");
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(blob.into());
+    let mut ids = Vec::new();
+    while let Some(frame) = decoder.try_next_packet().unwrap() {
+        if frame.id == ClientboundAddEntity::ID {
+            let add_entity = frame.decode::<ClientboundAddEntity>().unwrap();
+            assert!(
+                (add_entity.velocity.0 - DVec3::new(0.25, 0.5, -0.125))
+                    .abs()
+                    .max_element()
+                    < 1e-3,
+                "{:?}",
+                add_entity.velocity
+            );
+        }
+        ids.push(frame.id);
+    }
+    assert_eq!(
+        ids,
+        [
+            ClientboundBundleDelimiter::ID,
+            ClientboundAddEntity::ID,
+            ClientboundSetEntityData::ID,
+            ClientboundBundleDelimiter::ID,
+        ],
+        "spawn and metadata must share one bundle"
+    );
+}
+
+/// SectionBlocksUpdate encodes to one ClientboundSectionBlocksUpdate carrying
+/// the section position and every packed entry.
+#[test]
+fn section_blocks_update_encodes() {
+    let _lock = TELEMETRY_TEST_LOCK
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    let mut world = build_dispatch_world();
+    let (socket, mut rx) = spawn_mock_connection(&mut world);
+
+    let blocks = vec![
+        ChunkBlockUpdateEntry::new()
+            .with_off_y(15)
+            .with_block_state(1),
+        ChunkBlockUpdateEntry::new()
+            .with_off_x(3)
+            .with_off_z(9)
+            .with_block_state(27_000),
+    ];
+    {
+        let mut q = world
+            .get_mut::<OutboundQueue>(socket)
+            .expect("OutboundQueue");
+        q.push(OutboundPlayerPacket {
+            target: PacketTarget::AllPlayers,
+            priority: PacketPriority::Normal,
+            data: PacketPayload::SectionBlocksUpdate {
+                section: ChunkPos::new(-3, -4, 5),
+                blocks: blocks.clone(),
+            },
+        });
+    }
+
+    let before = BRIDGE_ENCODE_UNHANDLED_TOTAL.load(Ordering::Relaxed);
+    run_dispatch(&mut world);
+    let after = BRIDGE_ENCODE_UNHANDLED_TOTAL.load(Ordering::Relaxed);
+
+    assert_eq!(
+        after - before,
+        0,
+        "SectionBlocksUpdate must not increment BRIDGE_ENCODE_UNHANDLED_TOTAL"
+    );
+    let blob = rx
+        .try_recv()
+        .expect("dispatch must produce a blob for SectionBlocksUpdate");
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(blob.into());
+    let frame = decoder.try_next_packet().unwrap().expect("one frame");
+    let update = frame
+        .decode::<ClientboundSectionBlocksUpdate>()
+        .expect("Section Blocks Update frame");
+    assert_eq!(update.chunk_pos, ChunkPos::new(-3, -4, 5));
+    assert_eq!(update.blocks.as_ref(), blocks.as_slice());
+    assert!(decoder.try_next_packet().unwrap().is_none());
 }
 
 /// PlayerLeftView encodes to a real ClientboundRemoveEntities (non-empty blob) and
@@ -555,8 +646,10 @@ fn only_test_remains_counted_drop() {
                 uuid: Uuid::nil(),
                 kind: 128,
                 position: DVec3::ZERO,
+                velocity: DVec3::ZERO,
                 yaw: 0.0,
                 pitch: 0.0,
+                metadata: EntityMetadata::new(),
             },
         });
         let mut ids: SmallVec<[i32; 4]> = SmallVec::new();
@@ -585,3 +678,117 @@ fn only_test_remains_counted_drop() {
         after - before
     );
 }
+
+// ---------------------------------------------------------------------------
+// send errors
+// ---------------------------------------------------------------------------
+
+fn enqueue_block_update(world: &mut World, entity: Entity) {
+    use mcrs_engine::geometry::BlockPos;
+    use mcrs_protocol::BlockStateId;
+
+    world
+        .get_mut::<OutboundQueue>(entity)
+        .expect("OutboundQueue")
+        .push(OutboundPlayerPacket {
+            target: PacketTarget::AllPlayers,
+            priority: PacketPriority::Normal,
+            data: PacketPayload::BlockUpdate {
+                position: BlockPos::new(0, 64, 0),
+                new_state: BlockStateId(1),
+            },
+        });
+}
+
+/// A full channel is backpressure: the connection stays, the tick's bytes are
+/// held back, and they go out once the client catches up.
+#[test]
+fn full_channel_keeps_connection_and_retries() {
+    let mut world = build_dispatch_world();
+    let (socket, mut rx) = spawn_mock_connection(&mut world);
+
+    // The mock channel holds 16 blobs; nobody reads them.
+    for _ in 0..16 {
+        enqueue_block_update(&mut world, socket);
+        run_dispatch(&mut world);
+    }
+    enqueue_block_update(&mut world, socket);
+    run_dispatch(&mut world);
+
+    let conn = world
+        .get::<ServerSideConnection>(socket)
+        .expect("a full channel must not close the connection");
+    let held = conn.queued_bytes();
+    assert!(held > 0, "the rejected blob must be kept for the next tick");
+    let queue = world.get::<OutboundQueue>(socket).unwrap();
+    assert_eq!(queue.overflow_ticks, 1);
+
+    let mut drained = 0;
+    while rx.try_recv().is_ok() {
+        drained += 1;
+    }
+    assert_eq!(drained, 16);
+
+    // Nothing new queued: the next tick sends just the held-back bytes.
+    run_dispatch(&mut world);
+    let retried = rx.try_recv().expect("held-back bytes are retried");
+    assert_eq!(retried.len(), held);
+    let conn = world.get::<ServerSideConnection>(socket).unwrap();
+    assert_eq!(conn.queued_bytes(), 0);
+}
+
+/// A closed channel means the writer is gone; the connection is removed.
+#[test]
+fn disconnected_channel_removes_connection() {
+    let mut world = build_dispatch_world();
+    let (socket, rx) = spawn_mock_connection(&mut world);
+    drop(rx);
+
+    enqueue_block_update(&mut world, socket);
+    run_dispatch(&mut world);
+
+    assert!(
+        world.get::<ServerSideConnection>(socket).is_none(),
+        "ServerSideConnection must be removed once the writer is gone"
+    );
+}
+
+/// A packet that fails to encode is dropped on its own; the packets around it
+/// still reach the client intact.
+#[test]
+fn encode_error_skips_only_that_packet() {
+    use mcrs_protocol::packets::game::clientbound::ClientboundBlockUpdate;
+    use mcrs_protocol::{MAX_PACKET_SIZE, Packet, PacketDecoder};
+
+    let mut world = build_dispatch_world();
+    let (socket, mut rx) = spawn_mock_connection(&mut world);
+
+    enqueue_block_update(&mut world, socket);
+    world
+        .get_mut::<OutboundQueue>(socket)
+        .expect("OutboundQueue")
+        .push(OutboundPlayerPacket {
+            target: PacketTarget::AllPlayers,
+            priority: PacketPriority::Normal,
+            data: PacketPayload::ChunkLoad {
+                column: ColumnPos::new(0, 0),
+                // Larger than any packet may be.
+                chunk_bytes: vec![0u8; MAX_PACKET_SIZE as usize + 1],
+                light_data: LightData::default(),
+            },
+        });
+    enqueue_block_update(&mut world, socket);
+    run_dispatch(&mut world);
+
+    assert!(
+        world.get::<ServerSideConnection>(socket).is_some(),
+        "an encode error must not close the connection"
+    );
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(rx.try_recv().expect("one blob").into());
+    let mut ids = Vec::new();
+    while let Some(frame) = decoder.try_next_packet().unwrap() {
+        ids.push(frame.id);
+    }
+    assert_eq!(ids, [ClientboundBlockUpdate::ID; 2]);
+}
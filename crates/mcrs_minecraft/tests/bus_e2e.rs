@@ -34,6 +34,7 @@ use mcrs_minecraft_lighting::table::BlockStateLightTable;
 use mcrs_vanilla::biome::Biome;
 use mcrs_vanilla::block::Block;
 use mcrs_vanilla::enchantment::EnchantmentData;
+use mcrs_vanilla::item::Item;
 use smallvec::SmallVec;
 
 #[derive(Resource, Default)]
@@ -75,7 +76,9 @@ fn build_app() -> App {
     app.insert_resource(make_stub_block_light_table());
     app.insert_resource(StaticRegistry::<Block>::new());
     app.insert_resource(StaticRegistry::<EnchantmentData>::default());
+    app.insert_resource(StaticRegistry::<Item>::new());
     app.insert_resource(TagRegistry::<Block>::default());
+    app.insert_resource(TagRegistry::<Item>::default());
     app.insert_resource(RegistrySnapshot::<Biome>::default());
 
     // Host-side bus substrate (mirrors what `WorldPlugin::build` installs).
@@ -0,0 +1,146 @@
+//! Chat Session Update: a session is only trusted once Mojang's signature on
+//! its profile key checks out, and a forged one disconnects the client.
+
+#[path = "common/mock_connection.rs"]
+mod mock_connection;
+
+use std::time::Instant;
+
+use bevy_ecs::entity::Entity;
+use bevy_ecs::world::World;
+use mcrs_minecraft::chat_session::{ChatSession, ServicesKeys, now_millis, update_chat_session};
+use mcrs_minecraft::login::GameProfile;
+use mcrs_network::event::ReceivedPacketEvent;
+use mcrs_network::{ConnectionState, ServerSideConnection};
+use mcrs_protocol::packets::game::serverbound::ServerboundChatSessionUpdate;
+use mcrs_protocol::uuid::Uuid;
+use mcrs_protocol::{Bounded, Encode, Packet};
+use rsa::pkcs8::{DecodePrivateKey, EncodePublicKey};
+use rsa::{Pkcs1v15Sign, RsaPrivateKey};
+use sha1::{Digest, Sha1};
+
+/// The DER `DigestInfo` a PKCS#1 v1.5 signature wraps a SHA-1 hash in.
+const SHA1_DIGEST_INFO: [u8; 15] = [
+    0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14,
+];
+
+const PROFILE_ID: Uuid = Uuid::from_u128(0x069a79f444e94726a5befca90e38aaf5);
+
+fn services_key() -> RsaPrivateKey {
+    RsaPrivateKey::from_pkcs8_pem(include_str!("fixtures/chat/services_key.pem")).unwrap()
+}
+
+fn profile_key() -> RsaPrivateKey {
+    RsaPrivateKey::from_pkcs8_pem(include_str!("fixtures/chat/profile_key.pem")).unwrap()
+}
+
+/// `key`'s signature on the player's profile key, as Mojang signs it: the
+/// player id, the expiry and the X.509 key.
+fn sign_profile_key(key: &RsaPrivateKey, expires_at: i64, public_key_der: &[u8]) -> Vec<u8> {
+    let mut payload = PROFILE_ID.as_bytes().to_vec();
+    payload.extend_from_slice(&expires_at.to_be_bytes());
+    payload.extend_from_slice(public_key_der);
+    let scheme = Pkcs1v15Sign {
+        hash_len: Some(20),
+        prefix: SHA1_DIGEST_INFO.into(),
+    };
+    key.sign(scheme, &Sha1::digest(payload)).unwrap()
+}
+
+fn world() -> (World, Entity) {
+    let mut world = World::new();
+    world.insert_resource(ServicesKeys::new(vec![services_key().to_public_key()]));
+    world.add_observer(update_chat_session);
+    let (raw, _outgoing_rx) = mock_connection::make_mock_raw_connection();
+    let entity = world
+        .spawn((
+            ServerSideConnection { raw: Box::new(raw) },
+            ConnectionState::Game,
+            GameProfile {
+                id: PROFILE_ID,
+                username: "Notch".to_string(),
+                properties: Vec::new(),
+            },
+        ))
+        .id();
+    (world, entity)
+}
+
+/// Announce the profile key with `key_signature` on it.
+fn update_session(world: &mut World, entity: Entity, expires_at: i64, key_signature: &[u8]) {
+    let public_key_der = public_key_der();
+    let mut data = Vec::new();
+    ServerboundChatSessionUpdate {
+        session_id: Uuid::from_u128(7),
+        expires_at,
+        public_key: Bounded(&public_key_der),
+        key_signature: Bounded(key_signature),
+    }
+    .encode(&mut data)
+    .unwrap();
+    world.trigger(ReceivedPacketEvent {
+        entity,
+        id: ServerboundChatSessionUpdate::ID,
+        data: data.into(),
+        timestamp: Instant::now(),
+    });
+    world.flush();
+}
+
+fn public_key_der() -> Vec<u8> {
+    profile_key()
+        .to_public_key()
+        .to_public_key_der()
+        .unwrap()
+        .as_bytes()
+        .to_vec()
+}
+
+fn is_closing(world: &World, entity: Entity) -> bool {
+    world
+        .get::<ServerSideConnection>(entity)
+        .unwrap()
+        .is_closing()
+}
+
+#[test]
+fn session_signed_by_mojang_is_trusted() {
+    let (mut world, entity) = world();
+    let expires_at = now_millis() + 60_000;
+    let signature = sign_profile_key(&services_key(), expires_at, &public_key_der());
+    update_session(&mut world, entity, expires_at, &signature);
+
+    let session = world.get::<ChatSession>(entity).expect("session trusted");
+    assert_eq!(session.session_id, Uuid::from_u128(7));
+    assert_eq!(session.public_key, profile_key().to_public_key());
+    assert!(!is_closing(&world, entity));
+}
+
+#[test]
+fn forged_session_disconnects() {
+    let expires_at = now_millis() + 60_000;
+    let forged = [
+        // Signed with the player's own key instead of Mojang's.
+        sign_profile_key(&profile_key(), expires_at, &public_key_der()),
+        // Mojang's signature on some other expiry.
+        sign_profile_key(&services_key(), expires_at + 1, &public_key_der()),
+        vec![0; 512],
+    ];
+    for key_signature in forged {
+        let (mut world, entity) = world();
+        update_session(&mut world, entity, expires_at, &key_signature);
+        assert!(world.get::<ChatSession>(entity).is_none());
+        assert!(is_closing(&world, entity));
+    }
+}
+
+#[test]
+fn session_is_ignored_without_the_services_keys() {
+    let (mut world, entity) = world();
+    world.remove_resource::<ServicesKeys>();
+    let expires_at = now_millis() + 60_000;
+    let signature = sign_profile_key(&services_key(), expires_at, &public_key_der());
+    update_session(&mut world, entity, expires_at, &signature);
+    assert!(world.get::<ChatSession>(entity).is_none());
+    assert!(!is_closing(&world, entity));
+}
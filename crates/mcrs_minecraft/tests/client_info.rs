@@ -0,0 +1,120 @@
+//! Client Information packets land in the `ClientInfo` component, with the
+//! view distance clamped to the server maximum, and a resend overwrites it.
+
+use std::time::Instant;
+
+use bevy_ecs::entity::Entity;
+use bevy_ecs::world::World;
+use bevy_math::DVec3;
+use mcrs_minecraft::client_info::{ClientInfo, ServerViewConfig, update_client_info};
+use mcrs_network::ConnectionState;
+use mcrs_network::event::ReceivedPacketEvent;
+use mcrs_protocol::packets::common::serverbound::ClientInformation;
+use mcrs_protocol::packets::configuration::serverbound::ServerboundClientInformation as ConfigurationPacket;
+use mcrs_protocol::packets::game::serverbound::ServerboundClientInformation as GamePacket;
+use mcrs_protocol::setting::{ChatMode, DisplayedSkinParts, MainArm, ParticleStatus};
+use mcrs_protocol::{Encode, Packet};
+
+fn information(locale: &str, view_distance: u8, chat_mode: ChatMode) -> ClientInformation<'_> {
+    ClientInformation {
+        locale,
+        view_distance,
+        chat_mode,
+        chat_colors: true,
+        displayed_skin_parts: DisplayedSkinParts::new().with_hat(true),
+        main_arm: MainArm::Left,
+        enable_text_filtering: false,
+        allow_server_listings: true,
+        particle_status: ParticleStatus::All,
+    }
+}
+
+fn receive(world: &mut World, entity: Entity, id: i32, packet: impl Encode) {
+    let mut data = Vec::new();
+    packet.encode(&mut data).unwrap();
+    world.trigger(ReceivedPacketEvent {
+        entity,
+        id,
+        data: data.into(),
+        timestamp: Instant::now(),
+    });
+    world.flush();
+}
+
+#[test]
+fn client_information_updates_and_overwrites() {
+    let mut world = World::new();
+    world.insert_resource(ServerViewConfig {
+        view_distance: 10,
+        simulation_distance: 10,
+    });
+    world.add_observer(update_client_info);
+    let entity = world.spawn(ConnectionState::Configuration).id();
+
+    receive(
+        &mut world,
+        entity,
+        ConfigurationPacket::ID,
+        ConfigurationPacket(information("en_us", 32, ChatMode::Enabled)),
+    );
+    let info = world.get::<ClientInfo>(entity).unwrap();
+    assert_eq!(info.locale, "en_us");
+    assert_eq!(info.view_distance, 10, "clamped to the server maximum");
+    assert_eq!(info.chat_mode, ChatMode::Enabled);
+    assert_eq!(info.main_hand, MainArm::Left);
+    assert!(info.displayed_skin_parts.hat());
+    assert!(info.allow_server_listings);
+
+    world.entity_mut(entity).insert(ConnectionState::Game);
+    receive(
+        &mut world,
+        entity,
+        GamePacket::ID,
+        GamePacket(information("de_de", 6, ChatMode::CommandsOnly)),
+    );
+    let info = world.get::<ClientInfo>(entity).unwrap();
+    assert_eq!(info.locale, "de_de");
+    assert_eq!(info.view_distance, 6);
+    assert_eq!(info.chat_mode, ChatMode::CommandsOnly);
+    assert_eq!(
+        world.get::<ChatMode>(entity).copied(),
+        Some(ChatMode::CommandsOnly)
+    );
+}
+
+#[test]
+fn view_distance_is_clamped_to_the_server_config() {
+    let mut world = World::new();
+    world.insert_resource(ServerViewConfig {
+        view_distance: 10,
+        simulation_distance: 6,
+    });
+    world.add_observer(update_client_info);
+    let entity = world.spawn(ConnectionState::Game).id();
+
+    receive(
+        &mut world,
+        entity,
+        GamePacket::ID,
+        GamePacket(information("en_us", 32, ChatMode::Enabled)),
+    );
+    assert_eq!(world.get::<ClientInfo>(entity).unwrap().view_distance, 10);
+
+    let config = world.resource::<ServerViewConfig>();
+    assert_eq!(config.effective_view_distance(0), 2);
+    assert_eq!(config.effective_view_distance(7), 7);
+}
+
+#[test]
+fn simulation_distance_is_a_column_square_around_players() {
+    let config = ServerViewConfig {
+        view_distance: 10,
+        simulation_distance: 2,
+    };
+    let player = DVec3::new(8.0, 64.0, 8.0);
+    let players = || [player].into_iter();
+    assert!(config.in_simulation_distance(DVec3::new(40.0, 0.0, 40.0), players()));
+    assert!(config.in_simulation_distance(DVec3::new(-24.0, 0.0, 47.9), players()));
+    assert!(!config.in_simulation_distance(DVec3::new(48.0, 0.0, 8.0), players()));
+    assert!(!config.in_simulation_distance(player, std::iter::empty()));
+}
@@ -3,6 +3,10 @@
 //! These tests exercise `bridge_outbound` (packet routing only, no sockets).
 //! The world carries `OutboundQueue` + `PlayerIndex` but no real network
 //! transport — socket I/O belongs to separate dispatch tests.
+//!
+//! Each test binary includes this file and uses only some of it.
+
+#![allow(dead_code)]
 
 use bevy_ecs::entity::Entity;
 use bevy_ecs::message::Messages;
@@ -10,9 +14,13 @@ use bevy_ecs::system::{IntoSystem, System};
 use bevy_ecs::world::World;
 use bytes::Bytes;
 use mcrs_minecraft::world::bridge_queue::OutboundQueue;
-use mcrs_minecraft::world::bus::{OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget, TestPayload};
+use mcrs_minecraft::world::bus::{
+    OutboundPlayerPacket, PacketPayload, PacketPriority, PacketTarget, TestPayload,
+};
 use mcrs_minecraft::world::player_index::{PlayerIndex, PlayerLocation};
-use mcrs_network::RawConnection;
+use mcrs_network::{EngineConnection, RawConnection, ServerSideConnection};
+use mcrs_protocol::PacketDecoder;
+use mcrs_protocol::decode::PacketFrame;
 use smallvec::SmallVec;
 use tokio::sync::mpsc;
 
@@ -30,12 +38,7 @@ pub fn spawn_connection(world: &mut World) -> Entity {
 }
 
 /// Register a player in `PlayerIndex` pointing at `socket_entity`.
-pub fn register_player(
-    world: &mut World,
-    player: Entity,
-    socket: Entity,
-    dim: Entity,
-) {
+pub fn register_player(world: &mut World, player: Entity, socket: Entity, dim: Entity) {
     world.resource_mut::<PlayerIndex>().insert(
         player,
         PlayerLocation {
@@ -74,7 +77,9 @@ where
 /// Collect all packets from a connection entity's `OutboundQueue` in priority
 /// drain order (Critical → High → Normal → Low) into a flat `Vec`.
 pub fn drain_queue(world: &mut World, socket: Entity) -> Vec<OutboundPlayerPacket> {
-    let mut q = world.get_mut::<OutboundQueue>(socket).expect("OutboundQueue present");
+    let mut q = world
+        .get_mut::<OutboundQueue>(socket)
+        .expect("OutboundQueue present");
     let mut out = Vec::new();
     out.extend(q.critical.drain(..));
     out.extend(q.high.drain(..));
@@ -133,3 +138,30 @@ pub fn make_mock_raw_connection_full() -> (
 
     rt.block_on(async { RawConnection::new_for_test_full(16) })
 }
+
+/// Decode every frame that reached `outgoing_rx` so far.
+pub fn decode_frames(outgoing_rx: &mut mpsc::Receiver<Bytes>) -> Vec<PacketFrame> {
+    let mut decoder = PacketDecoder::new();
+    while let Ok(blob) = outgoing_rx.try_recv() {
+        decoder.queue_bytes(blob.into());
+    }
+    let mut frames = Vec::new();
+    while let Some(frame) = decoder.try_next_packet().unwrap() {
+        frames.push(frame);
+    }
+    frames
+}
+
+/// Flush `entity`'s connection and decode every frame it has sent so far.
+pub fn sent_frames(
+    world: &mut World,
+    entity: Entity,
+    outgoing_rx: &mut mpsc::Receiver<Bytes>,
+) -> Vec<PacketFrame> {
+    world
+        .get_mut::<ServerSideConnection>(entity)
+        .unwrap()
+        .flush()
+        .unwrap();
+    decode_frames(outgoing_rx)
+}
@@ -0,0 +1,109 @@
+//! Players in one dimension and the packets they are sent, for the entity
+//! sync tests.
+//!
+//! Each test binary includes this file and uses only some of it.
+
+#![allow(dead_code)]
+
+use bevy_app::{App, FixedPostUpdate, FixedPreUpdate, FixedUpdate, Plugins};
+use bevy_ecs::bundle::Bundle;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::Messages;
+use bevy_math::DVec3;
+use mcrs_engine::entity::EntityPlugin;
+use mcrs_engine::entity::physics::Transform;
+use mcrs_engine::entity::player::Player;
+use mcrs_engine::entity::player::chunk_view::PlayerViewDistance;
+use mcrs_engine::entity::player::reposition::Reposition;
+use mcrs_engine::world::dimension::{DimensionBundle, DimensionPlugin, InDimension};
+use mcrs_minecraft::login::GameProfile;
+use mcrs_minecraft::world::bus::{OutboundPlayerPacket, PacketPayload, PacketTarget};
+use mcrs_minecraft::world::entity::player::{HostAnchor, PlayerSkinParts};
+use mcrs_minecraft::world::entity::visibility::EntityVisibilityPlugin;
+use mcrs_protocol::uuid::Uuid;
+
+/// A player and the host anchor its packets are addressed to.
+pub struct Viewer {
+    pub entity: Entity,
+    pub host_anchor: Entity,
+}
+
+impl Viewer {
+    pub fn wire_id(&self) -> i32 {
+        self.entity.index_u32() as i32
+    }
+}
+
+/// An app that tracks which entities each player sees, with `plugins` on
+/// top.
+pub fn make_app<M>(plugins: impl Plugins<M>) -> App {
+    let mut app = App::new();
+    app.add_message::<OutboundPlayerPacket>();
+    app.add_plugins((DimensionPlugin, EntityPlugin, EntityVisibilityPlugin));
+    app.add_plugins(plugins);
+    app
+}
+
+/// A player at `pos` in `dim` with its own host anchor, carrying `extra`
+/// besides what every player here has.
+pub fn spawn_player(app: &mut App, dim: Entity, pos: DVec3, extra: impl Bundle) -> Viewer {
+    let host_anchor = app.world_mut().spawn_empty().id();
+    let entity = app
+        .world_mut()
+        .spawn((
+            Player,
+            Transform::from_translation(pos),
+            InDimension(dim),
+            PlayerViewDistance::default(),
+            Reposition::default(),
+            PlayerSkinParts::default(),
+            HostAnchor(host_anchor),
+            GameProfile {
+                id: Uuid::new_v4(),
+                username: "Steve".to_string(),
+                properties: Vec::new(),
+            },
+            extra,
+        ))
+        .id();
+    Viewer {
+        entity,
+        host_anchor,
+    }
+}
+
+/// Both players joined, a tick for the dimension to index them and one for
+/// them to be spawned for each other.
+pub fn spawn_pair(app: &mut App, extra: impl Bundle + Clone) -> (Viewer, Viewer) {
+    let dim = app.world_mut().spawn(DimensionBundle::default()).id();
+    let alice = spawn_player(app, dim, DVec3::new(0.5, 64.0, 0.5), extra.clone());
+    let bob = spawn_player(app, dim, DVec3::new(20.5, 64.0, 0.5), extra);
+    tick(app);
+    tick(app);
+    (alice, bob)
+}
+
+pub fn tick(app: &mut App) {
+    app.world_mut().run_schedule(FixedPreUpdate);
+    app.world_mut().run_schedule(FixedUpdate);
+    app.world_mut().run_schedule(FixedPostUpdate);
+}
+
+/// Drain every packet emitted since the last call.
+pub fn drain(app: &mut App) -> Vec<OutboundPlayerPacket> {
+    app.world_mut()
+        .resource_mut::<Messages<OutboundPlayerPacket>>()
+        .drain()
+        .collect()
+}
+
+/// The payloads of `packets` addressed to `viewer`, in order.
+pub fn sent_to<'a>(packets: &'a [OutboundPlayerPacket], viewer: &Viewer) -> Vec<&'a PacketPayload> {
+    packets
+        .iter()
+        .filter(|packet| {
+            matches!(packet.target, PacketTarget::SinglePlayer(target) if target == viewer.host_anchor)
+        })
+        .map(|packet| &packet.data)
+        .collect()
+}
@@ -0,0 +1,343 @@
+//! Configuration handshake: Select Known Packs, registry data that omits the
+//! NBT of packs the client already has, Finish Configuration, and the switch
+//! to Game only once the client acknowledges it; and the reconfiguration
+//! round trip from Game back through Configuration, which takes the player
+//! out of its dimension and places it again on return. The Login and chunk
+//! stream that follow are covered end to end in `login_handshake_e2e.rs`.
+
+#[path = "common/mock_connection.rs"]
+mod mock_connection;
+
+use std::time::Instant;
+
+use bevy_ecs::entity::Entity;
+use bevy_ecs::world::World;
+use mcrs_core::{
+    PackSource, RegistryAccess, RegistrySnapshotErased, ResourceLocation, TagRegistry,
+};
+use mcrs_minecraft::configuration::{
+    LoadedDimensionTypes, begin_reconfiguration, emit_initial_player_spawn, on_configuration_ack,
+    on_configuration_enter, on_game_configuration_ack, on_known_packs_response,
+};
+use mcrs_minecraft::login::GameProfile;
+use mcrs_minecraft::spawn_point::SpawnPoint;
+use mcrs_minecraft::world::bus::{PendingInboundLifecycle, SpawnReason};
+use mcrs_minecraft::world::player_index::{HostAnchorRef, PlayerIndex, PlayerLocation};
+use mcrs_minecraft::world::sub_app_builder::{DimLabel, DimSubAppHandle};
+use mcrs_nbt::compound::NbtCompound;
+use mcrs_network::event::ReceivedPacketEvent;
+use mcrs_network::{ConnectionState, InGameConnectionState, ServerSideConnection};
+use mcrs_protocol::packets::configuration::clientbound::{
+    ClientboundSelectKnownPacks, ClientboundUpdateTags,
+};
+use mcrs_protocol::packets::configuration::serverbound::{
+    ServerboundFinishConfiguration, ServerboundSelectKnownPacks,
+};
+use mcrs_protocol::packets::configuration::{
+    ClientboundFinishConfiguration, ClientboundRegistryData,
+};
+use mcrs_protocol::packets::game::clientbound::ClientboundStartConfiguration;
+use mcrs_protocol::packets::game::serverbound::ServerboundConfigurationAcknowledged;
+use mcrs_protocol::resource_pack::KnownPack;
+use mcrs_protocol::uuid::Uuid;
+use mcrs_protocol::{Encode, Packet};
+use mcrs_vanilla::block::Block;
+use mcrs_vanilla::enchantment::EnchantmentData;
+use mcrs_vanilla::entity::EntityType;
+use mcrs_vanilla::item::Item;
+use smallvec::SmallVec;
+
+use mock_connection::{run_system, sent_frames};
+
+fn entry(path: &str) -> (ResourceLocation<std::sync::Arc<str>>, Option<NbtCompound>) {
+    let mut nbt = NbtCompound::new();
+    nbt.put_string("name", path.to_string());
+    (ResourceLocation::new("minecraft", path), Some(nbt))
+}
+
+/// A world with one registry from the vanilla core pack and one from no pack,
+/// plus the handshake observers.
+fn handshake_world() -> World {
+    let mut world = World::new();
+    let mut access = RegistryAccess::default();
+    access.register(Box::new(RegistrySnapshotErased::from_entries(
+        "minecraft:worldgen/biome",
+        vec![entry("plains")],
+        Some(PackSource::vanilla_core()),
+    )));
+    access.register(Box::new(RegistrySnapshotErased::from_entries(
+        "minecraft:damage_type",
+        vec![entry("custom_damage")],
+        None,
+    )));
+    world.insert_resource(access);
+    world.init_resource::<LoadedDimensionTypes>();
+    world.init_resource::<TagRegistry<Block>>();
+    world.init_resource::<TagRegistry<Item>>();
+    world.init_resource::<TagRegistry<EnchantmentData>>();
+    world.init_resource::<TagRegistry<EntityType>>();
+    world.add_observer(on_known_packs_response);
+    world.add_observer(on_configuration_ack);
+    world.add_observer(on_game_configuration_ack);
+    world
+}
+
+fn receive(world: &mut World, entity: Entity, id: i32, packet: impl Encode) {
+    let mut data = Vec::new();
+    packet.encode(&mut data).unwrap();
+    world.trigger(ReceivedPacketEvent {
+        entity,
+        id,
+        data: data.into(),
+        timestamp: Instant::now(),
+    });
+    world.flush();
+}
+
+fn state(world: &World, entity: Entity) -> ConnectionState {
+    *world.get::<ConnectionState>(entity).unwrap()
+}
+
+#[test]
+fn client_walks_through_known_packs_and_finish() {
+    let mut world = handshake_world();
+    let (raw, mut outgoing_rx) = mock_connection::make_mock_raw_connection();
+    let entity = world
+        .spawn((
+            ServerSideConnection { raw: Box::new(raw) },
+            ConnectionState::Configuration,
+        ))
+        .id();
+
+    // Entering Configuration offers the core pack.
+    run_system(&mut world, on_configuration_enter);
+    let frames = sent_frames(&mut world, entity, &mut outgoing_rx);
+    assert_eq!(frames.len(), 1);
+    let offer = frames[0].decode::<ClientboundSelectKnownPacks>().unwrap();
+    assert_eq!(offer.known_packs.len(), 1);
+    assert_eq!(
+        (offer.known_packs[0].namespace, offer.known_packs[0].id),
+        ("minecraft", "core")
+    );
+
+    // Acknowledging before the server finished is ignored.
+    receive(
+        &mut world,
+        entity,
+        ServerboundFinishConfiguration::ID,
+        ServerboundFinishConfiguration,
+    );
+    assert_eq!(state(&world, entity), ConnectionState::Configuration);
+
+    receive(
+        &mut world,
+        entity,
+        ServerboundSelectKnownPacks::ID,
+        ServerboundSelectKnownPacks {
+            known_packs: vec![KnownPack {
+                namespace: "minecraft",
+                id: "core",
+                version: "1.21.11",
+            }],
+        },
+    );
+    let frames = sent_frames(&mut world, entity, &mut outgoing_rx);
+    let ids: Vec<i32> = frames.iter().map(|frame| frame.id).collect();
+    assert_eq!(
+        ids,
+        [
+            ClientboundRegistryData::ID,
+            ClientboundRegistryData::ID,
+            ClientboundUpdateTags::ID,
+            ClientboundFinishConfiguration::ID,
+        ]
+    );
+
+    // Registries go out sorted by key; only the pack the client knows is
+    // sent without NBT.
+    let damage_types = frames[0].decode::<ClientboundRegistryData>().unwrap();
+    assert_eq!(damage_types.registry.as_str(), "minecraft:damage_type");
+    assert_eq!(
+        damage_types.entries[0].id.as_str(),
+        "minecraft:custom_damage"
+    );
+    assert!(damage_types.entries[0].data.is_some());
+    let biomes = frames[1].decode::<ClientboundRegistryData>().unwrap();
+    assert_eq!(biomes.registry.as_str(), "minecraft:worldgen/biome");
+    assert_eq!(biomes.entries[0].id.as_str(), "minecraft:plains");
+    assert!(biomes.entries[0].data.is_none());
+
+    assert_eq!(state(&world, entity), ConnectionState::Configuration);
+    receive(
+        &mut world,
+        entity,
+        ServerboundFinishConfiguration::ID,
+        ServerboundFinishConfiguration,
+    );
+    assert_eq!(state(&world, entity), ConnectionState::Game);
+    assert!(world.get::<InGameConnectionState>(entity).is_some());
+}
+
+#[test]
+fn unknown_pack_gets_full_registry_data() {
+    let mut world = handshake_world();
+    let (raw, mut outgoing_rx) = mock_connection::make_mock_raw_connection();
+    let entity = world
+        .spawn((
+            ServerSideConnection { raw: Box::new(raw) },
+            ConnectionState::Configuration,
+        ))
+        .id();
+    run_system(&mut world, on_configuration_enter);
+    sent_frames(&mut world, entity, &mut outgoing_rx);
+
+    // A client without the core pack (e.g. a different version) needs the NBT.
+    receive(
+        &mut world,
+        entity,
+        ServerboundSelectKnownPacks::ID,
+        ServerboundSelectKnownPacks {
+            known_packs: vec![],
+        },
+    );
+    let frames = sent_frames(&mut world, entity, &mut outgoing_rx);
+    let biomes = frames[1].decode::<ClientboundRegistryData>().unwrap();
+    assert_eq!(biomes.registry.as_str(), "minecraft:worldgen/biome");
+    assert!(biomes.entries[0].data.is_some());
+}
+
+#[test]
+fn reconfiguration_returns_to_configuration_and_back_to_game() {
+    let mut world = handshake_world();
+    world.init_resource::<PlayerIndex>();
+    world.init_resource::<PendingInboundLifecycle>();
+    world.init_resource::<SpawnPoint>();
+    let dim = world
+        .spawn((DimSubAppHandle, DimLabel("minecraft:overworld".to_string())))
+        .id();
+    let host_anchor = world
+        .spawn(GameProfile {
+            id: Uuid::new_v4(),
+            username: "reconfiguring".into(),
+            properties: Vec::new(),
+        })
+        .id();
+    let (raw, mut outgoing_rx) = mock_connection::make_mock_raw_connection();
+    let entity = world
+        .spawn((
+            ServerSideConnection { raw: Box::new(raw) },
+            ConnectionState::Game,
+            InGameConnectionState,
+            HostAnchorRef(host_anchor),
+        ))
+        .id();
+    let in_dim_entity = world.spawn_empty().id();
+    world.resource_mut::<PlayerIndex>().insert(
+        host_anchor,
+        PlayerLocation {
+            socket: entity,
+            current_dim: dim,
+            previous_dim: None,
+            in_dim_entity: Some(in_dim_entity),
+            inbound_pending: SmallVec::new(),
+        },
+    );
+
+    // An acknowledgement the server never asked for is ignored.
+    receive(
+        &mut world,
+        entity,
+        ServerboundConfigurationAcknowledged::ID,
+        ServerboundConfigurationAcknowledged,
+    );
+    assert_eq!(state(&world, entity), ConnectionState::Game);
+
+    world
+        .commands()
+        .entity(entity)
+        .queue(begin_reconfiguration());
+    world.flush();
+    let frames = sent_frames(&mut world, entity, &mut outgoing_rx);
+    let ids: Vec<i32> = frames.iter().map(|frame| frame.id).collect();
+    assert_eq!(ids, [ClientboundStartConfiguration::ID]);
+    assert_eq!(
+        state(&world, entity),
+        ConnectionState::Game,
+        "the client has not acknowledged yet"
+    );
+
+    // The client drops its level, so the player leaves its dimension.
+    let location = world.resource::<PlayerIndex>().get(&host_anchor).unwrap();
+    assert_eq!(location.current_dim, Entity::PLACEHOLDER);
+    assert_eq!(location.in_dim_entity, None);
+    let despawns: Vec<Entity> = world
+        .resource_mut::<PendingInboundLifecycle>()
+        .per_dim
+        .remove(&dim)
+        .expect("a despawn for the dimension left")
+        .despawns
+        .into_iter()
+        .map(|despawn| despawn.host_anchor)
+        .collect();
+    assert_eq!(despawns, [host_anchor]);
+
+    receive(
+        &mut world,
+        entity,
+        ServerboundConfigurationAcknowledged::ID,
+        ServerboundConfigurationAcknowledged,
+    );
+    assert_eq!(state(&world, entity), ConnectionState::Configuration);
+    assert!(world.get::<InGameConnectionState>(entity).is_none());
+
+    // Back in Configuration, the handshake runs again from the top.
+    run_system(&mut world, on_configuration_enter);
+    let frames = sent_frames(&mut world, entity, &mut outgoing_rx);
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].id, ClientboundSelectKnownPacks::ID);
+
+    receive(
+        &mut world,
+        entity,
+        ServerboundSelectKnownPacks::ID,
+        ServerboundSelectKnownPacks {
+            known_packs: vec![],
+        },
+    );
+    let frames = sent_frames(&mut world, entity, &mut outgoing_rx);
+    let ids: Vec<i32> = frames.iter().map(|frame| frame.id).collect();
+    assert_eq!(
+        ids,
+        [
+            ClientboundRegistryData::ID,
+            ClientboundRegistryData::ID,
+            ClientboundUpdateTags::ID,
+            ClientboundFinishConfiguration::ID,
+        ]
+    );
+
+    receive(
+        &mut world,
+        entity,
+        ServerboundFinishConfiguration::ID,
+        ServerboundFinishConfiguration,
+    );
+    assert_eq!(state(&world, entity), ConnectionState::Game);
+    assert!(world.get::<InGameConnectionState>(entity).is_some());
+
+    // Back in Game, the player is placed again as on its first join.
+    run_system(&mut world, emit_initial_player_spawn);
+    assert_eq!(
+        world
+            .resource::<PlayerIndex>()
+            .get(&host_anchor)
+            .unwrap()
+            .current_dim,
+        dim
+    );
+    let lifecycle = world.resource::<PendingInboundLifecycle>();
+    let spawns = &lifecycle.per_dim[&dim].spawns;
+    assert_eq!(spawns.len(), 1);
+    assert_eq!(spawns[0].host_anchor, host_anchor);
+    assert_eq!(spawns[0].reason, SpawnReason::Join);
+}
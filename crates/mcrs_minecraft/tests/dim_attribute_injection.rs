@@ -46,6 +46,7 @@ use mcrs_minecraft_lighting::test_bench::bench_helpers;
 use vanilla::biome::Biome;
 use vanilla::block::Block;
 use vanilla::enchantment::EnchantmentData;
+use vanilla::item::Item;
 
 #[allow(unused_imports)]
 use mcrs_vanilla as vanilla;
@@ -79,7 +80,9 @@ fn build_app() -> App {
     app.insert_resource(make_stub_block_light_table());
     app.insert_resource(StaticRegistry::<Block>::new());
     app.insert_resource(StaticRegistry::<EnchantmentData>::default());
+    app.insert_resource(StaticRegistry::<Item>::new());
     app.insert_resource(TagRegistry::<Block>::default());
+    app.insert_resource(TagRegistry::<Item>::default());
     app.insert_resource(RegistrySnapshot::<Biome>::default());
 
     app.init_resource::<PlayerIndex>();
@@ -0,0 +1,259 @@
+//! Attribute sync: a player's attributes reach its own client when it joins
+//! and every viewer with its spawn, a modifier change is sent to both, and
+//! modifiers combine like vanilla's.
+
+#[path = "common/viewer.rs"]
+mod viewer;
+
+use bevy_app::App;
+use mcrs_minecraft::world::bus::{OutboundPlayerPacket, PacketPayload};
+use mcrs_minecraft::world::entity::attribute::{AttributeKind, AttributeSyncPlugin, Attributes};
+use mcrs_protocol::entity::attribute::{AttributeModifier, AttributeOperation, AttributeSnapshot};
+use mcrs_protocol::{VarInt, ident};
+use viewer::{Viewer, drain, sent_to, tick};
+
+fn make_app() -> App {
+    viewer::make_app(AttributeSyncPlugin)
+}
+
+fn spawn_pair(app: &mut App) -> (Viewer, Viewer) {
+    viewer::spawn_pair(app, Attributes::player())
+}
+
+/// The attribute lists of every Update Attributes about `seen` sent to
+/// `viewer`, in order.
+fn attribute_updates<'a>(
+    packets: &'a [OutboundPlayerPacket],
+    viewer: &Viewer,
+    seen: &Viewer,
+) -> Vec<&'a [AttributeSnapshot]> {
+    sent_to(packets, viewer)
+        .into_iter()
+        .filter_map(|data| match data {
+            PacketPayload::UpdateAttributes {
+                entity_id,
+                attributes,
+            } if *entity_id == seen.wire_id() => Some(attributes.as_slice()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn attributes_mut<'a>(app: &'a mut App, player: &Viewer) -> bevy_ecs::world::Mut<'a, Attributes> {
+    app.world_mut()
+        .get_mut::<Attributes>(player.entity)
+        .unwrap()
+}
+
+fn sprinting() -> AttributeModifier {
+    AttributeModifier {
+        id: ident!("sprinting").into(),
+        amount: 0.3,
+        operation: AttributeOperation::AddMultipliedTotal,
+    }
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-12,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn attributes_are_sent_on_join_and_with_the_spawn() {
+    let mut app = make_app();
+    let (alice, bob) = spawn_pair(&mut app);
+
+    let packets = drain(&mut app);
+    let expected = Attributes::player().snapshots();
+    assert_eq!(
+        attribute_updates(&packets, &alice, &alice),
+        [expected.as_slice()]
+    );
+    assert_eq!(
+        attribute_updates(&packets, &bob, &alice),
+        [expected.as_slice()]
+    );
+    assert_eq!(
+        attribute_updates(&packets, &alice, &bob),
+        [expected.as_slice()]
+    );
+
+    // Nothing changed, so the next tick sends nothing.
+    tick(&mut app);
+    assert!(drain(&mut app).is_empty());
+}
+
+#[test]
+fn re_adding_an_identical_modifier_sends_nothing() {
+    let mut app = make_app();
+    let (alice, _bob) = spawn_pair(&mut app);
+    attributes_mut(&mut app, &alice).add_modifier(AttributeKind::MovementSpeed, sprinting());
+    tick(&mut app);
+    drain(&mut app);
+
+    let replaced =
+        attributes_mut(&mut app, &alice).add_modifier(AttributeKind::MovementSpeed, sprinting());
+    assert_eq!(replaced, None);
+    tick(&mut app);
+    assert!(drain(&mut app).is_empty());
+}
+
+#[test]
+fn speed_modifier_is_sent_and_removing_it_reverts() {
+    let mut app = make_app();
+    let (alice, bob) = spawn_pair(&mut app);
+    drain(&mut app);
+
+    attributes_mut(&mut app, &alice).add_modifier(AttributeKind::MovementSpeed, sprinting());
+    assert_close(
+        app.world()
+            .get::<Attributes>(alice.entity)
+            .unwrap()
+            .value(AttributeKind::MovementSpeed),
+        0.13,
+    );
+    tick(&mut app);
+    let packets = drain(&mut app);
+    let sped_up = [AttributeSnapshot {
+        attribute: VarInt(AttributeKind::MovementSpeed as i32),
+        base: 0.1,
+        modifiers: vec![sprinting()],
+    }];
+    for viewer in [&alice, &bob] {
+        assert_eq!(attribute_updates(&packets, viewer, &alice), [&sped_up[..]]);
+    }
+
+    let removed = attributes_mut(&mut app, &alice)
+        .remove_modifier(AttributeKind::MovementSpeed, "minecraft:sprinting");
+    assert_eq!(removed, Some(sprinting()));
+    assert_close(
+        app.world()
+            .get::<Attributes>(alice.entity)
+            .unwrap()
+            .value(AttributeKind::MovementSpeed),
+        0.1,
+    );
+    tick(&mut app);
+    let packets = drain(&mut app);
+    let reverted = [AttributeSnapshot {
+        attribute: VarInt(AttributeKind::MovementSpeed as i32),
+        base: 0.1,
+        modifiers: Vec::new(),
+    }];
+    for viewer in [&alice, &bob] {
+        assert_eq!(attribute_updates(&packets, viewer, &alice), [&reverted[..]]);
+    }
+}
+
+#[test]
+fn modifiers_apply_by_operation_and_replace_by_id() {
+    let mut attributes = Attributes::player();
+    let modifier = |path: &str, amount, operation| AttributeModifier {
+        id: format!("test:{path}").try_into().unwrap(),
+        amount,
+        operation,
+    };
+    let kind = AttributeKind::MovementSpeed;
+    // Declared out of order: each operation still applies in its own pass.
+    attributes.add_modifier(
+        kind,
+        modifier("total", 1.0, AttributeOperation::AddMultipliedTotal),
+    );
+    attributes.add_modifier(
+        kind,
+        modifier("base", 0.5, AttributeOperation::AddMultipliedBase),
+    );
+    attributes.add_modifier(kind, modifier("add", 0.05, AttributeOperation::AddValue));
+    // (0.1 + 0.05) * (1 + 0.5) * (1 + 1)
+    assert_close(attributes.value(kind), 0.45);
+
+    let replaced =
+        attributes.add_modifier(kind, modifier("add", 0.2, AttributeOperation::AddValue));
+    assert_eq!(
+        replaced,
+        Some(modifier("add", 0.05, AttributeOperation::AddValue))
+    );
+    assert_eq!(attributes.get(kind).unwrap().modifiers().len(), 3);
+    // (0.1 + 0.2) * (1 + 0.5) * (1 + 1)
+    assert_close(attributes.value(kind), 0.9);
+
+    // Adding the same modifier again replaces nothing.
+    let replaced =
+        attributes.add_modifier(kind, modifier("add", 0.2, AttributeOperation::AddValue));
+    assert_eq!(replaced, None);
+    assert_eq!(attributes.get(kind).unwrap().modifiers().len(), 3);
+
+    // Values are clamped to the attribute's range.
+    attributes.add_modifier(
+        AttributeKind::MaxHealth,
+        modifier("drain", -100.0, AttributeOperation::AddValue),
+    );
+    assert_eq!(attributes.value(AttributeKind::MaxHealth), 1.0);
+}
+
+/// Vanilla's `minecraft:attribute` registry in registration order, which
+/// is the network id order.
+const VANILLA_ATTRIBUTES: [&str; 38] = [
+    "air_drag_modifier",
+    "armor",
+    "armor_toughness",
+    "attack_damage",
+    "attack_knockback",
+    "attack_speed",
+    "block_break_speed",
+    "block_interaction_range",
+    "bounciness",
+    "burning_time",
+    "camera_distance",
+    "explosion_knockback_resistance",
+    "entity_interaction_range",
+    "fall_damage_multiplier",
+    "flying_speed",
+    "follow_range",
+    "friction_modifier",
+    "gravity",
+    "jump_strength",
+    "knockback_resistance",
+    "luck",
+    "max_absorption",
+    "max_health",
+    "mining_efficiency",
+    "movement_efficiency",
+    "movement_speed",
+    "oxygen_bonus",
+    "safe_fall_distance",
+    "scale",
+    "sneaking_speed",
+    "spawn_reinforcements",
+    "step_height",
+    "submerged_mining_speed",
+    "sweeping_damage_ratio",
+    "tempt_range",
+    "water_movement_efficiency",
+    "waypoint_transmit_range",
+    "waypoint_receive_range",
+];
+
+#[test]
+fn attribute_ids_match_the_vanilla_registry() {
+    for kind in AttributeKind::ALL {
+        assert_eq!(
+            VANILLA_ATTRIBUTES
+                .iter()
+                .position(|name| *name == kind.name()),
+            Some(kind as usize),
+            "{kind:?}"
+        );
+    }
+
+    // Every registered attribute has a name in the bundled language file.
+    let lang = include_str!("../../../assets/minecraft/lang/en_us.json");
+    for name in VANILLA_ATTRIBUTES {
+        assert!(
+            lang.contains(&format!("\"attribute.name.{name}\"")),
+            "{name}"
+        );
+    }
+}
@@ -0,0 +1,284 @@
+//! Entity velocity: a change reaches the entity's own client and every
+//! viewer as Set Entity Velocity on the entity's update interval, or at once
+//! after an impulse, a moving item is spawned with its velocity, and item
+//! entities fall, slide and land by their velocity each tick until their
+//! lifetime runs out.
+
+#[path = "common/viewer.rs"]
+mod viewer;
+
+use bevy_app::App;
+use bevy_ecs::entity::Entity;
+use bevy_math::DVec3;
+use mcrs_engine::entity::physics::{Transform, Velocity};
+use mcrs_engine::world::chunk::{ChunkIndex, ChunkPos};
+use mcrs_engine::world::dimension::{DimensionBundle, InDimension};
+use mcrs_minecraft::client_info::ServerViewConfig;
+use mcrs_minecraft::world::block::minecraft::STONE;
+use mcrs_minecraft::world::bus::{OutboundPlayerPacket, PacketPayload};
+use mcrs_minecraft::world::entity::item::{ItemAge, ItemEntityBundle, ItemEntityPlugin, LIFETIME};
+use mcrs_minecraft::world::entity::player::movement::OnGround;
+use mcrs_minecraft::world::entity::velocity::{
+    UpdateInterval, VelocityImpulse, VelocitySyncPlugin,
+};
+use mcrs_minecraft::world::item::ItemStack;
+use mcrs_minecraft_block::palette::BlockPalette;
+use mcrs_protocol::ItemId;
+use viewer::{Viewer, drain, sent_to, spawn_pair, tick};
+
+fn make_app() -> App {
+    let mut app = viewer::make_app((ItemEntityPlugin, VelocitySyncPlugin));
+    app.init_resource::<ServerViewConfig>();
+    app
+}
+
+fn spawn_player(app: &mut App, dim: Entity, pos: DVec3) -> Viewer {
+    viewer::spawn_player(app, dim, pos, ())
+}
+
+/// The velocities about `seen` sent to `viewer`, in order.
+fn velocity_updates(packets: &[OutboundPlayerPacket], viewer: &Viewer, seen: Entity) -> Vec<DVec3> {
+    sent_to(packets, viewer)
+        .into_iter()
+        .filter_map(|data| match *data {
+            PacketPayload::EntityVelocity {
+                entity_id,
+                velocity,
+            } if entity_id == seen.index_u32() as i32 => Some(velocity),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A dimension whose section at the origin has a stone floor filling
+/// `y = 1`, so an item resting on it stands at `y = 2`.
+fn spawn_floored_dimension(app: &mut App) -> Entity {
+    let dim = app.world_mut().spawn(DimensionBundle::default()).id();
+    let mut palette = BlockPalette::default();
+    palette.fill_box(0, 16, 1, 2, 0, 16, STONE.default_state().id());
+    let section = ChunkPos::new(0, 0, 0);
+    let chunk = app
+        .world_mut()
+        .spawn((section, InDimension(dim), palette))
+        .id();
+    app.world_mut()
+        .get_mut::<ChunkIndex>(dim)
+        .unwrap()
+        .insert(section, chunk);
+    dim
+}
+
+fn stack() -> ItemStack {
+    ItemStack::new(ItemId(1), 1)
+}
+
+#[test]
+fn knockback_reaches_the_player_and_its_viewers() {
+    let mut app = make_app();
+    let (alice, bob) = spawn_pair(&mut app, ());
+    drain(&mut app);
+
+    // An impulse goes out the tick it is given.
+    let knockback = DVec3::new(0.4, 0.36, 0.0);
+    app.world_mut()
+        .entity_mut(alice.entity)
+        .insert((Velocity(knockback), VelocityImpulse));
+    tick(&mut app);
+    let packets = drain(&mut app);
+    for viewer in [&alice, &bob] {
+        assert_eq!(
+            velocity_updates(&packets, viewer, alice.entity),
+            [knockback]
+        );
+    }
+
+    // A change too small for the client to notice is not sent.
+    app.world_mut().get_mut::<Velocity>(alice.entity).unwrap().0 += DVec3::splat(1.0e-4);
+    tick(&mut app);
+    assert!(velocity_updates(&drain(&mut app), &bob, alice.entity).is_empty());
+
+    // Coming to a stop always is, once within the update interval.
+    app.world_mut().get_mut::<Velocity>(alice.entity).unwrap().0 = DVec3::ZERO;
+    let mut packets = Vec::new();
+    for _ in 0..UpdateInterval::PLAYER.0 {
+        tick(&mut app);
+        packets.extend(drain(&mut app));
+    }
+    for viewer in [&alice, &bob] {
+        assert_eq!(
+            velocity_updates(&packets, viewer, alice.entity),
+            [DVec3::ZERO]
+        );
+    }
+}
+
+#[test]
+fn moving_item_is_spawned_with_its_velocity() {
+    let mut app = make_app();
+    let dim = spawn_floored_dimension(&mut app);
+    let alice = spawn_player(&mut app, dim, DVec3::new(8.5, 2.0, 8.5));
+    tick(&mut app);
+    drain(&mut app);
+
+    let velocity = DVec3::new(0.05, 0.2, -0.05);
+    let item = app
+        .world_mut()
+        .spawn(
+            ItemEntityBundle::new(
+                InDimension(dim),
+                Transform::from_xyz(4.5, 5.0, 4.5),
+                stack(),
+            )
+            .with_velocity(velocity),
+        )
+        .id();
+    tick(&mut app);
+    let packets = drain(&mut app);
+
+    let entity_id = item.index_u32() as i32;
+    let spawned_with = packets
+        .iter()
+        .find_map(|packet| match packet.data {
+            PacketPayload::PlayerEnteredView {
+                entity_id: id,
+                velocity,
+                ..
+            } if id == entity_id => Some(velocity),
+            _ => None,
+        })
+        .expect("the item is spawned for alice");
+    // At most one tick of gravity and drag off the velocity it was given.
+    assert!(
+        (spawned_with - velocity).abs().max_element() < 0.05,
+        "spawned with {spawned_with}"
+    );
+    assert!(velocity_updates(&packets, &alice, item).is_empty());
+}
+
+#[test]
+fn falling_item_velocity_waits_for_the_update_interval() {
+    let mut app = make_app();
+    let dim = spawn_floored_dimension(&mut app);
+    let alice = spawn_player(&mut app, dim, DVec3::new(8.5, 2.0, 8.5));
+    tick(&mut app);
+    drain(&mut app);
+
+    let item = app
+        .world_mut()
+        .spawn(
+            ItemEntityBundle::new(
+                InDimension(dim),
+                Transform::from_xyz(4.5, 5.0, 4.5),
+                stack(),
+            )
+            .with_velocity(DVec3::new(0.05, 0.2, -0.05)),
+        )
+        .id();
+    tick(&mut app);
+    drain(&mut app);
+
+    // Gravity changes the velocity every tick, but it is only sent on the
+    // item's interval.
+    let mut updates = Vec::new();
+    for _ in 0..UpdateInterval::ITEM.0 - 1 {
+        tick(&mut app);
+        updates.extend(velocity_updates(&drain(&mut app), &alice, item));
+    }
+    assert!(updates.is_empty(), "sent early: {updates:?}");
+    tick(&mut app);
+    assert_eq!(
+        velocity_updates(&drain(&mut app), &alice, item),
+        [app.world().get::<Velocity>(item).unwrap().0]
+    );
+}
+
+#[test]
+fn item_position_integrates_over_ticks_and_lands() {
+    let mut app = make_app();
+    let dim = spawn_floored_dimension(&mut app);
+    spawn_player(&mut app, dim, DVec3::new(8.5, 2.0, 8.5));
+
+    let start = DVec3::new(8.5, 5.0, 8.5);
+    let mut velocity = DVec3::new(0.05, 0.2, -0.05);
+    let item = app
+        .world_mut()
+        .spawn(
+            ItemEntityBundle::new(
+                InDimension(dim),
+                Transform::from_translation(start),
+                stack(),
+            )
+            .with_velocity(velocity),
+        )
+        .id();
+    let position = |app: &App| app.world().get::<Transform>(item).unwrap().translation;
+    let on_ground = |app: &App| app.world().get::<OnGround>(item).unwrap().0;
+
+    // In the air: gravity, then the move, then drag.
+    let mut expected = start;
+    for _ in 0..5 {
+        tick(&mut app);
+        velocity.y -= 0.04;
+        expected += velocity;
+        velocity *= 0.98;
+        assert!(
+            (position(&app) - expected).abs().max_element() < 1e-9,
+            "expected {expected}, at {}",
+            position(&app)
+        );
+        let actual = app.world().get::<Velocity>(item).unwrap().0;
+        assert!((actual - velocity).abs().max_element() < 1e-9);
+    }
+    assert!(position(&app).y > start.y, "tossed up first");
+
+    let mut ticks = 5;
+    while !on_ground(&app) {
+        assert!(ticks < 100, "still falling at {}", position(&app));
+        tick(&mut app);
+        ticks += 1;
+    }
+    let landed = position(&app);
+    assert_eq!(landed.y, 2.0, "rests on top of the stone floor");
+    assert_eq!(app.world().get::<Velocity>(item).unwrap().y, 0.0);
+    assert!(landed.x > start.x && landed.z < start.z, "drifted sideways");
+
+    // Friction stops the slide, and a resting item stays put.
+    for _ in 0..40 {
+        tick(&mut app);
+    }
+    let rested = position(&app);
+    tick(&mut app);
+    assert_eq!(position(&app), rested);
+    assert!(on_ground(&app));
+    assert_eq!(rested.y, 2.0);
+}
+
+#[test]
+fn item_despawns_at_the_end_of_its_lifetime() {
+    let mut app = make_app();
+    let dim = spawn_floored_dimension(&mut app);
+    let alice = spawn_player(&mut app, dim, DVec3::new(8.5, 2.0, 8.5));
+
+    let item = app
+        .world_mut()
+        .spawn(ItemEntityBundle::new(
+            InDimension(dim),
+            Transform::from_translation(DVec3::new(4.5, 2.0, 4.5)),
+            stack(),
+        ))
+        .id();
+    tick(&mut app);
+    tick(&mut app);
+    assert_eq!(app.world().get::<ItemAge>(item), Some(&ItemAge(2)));
+    drain(&mut app);
+
+    app.world_mut().get_mut::<ItemAge>(item).unwrap().0 = LIFETIME - 1;
+    tick(&mut app);
+    tick(&mut app);
+    assert!(app.world().get_entity(item).is_err(), "despawned");
+    let packets = drain(&mut app);
+    let left = sent_to(&packets, &alice).into_iter().any(|data| {
+        matches!(data, PacketPayload::PlayerLeftView { entity_ids } if entity_ids.as_slice() == [item.index_u32() as i32])
+    });
+    assert!(left, "Alice is told the item is gone");
+}
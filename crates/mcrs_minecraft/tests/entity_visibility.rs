@@ -0,0 +1,271 @@
+//! Player visibility: two players within view distance are spawned for each
+//! other with metadata and a position sync, a short step goes out as a
+//! delta, walking out of range removes the entity on both sides, an arm
+//! swing reaches only the players in range, and sneaking and sprinting show
+//! up in the metadata they are sent.
+
+#[path = "common/viewer.rs"]
+mod viewer;
+
+use bevy_app::App;
+use bevy_math::DVec3;
+use mcrs_engine::entity::physics::Transform;
+use mcrs_engine::world::dimension::InDimension;
+use mcrs_minecraft::world::bus::{OutboundPlayerPacket, PacketPayload};
+use mcrs_minecraft::world::entity::player::posture::{
+    POSE_INDEX, PlayerPosture, PosturePlugin, SHARED_FLAGS_INDEX,
+};
+use mcrs_minecraft::world::entity::visibility::PLAYER_SKIN_PARTS_INDEX;
+use mcrs_network::event::ReceivedPacketEvent;
+use mcrs_protocol::entity::player::{PlayerCommandAction, PlayerInputFlags};
+use mcrs_protocol::entity::{EntityMetadata, MetaDataValue, Pose};
+use mcrs_protocol::packets::game::clientbound::ClientboundAnimate;
+use mcrs_protocol::packets::game::serverbound::{
+    ServerboundPlayerCommand, ServerboundPlayerInput, ServerboundSwing,
+};
+use mcrs_protocol::{Encode, Hand, Packet, VarInt};
+use std::time::Instant;
+use viewer::{Viewer, drain, sent_to, spawn_player, tick};
+
+fn make_app() -> App {
+    viewer::make_app(PosturePlugin)
+}
+
+fn spawn_pair(app: &mut App) -> (Viewer, Viewer) {
+    viewer::spawn_pair(app, PlayerPosture::default())
+}
+
+fn move_to(app: &mut App, player: &Viewer, pos: DVec3) {
+    app.world_mut()
+        .get_mut::<Transform>(player.entity)
+        .unwrap()
+        .translation = pos;
+}
+
+/// Deliver `packet` as if `sender`'s client had sent it.
+fn receive<P: Encode + Packet>(app: &mut App, sender: &Viewer, packet: P) {
+    let mut data = Vec::new();
+    packet.encode(&mut data).unwrap();
+    app.world_mut().trigger(ReceivedPacketEvent {
+        entity: sender.entity,
+        id: P::ID,
+        data: data.into(),
+        timestamp: Instant::now(),
+    });
+    app.world_mut().flush();
+}
+
+#[test]
+fn players_within_view_distance_spawn_each_other() {
+    let mut app = make_app();
+    let (alice, bob) = spawn_pair(&mut app);
+
+    let packets = drain(&mut app);
+    for (viewer, seen) in [(&alice, &bob), (&bob, &alice)] {
+        let sent = sent_to(&packets, viewer);
+        assert_eq!(sent.len(), 2, "spawn with metadata, then position sync");
+        let expected = app
+            .world()
+            .get::<Transform>(seen.entity)
+            .unwrap()
+            .translation;
+        assert!(matches!(
+            sent[0],
+            PacketPayload::PlayerEnteredView { entity_id, position, metadata, .. }
+                if *entity_id == seen.wire_id()
+                    && *position == expected
+                    && metadata.len() == 1
+                    && metadata.get(PLAYER_SKIN_PARTS_INDEX).is_some()
+        ));
+        assert!(matches!(
+            sent[1],
+            PacketPayload::EntityPosSync { entity_id, position, .. }
+                if *entity_id == seen.wire_id() && *position == expected
+        ));
+    }
+
+    // Nobody moved, so the next tick sends nothing.
+    tick(&mut app);
+    assert!(drain(&mut app).is_empty());
+}
+
+#[test]
+fn moving_out_of_view_distance_removes_the_entity() {
+    let mut app = make_app();
+    let (alice, bob) = spawn_pair(&mut app);
+    drain(&mut app);
+
+    // One block east fits a relative move.
+    move_to(&mut app, &bob, DVec3::new(21.5, 64.0, 0.5));
+    tick(&mut app);
+    let packets = drain(&mut app);
+    let sent = sent_to(&packets, &alice);
+    assert_eq!(sent.len(), 1);
+    assert!(matches!(
+        sent[0],
+        PacketPayload::EntityMove { entity_id, delta: [4096, 0, 0], look: None, .. }
+            if *entity_id == bob.wire_id()
+    ));
+
+    // Thirty chunks east is past the default view distance of twelve.
+    move_to(&mut app, &bob, DVec3::new(480.5, 64.0, 0.5));
+    tick(&mut app);
+    let packets = drain(&mut app);
+    for (viewer, gone) in [(&alice, &bob), (&bob, &alice)] {
+        let sent = sent_to(&packets, viewer);
+        assert_eq!(sent.len(), 1);
+        assert!(matches!(
+            sent[0],
+            PacketPayload::PlayerLeftView { entity_ids } if entity_ids.as_slice() == [gone.wire_id()]
+        ));
+    }
+
+    // Out of range, Bob's next step is not sent to Alice at all.
+    move_to(&mut app, &bob, DVec3::new(481.5, 64.0, 0.5));
+    tick(&mut app);
+    assert!(sent_to(&drain(&mut app), &alice).is_empty());
+}
+
+#[test]
+fn swing_is_shown_to_players_in_range_only() {
+    let mut app = make_app();
+    let (alice, bob) = spawn_pair(&mut app);
+    let dim = app.world().get::<InDimension>(alice.entity).unwrap().0;
+    let carol = spawn_player(
+        &mut app,
+        dim,
+        DVec3::new(480.5, 64.0, 0.5),
+        PlayerPosture::default(),
+    );
+    tick(&mut app);
+    tick(&mut app);
+    drain(&mut app);
+
+    receive(&mut app, &alice, ServerboundSwing { hand: Hand::Off });
+
+    let packets = drain(&mut app);
+    let sent = sent_to(&packets, &bob);
+    assert_eq!(sent.len(), 1);
+    assert!(matches!(
+        sent[0],
+        PacketPayload::EntityAnimation { entity_id, action }
+            if *entity_id == alice.wire_id() && *action == ClientboundAnimate::SWING_OFF_HAND
+    ));
+    assert!(sent_to(&packets, &carol).is_empty());
+    assert!(sent_to(&packets, &alice).is_empty());
+}
+
+#[test]
+fn sneaking_is_shown_in_metadata_to_observers() {
+    let mut app = make_app();
+    let (alice, bob) = spawn_pair(&mut app);
+    drain(&mut app);
+
+    for (shift, flags, pose) in [(true, 0x02, Pose::Crouching), (false, 0, Pose::Standing)] {
+        receive(
+            &mut app,
+            &alice,
+            ServerboundPlayerInput {
+                input: PlayerInputFlags::new().with_shift(shift),
+            },
+        );
+        let packets = drain(&mut app);
+        let sent = sent_to(&packets, &bob);
+        assert_eq!(sent.len(), 1);
+        let PacketPayload::EntityData {
+            entity_id,
+            metadata,
+        } = sent[0]
+        else {
+            panic!("expected metadata, got {:?}", sent[0]);
+        };
+        assert_eq!(*entity_id, alice.wire_id());
+        assert!(matches!(
+            metadata.get(SHARED_FLAGS_INDEX),
+            Some(MetaDataValue::Byte(value)) if *value == flags
+        ));
+        assert!(matches!(
+            metadata.get(POSE_INDEX),
+            Some(MetaDataValue::Pose(value)) if *value == pose
+        ));
+        assert!(sent_to(&packets, &alice).is_empty());
+    }
+}
+
+/// The flags byte and pose of the one metadata update `viewer` was sent.
+fn posture_sent_to(packets: &[OutboundPlayerPacket], viewer: &Viewer) -> (i8, Pose) {
+    let sent = sent_to(packets, viewer);
+    assert_eq!(sent.len(), 1);
+    let PacketPayload::EntityData { metadata, .. } = sent[0] else {
+        panic!("expected metadata, got {:?}", sent[0]);
+    };
+    let Some(MetaDataValue::Byte(flags)) = metadata.get(SHARED_FLAGS_INDEX) else {
+        panic!("no flags byte in {metadata:?}");
+    };
+    let Some(MetaDataValue::Pose(pose)) = metadata.get(POSE_INDEX) else {
+        panic!("no pose in {metadata:?}");
+    };
+    (*flags, *pose)
+}
+
+#[test]
+fn sprinting_is_shown_in_metadata_alongside_sneaking() {
+    let mut app = make_app();
+    let (alice, bob) = spawn_pair(&mut app);
+    drain(&mut app);
+
+    let command = |action| ServerboundPlayerCommand {
+        entity_id: VarInt(alice.wire_id()),
+        action,
+        data: VarInt(0),
+    };
+    receive(
+        &mut app,
+        &alice,
+        command(PlayerCommandAction::StartSprinting),
+    );
+    let packets = drain(&mut app);
+    assert_eq!(posture_sent_to(&packets, &bob), (0x08, Pose::Standing));
+    assert!(sent_to(&packets, &alice).is_empty());
+
+    // Sprinting again changes nothing, so nothing is sent.
+    receive(
+        &mut app,
+        &alice,
+        command(PlayerCommandAction::StartSprinting),
+    );
+    assert!(drain(&mut app).is_empty());
+
+    receive(
+        &mut app,
+        &alice,
+        ServerboundPlayerInput {
+            input: PlayerInputFlags::new().with_shift(true),
+        },
+    );
+    let packets = drain(&mut app);
+    assert_eq!(posture_sent_to(&packets, &bob), (0x0A, Pose::Crouching));
+
+    receive(
+        &mut app,
+        &alice,
+        command(PlayerCommandAction::StopSprinting),
+    );
+    let packets = drain(&mut app);
+    assert_eq!(posture_sent_to(&packets, &bob), (0x02, Pose::Crouching));
+}
+
+/// Posture only owns the crouching and sprinting bits of the flags byte.
+#[test]
+fn posture_keeps_the_other_entity_flags() {
+    let on_fire_and_invisible = 0x01 | 0x20;
+    let metadata = PlayerPosture {
+        sneaking: true,
+        sprinting: false,
+    }
+    .append_to(EntityMetadata::new().with_byte(SHARED_FLAGS_INDEX, on_fire_and_invisible | 0x08));
+    assert!(matches!(
+        metadata.get(SHARED_FLAGS_INDEX),
+        Some(MetaDataValue::Byte(flags)) if *flags == on_fire_and_invisible | 0x02
+    ));
+}
@@ -22,18 +22,21 @@ use mcrs_minecraft::world::bridge::{bridge_player_attach, partition_main_inbound
 use mcrs_minecraft::world::bus::{
     InboundPlayerDespawn, InboundPlayerPacket, InboundPlayerSpawn, OutboundPlayerAttached,
     OutboundPlayerDisconnect, OutboundPlayerPacket, OutboundPlayerTransfer,
-    PendingInboundLifecycle, PendingInboundPartition, PlayerTransferSnapshot,
+    PendingInboundLifecycle, PendingInboundPartition, PlayerTransferSnapshot, SpawnReason,
 };
 use mcrs_minecraft::world::player_index::{HostAnchorRef, PlayerIndex};
-use mcrs_minecraft::world::sub_app_builder::{drain_dim_spawn_queue, DimSubAppHandle};
+use mcrs_minecraft::world::sub_app_builder::{DimLabel, DimSubAppHandle, drain_dim_spawn_queue};
 use mcrs_minecraft_lighting::table::BlockStateLightTable;
 use mcrs_protocol::uuid::Uuid;
+use mcrs_protocol::{GameMode, ident};
 use mcrs_vanilla::biome::Biome;
 use mcrs_vanilla::block::Block;
 use mcrs_vanilla::enchantment::EnchantmentData;
+use mcrs_vanilla::item::Item;
 
 // System under test (Task 1) — must be pub in configuration.rs
 use mcrs_minecraft::configuration::emit_initial_player_spawn;
+use mcrs_minecraft::spawn_point::SpawnPoint;
 
 fn make_stub_block_light_table() -> BlockStateLightTable {
     let state_count = 2usize;
@@ -66,7 +69,9 @@ fn build_host_app() -> App {
     app.insert_resource(make_stub_block_light_table());
     app.insert_resource(StaticRegistry::<Block>::new());
     app.insert_resource(StaticRegistry::<EnchantmentData>::default());
+    app.insert_resource(StaticRegistry::<Item>::new());
     app.insert_resource(TagRegistry::<Block>::default());
+    app.insert_resource(TagRegistry::<Item>::default());
     app.insert_resource(RegistrySnapshot::<Biome>::default());
 
     app.init_resource::<PlayerIndex>();
@@ -85,6 +90,7 @@ fn build_host_app() -> App {
     );
 
     app.add_plugins(LoginPlugin);
+    app.init_resource::<SpawnPoint>();
     // System under test
     app.add_systems(Update, emit_initial_player_spawn);
 
@@ -117,6 +123,13 @@ fn spawn_accepted_connection(app: &mut App) -> (Entity, Entity) {
     (connection_entity, host_anchor)
 }
 
+/// A fake live sub-app label entity for the dimension `name`.
+fn spawn_dim_label(app: &mut App, name: &str) -> Entity {
+    app.world_mut()
+        .spawn((DimSubAppHandle, DimLabel(name.to_string())))
+        .id()
+}
+
 /// Transition a connection entity to `ConnectionState::Game` and insert
 /// `InGameConnectionState` — mirrors what `on_configuration_ack` does.
 fn transition_to_game(app: &mut App, connection_entity: Entity) {
@@ -141,7 +154,7 @@ fn game_transition_emits_initial_spawn() {
     let (connection_entity, host_anchor) = spawn_accepted_connection(&mut app);
 
     // Spawn a fake live DimSubAppHandle label entity on the host
-    let dim_label = app.world_mut().spawn(DimSubAppHandle).id();
+    let dim_label = spawn_dim_label(&mut app, "minecraft:overworld");
 
     // Transition to Game — the emit system should pick this up
     transition_to_game(&mut app, connection_entity);
@@ -175,6 +188,34 @@ fn game_transition_emits_initial_spawn() {
     );
 }
 
+/// The join goes to the sub-app of the spawn point's dimension, whichever
+/// sub-app was spawned first.
+#[test]
+fn initial_spawn_joins_the_spawn_point_dimension() {
+    let mut app = build_host_app();
+    app.insert_resource(SpawnPoint {
+        dimension: ident!("the_nether").into(),
+        ..Default::default()
+    });
+
+    let (connection_entity, host_anchor) = spawn_accepted_connection(&mut app);
+    let overworld = spawn_dim_label(&mut app, "minecraft:overworld");
+    let nether = spawn_dim_label(&mut app, "minecraft:the_nether");
+
+    transition_to_game(&mut app, connection_entity);
+    app.update();
+
+    let world = app.world();
+    let lifecycle = world.resource::<PendingInboundLifecycle>();
+    assert!(!lifecycle.per_dim.contains_key(&overworld));
+    assert_eq!(lifecycle.per_dim[&nether].spawns.len(), 1);
+    let location = world
+        .resource::<PlayerIndex>()
+        .get(&host_anchor)
+        .expect("PlayerLocation present");
+    assert_eq!(location.current_dim, nether);
+}
+
 /// When no live DimSubAppHandle label entity exists yet (dims still loading),
 /// the emitter must NOT push any spawn and must leave current_dim as PLACEHOLDER.
 #[test]
@@ -213,7 +254,7 @@ fn idempotent_single_emit() {
 
     let (connection_entity, host_anchor) = spawn_accepted_connection(&mut app);
 
-    let dim_label = app.world_mut().spawn(DimSubAppHandle).id();
+    let dim_label = spawn_dim_label(&mut app, "minecraft:overworld");
 
     // First Game transition — emits the spawn
     transition_to_game(&mut app, connection_entity);
@@ -287,6 +328,7 @@ fn spawn_consumer_materializes_in_dim_entity() {
             username: "consumer_test".into(),
             position: DVec3::new(0.0, 64.0, 0.0),
             rotation: Vec2::ZERO,
+            game_mode: GameMode::Survival,
         };
         app.world_mut()
             .resource_mut::<PendingInboundLifecycle>()
@@ -294,7 +336,11 @@ fn spawn_consumer_materializes_in_dim_entity() {
             .entry(dim_label)
             .or_default()
             .spawns
-            .push(InboundPlayerSpawn { host_anchor, snapshot });
+            .push(InboundPlayerSpawn {
+                host_anchor,
+                snapshot,
+                reason: SpawnReason::Join,
+            });
     }
 
     // Tick 1: extract shuttles the spawn into the sub-app; sub-app consumer
@@ -402,6 +448,7 @@ fn no_duplicate_spawn_on_reread() {
             username: "cursor_test".into(),
             position: DVec3::new(0.0, 64.0, 0.0),
             rotation: Vec2::ZERO,
+            game_mode: GameMode::Survival,
         };
         app.world_mut()
             .resource_mut::<PendingInboundLifecycle>()
@@ -409,7 +456,11 @@ fn no_duplicate_spawn_on_reread() {
             .entry(dim_label)
             .or_default()
             .spawns
-            .push(InboundPlayerSpawn { host_anchor, snapshot });
+            .push(InboundPlayerSpawn {
+                host_anchor,
+                snapshot,
+                reason: SpawnReason::Join,
+            });
     }
 
     // Tick 1: consumer reads the spawn and materializes one entity
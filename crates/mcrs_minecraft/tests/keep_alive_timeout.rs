@@ -0,0 +1,130 @@
+//! Keep-alive enforcement: a client that never answers is kicked once the
+//! configured timeout elapses, and an answered keep-alive updates `Latency`
+//! and `LatencySample`.
+
+#[path = "common/mock_connection.rs"]
+mod mock_connection;
+
+use std::time::{Duration, Instant};
+
+use bevy_ecs::entity::Entity;
+use bevy_ecs::world::World;
+use bytes::Bytes;
+use mcrs_minecraft::keep_alive::{
+    KeepAliveConfig, Latency, LatencySample, handle_keepalive, handle_keepalive_response,
+    new_connection,
+};
+use mcrs_network::event::ReceivedPacketEvent;
+use mcrs_network::{ConnectionState, ServerSideConnection};
+use mcrs_protocol::packets::common::serverbound::KeepAlive;
+use mcrs_protocol::packets::game::clientbound::{ClientboundDisconnect, ClientboundKeepAlive};
+use mcrs_protocol::packets::game::serverbound::ServerboundKeepAlive;
+use mcrs_protocol::{Encode, Packet, Text};
+use tokio::sync::mpsc;
+
+use mock_connection::{decode_frames, run_system, sent_frames};
+
+/// Spawn a Game-state connection with keep-alive state attached.
+fn spawn_game_connection(world: &mut World) -> (Entity, mpsc::Receiver<Bytes>) {
+    let (raw, outgoing_rx) = mock_connection::make_mock_raw_connection();
+    let entity = world
+        .spawn((
+            ServerSideConnection { raw: Box::new(raw) },
+            ConnectionState::Game,
+        ))
+        .id();
+    run_system(world, new_connection);
+    (entity, outgoing_rx)
+}
+
+#[test]
+fn unanswered_keepalive_disconnects_after_timeout() {
+    let mut world = World::new();
+    world.insert_resource(KeepAliveConfig {
+        interval: Duration::ZERO,
+        timeout: Duration::from_millis(50),
+    });
+    let (entity, mut outgoing_rx) = spawn_game_connection(&mut world);
+
+    // First tick sends the challenge; the client stays silent.
+    run_system(&mut world, handle_keepalive);
+    run_system(&mut world, handle_keepalive);
+    assert!(
+        !world
+            .get::<ServerSideConnection>(entity)
+            .unwrap()
+            .is_closing()
+    );
+
+    std::thread::sleep(Duration::from_millis(60));
+    run_system(&mut world, handle_keepalive);
+    assert!(
+        world
+            .get::<ServerSideConnection>(entity)
+            .unwrap()
+            .is_closing()
+    );
+
+    let frames = decode_frames(&mut outgoing_rx);
+    let ids: Vec<i32> = frames.iter().map(|frame| frame.id).collect();
+    assert_eq!(ids, [ClientboundKeepAlive::ID, ClientboundDisconnect::ID]);
+    let disconnect = frames[1].decode::<ClientboundDisconnect>().unwrap();
+    assert_eq!(
+        disconnect.reason,
+        Text::translate("disconnect.timeout", vec![])
+    );
+}
+
+#[test]
+fn answered_keepalive_records_latency() {
+    let mut world = World::new();
+    world.insert_resource(KeepAliveConfig {
+        interval: Duration::ZERO,
+        timeout: Duration::from_millis(50),
+    });
+    world.add_observer(handle_keepalive_response);
+    let (entity, mut outgoing_rx) = spawn_game_connection(&mut world);
+
+    let sent_at = Instant::now();
+    run_system(&mut world, handle_keepalive);
+    let frames = sent_frames(&mut world, entity, &mut outgoing_rx);
+    let challenge = frames[0]
+        .decode::<ClientboundKeepAlive>()
+        .unwrap()
+        .0
+        .payload;
+
+    let mut data = Vec::new();
+    ServerboundKeepAlive(KeepAlive { payload: challenge })
+        .encode(&mut data)
+        .unwrap();
+    world.trigger(ReceivedPacketEvent {
+        entity,
+        id: ServerboundKeepAlive::ID,
+        data: data.into(),
+        timestamp: sent_at + Duration::from_millis(100),
+    });
+    world.flush();
+
+    // One sample smooths from zero: (0 * 3 + ~100ms) / 4.
+    let latency = world.get::<Latency>(entity).unwrap().0;
+    assert!(
+        latency > Duration::from_millis(20) && latency <= Duration::from_millis(25),
+        "latency {latency:?}"
+    );
+    let sample = world.get::<LatencySample>(entity).unwrap().0;
+    assert!(
+        sample > Duration::from_millis(90) && sample <= Duration::from_millis(100),
+        "sample {sample:?}"
+    );
+
+    // Answered in time, so later ticks keep the connection open.
+    std::thread::sleep(Duration::from_millis(60));
+    run_system(&mut world, handle_keepalive);
+    assert!(
+        !world
+            .get::<ServerSideConnection>(entity)
+            .unwrap()
+            .is_closing()
+    );
+}
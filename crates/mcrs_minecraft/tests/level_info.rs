@@ -0,0 +1,175 @@
+//! Host-resident world state (time, border, weather) reaches a player only once they
+//! are in a dimension: the first copy goes through the bridge when they are
+//! attached, behind their Login, and the regular broadcasts skip them until
+//! then.
+
+use bevy_app::{App, FixedUpdate, Update};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::Messages;
+use bevy_time::{Fixed, Time};
+use mcrs_core::RegistryAccess;
+use mcrs_minecraft::weather::{Weather, WeatherPlugin};
+use mcrs_minecraft::world::bridge::bridge_player_attach;
+use mcrs_minecraft::world::bus::{
+    OutboundPlayerAttached, OutboundPlayerPacket, PacketPayload, PacketTarget,
+    PendingInboundPartition,
+};
+use mcrs_minecraft::world::player_index::{PlayerIndex, PlayerLocation};
+use mcrs_minecraft::world_border::{WorldBorder, WorldBorderPlugin};
+use mcrs_minecraft::world_time::{WorldTime, WorldTimePlugin};
+use mcrs_minecraft_worldgen::bevy::WorldGenConfig;
+use mcrs_protocol::GameEventKind;
+use smallvec::SmallVec;
+
+fn make_app() -> App {
+    let mut app = App::new();
+    app.add_message::<OutboundPlayerPacket>();
+    app.add_message::<OutboundPlayerAttached>();
+    app.init_resource::<RegistryAccess>();
+    app.init_resource::<Time<Fixed>>();
+    app.init_resource::<PlayerIndex>();
+    app.init_resource::<PendingInboundPartition>();
+    app.add_plugins((WorldTimePlugin, WorldBorderPlugin, WeatherPlugin));
+    app.add_systems(Update, bridge_player_attach);
+    app
+}
+
+/// The payloads queued for `host_anchor`, in order.
+fn queued(app: &mut App, host_anchor: Entity) -> Vec<PacketPayload> {
+    app.world_mut()
+        .resource_mut::<Messages<OutboundPlayerPacket>>()
+        .drain()
+        .filter(|packet| {
+            matches!(packet.target, PacketTarget::SinglePlayer(target) if target == host_anchor)
+        })
+        .map(|packet| packet.data)
+        .collect()
+}
+
+/// Put a player in Game into the index, not yet attached to a dimension.
+/// Returns its host anchor and socket.
+fn index_player(app: &mut App) -> (Entity, Entity) {
+    let host_anchor = app.world_mut().spawn_empty().id();
+    let socket = app.world_mut().spawn_empty().id();
+    let dim = app.world_mut().spawn_empty().id();
+    app.world_mut().resource_mut::<PlayerIndex>().insert(
+        host_anchor,
+        PlayerLocation {
+            socket,
+            current_dim: dim,
+            previous_dim: None,
+            in_dim_entity: None,
+            inbound_pending: SmallVec::new(),
+        },
+    );
+    (host_anchor, socket)
+}
+
+fn attach(app: &mut App, host_anchor: Entity) {
+    let in_dim_entity = app.world_mut().spawn_empty().id();
+    app.world_mut()
+        .resource_mut::<Messages<OutboundPlayerAttached>>()
+        .write(OutboundPlayerAttached {
+            host_anchor,
+            new_in_dim_entity: in_dim_entity,
+        });
+    app.world_mut().run_schedule(Update);
+}
+
+#[test]
+fn player_gets_the_time_and_border_once_attached() {
+    let mut app = make_app();
+    let (host_anchor, socket) = index_player(&mut app);
+
+    // In Game but not yet in a dimension: no Login has gone out, so neither
+    // may the time or the border.
+    app.world_mut()
+        .resource_mut::<WorldBorder>()
+        .set_diameter(1000.0);
+    for _ in 0..20 {
+        app.world_mut().run_schedule(FixedUpdate);
+    }
+    assert_eq!(app.world().resource::<WorldTime>().world_age, 20);
+    assert!(queued(&mut app, host_anchor).is_empty());
+    assert_eq!(
+        app.world()
+            .resource::<PlayerIndex>()
+            .attached_sockets()
+            .count(),
+        0
+    );
+
+    attach(&mut app, host_anchor);
+
+    // Clear weather adds nothing.
+    let payloads = queued(&mut app, host_anchor);
+    assert_eq!(payloads.len(), 2, "got {payloads:?}");
+    assert!(payloads.iter().any(
+        |payload| matches!(payload, PacketPayload::SetTime(set_time) if set_time.game_time == 20)
+    ));
+    assert!(payloads.iter().any(|payload| matches!(
+        payload,
+        PacketPayload::InitializeBorder(border) if border.new_size == 1000.0
+    )));
+    assert_eq!(
+        app.world()
+            .resource::<PlayerIndex>()
+            .attached_sockets()
+            .collect::<Vec<_>>(),
+        [socket]
+    );
+}
+
+#[test]
+fn player_joining_during_rain_gets_the_weather_once_attached() {
+    let mut app = make_app();
+    let (host_anchor, _) = index_player(&mut app);
+    {
+        let mut weather = app.world_mut().resource_mut::<Weather>();
+        weather.set_cycle(false);
+        weather.set_raining(true);
+        weather.set_rain_level(0.5);
+        weather.set_thunder_level(0.25);
+    }
+    attach(&mut app, host_anchor);
+    let game_events: Vec<GameEventKind> = queued(&mut app, host_anchor)
+        .into_iter()
+        .filter_map(|payload| match payload {
+            PacketPayload::GameEvent(packet) => Some(packet.game_event),
+            _ => None,
+        })
+        .collect();
+    assert!(
+        matches!(
+            game_events[..],
+            [
+                GameEventKind::BeginRaining,
+                GameEventKind::RainLevelChange(0.5),
+                GameEventKind::ThunderLevelChange(0.25),
+            ]
+        ),
+        "got {game_events:?}"
+    );
+}
+
+#[test]
+fn the_weather_cycle_follows_the_world_seed() {
+    let mut app = App::new();
+    app.insert_resource(WorldGenConfig {
+        seed: 7,
+        ..Default::default()
+    });
+    app.add_plugins(WeatherPlugin);
+    let rain = |mut weather: Weather| {
+        (0..40_000)
+            .map(|_| {
+                weather.tick();
+                weather.raining()
+            })
+            .collect::<Vec<_>>()
+    };
+    let from_plugin = rain(app.world_mut().remove_resource::<Weather>().unwrap());
+    // The first change comes within one and a half mean intervals.
+    assert!(from_plugin.contains(&true));
+    assert_eq!(from_plugin, rain(Weather::new(7)));
+}
@@ -0,0 +1,126 @@
+//! The login handoff follows `ServerConfig`: `online_mode` decides between
+//! Encryption Request and an offline login, `compression_threshold` the
+//! compression handoff.
+
+#[path = "common/mock_connection.rs"]
+mod mock_connection;
+
+use std::time::Instant;
+
+use bevy_ecs::entity::Entity;
+use bevy_ecs::world::World;
+use bytes::Bytes;
+use mcrs_minecraft::login::{LoginState, ServerKeyPair, handle_hello_packet};
+use mcrs_minecraft::server_config::ServerConfig;
+use mcrs_network::event::ReceivedPacketEvent;
+use mcrs_network::{ConnectionState, ServerSideConnection};
+use mcrs_protocol::packets::login::clientbound::{
+    ClientboundHello, ClientboundLoginFinished, LoginCompression,
+};
+use mcrs_protocol::packets::login::serverbound::ServerboundHello;
+use mcrs_protocol::uuid::Uuid;
+use mcrs_protocol::{Bounded, CompressionThreshold, Encode, Packet, PacketDecoder};
+use tokio::sync::mpsc;
+
+/// Spawn a connection in Login, send it Hello as `Notch` under `config`, and
+/// return the world with the receiving end of the connection's socket and the
+/// connection entity.
+fn hello(config: ServerConfig) -> (World, mpsc::Receiver<Bytes>, Entity) {
+    let mut world = World::new();
+    if config.online_mode {
+        world.insert_resource(ServerKeyPair::generate());
+    }
+    world.insert_resource(config);
+    world.add_observer(handle_hello_packet);
+    let (raw, outgoing_rx) = mock_connection::make_mock_raw_connection();
+    let entity = world
+        .spawn((
+            ServerSideConnection { raw: Box::new(raw) },
+            ConnectionState::Login,
+        ))
+        .id();
+
+    let mut data = Vec::new();
+    ServerboundHello {
+        username: Bounded("Notch"),
+        profile_id: Uuid::nil(),
+    }
+    .encode(&mut data)
+    .unwrap();
+    world.trigger(ReceivedPacketEvent {
+        entity,
+        id: ServerboundHello::ID,
+        data: data.into(),
+        timestamp: Instant::now(),
+    });
+    world.flush();
+    world
+        .get_mut::<ServerSideConnection>(entity)
+        .unwrap()
+        .flush()
+        .unwrap();
+    (world, outgoing_rx, entity)
+}
+
+fn received(outgoing_rx: &mut mpsc::Receiver<Bytes>) -> PacketDecoder {
+    let mut decoder = PacketDecoder::new();
+    while let Ok(blob) = outgoing_rx.try_recv() {
+        decoder.queue_bytes(blob.into());
+    }
+    decoder
+}
+
+#[test]
+fn compression_threshold_sends_set_compression_before_login_success() {
+    let (_world, mut outgoing_rx, _) = hello(ServerConfig {
+        compression_threshold: Some(256),
+        ..Default::default()
+    });
+    let mut decoder = received(&mut outgoing_rx);
+
+    let frame = decoder.try_next_packet().unwrap().expect("Set Compression");
+    assert_eq!(frame.id, LoginCompression::ID);
+    assert_eq!(frame.decode::<LoginCompression>().unwrap().threshold.0, 256);
+
+    // Login Success is already framed for the new threshold.
+    decoder.set_compression(CompressionThreshold(256));
+    let frame = decoder.try_next_packet().unwrap().expect("Login Success");
+    assert_eq!(frame.id, ClientboundLoginFinished::ID);
+    let finished = frame.decode::<ClientboundLoginFinished>().unwrap();
+    assert_eq!(finished.profile.username.0, "Notch");
+    assert!(decoder.try_next_packet().unwrap().is_none());
+}
+
+#[test]
+fn no_threshold_leaves_login_uncompressed() {
+    let (_world, mut outgoing_rx, _) = hello(ServerConfig::default());
+    let mut decoder = received(&mut outgoing_rx);
+
+    let frame = decoder.try_next_packet().unwrap().expect("Login Success");
+    assert_eq!(frame.id, ClientboundLoginFinished::ID);
+    assert!(decoder.try_next_packet().unwrap().is_none());
+}
+
+#[test]
+fn online_mode_asks_for_encryption_and_compresses_only_after_authentication() {
+    let (world, mut outgoing_rx, entity) = hello(ServerConfig {
+        online_mode: true,
+        compression_threshold: Some(256),
+        ..Default::default()
+    });
+    let mut decoder = received(&mut outgoing_rx);
+
+    let frame = decoder
+        .try_next_packet()
+        .unwrap()
+        .expect("Encryption Request");
+    assert_eq!(frame.id, ClientboundHello::ID);
+    assert!(
+        frame
+            .decode::<ClientboundHello>()
+            .unwrap()
+            .should_authenticate
+    );
+    assert!(decoder.try_next_packet().unwrap().is_none());
+    assert_eq!(world.get::<LoginState>(entity), Some(&LoginState::Key));
+}
@@ -0,0 +1,274 @@
+//! Online-mode login: Encryption Request, the client's Encryption Response,
+//! and the session server's `hasJoined` answer.
+
+#[path = "common/mock_connection.rs"]
+mod mock_connection;
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use bevy_ecs::entity::Entity;
+use bevy_ecs::system::RunSystemOnce;
+use bevy_ecs::world::World;
+use bevy_tasks::{IoTaskPool, TaskPool};
+use bytes::Bytes;
+use mcrs_minecraft::login::{
+    LoginState, ServerKeyPair, SessionLookup, SessionServer, finish_authentication,
+    handle_hello_packet, handle_key_packet, server_hash,
+};
+use mcrs_minecraft::server_config::ServerConfig;
+use mcrs_network::event::ReceivedPacketEvent;
+use mcrs_network::{ConnectionState, ServerSideConnection};
+use mcrs_protocol::packets::login::clientbound::{
+    ClientboundHello, ClientboundLoginDisconnect, ClientboundLoginFinished,
+};
+use mcrs_protocol::packets::login::serverbound::{ServerboundHello, ServerboundKey};
+use mcrs_protocol::uuid::Uuid;
+use mcrs_protocol::{Bounded, Encode, Packet, PacketDecoder};
+use rsa::pkcs8::DecodePublicKey;
+use rsa::rand_core::OsRng;
+use rsa::{Pkcs1v15Encrypt, RsaPublicKey};
+use tokio::sync::mpsc;
+
+const SHARED_SECRET: [u8; 16] = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3];
+
+const NOTCH: &str = r#"{
+    "id": "069a79f444e94726a5befca90e38aaf5",
+    "name": "Notch",
+    "properties": [{"name": "textures", "value": "e30=", "signature": "c2ln"}]
+}"#;
+
+/// A session server answering one request with `status` and `body`. Joining
+/// the handle gives the request line it was sent.
+fn session_server(
+    status: &'static str,
+    body: &'static str,
+) -> (String, thread::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!(
+        "http://{}/session/minecraft/hasJoined",
+        listener.local_addr().unwrap()
+    );
+    let handle = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            if header.trim_end().is_empty() {
+                break;
+            }
+        }
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        request_line
+    });
+    (url, handle)
+}
+
+struct Login {
+    world: World,
+    entity: Entity,
+    outgoing_rx: mpsc::Receiver<Bytes>,
+    public_key: Vec<u8>,
+    verify_token: Vec<u8>,
+}
+
+impl Login {
+    /// Send Hello as `Notch` to an online-mode server asking `url`, and read
+    /// back the Encryption Request.
+    fn start(url: String) -> Self {
+        IoTaskPool::get_or_init(TaskPool::new);
+        let mut world = World::new();
+        world.insert_resource(ServerConfig {
+            online_mode: true,
+            ..Default::default()
+        });
+        world.insert_resource(ServerKeyPair::generate());
+        world.insert_resource(SessionServer {
+            has_joined_url: url,
+        });
+        world.add_observer(handle_hello_packet);
+        world.add_observer(handle_key_packet);
+        let (raw, outgoing_rx) = mock_connection::make_mock_raw_connection();
+        let entity = world
+            .spawn((
+                ServerSideConnection { raw: Box::new(raw) },
+                ConnectionState::Login,
+            ))
+            .id();
+        let mut login = Self {
+            world,
+            entity,
+            outgoing_rx,
+            public_key: Vec::new(),
+            verify_token: Vec::new(),
+        };
+
+        login.receive(
+            ServerboundHello::ID,
+            ServerboundHello {
+                username: Bounded("Notch"),
+                profile_id: Uuid::nil(),
+            },
+        );
+        let mut decoder = login.sent();
+        let frame = decoder
+            .try_next_packet()
+            .unwrap()
+            .expect("Encryption Request");
+        let request = frame.decode::<ClientboundHello>().unwrap();
+        assert_eq!(request.server_id.0, "");
+        assert!(request.should_authenticate);
+        login.public_key = request.public_key.to_vec();
+        login.verify_token = request.verify_token.to_vec();
+        assert!(decoder.try_next_packet().unwrap().is_none());
+        assert_eq!(login.state(), LoginState::Key);
+        login
+    }
+
+    fn receive(&mut self, id: i32, packet: impl Encode) {
+        let mut data = Vec::new();
+        packet.encode(&mut data).unwrap();
+        self.world.trigger(ReceivedPacketEvent {
+            entity: self.entity,
+            id,
+            data: data.into(),
+            timestamp: Instant::now(),
+        });
+        self.world.flush();
+    }
+
+    /// Flush the connection and return everything sent since the last call.
+    fn sent(&mut self) -> PacketDecoder {
+        self.world
+            .get_mut::<ServerSideConnection>(self.entity)
+            .unwrap()
+            .flush()
+            .unwrap();
+        let mut decoder = PacketDecoder::new();
+        while let Ok(blob) = self.outgoing_rx.try_recv() {
+            decoder.queue_bytes(blob.into());
+        }
+        decoder
+    }
+
+    fn state(&self) -> LoginState {
+        *self.world.get::<LoginState>(self.entity).unwrap()
+    }
+
+    fn is_closing(&self) -> bool {
+        self.world
+            .get::<ServerSideConnection>(self.entity)
+            .unwrap()
+            .is_closing()
+    }
+
+    /// Answer Encryption Request with `verify_token`, encrypted the way the
+    /// client does.
+    fn respond(&mut self, verify_token: &[u8]) {
+        let public_key = RsaPublicKey::from_public_key_der(&self.public_key).unwrap();
+        let shared_secret = public_key
+            .encrypt(&mut OsRng, Pkcs1v15Encrypt, &SHARED_SECRET)
+            .unwrap();
+        let verify_token = public_key
+            .encrypt(&mut OsRng, Pkcs1v15Encrypt, verify_token)
+            .unwrap();
+        self.receive(
+            ServerboundKey::ID,
+            ServerboundKey {
+                shared_secret: &shared_secret,
+                verify_token: &verify_token,
+            },
+        );
+    }
+
+    /// Run `finish_authentication` until the session lookup is answered.
+    fn authenticate(&mut self) {
+        for _ in 0..500 {
+            self.world.run_system_once(finish_authentication).unwrap();
+            if self.world.get::<SessionLookup>(self.entity).is_none() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("the session server never answered");
+    }
+}
+
+#[test]
+fn joined_client_is_logged_in_with_its_session_profile() {
+    let (url, session) = session_server("200 OK", NOTCH);
+    let mut login = Login::start(url);
+    let verify_token = login.verify_token.clone();
+    login.respond(&verify_token);
+    assert_eq!(login.state(), LoginState::Authenticating);
+
+    login.authenticate();
+    assert_eq!(login.state(), LoginState::Accepted);
+    let request_line = session.join().unwrap();
+    assert!(request_line.contains("username=Notch"), "{request_line}");
+    let expected_hash = server_hash("", &SHARED_SECRET, &login.public_key);
+    assert!(
+        request_line.contains(&format!("serverId={expected_hash}")),
+        "{request_line}"
+    );
+
+    // Login Success is the first encrypted packet.
+    let mut decoder = login.sent();
+    decoder.enable_encryption(&SHARED_SECRET);
+    let frame = decoder.try_next_packet().unwrap().expect("Login Success");
+    let finished = frame.decode::<ClientboundLoginFinished>().unwrap();
+    assert_eq!(
+        finished.profile.id.to_string(),
+        "069a79f4-44e9-4726-a5be-fca90e38aaf5"
+    );
+    assert_eq!(finished.profile.username.0, "Notch");
+    assert_eq!(finished.profile.properties.len(), 1);
+    assert_eq!(finished.profile.properties[0].name, "textures");
+    assert_eq!(finished.profile.properties[0].signature, Some("c2ln"));
+}
+
+#[test]
+fn client_that_did_not_join_is_refused() {
+    let (url, session) = session_server("204 No Content", "");
+    let mut login = Login::start(url);
+    let verify_token = login.verify_token.clone();
+    login.respond(&verify_token);
+    login.authenticate();
+    session.join().unwrap();
+
+    assert!(login.is_closing());
+    let mut decoder = login.sent();
+    decoder.enable_encryption(&SHARED_SECRET);
+    let frame = decoder.try_next_packet().unwrap().expect("Disconnect");
+    let disconnect = frame.decode::<ClientboundLoginDisconnect>().unwrap();
+    assert!(
+        disconnect
+            .reason
+            .0
+            .contains("multiplayer.disconnect.unverified_username"),
+        "{}",
+        disconnect.reason.0
+    );
+}
+
+#[test]
+fn wrong_verify_token_is_refused_before_the_session_lookup() {
+    let mut login = Login::start("http://127.0.0.1:9/unused".to_string());
+    let mut verify_token = login.verify_token.clone();
+    verify_token[0] ^= 0xFF;
+    login.respond(&verify_token);
+
+    assert!(login.is_closing());
+    assert_eq!(login.state(), LoginState::Key);
+}
@@ -1,6 +1,6 @@
 //! CI-testable subset of the BRIDGE-09 E2E gate.
 //!
-//! Four in-process integration tests exercise the steady-state path without a
+//! In-process integration tests exercise the steady-state path without a
 //! real TCP socket:
 //!
 //! - `e2e_login_handshake_completes`: synthetic login → `PlayerIndex` entry + `HostAnchorRef`.
@@ -16,6 +16,9 @@
 //!   test that would have caught the original "Joining world" hang: it exercises a real
 //!   connection crossing into a sub-app and verifies the play-login blob reaches the
 //!   host-resident connection, not just the in-process bus.
+//! - `e2e_reconfiguration_rejoins_the_world`: a player that reconfigures mid-game leaves its
+//!   dimension and, after Finish Configuration, gets a fresh in-dim entity, a second
+//!   play-login and the start of its chunk stream.
 
 #[path = "common/mock_connection.rs"]
 mod mock_connection;
@@ -41,6 +44,7 @@ use mcrs_core::AppState;
 use mcrs_engine::world::sub_app::{DimDespawnQueue, DimSpawnQueue, DimSpawnRequest};
 use mcrs_minecraft::configuration::emit_initial_player_spawn;
 use mcrs_minecraft::login::{GameProfile, LoginPlugin, LoginState};
+use mcrs_minecraft::spawn_point::SpawnPoint;
 use mcrs_minecraft::world::aoi::TrackedBy;
 use mcrs_minecraft::world::bridge::{
     bridge_outbound, bridge_player_attach, dispatch_encode, partition_main_inbound,
@@ -59,6 +63,7 @@ use mcrs_protocol::uuid::Uuid;
 use mcrs_vanilla::biome::Biome;
 use mcrs_vanilla::block::Block;
 use mcrs_vanilla::enchantment::EnchantmentData;
+use mcrs_vanilla::item::Item;
 use smallvec::SmallVec;
 
 // ---------------------------------------------------------------------------
@@ -295,7 +300,9 @@ fn build_join_host_app() -> App {
     }
     app.insert_resource(StaticRegistry::<Block>::new());
     app.insert_resource(StaticRegistry::<EnchantmentData>::default());
+    app.insert_resource(StaticRegistry::<Item>::new());
     app.insert_resource(TagRegistry::<Block>::default());
+    app.insert_resource(TagRegistry::<Item>::default());
     app.insert_resource(RegistrySnapshot::<Biome>::default());
 
     app.init_resource::<PlayerIndex>();
@@ -314,6 +321,7 @@ fn build_join_host_app() -> App {
         (partition_main_inbound, bridge_player_attach, bridge_outbound, dispatch_encode),
     );
     app.add_plugins(LoginPlugin);
+    app.init_resource::<SpawnPoint>();
     app.add_systems(Update, emit_initial_player_spawn);
 
     app
@@ -439,6 +447,205 @@ fn e2e_join_releases_joining_world() {
     );
 }
 
+/// A player that reconfigures mid-game is taken out of its dimension, walks
+/// the configuration handshake again, and on Finish Configuration is placed
+/// back: a fresh in-dim entity, a second play-login and the start of its
+/// chunk stream reach the socket.
+#[test]
+fn e2e_reconfiguration_rejoins_the_world() {
+    use mcrs_engine::world::dimension::{DimensionId, DimensionTypeConfig};
+    use mcrs_minecraft::configuration::{
+        LoadedDimensionTypes, begin_reconfiguration, on_configuration_ack, on_configuration_enter,
+        on_game_configuration_ack, on_known_packs_response,
+    };
+    use mcrs_network::event::ReceivedPacketEvent;
+    use mcrs_network::{ConnectionState, InGameConnectionState};
+    use mcrs_protocol::game_event::GameEventKind;
+    use mcrs_protocol::packets::configuration::clientbound::ClientboundFinishConfiguration;
+    use mcrs_protocol::packets::configuration::serverbound::{
+        ServerboundFinishConfiguration, ServerboundSelectKnownPacks,
+    };
+    use mcrs_protocol::packets::game::clientbound::{
+        ClientboundGameEvent, ClientboundLogin, ClientboundSetChunkCacheCenter,
+        ClientboundStartConfiguration,
+    };
+    use mcrs_protocol::packets::game::serverbound::ServerboundConfigurationAcknowledged;
+    use mcrs_protocol::{Encode, Packet};
+    use mcrs_vanilla::entity::EntityType;
+    use std::time::Instant;
+
+    fn receive(app: &mut App, entity: Entity, id: i32, packet: impl Encode) {
+        let mut data = Vec::new();
+        packet.encode(&mut data).unwrap();
+        app.world_mut().trigger(ReceivedPacketEvent {
+            entity,
+            id,
+            data: data.into(),
+            timestamp: Instant::now(),
+        });
+        app.world_mut().flush();
+    }
+
+    let mut app = build_join_host_app();
+    app.init_resource::<LoadedDimensionTypes>();
+    app.init_resource::<TagRegistry<EnchantmentData>>();
+    app.init_resource::<TagRegistry<EntityType>>();
+    app.add_observer(on_game_configuration_ack);
+    app.add_observer(on_known_packs_response);
+    app.add_observer(on_configuration_ack);
+
+    let (raw, mut rx) = mock_connection::make_mock_raw_connection();
+    let connection_entity = app
+        .world_mut()
+        .spawn((
+            ServerSideConnection { raw: Box::new(raw) },
+            OutboundQueue::default(),
+            InboundRateBucket::new(),
+        ))
+        .id();
+    app.world_mut().entity_mut(connection_entity).insert((
+        GameProfile {
+            id: Uuid::new_v4(),
+            username: "e2e_reconfigure_test".into(),
+            properties: Vec::new(),
+        },
+        LoginState::Accepted,
+    ));
+    app.update();
+    let host_anchor = app
+        .world()
+        .get::<HostAnchorRef>(connection_entity)
+        .copied()
+        .expect("HostAnchorRef present after login")
+        .0;
+    if let Some(loc) = app
+        .world_mut()
+        .resource_mut::<PlayerIndex>()
+        .get_mut(&host_anchor)
+    {
+        loc.socket = connection_entity;
+    }
+
+    app.world_mut()
+        .resource_mut::<NextState<AppState>>()
+        .set(AppState::Playing);
+    app.update();
+    app.world_mut()
+        .resource_mut::<DimSpawnQueue>()
+        .0
+        .push(DimSpawnRequest {
+            dimension_id: DimensionId::new("test:overworld"),
+            type_config: DimensionTypeConfig::default(),
+            has_sky: true,
+        });
+    drain_dim_spawn_queue(&mut app);
+
+    // First join.
+    app.world_mut()
+        .entity_mut(connection_entity)
+        .insert((ConnectionState::Game, InGameConnectionState));
+    for _ in 0..3 {
+        app.update();
+    }
+    let location = app
+        .world()
+        .resource::<PlayerIndex>()
+        .get(&host_anchor)
+        .unwrap();
+    let (dim, first_in_dim) = (location.current_dim, location.in_dim_entity);
+    assert!(
+        first_in_dim.is_some(),
+        "the first join bound an in-dim entity"
+    );
+    let ids: Vec<i32> = mock_connection::decode_frames(&mut rx)
+        .iter()
+        .map(|frame| frame.id)
+        .collect();
+    assert!(
+        ids.contains(&ClientboundLogin::ID),
+        "first play-login: {ids:?}"
+    );
+
+    // Reconfigure: the player leaves its dimension.
+    app.world_mut()
+        .commands()
+        .entity(connection_entity)
+        .queue(begin_reconfiguration());
+    app.world_mut().flush();
+    app.update();
+    let location = app
+        .world()
+        .resource::<PlayerIndex>()
+        .get(&host_anchor)
+        .unwrap();
+    assert_eq!(location.current_dim, Entity::PLACEHOLDER);
+    assert_eq!(location.in_dim_entity, None);
+
+    receive(
+        &mut app,
+        connection_entity,
+        ServerboundConfigurationAcknowledged::ID,
+        ServerboundConfigurationAcknowledged,
+    );
+    run_system(app.world_mut(), on_configuration_enter);
+    receive(
+        &mut app,
+        connection_entity,
+        ServerboundSelectKnownPacks::ID,
+        ServerboundSelectKnownPacks {
+            known_packs: vec![],
+        },
+    );
+    receive(
+        &mut app,
+        connection_entity,
+        ServerboundFinishConfiguration::ID,
+        ServerboundFinishConfiguration,
+    );
+    assert_eq!(
+        app.world().get::<ConnectionState>(connection_entity),
+        Some(&ConnectionState::Game)
+    );
+
+    // Back in Game: placed again, as on the first join.
+    for _ in 0..3 {
+        app.update();
+    }
+    let location = app
+        .world()
+        .resource::<PlayerIndex>()
+        .get(&host_anchor)
+        .unwrap();
+    assert_eq!(location.current_dim, dim);
+    assert!(location.in_dim_entity.is_some(), "placed again");
+    assert_ne!(
+        location.in_dim_entity, first_in_dim,
+        "a fresh in-dim entity"
+    );
+
+    let frames = mock_connection::decode_frames(&mut rx);
+    let ids: Vec<i32> = frames.iter().map(|frame| frame.id).collect();
+    let position = |id: i32| ids.iter().position(|&other| other == id);
+    let start = position(ClientboundStartConfiguration::ID).expect("Start Configuration");
+    let finish = position(ClientboundFinishConfiguration::ID).expect("Finish Configuration");
+    let login = position(ClientboundLogin::ID).expect("second play-login");
+    let center = position(ClientboundSetChunkCacheCenter::ID).expect("chunk cache center");
+    assert!(
+        start < finish && finish < login && login < center,
+        "{ids:?}"
+    );
+    assert!(
+        frames[login..].iter().any(|frame| {
+            frame.id == ClientboundGameEvent::ID
+                && matches!(
+                    frame.decode::<ClientboundGameEvent>().unwrap().game_event,
+                    GameEventKind::LevelChunksLoadStart
+                )
+        }),
+        "the chunk stream starts after the second play-login"
+    );
+}
+
 // ---------------------------------------------------------------------------
 // Shared test utilities
 // ---------------------------------------------------------------------------
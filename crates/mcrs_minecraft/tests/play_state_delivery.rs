@@ -25,27 +25,41 @@ use mcrs_core::registry::static_registry::StaticRegistry;
 use mcrs_core::tag::TagRegistry;
 use mcrs_core::voxel_shape::VoxelShape;
 use mcrs_core::AppState;
+use mcrs_engine::geometry::BlockPos;
 use mcrs_engine::world::sub_app::{DimAppLabel, DimDespawnQueue, DimSpawnQueue, DimSpawnRequest};
+use mcrs_minecraft::biome::obfuscate_seed;
+use mcrs_minecraft::client_info::ServerViewConfig;
+use mcrs_minecraft::server_config::ServerConfig;
+use mcrs_minecraft::spawn_point::SpawnPoint;
 use mcrs_minecraft::world::bridge::dispatch_encode;
 use mcrs_minecraft::world::bridge_queue::OutboundQueue;
 use mcrs_minecraft::world::bus::{
     InboundPlayerDespawn, InboundPlayerPacket, InboundPlayerSpawn, OutboundPlayerAttached,
     OutboundPlayerDisconnect, OutboundPlayerPacket, OutboundPlayerTransfer, PacketPayload,
     PacketPriority, PacketTarget, PendingInboundLifecycle, PendingInboundPartition,
-    PlayerTransferSnapshot,
+    PlayerTransferSnapshot, SpawnReason,
 };
 use mcrs_minecraft::world::entity::player::HostAnchor;
 use mcrs_minecraft::world::player_index::{PlayerIndex, PlayerLocation};
 use mcrs_minecraft::world::sub_app_builder::{drain_dim_spawn_queue, DimSubAppHandle};
 use mcrs_minecraft_lighting::table::BlockStateLightTable;
+use mcrs_minecraft_worldgen::bevy::WorldGenConfig;
 use mcrs_network::metrics::{BRIDGE_ENCODE_UNHANDLED_TOTAL, TELEMETRY_TEST_LOCK};
 use mcrs_network::ServerSideConnection;
 use mcrs_protocol::chunk::LightData;
+use mcrs_protocol::entity::EntityMetadata;
+use mcrs_protocol::entity::attribute::{AttributeModifier, AttributeOperation, AttributeSnapshot};
+use mcrs_protocol::packets::game::clientbound::{
+    ClientboundLogin, ClientboundPlayerInfoUpdate, ClientboundRespawn,
+    ClientboundSetDefaultSpawnPosition, ClientboundSetEntityMotion,
+    ClientboundSetSimulationDistance, ClientboundUpdateAttributes,
+};
 use mcrs_protocol::uuid::Uuid;
-use mcrs_protocol::GameMode;
+use mcrs_protocol::{GameMode, PacketDecoder, VarInt, ident};
 use mcrs_vanilla::biome::Biome;
 use mcrs_vanilla::block::Block;
 use mcrs_vanilla::enchantment::EnchantmentData;
+use mcrs_vanilla::item::Item;
 use smallvec::SmallVec;
 use tokio::sync::mpsc;
 
@@ -115,7 +129,9 @@ fn build_host_app() -> App {
     app.insert_resource(make_stub_block_light_table());
     app.insert_resource(StaticRegistry::<Block>::new());
     app.insert_resource(StaticRegistry::<EnchantmentData>::default());
+    app.insert_resource(StaticRegistry::<Item>::new());
     app.insert_resource(TagRegistry::<Block>::default());
+    app.insert_resource(TagRegistry::<Item>::default());
     app.insert_resource(RegistrySnapshot::<Biome>::default());
 
     app.init_resource::<PlayerIndex>();
@@ -133,6 +149,10 @@ fn build_host_app() -> App {
 }
 
 fn spawn_subapp(app: &mut App) -> Entity {
+    spawn_subapp_for(app, "test:overworld")
+}
+
+fn spawn_subapp_for(app: &mut App, dimension: &str) -> Entity {
     app.world_mut()
         .resource_mut::<NextState<AppState>>()
         .set(AppState::Playing);
@@ -141,7 +161,7 @@ fn spawn_subapp(app: &mut App) -> Entity {
         .resource_mut::<DimSpawnQueue>()
         .0
         .push(DimSpawnRequest {
-            dimension_id: mcrs_engine::world::dimension::DimensionId::new("test:overworld"),
+            dimension_id: mcrs_engine::world::dimension::DimensionId::new(dimension),
             type_config: mcrs_engine::world::dimension::DimensionTypeConfig::default(),
             has_sky: true,
         });
@@ -181,6 +201,13 @@ fn player_login_encodes() {
             reduced_debug_info: false,
             show_death_screen: false,
             do_limited_crafting: false,
+            previous_game_mode: None,
+            hashed_seed: 0,
+            is_debug: false,
+            is_flat: false,
+            last_death_location: None,
+            portal_cooldown: 0,
+            sea_level: 63,
             enforces_secure_chat: false,
         },
     );
@@ -194,6 +221,118 @@ fn player_login_encodes() {
     assert!(!blob.is_empty(), "PlayerLogin must produce a non-empty blob");
 }
 
+/// The encoded Login carries the dimension list and both game modes the
+/// client reads, and every spawn-info field the payload was given.
+#[test]
+fn player_login_encodes_dimensions_and_game_modes() {
+    let _lock = TELEMETRY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (mut world, entity, mut rx) = build_dispatch_world();
+
+    push_critical(
+        &mut world,
+        entity,
+        PacketPayload::PlayerLogin {
+            player_id: 42,
+            hardcore: false,
+            game_mode: GameMode::Creative,
+            dimension: "minecraft:the_nether".to_string(),
+            dimension_type_id: 2,
+            dimensions: vec![
+                "minecraft:overworld".to_string(),
+                "minecraft:the_end".to_string(),
+                "minecraft:the_nether".to_string(),
+            ],
+            max_players: 20,
+            chunk_radius: 12,
+            simulation_distance: 10,
+            reduced_debug_info: false,
+            show_death_screen: true,
+            do_limited_crafting: false,
+            previous_game_mode: Some(GameMode::Spectator),
+            hashed_seed: -5,
+            is_debug: false,
+            is_flat: true,
+            last_death_location: Some((
+                "minecraft:overworld".to_string(),
+                BlockPos::new(10, -20, 30),
+            )),
+            portal_cooldown: 40,
+            sea_level: 32,
+            enforces_secure_chat: true,
+        },
+    );
+    run_dispatch(&mut world);
+
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(rx.try_recv().expect("blob sent to socket").into());
+    let frame = decoder.try_next_packet().unwrap().expect("one frame");
+    let login = frame.decode::<ClientboundLogin>().expect("Login frame");
+    let dimensions: Vec<&str> = login.dimensions.iter().map(|d| d.as_str()).collect();
+    assert_eq!(
+        dimensions,
+        [
+            "minecraft:overworld",
+            "minecraft:the_end",
+            "minecraft:the_nether"
+        ]
+    );
+    assert_eq!(login.player_id, 42);
+    assert_eq!(login.max_players.0, 20);
+    assert_eq!(login.chunk_radius.0, 12);
+    assert_eq!(login.simulation_distance.0, 10);
+    assert!(login.show_death_screen);
+    assert!(login.enforces_secure_chat);
+
+    let info = login.player_spawn_info;
+    assert_eq!(info.dimension_type_id.0, 2);
+    assert_eq!(info.dimension.as_str(), "minecraft:the_nether");
+    assert_eq!(info.seed, -5i64 as u64);
+    assert_eq!(info.game_mode, GameMode::Creative);
+    assert_eq!(info.prev_game_mode.0, Some(GameMode::Spectator));
+    assert!(!info.is_debug);
+    assert!(info.is_flat);
+    let death = info.last_depth_location.expect("death location");
+    assert_eq!(death.dimension_name.as_str(), "minecraft:overworld");
+    assert_eq!(death.position, BlockPos::new(10, -20, 30));
+    assert_eq!(info.portal_cooldown.0, 40);
+    assert_eq!(info.sea_level.0, 32);
+}
+
+/// `PacketPayload::SetDefaultSpawnPosition` encodes the spawn as a global
+/// position with its rotation.
+#[test]
+fn set_default_spawn_position_encodes() {
+    let _lock = TELEMETRY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (mut world, entity, mut rx) = build_dispatch_world();
+
+    let before = BRIDGE_ENCODE_UNHANDLED_TOTAL.load(Ordering::Relaxed);
+
+    push_critical(
+        &mut world,
+        entity,
+        PacketPayload::SetDefaultSpawnPosition {
+            dimension: "minecraft:overworld".to_string(),
+            position: BlockPos::new(-8, 70, 16),
+            yaw: 90.0,
+            pitch: -10.0,
+        },
+    );
+    run_dispatch(&mut world);
+
+    let after = BRIDGE_ENCODE_UNHANDLED_TOTAL.load(Ordering::Relaxed);
+    assert_eq!(after - before, 0, "SetDefaultSpawnPosition must not increment unhandled");
+
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(rx.try_recv().expect("blob sent to socket").into());
+    let frame = decoder.try_next_packet().unwrap().expect("one frame");
+    let spawn = frame
+        .decode::<ClientboundSetDefaultSpawnPosition>()
+        .expect("Set Default Spawn Position frame");
+    assert_eq!(spawn.pos.dimension_name.as_str(), "minecraft:overworld");
+    assert_eq!(spawn.pos.position, BlockPos::new(-8, 70, 16));
+    assert_eq!((spawn.yaw, spawn.pitch), (90.0, -10.0));
+}
+
 /// `PacketPayload::LevelChunksLoadStart` encodes to a non-empty blob
 /// (the GameEvent packet).
 #[test]
@@ -283,23 +422,58 @@ fn cache_radius_encodes() {
     assert!(!blob.is_empty(), "SetChunkCacheRadius must produce a non-empty blob");
 }
 
-/// `PacketPayload::PlayerInfoUpdate` encodes to a non-empty blob.
+/// `PacketPayload::SetSimulationDistance` encodes to a Set Simulation
+/// Distance packet carrying the distance.
+#[test]
+fn simulation_distance_encodes() {
+    let _lock = TELEMETRY_TEST_LOCK
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let (mut world, entity, mut rx) = build_dispatch_world();
+
+    let before = BRIDGE_ENCODE_UNHANDLED_TOTAL.load(Ordering::Relaxed);
+
+    push_critical(
+        &mut world,
+        entity,
+        PacketPayload::SetSimulationDistance {
+            simulation_distance: 5,
+        },
+    );
+    run_dispatch(&mut world);
+
+    let after = BRIDGE_ENCODE_UNHANDLED_TOTAL.load(Ordering::Relaxed);
+    assert_eq!(
+        after - before,
+        0,
+        "SetSimulationDistance must not increment unhandled"
+    );
+
+    let blob = rx.try_recv().expect("blob sent to socket");
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(blob.into());
+    let frame = decoder.try_next_packet().unwrap().expect("one packet");
+    let packet = frame.decode::<ClientboundSetSimulationDistance>().unwrap();
+    assert_eq!(packet.simulation_distance, VarInt(5));
+}
+
+/// `PacketPayload::PlayerInfoUpdate` encodes to a game-mode-only update, so a
+/// dimension never re-adds or re-lists a player the host `PlayerList` owns.
 #[test]
-fn player_info_update_encodes() {
+fn player_info_update_only_updates_the_game_mode() {
     let _lock = TELEMETRY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
     let (mut world, entity, mut rx) = build_dispatch_world();
 
     let before = BRIDGE_ENCODE_UNHANDLED_TOTAL.load(Ordering::Relaxed);
 
+    let uuid = Uuid::new_v4();
     push_critical(
         &mut world,
         entity,
         PacketPayload::PlayerInfoUpdate {
             entries: vec![mcrs_minecraft::world::bus::PlayerInfoEntry {
-                player_uuid: Uuid::nil(),
-                username: "test".to_string(),
+                player_uuid: uuid,
                 game_mode: GameMode::Creative,
-                listed: true,
             }],
         },
     );
@@ -308,8 +482,136 @@ fn player_info_update_encodes() {
     let after = BRIDGE_ENCODE_UNHANDLED_TOTAL.load(Ordering::Relaxed);
     assert_eq!(after - before, 0, "PlayerInfoUpdate must not increment unhandled");
 
-    let blob = rx.try_recv().expect("blob sent to socket");
-    assert!(!blob.is_empty(), "PlayerInfoUpdate must produce a non-empty blob");
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(rx.try_recv().expect("blob sent to socket").into());
+    let frame = decoder
+        .try_next_packet()
+        .unwrap()
+        .expect("PlayerInfoUpdate");
+    let update = frame.decode::<ClientboundPlayerInfoUpdate>().unwrap();
+    assert!(update.actions.update_game_mode());
+    assert!(!update.actions.add_player());
+    assert!(!update.actions.update_listed());
+    assert_eq!(update.entries.len(), 1);
+    assert_eq!(update.entries[0].player_uuid, uuid);
+    assert_eq!(update.entries[0].game_mode, GameMode::Creative);
+}
+
+/// `PacketPayload::Respawn` encodes to a Respawn packet naming the
+/// destination dimension, the player's game mode and the level's seed hash,
+/// sea level and portal cooldown.
+#[test]
+fn respawn_encodes_dimension_and_game_mode() {
+    let _lock = TELEMETRY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (mut world, entity, mut rx) = build_dispatch_world();
+
+    let before = BRIDGE_ENCODE_UNHANDLED_TOTAL.load(Ordering::Relaxed);
+
+    push_critical(
+        &mut world,
+        entity,
+        PacketPayload::Respawn {
+            dimension: "minecraft:the_nether".to_string(),
+            dimension_type_id: 1,
+            game_mode: GameMode::Survival,
+            hashed_seed: -7,
+            sea_level: 32,
+            portal_cooldown: 300,
+            data_to_keep: ClientboundRespawn::KEEP_ALL_DATA,
+        },
+    );
+    run_dispatch(&mut world);
+
+    let after = BRIDGE_ENCODE_UNHANDLED_TOTAL.load(Ordering::Relaxed);
+    assert_eq!(after - before, 0, "Respawn must not increment unhandled");
+
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(rx.try_recv().expect("blob sent to socket").into());
+    let frame = decoder.try_next_packet().unwrap().expect("one frame");
+    let respawn = frame.decode::<ClientboundRespawn>().expect("Respawn frame");
+    let info = &respawn.player_spawn_info;
+    assert_eq!(info.dimension.as_str(), "minecraft:the_nether");
+    assert_eq!(info.dimension_type_id.0, 1);
+    assert_eq!(info.game_mode, GameMode::Survival);
+    assert_eq!(info.seed as i64, -7);
+    assert_eq!(info.sea_level.0, 32);
+    assert_eq!(info.portal_cooldown.0, 300);
+    assert_eq!(respawn.data_to_keep, ClientboundRespawn::KEEP_ALL_DATA);
+}
+
+/// `PacketPayload::UpdateAttributes` encodes to an Update Attributes packet
+/// carrying the base value and modifiers as given.
+#[test]
+fn update_attributes_encodes_modifiers() {
+    let _lock = TELEMETRY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (mut world, entity, mut rx) = build_dispatch_world();
+
+    let before = BRIDGE_ENCODE_UNHANDLED_TOTAL.load(Ordering::Relaxed);
+
+    let attributes = vec![AttributeSnapshot {
+        attribute: VarInt(25),
+        base: 0.1,
+        modifiers: vec![AttributeModifier {
+            id: ident!("sprinting").into(),
+            amount: 0.3,
+            operation: AttributeOperation::AddMultipliedTotal,
+        }],
+    }];
+    push_critical(
+        &mut world,
+        entity,
+        PacketPayload::UpdateAttributes {
+            entity_id: 7,
+            attributes: attributes.clone(),
+        },
+    );
+    run_dispatch(&mut world);
+
+    let after = BRIDGE_ENCODE_UNHANDLED_TOTAL.load(Ordering::Relaxed);
+    assert_eq!(after - before, 0, "UpdateAttributes must not increment unhandled");
+
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(rx.try_recv().expect("blob sent to socket").into());
+    let frame = decoder.try_next_packet().unwrap().expect("one frame");
+    let update = frame
+        .decode::<ClientboundUpdateAttributes>()
+        .expect("Update Attributes frame");
+    assert_eq!(update.entity_id.0, 7);
+    assert_eq!(update.attributes, attributes);
+}
+
+/// `PacketPayload::EntityVelocity` encodes to a Set Entity Velocity packet
+/// carrying the velocity in blocks per tick.
+#[test]
+fn entity_velocity_encodes() {
+    let _lock = TELEMETRY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (mut world, entity, mut rx) = build_dispatch_world();
+
+    let before = BRIDGE_ENCODE_UNHANDLED_TOTAL.load(Ordering::Relaxed);
+
+    push_critical(
+        &mut world,
+        entity,
+        PacketPayload::EntityVelocity {
+            entity_id: 7,
+            velocity: DVec3::new(0.0, 0.2, 0.0),
+        },
+    );
+    run_dispatch(&mut world);
+
+    let after = BRIDGE_ENCODE_UNHANDLED_TOTAL.load(Ordering::Relaxed);
+    assert_eq!(after - before, 0, "EntityVelocity must not increment unhandled");
+
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(rx.try_recv().expect("blob sent to socket").into());
+    let frame = decoder.try_next_packet().unwrap().expect("one frame");
+    let motion = frame
+        .decode::<ClientboundSetEntityMotion>()
+        .expect("Set Entity Velocity frame");
+    assert_eq!(motion.entity_id.0, 7);
+    assert_eq!(motion.velocity.0.x, 0.0);
+    assert!((motion.velocity.0.y - 0.2).abs() < 1e-4);
+    assert_eq!(motion.velocity.0.z, 0.0);
 }
 
 // ---------------------------------------------------------------------------
@@ -352,7 +654,9 @@ fn play_login_emitted_on_spawn() {
                 username: "login_test".into(),
                 position: DVec3::new(0.0, 64.0, 0.0),
                 rotation: bevy_math::Vec2::ZERO,
+                game_mode: GameMode::Survival,
             },
+            reason: SpawnReason::Join,
         });
 
     // Tick 1: extract shuttles spawn into sub-app; sub-app consumer spawns
@@ -389,6 +693,83 @@ fn play_login_emitted_on_spawn() {
     );
 }
 
+/// The hashed seed is vanilla's `BiomeManager.obfuscateSeed`: SHA-256 of the
+/// little-endian seed, first eight bytes read back little-endian.
+#[test]
+fn hashed_seed_is_the_sha256_of_the_seed() {
+    assert_eq!(obfuscate_seed(0), 8794265229978523055);
+    assert_eq!(obfuscate_seed(12345), 293737985876514017);
+    assert_eq!(
+        obfuscate_seed(-4172144997902289642i64 as u64),
+        2159143436479834350
+    );
+}
+
+/// The Login carries the hashed world seed and the sea level of the
+/// dimension the player joins, not the overworld's.
+#[test]
+fn play_login_carries_hashed_seed_and_dimension_sea_level() {
+    let mut app = build_host_app();
+    let dim_label = spawn_subapp_for(&mut app, "minecraft:the_nether");
+    let seed = app
+        .sub_app(DimAppLabel(dim_label))
+        .world()
+        .resource::<WorldGenConfig>()
+        .seed;
+
+    let host_anchor = app.world_mut().spawn_empty().id();
+    app.world_mut().resource_mut::<PlayerIndex>().insert(
+        host_anchor,
+        PlayerLocation {
+            socket: Entity::PLACEHOLDER,
+            current_dim: dim_label,
+            previous_dim: None,
+            in_dim_entity: None,
+            inbound_pending: SmallVec::new(),
+        },
+    );
+    app.world_mut()
+        .resource_mut::<PendingInboundLifecycle>()
+        .per_dim
+        .entry(dim_label)
+        .or_default()
+        .spawns
+        .push(InboundPlayerSpawn {
+            host_anchor,
+            snapshot: PlayerTransferSnapshot {
+                uuid: Uuid::new_v4(),
+                username: "seed_test".into(),
+                position: DVec3::new(0.0, 64.0, 0.0),
+                rotation: bevy_math::Vec2::ZERO,
+                game_mode: GameMode::Survival,
+            },
+            reason: SpawnReason::Join,
+        });
+
+    app.update();
+    app.update();
+    let packets: Vec<OutboundPlayerPacket> = app
+        .world_mut()
+        .resource_mut::<Messages<OutboundPlayerPacket>>()
+        .drain()
+        .collect();
+    let Some(PacketPayload::PlayerLogin {
+        dimension,
+        hashed_seed,
+        sea_level,
+        ..
+    }) = packets
+        .iter()
+        .map(|p| &p.data)
+        .find(|data| matches!(data, PacketPayload::PlayerLogin { .. }))
+    else {
+        panic!("PlayerLogin packet must be present");
+    };
+    assert_eq!(dimension, "minecraft:the_nether");
+    assert_eq!(*hashed_seed, obfuscate_seed(seed));
+    assert_eq!(*sea_level, 32);
+}
+
 // ---------------------------------------------------------------------------
 // play_login_targets_host_anchor (Task 1)
 // ---------------------------------------------------------------------------
@@ -425,7 +806,9 @@ fn play_login_targets_host_anchor() {
                 username: "target_test".into(),
                 position: DVec3::new(0.0, 64.0, 0.0),
                 rotation: bevy_math::Vec2::ZERO,
+                game_mode: GameMode::Survival,
             },
+            reason: SpawnReason::Join,
         });
 
     // Tick 1: spawn consumed; emit_play_login fires; extract drains to host.
@@ -465,6 +848,114 @@ fn play_login_targets_host_anchor() {
     }
 }
 
+// ---------------------------------------------------------------------------
+// join_sends_host_spawn_point
+// ---------------------------------------------------------------------------
+
+/// The spawn point, player limit and distances set on the host reach the
+/// play login: the Login carries `ServerConfig::max_players` and is followed
+/// by Set Default Spawn Position for the host's `SpawnPoint` and by the
+/// `ServerViewConfig` distances.
+#[test]
+fn join_sends_host_spawn_point() {
+    let mut app = build_host_app();
+    app.insert_resource(ServerConfig {
+        max_players: 7,
+        ..Default::default()
+    });
+    app.insert_resource(ServerViewConfig {
+        view_distance: 9,
+        simulation_distance: 5,
+    });
+    let spawn_point = SpawnPoint {
+        dimension: ident!("overworld").into(),
+        position: BlockPos::new(100, 72, -50),
+        yaw: 180.0,
+        pitch: 0.0,
+    };
+    app.insert_resource(spawn_point.clone());
+    let dim_label = spawn_subapp(&mut app);
+
+    let host_anchor = app.world_mut().spawn_empty().id();
+    app.world_mut().resource_mut::<PlayerIndex>().insert(
+        host_anchor,
+        PlayerLocation {
+            socket: Entity::PLACEHOLDER,
+            current_dim: dim_label,
+            previous_dim: None,
+            in_dim_entity: None,
+            inbound_pending: SmallVec::new(),
+        },
+    );
+    app.world_mut()
+        .resource_mut::<PendingInboundLifecycle>()
+        .per_dim
+        .entry(dim_label)
+        .or_default()
+        .spawns
+        .push(InboundPlayerSpawn {
+            host_anchor,
+            snapshot: PlayerTransferSnapshot {
+                uuid: Uuid::new_v4(),
+                username: "spawn_test".into(),
+                position: spawn_point.player_position(),
+                rotation: spawn_point.rotation(),
+                game_mode: GameMode::Survival,
+            },
+            reason: SpawnReason::Join,
+        });
+
+    app.update();
+    app.update();
+    let packets: Vec<OutboundPlayerPacket> = app
+        .world_mut()
+        .resource_mut::<Messages<OutboundPlayerPacket>>()
+        .drain()
+        .collect();
+
+    let login = packets
+        .iter()
+        .position(|p| matches!(&p.data, PacketPayload::PlayerLogin { max_players: 7, .. }))
+        .expect("PlayerLogin with the host's player limit");
+    let spawn = packets
+        .iter()
+        .position(|p| {
+            matches!(
+                &p.data,
+                PacketPayload::SetDefaultSpawnPosition {
+                    dimension,
+                    position,
+                    yaw,
+                    ..
+                } if dimension == "minecraft:overworld"
+                    && *position == spawn_point.position
+                    && *yaw == 180.0
+            )
+        })
+        .expect("SetDefaultSpawnPosition for the host's spawn point");
+    assert!(login < spawn, "the spawn position must follow the Login");
+    assert!(matches!(
+        packets[spawn].target,
+        PacketTarget::SinglePlayer(e) if e == host_anchor
+    ));
+    let radius = packets
+        .iter()
+        .position(|p| matches!(&p.data, PacketPayload::SetChunkCacheRadius { radius: 9 }))
+        .expect("SetChunkCacheRadius for the host's view distance");
+    let simulation = packets
+        .iter()
+        .position(|p| {
+            matches!(
+                &p.data,
+                PacketPayload::SetSimulationDistance {
+                    simulation_distance: 5
+                }
+            )
+        })
+        .expect("SetSimulationDistance for the host's simulation distance");
+    assert!(login < radius && login < simulation);
+}
+
 // ---------------------------------------------------------------------------
 // in_dim_entity_carries_host_anchor (Task 1)
 // ---------------------------------------------------------------------------
@@ -492,7 +983,9 @@ fn in_dim_entity_carries_host_anchor() {
                 username: "anchor_test".into(),
                 position: DVec3::new(0.0, 64.0, 0.0),
                 rotation: bevy_math::Vec2::ZERO,
+                game_mode: GameMode::Survival,
             },
+            reason: SpawnReason::Join,
         });
 
     // One tick: extract shuttles spawn; consumer spawns entity with HostAnchor.
@@ -603,8 +1096,10 @@ fn view_enter_leave_route_via_bus() {
             uuid: Uuid::nil(),
             kind: 128,
             position: DVec3::ZERO,
+            velocity: DVec3::ZERO,
             yaw: 0.0,
             pitch: 0.0,
+            metadata: EntityMetadata::new(),
         },
     );
     push_critical(
@@ -647,3 +1142,119 @@ fn on_view_update_routes_cache_center() {
     let blob = rx.try_recv().expect("cache center/radius blob sent");
     assert!(!blob.is_empty(), "cache center/radius must produce a non-empty blob");
 }
+
+// ---------------------------------------------------------------------------
+// dimension_change_emits_respawn
+// ---------------------------------------------------------------------------
+
+/// Spawn a player into a fresh sub-app for `reason`, as `game_mode`, and
+/// return what the dimension sent with the player's host anchor.
+fn spawn_already_playing(
+    reason: SpawnReason,
+    game_mode: GameMode,
+) -> (Vec<OutboundPlayerPacket>, Entity) {
+    let mut app = build_host_app();
+    let dim_label = spawn_subapp(&mut app);
+
+    let host_anchor = app.world_mut().spawn_empty().id();
+    app.world_mut()
+        .resource_mut::<PendingInboundLifecycle>()
+        .per_dim
+        .entry(dim_label)
+        .or_default()
+        .spawns
+        .push(InboundPlayerSpawn {
+            host_anchor,
+            snapshot: PlayerTransferSnapshot {
+                uuid: Uuid::new_v4(),
+                username: "respawn_test".into(),
+                position: DVec3::new(0.0, 100.0, 0.0),
+                rotation: bevy_math::Vec2::ZERO,
+                game_mode,
+            },
+            reason,
+        });
+
+    app.update();
+    let mut packets: Vec<OutboundPlayerPacket> = app
+        .world_mut()
+        .resource_mut::<Messages<OutboundPlayerPacket>>()
+        .drain()
+        .collect();
+    if packets.is_empty() {
+        app.update();
+        packets = app
+            .world_mut()
+            .resource_mut::<Messages<OutboundPlayerPacket>>()
+            .drain()
+            .collect();
+    }
+    (packets, host_anchor)
+}
+
+/// A player arriving from another dimension already has a level on the
+/// client, so it is sent a Respawn into the sub-app's dimension followed by
+/// the position sync, never a second Login.
+#[test]
+fn dimension_change_emits_respawn() {
+    let (packets, host_anchor) =
+        spawn_already_playing(SpawnReason::DimensionChange, GameMode::Creative);
+
+    assert!(
+        !packets
+            .iter()
+            .any(|p| matches!(&p.data, PacketPayload::PlayerLogin { .. })),
+        "a dimension change must not re-send Login"
+    );
+    let respawn_at = packets
+        .iter()
+        .position(|p| matches!(&p.data, PacketPayload::Respawn { .. }))
+        .expect("Respawn packet must be present");
+    let PacketPayload::Respawn {
+        dimension,
+        game_mode,
+        sea_level,
+        data_to_keep,
+        ..
+    } = &packets[respawn_at].data
+    else {
+        unreachable!();
+    };
+    assert_eq!(dimension, "test:overworld");
+    assert_eq!(
+        *game_mode,
+        GameMode::Creative,
+        "the game mode travels along"
+    );
+    assert_eq!(*sea_level, 63);
+    assert_eq!(*data_to_keep, ClientboundRespawn::KEEP_ALL_DATA);
+    assert!(matches!(
+        packets[respawn_at].target,
+        PacketTarget::SinglePlayer(e) if e == host_anchor
+    ));
+    let position_at = packets
+        .iter()
+        .position(|p| matches!(&p.data, PacketPayload::PlayerPosition { .. }))
+        .expect("position sync must follow the Respawn");
+    assert!(respawn_at < position_at);
+}
+
+/// A death respawn is also a Respawn, but the client keeps none of the
+/// player's data.
+#[test]
+fn death_respawn_keeps_no_data() {
+    let (packets, _) = spawn_already_playing(SpawnReason::Death, GameMode::Adventure);
+
+    let respawn = packets
+        .iter()
+        .find_map(|p| match &p.data {
+            PacketPayload::Respawn {
+                game_mode,
+                data_to_keep,
+                ..
+            } => Some((*game_mode, *data_to_keep)),
+            _ => None,
+        })
+        .expect("Respawn packet must be present");
+    assert_eq!(respawn, (GameMode::Adventure, 0));
+}
@@ -0,0 +1,161 @@
+//! Player Abilities sync: a player entering a dimension is sent its abilities
+//! without a game mode event, and a game mode switch updates the player list
+//! and sends the player the event followed by the abilities of the new mode.
+//! Everything goes out on the bus, addressed to the player's host anchor.
+
+use std::time::Instant;
+
+use bevy_app::{App, FixedUpdate};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::Messages;
+use mcrs_engine::entity::player::Player;
+use mcrs_minecraft::login::GameProfile;
+use mcrs_minecraft::world::bus::{OutboundPlayerPacket, PacketPayload, PacketTarget};
+use mcrs_minecraft::world::entity::player::HostAnchor;
+use mcrs_minecraft::world::entity::player::ability::{
+    PlayerAbilitiesBundle, PlayerGameMode, PlayerOpLevel,
+};
+use mcrs_minecraft::world::entity::player::game_mode::GameModePlugin;
+use mcrs_network::event::ReceivedPacketEvent;
+use mcrs_protocol::packets::game::serverbound::ServerboundChangeGameMode;
+use mcrs_protocol::uuid::Uuid;
+use mcrs_protocol::{Encode, GameEventKind, GameMode, Packet};
+
+struct Harness {
+    app: App,
+    player: Entity,
+    host_anchor: Entity,
+    uuid: Uuid,
+}
+
+impl Harness {
+    fn new(game_mode: GameMode, op_level: u8) -> Self {
+        let mut app = App::new();
+        app.add_message::<OutboundPlayerPacket>();
+        app.add_plugins(GameModePlugin);
+        let host_anchor = app.world_mut().spawn_empty().id();
+        let uuid = Uuid::new_v4();
+        let player = app
+            .world_mut()
+            .spawn((
+                Player,
+                PlayerGameMode(game_mode),
+                PlayerOpLevel(op_level),
+                PlayerAbilitiesBundle::default(),
+                HostAnchor(host_anchor),
+                GameProfile {
+                    id: uuid,
+                    username: "Steve".to_string(),
+                    properties: Vec::new(),
+                },
+            ))
+            .id();
+        Self {
+            app,
+            player,
+            host_anchor,
+            uuid,
+        }
+    }
+
+    fn tick(&mut self) {
+        self.app.world_mut().run_schedule(FixedUpdate);
+    }
+
+    fn drain(&mut self) -> Vec<OutboundPlayerPacket> {
+        self.app
+            .world_mut()
+            .resource_mut::<Messages<OutboundPlayerPacket>>()
+            .drain()
+            .collect()
+    }
+
+    fn request_game_mode(&mut self, mode: GameMode) {
+        let mut data = Vec::new();
+        ServerboundChangeGameMode { mode }
+            .encode(&mut data)
+            .unwrap();
+        self.app.world_mut().trigger(ReceivedPacketEvent {
+            entity: self.player,
+            id: ServerboundChangeGameMode::ID,
+            data: data.into(),
+            timestamp: Instant::now(),
+        });
+        self.app.world_mut().flush();
+    }
+
+    fn is_to_player(&self, packet: &OutboundPlayerPacket) -> bool {
+        matches!(packet.target, PacketTarget::SinglePlayer(target) if target == self.host_anchor)
+    }
+}
+
+#[test]
+fn entering_survival_player_gets_abilities_without_game_event() {
+    let mut harness = Harness::new(GameMode::Survival, 0);
+    harness.tick();
+
+    let packets = harness.drain();
+    assert_eq!(packets.len(), 1);
+    assert!(harness.is_to_player(&packets[0]));
+    let PacketPayload::PlayerAbilities(abilities) = &packets[0].data else {
+        panic!("expected Player Abilities, got {:?}", packets[0].data);
+    };
+    assert_eq!(abilities.flags.into_bits(), 0);
+    assert_eq!(abilities.flying_speed, 0.05);
+    assert_eq!(abilities.walking_speed, 0.1);
+
+    harness.tick();
+    assert!(harness.drain().is_empty());
+}
+
+#[test]
+fn switching_to_creative_allows_flight() {
+    let mut harness = Harness::new(GameMode::Survival, 2);
+    harness.tick();
+    harness.drain();
+
+    harness.request_game_mode(GameMode::Creative);
+    harness.tick();
+
+    let packets = harness.drain();
+    assert_eq!(packets.len(), 3, "{packets:?}");
+    assert!(matches!(packets[0].target, PacketTarget::AllPlayers));
+    let PacketPayload::PlayerInfoUpdate { entries } = &packets[0].data else {
+        panic!("expected Player Info Update, got {:?}", packets[0].data);
+    };
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].player_uuid, harness.uuid);
+    assert_eq!(entries[0].game_mode, GameMode::Creative);
+
+    assert!(harness.is_to_player(&packets[1]));
+    let PacketPayload::GameEvent(event) = &packets[1].data else {
+        panic!("expected Game Event, got {:?}", packets[1].data);
+    };
+    assert!(matches!(
+        event.game_event,
+        GameEventKind::ChangeGameMode(GameMode::Creative)
+    ));
+
+    assert!(harness.is_to_player(&packets[2]));
+    let PacketPayload::PlayerAbilities(abilities) = &packets[2].data else {
+        panic!("expected Player Abilities, got {:?}", packets[2].data);
+    };
+    assert!(abilities.flags.may_fly());
+    assert!(abilities.flags.invulnerable());
+    assert!(abilities.flags.instabuild());
+    assert!(!abilities.flags.flying());
+}
+
+#[test]
+fn switch_without_permission_is_ignored() {
+    let mut harness = Harness::new(GameMode::Survival, 0);
+    harness.tick();
+    harness.drain();
+
+    harness.request_game_mode(GameMode::Creative);
+    harness.tick();
+
+    assert!(harness.drain().is_empty());
+    let game_mode = harness.app.world().get::<PlayerGameMode>(harness.player);
+    assert_eq!(game_mode.unwrap().0, GameMode::Survival);
+}
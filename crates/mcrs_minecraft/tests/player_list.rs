@@ -0,0 +1,158 @@
+//! Tab list sync: joining players get the whole roster and everyone else one
+//! add for them, ping changes from the same tick share one update, and a
+//! closed connection is removed from everyone's list.
+
+#[path = "common/mock_connection.rs"]
+mod mock_connection;
+
+use std::time::Duration;
+
+use bevy_ecs::entity::Entity;
+use bevy_ecs::schedule::{IntoScheduleConfigs, Schedule};
+use bevy_ecs::world::World;
+use bytes::Bytes;
+use mcrs_minecraft::keep_alive::Latency;
+use mcrs_minecraft::login::GameProfile;
+use mcrs_minecraft::player_list::{PlayerList, broadcast_player_list, track_player_list};
+use mcrs_network::{InGameConnectionState, ServerSideConnection};
+use mcrs_protocol::decode::PacketFrame;
+use mcrs_protocol::packets::game::clientbound::{
+    ClientboundPlayerInfoRemove, ClientboundPlayerInfoUpdate,
+};
+use mcrs_protocol::uuid::Uuid;
+use tokio::sync::mpsc;
+
+struct Harness {
+    world: World,
+    schedule: Schedule,
+}
+
+struct Client {
+    entity: Entity,
+    uuid: Uuid,
+    outgoing_rx: mpsc::Receiver<Bytes>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let mut world = World::new();
+        world.init_resource::<PlayerList>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems((track_player_list, broadcast_player_list).chain());
+        Self { world, schedule }
+    }
+
+    /// A connection that entered Game with a 50 ms ping.
+    fn join(&mut self, username: &str) -> Client {
+        let (raw, outgoing_rx) = mock_connection::make_mock_raw_connection();
+        let uuid = Uuid::new_v4();
+        let entity = self
+            .world
+            .spawn((
+                ServerSideConnection { raw: Box::new(raw) },
+                GameProfile {
+                    id: uuid,
+                    username: username.to_string(),
+                    properties: Vec::new(),
+                },
+                Latency(Duration::from_millis(50)),
+                InGameConnectionState,
+            ))
+            .id();
+        Client {
+            entity,
+            uuid,
+            outgoing_rx,
+        }
+    }
+
+    fn tick(&mut self) {
+        self.schedule.run(&mut self.world);
+    }
+
+    /// Flush the client's connection and decode every frame it was sent.
+    fn sent_frames(&mut self, client: &mut Client) -> Vec<PacketFrame> {
+        mock_connection::sent_frames(&mut self.world, client.entity, &mut client.outgoing_rx)
+    }
+}
+
+/// The one Player Info Update a client was sent, as `(uuid, ping)` pairs.
+fn single_update(frames: &[PacketFrame]) -> (bool, Vec<(Uuid, i32)>) {
+    assert_eq!(frames.len(), 1, "changes are coalesced into one packet");
+    let update = frames[0].decode::<ClientboundPlayerInfoUpdate>().unwrap();
+    assert!(update.actions.update_listed());
+    assert!(update.actions.update_latency());
+    let entries = update
+        .entries
+        .iter()
+        .map(|entry| {
+            assert!(entry.listed);
+            (entry.player_uuid, entry.ping)
+        })
+        .collect();
+    (update.actions.add_player(), entries)
+}
+
+#[test]
+fn two_players_joining_see_each_other_in_one_update() {
+    let mut harness = Harness::new();
+    let mut alice = harness.join("Alice");
+    let mut bob = harness.join("Bob");
+    harness.tick();
+
+    let roster = vec![(alice.uuid, 50), (bob.uuid, 50)];
+    for client in [&mut alice, &mut bob] {
+        let frames = harness.sent_frames(client);
+        assert_eq!(single_update(&frames), (true, roster.clone()));
+    }
+    assert_eq!(harness.world.resource::<PlayerList>().len(), 2);
+}
+
+#[test]
+fn late_joiner_gets_the_roster_and_leaving_removes_them() {
+    let mut harness = Harness::new();
+    let mut alice = harness.join("Alice");
+    harness.tick();
+    harness.sent_frames(&mut alice);
+
+    let mut bob = harness.join("Bob");
+    harness.tick();
+    let frames = harness.sent_frames(&mut alice);
+    assert_eq!(single_update(&frames), (true, vec![(bob.uuid, 50)]));
+    let frames = harness.sent_frames(&mut bob);
+    assert_eq!(
+        single_update(&frames),
+        (true, vec![(alice.uuid, 50), (bob.uuid, 50)])
+    );
+
+    // Both pings move in the same tick; an unchanged ping is not resent.
+    for (client, millis) in [(&alice, 80), (&bob, 120)] {
+        let mut latency = harness.world.get_mut::<Latency>(client.entity).unwrap();
+        latency.0 = Duration::from_millis(millis);
+    }
+    harness.tick();
+    let pings = vec![(alice.uuid, 80), (bob.uuid, 120)];
+    for client in [&mut alice, &mut bob] {
+        let frames = harness.sent_frames(client);
+        assert_eq!(single_update(&frames), (false, pings.clone()));
+    }
+    harness.tick();
+    assert!(harness.sent_frames(&mut alice).is_empty());
+
+    harness
+        .world
+        .entity_mut(bob.entity)
+        .remove::<ServerSideConnection>();
+    harness.tick();
+    let frames = harness.sent_frames(&mut alice);
+    assert_eq!(frames.len(), 1);
+    let remove = frames[0].decode::<ClientboundPlayerInfoRemove>().unwrap();
+    assert_eq!(remove.uuids, [bob.uuid]);
+    assert!(
+        harness
+            .world
+            .resource::<PlayerList>()
+            .get(bob.entity)
+            .is_none()
+    );
+}
@@ -0,0 +1,91 @@
+//! Death respawn: a dead player asking to respawn is sent to the spawn point
+//! as a `Death` transfer carrying its game mode; a living player's request is
+//! ignored.
+
+use std::time::Instant;
+
+use bevy_app::App;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::Messages;
+use mcrs_engine::entity::player::Player;
+use mcrs_minecraft::login::GameProfile;
+use mcrs_minecraft::spawn_point::SpawnPoint;
+use mcrs_minecraft::world::bus::{OutboundPlayerTransferRequest, SpawnReason};
+use mcrs_minecraft::world::entity::player::HostAnchor;
+use mcrs_minecraft::world::entity::player::ability::PlayerGameMode;
+use mcrs_minecraft::world::entity::player::respawn::{Dead, RespawnPlugin};
+use mcrs_network::event::ReceivedPacketEvent;
+use mcrs_protocol::entity::player::ClientCommandAction;
+use mcrs_protocol::packets::game::serverbound::ServerboundClientCommand;
+use mcrs_protocol::uuid::Uuid;
+use mcrs_protocol::{Encode, GameMode, Packet};
+
+fn make_app() -> App {
+    let mut app = App::new();
+    app.add_message::<OutboundPlayerTransferRequest>();
+    app.insert_resource(SpawnPoint::default());
+    app.add_plugins(RespawnPlugin);
+    app
+}
+
+fn spawn_player(app: &mut App, host_anchor: Entity) -> Entity {
+    app.world_mut()
+        .spawn((
+            Player,
+            HostAnchor(host_anchor),
+            PlayerGameMode(GameMode::Adventure),
+            GameProfile {
+                id: Uuid::new_v4(),
+                username: "Steve".to_string(),
+                properties: Vec::new(),
+            },
+        ))
+        .id()
+}
+
+fn perform_respawn(app: &mut App, player: Entity) -> Vec<OutboundPlayerTransferRequest> {
+    let mut data = Vec::new();
+    ServerboundClientCommand {
+        action: ClientCommandAction::PerformRespawn,
+    }
+    .encode(&mut data)
+    .unwrap();
+    app.world_mut().trigger(ReceivedPacketEvent {
+        entity: player,
+        id: ServerboundClientCommand::ID,
+        data: data.into(),
+        timestamp: Instant::now(),
+    });
+    app.world_mut().flush();
+    app.world_mut()
+        .resource_mut::<Messages<OutboundPlayerTransferRequest>>()
+        .drain()
+        .collect()
+}
+
+#[test]
+fn dead_player_respawns_at_the_spawn_point() {
+    let mut app = make_app();
+    let host_anchor = app.world_mut().spawn_empty().id();
+    let player = spawn_player(&mut app, host_anchor);
+    app.world_mut().entity_mut(player).insert(Dead);
+
+    let requests = perform_respawn(&mut app, player);
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    let spawn_point = SpawnPoint::default();
+    assert_eq!(request.host_anchor, host_anchor);
+    assert_eq!(request.dim_name, spawn_point.dimension.as_str());
+    assert_eq!(request.reason, SpawnReason::Death);
+    assert_eq!(request.snapshot.position, spawn_point.player_position());
+    assert_eq!(request.snapshot.game_mode, GameMode::Adventure);
+}
+
+#[test]
+fn living_player_cannot_respawn() {
+    let mut app = make_app();
+    let host_anchor = app.world_mut().spawn_empty().id();
+    let player = spawn_player(&mut app, host_anchor);
+
+    assert!(perform_respawn(&mut app, player).is_empty());
+}
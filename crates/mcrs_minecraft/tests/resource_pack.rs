@@ -0,0 +1,138 @@
+//! Resource packs: the push reaches the client, each Resource Pack Response
+//! updates the pack's status, and declining a forced pack disconnects.
+
+#[path = "common/mock_connection.rs"]
+mod mock_connection;
+
+use std::time::Instant;
+
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::Messages;
+use bevy_ecs::world::World;
+use bytes::Bytes;
+use mcrs_minecraft::resource_pack::{
+    ResourcePack, ResourcePackConfig, ResourcePackStatus, ResourcePacks, push_resource_pack,
+    track_resource_pack_responses,
+};
+use mcrs_network::event::{ClientPacket, ReceivedPacketEvent, emit_client_packet};
+use mcrs_network::{ConnectionState, EngineConnection, ServerSideConnection};
+use mcrs_protocol::packets::common::serverbound::ResourcePack as ResourcePackResponse;
+use mcrs_protocol::packets::configuration::clientbound::ClientboundResourcePackPush as ConfigurationPush;
+use mcrs_protocol::packets::configuration::serverbound::ServerboundResourcePack as ConfigurationResponse;
+use mcrs_protocol::packets::game::serverbound::ServerboundResourcePack as GameResponse;
+use mcrs_protocol::resource_pack::Status;
+use mcrs_protocol::uuid::Uuid;
+use mcrs_protocol::{Encode, Packet, PacketDecoder};
+use tokio::sync::mpsc;
+
+use mock_connection::run_system;
+
+const PACK_ID: Uuid = Uuid::from_u128(0x5eed);
+
+fn pack_world(state: ConnectionState) -> (World, Entity, mpsc::Receiver<Bytes>) {
+    let mut world = World::new();
+    world.init_resource::<Messages<ClientPacket>>();
+    world.init_resource::<ResourcePackConfig>();
+    world.add_observer(emit_client_packet);
+    let (raw, outgoing_rx) = mock_connection::make_mock_raw_connection();
+    let entity = world
+        .spawn((ServerSideConnection { raw: Box::new(raw) }, state))
+        .id();
+    (world, entity, outgoing_rx)
+}
+
+fn push(world: &mut World, entity: Entity, forced: bool) {
+    world
+        .commands()
+        .entity(entity)
+        .queue(push_resource_pack(ResourcePack {
+            id: PACK_ID,
+            url: "https://example.com/pack.zip".to_owned(),
+            hash: String::new(),
+            forced,
+            prompt: None,
+        }));
+    world.flush();
+}
+
+fn receive(world: &mut World, entity: Entity, id: i32, packet: impl Encode) {
+    let mut data = Vec::new();
+    packet.encode(&mut data).unwrap();
+    world.trigger(ReceivedPacketEvent {
+        entity,
+        id,
+        data: data.into(),
+        timestamp: Instant::now(),
+    });
+    world.flush();
+}
+
+fn response(status: Status) -> ResourcePackResponse {
+    ResourcePackResponse {
+        id: PACK_ID,
+        status,
+    }
+}
+
+fn status(world: &World, entity: Entity) -> Option<ResourcePackStatus> {
+    world.get::<ResourcePacks>(entity)?.status(PACK_ID)
+}
+
+fn is_closing(world: &World, entity: Entity) -> bool {
+    world
+        .get::<ServerSideConnection>(entity)
+        .unwrap()
+        .is_closing()
+}
+
+#[test]
+fn declining_a_forced_pack_disconnects() {
+    let (mut world, entity, mut outgoing_rx) = pack_world(ConnectionState::Configuration);
+    push(&mut world, entity, true);
+    assert_eq!(status(&world, entity), Some(ResourcePackStatus::Pending));
+
+    world
+        .get_mut::<ServerSideConnection>(entity)
+        .unwrap()
+        .flush()
+        .unwrap();
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(outgoing_rx.try_recv().unwrap().into());
+    let frame = decoder.try_next_packet().unwrap().unwrap();
+    let pushed = frame.decode::<ConfigurationPush>().unwrap().0;
+    assert_eq!(pushed.id, PACK_ID);
+    assert!(pushed.required);
+
+    receive(
+        &mut world,
+        entity,
+        ConfigurationResponse::ID,
+        ConfigurationResponse(response(Status::Declined)),
+    );
+    run_system(&mut world, track_resource_pack_responses);
+    assert_eq!(status(&world, entity), Some(ResourcePackStatus::Declined));
+    assert!(is_closing(&world, entity));
+}
+
+#[test]
+fn accepted_pack_records_loaded_on_the_final_response() {
+    let (mut world, entity, _outgoing_rx) = pack_world(ConnectionState::Game);
+    push(&mut world, entity, true);
+
+    for step in [
+        Status::Accepted,
+        Status::Downloaded,
+        Status::SuccessfullyLoaded,
+    ] {
+        receive(
+            &mut world,
+            entity,
+            GameResponse::ID,
+            GameResponse(response(step)),
+        );
+    }
+    run_system(&mut world, track_resource_pack_responses);
+    assert_eq!(status(&world, entity), Some(ResourcePackStatus::Loaded));
+    assert!(world.get::<ResourcePacks>(entity).unwrap().all_loaded());
+    assert!(!is_closing(&world, entity));
+}
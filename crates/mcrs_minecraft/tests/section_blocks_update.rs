@@ -0,0 +1,155 @@
+//! Block changes are sent once per section per tick: a lone change as a Block
+//! Update, several as one Section Blocks Update packing each block's position
+//! within the section with its new state.
+
+use bevy_app::{App, FixedPostUpdate, FixedUpdate};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::Messages;
+use mcrs_engine::aoi::PlayerObservers;
+use mcrs_engine::entity::player::Player;
+use mcrs_engine::geometry::ColumnPos;
+use mcrs_engine::world::block::BlockPos;
+use mcrs_engine::world::chunk::{ChunkIndex, ChunkPos};
+use mcrs_engine::world::dimension::InDimension;
+use mcrs_engine::world::storage::column::{ColumnIndex, ColumnSlot};
+use mcrs_minecraft::world::block_update::{BlockUpdatePlugin, BlockUpdateWirePlugin};
+use mcrs_minecraft::world::bus::{OutboundPlayerPacket, PacketPayload, PacketTarget};
+use mcrs_minecraft_block::block_update::{BlockPlaced, BlockSetRequest};
+use mcrs_minecraft_block::palette::BlockPalette;
+use mcrs_protocol::BlockStateId;
+
+/// A dimension with one player observing every section in `sections`.
+fn make_app(sections: &[ChunkPos]) -> (App, Entity, Entity) {
+    let mut app = App::new();
+    app.add_message::<OutboundPlayerPacket>();
+    app.add_message::<BlockSetRequest>();
+    app.add_message::<BlockPlaced>();
+    app.add_plugins((BlockUpdatePlugin, BlockUpdateWirePlugin));
+
+    let player = app.world_mut().spawn(Player).id();
+    let mut observers = PlayerObservers::default();
+    observers.0.push(player);
+    let column_entity = app.world_mut().spawn(observers).id();
+
+    let dim = app.world_mut().spawn_empty().id();
+    let mut chunk_index = ChunkIndex::default();
+    let mut column_index = ColumnIndex::default();
+    for &section in sections {
+        let chunk = app
+            .world_mut()
+            .spawn((section, InDimension(dim), BlockPalette::default()))
+            .id();
+        chunk_index.insert(section, chunk);
+        column_index.0.insert(
+            ColumnPos::from(section),
+            ColumnSlot {
+                entity: column_entity,
+                section_count: 1,
+            },
+        );
+    }
+    app.world_mut()
+        .entity_mut(dim)
+        .insert((chunk_index, column_index));
+
+    // Seeds each section's change set before any edit.
+    app.world_mut().run_schedule(FixedUpdate);
+    (app, dim, player)
+}
+
+fn set_blocks(app: &mut App, dim: Entity, edits: &[(BlockPos, u16)]) {
+    let mut writer = app.world_mut().resource_mut::<Messages<BlockSetRequest>>();
+    for &(pos, state) in edits {
+        writer.write(BlockSetRequest::set_block(dim, pos, BlockStateId(state)));
+    }
+}
+
+fn tick(app: &mut App) -> Vec<OutboundPlayerPacket> {
+    app.world_mut().run_schedule(FixedUpdate);
+    app.world_mut().run_schedule(FixedPostUpdate);
+    app.world_mut()
+        .resource_mut::<Messages<OutboundPlayerPacket>>()
+        .drain()
+        .collect()
+}
+
+#[test]
+fn three_edits_in_one_section_coalesce() {
+    let section = ChunkPos::new(1, -1, 2);
+    let (mut app, dim, player) = make_app(&[section]);
+
+    set_blocks(
+        &mut app,
+        dim,
+        &[
+            (BlockPos::new(16 + 15, -16, 32 + 3), 3),
+            (BlockPos::new(16 + 1, -16 + 2, 32 + 3), 2),
+            (BlockPos::new(16, -16, 32), 1),
+        ],
+    );
+    let packets = tick(&mut app);
+
+    assert_eq!(packets.len(), 1, "one packet for the section: {packets:?}");
+    assert!(matches!(
+        &packets[0].target,
+        PacketTarget::PlayerSet(set) if set.as_slice() == [player]
+    ));
+    let PacketPayload::SectionBlocksUpdate {
+        section: sent,
+        blocks,
+    } = &packets[0].data
+    else {
+        panic!(
+            "expected a Section Blocks Update, got {:?}",
+            packets[0].data
+        );
+    };
+    assert_eq!(*sent, section);
+    let entries: Vec<_> = blocks
+        .iter()
+        .map(|entry| {
+            (
+                (entry.off_x(), entry.off_y(), entry.off_z()),
+                entry.block_state(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        entries,
+        [((0, 0, 0), 1), ((15, 0, 3), 3), ((1, 2, 3), 2)],
+        "each block packed relative to the section, ordered by y, z, x"
+    );
+
+    // Everything was sent; the next tick has nothing to add.
+    assert!(tick(&mut app).is_empty());
+}
+
+#[test]
+fn lone_edits_stay_block_updates() {
+    let sections = [ChunkPos::new(0, 0, 0), ChunkPos::new(0, 1, 0)];
+    let (mut app, dim, _) = make_app(&sections);
+
+    // Two sections with one edit each, and the same block set twice in the
+    // second: neither is worth a Section Blocks Update.
+    set_blocks(
+        &mut app,
+        dim,
+        &[
+            (BlockPos::new(4, 5, 6), 1),
+            (BlockPos::new(4, 20, 6), 1),
+            (BlockPos::new(4, 20, 6), 2),
+        ],
+    );
+    let mut updates: Vec<_> = tick(&mut app)
+        .into_iter()
+        .map(|packet| match packet.data {
+            PacketPayload::BlockUpdate {
+                position,
+                new_state,
+            } => (position.y, new_state.0),
+            other => panic!("expected Block Updates only, got {other:?}"),
+        })
+        .collect();
+    updates.sort_unstable();
+    assert_eq!(updates, [(5, 1), (20, 2)]);
+}
@@ -54,15 +54,26 @@ pub struct BlockSetRequest {
 }
 
 impl BlockSetRequest {
-    pub fn remove_block<P: Into<BlockPos>>(dimension: Entity, pos: P) -> BlockSetRequest {
+    /// Set the block at `pos` with every update flag, as a player edit does.
+    /// The change is recorded for its chunk's observers and sent to them at
+    /// the end of the tick.
+    pub fn set_block<P: Into<BlockPos>>(
+        dimension: Entity,
+        pos: P,
+        new_state: BlockStateId,
+    ) -> BlockSetRequest {
         BlockSetRequest {
             dimension,
             pos: pos.into(),
-            new_state: BlockStateId(0),
+            new_state,
             flags: BlockUpdateFlags::all(),
             recursion_left: 512,
         }
     }
+
+    pub fn remove_block<P: Into<BlockPos>>(dimension: Entity, pos: P) -> BlockSetRequest {
+        Self::set_block(dimension, pos, BlockStateId(0))
+    }
 }
 
 #[derive(Default, Component)]
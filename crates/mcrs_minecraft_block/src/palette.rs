@@ -3,7 +3,8 @@ use mcrs_engine::world::block::BlockPos;
 use mcrs_engine::world::chunk;
 use mcrs_engine::world::chunk::palette::PalettedContainer::{Heterogeneous, Homogeneous};
 use mcrs_engine::world::chunk::palette::{PalettedContainer, encompassing_bits};
-use mcrs_protocol::BlockStateId;
+use mcrs_protocol::{BlockStateId, Encode};
+use std::io::Write;
 
 impl BiomePalette {
     /// Set the biome id for a 4x4x4 biome cell within this section.
@@ -198,6 +199,30 @@ impl BlockPalette {
     }
 }
 
+/// Write one chunk section in the vanilla `LevelChunkSection.write` layout:
+///
+/// ```text
+/// short non_empty_block_count
+/// short fluid_count
+/// PalettedContainer<BlockState>
+/// PalettedContainer<Biome>
+/// ```
+///
+/// The client reads both shorts unconditionally; omitting the fluid count
+/// desynchronises the reader by two bytes per section and turns the rest of
+/// the column into garbage. Each container picks the single-value, indirect or
+/// direct palette from its distinct entry count.
+pub fn write_section(
+    blocks: &BlockPalette,
+    biomes: &BiomePalette,
+    mut w: impl Write,
+) -> mcrs_protocol::anyhow::Result<()> {
+    blocks.non_air_block_count().encode(&mut w)?;
+    0u16.encode(&mut w)?;
+    blocks.convert_network().encode(&mut w)?;
+    biomes.convert_network().encode(&mut w)
+}
+
 // According to the wiki, palette serialization for disk and network is different. Disk
 // serialization always uses a palette if greater than one entry. Network serialization packs ids
 // directly instead of using a palette above a certain bits-per-entry
@@ -215,3 +240,63 @@ const BIOME_DISK_MIN_BITS: u8 = 0;
 const BIOME_NETWORK_MIN_MAP_BITS: u8 = 1;
 const BIOME_NETWORK_MAX_MAP_BITS: u8 = 3;
 pub(crate) const BIOME_NETWORK_MAX_BITS: u8 = 7;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcrs_engine::world::block::BlockPos;
+
+    fn section_bytes(blocks: &BlockPalette) -> Vec<u8> {
+        let mut data = Vec::new();
+        write_section(blocks, &BiomePalette::default(), &mut data).unwrap();
+        data
+    }
+
+    /// Single-value biome container for biome 0: zero bits, then the id.
+    const SINGLE_BIOME: [u8; 2] = [0, 0];
+
+    #[test]
+    fn all_air_section_uses_single_value_palettes() {
+        let data = section_bytes(&BlockPalette::default());
+        // Block count, fluid count, zero bits + air id, then the biomes.
+        assert_eq!(data, [0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(data[6..], SINGLE_BIOME);
+    }
+
+    #[test]
+    fn two_state_section_uses_minimum_indirect_palette() {
+        let mut blocks = BlockPalette::default();
+        blocks.set(BlockPos::new(1, 0, 0), BlockStateId(1));
+        let data = section_bytes(&blocks);
+
+        assert_eq!(data[..4], [0, 1, 0, 0]);
+        // Indirect palettes never drop below 4 bits per entry.
+        assert_eq!(data[4], BLOCK_NETWORK_MIN_MAP_BITS);
+        assert_eq!(data[5..8], [2, 0, 1]);
+        // 4096 entries at 16 per long, each long big-endian. Entry 1 points
+        // at palette index 1, every other entry at air (index 0).
+        let longs = &data[8..8 + 256 * 8];
+        assert_eq!(longs[..8], [0, 0, 0, 0, 0, 0, 0, 0x10]);
+        assert!(longs[8..].iter().all(|&b| b == 0));
+        assert_eq!(data[8 + 256 * 8..], SINGLE_BIOME);
+    }
+
+    #[test]
+    fn many_state_section_falls_back_to_direct_palette() {
+        let mut blocks = BlockPalette::default();
+        for i in 0..300u16 {
+            let (x, z) = ((i % 16) as i32, (i / 16 % 16) as i32);
+            blocks.set(BlockPos::new(x, (i / 256) as i32, z), BlockStateId(i + 1));
+        }
+        let data = section_bytes(&blocks);
+
+        assert_eq!(data[..4], [1, 44, 0, 0]);
+        assert_eq!(data[4], BLOCK_NETWORK_MAX_BITS);
+        // No palette: the global ids are packed straight in, 4 per long.
+        let longs = &data[5..5 + 1024 * 8];
+        let first = i64::from_be_bytes(longs[..8].try_into().unwrap());
+        let expected = (1..=4).fold(0i64, |acc, i| acc | (i << (15 * (i - 1))));
+        assert_eq!(first, expected);
+        assert_eq!(data[5 + 1024 * 8..], SINGLE_BIOME);
+    }
+}
@@ -0,0 +1,373 @@
+//! Whole-column light recompute, independent of the ECS pipeline.
+//!
+//! `LightEngine` lights one freshly generated chunk column from its block
+//! states alone: sky light falls straight down at 15 until the first block
+//! that does not let it through and spreads sideways from there, block light
+//! spreads from every emitter. Both use the same level rules as the
+//! incremental BFS in `common::bfs`. Neighbouring columns are not consulted,
+//! so light that would cross a column edge is missing until the incremental
+//! pipeline runs on the loaded neighbours.
+
+use std::borrow::Cow;
+
+use bevy_ecs::entity::Entity;
+use mcrs_core::voxel_shape::{Direction, VoxelShape};
+use mcrs_engine::world::column::ChunkLookup;
+use mcrs_minecraft_block::palette::BlockPalette;
+use mcrs_protocol::BlockStateId;
+use mcrs_protocol::chunk::{LightChunk, LightData};
+
+use crate::bfs::normal_of;
+use crate::codec::codec::{Layer, pack_chunk};
+use crate::nibble::LightNibbles;
+use crate::storage::LightStorage;
+use crate::table::{BlockStateLightTable, flag_bits};
+
+const DIRECTIONS: [Direction; 6] = [
+    Direction::Down,
+    Direction::Up,
+    Direction::North,
+    Direction::South,
+    Direction::West,
+    Direction::East,
+];
+
+/// Full-recompute lighting for a single chunk column.
+pub struct LightEngine<'a> {
+    table: &'a BlockStateLightTable,
+}
+
+/// Light levels of a column's sections, bottom to top.
+#[derive(Clone, Debug)]
+pub struct ColumnLight {
+    pub sky: Vec<LightStorage>,
+    pub block: Vec<LightStorage>,
+    pub has_sky_light: bool,
+}
+
+impl<'a> LightEngine<'a> {
+    pub fn new(table: &'a BlockStateLightTable) -> Self {
+        Self { table }
+    }
+
+    /// Light `sections`, ordered bottom to top. Sky light is only computed
+    /// when the dimension has a sky; otherwise every sky section is empty.
+    pub fn light_column(&self, sections: &[BlockPalette], has_sky_light: bool) -> ColumnLight {
+        let column = ColumnStates::new(sections);
+        let block = {
+            let mut levels = vec![0u8; column.states.len()];
+            let mut queue = Vec::new();
+            for (index, &state) in column.states.iter().enumerate() {
+                let emission = self.table.emission_for(state);
+                if emission > 0 {
+                    levels[index] = emission;
+                    queue.push(index);
+                }
+            }
+            self.propagate(&column, &mut levels, queue, false);
+            column.to_sections(&levels)
+        };
+        let sky = if has_sky_light {
+            let mut levels = vec![0u8; column.states.len()];
+            let mut queue = Vec::new();
+            for z in 0..16 {
+                for x in 0..16 {
+                    for y in (0..column.height).rev() {
+                        let index = ColumnStates::index(x, y, z);
+                        let flags = self.table.flags_for(column.states[index]);
+                        if flags & flag_bits::PROPAGATES_SKYLIGHT_DOWN == 0 {
+                            break;
+                        }
+                        levels[index] = 15;
+                        queue.push(index);
+                    }
+                }
+            }
+            self.propagate(&column, &mut levels, queue, true);
+            column.to_sections(&levels)
+        } else {
+            vec![LightStorage::Empty; sections.len()]
+        };
+        ColumnLight {
+            sky,
+            block,
+            has_sky_light,
+        }
+    }
+
+    /// Breadth-first spread from the cells in `queue`, whose levels are
+    /// already set, raising every cell they reach to its best level.
+    fn propagate(
+        &self,
+        column: &ColumnStates,
+        levels: &mut [u8],
+        mut queue: Vec<usize>,
+        sky: bool,
+    ) {
+        let mut read = 0;
+        while read < queue.len() {
+            let index = queue[read];
+            read += 1;
+            let level = levels[index];
+            if level <= 1 {
+                continue;
+            }
+            let (x, y, z) = ColumnStates::position(index);
+            let src_state = column.states[index];
+            let src_flags = self.table.flags_for(src_state);
+            let from_shape: &'static VoxelShape =
+                if src_flags & flag_bits::IS_CONDITIONALLY_OPAQUE != 0 {
+                    self.table.occlusion_for(src_state)
+                } else {
+                    VoxelShape::empty()
+                };
+            for d in DIRECTIONS {
+                let (dx, dy, dz) = normal_of(d);
+                let (nx, ny, nz) = (
+                    x as i32 + dx as i32,
+                    y as i32 + dy as i32,
+                    z as i32 + dz as i32,
+                );
+                if !(0..16).contains(&nx)
+                    || !(0..16).contains(&nz)
+                    || !(0..column.height as i32).contains(&ny)
+                {
+                    continue;
+                }
+                let neighbour = ColumnStates::index(nx as usize, ny as usize, nz as usize);
+                let dst_state = column.states[neighbour];
+                let dst_flags = self.table.flags_for(dst_state);
+                if (src_flags | dst_flags) & flag_bits::IS_CONDITIONALLY_OPAQUE != 0 {
+                    let culling_face = self.table.occlusion_for(dst_state).face_shape(d.opposite());
+                    if from_shape.face_occludes(culling_face, d) {
+                        continue;
+                    }
+                }
+                let target = if sky
+                    && d == Direction::Down
+                    && level == 15
+                    && dst_flags & flag_bits::PROPAGATES_SKYLIGHT_DOWN != 0
+                {
+                    15
+                } else {
+                    level.saturating_sub(self.table.dampening_for(dst_state).max(1))
+                };
+                if target > levels[neighbour] {
+                    levels[neighbour] = target;
+                    queue.push(neighbour);
+                }
+            }
+        }
+    }
+}
+
+impl ColumnLight {
+    /// Pack the column into the Chunk Data / Update Light layout: one bit per
+    /// section plus a padding section below and above, the bottom one empty
+    /// and the top one fully sky lit when the dimension has a sky.
+    pub fn to_light_data(&self) -> LightData<'static> {
+        let mut sky_mask = Vec::new();
+        let mut block_mask = Vec::new();
+        let mut empty_sky_mask = Vec::new();
+        let mut empty_block_mask = Vec::new();
+        let mut sky_arrays: Vec<LightChunk> = Vec::new();
+        let mut block_arrays: Vec<LightChunk> = Vec::new();
+
+        let rows = std::iter::once((ChunkLookup::BottomPadding, None))
+            .chain(
+                self.sky
+                    .iter()
+                    .zip(&self.block)
+                    .map(|storage| (ChunkLookup::Loaded(Entity::PLACEHOLDER), Some(storage))),
+            )
+            .chain(std::iter::once((ChunkLookup::TopPadding, None)));
+        for (bit_idx, (lookup, storage)) in rows.enumerate() {
+            pack_chunk(
+                lookup,
+                storage.map(|(_, block)| block),
+                Layer::Block,
+                self.has_sky_light,
+                bit_idx,
+                &mut block_mask,
+                &mut empty_block_mask,
+                &mut block_arrays,
+            );
+            pack_chunk(
+                lookup,
+                storage.map(|(sky, _)| sky),
+                Layer::Sky,
+                self.has_sky_light,
+                bit_idx,
+                &mut sky_mask,
+                &mut empty_sky_mask,
+                &mut sky_arrays,
+            );
+        }
+
+        LightData {
+            sky_light_mask: Cow::Owned(sky_mask),
+            block_light_mask: Cow::Owned(block_mask),
+            empty_sky_light_mask: Cow::Owned(empty_sky_mask),
+            empty_block_light_mask: Cow::Owned(empty_block_mask),
+            sky_light_arrays: Cow::Owned(sky_arrays),
+            block_light_arrays: Cow::Owned(block_arrays),
+        }
+    }
+}
+
+/// Block states of a whole column, flattened so neighbours across section
+/// boundaries are one index step away.
+struct ColumnStates {
+    /// `states[(y * 16 + z) * 16 + x]`, `y` counted from the bottom section.
+    states: Vec<BlockStateId>,
+    height: usize,
+}
+
+impl ColumnStates {
+    fn new(sections: &[BlockPalette]) -> Self {
+        let height = sections.len() * 16;
+        let mut states = Vec::with_capacity(height * 256);
+        for section in sections {
+            for y in 0..16i32 {
+                for z in 0..16i32 {
+                    for x in 0..16i32 {
+                        states.push(section.get((x, y, z)));
+                    }
+                }
+            }
+        }
+        Self { states, height }
+    }
+
+    #[inline]
+    fn index(x: usize, y: usize, z: usize) -> usize {
+        (y * 16 + z) * 16 + x
+    }
+
+    #[inline]
+    fn position(index: usize) -> (usize, usize, usize) {
+        (index & 15, index >> 8, (index >> 4) & 15)
+    }
+
+    fn to_sections(&self, levels: &[u8]) -> Vec<LightStorage> {
+        levels
+            .chunks_exact(4096)
+            .map(|section| {
+                let mut nibbles = LightNibbles::zeros();
+                for (index, &level) in section.iter().enumerate() {
+                    let (x, y, z) = Self::position(index);
+                    nibbles.set(x, y, z, level);
+                }
+                LightStorage::Dense(Box::new(nibbles)).compact()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcrs_engine::world::block::BlockPos;
+
+    const AIR: BlockStateId = BlockStateId(0);
+    const STONE: BlockStateId = BlockStateId(1);
+    const TORCH: BlockStateId = BlockStateId(2);
+
+    fn make_test_table() -> BlockStateLightTable {
+        let occlusion: Box<[&'static VoxelShape]> = vec![VoxelShape::empty(); 3].into_boxed_slice();
+        BlockStateLightTable {
+            emission: Box::new([0, 0, 14]),
+            dampening: Box::new([0, 15, 0]),
+            occlusion,
+            flags: Box::new([
+                flag_bits::PROPAGATES_SKYLIGHT_DOWN,
+                flag_bits::IS_SOLID_OPAQUE | flag_bits::IS_MOTION_BLOCKING | flag_bits::IS_NOT_AIR,
+                flag_bits::PROPAGATES_SKYLIGHT_DOWN | flag_bits::IS_NOT_AIR,
+            ]),
+        }
+    }
+
+    fn air_section() -> BlockPalette {
+        let mut palette = BlockPalette::default();
+        palette.fill(AIR);
+        palette
+    }
+
+    #[test]
+    fn torch_light_decays_one_level_per_block() {
+        let table = make_test_table();
+        let mut sections = vec![air_section(), air_section()];
+        // A torch on the top face of the lower section, so the light also
+        // has to cross into the section above.
+        sections[0].set(BlockPos::new(8, 15, 8), TORCH);
+        let light = LightEngine::new(&table).light_column(&sections, false);
+
+        let block_at = |x: usize, y: usize, z: usize| light.block[y / 16].get(x, y % 16, z);
+        assert_eq!(block_at(8, 15, 8), 14);
+        for distance in 1..=15usize {
+            let expected = 14u8.saturating_sub(distance as u8);
+            assert_eq!(block_at(8, 15 + distance, 8), expected, "{distance} up");
+            assert_eq!(block_at(8, 15 - distance, 8), expected, "{distance} down");
+        }
+        assert_eq!(block_at(12, 18, 10), 14 - 4 - 3 - 2);
+        assert!(
+            light
+                .sky
+                .iter()
+                .all(|sky| matches!(sky, LightStorage::Empty))
+        );
+    }
+
+    #[test]
+    fn stone_stops_block_light() {
+        let table = make_test_table();
+        let mut section = air_section();
+        section.set(BlockPos::new(4, 4, 4), TORCH);
+        section.set(BlockPos::new(5, 4, 4), STONE);
+        let light = LightEngine::new(&table).light_column(&[section], false);
+
+        assert_eq!(light.block[0].get(5, 4, 4), 0);
+        // Around the stone instead of through it: up, over, down.
+        assert_eq!(light.block[0].get(6, 4, 4), 14 - 4);
+    }
+
+    #[test]
+    fn sky_light_falls_through_a_hole_in_a_roof() {
+        let table = make_test_table();
+        let mut sections = vec![air_section(), air_section()];
+        for z in 0..16 {
+            for x in 0..16 {
+                if (x, z) != (8, 8) {
+                    sections[1].set(BlockPos::new(x, 0, z), STONE);
+                }
+            }
+        }
+        let light = LightEngine::new(&table).light_column(&sections, true);
+
+        assert_eq!(light.sky[1].get(0, 1, 0), 15);
+        assert_eq!(light.sky[1].get(0, 0, 0), 0, "inside the roof");
+        assert_eq!(light.sky[0].get(8, 0, 8), 15, "straight down the hole");
+        assert_eq!(light.sky[0].get(9, 15, 8), 14);
+        assert_eq!(light.sky[0].get(12, 10, 8), 15 - 4);
+        assert_eq!(light.sky[0].get(0, 15, 0), 0, "too far from the hole");
+    }
+
+    #[test]
+    fn light_data_has_padding_sections_around_the_column() {
+        let table = make_test_table();
+        let mut section = air_section();
+        section.set(BlockPos::new(0, 0, 0), TORCH);
+        let data = LightEngine::new(&table)
+            .light_column(&[section], true)
+            .to_light_data();
+
+        // Bit 0 is the padding below, bit 1 the section, bit 2 the padding above.
+        assert_eq!(data.block_light_mask.as_ref(), [0b010]);
+        assert_eq!(data.empty_block_light_mask.as_ref(), [0b101]);
+        assert_eq!(data.sky_light_mask.as_ref(), [0b110]);
+        assert_eq!(data.empty_sky_light_mask.as_ref(), [0b001]);
+        assert_eq!(data.block_light_arrays.len(), 1);
+        assert_eq!(data.sky_light_arrays.len(), 2);
+        assert_eq!(data.block_light_arrays[0].0[0] & 0x0F, 14);
+    }
+}
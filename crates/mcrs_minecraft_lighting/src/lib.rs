@@ -10,6 +10,7 @@ pub mod common;
 pub mod block_light;
 pub mod sky_light;
 
+pub mod engine;
 pub mod plugin;
 
 // Re-export the moved infrastructure modules so existing `crate::bfs::*` etc.
@@ -51,6 +52,7 @@ pub use codec::codec::{BlockLightDirty, ColumnLightUpdate, SkyLightDirty};
 pub use codec::sets::LightingSet;
 pub use lifecycle::ColumnHeightmapScan;
 pub use plugin::LightingPlugin;
+pub use engine::{ColumnLight, LightEngine};
 
 #[cfg(any(feature = "test-bench", feature = "bench-helpers"))]
 pub use common::test_bench;
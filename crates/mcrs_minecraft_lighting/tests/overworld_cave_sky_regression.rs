@@ -153,7 +153,7 @@ fn load_overworld_noise_router(assets_path: &std::path::Path) -> OverworldNoiseR
 
     // Match the production seed in `NoiseGeneratorSettingsPlugin` (bevy.rs).
     // Test with the same world the live server generates.
-    let router = build_functions(&functions, &noises, &settings, 2, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86));
+    let router = build_functions(&functions, &noises, &settings, 2, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86)).unwrap();
     OverworldNoiseRouter(Arc::new(router))
 }
 
@@ -14,9 +14,19 @@
 //!   --view-distance N    View distance in chunks (default: 10)
 //!   --assets PATH        Assets directory (default: ./assets)
 //!   --settings NAME      Noise settings name (default: overworld)
-
-use bevy_math::IVec3;
+//!
+//! After the view-distance run, the same router generates 64 chunks of noise
+//! single-threaded and with `generate_chunks_parallel` to report the speedup,
+//! then times the Zone A populate, which is mostly spline evaluation, and
+//! `fill_cell_blocks` against interpolating every cell. With the
+//! `parallel-columns` feature the populate is also timed with
+//! `populate_columns_parallel`.
+
+use bevy_math::{IVec2, IVec3};
 use mcrs_minecraft_worldgen::density_function::build_functions;
+use mcrs_minecraft_worldgen::density_function::parallel::{
+    ChunkNoiseWorker, generate_chunks_parallel,
+};
 use mcrs_minecraft_worldgen::density_function::proto::{
     DensityFunctionHolder, NoiseParam, ProtoDensityFunction,
 };
@@ -226,6 +236,71 @@ fn generate_column(
     (solid_count, skipped_sections)
 }
 
+/// Walk every cell of a chunk column and count solid blocks, deciding cells
+/// with `fill_cell_blocks` (`fast`) or always interpolating them.
+fn count_solid_by_cell(
+    router: &mcrs_minecraft_worldgen::density_function::NoiseRouter,
+    section_x: i32,
+    section_z: i32,
+    y_sections: &[i32],
+    fast: bool,
+) -> (u64, u64) {
+    let mut interp = router.new_noise_cell_interpolator();
+    let block_x = section_x * 16;
+    let block_z = section_z * 16;
+    let mut column_cache = router.new_column_cache(block_x, block_z);
+    router.populate_columns(&mut column_cache);
+
+    let h_cell_blocks = interp.h_cell_blocks();
+    let h_cells = interp.h_cells();
+    let v_cells = interp.v_cells();
+    let mut solid = vec![false; interp.cell_block_count()];
+    let mut solid_count = 0u64;
+    let mut uniform_cells = 0u64;
+
+    for &sy in y_sections {
+        let section_block_y = sy * 16;
+        interp.fill_plane_cached_reuse(
+            0,
+            true,
+            block_x,
+            section_block_y,
+            block_z,
+            router,
+            &mut column_cache,
+        );
+        for cell_x in 0..h_cells {
+            let next_x = block_x + ((cell_x + 1) * h_cell_blocks) as i32;
+            interp.fill_plane_cached_reuse(
+                cell_x + 1,
+                false,
+                next_x,
+                section_block_y,
+                block_z,
+                router,
+                &mut column_cache,
+            );
+            for cell_z in 0..h_cells {
+                for cell_y in 0..v_cells {
+                    interp.on_sampled_cell_corners(cell_y, cell_z);
+                    if fast {
+                        if interp.fill_cell_blocks(&mut solid).is_some() {
+                            uniform_cells += 1;
+                        }
+                    } else {
+                        interp.interpolate_cell_blocks(&mut solid);
+                    }
+                    solid_count += solid.iter().filter(|&&s| s).count() as u64;
+                }
+            }
+            interp.swap_buffers();
+        }
+        interp.end_section();
+    }
+
+    (solid_count, uniform_cells)
+}
+
 fn fmt_duration(d: Duration) -> String {
     if d.as_secs() >= 1 {
         format!("{:.3}s", d.as_secs_f64())
@@ -320,7 +395,8 @@ fn main() {
     );
 
     let t_build = Instant::now();
-    let router = build_functions(&functions, &noises, &settings, seed, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86));
+    let router = build_functions(&functions, &noises, &settings, seed, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86))
+        .unwrap_or_else(|e| panic!("Failed to build noise router: {}", e));
     let build_elapsed = t_build.elapsed();
     eprintln!("Built NoiseRouter in {}", fmt_duration(build_elapsed));
     router.print_zone_stats();
@@ -465,4 +541,99 @@ fn main() {
         "  Per chunk section (mean): {}",
         fmt_duration(mean / num_sections as u32),
     );
+
+    // --- Single- vs multi-threaded noise for an 8x8 block of chunks ---
+    let batch: Vec<IVec2> = (0..8)
+        .flat_map(|dx| (0..8).map(move |dz| IVec2::new(center_x + dx - 4, center_z + dz - 4)))
+        .collect();
+
+    let t_single = Instant::now();
+    let mut worker = ChunkNoiseWorker::new(&router);
+    let single: Vec<_> = batch.iter().map(|&pos| worker.generate(&router, pos)).collect();
+    let single_elapsed = t_single.elapsed();
+
+    let t_multi = Instant::now();
+    let multi = generate_chunks_parallel(&router, &batch);
+    let multi_elapsed = t_multi.elapsed();
+
+    assert!(single == multi, "parallel chunk noise diverged from serial");
+    eprintln!();
+    eprintln!("=== Chunk noise, {} chunks ===", batch.len());
+    eprintln!("  Single-threaded: {}", fmt_duration(single_elapsed));
+    eprintln!(
+        "  Multi-threaded:  {} ({} threads)",
+        fmt_duration(multi_elapsed),
+        std::thread::available_parallelism().map_or(1, |n| n.get()),
+    );
+    eprintln!(
+        "  Speedup:         {:.2}x",
+        single_elapsed.as_secs_f64() / multi_elapsed.as_secs_f64(),
+    );
+
+    // --- Zone A populate (mostly spline evaluation), serial vs task pool ---
+    let t_serial = Instant::now();
+    for pos in &batch {
+        let mut cache = router.new_column_cache(pos.x * 16, pos.y * 16);
+        router.populate_columns(&mut cache);
+    }
+    let serial_elapsed = t_serial.elapsed();
+
+    eprintln!();
+    eprintln!("=== Zone A populate, {} chunks ===", batch.len());
+    eprintln!("  Serial:    {}", fmt_duration(serial_elapsed));
+
+    #[cfg(feature = "parallel-columns")]
+    {
+        let t_parallel = Instant::now();
+        for pos in &batch {
+            let mut cache = router.new_column_cache(pos.x * 16, pos.y * 16);
+            router.populate_columns_parallel(&mut cache);
+        }
+        let parallel_elapsed = t_parallel.elapsed();
+
+        eprintln!(
+            "  Task pool: {} ({} threads)",
+            fmt_duration(parallel_elapsed),
+            bevy_tasks::ComputeTaskPool::get().thread_num(),
+        );
+        eprintln!(
+            "  Speedup:   {:.2}x",
+            serial_elapsed.as_secs_f64() / parallel_elapsed.as_secs_f64(),
+        );
+    }
+
+    // --- Uniform-cell fast path vs per-block interpolation ---
+    let t_fast = Instant::now();
+    let mut fast_solid = 0u64;
+    let mut uniform_cells = 0u64;
+    for pos in &batch {
+        let (solid, uniform) = count_solid_by_cell(&router, pos.x, pos.y, &y_sections, true);
+        fast_solid += solid;
+        uniform_cells += uniform;
+    }
+    let fast_elapsed = t_fast.elapsed();
+
+    let t_interp = Instant::now();
+    let mut interp_solid = 0u64;
+    for pos in &batch {
+        interp_solid += count_solid_by_cell(&router, pos.x, pos.y, &y_sections, false).0;
+    }
+    let interp_elapsed = t_interp.elapsed();
+
+    assert_eq!(fast_solid, interp_solid, "cell fast path diverged from interpolation");
+    let total_cells = batch.len() as u64 * num_sections as u64 * 4 * 4 * 2;
+    eprintln!();
+    eprintln!("=== Cell fill, {} chunks ===", batch.len());
+    eprintln!(
+        "  Uniform cells:   {}/{} ({:.1}%)",
+        uniform_cells,
+        total_cells,
+        uniform_cells as f64 / total_cells as f64 * 100.0,
+    );
+    eprintln!("  fill_cell_blocks:  {}", fmt_duration(fast_elapsed));
+    eprintln!("  Interpolate all:   {}", fmt_duration(interp_elapsed));
+    eprintln!(
+        "  Speedup:           {:.2}x",
+        interp_elapsed.as_secs_f64() / fast_elapsed.as_secs_f64(),
+    );
 }
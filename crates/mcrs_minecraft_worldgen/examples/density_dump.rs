@@ -0,0 +1,169 @@
+//! Standalone tool to print density function values for a single column, or
+//! dump the computation graph of one router root as a DOT file.
+//!
+//! Usage:
+//!   cargo run --example density_dump -- [--seed SEED] [--x X] [--z Z] [--y-range MIN:MAX] [--step N] [--assets PATH] [--root ROOT] [--settings NAME] [--dot FILE]
+//!
+//! Defaults:
+//!   seed = 0, x = 0, z = 0, y-range = the noise settings' full height, step = 1,
+//!   assets = ./assets, root = final_density, settings = overworld
+//!
+//! Values are printed top-down as `y<TAB>value`, one line per block, so two
+//! dumps (or a dump and values logged from vanilla) can be compared with `diff`.
+//!
+//! Example:
+//!   cargo run --release --example density_dump -p mcrs_minecraft_worldgen -- --seed 2 --x 100 --z -40 --y-range 0:128
+//!   cargo run --release --example density_dump -p mcrs_minecraft_worldgen -- --root depth --x 100 --z -40 --dot depth.dot
+
+mod common;
+
+use bevy_math::IVec3;
+use common::load_all;
+use mcrs_minecraft_worldgen::density_function::{Root, build_functions};
+use std::path::PathBuf;
+
+fn main() {
+    let mut seed: u64 = 0;
+    let mut x: i32 = 0;
+    let mut z: i32 = 0;
+    let mut y_range: Option<(i32, i32)> = None;
+    let mut step: usize = 1;
+    let mut assets_path = PathBuf::from("./assets");
+    let mut root_name = "final_density".to_string();
+    let mut settings_name = "overworld".to_string();
+    let mut dot_path: Option<PathBuf> = None;
+
+    // Simple arg parsing
+    let args: Vec<String> = std::env::args().collect();
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--seed" => {
+                i += 1;
+                seed = args[i].parse().expect("Invalid seed");
+            }
+            "--x" => {
+                i += 1;
+                x = args[i].parse().expect("Invalid X");
+            }
+            "--z" => {
+                i += 1;
+                z = args[i].parse().expect("Invalid Z");
+            }
+            "--y-range" => {
+                i += 1;
+                let parts: Vec<&str> = args[i].split(':').collect();
+                if parts.len() != 2 {
+                    panic!("Y range must be MIN:MAX");
+                }
+                let min: i32 = parts[0].parse().expect("Invalid min Y");
+                let max: i32 = parts[1].parse().expect("Invalid max Y");
+                y_range = Some((min.min(max), min.max(max)));
+            }
+            "--step" => {
+                i += 1;
+                step = args[i].parse().expect("Invalid step");
+                if step == 0 {
+                    panic!("Step must be at least 1");
+                }
+            }
+            "--assets" => {
+                i += 1;
+                assets_path = PathBuf::from(&args[i]);
+            }
+            "--root" => {
+                i += 1;
+                root_name = args[i].clone();
+            }
+            "--settings" => {
+                i += 1;
+                settings_name = args[i].clone();
+            }
+            "--dot" => {
+                i += 1;
+                dot_path = Some(PathBuf::from(&args[i]));
+            }
+            "--help" | "-h" => {
+                eprintln!(
+                    "Usage: density_dump [--seed SEED] [--x X] [--z Z] [--y-range MIN:MAX] [--step N] [--assets PATH] [--root ROOT] [--settings NAME] [--dot FILE]"
+                );
+                eprintln!();
+                eprintln!("Roots: barrier, fluid_level_floodedness, fluid_level_spread, lava,");
+                eprintln!("       temperature, vegetation, continents, erosion, depth, ridges,");
+                eprintln!(
+                    "       preliminary_surface_level, final_density, vein_toggle, vein_ridged,"
+                );
+                eprintln!("       vein_gap");
+                eprintln!();
+                eprintln!(
+                    "Defaults: seed=0, x=0, z=0, y-range=full height, step=1, assets=./assets, root=final_density, settings=overworld"
+                );
+                eprintln!();
+                eprintln!(
+                    "With --dot, the graph is evaluated at X:MAX:Z and written to FILE instead of printing values."
+                );
+                std::process::exit(0);
+            }
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    let (functions, noises, settings) = load_all(&assets_path, &settings_name);
+    let router = build_functions(
+        &functions,
+        &noises,
+        &settings,
+        seed,
+        mcrs_protocol::BlockStateId(1),
+        mcrs_protocol::BlockStateId(86),
+    )
+    .unwrap_or_else(|e| panic!("Failed to build noise router: {}", e));
+
+    // `roots()` lists the router outputs in `Root::ALL` order.
+    let all_roots = router.roots();
+    let found = Root::ALL
+        .into_iter()
+        .zip(all_roots.iter())
+        .find(|(_, (name, _))| *name == root_name);
+    let (root, name, idx) = match found {
+        Some((root, (name, idx))) => (root, *name, *idx),
+        None => {
+            eprintln!("Unknown root: {}. Available:", root_name);
+            for (name, _) in &all_roots {
+                eprintln!("  {}", name);
+            }
+            std::process::exit(1);
+        }
+    };
+
+    let (min_y, max_y) = y_range.unwrap_or_else(|| {
+        let min_y = router.noise_min_y();
+        (min_y, min_y + router.noise_height() as i32 - 1)
+    });
+
+    eprintln!(
+        "Seed: {}, Column: {}:{}, Y: {}..={}, Root: {}",
+        seed, x, z, min_y, max_y, name
+    );
+
+    if let Some(path) = dot_path {
+        let dot = router.dump_dot_graph(name, idx, IVec3::new(x, max_y, z));
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+        std::fs::write(&path, &dot)
+            .unwrap_or_else(|e| panic!("Failed to write {}: {}", path.display(), e));
+        eprintln!("Written to {}", path.display());
+        return;
+    }
+
+    let mut cache = router.new_cache();
+    for y in (min_y..=max_y).rev().step_by(step) {
+        let value = router.sample_root(root, IVec3::new(x, y, z), &mut cache);
+        println!("{}\t{}", y, value);
+    }
+}
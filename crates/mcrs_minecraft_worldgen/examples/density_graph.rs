@@ -13,134 +13,13 @@
 //!   # Generate combined graphs for all noise settings:
 //!   cargo run --release --example density_graph -p mcrs_minecraft_worldgen -- --settings all --root all --output graphs/
 
+mod common;
+
 use bevy_math::IVec3;
+use common::load_all;
 use mcrs_minecraft_worldgen::density_function::build_functions;
-use mcrs_minecraft_worldgen::density_function::proto::{
-    DensityFunctionHolder, NoiseParam, ProtoDensityFunction,
-};
-use mcrs_minecraft_worldgen::proto::NoiseGeneratorSettings;
-use mcrs_protocol::Ident;
-use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
-fn walk_json_files(
-    base: &Path,
-    dir: &Path,
-    namespace: &str,
-    out: &mut Vec<(Ident<String>, Vec<u8>)>,
-) {
-    let Ok(entries) = std::fs::read_dir(dir) else {
-        return;
-    };
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_dir() {
-            walk_json_files(base, &path, namespace, out);
-        } else if path.extension().is_some_and(|e| e == "json") {
-            let rel = path.strip_prefix(base).unwrap();
-            let name = rel.with_extension("").to_string_lossy().replace('\\', "/");
-            let ident_str = format!("{}:{}", namespace, name);
-            if let Ok(ident) = Ident::new(ident_str) {
-                let data = std::fs::read(&path).unwrap();
-                out.push((ident.into(), data));
-            }
-        }
-    }
-}
-
-fn resolve_holder(
-    id: &Ident<String>,
-    holder: &DensityFunctionHolder,
-    all: &BTreeMap<Ident<String>, DensityFunctionHolder>,
-    out: &mut BTreeMap<Ident<String>, ProtoDensityFunction>,
-) {
-    if out.contains_key(id) {
-        return;
-    }
-    match holder {
-        DensityFunctionHolder::Value(v) => {
-            out.insert(id.clone(), ProtoDensityFunction::Constant(v.clone()));
-        }
-        DensityFunctionHolder::Reference(r) => {
-            if let Some(dep) = all.get(r) {
-                resolve_holder(id, dep, all, out);
-            }
-        }
-        DensityFunctionHolder::Owned(proto) => {
-            out.insert(id.clone(), *proto.clone());
-        }
-    }
-}
-
-fn load_all(
-    assets_path: &Path,
-    settings_name: &str,
-) -> (
-    BTreeMap<Ident<String>, ProtoDensityFunction>,
-    BTreeMap<Ident<String>, NoiseParam>,
-    NoiseGeneratorSettings,
-) {
-    // Load noise settings
-    let settings_path = assets_path.join(format!(
-        "minecraft/worldgen/noise_settings/{}.json",
-        settings_name
-    ));
-    let settings_data = std::fs::read(&settings_path)
-        .unwrap_or_else(|e| panic!("Failed to read {}: {}", settings_path.display(), e));
-    let settings: NoiseGeneratorSettings = serde_json::from_slice(&settings_data)
-        .unwrap_or_else(|e| panic!("Failed to parse noise settings {}: {}", settings_name, e));
-
-    // Load all density function files
-    let df_dir = assets_path.join("minecraft/worldgen/density_function");
-    let mut df_files = Vec::new();
-    walk_json_files(&df_dir, &df_dir, "minecraft", &mut df_files);
-
-    let mut holders: BTreeMap<Ident<String>, DensityFunctionHolder> = BTreeMap::new();
-    for (ident, data) in &df_files {
-        match serde_json::from_slice::<DensityFunctionHolder>(data) {
-            Ok(holder) => {
-                holders.insert(ident.clone(), holder);
-            }
-            Err(e) => {
-                eprintln!("Warning: failed to parse {}: {}", ident, e);
-            }
-        }
-    }
-
-    // Resolve references to proto functions
-    let mut functions: BTreeMap<Ident<String>, ProtoDensityFunction> = BTreeMap::new();
-    let holders_snapshot = holders.clone();
-    for (ident, holder) in &holders_snapshot {
-        resolve_holder(ident, holder, &holders_snapshot, &mut functions);
-    }
-
-    // Load all noise files
-    let noise_dir = assets_path.join("minecraft/worldgen/noise");
-    let mut noise_files = Vec::new();
-    walk_json_files(&noise_dir, &noise_dir, "minecraft", &mut noise_files);
-
-    let mut noises: BTreeMap<Ident<String>, NoiseParam> = BTreeMap::new();
-    for (ident, data) in &noise_files {
-        match serde_json::from_slice::<NoiseParam>(data) {
-            Ok(noise) => {
-                noises.insert(ident.clone(), noise);
-            }
-            Err(e) => {
-                eprintln!("Warning: failed to parse noise {}: {}", ident, e);
-            }
-        }
-    }
-
-    eprintln!(
-        "Loaded {} density functions, {} noises (settings: {})",
-        functions.len(),
-        noises.len(),
-        settings_name
-    );
-
-    (functions, noises, settings)
-}
-
 fn list_noise_settings(assets_path: &Path) -> Vec<String> {
     let dir = assets_path.join("minecraft/worldgen/noise_settings");
     let mut names = Vec::new();
@@ -167,7 +46,8 @@ fn generate_graph(
     output_path: Option<&Path>,
 ) {
     let (functions, noises, settings) = load_all(assets_path, settings_name);
-    let router = build_functions(&functions, &noises, &settings, seed, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86));
+    let router = build_functions(&functions, &noises, &settings, seed, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86))
+        .unwrap_or_else(|e| panic!("Failed to build noise router: {}", e));
 
     // Cross-validate column cache against reference forward sweep
     let y_values: Vec<i32> = (-64..=320).step_by(16).collect();
@@ -0,0 +1,677 @@
+//! Port of vanilla `Aquifer` / `NoiseBasedAquifer`.
+//!
+//! Decides what fills a non-solid position once the noise router has computed
+//! its final density: air, the sea fluid, lava, or a local aquifer whose level
+//! comes from the router's `fluid_level_floodedness`, `fluid_level_spread` and
+//! `lava` entries. The `barrier` entry keeps neighbouring aquifers with
+//! different levels apart by turning the boundary between them back into
+//! terrain.
+
+use crate::density_function::{DensityCache, NoiseRouter, Root};
+use crate::math::{clamped_map, map};
+use bevy_math::IVec3;
+use mcrs_protocol::BlockStateId;
+use mcrs_random::{Random, RandomSource};
+use std::collections::HashMap;
+
+/// Vanilla `DimensionType.WAY_BELOW_MIN_Y`: fluid level of an aquifer that
+/// holds no fluid at all.
+pub const WAY_BELOW_MIN_Y: i32 = -2032 << 4;
+
+/// Level of the global lava sea that fills everything below it.
+pub const LAVA_LEVEL: i32 = -54;
+
+const AIR: BlockStateId = BlockStateId(0);
+
+const X_RANGE: i32 = 10;
+const Y_RANGE: i32 = 9;
+const Z_RANGE: i32 = 10;
+const X_SPACING: i32 = 16;
+const Y_SPACING: i32 = 12;
+const Z_SPACING: i32 = 16;
+
+/// `similarity(10², 12²)`: aquifer pairs closer than this are candidates for a
+/// scheduled fluid update.
+const FLOWING_UPDATE_SIMILARITY: f64 = 1.0 - (144 - 100) as f64 / 25.0;
+
+/// Chunk offsets sampled for the preliminary surface around an aquifer center.
+const SURFACE_SAMPLING_OFFSETS_IN_CHUNKS: [[i32; 2]; 13] = [
+    [0, 0],
+    [-2, -1],
+    [-1, -1],
+    [0, -1],
+    [1, -1],
+    [-3, 0],
+    [-2, 0],
+    [-1, 0],
+    [1, 0],
+    [-2, 1],
+    [-1, 1],
+    [0, 1],
+    [1, 1],
+];
+
+/// A fluid filling everything strictly below `fluid_level`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FluidStatus {
+    pub fluid_level: i32,
+    pub fluid_type: BlockStateId,
+}
+
+impl FluidStatus {
+    pub fn new(fluid_level: i32, fluid_type: BlockStateId) -> Self {
+        Self {
+            fluid_level,
+            fluid_type,
+        }
+    }
+
+    /// The block this fluid puts at `y`: the fluid below its level, air above.
+    pub fn at(&self, y: i32) -> BlockStateId {
+        if y < self.fluid_level {
+            self.fluid_type
+        } else {
+            AIR
+        }
+    }
+}
+
+/// Vanilla `NoiseBasedChunkGenerator.createFluidPicker`: lava below
+/// [`LAVA_LEVEL`] (or the sea level, whichever is lower) and the default fluid
+/// up to the sea level everywhere else.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FluidPicker {
+    pub lava: FluidStatus,
+    pub sea: FluidStatus,
+}
+
+impl FluidPicker {
+    pub fn new(router: &NoiseRouter, lava_state: BlockStateId) -> Self {
+        Self {
+            lava: FluidStatus::new(LAVA_LEVEL, lava_state),
+            sea: FluidStatus::new(router.sea_level(), router.default_fluid_state()),
+        }
+    }
+
+    pub fn compute_fluid(&self, _x: i32, y: i32, _z: i32) -> FluidStatus {
+        if y < LAVA_LEVEL.min(self.sea.fluid_level) {
+            self.lava
+        } else {
+            self.sea
+        }
+    }
+}
+
+/// Aquifer state for one chunk column.
+///
+/// Aquifer centers and their fluid levels are cached per grid cell, so reuse
+/// one `Aquifer` for every position of the chunk it was created for.
+pub struct Aquifer {
+    fluid_picker: FluidPicker,
+    /// `None` when the noise settings disable aquifers; only the global
+    /// fluid picker is consulted then.
+    grid: Option<AquiferGrid>,
+    should_schedule_fluid_update: bool,
+}
+
+struct AquiferGrid {
+    random: RandomSource,
+    min_grid_x: i32,
+    min_grid_y: i32,
+    min_grid_z: i32,
+    grid_size_x: i32,
+    grid_size_z: i32,
+    location_cache: Vec<Option<IVec3>>,
+    status_cache: Vec<Option<FluidStatus>>,
+    /// Preliminary surface level per quart-aligned column.
+    surface_cache: HashMap<(i32, i32), i32>,
+}
+
+impl Aquifer {
+    /// Create the aquifer for the chunk whose minimum block corner is
+    /// (`base_block_x`, `base_block_z`).
+    pub fn new(
+        router: &NoiseRouter,
+        base_block_x: i32,
+        base_block_z: i32,
+        lava_state: BlockStateId,
+    ) -> Self {
+        let fluid_picker = FluidPicker::new(router, lava_state);
+        if !router.aquifers_enabled() {
+            return Self {
+                fluid_picker,
+                grid: None,
+                should_schedule_fluid_update: false,
+            };
+        }
+
+        let min_y = router.noise_min_y();
+        let height = router.noise_height() as i32;
+        let min_grid_x = grid_x(base_block_x) - 1;
+        let max_grid_x = grid_x(base_block_x + 15) + 1;
+        let min_grid_y = grid_y(min_y) - 1;
+        let max_grid_y = grid_y(min_y + height) + 1;
+        let min_grid_z = grid_z(base_block_z) - 1;
+        let max_grid_z = grid_z(base_block_z + 15) + 1;
+        let grid_size_x = max_grid_x - min_grid_x + 1;
+        let grid_size_y = max_grid_y - min_grid_y + 1;
+        let grid_size_z = max_grid_z - min_grid_z + 1;
+        let len = (grid_size_x * grid_size_y * grid_size_z) as usize;
+
+        Self {
+            fluid_picker,
+            grid: Some(AquiferGrid {
+                random: router.aquifer_random().clone(),
+                min_grid_x,
+                min_grid_y,
+                min_grid_z,
+                grid_size_x,
+                grid_size_z,
+                location_cache: vec![None; len],
+                status_cache: vec![None; len],
+                surface_cache: HashMap::new(),
+            }),
+            should_schedule_fluid_update: false,
+        }
+    }
+
+    /// Vanilla `computeSubstance`: `None` keeps the terrain block, `Some` is
+    /// the block to place instead (air, water or lava).
+    ///
+    /// `density` is the final density at `pos`; positive values are solid.
+    pub fn compute_substance(
+        &mut self,
+        router: &NoiseRouter,
+        cache: &mut DensityCache,
+        pos: IVec3,
+        density: f64,
+    ) -> Option<BlockStateId> {
+        if density > 0.0 {
+            self.should_schedule_fluid_update = false;
+            return None;
+        }
+        let global = self.fluid_picker.compute_fluid(pos.x, pos.y, pos.z);
+        let Some(grid) = self.grid.as_mut() else {
+            self.should_schedule_fluid_update = false;
+            return Some(global.at(pos.y));
+        };
+        if global.at(pos.y) == self.fluid_picker.lava.fluid_type {
+            self.should_schedule_fluid_update = false;
+            return Some(self.fluid_picker.lava.fluid_type);
+        }
+
+        let (x, y, z) = (pos.x, pos.y, pos.z);
+        let base_x = (x - 5).div_euclid(X_SPACING);
+        let base_y = (y + 1).div_euclid(Y_SPACING);
+        let base_z = (z - 5).div_euclid(Z_SPACING);
+
+        // The four nearest aquifer centers, closest first.
+        let mut dist = [i32::MAX; 4];
+        let mut center = [IVec3::ZERO; 4];
+        for dx in 0..=1 {
+            for dy in -1..=1 {
+                for dz in 0..=1 {
+                    let location = grid.location(base_x + dx, base_y + dy, base_z + dz);
+                    let d = location - pos;
+                    let d = d.x * d.x + d.y * d.y + d.z * d.z;
+                    if dist[0] >= d {
+                        dist = [d, dist[0], dist[1], dist[2]];
+                        center = [location, center[0], center[1], center[2]];
+                    } else if dist[1] >= d {
+                        dist = [dist[0], d, dist[1], dist[2]];
+                        center = [center[0], location, center[1], center[2]];
+                    } else if dist[2] >= d {
+                        dist = [dist[0], dist[1], d, dist[2]];
+                        center = [center[0], center[1], location, center[2]];
+                    } else if dist[3] >= d {
+                        dist[3] = d;
+                        center[3] = location;
+                    }
+                }
+            }
+        }
+
+        let picker = self.fluid_picker;
+        let status0 = grid.status(router, cache, &picker, center[0]);
+        let similarity01 = similarity(dist[0], dist[1]);
+        let block = status0.at(y);
+        if similarity01 <= 0.0 {
+            self.should_schedule_fluid_update = similarity01 >= FLOWING_UPDATE_SIMILARITY
+                && status0 != grid.status(router, cache, &picker, center[1]);
+            return Some(block);
+        }
+        if block == picker.sea.fluid_type
+            && block != picker.lava.fluid_type
+            && picker.compute_fluid(x, y - 1, z).at(y - 1) == picker.lava.fluid_type
+        {
+            self.should_schedule_fluid_update = true;
+            return Some(block);
+        }
+
+        let mut barrier = None;
+        let status1 = grid.status(router, cache, &picker, center[1]);
+        let pressure = similarity01
+            * calculate_pressure(router, cache, &picker, pos, &mut barrier, status0, status1);
+        if density + pressure > 0.0 {
+            self.should_schedule_fluid_update = false;
+            return None;
+        }
+
+        let status2 = grid.status(router, cache, &picker, center[2]);
+        let similarity02 = similarity(dist[0], dist[2]);
+        if similarity02 > 0.0 {
+            let pressure = similarity01
+                * similarity02
+                * calculate_pressure(router, cache, &picker, pos, &mut barrier, status0, status2);
+            if density + pressure > 0.0 {
+                self.should_schedule_fluid_update = false;
+                return None;
+            }
+        }
+        let similarity12 = similarity(dist[1], dist[2]);
+        if similarity12 > 0.0 {
+            let pressure = similarity01
+                * similarity12
+                * calculate_pressure(router, cache, &picker, pos, &mut barrier, status1, status2);
+            if density + pressure > 0.0 {
+                self.should_schedule_fluid_update = false;
+                return None;
+            }
+        }
+
+        let differs01 = status0 != status1;
+        let differs12 = similarity12 >= FLOWING_UPDATE_SIMILARITY && status1 != status2;
+        let differs02 = similarity02 >= FLOWING_UPDATE_SIMILARITY && status0 != status2;
+        self.should_schedule_fluid_update = if differs01 || differs12 || differs02 {
+            true
+        } else {
+            similarity02 >= FLOWING_UPDATE_SIMILARITY
+                && similarity(dist[0], dist[3]) >= FLOWING_UPDATE_SIMILARITY
+                && status0 != grid.status(router, cache, &picker, center[3])
+        };
+        Some(block)
+    }
+
+    /// Whether the last [`compute_substance`](Self::compute_substance) call
+    /// placed fluid next to a differing aquifer, so it should be ticked to
+    /// let it flow.
+    pub fn should_schedule_fluid_update(&self) -> bool {
+        self.should_schedule_fluid_update
+    }
+}
+
+impl AquiferGrid {
+    fn index(&self, gx: i32, gy: i32, gz: i32) -> usize {
+        let x = gx - self.min_grid_x;
+        let y = gy - self.min_grid_y;
+        let z = gz - self.min_grid_z;
+        ((y * self.grid_size_z + z) * self.grid_size_x + x) as usize
+    }
+
+    /// Randomized aquifer center of a grid cell.
+    fn location(&mut self, gx: i32, gy: i32, gz: i32) -> IVec3 {
+        let index = self.index(gx, gy, gz);
+        if let Some(location) = self.location_cache[index] {
+            return location;
+        }
+        let mut random = self.random.clone().fork_at(IVec3::new(gx, gy, gz));
+        let location = IVec3::new(
+            gx * X_SPACING + random.next_i32_bound(X_RANGE),
+            gy * Y_SPACING + random.next_i32_bound(Y_RANGE),
+            gz * Z_SPACING + random.next_i32_bound(Z_RANGE),
+        );
+        self.location_cache[index] = Some(location);
+        location
+    }
+
+    fn status(
+        &mut self,
+        router: &NoiseRouter,
+        cache: &mut DensityCache,
+        picker: &FluidPicker,
+        center: IVec3,
+    ) -> FluidStatus {
+        let index = self.index(grid_x(center.x), grid_y(center.y), grid_z(center.z));
+        if let Some(status) = self.status_cache[index] {
+            return status;
+        }
+        let status = self.compute_fluid(router, cache, picker, center);
+        self.status_cache[index] = Some(status);
+        status
+    }
+
+    fn compute_fluid(
+        &mut self,
+        router: &NoiseRouter,
+        cache: &mut DensityCache,
+        picker: &FluidPicker,
+        center: IVec3,
+    ) -> FluidStatus {
+        let (x, y, z) = (center.x, center.y, center.z);
+        let global = picker.compute_fluid(x, y, z);
+        let mut min_surface_level = i32::MAX;
+        let top = y + 12;
+        let bottom = y - 12;
+        let mut fluid_present = false;
+
+        for [chunk_dx, chunk_dz] in SURFACE_SAMPLING_OFFSETS_IN_CHUNKS {
+            let sx = x + (chunk_dx << 4);
+            let sz = z + (chunk_dz << 4);
+            let surface = self.preliminary_surface_level(router, cache, sx, sz);
+            let max_fluid_y = surface + 8;
+            let is_center = chunk_dx == 0 && chunk_dz == 0;
+            if is_center && bottom > max_fluid_y {
+                return global;
+            }
+
+            let above_surface = top > max_fluid_y;
+            if above_surface || is_center {
+                let surface_fluid = picker.compute_fluid(sx, max_fluid_y, sz);
+                if !surface_fluid.at(max_fluid_y).is_air() {
+                    if is_center {
+                        fluid_present = true;
+                    }
+                    if above_surface {
+                        return surface_fluid;
+                    }
+                }
+            }
+            min_surface_level = min_surface_level.min(surface);
+        }
+
+        let level = compute_surface_level(
+            router,
+            cache,
+            center,
+            global,
+            min_surface_level,
+            fluid_present,
+        );
+        FluidStatus::new(
+            level,
+            compute_fluid_type(router, cache, picker, center, global, level),
+        )
+    }
+
+    fn preliminary_surface_level(
+        &mut self,
+        router: &NoiseRouter,
+        cache: &mut DensityCache,
+        x: i32,
+        z: i32,
+    ) -> i32 {
+        let x = x & !3;
+        let z = z & !3;
+        *self.surface_cache.entry((x, z)).or_insert_with(|| {
+            router
+                .sample_root(Root::PreliminarySurfaceLevel, IVec3::new(x, 0, z), cache)
+                .floor() as i32
+        })
+    }
+}
+
+fn grid_x(x: i32) -> i32 {
+    x.div_euclid(X_SPACING)
+}
+
+fn grid_y(y: i32) -> i32 {
+    y.div_euclid(Y_SPACING)
+}
+
+fn grid_z(z: i32) -> i32 {
+    z.div_euclid(Z_SPACING)
+}
+
+/// How alike two squared distances are: 1 when equal, falling below 0 once
+/// they differ by more than 25.
+fn similarity(first: i32, second: i32) -> f64 {
+    1.0 - (second - first).abs() as f64 / 25.0
+}
+
+/// Extra density pushing the boundary between two aquifers back to terrain.
+/// `barrier` memoizes the barrier noise at `pos` across calls.
+fn calculate_pressure(
+    router: &NoiseRouter,
+    cache: &mut DensityCache,
+    picker: &FluidPicker,
+    pos: IVec3,
+    barrier: &mut Option<f64>,
+    first: FluidStatus,
+    second: FluidStatus,
+) -> f64 {
+    let y = pos.y;
+    let first_block = first.at(y);
+    let second_block = second.at(y);
+    let (lava, water) = (picker.lava.fluid_type, picker.sea.fluid_type);
+    if first_block != second_block
+        && (first_block == lava || first_block == water)
+        && (second_block == lava || second_block == water)
+    {
+        // Lava meeting water at this height always stays separated.
+        return 2.0;
+    }
+
+    let level_diff = (first.fluid_level - second.fluid_level).abs();
+    if level_diff == 0 {
+        return 0.0;
+    }
+    let mid_level = 0.5 * (first.fluid_level + second.fluid_level) as f64;
+    let offset = y as f64 + 0.5 - mid_level;
+    let half_diff = level_diff as f64 / 2.0;
+    let distance = half_diff - offset.abs();
+    let gradient = if offset > 0.0 {
+        if distance > 0.0 {
+            distance / 1.5
+        } else {
+            distance / 2.5
+        }
+    } else {
+        let distance = 3.0 + distance;
+        if distance > 0.0 {
+            distance / 3.0
+        } else {
+            distance / 10.0
+        }
+    };
+
+    let barrier = if (-2.0..=2.0).contains(&gradient) {
+        *barrier.get_or_insert_with(|| router.sample_root(Root::Barrier, pos, cache) as f64)
+    } else {
+        0.0
+    };
+    2.0 * (barrier + gradient)
+}
+
+/// Vanilla `isDeepDarkRegion`: deep, heavily eroded terrain where aquifers
+/// never flood, so ancient cities stay dry.
+fn is_deep_dark_region(router: &NoiseRouter, cache: &mut DensityCache, pos: IVec3) -> bool {
+    router.sample_root(Root::Erosion, pos, cache) < -0.225
+        && router.sample_root(Root::Depth, pos, cache) > 0.9
+}
+
+fn compute_surface_level(
+    router: &NoiseRouter,
+    cache: &mut DensityCache,
+    center: IVec3,
+    global: FluidStatus,
+    max_surface_level: i32,
+    fluid_present: bool,
+) -> i32 {
+    let (partially_flooded, fully_flooded) = if is_deep_dark_region(router, cache, center) {
+        (-1.0, -1.0)
+    } else {
+        let distance_below_surface = max_surface_level + 8 - center.y;
+        let f = if fluid_present {
+            clamped_map(distance_below_surface as f64, 0.0, 64.0, 1.0, 0.0)
+        } else {
+            0.0
+        };
+        let floodedness = (router.sample_root(Root::FluidLevelFloodedness, center, cache) as f64)
+            .clamp(-1.0, 1.0);
+        let full_threshold = map(f, 1.0, 0.0, -0.3, 0.8);
+        let partial_threshold = map(f, 1.0, 0.0, -0.8, 0.4);
+        (
+            floodedness - partial_threshold,
+            floodedness - full_threshold,
+        )
+    };
+
+    if fully_flooded > 0.0 {
+        global.fluid_level
+    } else if partially_flooded > 0.0 {
+        compute_randomized_fluid_surface_level(router, cache, center, max_surface_level)
+    } else {
+        WAY_BELOW_MIN_Y
+    }
+}
+
+fn compute_randomized_fluid_surface_level(
+    router: &NoiseRouter,
+    cache: &mut DensityCache,
+    center: IVec3,
+    max_surface_level: i32,
+) -> i32 {
+    let spread_pos = IVec3::new(
+        center.x.div_euclid(16),
+        center.y.div_euclid(40),
+        center.z.div_euclid(16),
+    );
+    let middle = spread_pos.y * 40 + 20;
+    let spread = router.sample_root(Root::FluidLevelSpread, spread_pos, cache) as f64 * 10.0;
+    let quantized = (spread / 3.0).floor() as i32 * 3;
+    max_surface_level.min(middle + quantized)
+}
+
+/// Deep aquifers turn to lava in patches picked by the router's `lava` entry.
+fn compute_fluid_type(
+    router: &NoiseRouter,
+    cache: &mut DensityCache,
+    picker: &FluidPicker,
+    center: IVec3,
+    global: FluidStatus,
+    surface_level: i32,
+) -> BlockStateId {
+    if surface_level <= -10
+        && surface_level != WAY_BELOW_MIN_Y
+        && global.fluid_type != picker.lava.fluid_type
+    {
+        let lava_pos = IVec3::new(
+            center.x.div_euclid(64),
+            center.y.div_euclid(40),
+            center.z.div_euclid(64),
+        );
+        let noise = router.sample_root(Root::Lava, lava_pos, cache) as f64;
+        if noise.abs() > 0.3 {
+            return picker.lava.fluid_type;
+        }
+    }
+    global.fluid_type
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::density_function::tests::{overworld_router_with, overworld_settings};
+
+    const WATER: BlockStateId = BlockStateId(86);
+    const LAVA: BlockStateId = BlockStateId(102);
+
+    fn overworld_router(aquifers_enabled: bool) -> NoiseRouter {
+        let mut settings = overworld_settings();
+        settings.aquifers_enabled = aquifers_enabled;
+        overworld_router_with(&settings)
+    }
+
+    #[test]
+    fn fluid_status_fills_strictly_below_level() {
+        let status = FluidStatus::new(63, WATER);
+        assert_eq!(status.at(62), WATER);
+        assert_eq!(status.at(63), AIR);
+        assert_eq!(FluidStatus::new(WAY_BELOW_MIN_Y, WATER).at(-64), AIR);
+    }
+
+    #[test]
+    fn similarity_falls_off_over_25() {
+        assert_eq!(similarity(40, 40), 1.0);
+        assert_eq!(similarity(40, 65), 0.0);
+        assert_eq!(similarity(65, 40), 0.0);
+        assert_eq!(FLOWING_UPDATE_SIMILARITY, similarity(100, 144));
+    }
+
+    #[test]
+    fn disabled_aquifer_uses_global_fluid() {
+        let router = overworld_router(false);
+        let mut cache = router.new_cache();
+        let mut aquifer = Aquifer::new(&router, 0, 0, LAVA);
+        let mut at = |y| aquifer.compute_substance(&router, &mut cache, IVec3::new(3, y, 5), -1.0);
+        assert_eq!(at(62), Some(WATER));
+        assert_eq!(at(63), Some(AIR));
+        assert_eq!(at(-54), Some(WATER));
+        assert_eq!(at(-55), Some(LAVA));
+    }
+
+    #[test]
+    fn solid_density_keeps_terrain() {
+        let router = overworld_router(true);
+        let mut cache = router.new_cache();
+        let mut aquifer = Aquifer::new(&router, 0, 0, LAVA);
+        for y in [-60, 0, 40, 100] {
+            let pos = IVec3::new(7, y, 9);
+            assert_eq!(
+                aquifer.compute_substance(&router, &mut cache, pos, 0.5),
+                None
+            );
+            assert!(!aquifer.should_schedule_fluid_update());
+        }
+    }
+
+    #[test]
+    fn enabled_aquifer_places_lava_sea_and_air() {
+        let router = overworld_router(true);
+        let mut cache = router.new_cache();
+        let mut aquifer = Aquifer::new(&router, 0, 0, LAVA);
+        let mut at = |y| aquifer.compute_substance(&router, &mut cache, IVec3::new(3, y, 5), -1.0);
+        assert_eq!(at(-60), Some(LAVA));
+        assert_eq!(at(300), Some(AIR));
+    }
+
+    /// Centers and levels are cached per grid cell, so the order positions
+    /// are visited in must not change the result.
+    #[test]
+    fn results_do_not_depend_on_visit_order() {
+        let router = overworld_router(true);
+        let positions: Vec<IVec3> = (-64..200)
+            .step_by(3)
+            .flat_map(|y| {
+                [
+                    IVec3::new(0, y, 0),
+                    IVec3::new(15, y, 8),
+                    IVec3::new(6, y, 15),
+                ]
+            })
+            .collect();
+        let sample = |positions: &mut dyn Iterator<Item = &IVec3>| {
+            let mut cache = router.new_cache();
+            let mut aquifer = Aquifer::new(&router, 0, 0, LAVA);
+            let mut out: Vec<(IVec3, Option<BlockStateId>)> = positions
+                .map(|&pos| {
+                    let density = router.sample_root(Root::FinalDensity, pos, &mut cache) as f64;
+                    (
+                        pos,
+                        aquifer.compute_substance(&router, &mut cache, pos, density),
+                    )
+                })
+                .collect();
+            out.sort_by_key(|(pos, _)| (pos.x, pos.y, pos.z));
+            out
+        };
+        let forward = sample(&mut positions.iter());
+        let backward = sample(&mut positions.iter().rev());
+        assert_eq!(forward, backward);
+        assert!(
+            forward
+                .iter()
+                .all(|(_, block)| matches!(block, None | Some(AIR | WATER | LAVA)))
+        );
+    }
+}
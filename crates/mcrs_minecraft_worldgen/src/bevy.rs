@@ -1,7 +1,9 @@
 use crate::density_function::proto::{
     DensityFunctionHolder, NoiseHolder, NoiseParam, ProtoDensityFunction, Visitor,
 };
-use crate::density_function::{NoiseRouter, build_functions};
+#[cfg(feature = "serde")]
+use crate::density_function::router_inputs_hash;
+use crate::density_function::{NoiseRouter, WorldgenError, build_functions};
 use crate::proto::{Either, NoiseGeneratorSettings};
 use bevy_app::{App, Plugin, Startup, Update};
 use bevy_asset::io::Reader;
@@ -15,16 +17,18 @@ use bevy_reflect::TypePath;
 use mcrs_protocol::Ident;
 use std::collections::BTreeMap;
 use std::env;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use thiserror::Error;
-use tracing::info;
+use tracing::{error, info, warn};
 
 /// Configures which world preset to load and the world seed to use for generation.
 ///
 /// Insert this resource before `Startup` so that the noise-router build can
 /// read it. Defaults to the `normal` preset (overworld noise settings) and
 /// seed 0.  Override by setting `MCRS_WORLD_PRESET` and `MCRS_WORLD_SEED`
-/// environment variables.
+/// environment variables, and point `MCRS_ROUTER_CACHE` at a file to reuse the
+/// built router across restarts.
 #[derive(Resource, Clone, Debug)]
 pub struct WorldGenConfig {
     /// The `namespace:path` identifier of the active world preset.
@@ -44,6 +48,10 @@ pub struct WorldGenConfig {
     /// Registry-resolved default fluid state ID (water level 0) for `build_functions`.
     /// Populated by the mcrs_minecraft layer using minecraft::WATER.default_state_id.
     pub default_fluid_state_id: mcrs_protocol::BlockStateId,
+    /// Where the optimized router is saved after a build and loaded from on
+    /// the next start while its inputs are unchanged. Only used with the
+    /// `serde` feature.
+    pub router_cache: Option<PathBuf>,
 }
 
 impl Default for WorldGenConfig {
@@ -56,6 +64,7 @@ impl Default for WorldGenConfig {
             seed: 0,
             default_block_state_id: mcrs_protocol::BlockStateId(1),
             default_fluid_state_id: mcrs_protocol::BlockStateId(86),
+            router_cache: None,
         }
     }
 }
@@ -64,6 +73,7 @@ impl WorldGenConfig {
     /// Build from environment variables:
     /// - `MCRS_WORLD_PRESET`: `"normal"` or `"minecraft:normal"` (default: `"minecraft:normal"`)
     /// - `MCRS_WORLD_SEED`: decimal `u64` (default: `0`)
+    /// - `MCRS_ROUTER_CACHE`: path of the router cache file (default: none)
     pub fn from_env() -> Self {
         let (preset_namespace, preset_path) = match env::var("MCRS_WORLD_PRESET") {
             Ok(raw) => {
@@ -98,6 +108,7 @@ impl WorldGenConfig {
             seed,
             default_block_state_id: mcrs_protocol::BlockStateId(1),
             default_fluid_state_id: mcrs_protocol::BlockStateId(86),
+            router_cache: env::var_os("MCRS_ROUTER_CACHE").map(PathBuf::from),
         }
     }
 
@@ -122,6 +133,17 @@ impl WorldGenConfig {
     }
 }
 
+/// Read the world preset JSON `{preset_ns}:{preset_path}` from disk.
+fn read_world_preset(preset_ns: &str, preset_path: &str) -> Option<serde_json::Value> {
+    let asset_root = env::var("BEVY_ASSET_ROOT").unwrap_or_else(|_| ".".to_string());
+    let json_path = format!(
+        "{}/assets/{}/worldgen/world_preset/{}.json",
+        asset_root, preset_ns, preset_path
+    );
+    let data = std::fs::read_to_string(&json_path).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
 /// Read the world preset JSON from disk and extract the `generator.settings`
 /// id for the `minecraft:overworld` dimension.  Falls back to using the preset
 /// namespace/path unchanged when the file cannot be read or parsed.
@@ -129,22 +151,10 @@ pub(crate) fn resolve_overworld_noise_settings(
     preset_ns: &str,
     preset_path: &str,
 ) -> (Arc<str>, Arc<str>) {
-    let asset_root = env::var("BEVY_ASSET_ROOT").unwrap_or_else(|_| ".".to_string());
-    let json_path = format!(
-        "{}/assets/{}/worldgen/world_preset/{}.json",
-        asset_root, preset_ns, preset_path
-    );
-
     let fallback = || (Arc::from(preset_ns), Arc::from(preset_path));
 
-    let data = match std::fs::read_to_string(&json_path) {
-        Ok(d) => d,
-        Err(_) => return fallback(),
-    };
-
-    let json: serde_json::Value = match serde_json::from_str(&data) {
-        Ok(v) => v,
-        Err(_) => return fallback(),
+    let Some(json) = read_world_preset(preset_ns, preset_path) else {
+        return fallback();
     };
 
     let settings_str = json
@@ -155,17 +165,53 @@ pub(crate) fn resolve_overworld_noise_settings(
         .and_then(|s| s.as_str());
 
     match settings_str {
-        Some(s) => {
-            if let Some(colon) = s.find(':') {
-                (Arc::from(&s[..colon]), Arc::from(&s[colon + 1..]))
-            } else {
-                (Arc::from("minecraft"), Arc::from(s))
-            }
-        }
+        Some(s) => split_id(s),
         None => fallback(),
     }
 }
 
+/// Read the world preset JSON from disk and resolve the sea level of
+/// `dimension`'s chunk generator, as vanilla `ChunkGenerator.getSeaLevel`
+/// reports it: the noise settings' `sea_level` for a noise generator, `-63`
+/// for a flat one. `None` when the preset, the dimension or its noise
+/// settings cannot be read, or for any other generator.
+pub fn resolve_sea_level(preset_ns: &str, preset_path: &str, dimension: &str) -> Option<i32> {
+    let json = read_world_preset(preset_ns, preset_path)?;
+    let generator = json.get("dimensions")?.get(dimension)?.get("generator")?;
+    match generator.get("type")?.as_str()? {
+        "minecraft:noise" => {}
+        "minecraft:flat" => return Some(-63),
+        _ => return None,
+    }
+    let settings = generator.get("settings")?;
+    let sea_level = match settings.as_str() {
+        Some(id) => {
+            let (namespace, path) = split_id(id);
+            let asset_root = env::var("BEVY_ASSET_ROOT").unwrap_or_else(|_| ".".to_string());
+            let json_path = format!(
+                "{}/assets/{}/worldgen/noise_settings/{}.json",
+                asset_root, namespace, path
+            );
+            let data = std::fs::read_to_string(&json_path).ok()?;
+            serde_json::from_str::<serde_json::Value>(&data)
+                .ok()?
+                .get("sea_level")?
+                .as_i64()?
+        }
+        None => settings.get("sea_level")?.as_i64()?,
+    };
+    i32::try_from(sea_level).ok()
+}
+
+/// Split a `namespace:path` id, defaulting the namespace to `minecraft`.
+fn split_id(id: &str) -> (Arc<str>, Arc<str>) {
+    if let Some(colon) = id.find(':') {
+        (Arc::from(&id[..colon]), Arc::from(&id[colon + 1..]))
+    } else {
+        (Arc::from("minecraft"), Arc::from(id))
+    }
+}
+
 pub struct NoiseGeneratorSettingsPlugin;
 
 impl Plugin for NoiseGeneratorSettingsPlugin {
@@ -341,21 +387,105 @@ fn build_noise_router_on_load(
                         mcrs_protocol::BlockStateId(1),
                         mcrs_protocol::BlockStateId(86),
                     ));
-                let overworld = OverworldNoiseRouter(Arc::new(build_functions(
+                let router_cache = world_gen_config
+                    .as_ref()
+                    .and_then(|c| c.router_cache.as_deref());
+                match load_or_build_router(
+                    router_cache,
                     &functions_proto,
                     &noises_proto,
                     &settings.settings,
                     seed,
                     default_block,
                     default_fluid,
-                )));
-                commands.insert_resource(overworld);
+                ) {
+                    Ok(router) => {
+                        commands.insert_resource(OverworldNoiseRouter(Arc::new(router)));
+                    }
+                    Err(err) => {
+                        error!(
+                            noise_settings = %noise_settings_id,
+                            "Failed to build OverworldNoiseRouter: {err}"
+                        );
+                    }
+                }
             }
         }
         _ => {}
     });
 }
 
+/// Build the router, or load it from `cache` when the file there was written
+/// from the same inputs. A fresh build is written back to `cache`.
+#[cfg(feature = "serde")]
+fn load_or_build_router(
+    cache: Option<&Path>,
+    functions: &BTreeMap<Ident<String>, ProtoDensityFunction>,
+    noises: &BTreeMap<Ident<String>, NoiseParam>,
+    settings: &NoiseGeneratorSettings,
+    seed: u64,
+    default_block: mcrs_protocol::BlockStateId,
+    default_fluid: mcrs_protocol::BlockStateId,
+) -> Result<NoiseRouter, WorldgenError> {
+    let Some(cache) = cache else {
+        return build_functions(
+            functions,
+            noises,
+            settings,
+            seed,
+            default_block,
+            default_fluid,
+        );
+    };
+    let inputs_hash = router_inputs_hash(
+        functions,
+        noises,
+        settings,
+        seed,
+        default_block,
+        default_fluid,
+    );
+    if let Some(router) = std::fs::read(cache)
+        .ok()
+        .and_then(|bytes| NoiseRouter::from_bytes(&bytes, inputs_hash))
+    {
+        info!(path = %cache.display(), "Loaded OverworldNoiseRouter from cache");
+        return Ok(router);
+    }
+    let router = build_functions(
+        functions,
+        noises,
+        settings,
+        seed,
+        default_block,
+        default_fluid,
+    )?;
+    if let Err(err) = std::fs::write(cache, router.to_bytes(inputs_hash)) {
+        warn!(path = %cache.display(), "Failed to write the router cache: {err}");
+    }
+    Ok(router)
+}
+
+#[cfg(not(feature = "serde"))]
+fn load_or_build_router(
+    _cache: Option<&Path>,
+    functions: &BTreeMap<Ident<String>, ProtoDensityFunction>,
+    noises: &BTreeMap<Ident<String>, NoiseParam>,
+    settings: &NoiseGeneratorSettings,
+    seed: u64,
+    default_block: mcrs_protocol::BlockStateId,
+    default_fluid: mcrs_protocol::BlockStateId,
+) -> Result<NoiseRouter, WorldgenError> {
+    build_functions(
+        functions,
+        noises,
+        settings,
+        seed,
+        default_block,
+        default_fluid,
+    )
+}
+
 #[derive(TypePath, Debug)]
 pub struct NoiseGeneratorSettingsAsset {
     pub settings: NoiseGeneratorSettings,
@@ -562,7 +692,7 @@ impl<'a, 'b> Visitor for DensityFunctionVisitor<'a, 'b> {
 
 #[cfg(test)]
 mod tests {
-    use super::resolve_overworld_noise_settings;
+    use super::{resolve_overworld_noise_settings, resolve_sea_level};
 
     /// Verify that the normal preset resolves its overworld noise settings to
     /// `minecraft:overworld` (not `minecraft:normal`), by reading the actual
@@ -582,4 +712,16 @@ mod tests {
         assert_eq!(ns.as_ref(), "minecraft");
         assert_eq!(path.as_ref(), "beta");
     }
+
+    /// Each dimension reports its own generator's sea level.
+    #[test]
+    fn sea_level_follows_the_dimension_generator() {
+        let sea_level = |preset, dimension| resolve_sea_level("minecraft", preset, dimension);
+        assert_eq!(sea_level("normal", "minecraft:overworld"), Some(63));
+        assert_eq!(sea_level("normal", "minecraft:the_nether"), Some(32));
+        assert_eq!(sea_level("normal", "minecraft:the_end"), Some(0));
+        assert_eq!(sea_level("beta", "minecraft:overworld"), Some(64));
+        assert_eq!(sea_level("flat", "minecraft:overworld"), Some(-63));
+        assert_eq!(sea_level("normal", "test:overworld"), None);
+    }
 }
@@ -1,4 +1,6 @@
+use crate::density_function::{BiomeColumnCache, DensityCache, NoiseRouter, Root};
 use crate::proto::Interval;
+use bevy_math::IVec3;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
@@ -87,7 +89,9 @@ impl From<ParamPoint> for [Param; 7] {
     }
 }
 
-struct TargetPoint {
+/// A sampled point in climate space, compared against [`ParamPoint`] ranges.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct TargetPoint {
     temperature: QuantizedCoord,
     humidity: QuantizedCoord,
     continentalness: QuantizedCoord,
@@ -98,7 +102,7 @@ struct TargetPoint {
 
 impl TargetPoint {
     #[inline]
-    fn new<Q>(
+    pub fn new<Q>(
         temperature: Q,
         humidity: Q,
         continentalness: Q,
@@ -207,6 +211,7 @@ impl From<Param> for Interval<QuantizedCoord> {
     }
 }
 
+#[derive(Clone, Debug)]
 struct RTree<T>
 where
     T: Clone + PartialEq,
@@ -387,7 +392,10 @@ where
             .as_ref()
             .iter()
             .zip(self.parameter_spec())
-            .map(|(v, p)| p.distance(*v))
+            .map(|(v, p)| {
+                let d = p.distance(*v);
+                d * d
+            })
             .sum()
     }
 
@@ -398,7 +406,7 @@ where
         match &self {
             RNode::Leaf { .. } => Some(self),
             RNode::SubTree(subtree) => {
-                let mut dist = i64::MAX;
+                let mut dist = leaf.map_or(i64::MAX, |l| l.distance(values));
                 for node in &subtree.children {
                     let d1 = node.distance(values);
                     if dist > d1 {
@@ -420,9 +428,81 @@ where
     }
 }
 
+/// Climate parameter space mapped to values (vanilla `Climate.ParameterList`).
+///
+/// Lookups return the entry with the smallest squared distance to the target,
+/// using the same R-tree layout and pruning as vanilla so ties resolve identically.
+#[derive(Clone, Debug)]
+pub struct ParameterList<T>
+where
+    T: Clone + PartialEq,
+{
+    tree: RTree<T>,
+}
+
+impl<T> ParameterList<T>
+where
+    T: Clone + PartialEq + Debug,
+{
+    /// Returns `None` for an empty list, which has nothing to search.
+    pub fn new<I: IntoIterator<Item = (ParamPoint, T)>>(points: I) -> Option<Self> {
+        let points: Vec<_> = points.into_iter().collect();
+        if points.is_empty() {
+            return None;
+        }
+        Some(ParameterList {
+            tree: RTree::new(points),
+        })
+    }
+
+    pub fn find_value(&self, target: &TargetPoint) -> &T {
+        self.tree
+            .search(target)
+            .expect("non-empty parameter list always has a nearest entry")
+    }
+}
+
+/// Sample the six climate parameters at a biome-grid (quart) position, like
+/// vanilla `Climate.Sampler`: the router is evaluated at the quart's block corner.
+pub fn sample_climate(router: &NoiseRouter, quart_pos: IVec3, cache: &mut DensityCache) -> TargetPoint {
+    let pos = quart_pos * 4;
+    // Vanilla quantizes in float arithmetic: (long) (value * 10000.0F).
+    let mut sample = |root| QuantizedCoord((router.sample_root(root, pos, cache) * 10000.0) as i64);
+    TargetPoint {
+        temperature: sample(Root::Temperature),
+        humidity: sample(Root::Vegetation),
+        continentalness: sample(Root::Continents),
+        erosion: sample(Root::Erosion),
+        depth: sample(Root::Depth),
+        weirdness: sample(Root::Ridges),
+    }
+}
+
+/// [`sample_climate`] from the column pass stored in a [`BiomeColumnCache`],
+/// for biome placement over a chunk's 4x4 quart columns. Gives the same point
+/// as [`sample_climate`].
+pub fn sample_climate_from_biome_cache(
+    router: &NoiseRouter,
+    quart_pos: IVec3,
+    cache: &mut BiomeColumnCache,
+) -> TargetPoint {
+    let mut sample = |root| {
+        let value = router.sample_root_from_biome_cache(root, quart_pos, cache);
+        QuantizedCoord((value * 10000.0) as i64)
+    };
+    TargetPoint {
+        temperature: sample(Root::Temperature),
+        humidity: sample(Root::Vegetation),
+        continentalness: sample(Root::Continents),
+        erosion: sample(Root::Erosion),
+        depth: sample(Root::Depth),
+        weirdness: sample(Root::Ridges),
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::climate::{ParamPoint, RTree, TargetPoint};
+    use crate::climate::{ParamPoint, ParameterList, RTree, TargetPoint};
 
     #[test]
     fn search_test() {
@@ -540,4 +620,20 @@ mod test {
             "pink"
         );
     }
+
+    #[test]
+    fn parameter_list_uses_squared_distance() {
+        // Linear distance would pick "far" (0.5 < 0.3 + 0.3); vanilla squares each
+        // axis, which favours "near" (0.18 < 0.25).
+        let list = ParameterList::new([
+            (ParamPoint::new(0.3, 0.3, 0.0, 0.0, 0.0, 0.0, 0), "near"),
+            (ParamPoint::new(0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0), "far"),
+        ])
+        .unwrap();
+        assert_eq!(
+            *list.find_value(&TargetPoint::new(0.0, 0.0, 0.0, 0.0, 0.0, 0.0)),
+            "near"
+        );
+        assert!(ParameterList::<&str>::new([]).is_none());
+    }
 }
@@ -17,6 +17,7 @@ pub const CD_GRID: usize = CD_LL * CD_B2 * CD_LL; // 5 * 17 * 5 = 425
 ///   this.m           (selector, 8 octaves)          → d array
 ///   this.a           (scale, 10 octaves)             → g array
 ///   this.b           (depth, 16 octaves)             → h array
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BetaTerrainF64 {
     low:      OctavePerlinNoise<f64>, // this.k (e)
     high:     OctavePerlinNoise<f64>, // this.l (f)
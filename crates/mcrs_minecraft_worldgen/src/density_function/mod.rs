@@ -4,27 +4,36 @@ use crate::density_function::proto::{
 };
 use crate::noise::normal_noise::NoiseSampler;
 use crate::noise::octave_perlin_noise::OctavePerlinNoise;
+use crate::noise::simplex::SimplexNoise;
 use crate::proto::NoiseGeneratorSettings;
 use crate::spline::{RangeFunction, SplineFunction};
 use bevy_math::{Curve, FloatExt, IVec3};
 use mcrs_protocol::{BlockStateId, Ident};
 use mcrs_random::legacy::LegacyRandom;
 use mcrs_random::{Random, RandomSource};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
 use std::mem::swap;
 use std::ops::Index;
+use std::sync::Arc;
 use tracing::info;
 
 pub mod beta_seed;
 pub mod beta_terrain_f64;
+pub mod parallel;
 pub mod proto;
+#[cfg(test)]
+mod vanilla_fixtures;
 
 /// Maximum number of positions that can be batched in a single fill_plane call.
 /// 5 Z-columns * 3 Y-positions = 15, rounded up to 16 for alignment.
 #[cfg(feature = "batch-noise")]
 pub(crate) const MAX_BATCH: usize = 128;
 
+/// Number of Y positions evaluated together by `final_density_column_batch`.
+/// 8 f32 lanes fill one AVX register (two SSE/NEON registers).
+pub(crate) const COLUMN_LANES: usize = 8;
+
 struct ChunkNoiseFunctionBuilderOptions {
     // Number of blocks per cell per axis
     horizontal_cell_block_count: usize,
@@ -371,6 +380,102 @@ fn try_flatten_spline(
     })
 }
 
+/// Base LUT resolution per axis for flattened splines. Axes with many knots are
+/// scaled up further by `try_flatten_spline`.
+#[cfg(feature = "flatten-splines")]
+const SPLINE_FLATTEN_GRID_SIZE: usize = 64;
+
+/// Largest absolute error a flattened spline may show against the original
+/// before it is rejected.
+#[cfg(feature = "flatten-splines")]
+const SPLINE_FLATTEN_MAX_ERROR: f32 = 1e-3;
+
+/// Replace every spline with three independent coordinates by a tricubic LUT,
+/// keeping the original wherever the LUT is not accurate enough.
+/// Returns the number of splines flattened.
+#[cfg(feature = "flatten-splines")]
+fn flatten_splines(stack: &mut [DensityFunctionComponent]) -> usize {
+    let mut flattened = 0usize;
+    for i in 0..stack.len() {
+        if !matches!(
+            &stack[i],
+            DensityFunctionComponent::Dependent(DependentDensityFunction::Spline(_))
+        ) {
+            continue;
+        }
+        // `try_flatten_spline` only succeeds for exactly three independent axes.
+        let Some(flat) = try_flatten_spline(i, stack, SPLINE_FLATTEN_GRID_SIZE) else {
+            continue;
+        };
+        let max_error = flattened_spline_max_error(i, stack, &flat);
+        if max_error > SPLINE_FLATTEN_MAX_ERROR {
+            tracing::debug!(index = i, max_error, "Spline kept: flattened LUT too inaccurate");
+            continue;
+        }
+        stack[i] =
+            DensityFunctionComponent::Dependent(DependentDensityFunction::FlattenedSpline(flat));
+        flattened += 1;
+    }
+    flattened
+}
+
+/// Maximum absolute difference between `flat` and the spline at `spline_idx`
+/// over a coarse grid of world positions. Each position runs a full forward
+/// sweep, so this also catches splines whose derived coordinates are not
+/// purely functions of the three LUT axes.
+#[cfg(feature = "flatten-splines")]
+fn flattened_spline_max_error(
+    spline_idx: usize,
+    stack: &[DensityFunctionComponent],
+    flat: &FlattenedSpline,
+) -> f32 {
+    let DensityFunctionComponent::Dependent(DependentDensityFunction::Spline(spline)) =
+        &stack[spline_idx]
+    else {
+        return f32::INFINITY;
+    };
+    let [c0, c1, c2] = flat.coord_indices;
+    let mut cache = vec![0.0f32; spline_idx + 1];
+    let mut max_error = 0.0f32;
+    for x in (-8192..=8192).step_by(1024) {
+        for z in (-8192..=8192).step_by(1024) {
+            for y in [-64, 0, 64, 192] {
+                let pos = IVec3::new(x, y, z);
+                for i in 0..spline_idx {
+                    cache[i] = stack[i].sample_cached(&cache, stack, pos);
+                }
+                let expected = spline.sample_cached(&cache, stack, pos);
+                let actual = flat.evaluate(cache[c0], cache[c1], cache[c2]);
+                max_error = max_error.max((expected - actual).abs());
+            }
+        }
+    }
+    max_error
+}
+
+/// Sort key for Multiply operands; the larger one goes first. Inputs whose
+/// range ends at zero (gates like a clamped gradient or `max(0, x)`) sit at
+/// exactly zero over whole regions, inputs that merely span zero rarely hit
+/// it, and the rest never do. Ties go to the cheap leaves.
+fn multiply_first_key(entry: &DensityFunctionComponent) -> (u8, bool) {
+    let (min, max) = (entry.min_value(), entry.max_value());
+    let zero = if min == 0.0 || max == 0.0 {
+        2
+    } else if min < 0.0 && max > 0.0 {
+        1
+    } else {
+        0
+    };
+    let cheap = matches!(
+        entry,
+        DensityFunctionComponent::Independent(
+            IndependentDensityFunction::Constant(_)
+                | IndependentDensityFunction::ClampedYGradient(_)
+        )
+    );
+    (zero, cheap)
+}
+
 fn optimize_stack(stack: &mut Vec<DensityFunctionComponent>, roots: &mut [usize]) {
     let n = stack.len();
     if n == 0 {
@@ -385,15 +490,21 @@ fn optimize_stack(stack: &mut Vec<DensityFunctionComponent>, roots: &mut [usize]
     let mut identities_eliminated = 0usize;
     let mut binary_demotions = 0usize;
     let mut slide_fusions = 0usize;
+    let mut clamp_fusions = 0usize;
+    let mut multiply_swaps = 0usize;
 
     // Phase 1: Forward pass — peephole optimize
     for i in 0..n {
-        // 1. Resolve cache wrapper redirects (only CacheOnce and CacheAllInCell;
-        //    FlatCache and Cache2d are kept alive for column caching)
+        // 1. Resolve cache wrapper redirects (only CacheOnce, CacheAllInCell and
+        //    Interpolated; FlatCache and Cache2d are kept alive for column
+        //    caching). Interpolated samples its input as-is: cell corners are
+        //    interpolated by NoiseCellInterpolator, not by the wrapper.
         let is_eliminated_cache = matches!(
             &stack[i],
             DensityFunctionComponent::Wrapper(
-                WrapperDensityFunction::CacheOnce(_) | WrapperDensityFunction::CacheAllInCell(_)
+                WrapperDensityFunction::CacheOnce(_)
+                    | WrapperDensityFunction::CacheAllInCell(_)
+                    | WrapperDensityFunction::Interpolated(_)
             )
         );
 
@@ -405,6 +516,9 @@ fn optimize_stack(stack: &mut Vec<DensityFunctionComponent>, roots: &mut [usize]
                 DensityFunctionComponent::Wrapper(WrapperDensityFunction::CacheAllInCell(x)) => {
                     x.input_index
                 }
+                DensityFunctionComponent::Wrapper(WrapperDensityFunction::Interpolated(x)) => {
+                    x.input_index
+                }
                 _ => unreachable!(),
             };
             redirect[i] = redirect[input_index];
@@ -505,6 +619,25 @@ fn optimize_stack(stack: &mut Vec<DensityFunctionComponent>, roots: &mut [usize]
             }
         }
 
+        // 3c. Multiply operand order: `Binary::sample` skips input2 when input1
+        //     is zero, so put the operand most likely to be zero (then the
+        //     cheaper one) first. Products of finite values don't depend on
+        //     the order.
+        if let DensityFunctionComponent::Dependent(DependentDensityFunction::Binary(bin)) =
+            &stack[i]
+        {
+            if bin.operation == BinaryOperation::Multiply
+                && multiply_first_key(&stack[bin.input2_index])
+                    > multiply_first_key(&stack[bin.input1_index])
+            {
+                let mut bin = bin.clone();
+                std::mem::swap(&mut bin.input1_index, &mut bin.input2_index);
+                stack[i] =
+                    DensityFunctionComponent::Dependent(DependentDensityFunction::Binary(bin));
+                multiply_swaps += 1;
+            }
+        }
+
         // 4. Constant folding for all single-input operations
         let folded = match &stack[i] {
             DensityFunctionComponent::Dependent(DependentDensityFunction::Linear(lin)) => stack
@@ -839,6 +972,40 @@ fn optimize_stack(stack: &mut Vec<DensityFunctionComponent>, roots: &mut [usize]
             _ => {}
         }
 
+        // 7a. Nested clamp fusion: Clamp(a2, b2, Clamp(a1, b1, x)) → Clamp(max(a1, a2), min(b1, b2), x).
+        //     Disjoint ranges always produce the outer bound nearest the inner range.
+        if let DensityFunctionComponent::Dependent(DependentDensityFunction::Clamp(outer)) =
+            &stack[i]
+        {
+            if let DensityFunctionComponent::Dependent(DependentDensityFunction::Clamp(inner)) =
+                &stack[outer.input_index]
+            {
+                let min_value = inner.min_value.max(outer.min_value);
+                let max_value = inner.max_value.min(outer.max_value);
+                if min_value <= max_value {
+                    stack[i] = DensityFunctionComponent::Dependent(DependentDensityFunction::Clamp(
+                        Clamp {
+                            input_index: inner.input_index,
+                            min_value,
+                            max_value,
+                        },
+                    ));
+                    clamp_fusions += 1;
+                } else {
+                    let bound = if inner.max_value < outer.min_value {
+                        outer.min_value
+                    } else {
+                        outer.max_value
+                    };
+                    stack[i] = DensityFunctionComponent::Independent(
+                        IndependentDensityFunction::Constant(bound),
+                    );
+                    constants_folded += 1;
+                    continue;
+                }
+            }
+        }
+
         // 7. Clamp of in-range elimination
         if let DensityFunctionComponent::Dependent(DependentDensityFunction::Clamp(clamp)) =
             &stack[i]
@@ -852,9 +1019,16 @@ fn optimize_stack(stack: &mut Vec<DensityFunctionComponent>, roots: &mut [usize]
 
         // 8. RangeChoice range-based elimination: if the input's static range proves
         //    it always falls in or always falls out, redirect to the known branch.
+        //    Identical branches (e.g. both collapsed to one node by earlier redirects)
+        //    make the condition irrelevant.
         if let DensityFunctionComponent::Dependent(DependentDensityFunction::RangeChoice(rc)) =
             &stack[i]
         {
+            if rc.when_in_index == rc.when_out_index {
+                redirect[i] = rc.when_in_index;
+                identities_eliminated += 1;
+                continue;
+            }
             let input = &stack[rc.input_index];
             if input.max_value() < rc.min_inclusion_value
                 || input.min_value() >= rc.max_exclusion_value
@@ -993,20 +1167,44 @@ fn optimize_stack(stack: &mut Vec<DensityFunctionComponent>, roots: &mut [usize]
         *root = redirect[*root];
     }
 
-    // Phase 3: Flatten splines to lookup tables
-    let mut splines_flattened = 0usize;
-    // let grid_size = 128;
-    // for i in 0..stack.len() {
-    //     if let DensityFunctionComponent::Dependent(DependentDensityFunction::Spline(_)) = &stack[i]
-    //     {
-    //         if let Some(flat) = try_flatten_spline(i, stack, grid_size) {
-    //             stack[i] = DensityFunctionComponent::Dependent(
-    //                 DependentDensityFunction::FlattenedSpline(flat),
-    //             );
-    //             splines_flattened += 1;
-    //         }
-    //     }
-    // }
+    // Phase 3: Common subexpression elimination. Fusion and redirects can turn
+    // separately registered subtrees into identical nodes. Walk the live nodes
+    // in order, bucket them by (variant, rewritten inputs, range) and redirect
+    // each one equal to an earlier node in its bucket to that node. The first
+    // occurrence always sits at a lower index, so topological order holds.
+    let mut cse_redirect: Vec<usize> = (0..n).collect();
+    let mut buckets: HashMap<_, Vec<usize>> = HashMap::new();
+    let mut cse_merged = 0usize;
+    for i in 0..n {
+        if redirect[i] != i {
+            continue;
+        }
+        stack[i].rewrite_indices(&cse_redirect);
+        let mut inputs = Vec::new();
+        stack[i].visit_input_indices(&mut |input| inputs.push(input));
+        let key = (
+            std::mem::discriminant(&stack[i]),
+            inputs,
+            stack[i].min_value().to_bits(),
+            stack[i].max_value().to_bits(),
+        );
+        let bucket = buckets.entry(key).or_default();
+        if let Some(&first) = bucket.iter().find(|&&j| stack[j] == stack[i]) {
+            cse_redirect[i] = first;
+            cse_merged += 1;
+        } else {
+            bucket.push(i);
+        }
+    }
+    for root in roots.iter_mut() {
+        *root = cse_redirect[*root];
+    }
+
+    // Phase 4: Flatten splines to lookup tables
+    #[cfg(feature = "flatten-splines")]
+    let splines_flattened = flatten_splines(stack);
+    #[cfg(not(feature = "flatten-splines"))]
+    let splines_flattened = 0usize;
 
     info!(
         stack_size = n,
@@ -1017,6 +1215,9 @@ fn optimize_stack(stack: &mut Vec<DensityFunctionComponent>, roots: &mut [usize]
         identities_eliminated,
         binary_demotions,
         slide_fusions,
+        clamp_fusions,
+        multiply_swaps,
+        cse_merged,
         splines_flattened,
         "Density function stack optimized"
     );
@@ -1082,6 +1283,68 @@ fn compute_per_block(stack: &[DensityFunctionComponent], _roots: &[usize]) -> Ve
     per_block
 }
 
+/// Drop entries no root reaches, like those the optimizer redirected past,
+/// and compact the stack, `per_block` and `node_labels` to match. Survivors
+/// keep their relative (topological) order.
+fn eliminate_dead_entries(
+    stack: &mut Vec<DensityFunctionComponent>,
+    per_block: &mut Vec<bool>,
+    node_labels: &mut Vec<String>,
+    roots: &mut [usize],
+) {
+    let n = stack.len();
+
+    // Mark: backward walk from every root
+    let mut live = vec![false; n];
+    let mut worklist = Vec::with_capacity(roots.len());
+    for &root in roots.iter() {
+        if !live[root] {
+            live[root] = true;
+            worklist.push(root);
+        }
+    }
+    while let Some(idx) = worklist.pop() {
+        stack[idx].visit_input_indices(&mut |input| {
+            if !live[input] {
+                live[input] = true;
+                worklist.push(input);
+            }
+        });
+    }
+
+    // Sweep: keep live entries in order and map old indices to new ones.
+    // Dead entries map to 0; nothing live refers to them.
+    let mut old_to_new = vec![0usize; n];
+    let mut kept = 0usize;
+    for i in 0..n {
+        if live[i] {
+            old_to_new[i] = kept;
+            kept += 1;
+        }
+    }
+    retain_live(stack, &live);
+    retain_live(per_block, &live);
+    retain_live(node_labels, &live);
+
+    for entry in stack.iter_mut() {
+        entry.rewrite_indices(&old_to_new);
+    }
+    for root in roots.iter_mut() {
+        *root = old_to_new[*root];
+    }
+
+    info!(
+        dead_entries = n - kept,
+        stack_size = kept,
+        "Unreachable density function entries removed"
+    );
+}
+
+fn retain_live<T>(entries: &mut Vec<T>, live: &[bool]) {
+    let mut live = live.iter();
+    entries.retain(|_| *live.next().unwrap());
+}
+
 /// Reorder the stack into three zones for optimal `evaluate_forward` performance:
 ///
 ///   Zone A `[0..column_boundary)`:  column-only entries reachable from final_density
@@ -1249,17 +1512,54 @@ fn reorder_stack_for_evaluation(
         "Noise evaluations per zone"
     );
 
+    // ShiftedNoise is per-block, but its shifts should come from Zone A so
+    // they are sampled once per column whichever zone reads them.
+    let mut shift_inputs_zone_a = 0usize;
+    let mut shift_inputs_outside_zone_a = 0usize;
+    for entry in stack.iter() {
+        if let DensityFunctionComponent::Dependent(DependentDensityFunction::ShiftedNoise(f)) =
+            entry
+        {
+            for input in [f.input_x_index, f.input_y_index, f.input_z_index] {
+                if input < column_boundary {
+                    shift_inputs_zone_a += 1;
+                } else {
+                    shift_inputs_outside_zone_a += 1;
+                }
+            }
+        }
+    }
+    info!(
+        shift_inputs_zone_a,
+        shift_inputs_outside_zone_a, "ShiftedNoise shift inputs per zone"
+    );
+
     (column_boundary, fd_boundary)
 }
 
 /// Persistent cache for density function evaluation.
 /// Reuse across calls to `final_density` within the same chunk generation
 /// to cache column-only values (FlatCache/Cache2d dependency cones).
+///
+/// Any router outputs may share one cache, in any order: Zone A, B and C roots
+/// all read the same column pass, and a per-block evaluation never leaks into
+/// a later column pass on the same column.
 pub struct DensityCache {
     scratch: Vec<f32>,
+    /// Column pass (Y=0) values of the first `column_len` entries. Per-block
+    /// evaluations overwrite their entries in `scratch`, so extending the
+    /// column pass restores them from here first.
+    column: Vec<f32>,
     last_x: i32,
     last_z: i32,
-    column_valid: bool,
+    /// Number of leading stack entries whose column pass is valid for (last_x, last_z).
+    column_len: usize,
+    /// `scratch[..column_len]` holds per-block values that differ from `column`.
+    per_block_written: bool,
+    /// `(stack index, surface Y)` of `FindTopSurface` nodes already scanned in
+    /// the current column. Their result does not depend on Y, so one downward
+    /// scan serves every call until the column changes.
+    top_surface: Vec<(usize, f32)>,
 }
 
 /// Pre-populated cache holding Zone A (column-only) results for all 289 (17x17) XZ positions
@@ -1277,6 +1577,9 @@ pub struct ColumnCache {
     pub(crate) base_block_z: i32,
     /// Scratch buffer (len == stack.len()), reused per `final_density_from_column_cache` call.
     pub scratch: Vec<f32>,
+    /// Lane-major scratch for `final_density_column_batch`: one `COLUMN_LANES`-wide
+    /// row per stack entry up to `final_density`.
+    lane_scratch: Vec<[f32; COLUMN_LANES]>,
     /// Scratch buffer for batch evaluation: MAX_BATCH * stack_len flat layout.
     /// Pre-allocated at construction to avoid repeated allocation.
     #[cfg(feature = "batch-noise")]
@@ -1290,11 +1593,89 @@ pub struct ColumnCache {
     /// Fixed buffer for batch noise positions (scaled coordinates).
     #[cfg(feature = "batch-noise")]
     batch_noise_positions: [(f32, f32, f32); MAX_BATCH],
+    /// Edge already filled by `reuse_edge_from`, skipped by the next `populate_columns`.
+    reused_edge: Option<Edge>,
+}
+
+/// A side of the 17x17 column grid, named by the local coordinate it lies on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    /// `local_x == 0`, shared with the `+16` edge of the chunk at `-X`.
+    MinX,
+    /// `local_x == 16`, shared with the base edge of the chunk at `+X`.
+    MaxX,
+    /// `local_z == 0`, shared with the `+16` edge of the chunk at `-Z`.
+    MinZ,
+    /// `local_z == 16`, shared with the base edge of the chunk at `+Z`.
+    MaxZ,
+}
+
+impl Edge {
+    /// The same grid line as seen from the neighbouring chunk.
+    pub fn opposite(self) -> Self {
+        match self {
+            Edge::MinX => Edge::MaxX,
+            Edge::MaxX => Edge::MinX,
+            Edge::MinZ => Edge::MaxZ,
+            Edge::MaxZ => Edge::MinZ,
+        }
+    }
+
+    /// Local (x, z) of the `i`-th position along this edge.
+    fn local(self, i: i32) -> (i32, i32) {
+        let last = ColumnCache::GRID_SIDE - 1;
+        match self {
+            Edge::MinX => (0, i),
+            Edge::MaxX => (last, i),
+            Edge::MinZ => (i, 0),
+            Edge::MaxZ => (i, last),
+        }
+    }
+
+    fn contains(self, local_x: i32, local_z: i32) -> bool {
+        let last = ColumnCache::GRID_SIDE - 1;
+        match self {
+            Edge::MinX => local_x == 0,
+            Edge::MaxX => local_x == last,
+            Edge::MinZ => local_z == 0,
+            Edge::MaxZ => local_z == last,
+        }
+    }
 }
 
 impl ColumnCache {
     const GRID_SIDE: i32 = 17;
 
+    /// Copy the Zone A columns on `edge` from the populated cache of the
+    /// neighbouring chunk, whose opposite edge lies on the same block
+    /// positions. The next `populate_columns` call skips those 17 positions,
+    /// like `fill_plane_cached_reuse` skips the shared Y row between sections.
+    ///
+    /// Set `base_block_x`/`base_block_z` for this chunk before calling.
+    pub fn reuse_edge_from(&mut self, other: &ColumnCache, edge: Edge) {
+        let (dx, dz) = match edge {
+            Edge::MinX => (-16, 0),
+            Edge::MaxX => (16, 0),
+            Edge::MinZ => (0, -16),
+            Edge::MaxZ => (0, 16),
+        };
+        debug_assert_eq!(
+            (other.base_block_x, other.base_block_z),
+            (self.base_block_x + dx, self.base_block_z + dz),
+            "{edge:?} neighbour has the wrong base position"
+        );
+        debug_assert_eq!(self.zone_a_count, other.zone_a_count);
+        let n = self.zone_a_count;
+        for i in 0..Self::GRID_SIDE {
+            let (x, z) = edge.local(i);
+            let (other_x, other_z) = edge.opposite().local(i);
+            let dst = (x * Self::GRID_SIDE + z) as usize * n;
+            let src = (other_x * Self::GRID_SIDE + other_z) as usize * n;
+            self.column_data[dst..dst + n].copy_from_slice(&other.column_data[src..src + n]);
+        }
+        self.reused_edge = Some(edge);
+    }
+
     /// Read a single Zone A value for a given (local_x, local_z) without loading the full column.
     #[inline]
     pub fn read_za_value(&self, local_x: i32, local_z: i32, za_index: usize) -> f32 {
@@ -1312,133 +1693,357 @@ impl ColumnCache {
     }
 }
 
+impl DensityCache {
+    /// Drop everything cached, so the next evaluation starts a fresh column
+    /// pass. Moving to another XZ does this on its own; call it when the same
+    /// cache is reused with a different router.
+    pub fn invalidate(&mut self) {
+        self.last_x = i32::MIN;
+        self.last_z = i32::MIN;
+        self.column_len = 0;
+        self.per_block_written = false;
+        self.top_surface.clear();
+    }
+
+    /// Make `(x, z)` the current column, with `column_pass` as its first
+    /// entries' column pass values.
+    fn load_column(&mut self, x: i32, z: i32, column_pass: &[f32]) {
+        self.invalidate();
+        let len = column_pass.len();
+        self.scratch[..len].copy_from_slice(column_pass);
+        self.column[..len].copy_from_slice(column_pass);
+        self.last_x = x;
+        self.last_z = z;
+        self.column_len = len;
+    }
+}
+
+/// Column pass of the climate roots at the 4x4 biome-grid (quart) columns of a
+/// chunk. Biome positions are quart corners, not the cell corners a
+/// [`ColumnCache`] holds, so the column-only prefix is kept here and copied
+/// into a [`DensityCache`] whenever sampling moves to another column.
+pub struct BiomeColumnCache {
+    /// `column_data[xz_idx * column_len + entry_idx]`
+    /// where `xz_idx = local_quart_x * GRID_SIDE + local_quart_z` (row-major).
+    column_data: Vec<f32>,
+    column_len: usize,
+    base_quart_x: i32,
+    base_quart_z: i32,
+    density: DensityCache,
+}
+
+impl BiomeColumnCache {
+    const GRID_SIDE: i32 = 4;
+
+    /// Make the column of `pos` current in the inner cache, restoring its
+    /// pre-computed prefix if sampling was on another column.
+    fn load_column(&mut self, pos: IVec3) {
+        if pos.x == self.density.last_x && pos.z == self.density.last_z {
+            return;
+        }
+        let local_x = (pos.x >> 2) - self.base_quart_x;
+        let local_z = (pos.z >> 2) - self.base_quart_z;
+        assert!(
+            (0..Self::GRID_SIDE).contains(&local_x) && (0..Self::GRID_SIDE).contains(&local_z),
+            "quart ({}, {}) is outside the biome cache at ({}, {})",
+            pos.x >> 2,
+            pos.z >> 2,
+            self.base_quart_x,
+            self.base_quart_z
+        );
+        let off = (local_x * Self::GRID_SIDE + local_z) as usize * self.column_len;
+        self.density
+            .load_column(pos.x, pos.z, &self.column_data[off..off + self.column_len]);
+    }
+}
+
+/// How many RangeChoices deep a lazy Zone B plan may split. Each level
+/// doubles the number of evaluation lists; vanilla's `final_density` has two
+/// choices worth splitting on.
+const LAZY_RANGE_CHOICE_DEPTH: usize = 2;
+
 /// Compute lazy RangeChoice optimization for Zone B.
 ///
-/// Finds the RangeChoice in Zone B with the most exclusive entries and creates
-/// two evaluation lists: one for when the condition is true (when_in branch)
-/// and one for when it's false (when_out branch). Entries exclusive to the
-/// inactive branch are omitted from the respective list.
-#[cfg(feature = "lazy-range-choice")]
+/// Finds the RangeChoice in Zone B with the most exclusive entries and splits
+/// Zone B there: entries up to its input are always evaluated, and the rest
+/// goes into one list per branch, without the entries only the other branch
+/// needs. Each branch is planned the same way, so a second RangeChoice,
+/// nested in a branch or after the first, splits it again.
+///
+/// Returns `None` when no RangeChoice lets anything be skipped; Zone B is
+/// then swept in order.
 fn compute_lazy_range_choice(
     stack: &[DensityFunctionComponent],
     column_boundary: usize,
     final_density_index: usize,
 ) -> Option<LazyRangeChoice> {
-    // Find RangeChoice nodes in Zone B
-    let mut best: Option<LazyRangeChoice> = None;
-    let mut best_savings = 0usize;
+    let plan = plan_lazy_branch(
+        stack,
+        column_boundary,
+        column_boundary,
+        final_density_index,
+        &mut Vec::new(),
+        LAZY_RANGE_CHOICE_DEPTH,
+    );
+    match plan {
+        LazyBranch::Choice(plan) => Some(*plan),
+        LazyBranch::Sweep(_) => None,
+    }
+}
+
+/// Plan the Zone B entries from `start` on, once the RangeChoices in
+/// `decisions` have gone the recorded way. Everything `final_density` needs
+/// below `start` has already been evaluated.
+fn plan_lazy_branch(
+    stack: &[DensityFunctionComponent],
+    column_boundary: usize,
+    start: usize,
+    final_density_index: usize,
+    decisions: &mut Vec<(usize, bool)>,
+    depth: usize,
+) -> LazyBranch {
+    let needed = needed_entries(stack, column_boundary, final_density_index, decisions);
+    let needed_from = |from: usize| -> Box<[usize]> {
+        (from..=final_density_index)
+            .filter(|&i| needed[i])
+            .collect()
+    };
+    if depth == 0 {
+        return LazyBranch::Sweep(needed_from(start));
+    }
 
-    for rc_idx in column_boundary..=final_density_index {
-        let rc = match &stack[rc_idx] {
-            DensityFunctionComponent::Dependent(DependentDensityFunction::RangeChoice(rc)) => rc,
-            _ => continue,
+    let mut best = None;
+    let mut best_savings = 0usize;
+    for rc_idx in start..=final_density_index {
+        let DensityFunctionComponent::Dependent(DependentDensityFunction::RangeChoice(rc)) =
+            &stack[rc_idx]
+        else {
+            continue;
         };
+        if !needed[rc_idx] {
+            continue;
+        }
+        // Entries up to the input are evaluated before the choice is made.
+        let split = start.max(rc.input_index + 1);
+        let mut skipped = |in_range: bool| {
+            decisions.push((rc_idx, in_range));
+            let branch = needed_entries(stack, column_boundary, final_density_index, decisions);
+            decisions.pop();
+            (split..=final_density_index)
+                .filter(|&i| needed[i] && !branch[i])
+                .count()
+        };
+        let savings = skipped(true).max(skipped(false));
+        if savings > best_savings {
+            best_savings = savings;
+            best = Some((rc_idx, split));
+        }
+    }
 
-        // Compute reachable sets from when_in and when_out branches
-        let extent = final_density_index + 1;
-        let reachable_from_wi = reachable_backwards(rc.when_in_index, stack, extent);
-        let reachable_from_wo = reachable_backwards(rc.when_out_index, stack, extent);
-
-        // Compute reachable set from final_density WITHOUT going through this RC's branches.
-        // We walk back from final_density but when we encounter this RC node, we only
-        // follow the input edge (not when_in or when_out).
-        let reachable_without_branches = {
-            let mut visited = vec![false; final_density_index + 1];
-            visited[final_density_index] = true;
-            for i in (column_boundary..=final_density_index).rev() {
-                if !visited[i] {
-                    continue;
-                }
-                if i == rc_idx {
-                    // Only follow the input edge, not the branch edges
-                    if rc.input_index <= final_density_index {
-                        visited[rc.input_index] = true;
-                    }
-                } else {
-                    stack[i].visit_input_indices(&mut |dep| {
-                        if dep <= final_density_index {
-                            visited[dep] = true;
-                        }
-                    });
-                }
+    let Some((rc_idx, split)) = best else {
+        return LazyBranch::Sweep(needed_from(start));
+    };
+    let DensityFunctionComponent::Dependent(DependentDensityFunction::RangeChoice(rc)) =
+        &stack[rc_idx]
+    else {
+        unreachable!("only RangeChoice entries are picked");
+    };
+    let mut branch = |in_range: bool| {
+        decisions.push((rc_idx, in_range));
+        let plan = plan_lazy_branch(
+            stack,
+            column_boundary,
+            split,
+            final_density_index,
+            decisions,
+            depth - 1,
+        );
+        decisions.pop();
+        plan
+    };
+    let when_in = branch(true);
+    let when_out = branch(false);
+
+    info!(
+        range_choice = rc_idx,
+        depth = LAZY_RANGE_CHOICE_DEPTH - depth,
+        skipped = best_savings,
+        "Lazy RangeChoice split"
+    );
+
+    LazyBranch::Choice(Box::new(LazyRangeChoice {
+        prefix: (start..split).filter(|&i| needed[i]).collect(),
+        input_index: rc.input_index,
+        min_inclusion: rc.min_inclusion_value,
+        max_exclusion: rc.max_exclusion_value,
+        when_in,
+        when_out,
+    }))
+}
+
+/// Which Zone B entries `final_density` reads when each RangeChoice in
+/// `decisions` only reads its input and the recorded branch. Zone A entries
+/// are loaded per column and left unmarked.
+fn needed_entries(
+    stack: &[DensityFunctionComponent],
+    column_boundary: usize,
+    final_density_index: usize,
+    decisions: &[(usize, bool)],
+) -> Vec<bool> {
+    let mut needed = vec![false; final_density_index + 1];
+    needed[final_density_index] = true;
+    for i in (column_boundary..=final_density_index).rev() {
+        if !needed[i] {
+            continue;
+        }
+        let mut mark = |dep: usize| {
+            if dep >= column_boundary {
+                needed[dep] = true;
             }
-            visited
         };
+        let decision = decisions.iter().find(|&&(idx, _)| idx == i);
+        match (&stack[i], decision) {
+            (
+                DensityFunctionComponent::Dependent(DependentDensityFunction::RangeChoice(rc)),
+                Some(&(_, in_range)),
+            ) => {
+                mark(rc.input_index);
+                mark(if in_range {
+                    rc.when_in_index
+                } else {
+                    rc.when_out_index
+                });
+            }
+            (entry, _) => entry.visit_input_indices(&mut mark),
+        }
+    }
+    needed
+}
 
-        // Entries exclusive to when_out: reachable from WO, NOT from WI, NOT from other paths
-        let wo_exclusive: Vec<usize> = (column_boundary..rc_idx)
-            .filter(|&e| {
-                reachable_from_wo[e] && !reachable_from_wi[e] && !reachable_without_branches[e]
-            })
-            .collect();
+/// A datapack's noise settings reference something that isn't loaded.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum WorldgenError {
+    #[error("{referrer} references noise `{noise}`, which is not loaded")]
+    MissingNoise {
+        noise: Ident<String>,
+        referrer: Referrer,
+    },
+    #[error("{referrer} references density function `{function}`, which is not loaded")]
+    MissingDensityFunction {
+        function: Ident<String>,
+        referrer: Referrer,
+    },
+    #[error("density function `{0}` references itself")]
+    ReferenceCycle(Ident<String>),
+}
 
-        // Entries exclusive to when_in: reachable from WI, NOT from WO, NOT from other paths
-        let wi_exclusive: Vec<usize> = (column_boundary..rc_idx)
-            .filter(|&e| {
-                reachable_from_wi[e] && !reachable_from_wo[e] && !reachable_without_branches[e]
-            })
-            .collect();
+/// Where a missing reference was found: the innermost named density function
+/// around it, or the noise router field if it sits inline in the settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Referrer {
+    RouterField(&'static str),
+    DensityFunction(Ident<String>),
+}
 
-        let savings = wo_exclusive.len().max(wi_exclusive.len());
-        if savings <= best_savings {
-            continue;
+impl std::fmt::Display for Referrer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Referrer::RouterField(field) => write!(f, "noise router field `{field}`"),
+            Referrer::DensityFunction(id) => write!(f, "density function `{id}`"),
         }
+    }
+}
 
-        // Build branch-specific lists: Zone B entries AFTER input_index, minus exclusives.
-        // The common prefix [column_boundary..=input_index] is always evaluated.
-        let input_idx = rc.input_index;
-        let branch_when_in: Vec<usize> = ((input_idx + 1)..=final_density_index)
-            .filter(|e| !wo_exclusive.contains(e))
-            .collect();
-        let branch_when_out: Vec<usize> = ((input_idx + 1)..=final_density_index)
-            .filter(|e| !wi_exclusive.contains(e))
-            .collect();
+/// Noises `create_noise` builds itself for legacy (Beta) sources instead of
+/// reading them from the noise map.
+const LEGACY_BUILTIN_NOISES: [&str; 8] = [
+    "minecraft:temperature",
+    "minecraft:vegetation",
+    "minecraft:offset",
+    "mcrs:beta/scale",
+    "mcrs:beta/depth",
+    "mcrs:beta/temperature",
+    "mcrs:beta/vegetation",
+    "mcrs:beta/climate_detail",
+];
 
-        eprintln!(
-            "  Lazy RangeChoice at [{}]: when_in skips {}, when_out skips {}",
-            rc_idx,
-            wo_exclusive.len(),
-            wi_exclusive.len(),
-        );
+/// Walks everything reachable from the noise router before building, so the
+/// builder can assume every reference resolves.
+struct ReferenceChecker<'a> {
+    functions: &'a BTreeMap<Ident<String>, ProtoDensityFunction>,
+    noises: &'a BTreeMap<Ident<String>, NoiseParam>,
+    legacy: bool,
+    field: &'static str,
+    /// Named functions being visited, innermost last.
+    path: Vec<Ident<String>>,
+    checked: HashSet<Ident<String>>,
+    error: Option<WorldgenError>,
+}
 
-        best_savings = savings;
-        best = Some(LazyRangeChoice {
-            input_index: input_idx,
-            min_inclusion: rc.min_inclusion_value,
-            max_exclusion: rc.max_exclusion_value,
-            branch_when_in: branch_when_in.into_boxed_slice(),
-            branch_when_out: branch_when_out.into_boxed_slice(),
-        });
+impl<'a> ReferenceChecker<'a> {
+    fn check(
+        &mut self,
+        field: &'static str,
+        holder: &DensityFunctionHolder,
+    ) -> Result<(), WorldgenError> {
+        self.field = field;
+        self.visit_density_function_holder(holder);
+        self.error.take().map_or(Ok(()), Err)
+    }
+
+    fn referrer(&self) -> Referrer {
+        match self.path.last() {
+            Some(id) => Referrer::DensityFunction(id.clone()),
+            None => Referrer::RouterField(self.field),
+        }
     }
 
-    best
+    fn fail(&mut self, error: WorldgenError) {
+        self.error.get_or_insert(error);
+    }
 }
 
-#[cfg(feature = "lazy-range-choice")]
-/// Walk backwards from `start` through input edges, returning a reachability bitmap
-/// of size `extent`. Entries beyond `start` are always false.
-fn reachable_backwards(
-    start: usize,
-    stack: &[DensityFunctionComponent],
-    extent: usize,
-) -> Vec<bool> {
-    let mut visited = vec![false; extent];
-    if start < extent {
-        visited[start] = true;
+impl<'a> Visitor for ReferenceChecker<'a> {
+    fn visit_reference(&mut self, value: &Ident<String>) {
+        if self.error.is_some() || self.checked.contains(value) {
+            return;
+        }
+        if self.path.contains(value) {
+            self.fail(WorldgenError::ReferenceCycle(value.clone()));
+            return;
+        }
+        let functions = self.functions;
+        let Some(function) = functions.get(value) else {
+            let referrer = self.referrer();
+            self.fail(WorldgenError::MissingDensityFunction {
+                function: value.clone(),
+                referrer,
+            });
+            return;
+        };
+        self.path.push(value.clone());
+        self.visit_density_function(function);
+        self.path.pop();
+        self.checked.insert(value.clone());
     }
-    for i in (0..=start.min(extent - 1)).rev() {
-        if !visited[i] {
-            continue;
+
+    fn visit_noise_holder(&mut self, noise: &NoiseHolder) {
+        let NoiseHolder::Reference(id) = noise else {
+            return;
+        };
+        let builtin = self.legacy && LEGACY_BUILTIN_NOISES.contains(&id.as_str());
+        if !builtin && !self.noises.contains_key(id) {
+            let referrer = self.referrer();
+            self.fail(WorldgenError::MissingNoise {
+                noise: id.clone(),
+                referrer,
+            });
         }
-        stack[i].visit_input_indices(&mut |dep| {
-            if dep < extent {
-                visited[dep] = true;
-            }
-        });
     }
-    visited
 }
 
+/// Build the noise router of `noise_settings`, or report the first density
+/// function or noise it references that isn't in `functions` or `noises`.
 pub fn build_functions(
     functions: &BTreeMap<Ident<String>, ProtoDensityFunction>,
     noises: &BTreeMap<Ident<String>, NoiseParam>,
@@ -1446,8 +2051,41 @@ pub fn build_functions(
     seed: u64,
     default_block_state: BlockStateId,
     default_fluid_state: BlockStateId,
-) -> NoiseRouter {
+) -> Result<NoiseRouter, WorldgenError> {
+    let nr = &noise_settings.noise_router;
+    let mut checker = ReferenceChecker {
+        functions,
+        noises,
+        legacy: noise_settings.legacy_random_source,
+        field: "",
+        path: Vec::new(),
+        checked: HashSet::new(),
+        error: None,
+    };
+    for (field, holder) in [
+        ("barrier", &nr.barrier),
+        ("fluid_level_floodedness", &nr.fluid_level_floodedness),
+        ("fluid_level_spread", &nr.fluid_level_spread),
+        ("lava", &nr.lava),
+        ("temperature", &nr.temperature),
+        ("vegetation", &nr.vegetation),
+        ("continents", &nr.continents),
+        ("erosion", &nr.erosion),
+        ("depth", &nr.depth),
+        ("ridges", &nr.ridges),
+        ("preliminary_surface_level", &nr.preliminary_surface_level),
+        ("final_density", &nr.final_density),
+        ("vein_toggle", &nr.vein_toggle),
+        ("vein_ridged", &nr.vein_ridged),
+        ("vein_gap", &nr.vein_gap),
+    ] {
+        checker.check(field, holder)?;
+    }
+
     let random = RandomSource::new(seed, noise_settings.legacy_random_source);
+    let aquifer_random = random.clone().fork_hash("minecraft:aquifer");
+    let ore_random = random.clone().fork_hash("minecraft:ore");
+    let surface_random = random.clone();
     let builder_options = ChunkNoiseFunctionBuilderOptions {
         horizontal_cell_block_count: 4,
         vertical_cell_block_count: 8,
@@ -1458,7 +2096,6 @@ pub fn build_functions(
         horizontal_biome_end: 4,
     };
     let mut builder = FunctionStackBuilder::new(random, seed, functions, noises, &builder_options);
-    let nr = &noise_settings.noise_router;
     let barrier_index = builder.component(&nr.barrier);
     let fluid_level_floodedness_index = builder.component(&nr.fluid_level_floodedness);
     let fluid_level_spread_index = builder.component(&nr.fluid_level_spread);
@@ -1507,6 +2144,13 @@ pub fn build_functions(
         }
     }
 
+    eliminate_dead_entries(
+        &mut builder.stack,
+        &mut per_block,
+        &mut node_labels,
+        &mut roots,
+    );
+
     // Reorder the stack into evaluation zones for optimal forward evaluation:
     //   Zone A [0..column_boundary): column-only entries reachable from final_density
     //   Zone B [column_boundary..fd_boundary): per-Y entries for final_density
@@ -1522,7 +2166,6 @@ pub fn build_functions(
     let final_density_index = roots[11];
 
     // Compute lazy RangeChoice optimization for Zone B.
-    #[cfg(feature = "lazy-range-choice")]
     let lazy_rc = compute_lazy_range_choice(&builder.stack, column_boundary, final_density_index);
 
     // Surface-skip: find Zone A indices for offset/factor, and max of OldBlendedNoise.
@@ -1587,6 +2230,11 @@ pub fn build_functions(
         default_block_state,
         default_fluid_state,
         world_seed: seed,
+        random: surface_random,
+        aquifers_enabled: noise_settings.aquifers_enabled,
+        aquifer_random,
+        ore_veins_enabled: noise_settings.ore_veins_enabled,
+        ore_random,
         beta_beach_noise,
         beta_surface_noise,
         beta_terrain_f64: beta_terrain_f64_opt,
@@ -1597,7 +2245,6 @@ pub fn build_functions(
         v_cell_blocks: builder_options.vertical_cell_block_count,
         stack: Box::from(builder.stack),
         node_labels: node_labels.into_boxed_slice(),
-        #[cfg(feature = "lazy-range-choice")]
         lazy_rc,
         #[cfg(feature = "surface-skip")]
         offset_za_index,
@@ -1625,29 +2272,133 @@ pub fn build_functions(
         router
     };
 
-    router
+    Ok(router)
 }
 
-#[cfg(feature = "lazy-range-choice")]
-#[derive(Clone, Debug, PartialEq)]
-/// Lazy RangeChoice evaluation data.
-/// Zone B evaluation is split into a common prefix (up to and including the
-/// RangeChoice input) and branch-specific tails. The input is evaluated first,
-/// then based on the condition, only the needed branch entries are evaluated.
-struct LazyRangeChoice {
-    /// Stack index of the RangeChoice's input.
+/// Layout version of `NoiseRouter::to_bytes`. Bump it whenever a serialized
+/// stack type changes shape so stale blobs are rebuilt instead of misread.
+#[cfg(feature = "serde")]
+const ROUTER_BYTES_VERSION: u32 = 4;
+
+/// Fingerprint of everything `build_functions` reads, plus the features that
+/// change the router layout. A router saved with `NoiseRouter::to_bytes` can
+/// be loaded instead of rebuilt while this value stays the same.
+#[cfg(feature = "serde")]
+pub fn router_inputs_hash(
+    functions: &BTreeMap<Ident<String>, ProtoDensityFunction>,
+    noises: &BTreeMap<Ident<String>, NoiseParam>,
+    noise_settings: &NoiseGeneratorSettings,
+    seed: u64,
+    default_block_state: BlockStateId,
+    default_fluid_state: BlockStateId,
+) -> u64 {
+    // FNV-1a: unlike `DefaultHasher`, stable across Rust versions and platforms.
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    let mut feed = |bytes: &[u8]| {
+        for &byte in bytes {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+    feed(&ROUTER_BYTES_VERSION.to_le_bytes());
+    feed(&[
+        cfg!(feature = "surface-skip") as u8,
+        cfg!(feature = "batch-noise") as u8,
+        cfg!(feature = "flatten-splines") as u8,
+    ]);
+    feed(&seed.to_le_bytes());
+    feed(&default_block_state.0.to_le_bytes());
+    feed(&default_fluid_state.0.to_le_bytes());
+    feed(&serde_json::to_vec(functions).expect("density functions must serialize"));
+    feed(&serde_json::to_vec(noises).expect("noises must serialize"));
+    feed(&serde_json::to_vec(noise_settings).expect("noise settings must serialize"));
+    hash
+}
+
+/// Lazy RangeChoice evaluation data.
+/// Zone B evaluation is split into a prefix (ending with the RangeChoice input,
+/// unless an earlier prefix already evaluated it) and a branch-specific tail.
+/// The prefix is evaluated first, then based on the condition, only the
+/// entries the taken branch needs.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct LazyRangeChoice {
+    /// Zone B entries evaluated before the condition is checked, in stack order.
+    prefix: Box<[usize]>,
+    /// Stack index of the RangeChoice's input.
     input_index: usize,
     /// Range bounds for the condition.
     min_inclusion: f32,
     max_exclusion: f32,
-    /// Zone B entries AFTER input_index when condition is TRUE (input in range).
+    /// The rest of Zone B when the condition is TRUE (input in range).
     /// Excludes entries only needed by the when_out branch.
-    branch_when_in: Box<[usize]>,
-    /// Zone B entries AFTER input_index when condition is FALSE (input out of range).
+    when_in: LazyBranch,
+    /// The rest of Zone B when the condition is FALSE (input out of range).
     /// Excludes entries only needed by the when_in branch.
-    branch_when_out: Box<[usize]>,
+    when_out: LazyBranch,
+}
+
+/// What a [`LazyRangeChoice`] evaluates once its condition is known.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum LazyBranch {
+    /// These entries, in stack order.
+    Sweep(Box<[usize]>),
+    /// Split again on a later RangeChoice.
+    Choice(Box<LazyRangeChoice>),
+}
+
+/// Named outputs of a [`NoiseRouter`], matching the vanilla `noise_router` fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Root {
+    Barrier,
+    FluidLevelFloodedness,
+    FluidLevelSpread,
+    Lava,
+    Temperature,
+    Vegetation,
+    Continents,
+    Erosion,
+    Depth,
+    Ridges,
+    PreliminarySurfaceLevel,
+    FinalDensity,
+    VeinToggle,
+    VeinRidged,
+    VeinGap,
+}
+
+impl Root {
+    pub const ALL: [Root; 15] = [
+        Root::Barrier,
+        Root::FluidLevelFloodedness,
+        Root::FluidLevelSpread,
+        Root::Lava,
+        Root::Temperature,
+        Root::Vegetation,
+        Root::Continents,
+        Root::Erosion,
+        Root::Depth,
+        Root::Ridges,
+        Root::PreliminarySurfaceLevel,
+        Root::FinalDensity,
+        Root::VeinToggle,
+        Root::VeinRidged,
+        Root::VeinGap,
+    ];
+
+    /// The six roots a multi-noise biome source samples, in `TargetPoint` order.
+    pub const CLIMATE: [Root; 6] = [
+        Root::Temperature,
+        Root::Vegetation,
+        Root::Continents,
+        Root::Erosion,
+        Root::Depth,
+        Root::Ridges,
+    ];
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NoiseRouter {
     barrier_index: usize,
     fluid_level_floodedness_index: usize,
@@ -1670,6 +2421,16 @@ pub struct NoiseRouter {
     default_block_state: BlockStateId,
     default_fluid_state: BlockStateId,
     world_seed: u64,
+    /// Vanilla `RandomState.random`: the world's positional random, forked by
+    /// name for noises and at block positions for surface depth jitter.
+    random: RandomSource,
+    aquifers_enabled: bool,
+    /// Vanilla `RandomState.aquiferRandom()` before `forkPositional()`: clone it
+    /// and `fork_at` an aquifer grid cell to place that cell's center.
+    aquifer_random: RandomSource,
+    ore_veins_enabled: bool,
+    /// Vanilla `RandomState.oreRandom()`, forked per block by the vein generator.
+    ore_random: RandomSource,
     /// Beta beach octave noise (4 octaves, stream position 4 in seed_beta_terrain).
     /// None for the modern router. Used by apply_beta_surface to determine beach columns.
     beta_beach_noise: Option<Box<OctavePerlinNoise<f64>>>,
@@ -1697,7 +2458,6 @@ pub struct NoiseRouter {
     /// Lazy RangeChoice optimization for Zone B evaluation.
     /// If present, `final_density_from_column_cache` uses branch-specific
     /// evaluation lists to skip entries exclusive to the inactive branch.
-    #[cfg(feature = "lazy-range-choice")]
     lazy_rc: Option<LazyRangeChoice>,
     /// Zone A index of `"minecraft:overworld/offset"` for surface estimation.
     #[cfg(feature = "surface-skip")]
@@ -1714,6 +2474,48 @@ pub struct NoiseRouter {
 }
 
 impl NoiseRouter {
+    /// Serialize the fully optimized router, including the noise samplers'
+    /// seeded tables, tagged with the `router_inputs_hash` of its inputs.
+    #[cfg(feature = "serde")]
+    pub fn to_bytes(&self, inputs_hash: u64) -> Vec<u8> {
+        bincode::serialize(&(inputs_hash, self)).expect("noise router must serialize")
+    }
+
+    /// Load a router written by `to_bytes`. Returns `None` when the blob was
+    /// built from other inputs or cannot be decoded; rebuild it with
+    /// `build_functions` in that case.
+    #[cfg(feature = "serde")]
+    pub fn from_bytes(bytes: &[u8], inputs_hash: u64) -> Option<Self> {
+        // The hash is the leading field, so stale blobs are rejected without
+        // decoding the stack.
+        let stored_hash: u64 = bincode::deserialize(bytes).ok()?;
+        if stored_hash != inputs_hash {
+            return None;
+        }
+        let (_, mut router): (u64, NoiseRouter) = bincode::deserialize(bytes).ok()?;
+        router.share_noise_samplers();
+        Some(router)
+    }
+
+    /// Point entries sampling the same noise back at one sampler, as
+    /// `build_functions` leaves them. Decoding gives each entry its own copy.
+    #[cfg(feature = "serde")]
+    fn share_noise_samplers(&mut self) {
+        let mut shared: Vec<(String, Arc<NoiseSampler>)> = Vec::new();
+        for entry in self.stack.iter_mut() {
+            let Some((name, sampler)) = entry.noise_sampler_mut() else {
+                continue;
+            };
+            match shared
+                .iter()
+                .find(|(known_name, known)| known_name == name && **known == *sampler)
+            {
+                Some((_, known)) => *sampler = known.clone(),
+                None => shared.push((name.to_owned(), sampler.clone())),
+            }
+        }
+    }
+
     /// All noise router entries as (name, index) pairs.
     pub fn roots(&self) -> Vec<(&'static str, usize)> {
         vec![
@@ -1809,7 +2611,7 @@ impl NoiseRouter {
                     | IndependentDensityFunction::ShiftB(_)
                     | IndependentDensityFunction::Shift(_) => shift += 1,
                     IndependentDensityFunction::ClampedYGradient(_) => clamped_y += 1,
-                    IndependentDensityFunction::EndIslands => {}
+                    IndependentDensityFunction::EndIslands(_) => {}
                 },
                 DensityFunctionComponent::Wrapper(_) => {}
                 DensityFunctionComponent::Dependent(f) => match f {
@@ -1937,9 +2739,12 @@ impl NoiseRouter {
     pub fn new_cache(&self) -> DensityCache {
         DensityCache {
             scratch: vec![0.0f32; self.stack.len()],
+            column: vec![0.0f32; self.stack.len()],
             last_x: i32::MIN,
             last_z: i32::MIN,
-            column_valid: false,
+            column_len: 0,
+            per_block_written: false,
+            top_surface: Vec::new(),
         }
     }
 
@@ -2023,6 +2828,26 @@ impl NoiseRouter {
         self.default_fluid_state
     }
 
+    pub fn random(&self) -> &RandomSource {
+        &self.random
+    }
+
+    pub fn aquifers_enabled(&self) -> bool {
+        self.aquifers_enabled
+    }
+
+    pub fn aquifer_random(&self) -> &RandomSource {
+        &self.aquifer_random
+    }
+
+    pub fn ore_veins_enabled(&self) -> bool {
+        self.ore_veins_enabled
+    }
+
+    pub fn ore_random(&self) -> &RandomSource {
+        &self.ore_random
+    }
+
     /// Return the Beta beach octave noise sampler (4 octaves, stream position 4).
     /// None for the modern router. Used by apply_beta_surface for beach/sand conditions.
     pub fn beta_beach_noise(&self) -> Option<&OctavePerlinNoise<f64>> {
@@ -2039,6 +2864,35 @@ impl NoiseRouter {
         self.evaluate_forward(self.final_density_index, pos, cache)
     }
 
+    /// Stack index of a router output.
+    pub fn root_index(&self, root: Root) -> usize {
+        match root {
+            Root::Barrier => self.barrier_index,
+            Root::FluidLevelFloodedness => self.fluid_level_floodedness_index,
+            Root::FluidLevelSpread => self.fluid_level_spread_index,
+            Root::Lava => self.lava_index,
+            Root::Temperature => self.temperature_index,
+            Root::Vegetation => self.vegetation_index,
+            Root::Continents => self.continents_index,
+            Root::Erosion => self.erosion_index,
+            Root::Depth => self.depth_index,
+            Root::Ridges => self.ridges_index,
+            Root::PreliminarySurfaceLevel => self.preliminary_surface_level_index,
+            Root::FinalDensity => self.final_density_index,
+            Root::VeinToggle => self.vein_toggle_index,
+            Root::VeinRidged => self.vein_ridged_index,
+            Root::VeinGap => self.vein_gap_index,
+        }
+    }
+
+    /// Evaluate any router output at `pos`.
+    ///
+    /// Column-only work is shared through `cache`, so sampling several roots
+    /// at the same XZ (e.g. all six climate parameters) only runs it once.
+    pub fn sample_root(&self, root: Root, pos: IVec3, cache: &mut DensityCache) -> f32 {
+        self.evaluate_forward(self.root_index(root), pos, cache)
+    }
+
     /// Evaluate final_density without caching (recursive, for validation/comparison).
     pub fn final_density_uncached(&self, pos: IVec3) -> f32 {
         DensityFunctionComponent::sample_from_stack(&self.stack[..=self.final_density_index], pos)
@@ -2105,6 +2959,7 @@ impl NoiseRouter {
             base_block_x,
             base_block_z,
             scratch: vec![0.0f32; self.stack.len()],
+            lane_scratch: vec![[0.0f32; COLUMN_LANES]; self.final_density_index + 1],
             #[cfg(feature = "batch-noise")]
             batch_scratch: vec![0.0f32; MAX_BATCH * (self.final_density_index + 1)],
             #[cfg(feature = "batch-noise")]
@@ -2113,30 +2968,34 @@ impl NoiseRouter {
             batch_noise_results: [0.0f32; MAX_BATCH],
             #[cfg(feature = "batch-noise")]
             batch_noise_positions: [(0.0f32, 0.0f32, 0.0f32); MAX_BATCH],
+            reused_edge: None,
         }
     }
 
     /// Pre-populate Zone A values at cell corner positions in the chunk column grid.
     /// Only evaluates the (h_cells+1)^2 = 25 corner positions (step by h_cell_blocks),
     /// not every block position. This matches exactly the positions sampled by `fill_plane`.
+    ///
+    /// An edge filled by `ColumnCache::reuse_edge_from` is left as is.
     pub fn populate_columns(&self, cache: &mut ColumnCache) {
         let zone_a_count = cache.zone_a_count;
         let grid_side = ColumnCache::GRID_SIDE;
         let step = self.h_cell_blocks as i32;
         let corners = (16 / step) + 1; // h_cells + 1
+        let reused_edge = cache.reused_edge.take();
         for cx in 0..corners {
             let local_x = cx * step;
             for cz in 0..corners {
                 let local_z = cz * step;
+                if reused_edge.is_some_and(|edge| edge.contains(local_x, local_z)) {
+                    continue;
+                }
                 let y0_pos = IVec3::new(
                     cache.base_block_x + local_x,
                     0,
                     cache.base_block_z + local_z,
                 );
-                for i in 0..zone_a_count {
-                    cache.scratch[i] =
-                        self.stack[i].sample_cached(&cache.scratch, &self.stack, y0_pos);
-                }
+                self.sample_zone_a(&mut cache.scratch, zone_a_count, y0_pos);
                 let xz_idx = (local_x * grid_side + local_z) as usize;
                 let off = xz_idx * zone_a_count;
                 cache.column_data[off..off + zone_a_count]
@@ -2145,6 +3004,61 @@ impl NoiseRouter {
         }
     }
 
+    /// [`Self::populate_columns`] with the corners split across the compute
+    /// task pool. Each task samples into its own scratch buffer and writes
+    /// only its corners' columns, so the result is bit-identical to the
+    /// serial populate. Falls back to it on a single-threaded pool.
+    #[cfg(feature = "parallel-columns")]
+    pub fn populate_columns_parallel(&self, cache: &mut ColumnCache) {
+        use bevy_tasks::{ComputeTaskPool, TaskPool};
+
+        let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+        let zone_a_count = cache.zone_a_count;
+        if pool.thread_num() <= 1 || zone_a_count == 0 {
+            self.populate_columns(cache);
+            return;
+        }
+        let grid_side = ColumnCache::GRID_SIDE;
+        let step = self.h_cell_blocks as i32;
+        let reused_edge = cache.reused_edge.take();
+        let (base_x, base_z) = (cache.base_block_x, cache.base_block_z);
+        let mut corners: Vec<(IVec3, &mut [f32])> = cache
+            .column_data
+            .chunks_mut(zone_a_count)
+            .enumerate()
+            .filter_map(|(xz_idx, column)| {
+                let local_x = xz_idx as i32 / grid_side;
+                let local_z = xz_idx as i32 % grid_side;
+                let corner = local_x % step == 0 && local_z % step == 0;
+                let reused = reused_edge.is_some_and(|edge| edge.contains(local_x, local_z));
+                (corner && !reused)
+                    .then(|| (IVec3::new(base_x + local_x, 0, base_z + local_z), column))
+            })
+            .collect();
+        let per_task = corners.len().div_ceil(pool.thread_num()).max(1);
+        let stack_len = self.stack.len();
+        let groups: Vec<_> = corners.chunks_mut(per_task).collect();
+        pool.scope(move |scope| {
+            for group in groups {
+                scope.spawn(async move {
+                    let mut scratch = vec![0.0f32; stack_len];
+                    for (y0_pos, column) in group {
+                        self.sample_zone_a(&mut scratch, zone_a_count, *y0_pos);
+                        column.copy_from_slice(&scratch[..zone_a_count]);
+                    }
+                });
+            }
+        });
+    }
+
+    /// Forward sweep of the first `zone_a_count` entries at `y0_pos` into `scratch`.
+    #[inline]
+    fn sample_zone_a(&self, scratch: &mut [f32], zone_a_count: usize, y0_pos: IVec3) {
+        for i in 0..zone_a_count {
+            scratch[i] = self.stack[i].sample_cached(scratch, &self.stack, y0_pos);
+        }
+    }
+
     /// Read post-processed (temperature, humidity) for a column position from Zone A cache.
     ///
     /// Returns (0.0, 0.0) when climate nodes are not wired into the router (e.g., modern path).
@@ -2164,6 +3078,58 @@ impl NoiseRouter {
         (temperature, humidity)
     }
 
+    /// Create a [`BiomeColumnCache`] for the 4x4 quart columns starting at
+    /// `(base_quart_x, base_quart_z)`, running the column pass of every
+    /// climate root at each of them up front.
+    pub fn new_biome_column_cache(&self, base_quart_x: i32, base_quart_z: i32) -> BiomeColumnCache {
+        let column_len = Root::CLIMATE
+            .iter()
+            .map(|&root| self.root_index(root) + 1)
+            .max()
+            .unwrap_or(0);
+        let grid_side = BiomeColumnCache::GRID_SIDE;
+        let mut column_data = vec![0.0f32; (grid_side * grid_side) as usize * column_len];
+        let mut density = self.new_cache();
+        for local_x in 0..grid_side {
+            for local_z in 0..grid_side {
+                let y0_pos = IVec3::new(
+                    (base_quart_x + local_x) * 4,
+                    0,
+                    (base_quart_z + local_z) * 4,
+                );
+                for i in 0..column_len {
+                    density.scratch[i] =
+                        self.stack[i].sample_cached(&density.scratch, &self.stack, y0_pos);
+                }
+                let off = (local_x * grid_side + local_z) as usize * column_len;
+                column_data[off..off + column_len].copy_from_slice(&density.scratch[..column_len]);
+            }
+        }
+        BiomeColumnCache {
+            column_data,
+            column_len,
+            base_quart_x,
+            base_quart_z,
+            density,
+        }
+    }
+
+    /// Evaluate a router output at the block corner of `quart_pos`, starting
+    /// from the column pass stored in `cache`. Gives the same value as
+    /// [`Self::sample_root`] at `quart_pos * 4`.
+    ///
+    /// Panics if `quart_pos` is outside the cache's 4x4 columns.
+    pub fn sample_root_from_biome_cache(
+        &self,
+        root: Root,
+        quart_pos: IVec3,
+        cache: &mut BiomeColumnCache,
+    ) -> f32 {
+        let pos = quart_pos * 4;
+        cache.load_column(pos);
+        self.evaluate_forward(self.root_index(root), pos, &mut cache.density)
+    }
+
     /// Return the f64 Beta terrain noises, if this is a Beta router.
     /// None for the modern overworld router.
     pub fn beta_terrain_f64(&self) -> Option<&beta_terrain_f64::BetaTerrainF64> {
@@ -2219,34 +3185,234 @@ impl NoiseRouter {
     /// Only evaluates Zone B entries (branchless, no column_changed check).
     #[inline]
     pub fn final_density_from_column_cache(&self, pos: IVec3, cache: &mut ColumnCache) -> f32 {
-        #[cfg(feature = "lazy-range-choice")]
         if let Some(rc) = &self.lazy_rc {
-            // Phase 1: Evaluate the common prefix up to the RangeChoice input.
-            for i in self.column_boundary..=rc.input_index {
-                cache.scratch[i] = self.stack[i].sample_cached(&cache.scratch, &self.stack, pos);
+            self.evaluate_lazy_zone_b(rc, pos, &mut cache.scratch, usize::MAX);
+            return cache.scratch[self.final_density_index];
+        }
+
+        for i in self.column_boundary..=self.final_density_index {
+            cache.scratch[i] = self.stack[i].sample_cached(&cache.scratch, &self.stack, pos);
+        }
+        cache.scratch[self.final_density_index]
+    }
+
+    /// Evaluate the Zone B entries `plan` selects at `pos` into `scratch`,
+    /// leaving entry `skip` (`usize::MAX` for none) as it is.
+    #[inline]
+    fn evaluate_lazy_zone_b(
+        &self,
+        mut plan: &LazyRangeChoice,
+        pos: IVec3,
+        scratch: &mut [f32],
+        skip: usize,
+    ) {
+        loop {
+            for &i in plan.prefix.iter() {
+                if i != skip {
+                    scratch[i] = self.stack[i].sample_cached(scratch, &self.stack, pos);
+                }
             }
 
-            // Phase 2: Check the RangeChoice condition and select the branch.
-            let input_val = cache.scratch[rc.input_index];
-            let in_range = input_val >= rc.min_inclusion && input_val < rc.max_exclusion;
+            let input_val = scratch[plan.input_index];
+            let in_range = input_val >= plan.min_inclusion && input_val < plan.max_exclusion;
             let branch = if in_range {
-                &rc.branch_when_in
+                &plan.when_in
             } else {
-                &rc.branch_when_out
+                &plan.when_out
             };
 
-            // Phase 3: Evaluate only the needed branch entries.
-            for &i in branch.iter() {
-                cache.scratch[i] = self.stack[i].sample_cached(&cache.scratch, &self.stack, pos);
+            match branch {
+                LazyBranch::Sweep(entries) => {
+                    for &i in entries.iter() {
+                        if i != skip {
+                            scratch[i] = self.stack[i].sample_cached(scratch, &self.stack, pos);
+                        }
+                    }
+                    return;
+                }
+                LazyBranch::Choice(next) => plan = next,
             }
+        }
+    }
 
-            return cache.scratch[self.final_density_index];
+    /// Evaluate exact (non-interpolated) `final_density` for a vertical run of Y
+    /// values at block `(x, z)`, which must be a cell-corner column filled by
+    /// `populate_columns`.
+    ///
+    /// The Zone A column is loaded once and broadcast, then Zone B is swept entry
+    /// by entry over `COLUMN_LANES` Y values at a time. Noise-free arithmetic nodes
+    /// (linear, affine, clamp, binary, wrappers) run as fixed-width lane loops the
+    /// compiler vectorizes; `std::simd` is nightly-only, so no explicit intrinsics.
+    /// Noise nodes are evaluated per lane, and nodes that need the full scratch
+    /// slice (splines, weird-scaled samplers) gather their lane into `scratch` first.
+    pub fn final_density_column_batch(
+        &self,
+        x: i32,
+        z: i32,
+        y_values: &[i32],
+        out: &mut [f32],
+        cache: &mut ColumnCache,
+    ) {
+        debug_assert_eq!(y_values.len(), out.len());
+        let za = self.column_boundary;
+        let fd = self.final_density_index;
+
+        cache.load_column(x - cache.base_block_x, z - cache.base_block_z);
+        let mut lanes = std::mem::take(&mut cache.lane_scratch);
+        for (row, &value) in lanes[..za].iter_mut().zip(&cache.scratch[..za]) {
+            *row = [value; COLUMN_LANES];
         }
 
-        for i in self.column_boundary..=self.final_density_index {
-            cache.scratch[i] = self.stack[i].sample_cached(&cache.scratch, &self.stack, pos);
+        for (ys, out) in y_values.chunks(COLUMN_LANES).zip(out.chunks_mut(COLUMN_LANES)) {
+            // Pad a short tail with its last Y; the extra lanes are discarded.
+            let mut pos = [IVec3::new(x, ys[ys.len() - 1], z); COLUMN_LANES];
+            for (p, &y) in pos.iter_mut().zip(ys) {
+                p.y = y;
+            }
+
+            for i in za..=fd {
+                let (done, rest) = lanes.split_at_mut(i);
+                let dst = &mut rest[0];
+                match &self.stack[i] {
+                    DensityFunctionComponent::Independent(_) => {
+                        for l in 0..COLUMN_LANES {
+                            dst[l] = self.stack[i].sample_cached(&[], &self.stack, pos[l]);
+                        }
+                    }
+                    DensityFunctionComponent::Dependent(DependentDensityFunction::Linear(f)) => {
+                        let a = &done[f.input_index];
+                        match f.operation {
+                            LinearOperation::Add => {
+                                for l in 0..COLUMN_LANES {
+                                    dst[l] = a[l] + f.argument;
+                                }
+                            }
+                            LinearOperation::Multiply => {
+                                for l in 0..COLUMN_LANES {
+                                    dst[l] = a[l] * f.argument;
+                                }
+                            }
+                        }
+                    }
+                    DensityFunctionComponent::Dependent(DependentDensityFunction::Affine(f)) => {
+                        let a = &done[f.input_index];
+                        for l in 0..COLUMN_LANES {
+                            dst[l] = a[l].mul_add(f.scale, f.offset);
+                        }
+                    }
+                    DensityFunctionComponent::Dependent(
+                        DependentDensityFunction::PiecewiseAffine(f),
+                    ) => {
+                        let a = &done[f.input_index];
+                        for l in 0..COLUMN_LANES {
+                            let scale = if a[l] < 0.0 { f.neg_scale } else { f.pos_scale };
+                            dst[l] = a[l].mul_add(scale, f.offset);
+                        }
+                    }
+                    DensityFunctionComponent::Dependent(DependentDensityFunction::Clamp(f)) => {
+                        let a = &done[f.input_index];
+                        for l in 0..COLUMN_LANES {
+                            dst[l] = a[l].clamp(f.min_value, f.max_value);
+                        }
+                    }
+                    DensityFunctionComponent::Dependent(DependentDensityFunction::Binary(f)) => {
+                        let a = &done[f.input1_index];
+                        let b = &done[f.input2_index];
+                        match f.operation {
+                            BinaryOperation::Add => {
+                                for l in 0..COLUMN_LANES {
+                                    dst[l] = a[l] + b[l];
+                                }
+                            }
+                            BinaryOperation::Multiply => {
+                                for l in 0..COLUMN_LANES {
+                                    dst[l] = a[l] * b[l];
+                                }
+                            }
+                            BinaryOperation::Min => {
+                                for l in 0..COLUMN_LANES {
+                                    dst[l] = a[l].min(b[l]);
+                                }
+                            }
+                            BinaryOperation::Max => {
+                                for l in 0..COLUMN_LANES {
+                                    dst[l] = a[l].max(b[l]);
+                                }
+                            }
+                        }
+                    }
+                    DensityFunctionComponent::Dependent(DependentDensityFunction::Unary(f)) => {
+                        let a = &done[f.input_index];
+                        for l in 0..COLUMN_LANES {
+                            dst[l] = f.operation.apply(a[l]);
+                        }
+                    }
+                    DensityFunctionComponent::Dependent(DependentDensityFunction::Slide(f)) => {
+                        let a = &done[f.input_index];
+                        for l in 0..COLUMN_LANES {
+                            dst[l] = f.compute(a[l], pos[l].y as f32);
+                        }
+                    }
+                    DensityFunctionComponent::Dependent(
+                        DependentDensityFunction::RangeChoice(f),
+                    ) => {
+                        let a = &done[f.input_index];
+                        let when_in = &done[f.when_in_index];
+                        let when_out = &done[f.when_out_index];
+                        for l in 0..COLUMN_LANES {
+                            dst[l] = if a[l] >= f.min_inclusion_value
+                                && a[l] < f.max_exclusion_value
+                            {
+                                when_in[l]
+                            } else {
+                                when_out[l]
+                            };
+                        }
+                    }
+                    DensityFunctionComponent::Dependent(
+                        DependentDensityFunction::ShiftedNoise(f),
+                    ) => {
+                        let (sx, sy, sz) = (
+                            &done[f.input_x_index],
+                            &done[f.input_y_index],
+                            &done[f.input_z_index],
+                        );
+                        for l in 0..COLUMN_LANES {
+                            dst[l] = f.sampler.get(
+                                pos[l].x as f32 * f.xz_scale + sx[l],
+                                pos[l].y as f32 * f.y_scale + sy[l],
+                                pos[l].z as f32 * f.xz_scale + sz[l],
+                            );
+                        }
+                    }
+                    DensityFunctionComponent::Wrapper(f) => {
+                        let input_index = match f {
+                            WrapperDensityFunction::BlendDensity(x) => x.input_index,
+                            WrapperDensityFunction::Interpolated(x) => x.input_index,
+                            WrapperDensityFunction::FlatCache(x) => x.input_index,
+                            WrapperDensityFunction::Cache2d(x) => x.input_index,
+                            WrapperDensityFunction::CacheOnce(x) => x.input_index,
+                            WrapperDensityFunction::CacheAllInCell(x) => x.input_index,
+                        };
+                        *dst = done[input_index];
+                    }
+                    entry => {
+                        // Zone A is already in `scratch` from `load_column`; only the
+                        // Zone B prefix needs gathering for this lane.
+                        for l in 0..COLUMN_LANES {
+                            for (j, row) in done.iter().enumerate().skip(za) {
+                                cache.scratch[j] = row[l];
+                            }
+                            dst[l] = entry.sample_cached(&cache.scratch, &self.stack, pos[l]);
+                        }
+                    }
+                }
+            }
+
+            out.copy_from_slice(&lanes[fd][..out.len()]);
         }
-        cache.scratch[self.final_density_index]
+
+        cache.lane_scratch = lanes;
     }
 
     /// Batch-evaluate OldBlendedNoise for multiple positions.
@@ -2291,30 +3457,8 @@ impl NoiseRouter {
             cache.scratch[obn_skip] = obn_value;
         }
 
-        #[cfg(feature = "lazy-range-choice")]
         if let Some(rc) = &self.lazy_rc {
-            for i in self.column_boundary..=rc.input_index {
-                if i == obn_skip {
-                    continue;
-                }
-                cache.scratch[i] = self.stack[i].sample_cached(&cache.scratch, &self.stack, pos);
-            }
-
-            let input_val = cache.scratch[rc.input_index];
-            let in_range = input_val >= rc.min_inclusion && input_val < rc.max_exclusion;
-            let branch = if in_range {
-                &rc.branch_when_in
-            } else {
-                &rc.branch_when_out
-            };
-
-            for &i in branch.iter() {
-                if i == obn_skip {
-                    continue;
-                }
-                cache.scratch[i] = self.stack[i].sample_cached(&cache.scratch, &self.stack, pos);
-            }
-
+            self.evaluate_lazy_zone_b(rc, pos, &mut cache.scratch, obn_skip);
             return cache.scratch[self.final_density_index];
         }
 
@@ -2453,32 +3597,7 @@ impl NoiseRouter {
                         for p in 0..n {
                             let base = p * stack_len;
                             let density = cache.batch_scratch[base + x.input_index];
-                            let (amp, coord_mul) = match x.mapper {
-                                RarityValueMapper::Type1 => {
-                                    if density < -0.5 {
-                                        (0.75, 1.0 / 0.75)
-                                    } else if density < 0.0 {
-                                        (1.0, 1.0)
-                                    } else if density < 0.5 {
-                                        (1.5, 1.0 / 1.5)
-                                    } else {
-                                        (2.0, 0.5)
-                                    }
-                                }
-                                RarityValueMapper::Type2 => {
-                                    if density < -0.75 {
-                                        (0.5, 2.0)
-                                    } else if density < -0.5 {
-                                        (0.75, 1.0 / 0.75)
-                                    } else if density < 0.5 {
-                                        (1.0, 1.0)
-                                    } else if density < 0.75 {
-                                        (2.0, 0.5)
-                                    } else {
-                                        (3.0, 1.0 / 3.0)
-                                    }
-                                }
-                            };
+                            let (amp, coord_mul) = rarity_scale(x.mapper, density);
                             cache.batch_scratch[base + i] = amp
                                 * x.sampler
                                     .get(
@@ -2990,75 +4109,173 @@ impl NoiseRouter {
     ///   Zone B `[column_boundary..fd_boundary)`: per-Y entries for final_density
     ///   Zone C `[fd_boundary..n)`:               other roots (aquifer, veins, etc.)
     ///
-    /// For Zone A roots (continents, erosion, ridges, etc.):
-    ///   Only the column pass runs; the per-Y loop is empty.
+    /// For column-only Zone A roots (continents, erosion, ridges, etc.):
+    ///   Only the column pass runs; the per-Y loop is empty. A per-block Zone A
+    ///   root (only shielded from final_density by a FlatCache) takes the
+    ///   Zone C path, so it is still evaluated at its actual Y.
     ///
     /// For Zone B roots (final_density and its per-Y dependencies):
     ///   Column pass evaluates Zone A at Y=0; per-Y pass sweeps Zone B branchlessly.
     ///
     /// For Zone C roots (barrier, temperature, veins, etc.):
     ///   Falls back to the general per_block-checking approach.
+    ///
+    /// The column pass only covers entries not already evaluated for this column,
+    /// so different roots can share one `DensityCache`.
     fn evaluate_forward(&self, root: usize, pos: IVec3, cache: &mut DensityCache) -> f32 {
-        let column_changed = pos.x != cache.last_x || pos.z != cache.last_z || !cache.column_valid;
+        if pos.x != cache.last_x || pos.z != cache.last_z {
+            cache.invalidate();
+            cache.last_x = pos.x;
+            cache.last_z = pos.z;
+        }
 
-        if root < self.column_boundary {
+        if root < self.column_boundary && !self.per_block[root] {
             // Zone A root: column-only (e.g., continents, erosion, ridges)
-            if column_changed {
-                cache.last_x = pos.x;
-                cache.last_z = pos.z;
-                let y0_pos = IVec3::new(pos.x, 0, pos.z);
-                for i in 0..=root {
-                    cache.scratch[i] =
-                        self.stack[i].sample_cached(&cache.scratch, &self.stack, y0_pos);
-                }
-                cache.column_valid = true;
-            }
-        } else if root < self.fd_boundary {
-            // Zone B root: final_density path
-            if column_changed {
-                cache.last_x = pos.x;
-                cache.last_z = pos.z;
-                // Evaluate Zone A (column-only) entries at Y=0.
-                // This includes FlatCache inputs evaluated at Y=0 (correct for column caching).
-                let y0_pos = IVec3::new(pos.x, 0, pos.z);
-                for i in 0..self.column_boundary {
-                    cache.scratch[i] =
-                        self.stack[i].sample_cached(&cache.scratch, &self.stack, y0_pos);
-                }
-                cache.column_valid = true;
-            }
+            self.extend_column(root + 1, pos, cache);
+        } else if root >= self.column_boundary && root < self.fd_boundary {
+            // Zone B root: final_density path.
+            // Zone A is evaluated at Y=0, including FlatCache inputs (correct for column caching).
+            self.extend_column(self.column_boundary, pos, cache);
             // Evaluate Zone B (per-Y) entries at actual position — branchless.
             // All entries in this range are per_block=true by construction.
             for i in self.column_boundary..=root {
                 cache.scratch[i] = self.stack[i].sample_cached(&cache.scratch, &self.stack, pos);
             }
+            cache.per_block_written = true;
         } else {
             // Zone C root: fallback for aquifer, veins, temperature, etc.
-            if column_changed {
-                cache.last_x = pos.x;
-                cache.last_z = pos.z;
-                let y0_pos = IVec3::new(pos.x, 0, pos.z);
-                for i in 0..=root {
-                    cache.scratch[i] =
-                        self.stack[i].sample_cached(&cache.scratch, &self.stack, y0_pos);
-                }
-                cache.column_valid = true;
-            }
+            self.extend_column(root + 1, pos, cache);
             for i in 0..=root {
                 if self.per_block[i] {
-                    cache.scratch[i] =
-                        self.stack[i].sample_cached(&cache.scratch, &self.stack, pos);
+                    cache.scratch[i] = self.sample_per_block_entry(i, pos, cache);
                 }
             }
+            cache.per_block_written = true;
         }
 
         cache.scratch[root]
     }
 
+    /// Sample a per-block Zone C entry, reusing the column's `FindTopSurface`
+    /// scan when its upper bound is column-only and so cannot change with Y.
+    #[inline]
+    fn sample_per_block_entry(&self, i: usize, pos: IVec3, cache: &mut DensityCache) -> f32 {
+        let DensityFunctionComponent::Dependent(DependentDensityFunction::FindTopSurface(x)) =
+            &self.stack[i]
+        else {
+            return self.stack[i].sample_cached(&cache.scratch, &self.stack, pos);
+        };
+        if self.per_block[x.upper_bound_index] {
+            return self.stack[i].sample_cached(&cache.scratch, &self.stack, pos);
+        }
+        if let Some(&(_, y)) = cache.top_surface.iter().find(|(index, _)| *index == i) {
+            return y;
+        }
+        let y = self.stack[i].sample_cached(&cache.scratch, &self.stack, pos);
+        cache.top_surface.push((i, y));
+        y
+    }
+
+    /// Evaluate the column pass (at Y=0) for entries `cache.column_len..len`.
+    ///
+    /// Earlier entries are kept from previous calls on the same column, so roots
+    /// can be sampled in any order through one cache. A FlatCache or Cache2d
+    /// in the new range may read a per-block entry of the prefix, so per-block
+    /// values left there by an earlier root are put back to their Y=0 values.
+    #[inline]
+    fn extend_column(&self, len: usize, pos: IVec3, cache: &mut DensityCache) {
+        let start = cache.column_len;
+        if start >= len {
+            return;
+        }
+        if cache.per_block_written {
+            cache.scratch[..start].copy_from_slice(&cache.column[..start]);
+            cache.per_block_written = false;
+        }
+        let y0_pos = IVec3::new(pos.x, 0, pos.z);
+        for i in start..len {
+            cache.scratch[i] = self.stack[i].sample_cached(&cache.scratch, &self.stack, y0_pos);
+        }
+        cache.column[start..len].copy_from_slice(&cache.scratch[start..len]);
+        cache.column_len = len;
+    }
+
     /// Create a new `NoiseCellInterpolator` matching this router's cell dimensions.
     pub fn new_noise_cell_interpolator(&self) -> NoiseCellInterpolator {
         NoiseCellInterpolator::new(self.h_cell_blocks, self.v_cell_blocks)
     }
+
+    /// Fill `out` with the interpolated `final_density` of every block in the
+    /// 16x16x16 section at section coordinates (`section_x`, `section_y`,
+    /// `section_z`), indexed `out[(local_y * 16 + local_z) * 16 + local_x]`.
+    ///
+    /// `column_cache` must be populated for the section's chunk column (see
+    /// [`Self::populate_columns`]), and a grid precomputed on `interp` must be
+    /// for the same column. When the previous section filled with `interp` is
+    /// the one directly below, its top corner row is reused; otherwise every
+    /// corner is evaluated.
+    pub fn generate_section(
+        &self,
+        section_x: i32,
+        section_y: i32,
+        section_z: i32,
+        out: &mut [f32; 4096],
+        interp: &mut NoiseCellInterpolator,
+        column_cache: &mut ColumnCache,
+    ) {
+        let block_x = section_x * 16;
+        let block_y = section_y * 16;
+        let block_z = section_z * 16;
+        debug_assert_eq!(
+            (column_cache.base_block_x, column_cache.base_block_z),
+            (block_x, block_z),
+            "column cache is for another chunk column"
+        );
+        if interp.section_boundary_y() != Some(block_y) {
+            interp.reset_section_boundary();
+        }
+
+        let h_cell_blocks = interp.h_cell_blocks();
+        let v_cell_blocks = interp.v_cell_blocks();
+        let h_cells = interp.h_cells();
+        let v_cells = interp.v_cells();
+
+        interp.fill_plane_cached_reuse(0, true, block_x, block_y, block_z, self, column_cache);
+        for cell_x in 0..h_cells {
+            let next_x = block_x + ((cell_x + 1) * h_cell_blocks) as i32;
+            interp.fill_plane_cached_reuse(
+                cell_x + 1,
+                false,
+                next_x,
+                block_y,
+                block_z,
+                self,
+                column_cache,
+            );
+
+            for cell_z in 0..h_cells {
+                for cell_y in 0..v_cells {
+                    interp.on_sampled_cell_corners(cell_y, cell_z);
+                    for local_y in 0..v_cell_blocks {
+                        interp.interpolate_y(local_y as f32 / v_cell_blocks as f32);
+                        let y = cell_y * v_cell_blocks + local_y;
+                        for local_x in 0..h_cell_blocks {
+                            interp.interpolate_x(local_x as f32 / h_cell_blocks as f32);
+                            let x = cell_x * h_cell_blocks + local_x;
+                            for local_z in 0..h_cell_blocks {
+                                interp.interpolate_z(local_z as f32 / h_cell_blocks as f32);
+                                let z = cell_z * h_cell_blocks + local_z;
+                                out[(y * 16 + z) * 16 + x] = interp.result();
+                            }
+                        }
+                    }
+                }
+            }
+
+            interp.swap_buffers();
+        }
+        interp.end_section();
+    }
 }
 
 /// Trilinear interpolator for chunk section noise generation.
@@ -3096,9 +4313,11 @@ pub struct NoiseCellInterpolator {
     /// sequential fill_plane call index within a section (0..=h_cells) and
     /// z_count = h_cells + 1.
     saved_top_y: Vec<f32>,
-    /// True after the first section has been fully processed, meaning
-    /// `saved_top_y` contains valid data for the next section.
-    section_boundary_valid: bool,
+    /// Y of the row in `saved_top_y` once a section has been fully processed;
+    /// the next section must start at exactly this Y to reuse it.
+    section_boundary_y: Option<i32>,
+    /// Top corner row Y of the section currently being filled.
+    section_top_y: i32,
 
     /// Precomputed corner densities for the whole column:
     /// `grid[(plane_x * (h_cells + 1) + z_corner) * grid_rows + y_row]`.
@@ -3133,7 +4352,8 @@ impl NoiseCellInterpolator {
             after_x: [0.0f32; 2],
             val: 0.0f32,
             saved_top_y: vec![0.0f32; num_planes * z_count],
-            section_boundary_valid: false,
+            section_boundary_y: None,
+            section_top_y: 0,
             #[cfg(feature = "batch-noise")]
             grid: Vec::new(),
             #[cfg(feature = "batch-noise")]
@@ -3324,13 +4544,16 @@ impl NoiseCellInterpolator {
     }
 
     /// Like `fill_plane_cached`, but reuses the top-Y row from the previous section
-    /// as the bottom-Y row of this section when `section_boundary_valid` is true.
+    /// as the bottom-Y row of this section after [`end_section`](Self::end_section).
     ///
     /// `plane_seq` identifies which X-plane is being filled (0 = start plane,
     /// 1..=h_cells = successive end planes). This index is used to look up the
     /// correct saved top-Y row from the previous section.
     ///
     /// After filling, the top-Y row (cy = v_cells) is saved for the next section.
+    /// Sections must therefore be filled bottom-up and contiguously; after a gap
+    /// or any other order, call [`reset_section_boundary`](Self::reset_section_boundary)
+    /// first. Debug builds panic if `base_y` isn't the saved row's Y.
     pub fn fill_plane_cached_reuse(
         &mut self,
         plane_seq: usize,
@@ -3349,7 +4572,15 @@ impl NoiseCellInterpolator {
         let v_stride = self.v_cells + 1;
         let local_x = x - column_cache.base_block_x;
         let z_count = self.h_cells + 1;
-        let reuse = self.section_boundary_valid;
+        let reuse = self.section_boundary_y.is_some();
+        if let Some(boundary_y) = self.section_boundary_y {
+            debug_assert_eq!(
+                base_y, boundary_y,
+                "section at y {base_y} would reuse the corner row at y {boundary_y}; \
+                 call reset_section_boundary after a gap"
+            );
+        }
+        self.section_top_y = base_y + (self.v_cells * self.v_cell_blocks) as i32;
 
         // Fast path: copy the plane from the precomputed column grid.
         #[cfg(feature = "batch-noise")]
@@ -3460,15 +4691,22 @@ impl NoiseCellInterpolator {
     /// a section are done.
     #[inline]
     pub fn end_section(&mut self) {
-        self.section_boundary_valid = true;
+        self.section_boundary_y = Some(self.section_top_y);
     }
 
     /// Invalidate the Y-boundary cache, forcing the next section to compute
     /// all corner values from scratch. Must be called when the next section
-    /// is not adjacent to the current one (i.e. there is a gap in Y sections).
+    /// is not adjacent to the current one (i.e. there is a gap in Y sections,
+    /// or sections are filled top-down).
     #[inline]
     pub fn reset_section_boundary(&mut self) {
-        self.section_boundary_valid = false;
+        self.section_boundary_y = None;
+    }
+
+    /// Y at which the next section must start to reuse the saved boundary
+    /// row, or `None` if it will compute every corner.
+    pub fn section_boundary_y(&self) -> Option<i32> {
+        self.section_boundary_y
     }
 
     /// Load the 8 corner densities for a given cell from the start/end buffers.
@@ -3563,9 +4801,56 @@ impl NoiseCellInterpolator {
     pub fn result(&self) -> f32 {
         self.val
     }
+
+    /// Number of blocks in one cell: `h_cell_blocks² * v_cell_blocks`.
+    #[inline]
+    pub fn cell_block_count(&self) -> usize {
+        self.h_cell_blocks * self.h_cell_blocks * self.v_cell_blocks
+    }
+
+    /// Decide solid (`density > 0`) for every block of the cell loaded by
+    /// `on_sampled_cell_corners`, indexed
+    /// `solid[(local_y * h_cell_blocks + local_x) * h_cell_blocks + local_z]`.
+    ///
+    /// When all corners agree on sign, every block takes that decision without
+    /// per-block interpolation: a trilinear blend of same-sign corners keeps
+    /// the sign. Returns `corners_uniform_sign()` so callers can fill the cell
+    /// in bulk instead of reading `solid`.
+    #[inline]
+    pub fn fill_cell_blocks(&mut self, solid: &mut [bool]) -> Option<bool> {
+        let count = self.cell_block_count();
+        match self.corners_uniform_sign() {
+            Some(uniform) => {
+                solid[..count].fill(uniform);
+                Some(uniform)
+            }
+            None => {
+                self.interpolate_cell_blocks(solid);
+                None
+            }
+        }
+    }
+
+    /// The slow path of `fill_cell_blocks`: trilinearly interpolate every block
+    /// of the current cell, whatever the corner signs.
+    pub fn interpolate_cell_blocks(&mut self, solid: &mut [bool]) {
+        let h = self.h_cell_blocks;
+        let v = self.v_cell_blocks;
+        for local_y in 0..v {
+            self.interpolate_y(local_y as f32 / v as f32);
+            for local_x in 0..h {
+                self.interpolate_x(local_x as f32 / h as f32);
+                for local_z in 0..h {
+                    self.interpolate_z(local_z as f32 / h as f32);
+                    solid[(local_y * h + local_x) * h + local_z] = self.val > 0.0;
+                }
+            }
+        }
+    }
 }
 
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct BlendedNoise {
     xz_scale: f32,
     y_scale: f32,
@@ -3943,9 +5228,10 @@ impl DensityFunction for BlendedNoise {
 }
 
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Noise {
     noise_name: String,
-    sampler: NoiseSampler,
+    sampler: Arc<NoiseSampler>,
     xz_scale: f32,
     y_scale: f32,
 }
@@ -3987,9 +5273,10 @@ impl DensityFunction for Noise {
 }
 
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct ShiftA {
     noise_name: String,
-    sampler: NoiseSampler,
+    sampler: Arc<NoiseSampler>,
 }
 
 impl Debug for ShiftA {
@@ -4023,9 +5310,10 @@ impl DensityFunction for ShiftA {
 }
 
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct ShiftB {
     noise_name: String,
-    sampler: NoiseSampler,
+    sampler: Arc<NoiseSampler>,
 }
 
 impl Debug for ShiftB {
@@ -4059,9 +5347,10 @@ impl DensityFunction for ShiftB {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Shift {
     noise_name: String,
-    sampler: NoiseSampler,
+    sampler: Arc<NoiseSampler>,
 }
 
 impl RangeFunction for Shift {
@@ -4087,6 +5376,7 @@ impl DensityFunction for Shift {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct BlendDensity {
     input_index: usize,
     min_value: f32,
@@ -4113,6 +5403,7 @@ impl DensityFunction for BlendDensity {
 }
 
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Interpolated {
     input_index: usize,
 
@@ -4239,6 +5530,7 @@ impl Interpolated {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct FlatCache {
     input_index: usize,
     min_value: f32,
@@ -4264,6 +5556,7 @@ impl DensityFunction for FlatCache {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Cache2d {
     input_index: usize,
     min_value: f32,
@@ -4289,6 +5582,7 @@ impl DensityFunction for Cache2d {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct CacheOnce {
     input_index: usize,
     min_value: f32,
@@ -4314,6 +5608,7 @@ impl DensityFunction for CacheOnce {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct CacheAllInCell {
     input_index: usize,
     min_value: f32,
@@ -4339,6 +5634,7 @@ impl DensityFunction for CacheAllInCell {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct ClampedYGradient {
     from_y: f32,
     to_y: f32,
@@ -4369,29 +5665,98 @@ impl DensityFunction for ClampedYGradient {
     }
 }
 
+/// Vanilla `EndIslandDensityFunction`: the central island plus outer islands
+/// scattered on a 2D simplex grid. Depends on X/Z only, so it is evaluated
+/// once per column.
 #[derive(Clone, Debug, PartialEq)]
-enum IndependentDensityFunction {
-    Constant(f32),
-    OldBlendedNoise(OldBlendedNoise),
-    Noise(Noise),
-    ShiftA(ShiftA),
-    ShiftB(ShiftB),
-    Shift(Shift),
-    ClampedYGradient(ClampedYGradient),
-    EndIslands,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct EndIslands {
+    island_noise: SimplexNoise,
 }
 
-impl RangeFunction for IndependentDensityFunction {
-    fn min_value(&self) -> f32 {
-        match self {
-            IndependentDensityFunction::Constant(x) => *x,
-            IndependentDensityFunction::OldBlendedNoise(x) => x.min_value(),
-            IndependentDensityFunction::Noise(x) => x.min_value(),
-            IndependentDensityFunction::ShiftA(x) => x.min_value(),
+impl EndIslands {
+    fn new(seed: u64) -> Self {
+        let mut random = LegacyRandom::new(seed);
+        for _ in 0..17292 {
+            random.next_i32();
+        }
+        Self {
+            island_noise: SimplexNoise::from_random(&mut random),
+        }
+    }
+
+    /// Vanilla `getHeightValue` over island-grid coordinates (blocks / 8).
+    fn height_value(&self, x: i32, z: i32) -> f32 {
+        let chunk_x = x / 2;
+        let chunk_z = z / 2;
+        let offset_x = x % 2;
+        let offset_z = z % 2;
+        let distance = (x.wrapping_mul(x).wrapping_add(z.wrapping_mul(z)) as f32).sqrt();
+        let mut height = (100.0 - distance * 8.0).clamp(-100.0, 80.0);
+
+        for dx in -12..=12 {
+            for dz in -12..=12 {
+                let island_x = (chunk_x + dx) as i64;
+                let island_z = (chunk_z + dz) as i64;
+                if island_x * island_x + island_z * island_z > 4096
+                    && self.island_noise.sample_2d(island_x as f64, island_z as f64)
+                        < -0.9f32 as f64
+                {
+                    let falloff = ((island_x as f32).abs() * 3439.0
+                        + (island_z as f32).abs() * 147.0)
+                        % 13.0
+                        + 9.0;
+                    let h = (offset_x - dx * 2) as f32;
+                    let v = (offset_z - dz * 2) as f32;
+                    let island = (100.0 - (h * h + v * v).sqrt() * falloff).clamp(-100.0, 80.0);
+                    height = height.max(island);
+                }
+            }
+        }
+        height
+    }
+}
+
+impl RangeFunction for EndIslands {
+    fn min_value(&self) -> f32 {
+        -0.84375
+    }
+
+    fn max_value(&self) -> f32 {
+        0.5625
+    }
+}
+
+impl DensityFunction for EndIslands {
+    fn sample(&self, _stack: &[DensityFunctionComponent], pos: IVec3) -> f32 {
+        ((self.height_value(pos.x / 8, pos.z / 8) as f64 - 8.0) / 128.0) as f32
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum IndependentDensityFunction {
+    Constant(f32),
+    OldBlendedNoise(OldBlendedNoise),
+    Noise(Noise),
+    ShiftA(ShiftA),
+    ShiftB(ShiftB),
+    Shift(Shift),
+    ClampedYGradient(ClampedYGradient),
+    EndIslands(EndIslands),
+}
+
+impl RangeFunction for IndependentDensityFunction {
+    fn min_value(&self) -> f32 {
+        match self {
+            IndependentDensityFunction::Constant(x) => *x,
+            IndependentDensityFunction::OldBlendedNoise(x) => x.min_value(),
+            IndependentDensityFunction::Noise(x) => x.min_value(),
+            IndependentDensityFunction::ShiftA(x) => x.min_value(),
             IndependentDensityFunction::ShiftB(x) => x.min_value(),
             IndependentDensityFunction::Shift(x) => x.min_value(),
             IndependentDensityFunction::ClampedYGradient(x) => x.min_value(),
-            IndependentDensityFunction::EndIslands => -0.84375,
+            IndependentDensityFunction::EndIslands(x) => x.min_value(),
         }
     }
 
@@ -4404,7 +5769,7 @@ impl RangeFunction for IndependentDensityFunction {
             IndependentDensityFunction::ShiftB(x) => x.max_value(),
             IndependentDensityFunction::Shift(x) => x.max_value(),
             IndependentDensityFunction::ClampedYGradient(x) => x.max_value(),
-            IndependentDensityFunction::EndIslands => 0.5625,
+            IndependentDensityFunction::EndIslands(x) => x.max_value(),
         }
     }
 }
@@ -4419,15 +5784,13 @@ impl DensityFunction for IndependentDensityFunction {
             IndependentDensityFunction::ShiftB(x) => x.sample(stack, pos),
             IndependentDensityFunction::Shift(x) => x.sample(stack, pos),
             IndependentDensityFunction::ClampedYGradient(x) => x.sample(stack, pos),
-            IndependentDensityFunction::EndIslands => {
-                // TODO: implement proper end islands noise sampling
-                0.0
-            }
+            IndependentDensityFunction::EndIslands(x) => x.sample(stack, pos),
         }
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum DependentDensityFunction {
     Linear(Linear),
     Affine(Affine),
@@ -4445,6 +5808,7 @@ enum DependentDensityFunction {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum WrapperDensityFunction {
     BlendDensity(BlendDensity),
     Interpolated(Interpolated),
@@ -4552,6 +5916,7 @@ impl RangeFunction for DependentDensityFunction {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Linear {
     input_index: usize,
     min_value: f32,
@@ -4561,6 +5926,7 @@ struct Linear {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Affine {
     input_index: usize,
     scale: f32,
@@ -4570,6 +5936,7 @@ struct Affine {
 }
 
 #[derive(Clone, Debug, PartialEq, Copy, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum LinearOperation {
     Add,
     Multiply,
@@ -4618,6 +5985,7 @@ impl DensityFunction for Affine {
 ///
 /// Computes: `if x < 0 { x * neg_scale + offset } else { x * pos_scale + offset }`
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct PiecewiseAffine {
     input_index: usize,
     neg_scale: f32,
@@ -4691,6 +6059,7 @@ impl DensityFunction for PiecewiseAffine {
 /// the three offsets cancel out and the result equals `input + combined_offset`
 /// (which is typically ~0, i.e. identity).
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Slide {
     input_index: usize,
 
@@ -4796,6 +6165,7 @@ impl RangeFunction for Linear {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Unary {
     input_index: usize,
     min_value: f32,
@@ -4804,6 +6174,7 @@ struct Unary {
 }
 
 #[derive(Clone, Debug, PartialEq, Copy, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum UnaryOperation {
     Abs,
     Square,
@@ -4842,6 +6213,39 @@ impl UnaryOperation {
             }
         }
     }
+
+    /// Bounds of [`UnaryOperation::apply`] over inputs in `[min, max]`.
+    ///
+    /// Cube, the negative dampers and Squeeze never decrease, so the
+    /// endpoints are exact (Squeeze's slope `1/2 - x²/8` stays positive on
+    /// `[-1, 1]` and it is flat outside). Abs and Square bottom out at zero
+    /// when the range spans it and otherwise at the endpoint nearer zero.
+    /// Invert is unbounded once zero is a possible input.
+    pub fn range(&self, min: f32, max: f32) -> (f32, f32) {
+        let min_image = self.apply(min);
+        let max_image = self.apply(max);
+        match self {
+            UnaryOperation::Abs | UnaryOperation::Square => {
+                let low = if min <= 0.0 && max >= 0.0 {
+                    0.0
+                } else {
+                    min_image.min(max_image)
+                };
+                (low, min_image.max(max_image))
+            }
+            UnaryOperation::Invert => {
+                if min <= 0.0 && max >= 0.0 {
+                    (f32::NEG_INFINITY, f32::INFINITY)
+                } else {
+                    (max_image, min_image)
+                }
+            }
+            UnaryOperation::Cube
+            | UnaryOperation::HalfNegative
+            | UnaryOperation::QuarterNegative
+            | UnaryOperation::Squeeze => (min_image, max_image),
+        }
+    }
 }
 
 impl DensityFunction for Unary {
@@ -4865,6 +6269,7 @@ impl RangeFunction for Unary {
 }
 
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct ShiftedNoise {
     noise_name: String,
     input_x_index: usize,
@@ -4872,7 +6277,7 @@ struct ShiftedNoise {
     input_z_index: usize,
     xz_scale: f32,
     y_scale: f32,
-    sampler: NoiseSampler,
+    sampler: Arc<NoiseSampler>,
 }
 
 impl Debug for ShiftedNoise {
@@ -4921,10 +6326,11 @@ impl RangeFunction for ShiftedNoise {
 }
 
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct WeirdScaled {
     noise_name: String,
     input_index: usize,
-    sampler: NoiseSampler,
+    sampler: Arc<NoiseSampler>,
     mapper: RarityValueMapper,
 }
 
@@ -4957,36 +6363,10 @@ impl RangeFunction for WeirdScaled {
 }
 
 impl DensityFunction for WeirdScaled {
-    // todo: branchless
     #[inline]
     fn sample(&self, stack: &[DensityFunctionComponent], pos: IVec3) -> f32 {
         let density = DensityFunctionComponent::sample_from_stack(&stack[..=self.input_index], pos);
-        let (amp, coord_mul) = match self.mapper {
-            RarityValueMapper::Type1 => {
-                if density < -0.5 {
-                    (0.75, 1.0 / 0.75)
-                } else if density < 0.0 {
-                    (1.0, 1.0)
-                } else if density < 0.5 {
-                    (1.5, 1.0 / 1.5)
-                } else {
-                    (2.0, 0.5)
-                }
-            }
-            RarityValueMapper::Type2 => {
-                if density < -0.75 {
-                    (0.5, 2.0)
-                } else if density < -0.5 {
-                    (0.75, 1.0 / 0.75)
-                } else if density < 0.5 {
-                    (1.0, 1.0)
-                } else if density < 0.75 {
-                    (2.0, 0.5)
-                } else {
-                    (3.0, 1.0 / 3.0)
-                }
-            }
-        };
+        let (amp, coord_mul) = rarity_scale(self.mapper, density);
         amp * self
             .sampler
             .get(
@@ -4998,7 +6378,47 @@ impl DensityFunction for WeirdScaled {
     }
 }
 
+/// Rarity buckets of one `RarityValueMapper`. Bucket `i` covers inputs with
+/// `i` thresholds at or below them; Type1 has only three real thresholds and
+/// is padded with `+inf`, duplicating its last bucket.
+struct RarityTable {
+    thresholds: [f32; 4],
+    amplitudes: [f32; 5],
+    coord_multipliers: [f32; 5],
+}
+
+const RARITY_TYPE1: RarityTable = RarityTable {
+    thresholds: [-0.5, 0.0, 0.5, f32::INFINITY],
+    amplitudes: [0.75, 1.0, 1.5, 2.0, 2.0],
+    coord_multipliers: [1.0 / 0.75, 1.0, 1.0 / 1.5, 0.5, 0.5],
+};
+
+const RARITY_TYPE2: RarityTable = RarityTable {
+    thresholds: [-0.75, -0.5, 0.5, 0.75],
+    amplitudes: [0.5, 0.75, 1.0, 2.0, 3.0],
+    coord_multipliers: [2.0, 1.0 / 0.75, 1.0, 0.5, 1.0 / 3.0],
+};
+
+/// `(amplitude, coordinate multiplier)` that `WeirdScaled` applies for an
+/// input density. The bucket is found by counting thresholds above the input
+/// instead of an if-else ladder; NaN lands in the last bucket, as it did there.
+#[inline]
+fn rarity_scale(mapper: RarityValueMapper, density: f32) -> (f32, f32) {
+    let table = match mapper {
+        RarityValueMapper::Type1 => &RARITY_TYPE1,
+        RarityValueMapper::Type2 => &RARITY_TYPE2,
+    };
+    let above: usize = table
+        .thresholds
+        .iter()
+        .map(|&threshold| (density < threshold) as usize)
+        .sum();
+    let bucket = table.thresholds.len() - above;
+    (table.amplitudes[bucket], table.coord_multipliers[bucket])
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Clamp {
     input_index: usize,
     min_value: f32,
@@ -5026,6 +6446,7 @@ impl DensityFunction for Clamp {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct RangeChoice {
     input_index: usize,
     when_in_index: usize,
@@ -5066,6 +6487,7 @@ impl DensityFunction for RangeChoice {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum SplineValue {
     Spline(Spline),
     Constant(f32),
@@ -5097,14 +6519,20 @@ impl DensityFunction for SplineValue {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Segment {
     left: f32,
     inv_dist: f32,         // 1 / (x[i+1] - x[i])
     lower_deriv_dist: f32, // d[i]   * dist
     upper_deriv_dist: f32, // d[i+1] * dist
+    /// `[e0, e1 - e0, v0, v1 - v0]` for `Spline::eval_segment` when both ends
+    /// are constants, so sampling doesn't rebuild them; `None` when an end is
+    /// a nested spline.
+    coeffs: Option<[f32; 4]>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Spline {
     input_index: usize,
     min_value: f32,
@@ -5214,11 +6642,20 @@ impl Spline {
             let left = locations[i];
             let dist = locations[i + 1] - left;
             debug_assert!(dist > 0.0, "locations must be strictly increasing");
+            let lower_deriv_dist = derivatives[i] * dist;
+            let upper_deriv_dist = derivatives[i + 1] * dist;
+            let coeffs = match (&values[i], &values[i + 1]) {
+                (SplineValue::Constant(v0), SplineValue::Constant(v1)) => Some(
+                    Self::segment_coeffs(lower_deriv_dist, upper_deriv_dist, *v0, *v1),
+                ),
+                _ => None,
+            };
             segs.push(Segment {
                 left,
                 inv_dist: 1.0 / dist,
-                lower_deriv_dist: derivatives[i] * dist,
-                upper_deriv_dist: derivatives[i + 1] * dist,
+                lower_deriv_dist,
+                upper_deriv_dist,
+                coeffs,
             });
         }
 
@@ -5258,9 +6695,31 @@ impl Spline {
         }
     }
 
+    /// `[e0, e1 - e0, v0, v1 - v0]` of a segment with end values `v0` and
+    /// `v1`.
+    #[inline(always)]
+    fn segment_coeffs(lower_deriv_dist: f32, upper_deriv_dist: f32, v0: f32, v1: f32) -> [f32; 4] {
+        let delta = v1 - v0;
+        let e0 = lower_deriv_dist - delta;
+        let e1 = -upper_deriv_dist + delta;
+        [e0, e1 - e0, v0, delta]
+    }
+
+    /// Evaluate segment `i` at `location` as `cubic + linear`, in the same
+    /// operation order as rebuilding `e0`/`e1` per sample, so the result is
+    /// bit-identical. `ends` samples the end values and is only called when
+    /// one of them is a nested spline.
     #[inline(always)]
-    fn lerp(a: f32, b: f32, t: f32) -> f32 {
-        (b - a).mul_add(t, a)
+    fn eval_segment(&self, i: usize, location: f32, ends: impl FnOnce() -> (f32, f32)) -> f32 {
+        let seg = &self.segments[i];
+        let x = (location - seg.left) * seg.inv_dist;
+        let [e0, e_span, v0, v_span] = seg.coeffs.unwrap_or_else(|| {
+            let (v0, v1) = ends();
+            Self::segment_coeffs(seg.lower_deriv_dist, seg.upper_deriv_dist, v0, v1)
+        });
+        let cubic = (x * (1.0 - x)) * e_span.mul_add(x, e0);
+        let linear = v_span.mul_add(x, v0);
+        cubic + linear
     }
 }
 
@@ -5308,22 +6767,12 @@ impl DensityFunction for Spline {
 
         let i0 = idx_gt - 1;
         let i1 = idx_gt;
-
-        let v0 = self.values[i0].sample(stack, pos);
-        let v1 = self.values[i1].sample(stack, pos);
-
-        let seg = self.segments[i0];
-        let x = (location - seg.left) * seg.inv_dist;
-
-        let delta = v1 - v0;
-
-        let e0 = seg.lower_deriv_dist - delta;
-        let e1 = -seg.upper_deriv_dist + delta;
-
-        let cubic = (x * (1.0 - x)) * Self::lerp(e0, e1, x);
-        let linear = Self::lerp(v0, v1, x);
-
-        cubic + linear
+        self.eval_segment(i0, location, || {
+            (
+                self.values[i0].sample(stack, pos),
+                self.values[i1].sample(stack, pos),
+            )
+        })
     }
 }
 
@@ -5368,26 +6817,17 @@ impl Spline {
 
         let i0 = idx_gt - 1;
         let i1 = idx_gt;
-
-        let v0 = self.values[i0].sample_cached(cache, stack, pos);
-        let v1 = self.values[i1].sample_cached(cache, stack, pos);
-
-        let seg = self.segments[i0];
-        let x = (location - seg.left) * seg.inv_dist;
-
-        let delta = v1 - v0;
-
-        let e0 = seg.lower_deriv_dist - delta;
-        let e1 = -seg.upper_deriv_dist + delta;
-
-        let cubic = (x * (1.0 - x)) * Self::lerp(e0, e1, x);
-        let linear = Self::lerp(v0, v1, x);
-
-        cubic + linear
+        self.eval_segment(i0, location, || {
+            (
+                self.values[i0].sample_cached(cache, stack, pos),
+                self.values[i1].sample_cached(cache, stack, pos),
+            )
+        })
     }
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct FlattenedSpline {
     coord_indices: [usize; 3],
     coord_min: [f32; 3],
@@ -5524,6 +6964,7 @@ impl DensityFunction for FlattenedSpline {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct FindTopSurface {
     density_index: usize,
     upper_bound_index: usize,
@@ -5571,6 +7012,7 @@ impl DensityFunction for FindTopSurface {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Binary {
     input1_index: usize,
     input2_index: usize,
@@ -5601,6 +7043,10 @@ impl DensityFunction for Binary {
                 input1_density + input2_density
             }
             BinaryOperation::Multiply => {
+                // Vanilla's left-zero check: a zero input1 gives 0 even when
+                // input2 would be NaN or infinite. The cached paths multiply
+                // both sides, which only differs for such non-finite inputs,
+                // and `optimize_stack` may swap the operands on that basis.
                 if input1_density == 0.0 {
                     0.0
                 } else {
@@ -5652,6 +7098,7 @@ impl RangeFunction for Binary {
 }
 
 #[derive(Clone, Debug, PartialEq, Copy, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum BinaryOperation {
     Add,
     Multiply,
@@ -5660,6 +7107,7 @@ enum BinaryOperation {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum DensityFunctionComponent {
     Independent(IndependentDensityFunction),
     Dependent(DependentDensityFunction),
@@ -5693,7 +7141,7 @@ impl DensityFunctionComponent {
                         g.from_y, g.to_y, g.from_value, g.to_value
                     )
                 }
-                IndependentDensityFunction::EndIslands => "end_islands".into(),
+                IndependentDensityFunction::EndIslands(_) => "end_islands".into(),
             },
             DensityFunctionComponent::Dependent(f) => match f {
                 DependentDensityFunction::Linear(l) => match l.operation {
@@ -5769,6 +7217,26 @@ impl DensityFunctionComponent {
             _ => None,
         }
     }
+
+    /// Name and sampler of the noise this entry samples, if any.
+    #[cfg(feature = "serde")]
+    fn noise_sampler_mut(&mut self) -> Option<(&str, &mut Arc<NoiseSampler>)> {
+        match self {
+            DensityFunctionComponent::Independent(x) => match x {
+                IndependentDensityFunction::Noise(x) => Some((&x.noise_name, &mut x.sampler)),
+                IndependentDensityFunction::ShiftA(x) => Some((&x.noise_name, &mut x.sampler)),
+                IndependentDensityFunction::ShiftB(x) => Some((&x.noise_name, &mut x.sampler)),
+                IndependentDensityFunction::Shift(x) => Some((&x.noise_name, &mut x.sampler)),
+                _ => None,
+            },
+            DensityFunctionComponent::Dependent(x) => match x {
+                DependentDensityFunction::ShiftedNoise(x) => Some((&x.noise_name, &mut x.sampler)),
+                DependentDensityFunction::WeirdScaled(x) => Some((&x.noise_name, &mut x.sampler)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
 }
 
 impl TryFrom<DensityFunctionComponent> for f32 {
@@ -5965,7 +7433,7 @@ impl DensityFunctionComponent {
                 IndependentDensityFunction::ShiftB(x) => x.sample(&[], pos),
                 IndependentDensityFunction::Shift(x) => x.sample(&[], pos),
                 IndependentDensityFunction::ClampedYGradient(x) => x.sample(&[], pos),
-                IndependentDensityFunction::EndIslands => 0.0,
+                IndependentDensityFunction::EndIslands(x) => x.sample(&[], pos),
             },
             DensityFunctionComponent::Dependent(f) => match f {
                 DependentDensityFunction::Linear(x) => {
@@ -6006,32 +7474,7 @@ impl DensityFunctionComponent {
                 ),
                 DependentDensityFunction::WeirdScaled(x) => {
                     let density = cache[x.input_index];
-                    let (amp, coord_mul) = match x.mapper {
-                        RarityValueMapper::Type1 => {
-                            if density < -0.5 {
-                                (0.75, 1.0 / 0.75)
-                            } else if density < 0.0 {
-                                (1.0, 1.0)
-                            } else if density < 0.5 {
-                                (1.5, 1.0 / 1.5)
-                            } else {
-                                (2.0, 0.5)
-                            }
-                        }
-                        RarityValueMapper::Type2 => {
-                            if density < -0.75 {
-                                (0.5, 2.0)
-                            } else if density < -0.5 {
-                                (0.75, 1.0 / 0.75)
-                            } else if density < 0.5 {
-                                (1.0, 1.0)
-                            } else if density < 0.75 {
-                                (2.0, 0.5)
-                            } else {
-                                (3.0, 1.0 / 3.0)
-                            }
-                        }
-                    };
+                    let (amp, coord_mul) = rarity_scale(x.mapper, density);
                     amp * x
                         .sampler
                         .get(
@@ -6117,6 +7560,9 @@ struct FunctionStackBuilder<'a> {
     noises: &'a BTreeMap<Ident<String>, NoiseParam>,
     stack: Vec<DensityFunctionComponent>,
     built: HashMap<ProtoDensityFunction, usize>,
+    /// Samplers of referenced noises, built once per id and shared by every
+    /// node that samples them.
+    noise_samplers: HashMap<Ident<String>, Arc<NoiseSampler>>,
     builder_options: &'a ChunkNoiseFunctionBuilderOptions,
 }
 
@@ -6135,6 +7581,7 @@ impl<'a> FunctionStackBuilder<'a> {
             noises,
             stack: Vec::new(),
             built: HashMap::new(),
+            noise_samplers: HashMap::new(),
             builder_options,
         }
     }
@@ -6152,14 +7599,12 @@ impl<'a> FunctionStackBuilder<'a> {
         }
     }
 
-    fn component(&mut self, holder: &DensityFunctionHolder) -> (usize) {
+    /// Visit `holder` and return its stack index. References were resolved by
+    /// [`ReferenceChecker`] before building started.
+    fn component(&mut self, holder: &DensityFunctionHolder) -> usize {
         self.visit_density_function_holder(holder);
-        let idx = self.get_index(holder);
-        if idx.is_none() {
-            panic!("Component not found after visiting: {:?}", holder);
-        }
-        let idx = idx.unwrap();
-        idx
+        self.get_index(holder)
+            .expect("references are checked before building")
     }
 
     fn register_component(
@@ -6569,7 +8014,9 @@ impl<'a> Visitor for FunctionStackBuilder<'a> {
     fn visit_end_islands(&mut self) {
         self.register_component(
             ProtoDensityFunction::EndIslands,
-            DensityFunctionComponent::Independent(IndependentDensityFunction::EndIslands),
+            DensityFunctionComponent::Independent(IndependentDensityFunction::EndIslands(
+                EndIslands::new(self.world_seed),
+            )),
         );
     }
 
@@ -6792,10 +8239,7 @@ impl<'a> FunctionStackBuilder<'a> {
     fn unary(&mut self, arg: &SingleArgumentFunction, operation: UnaryOperation) {
         let (input_index) = self.component(&arg.argument);
         let input = &self.stack[input_index];
-        let min = input.min_value();
-        let max = input.max_value();
-        let min_image = operation.apply(min);
-        let max_image = operation.apply(max);
+        let (min_value, max_value) = operation.range(input.min_value(), input.max_value());
         let proto = match operation {
             UnaryOperation::Abs => ProtoDensityFunction::Abs(arg.clone()),
             UnaryOperation::Square => ProtoDensityFunction::Square(arg.clone()),
@@ -6806,20 +8250,6 @@ impl<'a> FunctionStackBuilder<'a> {
             UnaryOperation::Squeeze => ProtoDensityFunction::Squeeze(arg.clone()),
         };
 
-        let (min_value, max_value) = match operation {
-            UnaryOperation::Invert => {
-                if min < 0.0 && max > 0.0 {
-                    (f32::NEG_INFINITY, f32::INFINITY)
-                } else {
-                    (max_image, min_image)
-                }
-            }
-            UnaryOperation::Abs | UnaryOperation::Square => {
-                (min_image.max(0.0), min_image.max(max_image))
-            }
-            _ => (min_image, max_image),
-        };
-
         self.register_component(
             proto,
             DensityFunctionComponent::Dependent(DependentDensityFunction::Unary(Unary {
@@ -6842,14 +8272,21 @@ impl<'a> FunctionStackBuilder<'a> {
         }
     }
 
-    fn noise_sampler(&mut self, holder: &NoiseHolder) -> NoiseSampler {
+    fn noise_sampler(&mut self, holder: &NoiseHolder) -> Arc<NoiseSampler> {
         match holder {
-            NoiseHolder::Reference(x) => self.create_noise(x),
-            NoiseHolder::Owned(x) => NoiseSampler::new(
+            NoiseHolder::Reference(x) => {
+                if let Some(sampler) = self.noise_samplers.get(x) {
+                    return sampler.clone();
+                }
+                let sampler = Arc::new(self.create_noise(x));
+                self.noise_samplers.insert(x.clone(), sampler.clone());
+                sampler
+            }
+            NoiseHolder::Owned(x) => Arc::new(NoiseSampler::new(
                 &mut self.random.clone(),
                 x.first_octave,
                 x.amplitudes.iter().map(|x| x.0 as f32).collect(),
-            ),
+            )),
         }
     }
 
@@ -6906,11 +8343,10 @@ impl<'a> FunctionStackBuilder<'a> {
         }
 
         let mut random = self.random.clone().fork_hash(id.as_str());
-        let noise_param = self.noises.get(id);
-        if noise_param.is_none() {
-            panic!("Noise not loaded: {}", id);
-        }
-        let noise_param = noise_param.unwrap();
+        let noise_param = self
+            .noises
+            .get(id)
+            .expect("noise references are checked before building");
         NoiseSampler::new(
             &mut random,
             noise_param.first_octave,
@@ -6925,12 +8361,14 @@ pub fn lerp(delta: f32, start: f32, end: f32) -> f32 {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use crate::density_function::beta_seed::seed_beta_terrain;
     use crate::density_function::DensityFunction;
     use crate::proto::NoiseGeneratorSettings;
     use mcrs_random::RandomSource;
     use super::{BlendedNoise, OldBlendedNoise};
+    use mcrs_protocol::Ident;
+    use std::collections::BTreeMap;
 
     /// REGRESSION: modern BlendedNoise (formerly OldBlendedNoise) must sample
     /// to the same values as the post-07-01a baseline after the generalization.
@@ -6950,6 +8388,36 @@ mod tests {
         assert_eq!(v2.to_bits(), 1054301856u32, "v2 must match post-07-01a golden");
     }
 
+    /// Golden values from vanilla `EndIslandDensityFunction` with world seed 0.
+    #[test]
+    fn end_islands_matches_vanilla() {
+        let islands = super::EndIslands::new(0);
+        let sample = |x, z| islands.sample(&[], bevy_math::IVec3::new(x, 64, z));
+
+        // Central island peak and the void ring between it and the outer islands.
+        assert_eq!(sample(0, 0), 0.5625);
+        assert_eq!(sample(800, 0), -0.84375);
+
+        assert_eq!(sample(10000, 0), -0.46875);
+        assert_eq!(sample(-20000, 15000), 0.17115003);
+        assert_eq!(sample(-3000, -7000), -0.41403556);
+        assert_eq!(sample(5000, 5000), -0.05567324);
+
+        // Outer islands are sparse: far-out terrain is mostly below zero.
+        let mut sum = 0.0f32;
+        let mut count = 0;
+        for x in (-40000..=40000).step_by(2000) {
+            for z in (-40000..=40000).step_by(2000) {
+                if x.abs() < 4000 && z.abs() < 4000 {
+                    continue;
+                }
+                sum += sample(x, z);
+                count += 1;
+            }
+        }
+        assert!(sum / (count as f32) < 0.0);
+    }
+
     /// Verify that disabling the /128 divisor yields exactly 128x the enabled-divisor output.
     #[test]
     fn blended_noise_no_128_divisor() {
@@ -7025,7 +8493,7 @@ mod tests {
             serde_json::from_str(&json).expect("beta.json should deserialize");
         let functions = load_density_functions_from_disk();
         let noises = BTreeMap::new();
-        let router = super::build_functions(&functions, &noises, &settings, 12345, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86));
+        let router = super::build_functions(&functions, &noises, &settings, 12345, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86)).unwrap();
 
         // Sample a column at multiple Y values to find a sign flip
         let mut all_densities = vec![];
@@ -7053,7 +8521,7 @@ mod tests {
             serde_json::from_str(&json).expect("beta.json should deserialize");
         let functions = load_density_functions_from_disk();
         let noises = BTreeMap::new();
-        let router = super::build_functions(&functions, &noises, &settings, 12345, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86));
+        let router = super::build_functions(&functions, &noises, &settings, 12345, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86)).unwrap();
         // Zone A must contain the two FlatCache'd 2D nodes (scale/depth).
         assert!(router.column_boundary() > 0, "Zone A must be non-empty (FlatCache 2D scale/depth nodes)");
         // final_density must be wired into Zone B.
@@ -7164,7 +8632,7 @@ mod tests {
             serde_json::from_str(&json).expect("beta.json should deserialize");
         let functions = load_density_functions_from_disk();
         let noises = BTreeMap::new();
-        let router = super::build_functions(&functions, &noises, &settings, 845, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86));
+        let router = super::build_functions(&functions, &noises, &settings, 845, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86)).unwrap();
         let mut i = 0;
         let mut max_diff = 0.0_f32;
         for cx in 0..5i32 {
@@ -7211,7 +8679,7 @@ mod tests {
             serde_json::from_str(&json).expect("beta.json should deserialize");
         let functions = load_density_functions_from_disk();
         let noises = BTreeMap::new();
-        let router = super::build_functions(&functions, &noises, &settings, 845, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86));
+        let router = super::build_functions(&functions, &noises, &settings, 845, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86)).unwrap();
         for cx in 0..5i32 {
             for cz in 0..5i32 {
                 for cy in 0..17i32 {
@@ -7278,7 +8746,7 @@ mod tests {
     }
 
     /// Load all density_function JSON assets recursively into a `ProtoDensityFunction` map.
-    fn load_density_functions_from_disk() -> std::collections::BTreeMap<mcrs_protocol::Ident<String>, crate::density_function::ProtoDensityFunction> {
+    pub(crate) fn load_density_functions_from_disk() -> std::collections::BTreeMap<mcrs_protocol::Ident<String>, crate::density_function::ProtoDensityFunction> {
         let base = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
             .join("../../assets/minecraft/worldgen/density_function");
         let mut map = std::collections::BTreeMap::new();
@@ -7287,7 +8755,7 @@ mod tests {
     }
 
     /// Load all noise JSON assets into a `NoiseParam` map.
-    fn load_noises_from_disk() -> std::collections::BTreeMap<mcrs_protocol::Ident<String>, crate::density_function::proto::NoiseParam> {
+    pub(crate) fn load_noises_from_disk() -> std::collections::BTreeMap<mcrs_protocol::Ident<String>, crate::density_function::proto::NoiseParam> {
         let base = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
             .join("../../assets/minecraft/worldgen/noise");
         let mut map = std::collections::BTreeMap::new();
@@ -7310,25 +8778,156 @@ mod tests {
         map
     }
 
+    /// The vanilla `overworld.json` noise settings, as text.
+    pub(crate) fn overworld_json() -> String {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../assets/minecraft/worldgen/noise_settings/overworld.json"
+        );
+        std::fs::read_to_string(path).expect("overworld.json must exist")
+    }
+
+    /// The vanilla overworld noise settings.
+    pub(crate) fn overworld_settings() -> NoiseGeneratorSettings {
+        serde_json::from_str(&overworld_json()).expect("overworld.json must deserialize")
+    }
+
+    /// `overworld.json` with its `noise_router` object edited by `edit`.
+    pub(crate) fn overworld_settings_with(
+        edit: impl FnOnce(&mut serde_json::Value),
+    ) -> NoiseGeneratorSettings {
+        let mut value: serde_json::Value = serde_json::from_str(&overworld_json()).unwrap();
+        edit(&mut value["noise_router"]);
+        serde_json::from_value(value).expect("edited overworld.json must deserialize")
+    }
+
+    /// The router the tests sample: `settings` built for seed 2 with stone
+    /// and water as the default block and fluid.
+    pub(crate) fn overworld_router_with(settings: &NoiseGeneratorSettings) -> super::NoiseRouter {
+        super::build_functions(
+            &load_density_functions_from_disk(),
+            &load_noises_from_disk(),
+            settings,
+            2,
+            mcrs_protocol::BlockStateId(1),
+            mcrs_protocol::BlockStateId(86),
+        )
+        .unwrap()
+    }
+
+    /// [`overworld_router_with`] the unedited overworld settings.
+    pub(crate) fn overworld_router() -> super::NoiseRouter {
+        overworld_router_with(&overworld_settings())
+    }
+
+    fn build_error(
+        functions: &BTreeMap<Ident<String>, super::ProtoDensityFunction>,
+        noises: &BTreeMap<Ident<String>, super::proto::NoiseParam>,
+        settings: &NoiseGeneratorSettings,
+    ) -> super::WorldgenError {
+        let result = super::build_functions(
+            functions,
+            noises,
+            settings,
+            2,
+            mcrs_protocol::BlockStateId(1),
+            mcrs_protocol::BlockStateId(86),
+        );
+        result.err().expect("build must fail")
+    }
+
+    fn ident(id: &str) -> Ident<String> {
+        id.parse().unwrap()
+    }
+
+    #[test]
+    fn missing_noise_names_the_referencing_function() {
+        let settings = overworld_settings_with(|_| {});
+        let functions = load_density_functions_from_disk();
+        let mut noises = load_noises_from_disk();
+        noises.remove(&ident("minecraft:ridge"));
+
+        let error = build_error(&functions, &noises, &settings);
+        assert_eq!(
+            error,
+            super::WorldgenError::MissingNoise {
+                noise: ident("minecraft:ridge"),
+                referrer: super::Referrer::DensityFunction(ident("minecraft:overworld/ridges")),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "density function `minecraft:overworld/ridges` references noise \
+             `minecraft:ridge`, which is not loaded"
+        );
+    }
+
+    #[test]
+    fn missing_inline_noise_names_the_router_field() {
+        let settings = overworld_settings_with(|router| {
+            router["barrier"] = serde_json::json!({
+                "type": "minecraft:noise",
+                "noise": "mcrs:missing",
+                "xz_scale": 1.0,
+                "y_scale": 0.5
+            });
+        });
+        let error = build_error(
+            &load_density_functions_from_disk(),
+            &load_noises_from_disk(),
+            &settings,
+        );
+        assert_eq!(
+            error.to_string(),
+            "noise router field `barrier` references noise `mcrs:missing`, which is not loaded"
+        );
+    }
+
+    #[test]
+    fn missing_density_function_is_an_error() {
+        let settings = overworld_settings_with(|router| {
+            router["depth"] = serde_json::json!("mcrs:missing");
+        });
+        let error = build_error(
+            &load_density_functions_from_disk(),
+            &load_noises_from_disk(),
+            &settings,
+        );
+        assert_eq!(
+            error,
+            super::WorldgenError::MissingDensityFunction {
+                function: ident("mcrs:missing"),
+                referrer: super::Referrer::RouterField("depth"),
+            }
+        );
+    }
+
+    #[test]
+    fn reference_cycle_is_an_error() {
+        let settings = overworld_settings_with(|router| {
+            router["barrier"] = serde_json::json!("mcrs:a");
+        });
+        let mut functions = load_density_functions_from_disk();
+        for (id, argument) in [("mcrs:a", "mcrs:b"), ("mcrs:b", "mcrs:a")] {
+            let function = serde_json::json!({ "type": "minecraft:abs", "argument": argument });
+            functions.insert(ident(id), serde_json::from_value(function).unwrap());
+        }
+        let error = build_error(&functions, &load_noises_from_disk(), &settings);
+        assert_eq!(error, super::WorldgenError::ReferenceCycle(ident("mcrs:a")));
+    }
+
     /// Regression gate: the modern NoiseRouter built from overworld.json via
     /// build_functions must remain structurally and numerically unchanged after
     /// the data-driven preset refactor.  Any perturbation to the modern path
     /// (wrong seed forwarding, different build_functions code path, changed
     /// stack ordering) will cause this test to fail.
+    ///
+    /// Spline flattening approximates the offset/factor/jaggedness splines, so
+    /// the exact bits only hold without `flatten-splines`.
     #[test]
+    #[cfg(not(feature = "flatten-splines"))]
     fn overworld_router_unchanged() {
-        let path = concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/../../assets/minecraft/worldgen/noise_settings/overworld.json"
-        );
-        let json = std::fs::read_to_string(path).expect("overworld.json must exist");
-        let settings: NoiseGeneratorSettings =
-            serde_json::from_str(&json).expect("overworld.json must deserialize");
-
-        let functions: std::collections::BTreeMap<mcrs_protocol::Ident<String>, crate::density_function::ProtoDensityFunction> = load_density_functions_from_disk();
-        let noises: std::collections::BTreeMap<mcrs_protocol::Ident<String>, crate::density_function::proto::NoiseParam> = load_noises_from_disk();
-
-        let router = super::build_functions(&functions, &noises, &settings, 2, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86));
+        let router = overworld_router();
 
         assert!(
             router.final_density_idx() > 0,
@@ -7352,45 +8951,1154 @@ mod tests {
         );
     }
 
-    /// Regression gate: the Beta router must produce a numerically distinct
-    /// final_density sample from the modern overworld router, confirming the
-    /// two `build_functions` codepaths diverge as expected.
+    /// With spline flattening enabled the overworld router must stay within the
+    /// flattening error budget of the exact baseline sample.
     #[test]
-    fn beta_router_differs_from_modern() {
-        let beta_path = concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/../../assets/minecraft/worldgen/noise_settings/beta.json"
-        );
-        let beta_json = std::fs::read_to_string(beta_path).expect("beta.json must exist");
-        let beta_settings: NoiseGeneratorSettings =
-            serde_json::from_str(&beta_json).expect("beta.json must deserialize");
+    #[cfg(feature = "flatten-splines")]
+    fn overworld_router_flattened_close_to_baseline() {
+        let router = overworld_router();
 
-        let overworld_path = concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/../../assets/minecraft/worldgen/noise_settings/overworld.json"
+        let sample = router.final_density_uncached(bevy_math::IVec3::new(0, 64, 0));
+        let baseline = f32::from_bits(3168572737u32);
+        assert!(
+            (sample - baseline).abs() < 5e-2,
+            "flattened router sample {sample} drifted from baseline {baseline}"
         );
-        let overworld_json =
-            std::fs::read_to_string(overworld_path).expect("overworld.json must exist");
-        let overworld_settings: NoiseGeneratorSettings =
-            serde_json::from_str(&overworld_json).expect("overworld.json must deserialize");
+        assert!(router.verify_evaluation(&[
+            bevy_math::IVec3::new(0, 64, 0),
+            bevy_math::IVec3::new(1000, -20, -3000),
+            bevy_math::IVec3::new(-5000, 120, 700),
+        ]));
+    }
 
-        let functions = load_density_functions_from_disk();
-        let noises = load_noises_from_disk();
+    /// Parallel chunk noise must be bit-identical to generating the same chunks
+    /// one after another on a single worker.
+    #[test]
+    fn parallel_chunk_noise_matches_serial() {
+        let router = overworld_router();
 
-        let modern_router = super::build_functions(&functions, &noises, &overworld_settings, 2, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86));
-        let beta_router = super::build_functions(&functions, &noises, &beta_settings, 2, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86));
+        let chunks: Vec<bevy_math::IVec2> = (-1..=1)
+            .flat_map(|x| (-1..=1).map(move |z| bevy_math::IVec2::new(x, z)))
+            .collect();
+        let parallel = super::parallel::generate_chunks_parallel(&router, &chunks);
 
-        let pos = bevy_math::IVec3::new(0, 64, 0);
-        let modern_sample = modern_router.final_density_uncached(pos);
-        let beta_sample = beta_router.final_density_uncached(pos);
+        let mut worker = super::parallel::ChunkNoiseWorker::new(&router);
+        for (noise, &pos) in parallel.iter().zip(&chunks) {
+            assert_eq!(noise.chunk_pos, pos);
+            assert_eq!(*noise, worker.generate(&router, pos));
+        }
 
-        assert!(modern_sample.is_finite(), "modern sample must be finite");
-        assert!(beta_sample.is_finite(), "beta sample must be finite");
-        assert_ne!(
-            modern_sample.to_bits(),
-            beta_sample.to_bits(),
+        // Blocks on cell corners are not interpolated, so they must match the
+        // directly evaluated density.
+        let direct = router.final_density_uncached(bevy_math::IVec3::new(0, 64, 0));
+        let center = &parallel[4];
+        assert!((center.density(0, 64, 0) - direct).abs() < 1e-5);
+    }
+
+    /// `Interpolated` passes its input through, so none is left for any
+    /// router output to evaluate; `overworld_router_unchanged` pins that the
+    /// final density stays the same.
+    #[test]
+    fn interpolated_wrappers_are_redirected_to_their_input() {
+        use super::{DensityFunctionComponent, WrapperDensityFunction};
+
+        assert!(
+            overworld_json().contains("minecraft:interpolated"),
+            "the overworld router must contain interpolated wrappers to eliminate"
+        );
+        let router = overworld_router();
+
+        let mut reachable = vec![false; router.stack.len()];
+        let mut pending = vec![
+            router.barrier_index,
+            router.fluid_level_floodedness_index,
+            router.fluid_level_spread_index,
+            router.lava_index,
+            router.temperature_index,
+            router.vegetation_index,
+            router.continents_index,
+            router.erosion_index,
+            router.depth_index,
+            router.ridges_index,
+            router.preliminary_surface_level_index,
+            router.final_density_index,
+            router.vein_toggle_index,
+            router.vein_ridged_index,
+            router.vein_gap_index,
+        ];
+        while let Some(i) = pending.pop() {
+            if !std::mem::replace(&mut reachable[i], true) {
+                router.stack[i].visit_input_indices(&mut |input| pending.push(input));
+            }
+        }
+        let interpolated = router
+            .stack
+            .iter()
+            .zip(&reachable)
+            .filter(|(entry, reachable)| {
+                **reachable
+                    && matches!(
+                        entry,
+                        DensityFunctionComponent::Wrapper(WrapperDensityFunction::Interpolated(_))
+                    )
+            })
+            .count();
+        assert_eq!(interpolated, 0);
+    }
+
+    /// A RangeChoice whose branches redirect to the same node is replaced by
+    /// that node, even when the input range straddles the condition.
+    #[test]
+    fn range_choice_with_identical_branches_is_eliminated() {
+        use super::{
+            CacheOnce, ClampedYGradient, DensityFunctionComponent, DependentDensityFunction,
+            IndependentDensityFunction, RangeChoice, WrapperDensityFunction,
+        };
+
+        let mut stack = vec![
+            DensityFunctionComponent::Independent(IndependentDensityFunction::ClampedYGradient(
+                ClampedYGradient {
+                    from_y: -64.0,
+                    to_y: 320.0,
+                    from_value: -1.0,
+                    to_value: 1.0,
+                },
+            )),
+            DensityFunctionComponent::Independent(IndependentDensityFunction::Constant(0.5)),
+            DensityFunctionComponent::Wrapper(WrapperDensityFunction::CacheOnce(CacheOnce {
+                input_index: 1,
+                min_value: 0.5,
+                max_value: 0.5,
+            })),
+            DensityFunctionComponent::Dependent(DependentDensityFunction::RangeChoice(
+                RangeChoice {
+                    input_index: 0,
+                    when_in_index: 1,
+                    when_out_index: 2,
+                    min_inclusion_value: 0.0,
+                    max_exclusion_value: 0.5,
+                    min_value: 0.5,
+                    max_value: 0.5,
+                },
+            )),
+        ];
+        let mut roots = [3];
+        super::optimize_stack(&mut stack, &mut roots);
+
+        assert_eq!(roots, [1]);
+        assert_eq!(stack[roots[0]].as_constant(), Some(0.5));
+    }
+
+    /// Stack of a -2..2 Y gradient feeding `Clamp(outer, Clamp(inner, gradient))`.
+    fn nested_clamp_stack(inner: (f32, f32), outer: (f32, f32)) -> Vec<super::DensityFunctionComponent> {
+        use super::{
+            Clamp, ClampedYGradient, DensityFunctionComponent, DependentDensityFunction,
+            IndependentDensityFunction,
+        };
+
+        let clamp = |input_index, (min_value, max_value)| {
+            DensityFunctionComponent::Dependent(DependentDensityFunction::Clamp(Clamp {
+                input_index,
+                min_value,
+                max_value,
+            }))
+        };
+        vec![
+            DensityFunctionComponent::Independent(IndependentDensityFunction::ClampedYGradient(
+                ClampedYGradient {
+                    from_y: -64.0,
+                    to_y: 320.0,
+                    from_value: -2.0,
+                    to_value: 2.0,
+                },
+            )),
+            clamp(0, inner),
+            clamp(1, outer),
+        ]
+    }
+
+    /// Nested clamps fuse into one node that samples like the original chain,
+    /// including inputs outside both ranges.
+    #[test]
+    fn nested_clamps_fuse_into_one() {
+        use super::{DensityFunctionComponent, DependentDensityFunction};
+
+        for (inner, outer) in [
+            ((-1.0, 1.0), (-0.5, 2.0)),
+            ((-0.5, 2.0), (-1.0, 1.0)),
+            ((-1.5, 0.25), (-1.5, 0.25)),
+        ] {
+            let original = nested_clamp_stack(inner, outer);
+            let mut stack = original.clone();
+            let mut roots = [2];
+            super::optimize_stack(&mut stack, &mut roots);
+
+            let DensityFunctionComponent::Dependent(DependentDensityFunction::Clamp(fused)) =
+                &stack[roots[0]]
+            else {
+                panic!("{inner:?} {outer:?}: root is not a clamp");
+            };
+            assert_eq!(fused.input_index, 0);
+            for y in [-64, -40, 0, 64, 100, 200, 320] {
+                let pos = bevy_math::IVec3::new(0, y, 0);
+                assert_eq!(
+                    DensityFunctionComponent::sample_from_stack(&stack[..=roots[0]], pos),
+                    DensityFunctionComponent::sample_from_stack(&original, pos),
+                    "{inner:?} {outer:?} at y={y}"
+                );
+            }
+        }
+    }
+
+    /// Disjoint nested clamps fold to the outer bound closest to the inner range.
+    #[test]
+    fn disjoint_nested_clamps_fold_to_constant() {
+        use super::DensityFunctionComponent;
+
+        for (inner, outer, expected) in [
+            ((-1.0, 0.0), (0.5, 1.0), 0.5),
+            ((0.5, 1.0), (-1.0, 0.0), 0.0),
+        ] {
+            let original = nested_clamp_stack(inner, outer);
+            let mut stack = original.clone();
+            let mut roots = [2];
+            super::optimize_stack(&mut stack, &mut roots);
+
+            assert_eq!(stack[roots[0]].as_constant(), Some(expected));
+            for y in [-64, 0, 128, 320] {
+                let pos = bevy_math::IVec3::new(0, y, 0);
+                assert_eq!(DensityFunctionComponent::sample_from_stack(&original, pos), expected);
+            }
+        }
+    }
+
+    /// `(x * 2) + 3` and `(x + 1.5) * 2` are built as separate chains, fuse to
+    /// the same affine node and are merged into one.
+    #[test]
+    fn identical_affine_chains_merge_after_fusion() {
+        use super::{
+            ClampedYGradient, DensityFunctionComponent, DependentDensityFunction,
+            IndependentDensityFunction, Linear, LinearOperation,
+        };
+
+        let linear = |input_index, operation, argument| {
+            DensityFunctionComponent::Dependent(DependentDensityFunction::Linear(Linear {
+                input_index,
+                min_value: f32::NEG_INFINITY,
+                max_value: f32::INFINITY,
+                argument,
+                operation,
+            }))
+        };
+        let mut stack = vec![
+            DensityFunctionComponent::Independent(IndependentDensityFunction::ClampedYGradient(
+                ClampedYGradient {
+                    from_y: -64.0,
+                    to_y: 320.0,
+                    from_value: -1.0,
+                    to_value: 1.0,
+                },
+            )),
+            linear(0, LinearOperation::Multiply, 2.0),
+            linear(1, LinearOperation::Add, 3.0),
+            linear(0, LinearOperation::Add, 1.5),
+            linear(3, LinearOperation::Multiply, 2.0),
+        ];
+        let original = stack.clone();
+        let mut roots = [2, 4];
+        super::optimize_stack(&mut stack, &mut roots);
+
+        assert_eq!(roots[0], roots[1]);
+        let DensityFunctionComponent::Dependent(DependentDensityFunction::Affine(affine)) =
+            &stack[roots[0]]
+        else {
+            panic!(
+                "the merged chain must be an Affine, got {:?}",
+                stack[roots[0]]
+            );
+        };
+        assert_eq!(
+            (affine.input_index, affine.scale, affine.offset),
+            (0, 2.0, 3.0)
+        );
+        for y in [-64, 32, 128, 320] {
+            let pos = bevy_math::IVec3::new(0, y, 0);
+            assert_eq!(
+                DensityFunctionComponent::sample_from_stack(&stack[..=roots[0]], pos),
+                DensityFunctionComponent::sample_from_stack(&original[..=4], pos),
+            );
+        }
+    }
+
+    /// An identity the optimizer redirected past is reachable from no root and
+    /// is dropped, and every root still samples like the original stack.
+    #[test]
+    fn entries_no_root_reaches_are_removed() {
+        use super::{
+            ClampedYGradient, DensityFunctionComponent, DependentDensityFunction,
+            IndependentDensityFunction, Linear, LinearOperation,
+        };
+
+        let linear = |input_index, operation, argument| {
+            DensityFunctionComponent::Dependent(DependentDensityFunction::Linear(Linear {
+                input_index,
+                min_value: f32::NEG_INFINITY,
+                max_value: f32::INFINITY,
+                argument,
+                operation,
+            }))
+        };
+        let mut stack = vec![
+            DensityFunctionComponent::Independent(IndependentDensityFunction::Constant(2.0)),
+            DensityFunctionComponent::Independent(IndependentDensityFunction::ClampedYGradient(
+                ClampedYGradient {
+                    from_y: -64.0,
+                    to_y: 320.0,
+                    from_value: -1.0,
+                    to_value: 1.0,
+                },
+            )),
+            linear(1, LinearOperation::Add, 0.0),
+            linear(2, LinearOperation::Multiply, 3.0),
+        ];
+        let original = stack.clone();
+        let original_roots = [3, 0];
+        let mut roots = original_roots;
+        super::optimize_stack(&mut stack, &mut roots);
+        let mut per_block = vec![false; stack.len()];
+        let mut node_labels = vec![String::new(); stack.len()];
+        super::eliminate_dead_entries(&mut stack, &mut per_block, &mut node_labels, &mut roots);
+
+        assert_eq!(stack.len(), 3);
+        assert_eq!((per_block.len(), node_labels.len()), (3, 3));
+        assert!(!stack.iter().any(|entry| matches!(
+            entry,
+            DensityFunctionComponent::Dependent(DependentDensityFunction::Linear(_))
+        )));
+        for (root, original_root) in roots.into_iter().zip(original_roots) {
+            for y in [-64, 0, 128, 320] {
+                let pos = bevy_math::IVec3::new(0, y, 0);
+                assert_eq!(
+                    DensityFunctionComponent::sample_from_stack(&stack[..=root], pos),
+                    DensityFunctionComponent::sample_from_stack(&original[..=original_root], pos),
+                );
+            }
+        }
+    }
+
+    /// A router loaded from `to_bytes` samples exactly like the freshly built
+    /// one, and blobs built from other inputs are rejected.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn router_bytes_round_trip() {
+        let settings = overworld_settings();
+        let functions = load_density_functions_from_disk();
+        let noises = load_noises_from_disk();
+        let (block, fluid) = (mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86));
+        let router = super::build_functions(&functions, &noises, &settings, 2, block, fluid).unwrap();
+        let hash = super::router_inputs_hash(&functions, &noises, &settings, 2, block, fluid);
+        assert_ne!(
+            hash,
+            super::router_inputs_hash(&functions, &noises, &settings, 3, block, fluid)
+        );
+
+        let bytes = router.to_bytes(hash);
+        assert!(super::NoiseRouter::from_bytes(&bytes, hash ^ 1).is_none());
+        let loaded = super::NoiseRouter::from_bytes(&bytes, hash).expect("blob must load");
+
+        for (x, z) in [(0, 0), (-37, 181), (1021, -640)] {
+            for y in (-64..320).step_by(23) {
+                let pos = bevy_math::IVec3::new(x, y, z);
+                assert_eq!(
+                    router.final_density_uncached(pos).to_bits(),
+                    loaded.final_density_uncached(pos).to_bits(),
+                    "{pos}"
+                );
+            }
+        }
+    }
+
+    /// Entries that sample the same noise id share one sampler, both as built
+    /// and as loaded from `to_bytes`, and the same seed builds the same tables.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn noise_samplers_are_shared_by_id() {
+        use super::NoiseSampler;
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        let settings = overworld_settings();
+        let functions = load_density_functions_from_disk();
+        let noises = load_noises_from_disk();
+        let (block, fluid) = (
+            mcrs_protocol::BlockStateId(1),
+            mcrs_protocol::BlockStateId(86),
+        );
+        let build = || super::build_functions(&functions, &noises, &settings, 2, block, fluid);
+        let mut router = build().unwrap();
+        assert!(router.stack == build().unwrap().stack);
+
+        let hash = super::router_inputs_hash(&functions, &noises, &settings, 2, block, fluid);
+        let mut loaded =
+            super::NoiseRouter::from_bytes(&router.to_bytes(hash), hash).expect("blob must load");
+
+        for router in [&mut router, &mut loaded] {
+            let mut by_name: HashMap<String, Vec<Arc<NoiseSampler>>> = HashMap::new();
+            for entry in router.stack.iter_mut() {
+                if let Some((name, sampler)) = entry.noise_sampler_mut() {
+                    by_name
+                        .entry(name.to_owned())
+                        .or_default()
+                        .push(sampler.clone());
+                }
+            }
+            // shift_x and shift_z both sample minecraft:offset.
+            assert!(by_name["offset"].len() >= 2);
+            for (name, samplers) in by_name.iter().filter(|(name, _)| *name != "inline") {
+                assert!(
+                    samplers.iter().all(|s| Arc::ptr_eq(s, &samplers[0])),
+                    "{name} samplers are not shared"
+                );
+            }
+        }
+    }
+
+    /// The uniform-sign fast path of `fill_cell_blocks` decides every block the
+    /// same way per-block interpolation does.
+    #[test]
+    fn fill_cell_blocks_fast_path_matches_interpolation() {
+        let mut interp = super::NoiseCellInterpolator::new(4, 8);
+        let count = interp.cell_block_count();
+        let mut fast = vec![false; count];
+        let mut interpolated = vec![true; count];
+
+        let cells: [([f32; 8], Option<bool>); 4] = [
+            ([0.9, 0.01, 2.5, 1e-4, 0.3, 7.0, 0.02, 0.5], Some(true)),
+            ([-0.9, 0.0, -2.5, -1e-4, 0.0, -7.0, -0.02, -0.5], Some(false)),
+            ([0.0; 8], Some(false)),
+            ([0.9, -0.01, 2.5, -1e-4, 0.3, -7.0, 0.02, -0.5], None),
+        ];
+        for (corners, uniform) in cells {
+            interp.corners = corners;
+            assert_eq!(interp.fill_cell_blocks(&mut fast), uniform, "{corners:?}");
+            interp.interpolate_cell_blocks(&mut interpolated);
+            assert_eq!(fast, interpolated, "{corners:?}");
+        }
+    }
+
+    fn interpolator_fixture() -> (super::NoiseRouter, super::ColumnCache) {
+        let router = overworld_router();
+        let mut cache = router.new_column_cache(0, 0);
+        router.populate_columns(&mut cache);
+        (router, cache)
+    }
+
+    /// Fill every plane of the section whose bottom corner row is `base_y`.
+    fn fill_section(
+        interp: &mut super::NoiseCellInterpolator,
+        router: &super::NoiseRouter,
+        cache: &mut super::ColumnCache,
+        base_y: i32,
+    ) {
+        for plane in 0..=interp.h_cells() {
+            let x = (plane * interp.h_cell_blocks()) as i32;
+            interp.fill_plane_cached_reuse(plane, plane == 0, x, base_y, 0, router, cache);
+        }
+        interp.end_section();
+    }
+
+    #[test]
+    fn section_boundary_follows_contiguous_sections() {
+        let (router, mut cache) = interpolator_fixture();
+        let mut interp = router.new_noise_cell_interpolator();
+        assert_eq!(interp.section_boundary_y(), None);
+
+        fill_section(&mut interp, &router, &mut cache, 0);
+        assert_eq!(interp.section_boundary_y(), Some(16));
+        fill_section(&mut interp, &router, &mut cache, 16);
+        assert_eq!(interp.section_boundary_y(), Some(32));
+        let reused = interp.start_buf.clone();
+
+        // Recomputing the section from scratch gives the same corners.
+        interp.reset_section_boundary();
+        assert_eq!(interp.section_boundary_y(), None);
+        fill_section(&mut interp, &router, &mut cache, 16);
+        assert_eq!(interp.start_buf, reused);
+
+        interp.reset_section_boundary();
+        fill_section(&mut interp, &router, &mut cache, 64);
+        assert_eq!(interp.section_boundary_y(), Some(80));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "call reset_section_boundary after a gap")]
+    fn section_boundary_reuse_across_gap_panics() {
+        let (router, mut cache) = interpolator_fixture();
+        let mut interp = router.new_noise_cell_interpolator();
+        fill_section(&mut interp, &router, &mut cache, 0);
+        fill_section(&mut interp, &router, &mut cache, 48);
+    }
+
+    /// Blocks on cell corners come out of `generate_section` as the directly
+    /// evaluated density, whether the section reuses the row below it or
+    /// follows a gap.
+    #[test]
+    fn generate_section_matches_final_density_on_cell_corners() {
+        let (router, mut cache) = interpolator_fixture();
+        let mut interp = router.new_noise_cell_interpolator();
+        let mut out = [0.0f32; 4096];
+        for section_y in [3, 4, 1] {
+            router.generate_section(0, section_y, 0, &mut out, &mut interp, &mut cache);
+            for (x, y, z) in [(0, 0, 0), (4, 8, 12), (12, 0, 8), (8, 8, 4)] {
+                let pos = bevy_math::IVec3::new(x, section_y * 16 + y, z);
+                let direct = router.final_density_uncached(pos);
+                let interpolated = out[((y * 16 + z) * 16 + x) as usize];
+                assert!(
+                    (interpolated - direct).abs() < 1e-5,
+                    "{pos}: {interpolated} != {direct}"
+                );
+            }
+        }
+        assert_eq!(interp.section_boundary_y(), Some(32));
+    }
+
+    /// The if-else ladder `WeirdScaled` used before the rarity tables.
+    fn rarity_scale_ladder(mapper: super::RarityValueMapper, density: f32) -> (f32, f32) {
+        use super::RarityValueMapper;
+        match mapper {
+            RarityValueMapper::Type1 => {
+                if density < -0.5 {
+                    (0.75, 1.0 / 0.75)
+                } else if density < 0.0 {
+                    (1.0, 1.0)
+                } else if density < 0.5 {
+                    (1.5, 1.0 / 1.5)
+                } else {
+                    (2.0, 0.5)
+                }
+            }
+            RarityValueMapper::Type2 => {
+                if density < -0.75 {
+                    (0.5, 2.0)
+                } else if density < -0.5 {
+                    (0.75, 1.0 / 0.75)
+                } else if density < 0.5 {
+                    (1.0, 1.0)
+                } else if density < 0.75 {
+                    (2.0, 0.5)
+                } else {
+                    (3.0, 1.0 / 3.0)
+                }
+            }
+        }
+    }
+
+    /// The table lookup picks exactly the ladder's values across -1..1, right
+    /// at and next to every threshold, and for non-finite inputs.
+    #[test]
+    fn rarity_table_matches_ladder() {
+        use super::RarityValueMapper;
+
+        let mut inputs: Vec<f32> = (-4096..=4096).map(|i| i as f32 / 4096.0).collect();
+        for threshold in [-0.75f32, -0.5, 0.5, 0.75] {
+            let bits = threshold.to_bits();
+            inputs.extend([threshold, f32::from_bits(bits - 1), f32::from_bits(bits + 1)]);
+        }
+        let smallest = f32::from_bits(1);
+        inputs.extend([0.0, -0.0, smallest, -smallest]);
+        inputs.extend([-2.0, 2.0, f32::INFINITY, f32::NEG_INFINITY, f32::NAN]);
+
+        for mapper in [RarityValueMapper::Type1, RarityValueMapper::Type2] {
+            for &density in &inputs {
+                let (amp, mul) = super::rarity_scale(mapper, density);
+                let (expected_amp, expected_mul) = rarity_scale_ladder(mapper, density);
+                assert_eq!(
+                    (amp.to_bits(), mul.to_bits()),
+                    (expected_amp.to_bits(), expected_mul.to_bits()),
+                    "{mapper:?} at {density}"
+                );
+            }
+        }
+    }
+
+    /// The memoized `FindTopSurface` scan matches a fresh scan at every Y and
+    /// is redone when the column changes.
+    #[test]
+    fn find_top_surface_is_memoized_per_column() {
+        use super::Root;
+
+        let router = overworld_router();
+        let root = router.root_index(Root::PreliminarySurfaceLevel);
+        let scanned = |pos: bevy_math::IVec3| {
+            super::DensityFunctionComponent::sample_from_stack(&router.stack[..=root], pos)
+        };
+
+        let mut cache = router.new_cache();
+        let columns = [(0, 0), (512, -300), (-2000, 77)];
+        let mut surfaces = Vec::new();
+        for (x, z) in columns {
+            let expected = scanned(bevy_math::IVec3::new(x, 0, z));
+            for y in [-64, 0, 100, 319] {
+                let pos = bevy_math::IVec3::new(x, y, z);
+                assert_eq!(router.sample_root(Root::PreliminarySurfaceLevel, pos, &mut cache), expected, "{pos}");
+            }
+            assert_eq!(cache.top_surface.len(), 1);
+            surfaces.push(expected);
+        }
+        assert!(surfaces.windows(2).any(|w| w[0] != w[1]), "{surfaces:?}");
+    }
+
+    /// The lane-batched column sweep must agree with the per-position Zone B path,
+    /// including a short tail chunk.
+    #[test]
+    fn column_batch_matches_per_position() {
+        let router = overworld_router();
+
+        let (base_x, base_z) = (-48, 160);
+        let mut cache = router.new_column_cache(base_x, base_z);
+        router.populate_columns(&mut cache);
+
+        let y_values: Vec<i32> = (-64..320).step_by(5).collect();
+        assert_ne!(y_values.len() % super::COLUMN_LANES, 0);
+        let mut batch = vec![0.0f32; y_values.len()];
+
+        for (local_x, local_z) in [(0, 0), (4, 12), (16, 8), (16, 16)] {
+            let (x, z) = (base_x + local_x, base_z + local_z);
+            router.final_density_column_batch(x, z, &y_values, &mut batch, &mut cache);
+
+            for (&y, &actual) in y_values.iter().zip(&batch) {
+                cache.load_column(local_x, local_z);
+                let expected =
+                    router.final_density_from_column_cache(bevy_math::IVec3::new(x, y, z), &mut cache);
+                assert!(
+                    (expected - actual).abs() <= 1e-6,
+                    "({x},{y},{z}): per-position {expected}, batch {actual}"
+                );
+            }
+        }
+    }
+
+    /// Columns carried over from a neighbouring chunk must match a fresh
+    /// populate, and `populate_columns` must not re-evaluate them.
+    #[test]
+    fn reused_column_edge_matches_fresh_populate() {
+        use super::Edge;
+
+        let router = overworld_router();
+
+        let (base_x, base_z) = (-48, 160);
+        let mut center = router.new_column_cache(base_x, base_z);
+        router.populate_columns(&mut center);
+
+        for edge in [Edge::MinX, Edge::MaxX, Edge::MinZ, Edge::MaxZ] {
+            // A chunk whose `edge` lies on the center chunk's opposite edge.
+            let (dx, dz) = match edge {
+                Edge::MinX => (16, 0),
+                Edge::MaxX => (-16, 0),
+                Edge::MinZ => (0, 16),
+                Edge::MaxZ => (0, -16),
+            };
+            let mut fresh = router.new_column_cache(base_x + dx, base_z + dz);
+            router.populate_columns(&mut fresh);
+
+            let mut reused = router.new_column_cache(base_x + dx, base_z + dz);
+            reused.reuse_edge_from(&center, edge);
+            router.populate_columns(&mut reused);
+            assert_eq!(fresh.column_data, reused.column_data, "{edge:?}");
+        }
+
+        // Poisoned edge values survive populate, so those columns were skipped.
+        let mut poisoned = router.new_column_cache(base_x - 16, base_z);
+        router.populate_columns(&mut poisoned);
+        poisoned.column_data.fill(f32::MAX);
+        let mut reused = router.new_column_cache(base_x, base_z);
+        reused.reuse_edge_from(&poisoned, Edge::MinX);
+        router.populate_columns(&mut reused);
+        for local_z in 0..17 {
+            assert_eq!(reused.read_za_value(0, local_z, 0), f32::MAX);
+        }
+        assert_ne!(reused.read_za_value(4, 0, 0), f32::MAX);
+    }
+
+    /// The task-pool populate writes the same bits as the serial one, with and
+    /// without a reused edge.
+    #[cfg(feature = "parallel-columns")]
+    #[test]
+    fn parallel_populate_matches_serial() {
+        use super::Edge;
+
+        let router = overworld_router();
+        bevy_tasks::ComputeTaskPool::get_or_init(|| {
+            bevy_tasks::TaskPoolBuilder::new().num_threads(4).build()
+        });
+        let bits = |cache: &super::ColumnCache| -> Vec<u32> {
+            cache
+                .column_data
+                .iter()
+                .map(|value| value.to_bits())
+                .collect()
+        };
+
+        let (base_x, base_z) = (-48, 160);
+        let mut serial = router.new_column_cache(base_x, base_z);
+        router.populate_columns(&mut serial);
+        let mut parallel = router.new_column_cache(base_x, base_z);
+        router.populate_columns_parallel(&mut parallel);
+        assert_eq!(bits(&serial), bits(&parallel));
+
+        let mut neighbour = router.new_column_cache(base_x - 16, base_z);
+        router.populate_columns(&mut neighbour);
+        let mut reused = router.new_column_cache(base_x, base_z);
+        reused.reuse_edge_from(&neighbour, Edge::MinX);
+        router.populate_columns_parallel(&mut reused);
+        assert_eq!(bits(&serial), bits(&reused));
+    }
+
+    /// `sample_root` must match a plain forward sweep for every root, even when
+    /// roots from different zones are interleaved on one cache.
+    #[test]
+    fn sample_root_matches_forward_sweep() {
+        let router = overworld_router();
+
+        let mut cache = router.new_cache();
+        for pos in [
+            bevy_math::IVec3::new(0, 64, 0),
+            bevy_math::IVec3::new(0, -30, 0),
+            bevy_math::IVec3::new(1000, 12, -3000),
+        ] {
+            // Zone A roots first, then Zone C, then Zone B, so every column-pass
+            // extension path is exercised.
+            for root in [super::Root::Continents, super::Root::Temperature, super::Root::FinalDensity]
+                .into_iter()
+                .chain(super::Root::ALL)
+            {
+                let idx = router.root_index(root);
+                let mut values = vec![0.0f32; idx + 1];
+                for i in 0..=idx {
+                    values[i] = router.stack[i].sample_cached(&values, &router.stack, pos);
+                }
+                let actual = router.sample_root(root, pos, &mut cache);
+                assert!(
+                    (values[idx] - actual).abs() <= 1e-6,
+                    "{root:?} at {pos}: sweep {}, sample_root {actual}",
+                    values[idx]
+                );
+            }
+        }
+    }
+
+    /// Regression gate: the Beta router must produce a numerically distinct
+    /// final_density sample from the modern overworld router, confirming the
+    /// two `build_functions` codepaths diverge as expected.
+    #[test]
+    fn beta_router_differs_from_modern() {
+        let beta_path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../assets/minecraft/worldgen/noise_settings/beta.json"
+        );
+        let beta_json = std::fs::read_to_string(beta_path).expect("beta.json must exist");
+        let beta_settings: NoiseGeneratorSettings =
+            serde_json::from_str(&beta_json).expect("beta.json must deserialize");
+
+        let functions = load_density_functions_from_disk();
+        let noises = load_noises_from_disk();
+
+        let modern_router = overworld_router();
+        let beta_router = super::build_functions(&functions, &noises, &beta_settings, 2, mcrs_protocol::BlockStateId(1), mcrs_protocol::BlockStateId(86)).unwrap();
+
+        let pos = bevy_math::IVec3::new(0, 64, 0);
+        let modern_sample = modern_router.final_density_uncached(pos);
+        let beta_sample = beta_router.final_density_uncached(pos);
+
+        assert!(modern_sample.is_finite(), "modern sample must be finite");
+        assert!(beta_sample.is_finite(), "beta sample must be finite");
+        assert_ne!(
+            modern_sample.to_bits(),
+            beta_sample.to_bits(),
             "beta router must produce a different final_density than the modern router at (0,64,0)"
         );
     }
 
+    /// Every unary's static range must bound what it actually produces, for
+    /// input ranges on either side of zero and spanning it.
+    #[test]
+    fn unary_ranges_bound_sampled_outputs() {
+        use super::UnaryOperation;
+
+        let operations = [
+            UnaryOperation::Abs,
+            UnaryOperation::Square,
+            UnaryOperation::Cube,
+            UnaryOperation::HalfNegative,
+            UnaryOperation::QuarterNegative,
+            UnaryOperation::Invert,
+            UnaryOperation::Squeeze,
+        ];
+        let inputs = [
+            (-3.0f32, -1.0f32),
+            (-1.0, 3.0),
+            (0.5, 2.0),
+            (-4.0, 4.0),
+            (-0.5, 0.25),
+        ];
+        for operation in operations {
+            for (min, max) in inputs {
+                let (low, high) = operation.range(min, max);
+                assert!(low <= high, "{operation:?} over [{min}, {max}]");
+                for step in 0..=1000 {
+                    let x = min + (max - min) * step as f32 / 1000.0;
+                    let y = operation.apply(x);
+                    assert!(
+                        low <= y && y <= high,
+                        "{operation:?}({x}) = {y} outside [{low}, {high}]"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unary_ranges_are_tight() {
+        use super::UnaryOperation;
+
+        assert_eq!(UnaryOperation::Abs.range(-3.0, -1.0), (1.0, 3.0));
+        assert_eq!(UnaryOperation::Abs.range(-1.0, 3.0), (0.0, 3.0));
+        assert_eq!(UnaryOperation::Square.range(-2.0, 1.0), (0.0, 4.0));
+        assert_eq!(UnaryOperation::Cube.range(-2.0, 1.0), (-8.0, 1.0));
+        assert_eq!(UnaryOperation::HalfNegative.range(-2.0, 1.0), (-1.0, 1.0));
+        assert_eq!(UnaryOperation::Invert.range(2.0, 4.0), (0.25, 0.5));
+        assert_eq!(
+            UnaryOperation::Invert.range(0.0, 4.0),
+            (f32::NEG_INFINITY, f32::INFINITY)
+        );
+        let squeezed = 0.5 - 1.0 / 24.0;
+        assert_eq!(
+            UnaryOperation::Squeeze.range(-4.0, 4.0),
+            (-squeezed, squeezed)
+        );
+    }
+
+    /// A Noise with two octaves from `first_octave` -6, seeded from `seed`.
+    fn test_noise(seed: u64) -> super::DensityFunctionComponent {
+        use super::{DensityFunctionComponent, IndependentDensityFunction, Noise};
+        use crate::NoiseSampler;
+
+        DensityFunctionComponent::Independent(IndependentDensityFunction::Noise(Noise {
+            noise_name: "test".to_string(),
+            sampler: NoiseSampler::new(&mut RandomSource::new(seed, false), -6, vec![1.0, 1.0])
+                .into(),
+            xz_scale: 1.0,
+            y_scale: 1.0,
+        }))
+    }
+
+    fn multiply(input1_index: usize, input2_index: usize) -> super::DensityFunctionComponent {
+        use super::{Binary, BinaryOperation, DensityFunctionComponent, DependentDensityFunction};
+
+        DensityFunctionComponent::Dependent(DependentDensityFunction::Binary(Binary {
+            input1_index,
+            input2_index,
+            min_value: f32::NEG_INFINITY,
+            max_value: f32::INFINITY,
+            operation: BinaryOperation::Multiply,
+        }))
+    }
+
+    /// `Mul(0, noise)` is exactly zero on both the recursive and the cached
+    /// path.
+    #[test]
+    fn multiply_by_leading_zero_is_zero() {
+        use super::{DensityFunctionComponent, IndependentDensityFunction};
+
+        let stack = vec![
+            DensityFunctionComponent::Independent(IndependentDensityFunction::Constant(0.0)),
+            test_noise(7),
+            multiply(0, 1),
+        ];
+        let mut cache = vec![0.0f32; stack.len()];
+        for x in (-256..=256).step_by(37) {
+            for y in [-64, 0, 63, 200] {
+                let pos = bevy_math::IVec3::new(x, y, -x);
+                let direct = DensityFunctionComponent::sample_from_stack(&stack, pos);
+                assert_eq!(direct, 0.0);
+                for i in 0..stack.len() {
+                    cache[i] = stack[i].sample_cached(&cache, &stack, pos);
+                }
+                assert_eq!(cache[2], 0.0);
+            }
+        }
+    }
+
+    /// The optimizer puts a gate that is zero below y=0 in front of a noise,
+    /// and the product is unchanged wherever both operands are finite.
+    #[test]
+    fn multiply_operands_are_ordered_zero_first() {
+        use super::{
+            ClampedYGradient, DensityFunctionComponent, DependentDensityFunction,
+            IndependentDensityFunction,
+        };
+
+        let mut stack = vec![
+            test_noise(7),
+            DensityFunctionComponent::Independent(IndependentDensityFunction::ClampedYGradient(
+                ClampedYGradient {
+                    from_y: 0.0,
+                    to_y: 64.0,
+                    from_value: 0.0,
+                    to_value: 1.0,
+                },
+            )),
+            multiply(0, 1),
+        ];
+        let original = stack.clone();
+        let mut roots = [2];
+        super::optimize_stack(&mut stack, &mut roots);
+
+        let DensityFunctionComponent::Dependent(DependentDensityFunction::Binary(bin)) =
+            &stack[roots[0]]
+        else {
+            panic!("the product must stay a Binary, got {:?}", stack[roots[0]]);
+        };
+        assert_eq!((bin.input1_index, bin.input2_index), (1, 0));
+
+        for x in (-256..=256).step_by(37) {
+            for y in [-64, -1, 0, 1, 32, 63, 64, 200] {
+                let pos = bevy_math::IVec3::new(x, y, x / 2);
+                let before = DensityFunctionComponent::sample_from_stack(&original, pos);
+                let after = DensityFunctionComponent::sample_from_stack(&stack[..=roots[0]], pos);
+                assert!(before.is_finite());
+                assert_eq!(before, after, "at {pos}");
+            }
+        }
+    }
+
+    /// Climate read through a biome column cache matches sampling each quart
+    /// position through a plain `DensityCache`, in every column and at
+    /// several heights, including a chunk at negative coordinates.
+    #[test]
+    fn biome_column_cache_matches_uncached_climate() {
+        use crate::climate::{sample_climate, sample_climate_from_biome_cache};
+
+        let router = overworld_router();
+
+        for (chunk_x, chunk_z) in [(0, 0), (-3, 7), (125, -40)] {
+            let mut biome_cache = router.new_biome_column_cache(chunk_x * 4, chunk_z * 4);
+            for quart_y in [-16, 0, 15, 40] {
+                for quart_x in chunk_x * 4..chunk_x * 4 + 4 {
+                    for quart_z in chunk_z * 4..chunk_z * 4 + 4 {
+                        let quart_pos = bevy_math::IVec3::new(quart_x, quart_y, quart_z);
+                        let expected = sample_climate(&router, quart_pos, &mut router.new_cache());
+                        let actual =
+                            sample_climate_from_biome_cache(&router, quart_pos, &mut biome_cache);
+                        assert_eq!(actual, expected, "at quart {quart_pos}");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Alternating router outputs from different zones on one `DensityCache`
+    /// gives the same values as giving every query a fresh cache, whatever
+    /// the order and including going back to an earlier Y.
+    #[test]
+    fn roots_from_every_zone_can_share_a_cache() {
+        use super::Root;
+
+        let router = overworld_router();
+        assert!(router.root_index(Root::Continents) < router.column_boundary());
+        assert!(router.root_index(Root::FinalDensity) >= router.column_boundary());
+
+        let queries = [
+            Root::FinalDensity,
+            Root::Depth,
+            Root::Continents,
+            Root::FinalDensity,
+            Root::Temperature,
+            Root::VeinToggle,
+            Root::Depth,
+            Root::Barrier,
+            Root::Erosion,
+            Root::FinalDensity,
+        ];
+        let mut shared = router.new_cache();
+        for (x, z) in [(0, 0), (-301, 77), (4000, -1234)] {
+            for y in [-60, 100, 12, 100] {
+                let pos = bevy_math::IVec3::new(x, y, z);
+                for root in queries {
+                    let expected = router.sample_root(root, pos, &mut router.new_cache());
+                    let actual = router.sample_root(root, pos, &mut shared);
+                    assert_eq!(actual.to_bits(), expected.to_bits(), "{root:?} at {pos}");
+                }
+            }
+        }
+
+        shared.invalidate();
+        let pos = bevy_math::IVec3::new(0, 100, 0);
+        let expected = router.sample_root(Root::FinalDensity, pos, &mut router.new_cache());
+        let actual = router.sample_root(Root::FinalDensity, pos, &mut shared);
+        assert_eq!(actual, expected);
+    }
+
+    /// ShiftedNoise itself is per-block, but every one in the overworld
+    /// router reads its shifts (flat-cached `shift_x`/`shift_z` and the
+    /// constant `shift_y`) from Zone A, so they are evaluated once per column.
+    #[test]
+    fn shifted_noise_shift_inputs_are_in_zone_a() {
+        use super::{DensityFunctionComponent, DependentDensityFunction};
+
+        let router = overworld_router();
+
+        let mut shifted_noises = 0;
+        for (i, entry) in router.stack.iter().enumerate() {
+            let DensityFunctionComponent::Dependent(DependentDensityFunction::ShiftedNoise(f)) =
+                entry
+            else {
+                continue;
+            };
+            assert!(
+                router.per_block[i],
+                "{} is not per-block",
+                router.node_labels[i]
+            );
+            for input in [f.input_x_index, f.input_y_index, f.input_z_index] {
+                assert!(
+                    input < router.column_boundary && !router.per_block[input],
+                    "shift input {} of {} is outside Zone A",
+                    router.node_labels[input],
+                    router.node_labels[i]
+                );
+            }
+            shifted_noises += 1;
+        }
+        // continents, erosion, ridges, temperature and vegetation
+        assert!(
+            shifted_noises >= 5,
+            "only {shifted_noises} ShiftedNoise entries"
+        );
+    }
+
+    /// The overworld's lazy Zone B plan splits on both of final_density's
+    /// RangeChoices and gives the same bits as the straight-line sweep over a
+    /// whole chunk.
+    #[test]
+    fn lazy_range_choice_matches_straight_sweep() {
+        use super::{LazyBranch, LazyRangeChoice};
+
+        fn choices(plan: &LazyRangeChoice) -> usize {
+            let count = |branch: &LazyBranch| match branch {
+                LazyBranch::Sweep(_) => 0,
+                LazyBranch::Choice(next) => choices(next),
+            };
+            1 + count(&plan.when_in).max(count(&plan.when_out))
+        }
+
+        let mut router = overworld_router();
+        let plan = router
+            .lazy_rc
+            .as_ref()
+            .expect("final_density has a lazy plan");
+        assert_eq!(choices(plan), 2, "both RangeChoices are split on");
+
+        let (base_x, base_z) = (-48, 112);
+        let y_values: Vec<i32> = (-64..320).step_by(8).collect();
+        assert!(router.verify_column_cache(base_x, base_z, &y_values));
+
+        let sample_chunk = |router: &super::NoiseRouter| {
+            let mut cache = router.new_column_cache(base_x, base_z);
+            router.populate_columns(&mut cache);
+            let mut bits = Vec::new();
+            for local_x in (0..=16).step_by(4) {
+                for local_z in (0..=16).step_by(4) {
+                    for &y in &y_values {
+                        cache.load_column(local_x, local_z);
+                        let pos = bevy_math::IVec3::new(base_x + local_x, y, base_z + local_z);
+                        bits.push(
+                            router
+                                .final_density_from_column_cache(pos, &mut cache)
+                                .to_bits(),
+                        );
+                    }
+                }
+            }
+            bits
+        };
+        let lazy = sample_chunk(&router);
+        router.lazy_rc = None;
+        assert!(router.verify_column_cache(base_x, base_z, &y_values));
+        assert_eq!(lazy, sample_chunk(&router));
+    }
+
+    /// The precomputed segment coefficients give exactly the bits of the
+    /// `cubic + linear` form rebuilt per sample, across and past every
+    /// overworld spline's locations.
+    #[test]
+    fn spline_coefficients_match_cubic_plus_linear() {
+        use super::{DensityFunctionComponent, DependentDensityFunction, Spline, SplineValue};
+
+        fn cubic_plus_linear(spline: &Spline, cache: &[f32]) -> f32 {
+            let value = |value: &SplineValue| match value {
+                SplineValue::Spline(nested) => cubic_plus_linear(nested, cache),
+                SplineValue::Constant(x) => *x,
+            };
+            let location = cache[spline.input_index];
+            let locs = &spline.locations;
+            let idx_gt = Spline::upper_bound(locs, location);
+            if idx_gt == 0 || idx_gt == locs.len() {
+                let i = idx_gt.min(locs.len() - 1);
+                let v = value(&spline.values[i]);
+                let d = spline.derivatives[i];
+                return if d == 0.0 {
+                    v
+                } else {
+                    d.mul_add(location - locs[i], v)
+                };
+            }
+            let (i0, i1) = (idx_gt - 1, idx_gt);
+            let (v0, v1) = (value(&spline.values[i0]), value(&spline.values[i1]));
+            let dist = locs[i1] - locs[i0];
+            let x = (location - locs[i0]) * (1.0 / dist);
+            let delta = v1 - v0;
+            let e0 = spline.derivatives[i0] * dist - delta;
+            let e1 = -(spline.derivatives[i1] * dist) + delta;
+            let lerp = |a: f32, b: f32, t: f32| (b - a).mul_add(t, a);
+            (x * (1.0 - x)) * lerp(e0, e1, x) + lerp(v0, v1, x)
+        }
+
+        let router = overworld_router();
+
+        let pos = bevy_math::IVec3::ZERO;
+        let mut cache = vec![0.0f32; router.stack.len()];
+        let mut checked = 0;
+        for entry in router.stack.iter() {
+            let DensityFunctionComponent::Dependent(DependentDensityFunction::Spline(spline)) =
+                entry
+            else {
+                continue;
+            };
+            let first = spline.locations[0];
+            let last = spline.locations[spline.locations.len() - 1];
+            let margin = (last - first).max(1.0) * 0.25;
+            for step in 0..=2048 {
+                // Nested splines read other inputs; spread those over [-1.5, 1.5].
+                for (j, input) in cache.iter_mut().enumerate() {
+                    *input =
+                        ((step as f32) * 0.618_034 + (j as f32) * 0.414_213).fract() * 3.0 - 1.5;
+                }
+                cache[spline.input_index] =
+                    first - margin + (last - first + 2.0 * margin) * (step as f32 / 2048.0);
+                let expected = cubic_plus_linear(spline, &cache);
+                let actual = spline.sample_cached(&cache, &router.stack, pos);
+                assert_eq!(
+                    actual.to_bits(),
+                    expected.to_bits(),
+                    "input {} = {}: {actual} != {expected}",
+                    spline.input_index,
+                    cache[spline.input_index]
+                );
+            }
+            checked += 1;
+        }
+        assert!(checked > 0 || cfg!(feature = "flatten-splines"));
+    }
 }
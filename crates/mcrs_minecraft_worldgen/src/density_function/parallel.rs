@@ -0,0 +1,167 @@
+//! Multithreaded noise generation for batches of chunk columns.
+//!
+//! `NoiseRouter` is built once and never mutated afterwards: the density
+//! function stack, the noise permutation tables, spline LUTs and the zone
+//! boundaries are all plain owned data behind `&self`. Every piece of mutable
+//! state used while evaluating the router lives in caller-owned scratch
+//! buffers (`DensityCache`, `ColumnCache`, `NoiseCellInterpolator`), so a single
+//! `&NoiseRouter` can be shared by any number of threads as long as each
+//! thread owns its own scratch buffers.
+
+use crate::density_function::{ColumnCache, NoiseCellInterpolator, NoiseRouter};
+use bevy_math::IVec2;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// The whole point of this module: fail the build if a future change adds
+// interior mutability to the router.
+const _: () = {
+    const fn assert_sync<T: Sync>() {}
+    assert_sync::<NoiseRouter>();
+};
+
+/// Interpolated `final_density` for every block of one chunk column inside the
+/// router's noise range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkNoise {
+    pub chunk_pos: IVec2,
+    pub min_y: i32,
+    pub height: u32,
+    /// `densities[((y - min_y) * 16 + local_z) * 16 + local_x]`
+    densities: Vec<f32>,
+}
+
+impl ChunkNoise {
+    /// Density at a chunk-local X/Z and a world Y inside `min_y..min_y + height`.
+    #[inline]
+    pub fn density(&self, local_x: i32, y: i32, local_z: i32) -> f32 {
+        self.densities[Self::index(local_x, y - self.min_y, local_z)]
+    }
+
+    /// True when the density at the given position is positive (solid terrain).
+    #[inline]
+    pub fn is_solid(&self, local_x: i32, y: i32, local_z: i32) -> bool {
+        self.density(local_x, y, local_z) > 0.0
+    }
+
+    pub fn densities(&self) -> &[f32] {
+        &self.densities
+    }
+
+    #[inline]
+    fn index(local_x: i32, rel_y: i32, local_z: i32) -> usize {
+        ((rel_y as usize * 16) + local_z as usize) * 16 + local_x as usize
+    }
+}
+
+/// Per-thread scratch buffers for chunk noise generation.
+///
+/// None of these are safe to share: every evaluation writes into them. Reuse one
+/// `ChunkNoiseWorker` for many chunks on the same thread to avoid reallocating.
+pub struct ChunkNoiseWorker {
+    column_cache: ColumnCache,
+    interp: NoiseCellInterpolator,
+}
+
+impl ChunkNoiseWorker {
+    pub fn new(router: &NoiseRouter) -> Self {
+        Self {
+            column_cache: router.new_column_cache(0, 0),
+            interp: router.new_noise_cell_interpolator(),
+        }
+    }
+
+    /// Generate the interpolated noise for one chunk column.
+    ///
+    /// Zone A is populated once per column and corner densities are
+    /// precomputed for the full column height, then each section is filled
+    /// bottom-up with [`NoiseRouter::generate_section`].
+    pub fn generate(&mut self, router: &NoiseRouter, chunk_pos: IVec2) -> ChunkNoise {
+        let block_x = chunk_pos.x * 16;
+        let block_z = chunk_pos.y * 16;
+        let min_y = router.noise_min_y();
+        let height = router.noise_height();
+        debug_assert!(
+            min_y % 16 == 0 && height % 16 == 0,
+            "noise range must be section-aligned"
+        );
+
+        let cache = &mut self.column_cache;
+        cache.base_block_x = block_x;
+        cache.base_block_z = block_z;
+        router.populate_columns(cache);
+
+        let interp = &mut self.interp;
+        interp.reset_section_boundary();
+        let rows = height as usize / interp.v_cell_blocks() + 1;
+        interp.precompute_column_grid(router, cache, min_y, rows);
+
+        let mut densities = vec![0.0f32; 16 * 16 * height as usize];
+        let first_section = min_y.div_euclid(16);
+        for (sy, section) in (first_section..).zip(densities.chunks_exact_mut(16 * 16 * 16)) {
+            let section: &mut [f32; 4096] = section.try_into().expect("whole sections");
+            router.generate_section(chunk_pos.x, sy, chunk_pos.y, section, interp, cache);
+        }
+
+        ChunkNoise {
+            chunk_pos,
+            min_y,
+            height,
+            densities,
+        }
+    }
+}
+
+/// Generate noise for every chunk in `chunk_positions` across all available cores.
+///
+/// Work is handed out one chunk at a time from a shared counter so slow chunks
+/// (mountains, deep caves) don't leave other threads idle. Each thread owns a
+/// `ChunkNoiseWorker`; only `&NoiseRouter` is shared. The result is in the same
+/// order as `chunk_positions` and is identical to generating each chunk serially.
+pub fn generate_chunks_parallel(
+    router: &NoiseRouter,
+    chunk_positions: &[IVec2],
+) -> Vec<ChunkNoise> {
+    let threads = std::thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(chunk_positions.len());
+    if threads <= 1 {
+        let mut worker = ChunkNoiseWorker::new(router);
+        return chunk_positions
+            .iter()
+            .map(|&pos| worker.generate(router, pos))
+            .collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<ChunkNoise>> = vec![None; chunk_positions.len()];
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                let next = &next;
+                scope.spawn(move || {
+                    let mut worker = ChunkNoiseWorker::new(router);
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(&pos) = chunk_positions.get(i) else {
+                            break;
+                        };
+                        done.push((i, worker.generate(router, pos)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        for handle in handles {
+            for (i, noise) in handle.join().expect("chunk noise worker panicked") {
+                results[i] = Some(noise);
+            }
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|noise| noise.expect("every chunk is claimed by exactly one worker"))
+        .collect()
+}
@@ -0,0 +1,131 @@
+//! Noise router outputs checked against values recorded from a vanilla
+//! reference client. `verify_evaluation` and `verify_column_cache` only
+//! compare our own evaluation paths with each other; these fixtures catch
+//! noise seeding, spline and optimizer regressions that keep every path
+//! consistent but wrong.
+//!
+//! Each sample in `fixtures/vanilla_router.json` is the router output
+//! `root` (a vanilla `noise_router` field name) at block `pos` for world
+//! `seed`, as returned by `NoiseRouter.<root>().compute(...)` on the vanilla
+//! side. Samples are grouped by noise settings so one router is built per
+//! `(settings, seed)` pair.
+
+use super::tests::{load_density_functions_from_disk, load_noises_from_disk};
+use super::{NoiseRouter, Root, build_functions};
+use crate::proto::NoiseGeneratorSettings;
+use bevy_math::IVec3;
+use mcrs_protocol::BlockStateId;
+use std::collections::BTreeMap;
+
+#[derive(serde::Deserialize)]
+struct RouterFixtures {
+    schema_version: u32,
+    /// Largest accepted absolute difference, unless a sample sets its own.
+    tolerance: f32,
+    samples: Vec<RouterSample>,
+}
+
+#[derive(serde::Deserialize)]
+struct RouterSample {
+    /// File stem under `assets/minecraft/worldgen/noise_settings`.
+    noise_settings: String,
+    seed: u64,
+    pos: [i32; 3],
+    root: String,
+    expected: f32,
+    #[serde(default)]
+    tolerance: Option<f32>,
+}
+
+fn load_fixtures() -> RouterFixtures {
+    serde_json::from_str(include_str!("fixtures/vanilla_router.json"))
+        .expect("valid vanilla_router.json fixture")
+}
+
+fn load_noise_settings(name: &str) -> NoiseGeneratorSettings {
+    let path = format!(
+        "{}/../../assets/minecraft/worldgen/noise_settings/{name}.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let json = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{path}: {e}"));
+    serde_json::from_str(&json).unwrap_or_else(|e| panic!("{path}: {e}"))
+}
+
+/// The router output named `name`, as spelled in vanilla's `noise_router`.
+fn root_by_name(router: &NoiseRouter, name: &str) -> Option<Root> {
+    Root::ALL
+        .into_iter()
+        .zip(router.roots())
+        .find(|(_, (root_name, _))| *root_name == name)
+        .map(|(root, _)| root)
+}
+
+#[test]
+fn vanilla_router_fixtures_match() {
+    let fixtures = load_fixtures();
+    assert_eq!(fixtures.schema_version, 1, "unknown fixture schema");
+    assert!(
+        !fixtures.samples.is_empty(),
+        "vanilla_router.json has no samples"
+    );
+
+    let functions = load_density_functions_from_disk();
+    let noises = load_noises_from_disk();
+    let mut routers: BTreeMap<(&str, u64), NoiseRouter> = BTreeMap::new();
+    let mut mismatches = Vec::new();
+    for sample in &fixtures.samples {
+        let router = routers
+            .entry((sample.noise_settings.as_str(), sample.seed))
+            .or_insert_with(|| {
+                build_functions(
+                    &functions,
+                    &noises,
+                    &load_noise_settings(&sample.noise_settings),
+                    sample.seed,
+                    BlockStateId(1),
+                    BlockStateId(86),
+                )
+                .unwrap()
+            });
+        let root = root_by_name(router, &sample.root)
+            .unwrap_or_else(|| panic!("unknown router output {:?}", sample.root));
+        let pos = IVec3::from_array(sample.pos);
+        let actual = router.sample_root(root, pos, &mut router.new_cache());
+        let tolerance = sample.tolerance.unwrap_or(fixtures.tolerance);
+        if !((actual - sample.expected).abs() <= tolerance) {
+            mismatches.push(format!(
+                "{} seed {} {} at {pos}: expected {}, got {actual}",
+                sample.noise_settings, sample.seed, sample.root, sample.expected
+            ));
+        }
+    }
+    assert!(
+        mismatches.is_empty(),
+        "{} of {} samples differ from vanilla:\n{}",
+        mismatches.len(),
+        fixtures.samples.len(),
+        mismatches.join("\n")
+    );
+}
+
+/// Every vanilla `noise_router` field resolves to a router output, so
+/// fixtures may name any of them.
+#[test]
+fn every_vanilla_root_name_resolves() {
+    let functions = load_density_functions_from_disk();
+    let noises = load_noises_from_disk();
+    let router = build_functions(
+        &functions,
+        &noises,
+        &load_noise_settings("overworld"),
+        0,
+        BlockStateId(1),
+        BlockStateId(86),
+    )
+    .unwrap();
+    for (name, index) in router.roots() {
+        let root = root_by_name(&router, name).expect("listed by roots()");
+        assert_eq!(router.root_index(root), index, "{name}");
+    }
+    assert_eq!(root_by_name(&router, "not_a_root"), None);
+}
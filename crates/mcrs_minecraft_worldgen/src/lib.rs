@@ -30,13 +30,20 @@
     unexpected_cfgs
 )]
 
+pub mod aquifer;
 pub mod carver;
 pub mod feature;
-mod climate;
+pub mod climate;
 pub mod density_function;
+mod math;
 mod noise;
+pub mod ore_vein;
 pub mod proto;
+pub mod surface;
 mod spline;
 
+pub use noise::normal_noise::NoiseSampler;
+pub use noise::octave_perlin_noise::OctavePerlinNoise;
+
 #[cfg(feature = "bevy")]
 pub mod bevy;
@@ -0,0 +1,36 @@
+//! Ports of the vanilla `Mth` helpers worldgen shares.
+
+/// Vanilla `Mth.map`: `value` moved linearly from `from_min..from_max` onto
+/// `to_min..to_max`, extrapolating outside the range.
+pub(crate) fn map(value: f64, from_min: f64, from_max: f64, to_min: f64, to_max: f64) -> f64 {
+    let t = (value - from_min) / (from_max - from_min);
+    to_min + t * (to_max - to_min)
+}
+
+/// Vanilla `Mth.clampedMap`: [`map`] with the result held to
+/// `to_min..=to_max`.
+pub(crate) fn clamped_map(
+    value: f64,
+    from_min: f64,
+    from_max: f64,
+    to_min: f64,
+    to_max: f64,
+) -> f64 {
+    let t = ((value - from_min) / (from_max - from_min)).clamp(0.0, 1.0);
+    to_min + t * (to_max - to_min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_extrapolates_and_clamped_map_does_not() {
+        assert_eq!(map(0.5, 0.0, 1.0, 10.0, 20.0), 15.0);
+        assert_eq!(map(2.0, 0.0, 1.0, 10.0, 20.0), 30.0);
+        assert_eq!(map(0.25, 1.0, 0.0, -0.3, 0.8), 0.525);
+        assert_eq!(clamped_map(2.0, 0.0, 1.0, 10.0, 20.0), 20.0);
+        assert_eq!(clamped_map(-1.0, 0.0, 64.0, 1.0, 0.0), 1.0);
+        assert_eq!(clamped_map(32.0, 0.0, 64.0, 1.0, 0.0), 0.5);
+    }
+}
@@ -2,6 +2,7 @@ use crate::noise::simplex::SimplexNoise;
 use mcrs_random::Random;
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimplexOctaveNoise {
     noises: Vec<SimplexNoise>,
     noise_scale: f64,
@@ -13,7 +13,9 @@ const FLAT_SIMPLEX_GRAD: [f32; 64] = [
 ];
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ImprovedNoise<F: Float> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::noise::permutation_serde"))]
     permutation: [u8; 256],
     pub origin_x: F,
     pub origin_y: F,
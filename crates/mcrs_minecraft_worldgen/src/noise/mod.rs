@@ -3,6 +3,7 @@ pub mod gradient;
 pub mod improved_noise;
 pub mod normal_noise;
 pub mod octave_perlin_noise;
+pub mod octave_simplex_noise;
 pub mod simplex;
 
 use serde::{Deserialize, Serialize};
@@ -32,6 +33,28 @@ impl NoiseParam {
     }
 }
 
+/// Serde adapter for the 256-entry permutation tables, which are longer than
+/// the arrays serde handles out of the box.
+#[cfg(feature = "serde")]
+pub(crate) mod permutation_serde {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        permutation: &[u8; 256],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(permutation)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 256], D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        bytes
+            .try_into()
+            .map_err(|bytes: Vec<u8>| D::Error::invalid_length(bytes.len(), &"256 bytes"))
+    }
+}
+
 impl From<Noises> for NoiseParam {
     #[inline]
     fn from(noise: Noises) -> Self {
@@ -5,6 +5,7 @@ use mcrs_random::Random;
 const INPUT_FACTOR: f32 = 1.0181268882175227;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NormalNoise {
     first: OctavePerlinNoise<f32>,
     second: OctavePerlinNoise<f32>,
@@ -16,6 +17,7 @@ pub struct NormalNoise {
 /// (block >> 2, matching Java's per-cell sampling) with an id-intrinsic frequency;
 /// y is ignored entirely.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BetaOctave2dNoise {
     noise: OctavePerlinNoise<f32>,
     frequency: f32,
@@ -25,6 +27,7 @@ pub struct BetaOctave2dNoise {
 /// Beta climate 2D simplex noise (temperature/vegetation/detail). Samples at block
 /// coordinates with id-intrinsic scale/lacunarity constants; y is ignored entirely.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BetaSimplex2dNoise {
     noise: SimplexOctaveNoise,
     scale: f64,
@@ -32,7 +35,21 @@ pub struct BetaSimplex2dNoise {
     max_value: f32,
 }
 
+/// The noise behind the router's `noise` density functions: vanilla's
+/// `NormalNoise`, two octave Perlin noises summed and scaled to roughly unit
+/// range. The Beta variants sample the 2D noises of the old terrain.
+///
+/// ```
+/// use mcrs_minecraft_worldgen::NoiseSampler;
+/// use mcrs_random::RandomSource;
+///
+/// let noise = |seed| NoiseSampler::new(&mut RandomSource::new(seed, false), -6, vec![1.0, 1.0]);
+/// let sample = noise(42).get(0.0, 0.0, 0.0);
+/// assert!(sample.abs() <= noise(42).max_value());
+/// assert_eq!(sample, noise(42).get(0.0, 0.0, 0.0));
+/// ```
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NoiseSampler {
     Normal(NormalNoise),
     BetaOctave2d(BetaOctave2dNoise),
@@ -40,6 +57,9 @@ pub enum NoiseSampler {
 }
 
 impl NoiseSampler {
+    /// Vanilla normal noise with octaves from `first_octave` up, one per
+    /// amplitude; a zero amplitude skips its octave. Draws from `random` the
+    /// same way vanilla does, so a fork of the world seed gives the same noise.
     pub fn new<R>(random: &mut R, first_octave: i32, amplitudes: Vec<f32>) -> Self
     where
         R: Random,
@@ -90,6 +110,7 @@ impl NoiseSampler {
         })
     }
 
+    /// Upper bound of [`NoiseSampler::get`]'s magnitude.
     #[inline]
     pub fn max_value(&self) -> f32 {
         match self {
@@ -99,6 +120,7 @@ impl NoiseSampler {
         }
     }
 
+    /// Sample at block coordinates.
     pub fn get(&self, x: f32, y: f32, z: f32) -> f32 {
         match self {
             Self::Normal(n) => {
@@ -3,6 +3,7 @@ use mcrs_random::Random;
 use num_traits::Float;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OctavePerlinNoise<F: Float> {
     lacunarity: F,
     persistence: F,
@@ -19,6 +20,9 @@ impl Default for OctavePerlinNoise<f32> {
 }
 
 impl<F: Float + Clone> OctavePerlinNoise<F> {
+    /// One Perlin octave per non-zero amplitude, starting at `first_octave`
+    /// (frequency `2^first_octave`). `legacy` selects the pre-1.18 layout that
+    /// draws octaves from `random` in sequence instead of forking per octave.
     pub fn new<T>(random: &mut T, first_octave: i32, amplitudes: Vec<F>, legacy: bool) -> Self
     where
         T: Random + Clone,
@@ -75,6 +79,8 @@ impl<F: Float + Clone> OctavePerlinNoise<F> {
         self.max_value
     }
 
+    /// The largest value the octaves can sum to when each one returns
+    /// `scale`; with a scale of 2 this is [`OctavePerlinNoise::max_value`].
     pub fn edge_value(&self, scale: F) -> F {
         let mut value = F::zero();
         let mut factor = self.persistence;
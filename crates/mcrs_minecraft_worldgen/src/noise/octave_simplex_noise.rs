@@ -0,0 +1,151 @@
+use crate::noise::simplex::SimplexNoise;
+use mcrs_random::Random;
+use mcrs_random::legacy::LegacyRandom;
+use std::collections::BTreeSet;
+
+/// Vanilla `PerlinSimplexNoise`: 2D simplex octaves summed from the highest
+/// frequency down, each sampled at half the input scale and twice the weight
+/// of the one before.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OctaveSimplexNoise {
+    noise_levels: Vec<Option<SimplexNoise>>,
+    highest_freq_input_factor: f64,
+    highest_freq_value_factor: f64,
+}
+
+/// Vanilla skips an absent octave by drawing as many values as building one would.
+fn skip_octave<T: Random>(random: &mut T) {
+    for _ in 0..262 {
+        random.next_i32();
+    }
+}
+
+impl OctaveSimplexNoise {
+    /// One simplex octave per entry of `octaves`, where octave `n` samples at
+    /// frequency `2^n`. Octave 0 and the lower frequencies are drawn from
+    /// `random` in order; higher frequencies come from a [`LegacyRandom`]
+    /// seeded off octave 0, like vanilla.
+    ///
+    /// Panics if `octaves` is empty or spans no octave at all.
+    pub fn new<T: Random>(random: &mut T, octaves: &[i32]) -> Self {
+        let octaves: BTreeSet<i32> = octaves.iter().copied().collect();
+        let (Some(&first), Some(&last)) = (octaves.first(), octaves.last()) else {
+            panic!("OctaveSimplexNoise needs some octaves");
+        };
+        let low_freq_octaves = -first;
+        let high_freq_octaves = last;
+        let octave_count = low_freq_octaves + high_freq_octaves + 1;
+        assert!(
+            octave_count >= 1,
+            "total number of octaves needs to be >= 1"
+        );
+
+        let zero_octave = SimplexNoise::from_random(random);
+        let zero_index = high_freq_octaves;
+        // Vanilla scales by the float literal `9.223372E18F`; `as` saturates like Java's cast.
+        let positional_seed = (zero_octave.sample_3d(
+            zero_octave.origin_x,
+            zero_octave.origin_y,
+            zero_octave.origin_z,
+        ) * 9.223372e18_f32 as f64) as i64;
+
+        let mut noise_levels = vec![None; octave_count as usize];
+        if (0..octave_count).contains(&zero_index) && octaves.contains(&0) {
+            noise_levels[zero_index as usize] = Some(zero_octave);
+        }
+        for i in zero_index + 1..octave_count {
+            if i >= 0 && octaves.contains(&(zero_index - i)) {
+                noise_levels[i as usize] = Some(SimplexNoise::from_random(random));
+            } else {
+                skip_octave(random);
+            }
+        }
+
+        if high_freq_octaves > 0 {
+            let mut positional = LegacyRandom::new(positional_seed as u64);
+            for i in (0..zero_index).rev() {
+                if i < octave_count && octaves.contains(&(zero_index - i)) {
+                    noise_levels[i as usize] = Some(SimplexNoise::from_random(&mut positional));
+                } else {
+                    skip_octave(&mut positional);
+                }
+            }
+        }
+
+        Self {
+            noise_levels,
+            highest_freq_input_factor: 2.0_f64.powi(high_freq_octaves),
+            highest_freq_value_factor: 1.0 / (2.0_f64.powi(octave_count) - 1.0),
+        }
+    }
+
+    /// Vanilla `getValue(x, y, useNoiseOffsets)`. With `use_noise_offsets`
+    /// each octave is shifted by its own origin; the biome temperature noises
+    /// sample without.
+    pub fn sample(&self, x: f64, y: f64, use_noise_offsets: bool) -> f64 {
+        let mut value = 0.0;
+        let mut factor = self.highest_freq_input_factor;
+        let mut value_factor = self.highest_freq_value_factor;
+        for noise in &self.noise_levels {
+            if let Some(noise) = noise {
+                let (offset_x, offset_y) = if use_noise_offsets {
+                    (noise.origin_x, noise.origin_y)
+                } else {
+                    (0.0, 0.0)
+                };
+                value +=
+                    noise.sample_2d(x * factor + offset_x, y * factor + offset_y) * value_factor;
+            }
+            factor /= 2.0;
+            value_factor *= 2.0;
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::OctaveSimplexNoise;
+    use crate::noise::simplex::SimplexNoise;
+    use mcrs_random::legacy::LegacyRandom;
+
+    // Vanilla's biome temperature noises: `Biome.TEMPERATURE_NOISE` (seed 1234,
+    // octave 0) and `FROZEN_TEMPERATURE_NOISE` (seed 3456, octaves -2..=0), both
+    // sampled without offsets. Expected values come from vanilla's
+    // `PerlinSimplexNoise` logic over `java.util.Random` run under Java.
+    #[test]
+    fn biome_temperature_noises_match_vanilla() {
+        let temperature = OctaveSimplexNoise::new(&mut LegacyRandom::new(1234), &[0]);
+        let frozen = OctaveSimplexNoise::new(&mut LegacyRandom::new(3456), &[-2, -1, 0]);
+
+        let cases = [
+            ((0.1, 0.2), -0.8681950698722417, 0.20694367387465373),
+            ((12.5, -7.25), 0.31056928987772464, -0.29938874104678026),
+            ((-300.4, 1024.6), -0.40750730724934375, -0.41116414544687824),
+            ((0.0015, -0.004), 0.010935903212426788, -6.24746229252245E-4),
+        ];
+        for ((x, z), expected_temperature, expected_frozen) in cases {
+            assert_eq!(temperature.sample(x, z, false), expected_temperature);
+            assert_eq!(frozen.sample(x, z, false), expected_frozen);
+        }
+    }
+
+    #[test]
+    fn single_octave_is_the_bare_sampler() {
+        let noise = OctaveSimplexNoise::new(&mut LegacyRandom::new(845), &[0]);
+        let bare = SimplexNoise::from_random(&mut LegacyRandom::new(845));
+        assert_eq!(noise.sample(3.25, -8.5, false), bare.sample_2d(3.25, -8.5));
+        assert_eq!(
+            noise.sample(3.25, -8.5, true),
+            bare.sample_2d(3.25 + bare.origin_x, -8.5 + bare.origin_y)
+        );
+    }
+
+    #[test]
+    fn high_frequency_octaves_are_reachable() {
+        let noise = OctaveSimplexNoise::new(&mut LegacyRandom::new(845), &[-1, 0, 2]);
+        assert_eq!(noise.noise_levels.iter().flatten().count(), 3);
+        assert!(noise.sample(0.5, 0.5, true).is_finite());
+    }
+}
@@ -5,7 +5,9 @@ use mcrs_random::Random;
 /// (`SimplexNoise`). The generator is parameterized over the RNG, so the same struct
 /// serves the legacy (`LegacyRandom`) and modern (`Xoroshiro`) initialization paths.
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimplexNoise {
+    #[cfg_attr(feature = "serde", serde(with = "crate::noise::permutation_serde"))]
     permutation: [u8; 256],
     pub origin_x: f64,
     pub origin_y: f64,
@@ -29,7 +31,8 @@ fn corner(gradient_index: usize, x: f64, y: f64, z: f64, distance: f64) -> f64 {
 
 impl SimplexNoise {
     const SKEW_2D: f64 = 0.3660254037844386;
-    const UNSKEW_2D: f64 = 0.2113248654051871;
+    // `(3 - sqrt(3)) / 6` as vanilla computes it; `0.2113248654051871` is one ulp short.
+    const UNSKEW_2D: f64 = 0.21132486540518713;
 
     const SKEW_3D: f64 = 0.3333333333333333;
     const UNSKEW_3D: f64 = 0.16666666666666666;
@@ -57,9 +60,14 @@ impl SimplexNoise {
     }
 
     pub fn sample(&self, x: f64, z: f64, scale_x: f64, scale_z: f64) -> f64 {
-        let px = x * scale_x + self.origin_x;
-        let py = z * scale_z + self.origin_y;
+        self.sample_2d(x * scale_x + self.origin_x, z * scale_z + self.origin_y)
+    }
 
+    /// 2D simplex noise — vanilla `SimplexNoise.getValue(x, y)`.
+    ///
+    /// Like [`sample_3d`](Self::sample_3d) this takes already-offset coordinates; End
+    /// island generation samples it directly at island-grid coordinates.
+    pub fn sample_2d(&self, px: f64, py: f64) -> f64 {
         let skew = (px + py) * Self::SKEW_2D;
         let i = (px + skew).floor() as i32;
         let j = (py + skew).floor() as i32;
@@ -203,4 +211,32 @@ mod test {
             assert_eq!(noise.sample_3d(x, y, z), expected);
         }
     }
+
+    // Same sampler as above. Expected values come from vanilla's `SimplexNoise.getValue(x, y)`
+    // and `XoroshiroRandomSource` logic run under Java, a harness that also reproduces the
+    // 3D vectors above bit for bit.
+    #[test]
+    fn sample_2d_matches_vanilla() {
+        use mcrs_random::Random;
+        use mcrs_random::xoroshiro::XoroshiroRandom;
+
+        let mut rng = XoroshiroRandom::new(111);
+        rng.next_i32();
+        let noise = SimplexNoise::from_random(&mut rng);
+
+        let cases = [
+            ((0.0, 0.0), 0.0),
+            ((noise.origin_x, noise.origin_y), -0.3712496055452194),
+            ((1.5, -2.25), -0.23642724023194656),
+            ((-37.8, 123.4), -0.617353223992231),
+            ((10000.5, -20000.25), 0.43598383328441503),
+            (
+                (-3.134738528791615E8, 5.676610095659718E7),
+                0.018940199193618792,
+            ),
+        ];
+        for ((x, y), expected) in cases {
+            assert_eq!(noise.sample_2d(x, y), expected);
+        }
+    }
 }
@@ -0,0 +1,209 @@
+//! Port of vanilla `OreVeinifier`: the large copper and iron veins placed
+//! from the router's `vein_toggle`, `vein_ridged` and `vein_gap` entries.
+
+use crate::density_function::{DensityCache, NoiseRouter, Root};
+use crate::math::clamped_map;
+use bevy_math::IVec3;
+use mcrs_protocol::BlockStateId;
+use mcrs_random::{Random, RandomSource};
+
+const VEININESS_THRESHOLD: f64 = 0.4f32 as f64;
+const EDGE_ROUNDOFF_BEGIN: f64 = 20.0;
+const MAX_EDGE_ROUNDOFF: f64 = 0.2;
+const VEIN_SOLIDNESS: f32 = 0.7;
+const MIN_RICHNESS: f64 = 0.1f32 as f64;
+const MAX_RICHNESS: f64 = 0.3f32 as f64;
+const MAX_RICHNESS_THRESHOLD: f64 = 0.6f32 as f64;
+const CHANCE_OF_RAW_ORE_BLOCK: f32 = 0.02;
+const SKIP_ORE_IF_GAP_NOISE_IS_BELOW: f64 = -0.3f32 as f64;
+
+/// Blocks and Y range of one vein kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VeinType {
+    pub ore: BlockStateId,
+    pub raw_ore_block: BlockStateId,
+    pub filler: BlockStateId,
+    /// Inclusive Y range the vein can appear in.
+    pub min_y: i32,
+    pub max_y: i32,
+}
+
+impl VeinType {
+    /// Copper ore and raw copper blocks in granite, between Y 0 and 50.
+    pub fn copper(ore: BlockStateId, raw_ore_block: BlockStateId, filler: BlockStateId) -> Self {
+        Self {
+            ore,
+            raw_ore_block,
+            filler,
+            min_y: 0,
+            max_y: 50,
+        }
+    }
+
+    /// Deepslate iron ore and raw iron blocks in tuff, between Y -60 and -8.
+    pub fn iron(ore: BlockStateId, raw_ore_block: BlockStateId, filler: BlockStateId) -> Self {
+        Self {
+            ore,
+            raw_ore_block,
+            filler,
+            min_y: -60,
+            max_y: -8,
+        }
+    }
+}
+
+/// Picks ore vein blocks for solid terrain positions.
+///
+/// Apply it after the base terrain pass, only where the aquifer kept the
+/// terrain block: a `Some` result replaces that block.
+pub struct VeinGenerator {
+    enabled: bool,
+    random: RandomSource,
+    copper: VeinType,
+    iron: VeinType,
+}
+
+impl VeinGenerator {
+    pub fn new(router: &NoiseRouter, copper: VeinType, iron: VeinType) -> Self {
+        Self {
+            enabled: router.ore_veins_enabled(),
+            random: router.ore_random().clone(),
+            copper,
+            iron,
+        }
+    }
+
+    /// The vein block at `pos`, or `None` to keep the terrain block.
+    pub fn compute(
+        &self,
+        router: &NoiseRouter,
+        cache: &mut DensityCache,
+        pos: IVec3,
+    ) -> Option<BlockStateId> {
+        if !self.enabled {
+            return None;
+        }
+        let toggle = router.sample_root(Root::VeinToggle, pos, cache) as f64;
+        let vein = if toggle > 0.0 {
+            &self.copper
+        } else {
+            &self.iron
+        };
+        let above_bottom = pos.y - vein.min_y;
+        let below_top = vein.max_y - pos.y;
+        if above_bottom < 0 || below_top < 0 {
+            return None;
+        }
+
+        // Veins thin out over the 20 blocks closest to either end of the range.
+        let edge_distance = above_bottom.min(below_top) as f64;
+        let roundoff = clamped_map(
+            edge_distance,
+            0.0,
+            EDGE_ROUNDOFF_BEGIN,
+            -MAX_EDGE_ROUNDOFF,
+            0.0,
+        );
+        let veininess = toggle.abs();
+        if veininess + roundoff < VEININESS_THRESHOLD {
+            return None;
+        }
+
+        let mut random = self.random.clone().fork_at(pos);
+        if random.next_f32() > VEIN_SOLIDNESS {
+            return None;
+        }
+        if router.sample_root(Root::VeinRidged, pos, cache) >= 0.0 {
+            return None;
+        }
+
+        let richness = clamped_map(
+            veininess,
+            VEININESS_THRESHOLD,
+            MAX_RICHNESS_THRESHOLD,
+            MIN_RICHNESS,
+            MAX_RICHNESS,
+        );
+        if (random.next_f32() as f64) < richness
+            && router.sample_root(Root::VeinGap, pos, cache) as f64 > SKIP_ORE_IF_GAP_NOISE_IS_BELOW
+        {
+            if random.next_f32() < CHANCE_OF_RAW_ORE_BLOCK {
+                Some(vein.raw_ore_block)
+            } else {
+                Some(vein.ore)
+            }
+        } else {
+            Some(vein.filler)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::density_function::tests::{overworld_router_with, overworld_settings_with};
+
+    const COPPER: VeinType = VeinType {
+        ore: BlockStateId(10),
+        raw_ore_block: BlockStateId(11),
+        filler: BlockStateId(12),
+        min_y: 0,
+        max_y: 50,
+    };
+    const IRON: VeinType = VeinType {
+        ore: BlockStateId(20),
+        raw_ore_block: BlockStateId(21),
+        filler: BlockStateId(22),
+        min_y: -60,
+        max_y: -8,
+    };
+
+    /// Overworld router (seed 2) with the vein roots replaced by constants, so
+    /// only the positional ore random decides the outcome.
+    fn router_with_veins(toggle: f64) -> NoiseRouter {
+        overworld_router_with(&overworld_settings_with(|router| {
+            router["vein_toggle"] = serde_json::json!(toggle);
+            router["vein_ridged"] = serde_json::json!(-1.0);
+            router["vein_gap"] = serde_json::json!(0.0);
+        }))
+    }
+
+    #[test]
+    fn copper_vein_blocks_at_known_positions() {
+        let router = router_with_veins(0.5);
+        let veins = VeinGenerator::new(&router, COPPER, IRON);
+        let mut cache = router.new_cache();
+        let mut at = |x, z| veins.compute(&router, &mut cache, IVec3::new(x, 25, z));
+        assert_eq!(at(0, 0), Some(COPPER.ore));
+        assert_eq!(at(1, 0), Some(COPPER.filler));
+        assert_eq!(at(3, 0), None);
+        assert_eq!(at(6, 38), Some(COPPER.raw_ore_block));
+    }
+
+    #[test]
+    fn iron_vein_blocks_at_known_positions() {
+        let router = router_with_veins(-0.5);
+        let veins = VeinGenerator::new(&router, COPPER, IRON);
+        let mut cache = router.new_cache();
+        let mut at = |x, z| veins.compute(&router, &mut cache, IVec3::new(x, -30, z));
+        assert_eq!(at(0, 0), Some(IRON.ore));
+        assert_eq!(at(2, 0), Some(IRON.filler));
+        assert_eq!(at(3, 0), None);
+        assert_eq!(at(2, 20), Some(IRON.raw_ore_block));
+    }
+
+    #[test]
+    fn positions_outside_vein_range_are_untouched() {
+        for (toggle, vein) in [(1.0, COPPER), (-1.0, IRON)] {
+            let router = router_with_veins(toggle);
+            let veins = VeinGenerator::new(&router, COPPER, IRON);
+            let mut cache = router.new_cache();
+            for y in (-64..vein.min_y).chain(vein.max_y + 1..320) {
+                for x in 0..16 {
+                    let pos = IVec3::new(x, y, x * 3);
+                    assert_eq!(veins.compute(&router, &mut cache, pos), None, "{pos}");
+                }
+            }
+        }
+    }
+}
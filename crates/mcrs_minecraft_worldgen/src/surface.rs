@@ -0,0 +1,704 @@
+//! Port of vanilla `SurfaceSystem` / `SurfaceRules`.
+//!
+//! After the noise pass a column is solid stone, fluid and air. The surface
+//! system walks each column from the top, tracking how deep into the stone
+//! and how far below water every block is, and lets a rule tree replace the
+//! default block with grass, dirt, sand and so on.
+//!
+//! [`Rule`] and [`Condition`] mirror the datapack `surface_rule` format in
+//! [`crate::proto`] and reuse its anchor types, so rules read from noise
+//! settings can be lowered onto them once block states can be resolved here.
+
+use crate::density_function::proto::NoiseParam;
+use crate::density_function::{DensityCache, NoiseRouter, Root};
+use crate::noise::normal_noise::NoiseSampler;
+use crate::proto::{CaveSurface, VerticalAnchor};
+use bevy_math::IVec3;
+use mcrs_protocol::{BlockStateId, Ident};
+use mcrs_random::{Random, RandomSource};
+use std::collections::{BTreeMap, HashMap};
+
+const AIR: BlockStateId = BlockStateId(0);
+
+/// A surface rule: the block to place, or `None` to leave the block alone.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Rule {
+    Block(BlockStateId),
+    /// The first rule that places a block wins.
+    Sequence(Vec<Rule>),
+    Condition(Condition, Box<Rule>),
+}
+
+/// A test against the [`SurfaceRuleContext`] of the block being placed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    Biome(Vec<Ident<String>>),
+    /// At or above the interpolated preliminary surface, minus a margin.
+    AbovePreliminarySurface,
+    /// Above the water surface of this column, or no water above at all.
+    Water {
+        offset: i32,
+        surface_depth_multiplier: i32,
+        add_stone_depth: bool,
+    },
+    YAbove {
+        anchor: VerticalAnchor,
+        surface_depth_multiplier: i32,
+        add_stone_depth: bool,
+    },
+    /// Within a number of blocks of the nearest floor or ceiling.
+    StoneDepth {
+        offset: i32,
+        add_surface_depth: bool,
+        secondary_depth_range: i32,
+        surface_type: CaveSurface,
+    },
+    /// Random dithering between two anchors, true below and false above.
+    VerticalGradient {
+        random_name: Ident<String>,
+        true_at_and_below: VerticalAnchor,
+        false_at_and_above: VerticalAnchor,
+    },
+    /// The surface noise left no surface layer in this column.
+    Hole,
+    Not(Box<Condition>),
+}
+
+impl Rule {
+    pub fn block(state: BlockStateId) -> Self {
+        Rule::Block(state)
+    }
+
+    pub fn sequence(rules: impl IntoIterator<Item = Rule>) -> Self {
+        Rule::Sequence(rules.into_iter().collect())
+    }
+
+    pub fn if_true(condition: Condition, then_run: Rule) -> Self {
+        Rule::Condition(condition, Box::new(then_run))
+    }
+
+    /// The default overworld ruleset (vanilla `SurfaceRuleData.overworld()`),
+    /// limited to the branches that need no extra noises or heightmaps:
+    /// bedrock and deepslate, beaches and deserts, grass over dirt, and
+    /// sand or gravel on sea floors. Badlands, peaks, frozen oceans and the
+    /// noise-driven patches are not included yet.
+    pub fn overworld(blocks: &OverworldSurfaceBlocks) -> Self {
+        let sand_beach = || biome(&["warm_ocean", "beach", "snowy_beach"]);
+        let desert = || biome(&["desert"]);
+        let sand_with_sandstone_ceiling = || {
+            Rule::sequence([
+                Rule::if_true(on_ceiling(), Rule::block(blocks.sandstone)),
+                Rule::block(blocks.sand),
+            ])
+        };
+        let gravel_with_stone_ceiling = || {
+            Rule::sequence([
+                Rule::if_true(on_ceiling(), Rule::block(blocks.stone)),
+                Rule::block(blocks.gravel),
+            ])
+        };
+        let grass_or_dirt = || {
+            Rule::sequence([
+                Rule::if_true(water(0, 0, false), Rule::block(blocks.grass_block)),
+                Rule::block(blocks.dirt),
+            ])
+        };
+
+        let surface = Rule::sequence([
+            Rule::if_true(
+                on_floor(),
+                Rule::if_true(
+                    water(-1, 0, false),
+                    Rule::sequence([
+                        Rule::if_true(sand_beach(), sand_with_sandstone_ceiling()),
+                        Rule::if_true(desert(), sand_with_sandstone_ceiling()),
+                        grass_or_dirt(),
+                    ]),
+                ),
+            ),
+            Rule::if_true(
+                water(-6, -1, true),
+                Rule::sequence([
+                    Rule::if_true(
+                        under_floor(),
+                        Rule::sequence([
+                            Rule::if_true(sand_beach(), sand_with_sandstone_ceiling()),
+                            Rule::if_true(desert(), sand_with_sandstone_ceiling()),
+                            Rule::block(blocks.dirt),
+                        ]),
+                    ),
+                    Rule::if_true(
+                        sand_beach(),
+                        Rule::if_true(stone_depth_floor(true, 6), Rule::block(blocks.sandstone)),
+                    ),
+                    Rule::if_true(
+                        desert(),
+                        Rule::if_true(stone_depth_floor(true, 30), Rule::block(blocks.sandstone)),
+                    ),
+                ]),
+            ),
+            Rule::if_true(
+                on_floor(),
+                Rule::sequence([
+                    Rule::if_true(
+                        biome(&["warm_ocean", "lukewarm_ocean", "deep_lukewarm_ocean"]),
+                        sand_with_sandstone_ceiling(),
+                    ),
+                    gravel_with_stone_ceiling(),
+                ]),
+            ),
+        ]);
+
+        Rule::sequence([
+            Rule::if_true(
+                Condition::VerticalGradient {
+                    random_name: Ident::new("minecraft:bedrock_floor").unwrap().into(),
+                    true_at_and_below: VerticalAnchor::AboveBottom { above_bottom: 0 },
+                    false_at_and_above: VerticalAnchor::AboveBottom { above_bottom: 5 },
+                },
+                Rule::block(blocks.bedrock),
+            ),
+            Rule::if_true(Condition::AbovePreliminarySurface, surface),
+            Rule::if_true(
+                Condition::VerticalGradient {
+                    random_name: Ident::new("minecraft:deepslate").unwrap().into(),
+                    true_at_and_below: VerticalAnchor::Absolute { absolute: 0 },
+                    false_at_and_above: VerticalAnchor::Absolute { absolute: 8 },
+                },
+                Rule::block(blocks.deepslate),
+            ),
+        ])
+    }
+}
+
+fn biome(names: &[&str]) -> Condition {
+    Condition::Biome(
+        names
+            .iter()
+            .map(|name| Ident::new(format!("minecraft:{name}")).unwrap().into())
+            .collect(),
+    )
+}
+
+fn water(offset: i32, surface_depth_multiplier: i32, add_stone_depth: bool) -> Condition {
+    Condition::Water {
+        offset,
+        surface_depth_multiplier,
+        add_stone_depth,
+    }
+}
+
+/// Vanilla `ON_FLOOR`: the topmost block of a floor.
+fn on_floor() -> Condition {
+    stone_depth(0, false, 0, CaveSurface::Floor)
+}
+
+/// Vanilla `UNDER_FLOOR`: the surface layer of a floor, `surface_depth` deep.
+fn under_floor() -> Condition {
+    stone_depth(0, true, 0, CaveSurface::Floor)
+}
+
+fn on_ceiling() -> Condition {
+    stone_depth(0, false, 0, CaveSurface::Ceiling)
+}
+
+fn stone_depth_floor(add_surface_depth: bool, secondary_depth_range: i32) -> Condition {
+    stone_depth(
+        0,
+        add_surface_depth,
+        secondary_depth_range,
+        CaveSurface::Floor,
+    )
+}
+
+fn stone_depth(
+    offset: i32,
+    add_surface_depth: bool,
+    secondary_depth_range: i32,
+    surface_type: CaveSurface,
+) -> Condition {
+    Condition::StoneDepth {
+        offset,
+        add_surface_depth,
+        secondary_depth_range,
+        surface_type,
+    }
+}
+
+/// Block states placed by [`Rule::overworld`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OverworldSurfaceBlocks {
+    pub bedrock: BlockStateId,
+    pub deepslate: BlockStateId,
+    pub stone: BlockStateId,
+    pub grass_block: BlockStateId,
+    pub dirt: BlockStateId,
+    pub sand: BlockStateId,
+    pub sandstone: BlockStateId,
+    pub gravel: BlockStateId,
+}
+
+/// The blocks of one column, indexed by world Y.
+pub trait BlockColumn {
+    fn get_block(&self, y: i32) -> BlockStateId;
+
+    fn set_block(&mut self, y: i32, state: BlockStateId);
+}
+
+/// Everything a [`Condition`] can ask about the block being placed.
+pub struct SurfaceRuleContext<'a> {
+    pub block_x: i32,
+    pub block_y: i32,
+    pub block_z: i32,
+    /// Depth of the soil layer in this column, from the surface noise.
+    pub surface_depth: i32,
+    /// `Integer.MIN_VALUE` when there is no water above this block.
+    pub water_height: i32,
+    /// Solid blocks from this one up to the nearest air or fluid, inclusive.
+    pub stone_depth_above: i32,
+    /// Solid blocks from this one down to the nearest air or fluid, inclusive.
+    pub stone_depth_below: i32,
+    pub min_surface_level: i32,
+    system: &'a SurfaceSystem,
+    surface_secondary: Option<f64>,
+    biome: Option<Ident<String>>,
+    biome_at: &'a mut dyn FnMut(IVec3) -> Ident<String>,
+}
+
+impl SurfaceRuleContext<'_> {
+    fn surface_secondary(&mut self) -> f64 {
+        let (system, x, z) = (self.system, self.block_x, self.block_z);
+        *self.surface_secondary.get_or_insert_with(|| {
+            system.surface_secondary_noise.get(x as f32, 0.0, z as f32) as f64
+        })
+    }
+
+    fn biome(&mut self) -> &Ident<String> {
+        let pos = IVec3::new(self.block_x, self.block_y, self.block_z);
+        let biome_at = &mut self.biome_at;
+        self.biome.get_or_insert_with(|| biome_at(pos))
+    }
+
+    fn resolve_y(&self, anchor: &VerticalAnchor) -> i32 {
+        match *anchor {
+            VerticalAnchor::Absolute { absolute } => absolute,
+            VerticalAnchor::AboveBottom { above_bottom } => self.system.min_y + above_bottom,
+            VerticalAnchor::BelowTop { below_top } => {
+                self.system.min_y + self.system.height - 1 - below_top
+            }
+        }
+    }
+
+    pub fn test(&mut self, condition: &Condition) -> bool {
+        match condition {
+            Condition::Biome(biomes) => {
+                let biome = self.biome();
+                biomes.iter().any(|b| b == biome)
+            }
+            Condition::AbovePreliminarySurface => self.block_y >= self.min_surface_level,
+            Condition::Water {
+                offset,
+                surface_depth_multiplier,
+                add_stone_depth,
+            } => {
+                if self.water_height == i32::MIN {
+                    return true;
+                }
+                let y = self.block_y
+                    + if *add_stone_depth {
+                        self.stone_depth_above
+                    } else {
+                        0
+                    };
+                y >= self.water_height + offset + self.surface_depth * surface_depth_multiplier
+            }
+            Condition::YAbove {
+                anchor,
+                surface_depth_multiplier,
+                add_stone_depth,
+            } => {
+                let y = self.block_y
+                    + if *add_stone_depth {
+                        self.stone_depth_above
+                    } else {
+                        0
+                    };
+                y >= self.resolve_y(anchor) + self.surface_depth * surface_depth_multiplier
+            }
+            Condition::StoneDepth {
+                offset,
+                add_surface_depth,
+                secondary_depth_range,
+                surface_type,
+            } => {
+                let depth = match surface_type {
+                    CaveSurface::Ceiling => self.stone_depth_below,
+                    CaveSurface::Floor => self.stone_depth_above,
+                };
+                let surface_depth = if *add_surface_depth {
+                    self.surface_depth
+                } else {
+                    0
+                };
+                let secondary_depth = if *secondary_depth_range == 0 {
+                    0
+                } else {
+                    let secondary = self.surface_secondary();
+                    ((secondary + 1.0) / 2.0 * *secondary_depth_range as f64) as i32
+                };
+                depth <= 1 + offset + surface_depth + secondary_depth
+            }
+            Condition::VerticalGradient {
+                random_name,
+                true_at_and_below,
+                false_at_and_above,
+            } => {
+                let true_y = self.resolve_y(true_at_and_below);
+                let false_y = self.resolve_y(false_at_and_above);
+                if self.block_y <= true_y {
+                    return true;
+                }
+                if self.block_y >= false_y {
+                    return false;
+                }
+                let chance = 1.0 - (self.block_y - true_y) as f64 / (false_y - true_y) as f64;
+                let mut random = self
+                    .system
+                    .positional_random(random_name)
+                    .clone()
+                    .fork_at(IVec3::new(self.block_x, self.block_y, self.block_z));
+                (random.next_f32() as f64) < chance
+            }
+            Condition::Hole => self.surface_depth <= 0,
+            Condition::Not(condition) => !self.test(condition),
+        }
+    }
+
+    pub fn apply(&mut self, rule: &Rule) -> Option<BlockStateId> {
+        match rule {
+            Rule::Block(state) => Some(*state),
+            Rule::Sequence(rules) => rules.iter().find_map(|rule| self.apply(rule)),
+            Rule::Condition(condition, then_run) => {
+                if self.test(condition) {
+                    self.apply(then_run)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// Applies a surface [`Rule`] to generated columns.
+pub struct SurfaceSystem {
+    rule: Rule,
+    default_block: BlockStateId,
+    /// Block states treated as fluid rather than stone while walking a column.
+    fluids: Vec<BlockStateId>,
+    min_y: i32,
+    height: i32,
+    random: RandomSource,
+    surface_noise: NoiseSampler,
+    surface_secondary_noise: NoiseSampler,
+    /// `fromHashOf(name)` forks for the vertical gradients used by `rule`.
+    gradient_randoms: HashMap<Ident<String>, RandomSource>,
+}
+
+impl SurfaceSystem {
+    pub fn new(
+        router: &NoiseRouter,
+        noises: &BTreeMap<Ident<String>, NoiseParam>,
+        rule: Rule,
+        fluids: &[BlockStateId],
+    ) -> Self {
+        let random = router.random().clone();
+        let noise = |id: &str| {
+            let param = noises
+                .get(id)
+                .unwrap_or_else(|| panic!("Noise not loaded: {}", id));
+            NoiseSampler::new(
+                &mut random.clone().fork_hash(id),
+                param.first_octave,
+                param.amplitudes.iter().map(|x| x.0 as f32).collect(),
+            )
+        };
+        let surface_noise = noise("minecraft:surface");
+        let surface_secondary_noise = noise("minecraft:surface_secondary");
+
+        let mut gradient_randoms = HashMap::new();
+        collect_gradient_randoms(&rule, &random, &mut gradient_randoms);
+
+        Self {
+            rule,
+            default_block: router.default_block_state(),
+            fluids: fluids.to_vec(),
+            min_y: router.noise_min_y(),
+            height: router.noise_height() as i32,
+            random,
+            surface_noise,
+            surface_secondary_noise,
+            gradient_randoms,
+        }
+    }
+
+    fn positional_random(&self, name: &Ident<String>) -> &RandomSource {
+        &self.gradient_randoms[name]
+    }
+
+    fn is_stone(&self, state: BlockStateId) -> bool {
+        state != AIR && !self.fluids.contains(&state)
+    }
+
+    /// Depth of the soil layer at a column, jittered per block.
+    pub fn surface_depth(&self, x: i32, z: i32) -> i32 {
+        let noise = self.surface_noise.get(x as f32, 0.0, z as f32) as f64;
+        let jitter = self.random.clone().fork_at(IVec3::new(x, 0, z)).next_f64();
+        (noise * 2.75 + 3.0 + jitter * 0.25) as i32
+    }
+
+    /// Lowest Y that counts as [`Condition::AbovePreliminarySurface`] in a
+    /// column: the router's preliminary surface bilinearly interpolated
+    /// between the chunk corners, minus a margin of `8 - surface_depth`.
+    pub fn min_surface_level(
+        &self,
+        router: &NoiseRouter,
+        cache: &mut DensityCache,
+        x: i32,
+        z: i32,
+    ) -> i32 {
+        let mut corner = |dx: i32, dz: i32| {
+            let pos = IVec3::new(((x >> 4) + dx) << 4, 0, ((z >> 4) + dz) << 4);
+            router
+                .sample_root(Root::PreliminarySurfaceLevel, pos, cache)
+                .floor() as f64
+        };
+        let (c00, c10, c01, c11) = (corner(0, 0), corner(1, 0), corner(0, 1), corner(1, 1));
+        let fx = (x & 15) as f64 / 16.0;
+        let fz = (z & 15) as f64 / 16.0;
+        let lerp = |t: f64, a: f64, b: f64| a + t * (b - a);
+        let level = lerp(fz, lerp(fx, c00, c10), lerp(fx, c01, c11)).floor() as i32;
+        level + self.surface_depth(x, z) - 8
+    }
+
+    /// Vanilla `buildSurface` for one column: walks it top-down and replaces
+    /// default blocks with whatever the rule picks.
+    pub fn build_column(
+        &self,
+        router: &NoiseRouter,
+        cache: &mut DensityCache,
+        x: i32,
+        z: i32,
+        column: &mut impl BlockColumn,
+        biome_at: &mut dyn FnMut(IVec3) -> Ident<String>,
+    ) {
+        let min_y = self.min_y;
+        let mut context = SurfaceRuleContext {
+            block_x: x,
+            block_y: min_y + self.height - 1,
+            block_z: z,
+            surface_depth: self.surface_depth(x, z),
+            water_height: i32::MIN,
+            stone_depth_above: 0,
+            stone_depth_below: 0,
+            min_surface_level: self.min_surface_level(router, cache, x, z),
+            system: self,
+            surface_secondary: None,
+            biome: None,
+            biome_at,
+        };
+
+        let mut stone_depth_above = 0;
+        let mut water_height = i32::MIN;
+        // Bottom of the stone run the current block belongs to.
+        let mut next_ceiling_y = i32::MAX;
+        for y in (min_y..min_y + self.height).rev() {
+            let state = column.get_block(y);
+            if state == AIR {
+                stone_depth_above = 0;
+                water_height = i32::MIN;
+                continue;
+            }
+            if !self.is_stone(state) {
+                if water_height == i32::MIN {
+                    water_height = y + 1;
+                }
+                continue;
+            }
+
+            if next_ceiling_y >= y {
+                next_ceiling_y = crate::aquifer::WAY_BELOW_MIN_Y;
+                for below in (min_y - 1..y).rev() {
+                    if below < min_y || !self.is_stone(column.get_block(below)) {
+                        next_ceiling_y = below + 1;
+                        break;
+                    }
+                }
+            }
+            stone_depth_above += 1;
+
+            context.block_y = y;
+            context.water_height = water_height;
+            context.stone_depth_above = stone_depth_above;
+            context.stone_depth_below = y - next_ceiling_y + 1;
+            context.biome = None;
+            if state == self.default_block {
+                if let Some(result) = context.apply(&self.rule) {
+                    column.set_block(y, result);
+                }
+            }
+        }
+    }
+}
+
+fn collect_gradient_randoms(
+    rule: &Rule,
+    random: &RandomSource,
+    out: &mut HashMap<Ident<String>, RandomSource>,
+) {
+    fn visit_condition(
+        condition: &Condition,
+        random: &RandomSource,
+        out: &mut HashMap<Ident<String>, RandomSource>,
+    ) {
+        match condition {
+            Condition::VerticalGradient { random_name, .. } => {
+                out.entry(random_name.clone())
+                    .or_insert_with(|| random.clone().fork_hash(random_name.as_str()));
+            }
+            Condition::Not(condition) => visit_condition(condition, random, out),
+            _ => {}
+        }
+    }
+    match rule {
+        Rule::Block(_) => {}
+        Rule::Sequence(rules) => {
+            for rule in rules {
+                collect_gradient_randoms(rule, random, out);
+            }
+        }
+        Rule::Condition(condition, then_run) => {
+            visit_condition(condition, random, out);
+            collect_gradient_randoms(then_run, random, out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::density_function::tests::{load_noises_from_disk, overworld_router};
+
+    const STONE: BlockStateId = BlockStateId(1);
+    const WATER: BlockStateId = BlockStateId(86);
+    const BLOCKS: OverworldSurfaceBlocks = OverworldSurfaceBlocks {
+        bedrock: BlockStateId(2),
+        deepslate: BlockStateId(3),
+        stone: STONE,
+        grass_block: BlockStateId(4),
+        dirt: BlockStateId(5),
+        sand: BlockStateId(6),
+        sandstone: BlockStateId(7),
+        gravel: BlockStateId(8),
+    };
+
+    struct TestColumn {
+        min_y: i32,
+        blocks: Vec<BlockStateId>,
+    }
+
+    impl BlockColumn for TestColumn {
+        fn get_block(&self, y: i32) -> BlockStateId {
+            self.blocks[(y - self.min_y) as usize]
+        }
+
+        fn set_block(&mut self, y: i32, state: BlockStateId) {
+            self.blocks[(y - self.min_y) as usize] = state;
+        }
+    }
+
+    /// Runs the overworld rules over a stone column in `biome` and returns the
+    /// column with its top Y and surface depth. The top sits at the preliminary
+    /// surface or sea level, whichever is higher, plus `raise`.
+    fn surface_column(biome: &str, raise: i32) -> (TestColumn, i32, i32) {
+        let router = overworld_router();
+        let noises = load_noises_from_disk();
+        let system = SurfaceSystem::new(&router, &noises, Rule::overworld(&BLOCKS), &[WATER]);
+
+        let (x, z) = (5, 9);
+        let mut cache = router.new_cache();
+        let min_surface_level = system.min_surface_level(&router, &mut cache, x, z);
+        let top = min_surface_level.max(router.sea_level()) + raise;
+
+        let min_y = router.noise_min_y();
+        let height = router.noise_height() as i32;
+        let mut column = TestColumn {
+            min_y,
+            blocks: (min_y..min_y + height)
+                .map(|y| if y <= top { STONE } else { AIR })
+                .collect(),
+        };
+        let biome: Ident<String> = Ident::new(biome.to_string()).unwrap().into();
+        system.build_column(&router, &mut cache, x, z, &mut column, &mut |_| {
+            biome.clone()
+        });
+        (column, top, system.surface_depth(x, z))
+    }
+
+    #[test]
+    fn plains_column_is_grass_over_dirt() {
+        let (column, top, depth) = surface_column("minecraft:plains", 8);
+        assert_eq!(column.get_block(top), BLOCKS.grass_block);
+        for y in top - depth..top {
+            assert_eq!(column.get_block(y), BLOCKS.dirt, "y = {y}");
+        }
+        assert_eq!(column.get_block(top - depth.max(0) - 1), STONE);
+        assert_eq!(column.get_block(column.min_y), BLOCKS.bedrock);
+        assert_eq!(column.get_block(-10), BLOCKS.deepslate);
+    }
+
+    #[test]
+    fn beach_column_is_sand_over_sandstone() {
+        let (column, top, depth) = surface_column("minecraft:beach", 0);
+        assert_eq!(column.get_block(top), BLOCKS.sand);
+        for y in top - depth..top {
+            assert_eq!(column.get_block(y), BLOCKS.sand, "y = {y}");
+        }
+        // Sandstone reaches at most 6 blocks below the sand.
+        let below_sand = top - depth.max(0) - 1;
+        for y in below_sand - 5..=below_sand {
+            let block = column.get_block(y);
+            assert!(block == BLOCKS.sandstone || block == STONE, "y = {y}");
+        }
+        assert_eq!(column.get_block(below_sand - 6), STONE);
+    }
+
+    #[test]
+    fn flooded_floor_is_dirt() {
+        let router = overworld_router();
+        let noises = load_noises_from_disk();
+        let rule = Rule::overworld(&BLOCKS);
+        let system = SurfaceSystem::new(&router, &noises, rule.clone(), &[WATER]);
+        let mut biome_at =
+            |_: IVec3| -> Ident<String> { Ident::new("minecraft:plains").unwrap().into() };
+        let mut context = SurfaceRuleContext {
+            block_x: 0,
+            block_y: 50,
+            block_z: 0,
+            surface_depth: 3,
+            water_height: 60,
+            stone_depth_above: 1,
+            stone_depth_below: 20,
+            min_surface_level: 40,
+            system: &system,
+            surface_secondary: None,
+            biome: None,
+            biome_at: &mut biome_at,
+        };
+        assert_eq!(context.apply(&rule), Some(BLOCKS.dirt));
+        // Right below the water surface grass still needs open air above.
+        context.water_height = 51;
+        assert_eq!(context.apply(&rule), Some(BLOCKS.dirt));
+        context.water_height = i32::MIN;
+        assert_eq!(context.apply(&rule), Some(BLOCKS.grass_block));
+    }
+}
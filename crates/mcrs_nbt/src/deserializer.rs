@@ -111,10 +111,19 @@ impl<'de, R: Read + Seek> de::Deserializer<'de> for &mut Deserializer<R> {
     }
 
     fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        let Some(tag_to_deserialize) = self.tag_to_deserialize_stack else {
-            return Err(Error::SerdeError(
-                "The top level must be a component (e.g. a struct)".to_string(),
-            ));
+        let tag_to_deserialize = match self.tag_to_deserialize_stack {
+            Some(tag) => tag,
+            // Self-describing root, e.g. a text component: read its tag id
+            // (and name) here, as `deserialize_map` does for structs.
+            None => {
+                let tag = self.input.get_u8_be()?;
+                if self.is_named {
+                    let length = self.input.get_u16_be()? as usize;
+                    let _ = self.input.read_boxed_slice(length)?;
+                }
+                self.tag_to_deserialize_stack = Some(tag);
+                tag
+            }
         };
 
         match tag_to_deserialize {
@@ -1,23 +1,36 @@
-use crate::SharedNetworkState;
-use crate::intent::handle_intent;
+use crate::intent::{SERVER_VERSION_NAME, handle_intent};
 use crate::metrics::BRIDGE_HANDSHAKE_INFLIGHT;
-use crate::packet_io::PacketIo;
+use crate::{NetworkConfig, SharedNetworkState};
 use log::{error, info, warn};
+use socket2::{Domain, Protocol, Socket, Type};
 use std::collections::HashMap;
-use std::net::IpAddr;
-use std::sync::Arc;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::{Duration, Instant};
-use tokio::net::TcpListener;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
 use tokio::time::timeout;
 
 const HANDLE_CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub const ACCEPT_BUCKET_CAP: u32 = 5;
+pub const ACCEPT_BUCKET_WINDOW: Duration = Duration::from_secs(10);
 // 5 tokens over a 10 s window → 0.5 tokens/s
 pub const ACCEPT_REFILL_PER_SEC: f32 = 0.5;
 pub const GLOBAL_HANDSHAKE_CAP: usize = 64;
 
+/// First byte of a pre-1.7 server list ping. It is not VarInt-framed, so it
+/// must be answered before the stream reaches the packet decoder.
+pub const LEGACY_PING_ID: u8 = 0xFE;
+/// Vanilla always reports 127 in legacy pings; old clients can't use the
+/// real protocol number anyway.
+const LEGACY_PROTOCOL_VERSION: i32 = 127;
+
+/// Fixed 12-byte prefix of every PROXY protocol v2 header.
+pub const PROXY_V2_SIGNATURE: [u8; 12] = *b"\r\n\r\n\0\r\nQUIT\n";
+
 pub struct TokenBucket {
     pub tokens: u32,
     last_refill: Instant,
@@ -32,9 +45,22 @@ impl TokenBucket {
     }
 
     pub fn consume(&mut self, cap: u32, refill_per_sec: f32) -> bool {
-        let elapsed = self.last_refill.elapsed().as_secs_f32();
-        self.tokens = (self.tokens as f32 + elapsed * refill_per_sec).min(cap as f32) as u32;
-        self.last_refill = Instant::now();
+        self.consume_at(Instant::now(), cap, refill_per_sec)
+    }
+
+    /// Take a token at `now`, if there is one. A refused attempt costs
+    /// nothing: the part of a token accrued so far is kept, so retrying
+    /// faster than the refill still gets a token once it is due.
+    pub fn consume_at(&mut self, now: Instant, cap: u32, refill_per_sec: f32) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let refilled = (elapsed.as_secs_f32() * refill_per_sec) as u32;
+        if self.tokens.saturating_add(refilled) >= cap {
+            self.tokens = cap;
+            self.last_refill = now;
+        } else if refilled > 0 {
+            self.tokens += refilled;
+            self.last_refill += Duration::from_secs_f32(refilled as f32 / refill_per_sec);
+        }
         if self.tokens > 0 {
             self.tokens -= 1;
             true
@@ -44,14 +70,68 @@ impl TokenBucket {
     }
 }
 
-/// Decision function separated from the async loop so it is testable without a real socket.
-pub fn accept_decision(bucket: &mut TokenBucket, inflight: usize) -> AcceptOutcome {
-    if !bucket.consume(ACCEPT_BUCKET_CAP, ACCEPT_REFILL_PER_SEC) {
-        return AcceptOutcome::RateLimited;
+/// A [`TokenBucket`] per client IP: each may open `cap` connections in a
+/// burst and gets them back evenly over `window`.
+pub struct PerIpBuckets {
+    cap: u32,
+    refill_per_sec: f32,
+    window: Duration,
+    buckets: HashMap<IpAddr, TokenBucket>,
+}
+
+impl PerIpBuckets {
+    /// Forget idle IPs once the map grows past this many entries.
+    const PRUNE_THRESHOLD: usize = 1024;
+
+    pub fn new(cap: u32, window: Duration) -> Self {
+        Self {
+            cap,
+            refill_per_sec: cap as f32 / window.as_secs_f32(),
+            window,
+            buckets: HashMap::new(),
+        }
     }
+
+    /// The limit set by [`NetworkConfig::connection_throttle`] and
+    /// [`NetworkConfig::connection_throttle_window`].
+    pub fn from_config(config: &NetworkConfig) -> Self {
+        Self::new(
+            config.connection_throttle,
+            config.connection_throttle_window,
+        )
+    }
+
+    /// Take a token from `ip`'s bucket at `now`. Returns `false` if it has
+    /// none left.
+    pub fn try_accept(&mut self, ip: IpAddr, now: Instant) -> bool {
+        if self.buckets.len() > Self::PRUNE_THRESHOLD {
+            // A bucket left alone for a whole window is full again.
+            let window = self.window;
+            self.buckets
+                .retain(|_, bucket| now.saturating_duration_since(bucket.last_refill) < window);
+        }
+        let cap = self.cap;
+        self.buckets
+            .entry(ip)
+            .or_insert_with(|| TokenBucket::new(cap))
+            .consume_at(now, cap, self.refill_per_sec)
+    }
+}
+
+/// Decision function separated from the async loop so it is testable without a real socket.
+/// A connection refused for the global cap does not use up `ip`'s token.
+pub fn accept_decision(
+    buckets: &mut PerIpBuckets,
+    ip: IpAddr,
+    now: Instant,
+    inflight: usize,
+) -> AcceptOutcome {
     if inflight >= GLOBAL_HANDSHAKE_CAP {
         return AcceptOutcome::CapExceeded;
     }
+    if !buckets.try_accept(ip, now) {
+        return AcceptOutcome::RateLimited;
+    }
     AcceptOutcome::Accept
 }
 
@@ -73,30 +153,98 @@ impl Drop for InflightGuard {
     }
 }
 
-pub(crate) async fn start_accept_loop(shared: SharedNetworkState) {
-    let listener = match TcpListener::bind(shared.0.address).await {
+/// Why [`bind_listener`] could not open the listener.
+#[derive(Debug, thiserror::Error)]
+pub enum BindError {
+    #[error("{0} is already in use; is another server running on this port?")]
+    AddrInUse(SocketAddr),
+    #[error("failed to bind {address}: {source}")]
+    Io {
+        address: SocketAddr,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// Open the listening socket for `config`, applying its dual-stack setting.
+/// Must be called from within a tokio runtime.
+pub fn bind_listener(config: &NetworkConfig) -> Result<TcpListener, BindError> {
+    let address = config.address;
+    let bind = || -> io::Result<TcpListener> {
+        let socket = Socket::new(
+            Domain::for_address(address),
+            Type::STREAM,
+            Some(Protocol::TCP),
+        )?;
+        if address.is_ipv6() {
+            socket.set_only_v6(!config.dual_stack)?;
+        }
+        // What `TcpListener::bind` does: restart without waiting out TIME_WAIT.
+        #[cfg(unix)]
+        socket.set_reuse_address(true)?;
+        socket.set_nonblocking(true)?;
+        socket.bind(&address.into())?;
+        socket.listen(1024)?;
+        TcpListener::from_std(socket.into())
+    };
+    bind().map_err(|source| match source.kind() {
+        io::ErrorKind::AddrInUse => BindError::AddrInUse(address),
+        _ => BindError::Io { address, source },
+    })
+}
+
+/// The address a client connected from. A dual-stack listener reports IPv4
+/// clients as IPv4-mapped IPv6 addresses; those are turned back into IPv4.
+pub fn client_addr(remote_addr: SocketAddr) -> SocketAddr {
+    SocketAddr::new(remote_addr.ip().to_canonical(), remote_addr.port())
+}
+
+pub(crate) async fn start_accept_loop(shared: SharedNetworkState, config: NetworkConfig) {
+    let address = config.address;
+    let listener = match bind_listener(&config) {
         Ok(listener) => listener,
         Err(e) => {
-            error!("Failed to bind to address {} {}", shared.0.address, e);
+            error!("{e}");
             return;
         }
     };
-    info!("Listening on {}", shared.0.address);
+    info!("Listening on {address}");
 
-    // HashMap is safe without locks: the accept-loop runs in a single tokio task.
-    let mut per_ip_buckets: HashMap<IpAddr, TokenBucket> = HashMap::new();
+    // Shared with the connection tasks: behind a proxy the client's address
+    // is only known once its PROXY header has been read.
+    let per_ip_buckets = Arc::new(Mutex::new(PerIpBuckets::from_config(&config)));
     let inflight = Arc::new(AtomicUsize::new(0));
+    let config = Arc::new(config);
+    let mut shutdown = shared.0.shutdown.subscribe();
 
     loop {
-        match listener.accept().await {
+        let accepted = tokio::select! {
+            accepted = listener.accept() => accepted,
+            _ = shutdown.wait_for(|&stop| stop) => {
+                info!("Stopped listening on {address}");
+                return;
+            }
+        };
+        match accepted {
             Ok((socket, remote_addr)) => {
+                let remote_addr = client_addr(remote_addr);
                 let ip = remote_addr.ip();
-                let bucket = per_ip_buckets
-                    .entry(ip)
-                    .or_insert_with(|| TokenBucket::new(ACCEPT_BUCKET_CAP));
-
                 let current_inflight = inflight.load(Ordering::Relaxed);
-                match accept_decision(bucket, current_inflight) {
+                let outcome = if config.proxy_protocol {
+                    // `ip` is the proxy's; the client is charged in
+                    // `accept_proxied` instead.
+                    if current_inflight >= GLOBAL_HANDSHAKE_CAP {
+                        AcceptOutcome::CapExceeded
+                    } else {
+                        AcceptOutcome::Accept
+                    }
+                } else {
+                    let mut buckets = per_ip_buckets
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner);
+                    accept_decision(&mut buckets, ip, Instant::now(), current_inflight)
+                };
+                match outcome {
                     AcceptOutcome::RateLimited => {
                         warn!("accept-rate limit exceeded for {ip}");
                         // socket dropped here — no tokio task spawned
@@ -114,11 +262,13 @@ pub(crate) async fn start_accept_loop(shared: SharedNetworkState) {
 
                 let guard = InflightGuard(inflight.clone());
                 let shared = shared.clone();
+                let config = config.clone();
+                let per_ip_buckets = per_ip_buckets.clone();
                 tokio::spawn(async move {
                     let _guard = guard;
                     if let Err(e) = timeout(
                         HANDLE_CONNECTION_TIMEOUT,
-                        handle_connection(shared, socket, remote_addr),
+                        handle_connection(shared, socket, remote_addr, &config, &per_ip_buckets),
                     )
                     .await
                     {
@@ -134,16 +284,154 @@ pub(crate) async fn start_accept_loop(shared: SharedNetworkState) {
     }
 }
 
+/// Peek at the first byte without consuming it. `true` if the client opened
+/// with a legacy ping rather than a modern length-prefixed handshake.
+pub async fn is_legacy_ping(stream: &TcpStream) -> io::Result<bool> {
+    let mut first = [0u8; 1];
+    let n = stream.peek(&mut first).await?;
+    Ok(n == 1 && first[0] == LEGACY_PING_ID)
+}
+
+/// Encode the legacy kick packet (`0xFF`, UTF-16BE length-prefixed string)
+/// carrying the `§1`-delimited status understood by 1.4–1.6 clients.
+pub fn legacy_ping_response(version: &str, motd: &str, online: u32, max: u32) -> Vec<u8> {
+    let status = format!("§1\0{LEGACY_PROTOCOL_VERSION}\0{version}\0{motd}\0{online}\0{max}");
+    let units: Vec<u16> = status.encode_utf16().collect();
+    let mut out = Vec::with_capacity(3 + units.len() * 2);
+    out.push(0xFF);
+    out.extend_from_slice(&(units.len() as u16).to_be_bytes());
+    for unit in units {
+        out.extend_from_slice(&unit.to_be_bytes());
+    }
+    out
+}
+
+async fn handle_legacy_ping(mut stream: TcpStream, config: &NetworkConfig) -> io::Result<()> {
+    // Drain the request so closing doesn't reset the connection before the
+    // client reads the response. Its contents don't change the answer.
+    let mut request = [0u8; 512];
+    let _ = stream.try_read(&mut request);
+    stream
+        .write_all(&legacy_ping_response(
+            SERVER_VERSION_NAME,
+            &config.motd.to_legacy_lossy(),
+            0,
+            config.max_players,
+        ))
+        .await?;
+    stream.shutdown().await
+}
+
+/// Read a PROXY protocol v2 header off the front of `stream`, consuming
+/// exactly the header and nothing after it.
+///
+/// Returns the client's source address, or `None` when the header carries no
+/// usable one (a `LOCAL` health check from the proxy, or a non-IP family).
+/// A missing or malformed header is an error.
+pub async fn read_proxy_header<R>(stream: &mut R) -> anyhow::Result<Option<SocketAddr>>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut header = [0u8; 16];
+    stream.read_exact(&mut header).await?;
+    anyhow::ensure!(
+        header[..12] == PROXY_V2_SIGNATURE,
+        "missing PROXY v2 signature"
+    );
+    let version = header[12] >> 4;
+    anyhow::ensure!(version == 2, "unsupported PROXY protocol version {version}");
+    let family = header[13] >> 4;
+    let len = u16::from_be_bytes([header[14], header[15]]) as usize;
+    let mut body = vec![0u8; len];
+    stream.read_exact(&mut body).await?;
+
+    match header[12] & 0x0F {
+        0x0 => return Ok(None),
+        0x1 => {}
+        command => anyhow::bail!("unknown PROXY command {command:#x}"),
+    }
+    // Addresses are source then destination, followed by the two ports.
+    // Trailing TLVs are ignored.
+    match family {
+        0x1 => {
+            anyhow::ensure!(len >= 12, "PROXY v2 IPv4 block too short ({len})");
+            let ip = Ipv4Addr::new(body[0], body[1], body[2], body[3]);
+            let port = u16::from_be_bytes([body[8], body[9]]);
+            Ok(Some(SocketAddr::new(ip.into(), port)))
+        }
+        0x2 => {
+            anyhow::ensure!(len >= 36, "PROXY v2 IPv6 block too short ({len})");
+            let octets: [u8; 16] = body[..16].try_into()?;
+            let port = u16::from_be_bytes([body[32], body[33]]);
+            Ok(Some(SocketAddr::new(Ipv6Addr::from(octets).into(), port)))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Read the PROXY header off a connection from the proxy at `proxy_addr` and
+/// take a token for the client it names from `per_ip_buckets`.
+///
+/// Returns the client's address, or `None` if the connection is to be
+/// dropped. The proxy's own `LOCAL` health checks keep `proxy_addr` and are
+/// not throttled.
+pub async fn accept_proxied<R>(
+    stream: &mut R,
+    proxy_addr: SocketAddr,
+    per_ip_buckets: &Mutex<PerIpBuckets>,
+) -> Option<SocketAddr>
+where
+    R: AsyncRead + Unpin,
+{
+    let source = match read_proxy_header(stream).await {
+        Ok(Some(source)) => client_addr(source),
+        Ok(None) => return Some(proxy_addr),
+        Err(e) => {
+            warn!("Dropping {}: bad PROXY header: {}", proxy_addr, e);
+            return None;
+        }
+    };
+    let accepted = per_ip_buckets
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .try_accept(source.ip(), Instant::now());
+    if !accepted {
+        warn!("accept-rate limit exceeded for {}", source.ip());
+        return None;
+    }
+    Some(source)
+}
+
 async fn handle_connection(
     shared: SharedNetworkState,
-    stream: tokio::net::TcpStream,
-    remote_addr: std::net::SocketAddr,
+    mut stream: TcpStream,
+    mut remote_addr: SocketAddr,
+    config: &NetworkConfig,
+    per_ip_buckets: &Mutex<PerIpBuckets>,
 ) {
     if let Err(e) = stream.set_nodelay(true) {
         warn!("Failed to set nodelay on {}: {}", remote_addr, e);
     }
-    let io = PacketIo::new(stream);
-    if let Err(e) = handle_intent(shared, io, remote_addr).await {
+    if config.proxy_protocol {
+        match accept_proxied(&mut stream, remote_addr, per_ip_buckets).await {
+            Some(source) => remote_addr = source,
+            None => return,
+        }
+    }
+    match is_legacy_ping(&stream).await {
+        Ok(true) => {
+            if let Err(e) = handle_legacy_ping(stream, config).await {
+                warn!("Failed to answer legacy ping from {}: {}", remote_addr, e);
+            }
+            return;
+        }
+        Ok(false) => {}
+        Err(e) => {
+            warn!("Failed to read from {}: {}", remote_addr, e);
+            return;
+        }
+    }
+    if let Err(e) = handle_intent(shared, stream, remote_addr, config).await {
         warn!("Error during handshake with {}: {}", remote_addr, e);
     }
 }
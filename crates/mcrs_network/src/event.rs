@@ -1,14 +1,19 @@
-use crate::{EngineConnection, InGameConnectionState, ServerSideConnection};
-use bevy_app::{App, Plugin, Update};
+use crate::{
+    ConnectionState, EngineConnection, InGameConnectionState, NetworkSet, ServerSideConnection,
+};
+use bevy_app::{App, FixedPostUpdate, Plugin, Update};
 use bevy_ecs::entity::Entity;
 use bevy_ecs::event::EntityEvent;
+use bevy_ecs::message::{Message, MessageWriter};
+use bevy_ecs::observer::On;
 use bevy_ecs::prelude::Commands;
 use bevy_ecs::query::Without;
-use bevy_ecs::schedule::ScheduleLabel;
+use bevy_ecs::schedule::{IntoScheduleConfigs, ScheduleLabel};
 use bevy_ecs::system::Query;
 use bytes::Bytes;
 use log::warn;
-use mcrs_protocol::{Decode, Packet};
+use mcrs_protocol::packets::{configuration, game, login};
+use mcrs_protocol::{Decode, Packet, Text};
 use std::time::Instant;
 
 #[derive(Debug, Clone, EntityEvent)]
@@ -45,13 +50,114 @@ impl ReceivedPacketEvent {
     }
 }
 
-pub(crate) struct EventLoopPlugin;
+/// A serverbound packet together with the connection state it arrived in.
+///
+/// The same id means different packets in Login, Configuration and Game, so
+/// [`packet`](Self::packet) decodes with the table for `state`. Only packets
+/// whose body decoded cleanly are sent; a malformed one disconnects the
+/// client instead. Ids without a packet type in that state are not sent.
+#[derive(Message, Debug, Clone)]
+pub struct ClientPacket {
+    pub entity: Entity,
+    pub state: ConnectionState,
+    pub id: i32,
+    pub data: Bytes,
+    pub timestamp: Instant,
+}
+
+/// A decoded [`ClientPacket`], one variant per connection state.
+#[derive(Clone, Debug)]
+pub enum ServerboundPacket<'a> {
+    Login(login::serverbound::ServerboundPacket<'a>),
+    Configuration(configuration::serverbound::Packet<'a>),
+    Game(game::serverbound::Packet<'a>),
+}
+
+/// Decode `data` with the serverbound packet table for `state`.
+fn decode_for_state(
+    state: ConnectionState,
+    id: i32,
+    data: &[u8],
+) -> anyhow::Result<Option<ServerboundPacket<'_>>> {
+    Ok(match state {
+        ConnectionState::Login => login::serverbound::ServerboundPacket::decode_body(id, data)?
+            .map(ServerboundPacket::Login),
+        ConnectionState::Configuration => {
+            configuration::serverbound::Packet::decode_body(id, data)?
+                .map(ServerboundPacket::Configuration)
+        }
+        ConnectionState::Game => {
+            game::serverbound::Packet::decode_body(id, data)?.map(ServerboundPacket::Game)
+        }
+    })
+}
+
+impl ClientPacket {
+    pub fn packet(&self) -> ServerboundPacket<'_> {
+        decode_for_state(self.state, self.id, &self.data)
+            .ok()
+            .flatten()
+            .expect("ClientPacket is only sent after its body decoded")
+    }
+}
+
+/// Re-send each received packet as a [`ClientPacket`] for the connection's
+/// current state, disconnecting clients whose packet fails to decode.
+pub fn emit_client_packet(
+    event: On<ReceivedPacketEvent>,
+    mut query: Query<(&ConnectionState, &mut ServerSideConnection)>,
+    mut writer: MessageWriter<ClientPacket>,
+) {
+    let Ok((state, mut conn)) = query.get_mut(event.entity) else {
+        return;
+    };
+    match decode_for_state(*state, event.id, &event.data) {
+        Ok(Some(_)) => {
+            writer.write(ClientPacket {
+                entity: event.entity,
+                state: *state,
+                id: event.id,
+                data: event.data.clone(),
+                timestamp: event.timestamp,
+            });
+        }
+        Ok(None) => {}
+        Err(e) => {
+            warn!(
+                "disconnecting {}: bad packet {:#04x} in {:?}: {e}",
+                conn.remote_addr(),
+                event.id,
+                state
+            );
+            conn.disconnect(*state, Text::translate("disconnect.packetError", vec![]));
+        }
+    }
+}
+
+/// Receiving and flushing for connections that are not in Game, and the
+/// [`NetworkSet`] order. Added by `NetworkPlugin`; add it on its own to run
+/// these systems without a listener.
+pub struct EventLoopPlugin;
 
 impl Plugin for EventLoopPlugin {
     fn build(&self, app: &mut App) {
         // app.init_schedule(RunEventLoop);
         // let mut order = app.world_mut().resource_mut::<MainScheduleOrder>();
-        app.add_systems(Update, run_event_loop);
+        app.add_message::<ClientPacket>();
+        app.add_observer(emit_client_packet);
+        for schedule in [Update.intern(), FixedPostUpdate.intern()] {
+            app.configure_sets(
+                schedule,
+                (NetworkSet::Receive, NetworkSet::Process, NetworkSet::Flush).chain(),
+            );
+        }
+        app.add_systems(
+            Update,
+            (
+                run_event_loop.in_set(NetworkSet::Receive),
+                flush_connections.in_set(NetworkSet::Flush),
+            ),
+        );
     }
 }
 
@@ -67,30 +173,29 @@ fn run_event_loop(
     mut commands: Commands,
 ) {
     query.iter_mut().for_each(|(entity, mut conn)| {
-        loop {
-            match conn.try_recv() {
-                Ok(Some(pkt)) => {
-                    commands.trigger(ReceivedPacketEvent {
-                        entity,
-                        id: pkt.id,
-                        data: pkt.payload,
-                        timestamp: pkt.timestamp,
-                    });
-                    // let now = Instant::now();
-                    // info!(
-                    //     "{}: processed packet {} in {:?}",
-                    //     entity,
-                    //     pkt.id,
-                    //     now - pkt.timestamp
-                    // );
-                }
-                Ok(None) => break,
-                Err(e) => {
-                    warn!("disconnecting client: {e}");
-                    commands.entity(entity).despawn();
-                    break;
-                }
-            }
+        let mut received = conn.drain_received();
+        for pkt in &mut received {
+            commands.trigger(ReceivedPacketEvent {
+                entity,
+                id: pkt.id,
+                data: pkt.payload,
+                timestamp: pkt.timestamp,
+            });
+        }
+        if received.disconnected() {
+            warn!("disconnecting client: connection closed");
+            commands.entity(entity).despawn();
         }
     });
 }
+
+/// Send what `NetworkSet::Process` systems wrote to connections that are not
+/// in Game. In-game connections are flushed by the bridge in
+/// `FixedPostUpdate`.
+fn flush_connections(mut query: Query<&mut ServerSideConnection, Without<InGameConnectionState>>) {
+    for mut conn in &mut query {
+        // A full channel keeps the bytes for the next flush, and a closed one
+        // is despawned by `run_event_loop`.
+        let _ = conn.flush();
+    }
+}
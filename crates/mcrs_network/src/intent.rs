@@ -1,61 +1,148 @@
-use crate::SharedNetworkState;
 use crate::packet_io::PacketIo;
+use crate::{NetworkConfig, RawConnection, SharedNetworkState};
 use log::debug;
-use mcrs_protocol::PROTOCOL_VERSION;
 use mcrs_protocol::handshake::Intent;
 use mcrs_protocol::packets::intent::serverbound::ServerboundHandshake;
+use mcrs_protocol::packets::login::clientbound::ClientboundLoginDisconnect;
 use mcrs_protocol::packets::ping::clientbound::PongResponse;
 use mcrs_protocol::packets::ping::serverbound::PingRequest;
 use mcrs_protocol::packets::status::clientbound::StatusResponse;
+use mcrs_protocol::packets::status::serverbound::StatusRequest;
+use mcrs_protocol::{Bounded, MINECRAFT_VERSION, PROTOCOL_VERSION, Text};
 use serde_json::json;
+use std::net::SocketAddr;
+use tokio::net::TcpStream;
 
-pub(crate) async fn handle_intent(
-    shared: SharedNetworkState,
-    mut io: PacketIo,
-    remote_addr: std::net::SocketAddr,
-) -> anyhow::Result<()> {
+/// Version name advertised in server list pings, modern and legacy.
+pub(crate) const SERVER_VERSION_NAME: &str = "mcrs";
+
+/// Oldest protocol (1.16.4) vanilla blames the client for rather than
+/// reporting a plain incompatibility.
+const OUTDATED_CLIENT_PROTOCOL: i32 = 754;
+
+/// The Login disconnect reason for a client speaking `protocol_version`, or
+/// `None` if it matches [`PROTOCOL_VERSION`]. Worded like vanilla: clients
+/// older than 1.16.4 are told they are outdated, anything else that differs
+/// is "incompatible". Status pings skip this check so the MOTD still shows.
+pub fn incompatible_version_reason(protocol_version: i32) -> Option<Text> {
+    if protocol_version == PROTOCOL_VERSION {
+        return None;
+    }
+    let key = if protocol_version < OUTDATED_CLIENT_PROTOCOL {
+        "multiplayer.disconnect.outdated_client"
+    } else {
+        "multiplayer.disconnect.incompatible"
+    };
+    Some(Text::translate(key, vec![Text::text(MINECRAFT_VERSION)]))
+}
+
+/// Where a connection went after its handshake.
+pub enum HandshakeOutcome {
+    /// Intent 1: the client was sent the server list status, and a pong if it
+    /// pinged. The connection is closed.
+    Status,
+    /// Intent 2, or 3 when transfers are accepted: the connection continues
+    /// in Login.
+    Login(RawConnection),
+    /// The client was sent a Login disconnect, for an incompatible protocol
+    /// or a transfer the server doesn't accept.
+    Refused,
+}
+
+/// Read the handshake from a freshly accepted `stream` and serve its intent:
+/// answer a server list ping, or check the client can log in and hand the
+/// connection back for Login.
+pub async fn serve_handshake(
+    stream: TcpStream,
+    remote_addr: SocketAddr,
+    config: &NetworkConfig,
+) -> anyhow::Result<HandshakeOutcome> {
     debug!("Handling intent from {}", remote_addr);
+    let mut io = PacketIo::new(stream);
     let handshake = io.recv_packet::<ServerboundHandshake>().await?;
-    let intent = handshake.intent;
+    let protocol_version = handshake.protocol_version.0;
 
-    match intent {
+    match handshake.intent {
         Intent::Status => {
-            let _request = io
-                .recv_packet::<mcrs_protocol::packets::status::serverbound::StatusRequest>()
-                .await?;
-            let json = json!({
-                "version": {
-                    "name": "mcrs",
-                    "protocol": PROTOCOL_VERSION
-                },
-                "players": {
-                    "max": 0,
-                    "online": 0,
-                    "sample": []
-                },
-                "description": {
-                    "text": "mcrs Server"
-                }
-            })
-            .to_string();
-            io.send_packet(&StatusResponse { json: &json }).await?;
-
-            if let Ok(ping) = io.recv_packet::<PingRequest>().await {
-                io.send_packet(&PongResponse {
-                    payload: ping.payload,
-                })
-                .await?;
-            }
+            serve_status(&mut io, config).await?;
+            Ok(HandshakeOutcome::Status)
         }
-        Intent::Login => {
-            let raw_connection = io.into_raw_connection(remote_addr);
-            shared
-                .0
-                .new_connections_send
-                .send(Box::new(raw_connection))
-                .await?;
+        Intent::Login => begin_login(io, remote_addr, protocol_version).await,
+        Intent::Transfer => {
+            if !config.accepts_transfers {
+                debug!("{remote_addr} was transferred, but transfers are disabled");
+                let reason = Text::translate("multiplayer.disconnect.transfers_disabled", vec![]);
+                refuse_login(&mut io, &reason).await?;
+                return Ok(HandshakeOutcome::Refused);
+            }
+            begin_login(io, remote_addr, protocol_version).await
         }
-        Intent::Transfer => {}
+    }
+}
+
+/// Answer Status Request with the server list status, then Ping Request with
+/// a pong if the client sends one.
+async fn serve_status(io: &mut PacketIo, config: &NetworkConfig) -> anyhow::Result<()> {
+    let _request = io.recv_packet::<StatusRequest>().await?;
+    let json = json!({
+        "version": {
+            "name": SERVER_VERSION_NAME,
+            "protocol": PROTOCOL_VERSION
+        },
+        "players": {
+            "max": config.max_players,
+            "online": 0,
+            "sample": []
+        },
+        "description": config.motd
+    })
+    .to_string();
+    io.send_packet(&StatusResponse { json: &json }).await?;
+
+    if let Ok(ping) = io.recv_packet::<PingRequest>().await {
+        io.send_packet(&PongResponse {
+            payload: ping.payload,
+        })
+        .await?;
+    }
+    Ok(())
+}
+
+async fn begin_login(
+    mut io: PacketIo,
+    remote_addr: SocketAddr,
+    protocol_version: i32,
+) -> anyhow::Result<HandshakeOutcome> {
+    if let Some(reason) = incompatible_version_reason(protocol_version) {
+        debug!("{remote_addr} uses protocol {protocol_version}, expected {PROTOCOL_VERSION}");
+        refuse_login(&mut io, &reason).await?;
+        return Ok(HandshakeOutcome::Refused);
+    }
+    Ok(HandshakeOutcome::Login(io.into_raw_connection(remote_addr)))
+}
+
+async fn refuse_login(io: &mut PacketIo, reason: &Text) -> anyhow::Result<()> {
+    let json = serde_json::to_string(reason)?;
+    io.send_packet(&ClientboundLoginDisconnect {
+        reason: Bounded(json.as_str()),
+    })
+    .await
+}
+
+pub(crate) async fn handle_intent(
+    shared: SharedNetworkState,
+    stream: TcpStream,
+    remote_addr: SocketAddr,
+    config: &NetworkConfig,
+) -> anyhow::Result<()> {
+    if let HandshakeOutcome::Login(raw_connection) =
+        serve_handshake(stream, remote_addr, config).await?
+    {
+        shared
+            .0
+            .new_connections_send
+            .send(Box::new(raw_connection))
+            .await?;
     }
     Ok(())
 }
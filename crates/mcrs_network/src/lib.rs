@@ -1,34 +1,62 @@
 pub mod connect;
 pub mod event;
+pub mod intent;
 pub mod metrics;
-mod intent;
 mod packet_io;
 mod status;
 
-pub use crate::packet_io::{MAX_QUEUED_BYTES_PER_SOCKET, RawConnection};
-use bevy_app::{App, FixedPreUpdate, Plugin, PostStartup};
+pub use crate::metrics::ConnectionStats;
+pub use crate::packet_io::{
+    MAX_QUEUED_BYTES_PER_SOCKET, OUTBOUND_CHANNEL_CAPACITY, RawConnection, ReceivedPackets,
+    SendError, SendPriority,
+};
+use bevy_app::{App, AppExit, FixedPreUpdate, Last, Plugin, PostStartup};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::{MessageCursor, Messages};
 use bevy_ecs::prelude::Component;
 use bevy_ecs::resource::Resource;
 use bevy_ecs::schedule::{IntoScheduleConfigs, SystemSet};
-use bevy_ecs::system::{Res};
-use bevy_ecs::world::World;
+use bevy_ecs::system::{Commands, EntityCommand, Local, Query, Res};
+use bevy_ecs::world::{EntityWorldMut, World};
 
 /// System sets for the network layer, usable for ordering constraints in
 /// downstream crates. `SpawnConnections` contains `spawn_new_raw_connections`.
 /// Other crates should schedule their connection-setup systems
 /// `.after(NetworkSet::SpawnConnections)` in `FixedPreUpdate`.
+///
+/// `Receive`, `Process` and `Flush` are chained in both `Update`, where
+/// connections that are not in Game are served, and `FixedPostUpdate`, where
+/// the in-game bridge reads and writes sockets. A system in
+/// `NetworkSet::Process` sees every packet received earlier in the same run of
+/// its schedule, and what it writes is sent before the run ends.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, SystemSet)]
 pub enum NetworkSet {
     SpawnConnections,
+    /// Drains the sockets and triggers a `ReceivedPacketEvent` per packet.
+    Receive,
+    /// Gameplay systems reacting to received packets.
+    Process,
+    /// Sends everything written to the connections.
+    Flush,
 }
 use bytes::Bytes;
-use mcrs_protocol::{Encode, Packet, WritePacket};
-use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
-use std::sync::Arc;
-use std::time::Instant;
+use log::warn;
+use mcrs_protocol::packets::configuration::clientbound::ClientboundDisconnect as ConfigurationDisconnect;
+use mcrs_protocol::packets::game::clientbound::ClientboundBundleDelimiter;
+use mcrs_protocol::packets::game::clientbound::ClientboundDisconnect as GameDisconnect;
+use mcrs_protocol::packets::login::clientbound::ClientboundLoginDisconnect;
+use mcrs_protocol::{Bounded, CompressionThreshold, Encode, Packet, Text, WritePacket};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
 use tokio::runtime::{Handle, Runtime};
 use tokio::sync::mpsc::error::TryRecvError;
 use tokio::sync::mpsc::{Sender, channel};
+use tokio::sync::watch;
+
+/// How long teardown waits for queued packets to reach the sockets, and then
+/// for the runtime's tasks to stop.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
 
 pub struct NetworkPlugin;
 
@@ -45,17 +73,22 @@ fn build_plugin(app: &mut App) -> anyhow::Result<()> {
     let (new_sessions_send, mut new_sessions_recv) = channel(128);
 
     let shared_state = SharedNetworkState(Arc::new(SharedNetworkStateInner {
-        address: SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 25565).into(),
         tokio_handle,
-        tokio_runtime: Some(runtime),
+        tokio_runtime: Mutex::new(Some(runtime)),
         new_connections_send: new_sessions_send,
+        shutdown: watch::Sender::new(false),
     }));
 
     app.insert_resource(shared_state.clone());
+    app.init_resource::<NetworkConfig>();
 
-    let start_accept_loop = move |shared_state: Res<SharedNetworkState>| {
+    let start_accept_loop = move |shared_state: Res<SharedNetworkState>,
+                                  config: Res<NetworkConfig>| {
         let _guard = shared_state.0.tokio_handle.enter();
-        tokio::spawn(connect::start_accept_loop(shared_state.clone()));
+        tokio::spawn(connect::start_accept_loop(
+            shared_state.clone(),
+            config.clone(),
+        ));
     };
     let spawn_new_raw_connections = move |world: &mut World| {
         for _ in 0..new_sessions_recv.len() {
@@ -71,10 +104,14 @@ fn build_plugin(app: &mut App) -> anyhow::Result<()> {
     };
 
     app.add_systems(PostStartup, start_accept_loop);
+    app.add_systems(Last, shutdown_on_exit);
     app.configure_sets(FixedPreUpdate, NetworkSet::SpawnConnections);
     app.add_systems(
         FixedPreUpdate,
-        spawn_new_raw_connections.in_set(NetworkSet::SpawnConnections),
+        (
+            despawn_closed_connections.before(NetworkSet::SpawnConnections),
+            spawn_new_raw_connections.in_set(NetworkSet::SpawnConnections),
+        ),
     );
     // flush_packets and check_congestion removed; the FixedPostUpdate bridge chain
     // is registered by BridgePlugin in mcrs_minecraft.
@@ -83,16 +120,166 @@ fn build_plugin(app: &mut App) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Despawn every connection that was closed via
+/// [`ServerSideConnection::disconnect`] during the previous tick. Its
+/// disconnect packet has already been flushed to the writer task.
+fn despawn_closed_connections(
+    query: Query<(Entity, &ServerSideConnection)>,
+    mut commands: Commands,
+) {
+    for (entity, conn) in &query {
+        if conn.is_closing() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Tear the network down once [`AppExit`] has been written: stop the accept
+/// loop, kick every client with vanilla's shutdown message, wait for the
+/// queued packets to reach the sockets, then shut the tokio runtime down so
+/// every socket is closed rather than left for the client to time out.
+pub fn shutdown_on_exit(world: &mut World, mut exit: Local<MessageCursor<AppExit>>) {
+    let Some(messages) = world.get_resource::<Messages<AppExit>>() else {
+        return;
+    };
+    if exit.read(messages).last().is_none() {
+        return;
+    }
+    let runtime = world
+        .get_resource::<SharedNetworkState>()
+        .and_then(SharedNetworkState::begin_shutdown);
+
+    let reason = Text::translate("multiplayer.disconnect.server_shutdown", vec![]);
+    let mut connections =
+        world.query::<(Entity, &mut ServerSideConnection, Option<&ConnectionState>)>();
+    let mut closed = Vec::new();
+    let mut writers = Vec::new();
+    for (entity, mut conn, state) in connections.iter_mut(world) {
+        if let Some(&state) = state
+            && !conn.is_closing()
+        {
+            conn.disconnect(state, reason.clone());
+        }
+        writers.extend(conn.raw.take_writer_task());
+        closed.push(entity);
+    }
+    // Dropping a connection drops its outgoing channel, so its writer task
+    // drains what is queued and closes the socket.
+    for entity in closed {
+        world.despawn(entity);
+    }
+
+    let Some(runtime) = runtime else {
+        return;
+    };
+    let pending = writers.len();
+    let flushed = runtime.block_on(tokio::time::timeout(SHUTDOWN_TIMEOUT, async {
+        for writer in writers {
+            let _ = writer.await;
+        }
+    }));
+    if flushed.is_err() {
+        warn!("{pending} connections did not flush within {SHUTDOWN_TIMEOUT:?}");
+    }
+    runtime.shutdown_timeout(SHUTDOWN_TIMEOUT);
+}
+
+/// Listener settings. Insert before [`NetworkPlugin`] to override the
+/// defaults; the accept loop reads it once at startup.
+#[derive(Resource, Clone, Debug)]
+pub struct NetworkConfig {
+    /// Address the listener binds. Defaults to every IPv4 interface on 25565.
+    pub address: SocketAddr,
+    /// For an IPv6 `address`, also serve IPv4 clients on the same listener
+    /// (`IPV6_V6ONLY` off); they are reported by their IPv4 address. Without
+    /// it an IPv6 listener serves IPv6 only. Ignored for IPv4 addresses.
+    pub dual_stack: bool,
+    /// Connections one IP may open in a burst. It gets them back evenly over
+    /// `connection_throttle_window`; sockets beyond that are closed
+    /// immediately and do not count against it.
+    pub connection_throttle: u32,
+    pub connection_throttle_window: Duration,
+    /// Expect a PROXY protocol v2 header on every connection and use its
+    /// source address as the client's. Connections without one are dropped.
+    /// Connections are throttled by that source address too.
+    pub proxy_protocol: bool,
+    /// Description shown in the server list. Legacy pings get it as plain
+    /// text.
+    pub motd: Text,
+    /// Player limit shown in the server list.
+    pub max_players: u32,
+    /// Let clients arrive by transfer (handshake intent 3). Otherwise they
+    /// are disconnected before Login, like vanilla's `accepts-transfers`.
+    pub accepts_transfers: bool,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self::all_v4(25565)
+    }
+}
+
+impl NetworkConfig {
+    /// Every IPv4 interface.
+    pub fn all_v4(port: u16) -> Self {
+        Self::with_address(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), port))
+    }
+
+    /// Every IPv6 interface, IPv6 clients only.
+    pub fn all_v6(port: u16) -> Self {
+        Self::with_address(SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), port))
+    }
+
+    /// `127.0.0.1` only, for local testing.
+    pub fn loopback(port: u16) -> Self {
+        Self::with_address(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port))
+    }
+
+    /// Every IPv6 interface, serving IPv4 clients on the same listener.
+    pub fn dual_stack(port: u16) -> Self {
+        Self {
+            dual_stack: true,
+            ..Self::all_v6(port)
+        }
+    }
+
+    fn with_address(address: SocketAddr) -> Self {
+        Self {
+            address,
+            dual_stack: false,
+            connection_throttle: connect::ACCEPT_BUCKET_CAP,
+            connection_throttle_window: connect::ACCEPT_BUCKET_WINDOW,
+            proxy_protocol: false,
+            motd: Text::text("mcrs Server"),
+            max_players: 20,
+            accepts_transfers: false,
+        }
+    }
+}
+
 #[derive(Resource, Clone)]
 struct SharedNetworkState(Arc<SharedNetworkStateInner>);
 
+impl SharedNetworkState {
+    /// Stop the accept loop and take the runtime. `None` if it was already
+    /// taken.
+    fn begin_shutdown(&self) -> Option<Runtime> {
+        self.0.shutdown.send_replace(true);
+        self.0
+            .tokio_runtime
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+}
+
 struct SharedNetworkStateInner {
-    address: SocketAddr,
     tokio_handle: Handle,
-    // Held to keep the runtime alive for the process lifetime; dropping it shuts down all tasks.
-    #[allow(dead_code)]
-    tokio_runtime: Option<Runtime>,
+    /// Taken and shut down by [`shutdown_on_exit`].
+    tokio_runtime: Mutex<Option<Runtime>>,
     new_connections_send: Sender<Box<RawConnection>>,
+    /// Set to `true` to stop the accept loop.
+    shutdown: watch::Sender<bool>,
 }
 
 #[derive(Clone, Debug)]
@@ -114,6 +301,47 @@ pub enum ConnectionState {
     Game,
 }
 
+impl ConnectionState {
+    /// Returns `true` if vanilla allows moving from `self` to `next`.
+    ///
+    /// The legal flow is `Login → Configuration → Game`, plus
+    /// `Game → Configuration` for the reconfigure path. Staying in the same
+    /// state is not a transition and returns `false`.
+    pub fn can_transition_to(&self, next: ConnectionState) -> bool {
+        matches!(
+            (self, next),
+            (ConnectionState::Login, ConnectionState::Configuration)
+                | (ConnectionState::Configuration, ConnectionState::Game)
+                | (ConnectionState::Game, ConnectionState::Configuration)
+        )
+    }
+
+    /// Move to `next` if the transition is legal. An illegal jump logs a
+    /// warning and leaves the state untouched, so packet handlers never
+    /// decode with the wrong state's id table.
+    pub fn transition_to(&mut self, next: ConnectionState) -> bool {
+        if !self.can_transition_to(next) {
+            warn!("illegal connection state transition {self:?} -> {next:?}");
+            return false;
+        }
+        *self = next;
+        true
+    }
+}
+
+/// Entity command performing a validated [`ConnectionState`] transition.
+///
+/// Use this instead of inserting a new `ConnectionState` directly:
+/// `commands.entity(e).queue(transition_connection_state(ConnectionState::Game))`.
+/// Entities without a `ConnectionState` are left alone.
+pub fn transition_connection_state(next: ConnectionState) -> impl EntityCommand {
+    move |mut entity: EntityWorldMut| {
+        if let Some(mut state) = entity.get_mut::<ConnectionState>() {
+            state.transition_to(next);
+        }
+    }
+}
+
 #[derive(Component)]
 #[component(storage = "SparseSet")]
 pub struct InGameConnectionState;
@@ -126,6 +354,102 @@ impl ServerSideConnection {
     pub fn queued_bytes(&self) -> usize {
         self.raw.queued_bytes()
     }
+
+    /// How full the send buffer is, from 0.0 to 1.0. See
+    /// [`RawConnection::send_buffer_pressure`].
+    pub fn send_buffer_pressure(&self) -> f32 {
+        self.raw.send_buffer_pressure()
+    }
+
+    /// Write `packet` to the lane for `priority`; see [`SendPriority`].
+    pub fn write_packet_with_priority<P>(&mut self, packet: &P, priority: SendPriority)
+    where
+        P: Encode + Packet,
+    {
+        self.raw.write_packet_with_priority(packet, priority);
+    }
+
+    /// Kick the client with `reason`.
+    ///
+    /// Writes the disconnect packet matching `state` — JSON text during
+    /// Login, an NBT text component in Configuration and Game — ahead of
+    /// anything still queued, flushes it, and marks the connection closed so
+    /// the entity is despawned on the next fixed tick.
+    pub fn disconnect(&mut self, state: ConnectionState, reason: Text) {
+        match state {
+            ConnectionState::Login => {
+                let json = serde_json::to_string(&reason).unwrap_or_default();
+                self.write_packet_with_priority(
+                    &ClientboundLoginDisconnect {
+                        reason: Bounded(json.as_str()),
+                    },
+                    SendPriority::High,
+                );
+            }
+            ConnectionState::Configuration => {
+                self.write_packet_with_priority(
+                    &ConfigurationDisconnect { reason },
+                    SendPriority::High,
+                );
+            }
+            ConnectionState::Game => {
+                self.write_packet_with_priority(&GameDisconnect { reason }, SendPriority::High);
+            }
+        }
+        if let Err(e) = self.flush() {
+            warn!("failed to flush disconnect to {}: {e}", self.remote_addr());
+        }
+        self.raw.close();
+    }
+
+    /// Run `f`'s writes as one bundle: the client handles every packet it
+    /// writes in the same frame, e.g. an entity spawn together with its
+    /// metadata. Nothing is flushed until the bundle closes, and a bundle
+    /// opened inside another simply joins it.
+    pub fn bundle(&mut self, f: impl FnOnce(&mut Self)) {
+        if self.raw.is_bundling() {
+            f(self);
+            return;
+        }
+        self.write_packet(&ClientboundBundleDelimiter);
+        self.raw.set_bundling(true);
+        f(self);
+        self.raw.set_bundling(false);
+        self.write_packet(&ClientboundBundleDelimiter);
+    }
+
+    /// Compress packets at or above `threshold` bytes from here on; see
+    /// [`RawConnection::set_compression`].
+    pub fn set_compression(&mut self, threshold: CompressionThreshold) {
+        self.raw.set_compression(threshold);
+    }
+
+    /// Pause inbound decoding after the client's Encryption Response; see
+    /// [`RawConnection::expect_encryption`].
+    pub fn expect_encryption(&mut self) {
+        self.raw.expect_encryption();
+    }
+
+    /// Encrypt the connection with the shared secret `key`; see
+    /// [`RawConnection::enable_encryption`].
+    pub fn enable_encryption(&mut self, key: &[u8; 16]) {
+        self.raw.enable_encryption(key);
+    }
+
+    /// `true` once [`disconnect`](Self::disconnect) has been called.
+    pub fn is_closing(&self) -> bool {
+        self.raw.is_closing()
+    }
+
+    /// Bytes and packets sent/received on this connection so far.
+    pub fn stats(&self) -> ConnectionStats {
+        self.raw.stats()
+    }
+
+    /// Every packet received so far; see [`RawConnection::drain_received`].
+    pub fn drain_received(&mut self) -> ReceivedPackets<'_> {
+        self.raw.drain_received()
+    }
 }
 
 impl WritePacket for ServerSideConnection {
@@ -146,7 +470,7 @@ impl EngineConnection for ServerSideConnection {
         self.raw.try_recv()
     }
 
-    fn flush(&mut self) -> anyhow::Result<()> {
+    fn flush(&mut self) -> Result<(), SendError> {
         self.raw.flush()
     }
 
@@ -163,6 +487,6 @@ impl Drop for ServerSideConnection {
 
 pub trait EngineConnection: Send + Sync + 'static {
     fn try_recv(&mut self) -> Result<Option<ReceivedPacket>, TryRecvError>;
-    fn flush(&mut self) -> anyhow::Result<()>;
+    fn flush(&mut self) -> Result<(), SendError>;
     fn queued_bytes(&self) -> usize;
 }
@@ -1,5 +1,53 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 
+/// Per-connection traffic counters shared between a `RawConnection` and its
+/// reader task. All updates are `Relaxed`: the values are monotone totals
+/// read for dashboards, never used for synchronisation.
+#[derive(Debug, Default)]
+pub(crate) struct ConnectionCounters {
+    pub(crate) bytes_sent: AtomicU64,
+    pub(crate) bytes_recv: AtomicU64,
+    pub(crate) packets_sent: AtomicU64,
+    pub(crate) packets_recv: AtomicU64,
+}
+
+impl ConnectionCounters {
+    pub(crate) fn snapshot(&self) -> ConnectionStats {
+        ConnectionStats {
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_recv: self.bytes_recv.load(Ordering::Relaxed),
+            packets_sent: self.packets_sent.load(Ordering::Relaxed),
+            packets_recv: self.packets_recv.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Point-in-time copy of a connection's traffic totals.
+///
+/// Byte counts are wire bytes: `bytes_sent` is measured on the encoded
+/// (post-compression) blobs handed to the writer task, `bytes_recv` on the
+/// raw bytes read off the socket. Packet counts are logical packets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    pub bytes_sent: u64,
+    pub bytes_recv: u64,
+    pub packets_sent: u64,
+    pub packets_recv: u64,
+}
+
+impl std::ops::Add for ConnectionStats {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            bytes_sent: self.bytes_sent + rhs.bytes_sent,
+            bytes_recv: self.bytes_recv + rhs.bytes_recv,
+            packets_sent: self.packets_sent + rhs.packets_sent,
+            packets_recv: self.packets_recv + rhs.packets_recv,
+        }
+    }
+}
+
 pub static BRIDGE_QUEUE_DEPTH_CRITICAL: AtomicU64 = AtomicU64::new(0);
 pub static BRIDGE_QUEUE_DEPTH_HIGH: AtomicU64 = AtomicU64::new(0);
 pub static BRIDGE_QUEUE_DEPTH_NORMAL: AtomicU64 = AtomicU64::new(0);
@@ -1,16 +1,21 @@
+use crate::metrics::{ConnectionCounters, ConnectionStats};
 use crate::{EngineConnection, ReceivedPacket};
 use bytes::{Bytes, BytesMut};
 use log::{error, warn};
-use mcrs_protocol::{Decode, Encode, Packet, PacketDecoder, PacketEncoder, WritePacket};
+use mcrs_protocol::packets::login::serverbound::ServerboundKey;
+use mcrs_protocol::{
+    CompressionThreshold, Decode, Encode, Packet, PacketDecoder, PacketEncoder, PacketEncryptor,
+    WritePacket,
+};
 use std::io;
 use std::io::ErrorKind;
 use std::net::SocketAddr;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use tokio::io::{AsyncReadExt, AsyncWriteExt, BufWriter};
-use tokio::sync::mpsc;
-use tokio::sync::mpsc::error::TryRecvError;
+use tokio::sync::mpsc::error::{TryRecvError, TrySendError};
+use tokio::sync::{mpsc, oneshot};
 use tokio::task::JoinHandle;
 
 pub(crate) struct PacketIo {
@@ -22,9 +27,50 @@ pub(crate) struct PacketIo {
 
 const READ_BUF_SIZE: usize = 4096;
 
-pub(crate) const OUTBOUND_CHANNEL_CAPACITY: usize = 4;
+/// Flushed blobs the outbound channel buffers before sends report
+/// [`SendError::Full`]. Each blob is one tick's worth of packets, so a client
+/// more than this many ticks behind is backed up. [`RawConnection::send_buffer_pressure`] is
+/// measured against this.
+pub const OUTBOUND_CHANNEL_CAPACITY: usize = 4;
 pub const MAX_QUEUED_BYTES_PER_SOCKET: usize = 4 * 1024 * 1024;
 
+/// The key the client encrypts everything after its Encryption Response
+/// with, handed from [`RawConnection::enable_encryption`] to the reader task.
+/// Armed by [`RawConnection::expect_encryption`].
+type PendingKey = Arc<Mutex<Option<oneshot::Receiver<[u8; 16]>>>>;
+
+/// Which lane of a connection's outbound queue a packet is written to.
+///
+/// Packets keep their order within a lane. On every send the `High` lane goes
+/// out first, ahead of `Normal` packets written earlier and of bytes a full
+/// channel held back, so only use it for packets whose order relative to the
+/// rest doesn't matter, like keep-alives and disconnects. A connection's
+/// state only changes once the client acknowledges the switch, so a `High`
+/// packet never reaches a client that has already left the state it was
+/// written for. Blobs already handed to the writer task are never overtaken.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SendPriority {
+    #[default]
+    Normal,
+    High,
+}
+
+/// Why a packet didn't make it onto the outbound channel.
+#[derive(Debug, thiserror::Error)]
+pub enum SendError {
+    /// The packet failed to serialize. Nothing was written for it, so the
+    /// connection is still usable.
+    #[error("failed to encode packet: {0:#}")]
+    Encode(anyhow::Error),
+    /// The channel is full because the client is reading slowly. The bytes
+    /// are kept and go out ahead of the next send.
+    #[error("outbound channel is full")]
+    Full,
+    /// The writer task has exited; the connection is gone.
+    #[error("connection closed")]
+    Disconnected,
+}
+
 impl PacketIo {
     pub(crate) fn new(stream: tokio::net::TcpStream) -> Self {
         Self {
@@ -74,10 +120,20 @@ impl PacketIo {
         let (incoming_sender, incoming_receiver) = mpsc::channel(256);
         let (outgoing_sender, outgoing_receiver) = mpsc::channel::<Bytes>(OUTBOUND_CHANNEL_CAPACITY);
         let disconnect_flag = Arc::new(AtomicBool::new(false));
+        let counters = Arc::new(ConnectionCounters::default());
+        let compression = Arc::new(AtomicI32::new(CompressionThreshold::DEFAULT.0));
+        let pending_key = PendingKey::default();
 
         let (reader, writer) = self.stream.into_split();
 
-        let reader_task = tokio::spawn(reader_loop(reader, self.dec, incoming_sender));
+        let reader_task = tokio::spawn(reader_loop(
+            reader,
+            self.dec,
+            incoming_sender,
+            counters.clone(),
+            compression.clone(),
+            pending_key.clone(),
+        ));
         let writer_task =
             tokio::spawn(writer_loop(outgoing_receiver, writer, disconnect_flag.clone()));
 
@@ -85,10 +141,21 @@ impl PacketIo {
             outgoing: outgoing_sender,
             recv: incoming_receiver,
             reader_task,
-            writer_task,
+            writer_task: Some(writer_task),
             enc: self.enc,
+            urgent: PacketEncoder::new(),
             remote_addr,
             disconnect_flag,
+            counters,
+            compression,
+            cipher: None,
+            pending_key,
+            key_sender: None,
+            urgent_unsent: None,
+            unsent: None,
+            unsent_is_barrier: false,
+            closing: false,
+            bundling: false,
         }
     }
 }
@@ -97,9 +164,16 @@ async fn reader_loop(
     mut reader: tokio::net::tcp::OwnedReadHalf,
     mut dec: PacketDecoder,
     incoming_sender: mpsc::Sender<ReceivedPacket>,
+    counters: Arc<ConnectionCounters>,
+    compression: Arc<AtomicI32>,
+    pending_key: PendingKey,
 ) {
     let mut buf = BytesMut::new();
     loop {
+        // Picked up before every frame: the client compresses everything it
+        // sends after Set Compression, and the threshold is stored before
+        // that packet is written.
+        dec.set_compression(CompressionThreshold(compression.load(Ordering::Acquire)));
         let frame = match dec.try_next_packet() {
             Ok(Some(frame)) => frame,
             Ok(None) => {
@@ -109,7 +183,9 @@ async fn reader_loop(
                         warn!("Connection closed!");
                         break;
                     }
-                    Ok(_) => {}
+                    Ok(n) => {
+                        counters.bytes_recv.fetch_add(n as u64, Ordering::Relaxed);
+                    }
                     Err(e) => {
                         error!("error reading data from stream: {e}");
                         break;
@@ -126,6 +202,14 @@ async fn reader_loop(
 
         let timestamp = Instant::now();
 
+        // Everything the client sends after its Encryption Response is
+        // encrypted, so nothing more is decoded until the key is known.
+        let key = if frame.id == ServerboundKey::ID {
+            pending_key.lock().unwrap().take()
+        } else {
+            None
+        };
+
         let packet = ReceivedPacket {
             timestamp,
             id: frame.id,
@@ -136,6 +220,16 @@ async fn reader_loop(
             warn!("error sending incoming packet: receiver dropped");
             break;
         }
+
+        if let Some(key) = key {
+            match key.await {
+                Ok(key) => dec.enable_encryption(&key),
+                Err(_) => {
+                    // The response was rejected; the connection is closing.
+                    break;
+                }
+            }
+        }
     }
 }
 
@@ -162,12 +256,38 @@ pub struct RawConnection {
     outgoing: mpsc::Sender<Bytes>,
     recv: mpsc::Receiver<ReceivedPacket>,
     reader_task: JoinHandle<()>,
-    // Held to keep the writer task alive; dropped implicitly when RawConnection is dropped.
-    #[allow(dead_code)]
-    writer_task: JoinHandle<()>,
+    /// Finishes once the outgoing channel is dropped and drained; taken by
+    /// network teardown to wait for it.
+    writer_task: Option<JoinHandle<()>>,
     pub enc: PacketEncoder,
+    /// The [`SendPriority::High`] lane, sent ahead of `enc` and `unsent`.
+    urgent: PacketEncoder,
     pub remote_addr: SocketAddr,
     disconnect_flag: Arc<AtomicBool>,
+    counters: Arc<ConnectionCounters>,
+    /// Threshold the reader task decodes inbound frames with.
+    compression: Arc<AtomicI32>,
+    /// Encrypts every blob handed to the writer task once encryption is
+    /// enabled.
+    cipher: Option<PacketEncryptor>,
+    /// Shared with the reader task; see [`Self::expect_encryption`].
+    pending_key: PendingKey,
+    /// Hands the key to the reader task in [`Self::enable_encryption`].
+    key_sender: Option<oneshot::Sender<[u8; 16]>>,
+    /// [`SendPriority::High`] bytes the channel had no room for, sent before
+    /// newer urgent ones.
+    urgent_unsent: Option<Bytes>,
+    /// Bytes the channel had no room for, sent before anything newer.
+    unsent: Option<Bytes>,
+    /// Set by `set_compression`: `unsent` holds packets framed for the old
+    /// threshold, which urgent packets framed for the new one must not pass.
+    unsent_is_barrier: bool,
+    closing: bool,
+    /// Set while a [`ServerSideConnection::bundle`] is open; `flush` holds
+    /// the bytes back so the bundle never goes out half-written.
+    ///
+    /// [`ServerSideConnection::bundle`]: crate::ServerSideConnection::bundle
+    bundling: bool,
 }
 
 impl Drop for RawConnection {
@@ -201,10 +321,21 @@ impl RawConnection {
             outgoing,
             recv: inbound_rx,
             reader_task,
-            writer_task,
+            writer_task: Some(writer_task),
             enc: PacketEncoder::new(),
+            urgent: PacketEncoder::new(),
             remote_addr: addr,
             disconnect_flag,
+            counters: Arc::default(),
+            compression: Arc::new(AtomicI32::new(CompressionThreshold::DEFAULT.0)),
+            cipher: None,
+            pending_key: PendingKey::default(),
+            key_sender: None,
+            urgent_unsent: None,
+            unsent: None,
+            unsent_is_barrier: false,
+            closing: false,
+            bundling: false,
         }
     }
 
@@ -233,18 +364,96 @@ impl RawConnection {
             outgoing: outgoing_tx,
             recv: inbound_rx,
             reader_task,
-            writer_task,
+            writer_task: Some(writer_task),
             enc: PacketEncoder::new(),
+            urgent: PacketEncoder::new(),
             remote_addr: addr,
             disconnect_flag,
+            counters: Arc::default(),
+            compression: Arc::new(AtomicI32::new(CompressionThreshold::DEFAULT.0)),
+            cipher: None,
+            pending_key: PendingKey::default(),
+            key_sender: None,
+            urgent_unsent: None,
+            unsent: None,
+            unsent_is_barrier: false,
+            closing: false,
+            bundling: false,
         };
         (raw, outgoing_rx, inbound_tx)
     }
 
-    /// Returns `true` if the blob was accepted, `false` if the channel is full or closed.
-    /// The `false` case is the backpressure signal consumed by the bridge dispatch system.
-    pub fn try_send_blob(&self, blob: Bytes) -> bool {
-        self.outgoing.try_send(blob).is_ok()
+    /// Queue `blob` for the writer task. Packets written with
+    /// [`SendPriority::High`] go first, older ones before newer, then bytes
+    /// left over from an earlier [`SendError::Full`], then `blob`.
+    ///
+    /// On `Full` the bytes are kept for the next call, so a slow client
+    /// loses nothing; the bridge dispatch system treats it as backpressure.
+    /// `Disconnected` means the writer is gone and the connection should be
+    /// dropped.
+    pub fn try_send_blob(&mut self, blob: Bytes) -> Result<(), SendError> {
+        let urgent_unsent = self.urgent_unsent.take().unwrap_or_default();
+        let urgent = self.urgent.take().freeze();
+        let unsent = self.unsent.take().unwrap_or_default();
+        // Behind a compression barrier everything stays in write order.
+        let urgent_len = if self.unsent_is_barrier {
+            0
+        } else {
+            urgent_unsent.len() + urgent.len()
+        };
+        let parts = if self.unsent_is_barrier {
+            [unsent, urgent_unsent, urgent, blob]
+        } else {
+            [urgent_unsent, urgent, unsent, blob]
+        };
+        let blob = match parts.iter().filter(|part| !part.is_empty()).count() {
+            0 => return Ok(()),
+            1 => parts.into_iter().find(|part| !part.is_empty()).unwrap(),
+            _ => {
+                let mut joined = BytesMut::with_capacity(parts.iter().map(Bytes::len).sum());
+                for part in &parts {
+                    joined.extend_from_slice(part);
+                }
+                joined.freeze()
+            }
+        };
+        let len = blob.len() as u64;
+        // The cipher only moves on once the bytes are handed over; a full
+        // channel keeps them in plain text to be joined with the next send.
+        let (wire, cipher) = match &self.cipher {
+            Some(cipher) => {
+                let mut cipher = cipher.clone();
+                let mut wire = BytesMut::from(&blob[..]);
+                cipher.encrypt(&mut wire);
+                (wire.freeze(), Some(cipher))
+            }
+            None => (blob.clone(), None),
+        };
+        match self.outgoing.try_send(wire) {
+            Ok(()) => {
+                self.counters.bytes_sent.fetch_add(len, Ordering::Relaxed);
+                self.unsent_is_barrier = false;
+                if let Some(cipher) = cipher {
+                    self.cipher = Some(cipher);
+                }
+                Ok(())
+            }
+            Err(TrySendError::Full(_)) => {
+                let (urgent, rest) = (blob.slice(..urgent_len), blob.slice(urgent_len..));
+                self.urgent_unsent = (!urgent.is_empty()).then_some(urgent);
+                self.unsent = (!rest.is_empty()).then_some(rest);
+                Err(SendError::Full)
+            }
+            Err(TrySendError::Closed(_)) => Err(SendError::Disconnected),
+        }
+    }
+
+    /// Outbound channel occupancy, from 0.0 (empty) to 1.0 (full), out of
+    /// [`OUTBOUND_CHANNEL_CAPACITY`] blobs for real sockets. Lets senders of
+    /// bulk data back off before `flush` fails.
+    pub fn send_buffer_pressure(&self) -> f32 {
+        let max = self.outgoing.max_capacity();
+        (max - self.outgoing.capacity()) as f32 / max as f32
     }
 
     pub fn take_encoded(&mut self) -> Bytes {
@@ -255,36 +464,185 @@ impl RawConnection {
         self.disconnect_flag.load(Ordering::Relaxed)
     }
 
-    pub fn append<P: Encode + Packet>(&mut self, pkt: &P) -> anyhow::Result<()> {
-        self.enc.append_packet(pkt)
+    /// Mark the connection for teardown. The owning entity is despawned by
+    /// the network plugin at the start of the next fixed tick.
+    pub fn close(&mut self) {
+        self.closing = true;
+    }
+
+    pub fn is_closing(&self) -> bool {
+        self.closing
+    }
+
+    /// `true` while a [`ServerSideConnection::bundle`] is open.
+    ///
+    /// [`ServerSideConnection::bundle`]: crate::ServerSideConnection::bundle
+    pub fn is_bundling(&self) -> bool {
+        self.bundling
+    }
+
+    pub(crate) fn set_bundling(&mut self, bundling: bool) {
+        self.bundling = bundling;
+    }
+
+    /// Take the writer task's handle. The task exits once this connection is
+    /// dropped and everything already queued has been written.
+    pub(crate) fn take_writer_task(&mut self) -> Option<JoinHandle<()>> {
+        self.writer_task.take()
+    }
+
+    /// Compress packets at or above `threshold` bytes in both directions.
+    /// Packets already written stay uncompressed, so send Set Compression
+    /// first and call this right after it.
+    pub fn set_compression(&mut self, threshold: CompressionThreshold) {
+        // Everything written so far is framed for the old threshold and must
+        // reach the client before any packet framed for the new one.
+        let mut pending = BytesMut::new();
+        if let Some(urgent_unsent) = self.urgent_unsent.take() {
+            pending.extend_from_slice(&urgent_unsent);
+        }
+        pending.extend_from_slice(&self.urgent.take());
+        if let Some(unsent) = self.unsent.take() {
+            pending.extend_from_slice(&unsent);
+        }
+        pending.extend_from_slice(&self.enc.take());
+        if !pending.is_empty() {
+            self.unsent = Some(pending.freeze());
+            self.unsent_is_barrier = true;
+        }
+        self.enc.set_compression(threshold);
+        self.urgent.set_compression(threshold);
+        self.compression.store(threshold.0, Ordering::Release);
+    }
+
+    /// Get ready for the client's Encryption Response: call it when writing
+    /// Encryption Request. The reader task stops after that response until
+    /// [`enable_encryption`](Self::enable_encryption) hands it the key, and
+    /// ends the connection if the key never comes.
+    pub fn expect_encryption(&mut self) {
+        let (sender, receiver) = oneshot::channel();
+        *self.pending_key.lock().unwrap() = Some(receiver);
+        self.key_sender = Some(sender);
+    }
+
+    /// Encrypt the connection in both directions with the shared secret
+    /// `key`. Everything not yet handed to the writer task is encrypted,
+    /// including packets written before the call, as in
+    /// [`PacketEncoder::enable_encryption`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if encryption is already enabled.
+    pub fn enable_encryption(&mut self, key: &[u8; 16]) {
+        assert!(self.cipher.is_none(), "encryption is already enabled");
+        self.cipher = Some(PacketEncryptor::new(key));
+        if let Some(sender) = self.key_sender.take() {
+            // The reader task is gone if this fails; the connection is closing.
+            let _ = sender.send(*key);
+        }
+    }
+
+    pub fn append<P: Encode + Packet>(&mut self, pkt: &P) -> Result<(), SendError> {
+        self.enc.append_packet(pkt).map_err(SendError::Encode)?;
+        self.counters.packets_sent.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Write `packet` to the lane for `priority`. [`WritePacket`] writes go
+    /// to [`SendPriority::Normal`].
+    pub fn write_packet_with_priority<P>(&mut self, packet: &P, priority: SendPriority)
+    where
+        P: Encode + Packet,
+    {
+        match priority {
+            SendPriority::Normal => self.write_packet(packet),
+            SendPriority::High => match self.urgent.append_packet(packet) {
+                Ok(()) => {
+                    self.counters.packets_sent.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(e) => warn!("failed to write packet '{}': {e:#}", P::NAME),
+            },
+        }
+    }
+
+    /// Traffic totals since the connection was created.
+    pub fn stats(&self) -> ConnectionStats {
+        self.counters.snapshot()
+    }
+
+    /// Every packet received so far, in arrival order. Check
+    /// [`ReceivedPackets::disconnected`] once the iterator is exhausted.
+    pub fn drain_received(&mut self) -> ReceivedPackets<'_> {
+        ReceivedPackets {
+            connection: self,
+            disconnected: false,
+        }
+    }
+}
+
+/// Iterator returned by [`RawConnection::drain_received`]. Ends when nothing
+/// more is queued or the reader task has gone away.
+pub struct ReceivedPackets<'a> {
+    connection: &'a mut RawConnection,
+    disconnected: bool,
+}
+
+impl ReceivedPackets<'_> {
+    /// `true` if iteration ended because the client disconnected rather than
+    /// because the queue ran dry. The connection should be despawned.
+    pub fn disconnected(&self) -> bool {
+        self.disconnected
+    }
+}
+
+impl Iterator for ReceivedPackets<'_> {
+    type Item = ReceivedPacket;
+
+    fn next(&mut self) -> Option<ReceivedPacket> {
+        if self.disconnected {
+            return None;
+        }
+        match self.connection.try_recv() {
+            Ok(packet) => packet,
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.disconnected = true;
+                None
+            }
+        }
     }
 }
 
 impl EngineConnection for RawConnection {
     fn try_recv(&mut self) -> Result<Option<ReceivedPacket>, TryRecvError> {
         match self.recv.try_recv() {
-            Ok(packet) => Ok(Some(packet)),
+            Ok(packet) => {
+                self.counters.packets_recv.fetch_add(1, Ordering::Relaxed);
+                Ok(Some(packet))
+            }
             Err(TryRecvError::Empty) => Ok(None),
             Err(TryRecvError::Disconnected) => Err(TryRecvError::Disconnected),
         }
     }
 
-    fn flush(&mut self) -> anyhow::Result<()> {
-        let bytes = self.enc.take();
-        if bytes.is_empty() {
+    /// Packets written since the last flush sit in the encoder's buffer;
+    /// they leave as a single contiguous blob, one channel send and one
+    /// socket write per tick rather than one per packet.
+    fn flush(&mut self) -> Result<(), SendError> {
+        if self.bundling {
             return Ok(());
         }
-        let blob = bytes.freeze();
-        self.outgoing
-            .try_send(blob)
-            .map_err(|_| anyhow::anyhow!("connection closed"))
+        let blob = self.enc.take().freeze();
+        self.try_send_blob(blob)
     }
 
+    /// Bytes held back by a full channel, waiting for the next send.
     fn queued_bytes(&self) -> usize {
-        // Depth is tracked in ECS via OutboundQueue; the cross-thread atomic
-        // was removed (AP-03). Return 0 so the handshake/login flush path
-        // still compiles without breaking the EngineConnection contract.
-        0
+        [&self.urgent_unsent, &self.unsent]
+            .into_iter()
+            .flatten()
+            .map(Bytes::len)
+            .sum()
     }
 }
 
@@ -293,10 +651,13 @@ impl WritePacket for RawConnection {
     where
         P: Encode + Packet,
     {
-        self.enc.write_packet_fallible(packet)
+        self.enc.write_packet_fallible(packet)?;
+        self.counters.packets_sent.fetch_add(1, Ordering::Relaxed);
+        Ok(())
     }
 
     fn write_packet_bytes(&mut self, bytes: &[u8]) {
-        self.enc.write_packet_bytes(bytes)
+        self.enc.write_packet_bytes(bytes);
+        self.counters.packets_sent.fetch_add(1, Ordering::Relaxed);
     }
 }
@@ -1,7 +1,9 @@
 use mcrs_network::connect::{
-    AcceptOutcome, ACCEPT_BUCKET_CAP, ACCEPT_REFILL_PER_SEC, GLOBAL_HANDSHAKE_CAP, TokenBucket,
-    accept_decision,
+    ACCEPT_BUCKET_CAP, ACCEPT_BUCKET_WINDOW, ACCEPT_REFILL_PER_SEC, AcceptOutcome,
+    GLOBAL_HANDSHAKE_CAP, PerIpBuckets, TokenBucket, accept_decision,
 };
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Instant;
 
 /// Verifies that a bucket with cap 5 allows exactly 5 consecutive accepts in
 /// a tight loop (no real elapsed time, so no refill occurs) and then rejects.
@@ -24,18 +26,44 @@ fn accept_rate_limit() {
 #[test]
 fn global_handshake_cap() {
     // At exactly the cap: should be rejected
-    let mut bucket_full = TokenBucket::new(ACCEPT_BUCKET_CAP);
+    let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    let now = Instant::now();
+    let mut buckets_full = PerIpBuckets::new(ACCEPT_BUCKET_CAP, ACCEPT_BUCKET_WINDOW);
     assert_eq!(
-        accept_decision(&mut bucket_full, GLOBAL_HANDSHAKE_CAP),
+        accept_decision(&mut buckets_full, ip, now, GLOBAL_HANDSHAKE_CAP),
         AcceptOutcome::CapExceeded,
         "expected CapExceeded when inflight == GLOBAL_HANDSHAKE_CAP"
     );
 
     // One below the cap: should be accepted
-    let mut bucket_ok = TokenBucket::new(ACCEPT_BUCKET_CAP);
+    let mut buckets_ok = PerIpBuckets::new(ACCEPT_BUCKET_CAP, ACCEPT_BUCKET_WINDOW);
     assert_eq!(
-        accept_decision(&mut bucket_ok, GLOBAL_HANDSHAKE_CAP - 1),
+        accept_decision(&mut buckets_ok, ip, now, GLOBAL_HANDSHAKE_CAP - 1),
         AcceptOutcome::Accept,
         "expected Accept when inflight == GLOBAL_HANDSHAKE_CAP - 1"
     );
 }
+
+/// A connection refused for the global cap leaves the IP's bucket untouched.
+#[test]
+fn global_cap_rejection_keeps_the_token() {
+    let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    let now = Instant::now();
+    let mut buckets = PerIpBuckets::new(ACCEPT_BUCKET_CAP, ACCEPT_BUCKET_WINDOW);
+    for _ in 0..10 {
+        assert_eq!(
+            accept_decision(&mut buckets, ip, now, GLOBAL_HANDSHAKE_CAP),
+            AcceptOutcome::CapExceeded
+        );
+    }
+    for _ in 0..ACCEPT_BUCKET_CAP {
+        assert_eq!(
+            accept_decision(&mut buckets, ip, now, 0),
+            AcceptOutcome::Accept
+        );
+    }
+    assert_eq!(
+        accept_decision(&mut buckets, ip, now, 0),
+        AcceptOutcome::RateLimited
+    );
+}
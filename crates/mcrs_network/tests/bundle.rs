@@ -0,0 +1,48 @@
+mod common;
+
+use common::mock_connection::test_runtime;
+use mcrs_network::{EngineConnection, RawConnection, ServerSideConnection};
+use mcrs_protocol::packets::game::clientbound::ClientboundBundleDelimiter;
+use mcrs_protocol::packets::ping::clientbound::PongResponse;
+use mcrs_protocol::{Packet, PacketDecoder, WritePacket};
+
+/// A bundle brackets its packets with delimiters, and a flush from inside it
+/// sends nothing until the closing delimiter is written.
+#[test]
+fn bundle_brackets_inner_packets_with_delimiters() {
+    let rt = test_runtime();
+    let (raw, mut outgoing_rx, _inbound_tx) =
+        rt.block_on(async { RawConnection::new_for_test_full(16) });
+    let mut conn = ServerSideConnection { raw: Box::new(raw) };
+
+    conn.write_packet(&PongResponse { payload: 0 });
+    conn.bundle(|conn| {
+        conn.write_packet(&PongResponse { payload: 1 });
+        conn.flush().expect("flush");
+        // A nested bundle joins the open one instead of adding delimiters.
+        conn.bundle(|conn| conn.write_packet(&PongResponse { payload: 2 }));
+    });
+    assert!(
+        outgoing_rx.try_recv().is_err(),
+        "nothing is sent while the bundle is open"
+    );
+    conn.write_packet(&PongResponse { payload: 3 });
+    conn.flush().expect("flush");
+
+    let blob = outgoing_rx.try_recv().expect("one combined blob");
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(blob.into());
+    let mut frames = Vec::new();
+    while let Some(frame) = decoder.try_next_packet().unwrap() {
+        frames.push(if frame.id == ClientboundBundleDelimiter::ID {
+            None
+        } else {
+            Some(frame.decode::<PongResponse>().unwrap().payload)
+        });
+    }
+    assert_eq!(
+        frames,
+        [Some(0), None, Some(1), Some(2), None, Some(3)],
+        "delimiters around the bundled packets only"
+    );
+}
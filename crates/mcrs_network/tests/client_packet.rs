@@ -0,0 +1,112 @@
+mod common;
+
+use bevy_ecs::entity::Entity;
+use bevy_ecs::message::Messages;
+use bevy_ecs::world::World;
+use common::mock_connection::test_runtime;
+use mcrs_network::event::{
+    ClientPacket, ReceivedPacketEvent, ServerboundPacket, emit_client_packet,
+};
+use mcrs_network::{ConnectionState, RawConnection, ServerSideConnection};
+use mcrs_protocol::packets::common::serverbound::KeepAlive;
+use mcrs_protocol::packets::configuration::serverbound::{
+    self as configuration, ServerboundKeepAlive,
+};
+use mcrs_protocol::packets::game::serverbound::{self as game, ServerboundChangeDifficulty};
+use mcrs_protocol::{Difficulty, Encode};
+use std::time::Instant;
+
+fn world_with_connection(state: ConnectionState) -> (World, Entity) {
+    let mut world = World::new();
+    world.init_resource::<Messages<ClientPacket>>();
+    world.add_observer(emit_client_packet);
+    let (raw, _outgoing_rx, _inbound_tx) =
+        test_runtime().block_on(async { RawConnection::new_for_test_full(16) });
+    let entity = world
+        .spawn((ServerSideConnection { raw: Box::new(raw) }, state))
+        .id();
+    (world, entity)
+}
+
+fn receive(world: &mut World, entity: Entity, id: i32, body: impl Encode) {
+    let mut data = Vec::new();
+    body.encode(&mut data).unwrap();
+    world.trigger(ReceivedPacketEvent {
+        entity,
+        id,
+        data: data.into(),
+        timestamp: Instant::now(),
+    });
+    world.flush();
+}
+
+fn sent(world: &mut World) -> Vec<ClientPacket> {
+    world
+        .resource_mut::<Messages<ClientPacket>>()
+        .drain()
+        .collect()
+}
+
+fn is_closing(world: &World, entity: Entity) -> bool {
+    world
+        .get::<ServerSideConnection>(entity)
+        .unwrap()
+        .is_closing()
+}
+
+/// Id 0x04 is a keep-alive in Configuration but a difficulty change in Game.
+#[test]
+fn same_id_decodes_per_state() {
+    let (mut world, entity) = world_with_connection(ConnectionState::Configuration);
+    receive(
+        &mut world,
+        entity,
+        0x04,
+        ServerboundKeepAlive(KeepAlive { payload: 7 }),
+    );
+    let packets = sent(&mut world);
+    assert_eq!(packets.len(), 1);
+    assert!(matches!(
+        packets[0].packet(),
+        ServerboundPacket::Configuration(configuration::Packet::KeepAlive(ServerboundKeepAlive(
+            KeepAlive { payload: 7 }
+        )))
+    ));
+
+    let (mut world, entity) = world_with_connection(ConnectionState::Game);
+    receive(
+        &mut world,
+        entity,
+        0x04,
+        ServerboundChangeDifficulty {
+            difficulty: Difficulty::Hard,
+        },
+    );
+    let packets = sent(&mut world);
+    assert_eq!(packets.len(), 1);
+    assert!(matches!(
+        packets[0].packet(),
+        ServerboundPacket::Game(game::Packet::ChangeDifficulty(
+            ServerboundChangeDifficulty {
+                difficulty: Difficulty::Hard
+            }
+        ))
+    ));
+}
+
+#[test]
+fn malformed_packet_disconnects() {
+    let (mut world, entity) = world_with_connection(ConnectionState::Game);
+    // A keep-alive carries an i64; two bytes can't decode.
+    receive(&mut world, entity, 0x1C, [0u8; 2]);
+    assert!(sent(&mut world).is_empty());
+    assert!(is_closing(&world, entity));
+}
+
+#[test]
+fn unknown_id_is_ignored() {
+    let (mut world, entity) = world_with_connection(ConnectionState::Game);
+    receive(&mut world, entity, 0x7F, [0u8; 4]);
+    assert!(sent(&mut world).is_empty());
+    assert!(!is_closing(&world, entity));
+}
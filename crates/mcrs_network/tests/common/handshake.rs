@@ -0,0 +1,48 @@
+use mcrs_network::NetworkConfig;
+use mcrs_network::intent::{HandshakeOutcome, serve_handshake};
+use mcrs_protocol::handshake::Intent;
+use mcrs_protocol::packets::intent::serverbound::ServerboundHandshake;
+use mcrs_protocol::{Bounded, PROTOCOL_VERSION, PacketDecoder, PacketEncoder, VarInt};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// A handshake for `intent` from a client speaking the server's protocol.
+pub fn handshake(intent: Intent) -> ServerboundHandshake<'static> {
+    handshake_with_protocol(intent, PROTOCOL_VERSION)
+}
+
+pub fn handshake_with_protocol(
+    intent: Intent,
+    protocol_version: i32,
+) -> ServerboundHandshake<'static> {
+    ServerboundHandshake {
+        protocol_version: VarInt(protocol_version),
+        server_address: Bounded("localhost"),
+        server_port: 25565,
+        intent,
+    }
+}
+
+/// Connect a client to a loopback listener, send it everything in `encoder`,
+/// serve the handshake on the server side, and return the outcome with every
+/// packet the client was sent before the server closed the socket.
+pub async fn serve(
+    encoder: &mut PacketEncoder,
+    config: &NetworkConfig,
+) -> (HandshakeOutcome, PacketDecoder) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap())
+        .await
+        .unwrap();
+    client.write_all(&encoder.take()).await.unwrap();
+    let (server, remote_addr) = listener.accept().await.unwrap();
+
+    let outcome = serve_handshake(server, remote_addr, config).await.unwrap();
+    let mut received = Vec::new();
+    if !matches!(outcome, HandshakeOutcome::Login(_)) {
+        client.read_to_end(&mut received).await.unwrap();
+    }
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_slice(&received);
+    (outcome, decoder)
+}
@@ -25,3 +25,18 @@ pub fn make_mock_sink(capacity: usize) -> (MockSink, mpsc::Receiver<Bytes>) {
     let (tx, rx) = mpsc::channel(capacity);
     (MockSink { tx }, rx)
 }
+
+/// Process-global runtime for tests that build a `RawConnection` mock; the
+/// mock constructors spawn placeholder tasks and need a tokio context.
+pub fn test_runtime() -> &'static tokio::runtime::Runtime {
+    use std::sync::OnceLock;
+
+    static TEST_RT: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    TEST_RT.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .expect("test tokio runtime")
+    })
+}
@@ -1 +1,2 @@
+pub mod handshake;
 pub mod mock_connection;
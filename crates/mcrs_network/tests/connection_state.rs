@@ -0,0 +1,54 @@
+use bevy_ecs::world::World;
+use mcrs_network::{ConnectionState, transition_connection_state};
+
+#[test]
+fn vanilla_flow_transitions_are_legal() {
+    use ConnectionState::*;
+    assert!(Login.can_transition_to(Configuration));
+    assert!(Configuration.can_transition_to(Game));
+    assert!(Game.can_transition_to(Configuration));
+}
+
+#[test]
+fn skipping_or_reversing_the_flow_is_illegal() {
+    use ConnectionState::*;
+    assert!(!Login.can_transition_to(Game));
+    assert!(!Configuration.can_transition_to(Login));
+    assert!(!Game.can_transition_to(Login));
+    assert!(!Game.can_transition_to(Game));
+}
+
+#[test]
+fn illegal_transition_leaves_state_unchanged() {
+    let mut state = ConnectionState::Login;
+    assert!(!state.transition_to(ConnectionState::Game));
+    assert_eq!(state, ConnectionState::Login);
+    assert!(state.transition_to(ConnectionState::Configuration));
+    assert_eq!(state, ConnectionState::Configuration);
+}
+
+#[test]
+fn transition_command_validates_against_current_state() {
+    let mut world = World::new();
+    let entity = world.spawn(ConnectionState::Login).id();
+
+    world
+        .commands()
+        .entity(entity)
+        .queue(transition_connection_state(ConnectionState::Game));
+    world.flush();
+    assert_eq!(
+        *world.get::<ConnectionState>(entity).unwrap(),
+        ConnectionState::Login
+    );
+
+    world
+        .commands()
+        .entity(entity)
+        .queue(transition_connection_state(ConnectionState::Configuration));
+    world.flush();
+    assert_eq!(
+        *world.get::<ConnectionState>(entity).unwrap(),
+        ConnectionState::Configuration
+    );
+}
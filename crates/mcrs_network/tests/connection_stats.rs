@@ -0,0 +1,49 @@
+mod common;
+
+use bytes::Bytes;
+use common::mock_connection::test_runtime;
+use mcrs_network::{ConnectionStats, EngineConnection, RawConnection, ReceivedPacket};
+use mcrs_protocol::WritePacket;
+use mcrs_protocol::packets::ping::clientbound::PongResponse;
+use std::time::Instant;
+
+#[test]
+fn counters_track_sent_and_received_traffic() {
+    let rt = test_runtime();
+    let (mut raw, mut outgoing_rx, inbound_tx) =
+        rt.block_on(async { RawConnection::new_for_test_full(16) });
+
+    assert_eq!(raw.stats(), ConnectionStats::default());
+
+    raw.write_packet(&PongResponse { payload: 1 });
+    raw.write_packet(&PongResponse { payload: 2 });
+    raw.flush().expect("flush");
+
+    let blob = outgoing_rx.try_recv().expect("flushed blob");
+    let stats = raw.stats();
+    assert_eq!(stats.packets_sent, 2);
+    assert_eq!(stats.bytes_sent, blob.len() as u64);
+
+    for id in 0..3 {
+        inbound_tx
+            .try_send(ReceivedPacket {
+                timestamp: Instant::now(),
+                id,
+                payload: Bytes::new(),
+            })
+            .unwrap();
+    }
+    while raw.try_recv().unwrap().is_some() {}
+    assert_eq!(raw.stats().packets_recv, 3);
+}
+
+#[test]
+fn blob_bytes_count_only_when_accepted() {
+    let rt = test_runtime();
+    let (mut raw, _outgoing_rx, _inbound_tx) =
+        rt.block_on(async { RawConnection::new_for_test_full(1) });
+
+    assert!(raw.try_send_blob(Bytes::from_static(&[0u8; 32])).is_ok());
+    assert!(raw.try_send_blob(Bytes::from_static(&[0u8; 64])).is_err());
+    assert_eq!(raw.stats().bytes_sent, 32);
+}
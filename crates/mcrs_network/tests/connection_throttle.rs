@@ -0,0 +1,74 @@
+use mcrs_network::NetworkConfig;
+use mcrs_network::connect::PerIpBuckets;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+
+fn default_buckets() -> (PerIpBuckets, u32, Duration) {
+    let config = NetworkConfig::default();
+    (
+        PerIpBuckets::from_config(&config),
+        config.connection_throttle,
+        config.connection_throttle_window,
+    )
+}
+
+/// A burst from one IP is cut off at the limit, while a second IP connecting
+/// during the same burst is still accepted.
+#[test]
+fn burst_from_one_ip_is_partially_rejected() {
+    let (mut buckets, limit, _) = default_buckets();
+    let flooder = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+    let other = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+    let now = Instant::now();
+
+    let accepted = (0..limit + 5)
+        .filter(|i| buckets.try_accept(flooder, now + Duration::from_millis(*i as u64)))
+        .count();
+    assert_eq!(accepted, limit as usize);
+
+    assert!(buckets.try_accept(other, now + Duration::from_millis(10)));
+}
+
+/// A throttled IP gets a connection back each `window / limit`, and the whole
+/// burst back after the window.
+#[test]
+fn throttle_refills_over_window() {
+    let (mut buckets, limit, window) = default_buckets();
+    let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    let now = Instant::now();
+    let per_token = window / limit;
+
+    for _ in 0..limit {
+        assert!(buckets.try_accept(ip, now));
+    }
+    assert!(!buckets.try_accept(ip, now + per_token - Duration::from_millis(1)));
+    assert!(buckets.try_accept(ip, now + per_token));
+    assert!(!buckets.try_accept(ip, now + per_token));
+
+    let later = now + per_token + window;
+    for _ in 0..limit {
+        assert!(buckets.try_accept(ip, later));
+    }
+    assert!(!buckets.try_accept(ip, later));
+}
+
+/// Rejected attempts don't count: a client retrying faster than the refill
+/// still gets in as soon as a token is due.
+#[test]
+fn retrying_client_is_not_locked_out() {
+    let (mut buckets, limit, window) = default_buckets();
+    let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    let now = Instant::now();
+    let per_token = window / limit;
+
+    for _ in 0..limit {
+        assert!(buckets.try_accept(ip, now));
+    }
+    let retry_every = per_token / 10;
+    let mut at = now;
+    while at < now + per_token - retry_every {
+        at += retry_every;
+        assert!(!buckets.try_accept(ip, at));
+    }
+    assert!(buckets.try_accept(ip, now + per_token + retry_every));
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::mock_connection::test_runtime;
+use mcrs_network::{ConnectionState, RawConnection, ServerSideConnection};
+use mcrs_protocol::packets::configuration::clientbound::ClientboundDisconnect as ConfigurationDisconnect;
+use mcrs_protocol::packets::game::clientbound::ClientboundDisconnect as GameDisconnect;
+use mcrs_protocol::packets::login::clientbound::ClientboundLoginDisconnect;
+use mcrs_protocol::{Packet, PacketDecoder, Text};
+
+fn disconnect_frame(
+    state: ConnectionState,
+) -> (ServerSideConnection, mcrs_protocol::decode::PacketFrame) {
+    let rt = test_runtime();
+    let (raw, mut outgoing_rx, _inbound_tx) =
+        rt.block_on(async { RawConnection::new_for_test_full(16) });
+    let mut conn = ServerSideConnection { raw: Box::new(raw) };
+
+    conn.disconnect(state, Text::text("Server closed"));
+
+    let blob = outgoing_rx
+        .try_recv()
+        .expect("disconnect is flushed immediately");
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(blob.into());
+    let frame = decoder.try_next_packet().unwrap().expect("one packet");
+    assert!(decoder.try_next_packet().unwrap().is_none());
+    (conn, frame)
+}
+
+#[test]
+fn login_disconnect_sends_json_reason() {
+    let (conn, frame) = disconnect_frame(ConnectionState::Login);
+    assert_eq!(frame.id, ClientboundLoginDisconnect::ID);
+
+    let packet = frame.decode::<ClientboundLoginDisconnect>().unwrap();
+    let reason: Text = serde_json::from_str(packet.reason.0).unwrap();
+    assert_eq!(reason, Text::text("Server closed"));
+    assert!(conn.is_closing());
+}
+
+#[test]
+fn configuration_disconnect_uses_configuration_packet() {
+    let (conn, frame) = disconnect_frame(ConnectionState::Configuration);
+    assert_eq!(frame.id, ConfigurationDisconnect::ID);
+
+    let packet = frame.decode::<ConfigurationDisconnect>().unwrap();
+    assert_eq!(packet.reason, Text::text("Server closed"));
+    assert!(conn.is_closing());
+}
+
+#[test]
+fn game_disconnect_uses_game_packet() {
+    let (conn, frame) = disconnect_frame(ConnectionState::Game);
+    assert_eq!(frame.id, GameDisconnect::ID);
+
+    let packet = frame.decode::<GameDisconnect>().unwrap();
+    assert_eq!(packet.reason, Text::text("Server closed"));
+    assert!(conn.is_closing());
+}
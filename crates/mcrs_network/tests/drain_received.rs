@@ -0,0 +1,49 @@
+mod common;
+
+use bytes::Bytes;
+use common::mock_connection::test_runtime;
+use mcrs_network::{RawConnection, ReceivedPacket, ServerSideConnection};
+use std::time::Instant;
+
+fn packet(id: i32) -> ReceivedPacket {
+    ReceivedPacket {
+        timestamp: Instant::now(),
+        id,
+        payload: Bytes::from(vec![id as u8]),
+    }
+}
+
+#[test]
+fn drains_queued_packets_in_order() {
+    let (raw, _outgoing_rx, inbound_tx) =
+        test_runtime().block_on(async { RawConnection::new_for_test_full(16) });
+    let mut conn = ServerSideConnection { raw: Box::new(raw) };
+    for id in [3, 1, 2] {
+        inbound_tx.try_send(packet(id)).unwrap();
+    }
+
+    let mut received = conn.drain_received();
+    let ids: Vec<i32> = received.by_ref().map(|pkt| pkt.id).collect();
+    assert_eq!(ids, [3, 1, 2]);
+    assert!(!received.disconnected());
+
+    // Nothing new arrived, so the next tick drains nothing.
+    let mut received = conn.drain_received();
+    assert!(received.next().is_none());
+    assert!(!received.disconnected());
+    assert_eq!(conn.stats().packets_recv, 3);
+}
+
+#[test]
+fn disconnect_surfaces_after_remaining_packets() {
+    let (raw, _outgoing_rx, inbound_tx) =
+        test_runtime().block_on(async { RawConnection::new_for_test_full(16) });
+    let mut conn = ServerSideConnection { raw: Box::new(raw) };
+    inbound_tx.try_send(packet(7)).unwrap();
+    drop(inbound_tx);
+
+    let mut received = conn.drain_received();
+    assert_eq!(received.next().map(|pkt| pkt.id), Some(7));
+    assert!(received.next().is_none());
+    assert!(received.disconnected());
+}
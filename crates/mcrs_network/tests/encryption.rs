@@ -0,0 +1,115 @@
+mod common;
+
+use std::time::Duration;
+
+use common::handshake::handshake;
+use common::mock_connection::test_runtime;
+use mcrs_network::intent::{HandshakeOutcome, serve_handshake};
+use mcrs_network::{EngineConnection, NetworkConfig, RawConnection, SendError};
+use mcrs_protocol::handshake::Intent;
+use mcrs_protocol::packets::login::serverbound::{ServerboundHello, ServerboundKey};
+use mcrs_protocol::packets::ping::clientbound::PongResponse;
+use mcrs_protocol::uuid::Uuid;
+use mcrs_protocol::{Bounded, Decode, Packet, PacketDecoder, PacketEncoder, WritePacket};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+
+const KEY: [u8; 16] = [9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 1, 2, 3, 4, 5, 6];
+
+fn payloads(decoder: &mut PacketDecoder) -> Vec<u64> {
+    let mut payloads = Vec::new();
+    while let Some(frame) = decoder.try_next_packet().unwrap() {
+        payloads.push(frame.decode::<PongResponse>().unwrap().payload);
+    }
+    payloads
+}
+
+/// Packets flushed before encryption is enabled go out in plain text, the
+/// rest as one encrypted stream, including bytes a full channel held back.
+#[test]
+fn outbound_stream_is_encrypted_from_the_key_on() {
+    let (mut raw, mut outgoing_rx, _inbound_tx) =
+        test_runtime().block_on(async { RawConnection::new_for_test_full(1) });
+    let mut decoder = PacketDecoder::new();
+
+    raw.write_packet(&PongResponse { payload: 0 });
+    raw.flush().unwrap();
+    decoder.queue_bytes(outgoing_rx.try_recv().unwrap().into());
+    assert_eq!(payloads(&mut decoder), [0]);
+
+    raw.enable_encryption(&KEY);
+    decoder.enable_encryption(&KEY);
+    raw.write_packet(&PongResponse { payload: 1 });
+    raw.flush().unwrap();
+    raw.write_packet(&PongResponse { payload: 2 });
+    assert!(matches!(raw.flush(), Err(SendError::Full)));
+    decoder.queue_bytes(outgoing_rx.try_recv().unwrap().into());
+    assert_eq!(payloads(&mut decoder), [1]);
+
+    raw.write_packet(&PongResponse { payload: 3 });
+    raw.flush().unwrap();
+    decoder.queue_bytes(outgoing_rx.try_recv().unwrap().into());
+    assert_eq!(payloads(&mut decoder), [2, 3]);
+}
+
+/// The reader stops at Encryption Response and decrypts everything after it
+/// once the key is handed over, even bytes that arrived in the same read.
+#[test]
+fn inbound_stream_is_decrypted_after_encryption_response() {
+    test_runtime().block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let mut encoder = PacketEncoder::new();
+        encoder.append_packet(&handshake(Intent::Login)).unwrap();
+        client.write_all(&encoder.take()).await.unwrap();
+        let (server, remote_addr) = listener.accept().await.unwrap();
+        let HandshakeOutcome::Login(mut raw) =
+            serve_handshake(server, remote_addr, &NetworkConfig::loopback(0))
+                .await
+                .unwrap()
+        else {
+            panic!("expected a login");
+        };
+        raw.expect_encryption();
+
+        encoder
+            .append_packet(&ServerboundKey {
+                shared_secret: &[1; 128],
+                verify_token: &[2; 128],
+            })
+            .unwrap();
+        let mut stream = encoder.take();
+        encoder.enable_encryption(&KEY);
+        encoder
+            .append_packet(&ServerboundHello {
+                username: Bounded("Notch"),
+                profile_id: Uuid::nil(),
+            })
+            .unwrap();
+        stream.extend_from_slice(&encoder.take());
+        client.write_all(&stream).await.unwrap();
+
+        let key = next_packet(&mut raw).await;
+        assert_eq!(key.id, ServerboundKey::ID);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(raw.try_recv().unwrap().is_none(), "nothing past the key");
+
+        raw.enable_encryption(&KEY);
+        let hello = next_packet(&mut raw).await;
+        assert_eq!(hello.id, ServerboundHello::ID);
+        let hello = ServerboundHello::decode(&mut &hello.payload[..]).unwrap();
+        assert_eq!(hello.username.0, "Notch");
+    });
+}
+
+async fn next_packet(raw: &mut RawConnection) -> mcrs_network::ReceivedPacket {
+    for _ in 0..100 {
+        if let Some(packet) = raw.try_recv().unwrap() {
+            return packet;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("no packet arrived");
+}
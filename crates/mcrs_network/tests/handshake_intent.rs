@@ -0,0 +1,95 @@
+mod common;
+
+use common::handshake::{handshake, serve};
+use common::mock_connection::test_runtime;
+use mcrs_network::NetworkConfig;
+use mcrs_network::intent::HandshakeOutcome;
+use mcrs_protocol::handshake::Intent;
+use mcrs_protocol::packets::login::clientbound::ClientboundLoginDisconnect;
+use mcrs_protocol::packets::ping::clientbound::PongResponse;
+use mcrs_protocol::packets::ping::serverbound::PingRequest;
+use mcrs_protocol::packets::status::clientbound::StatusResponse;
+use mcrs_protocol::packets::status::serverbound::StatusRequest;
+use mcrs_protocol::{PROTOCOL_VERSION, Packet, PacketEncoder, Text};
+
+#[test]
+fn status_intent_gets_status_and_never_logs_in() {
+    test_runtime().block_on(async {
+        let config = NetworkConfig {
+            motd: Text::text("handshake test"),
+            max_players: 7,
+            ..NetworkConfig::loopback(0)
+        };
+        let mut encoder = PacketEncoder::new();
+        encoder.append_packet(&handshake(Intent::Status)).unwrap();
+        encoder.append_packet(&StatusRequest).unwrap();
+        encoder.append_packet(&PingRequest { payload: 42 }).unwrap();
+
+        let (outcome, mut decoder) = serve(&mut encoder, &config).await;
+        assert!(matches!(outcome, HandshakeOutcome::Status));
+
+        let frame = decoder.try_next_packet().unwrap().expect("Status Response");
+        assert_eq!(frame.id, StatusResponse::ID);
+        let response = frame.decode::<StatusResponse>().unwrap();
+        let status: serde_json::Value = serde_json::from_str(response.json).unwrap();
+        assert_eq!(status["version"]["protocol"], PROTOCOL_VERSION);
+        assert_eq!(status["players"]["max"], 7);
+        assert_eq!(
+            status["description"],
+            serde_json::to_value(&config.motd).unwrap()
+        );
+
+        let frame = decoder.try_next_packet().unwrap().expect("Pong Response");
+        assert_eq!(frame.id, PongResponse::ID);
+        assert_eq!(frame.decode::<PongResponse>().unwrap().payload, 42);
+        assert!(decoder.try_next_packet().unwrap().is_none());
+    });
+}
+
+#[test]
+fn login_intent_continues_in_login() {
+    test_runtime().block_on(async {
+        let mut encoder = PacketEncoder::new();
+        encoder.append_packet(&handshake(Intent::Login)).unwrap();
+
+        let (outcome, _) = serve(&mut encoder, &NetworkConfig::loopback(0)).await;
+        assert!(matches!(outcome, HandshakeOutcome::Login(_)));
+    });
+}
+
+#[test]
+fn transfer_is_refused_unless_accepted() {
+    test_runtime().block_on(async {
+        let mut encoder = PacketEncoder::new();
+        encoder.append_packet(&handshake(Intent::Transfer)).unwrap();
+
+        let (outcome, mut decoder) = serve(&mut encoder, &NetworkConfig::loopback(0)).await;
+        assert!(matches!(outcome, HandshakeOutcome::Refused));
+        let frame = decoder
+            .try_next_packet()
+            .unwrap()
+            .expect("Login Disconnect");
+        assert_eq!(frame.id, ClientboundLoginDisconnect::ID);
+        let disconnect = frame.decode::<ClientboundLoginDisconnect>().unwrap();
+        let reason: Text = serde_json::from_str(disconnect.reason.0).unwrap();
+        assert_eq!(
+            reason,
+            Text::translate("multiplayer.disconnect.transfers_disabled", vec![])
+        );
+    });
+}
+
+#[test]
+fn accepted_transfer_continues_in_login() {
+    test_runtime().block_on(async {
+        let config = NetworkConfig {
+            accepts_transfers: true,
+            ..NetworkConfig::loopback(0)
+        };
+        let mut encoder = PacketEncoder::new();
+        encoder.append_packet(&handshake(Intent::Transfer)).unwrap();
+
+        let (outcome, _) = serve(&mut encoder, &config).await;
+        assert!(matches!(outcome, HandshakeOutcome::Login(_)));
+    });
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::mock_connection::test_runtime;
+use mcrs_network::connect::{is_legacy_ping, legacy_ping_response};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Connect a client to a loopback listener, send `bytes`, and return the
+/// server side of the socket.
+async fn server_stream_after(bytes: &[u8]) -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap())
+        .await
+        .unwrap();
+    client.write_all(bytes).await.unwrap();
+    let (server, _) = listener.accept().await.unwrap();
+    (server, client)
+}
+
+#[test]
+fn legacy_ping_is_detected() {
+    test_runtime().block_on(async {
+        let (server, _client) = server_stream_after(&[0xFE, 0x01, 0xFA]).await;
+        assert!(is_legacy_ping(&server).await.unwrap());
+    });
+}
+
+/// A modern handshake starts with a length VarInt; detection must only peek,
+/// leaving every byte for the packet decoder.
+#[test]
+fn modern_handshake_is_untouched() {
+    test_runtime().block_on(async {
+        let handshake = [0x10, 0x00, 0xFB, 0x05];
+        let (mut server, _client) = server_stream_after(&handshake).await;
+        assert!(!is_legacy_ping(&server).await.unwrap());
+
+        let mut read = [0u8; 4];
+        server.read_exact(&mut read).await.unwrap();
+        assert_eq!(read, handshake);
+    });
+}
+
+#[test]
+fn legacy_response_is_utf16_kick_packet() {
+    let response = legacy_ping_response("mcrs", "A Server", 2, 20);
+    assert_eq!(response[0], 0xFF);
+
+    let len = u16::from_be_bytes([response[1], response[2]]) as usize;
+    let units: Vec<u16> = response[3..]
+        .chunks_exact(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .collect();
+    assert_eq!(units.len(), len);
+    let status = String::from_utf16(&units).unwrap();
+    assert_eq!(status, "§1\0127\0mcrs\0A Server\02\020");
+}
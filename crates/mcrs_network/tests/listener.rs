@@ -0,0 +1,45 @@
+mod common;
+
+use common::mock_connection::test_runtime;
+use mcrs_network::NetworkConfig;
+use mcrs_network::connect::{BindError, bind_listener, client_addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::net::TcpStream;
+
+/// A loopback listener accepts a client on 127.0.0.1, and a second listener
+/// on the same port fails with `AddrInUse` instead of a bare OS error.
+#[test]
+fn loopback_listener_accepts_and_port_in_use_is_reported() {
+    test_runtime().block_on(async {
+        let listener = bind_listener(&NetworkConfig::loopback(0)).expect("bind loopback");
+        let address = listener.local_addr().unwrap();
+        assert_eq!(address.ip(), IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        let client = TcpStream::connect(address).await.expect("connect");
+        let (_socket, remote_addr) = listener.accept().await.expect("accept");
+        assert_eq!(remote_addr, client.local_addr().unwrap());
+
+        match bind_listener(&NetworkConfig::loopback(address.port())) {
+            Err(BindError::AddrInUse(in_use)) => assert_eq!(in_use, address),
+            other => panic!("expected AddrInUse, got {other:?}"),
+        }
+    });
+}
+
+/// IPv4 clients of a dual-stack listener show up as IPv4-mapped IPv6
+/// addresses and are reported as plain IPv4.
+#[test]
+fn mapped_ipv4_client_is_reported_as_ipv4() {
+    let mapped = SocketAddr::new(Ipv4Addr::new(203, 0, 113, 7).to_ipv6_mapped().into(), 54321);
+    assert_eq!(
+        client_addr(mapped),
+        SocketAddr::new(Ipv4Addr::new(203, 0, 113, 7).into(), 54321)
+    );
+
+    let v6 = SocketAddr::new(Ipv6Addr::LOCALHOST.into(), 54321);
+    assert_eq!(client_addr(v6), v6);
+
+    let config = NetworkConfig::dual_stack(25565);
+    assert!(config.dual_stack);
+    assert_eq!(config.address.ip(), IpAddr::V6(Ipv6Addr::UNSPECIFIED));
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use bevy_app::{App, Update};
+use bevy_ecs::message::MessageReader;
+use bevy_ecs::schedule::IntoScheduleConfigs;
+use bevy_ecs::system::Query;
+use bytes::Bytes;
+use common::mock_connection::test_runtime;
+use mcrs_network::event::{ClientPacket, EventLoopPlugin, ServerboundPacket};
+use mcrs_network::{
+    ConnectionState, NetworkSet, RawConnection, ReceivedPacket, ServerSideConnection,
+};
+use mcrs_protocol::packets::common::{clientbound, serverbound};
+use mcrs_protocol::packets::configuration::ClientboundKeepAlive;
+use mcrs_protocol::packets::configuration::serverbound::{
+    self as configuration, ServerboundKeepAlive,
+};
+use mcrs_protocol::{Encode, Packet, PacketDecoder, WritePacket};
+use std::time::Instant;
+
+/// Answer every Configuration keep-alive with the same payload.
+fn echo_keep_alive(
+    mut packets: MessageReader<ClientPacket>,
+    mut connections: Query<&mut ServerSideConnection>,
+) {
+    for packet in packets.read() {
+        let ServerboundPacket::Configuration(configuration::Packet::KeepAlive(
+            ServerboundKeepAlive(keep_alive),
+        )) = packet.packet()
+        else {
+            continue;
+        };
+        if let Ok(mut conn) = connections.get_mut(packet.entity) {
+            conn.write_packet(&ClientboundKeepAlive(clientbound::KeepAlive {
+                payload: keep_alive.payload,
+            }));
+        }
+    }
+}
+
+/// A packet received in an update reaches a `Process` system in the same
+/// update, and its reply is flushed before the update ends.
+#[test]
+fn process_sees_received_packets_before_flush() {
+    let (raw, mut outgoing_rx, inbound_tx) =
+        test_runtime().block_on(async { RawConnection::new_for_test_full(16) });
+
+    let mut app = App::new();
+    app.add_plugins(EventLoopPlugin);
+    app.add_systems(Update, echo_keep_alive.in_set(NetworkSet::Process));
+    app.world_mut().spawn((
+        ServerSideConnection { raw: Box::new(raw) },
+        ConnectionState::Configuration,
+    ));
+
+    let mut payload = Vec::new();
+    ServerboundKeepAlive(serverbound::KeepAlive { payload: 7 })
+        .encode(&mut payload)
+        .unwrap();
+    inbound_tx
+        .try_send(ReceivedPacket {
+            timestamp: Instant::now(),
+            id: ServerboundKeepAlive::ID,
+            payload: Bytes::from(payload),
+        })
+        .unwrap();
+    app.update();
+
+    let blob = outgoing_rx.try_recv().expect("the reply was flushed");
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(blob.into());
+    let frame = decoder.try_next_packet().unwrap().expect("one packet");
+    assert_eq!(frame.id, ClientboundKeepAlive::ID);
+    let ClientboundKeepAlive(reply) = frame.decode::<ClientboundKeepAlive>().unwrap();
+    assert_eq!(reply.payload, 7);
+}
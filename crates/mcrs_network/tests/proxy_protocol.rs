@@ -0,0 +1,104 @@
+mod common;
+
+use common::mock_connection::test_runtime;
+use mcrs_network::connect::{PROXY_V2_SIGNATURE, PerIpBuckets, accept_proxied, read_proxy_header};
+use mcrs_network::{RawConnection, ServerSideConnection};
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// PROXY v2 header for a TCP/IPv4 connection from `203.0.113.7:54321` to
+/// `10.0.0.1:25565`.
+fn ipv4_header() -> Vec<u8> {
+    ipv4_header_from([203, 0, 113, 7])
+}
+
+fn ipv4_header_from(source: [u8; 4]) -> Vec<u8> {
+    let mut header = PROXY_V2_SIGNATURE.to_vec();
+    header.extend_from_slice(&[0x21, 0x11, 0x00, 12]);
+    header.extend_from_slice(&source);
+    header.extend_from_slice(&[10, 0, 0, 1]);
+    header.extend_from_slice(&54321u16.to_be_bytes());
+    header.extend_from_slice(&25565u16.to_be_bytes());
+    header
+}
+
+#[test]
+fn proxy_header_sets_remote_addr() {
+    let rt = test_runtime();
+    let mut stream = ipv4_header();
+    // The modern handshake that follows must be left unread.
+    stream.extend_from_slice(&[0x10, 0x00]);
+    let mut reader = &stream[..];
+
+    let source = rt
+        .block_on(read_proxy_header(&mut reader))
+        .unwrap()
+        .expect("PROXY command carries a source address");
+    assert_eq!(reader, [0x10, 0x00]);
+
+    let (outgoing_tx, _outgoing_rx) = mpsc::channel(4);
+    let mut raw = rt.block_on(async { RawConnection::new_for_test(outgoing_tx) });
+    raw.remote_addr = source;
+    let conn = ServerSideConnection { raw: Box::new(raw) };
+    assert_eq!(
+        conn.remote_addr(),
+        "203.0.113.7:54321".parse::<SocketAddr>().unwrap()
+    );
+}
+
+#[test]
+fn local_command_keeps_socket_addr() {
+    let mut header = PROXY_V2_SIGNATURE.to_vec();
+    header.extend_from_slice(&[0x20, 0x00, 0x00, 0x00]);
+    let mut reader = &header[..];
+    let source = test_runtime()
+        .block_on(read_proxy_header(&mut reader))
+        .unwrap();
+    assert_eq!(source, None);
+}
+
+#[test]
+fn missing_or_malformed_header_is_rejected() {
+    let rt = test_runtime();
+
+    // A plain modern handshake with no PROXY header in front.
+    let mut plain: &[u8] = &[
+        0x10, 0x00, 0xFB, 0x05, 0x09, 0x6C, 0x6F, 0x63, 0x61, 0x6C, 0x68, 0x6F, 0x73, 0x74, 0x63,
+        0xDD,
+    ];
+    assert!(rt.block_on(read_proxy_header(&mut plain)).is_err());
+
+    // Valid signature, but the IPv4 block is cut short.
+    let mut truncated = PROXY_V2_SIGNATURE.to_vec();
+    truncated.extend_from_slice(&[0x21, 0x11, 0x00, 4, 203, 0, 113, 7]);
+    let mut reader = &truncated[..];
+    assert!(rt.block_on(read_proxy_header(&mut reader)).is_err());
+}
+
+/// Clients behind one proxy are throttled by their own addresses, not the
+/// proxy's.
+#[test]
+fn proxied_clients_are_throttled_by_source_address() {
+    let rt = test_runtime();
+    let proxy: SocketAddr = "10.0.0.2:40000".parse().unwrap();
+    let buckets = Mutex::new(PerIpBuckets::new(1, Duration::from_secs(60)));
+    let accept = |source: [u8; 4]| {
+        let header = ipv4_header_from(source);
+        rt.block_on(accept_proxied(&mut &header[..], proxy, &buckets))
+    };
+
+    let first = accept([203, 0, 113, 7]).expect("first client accepted");
+    assert_eq!(first, "203.0.113.7:54321".parse::<SocketAddr>().unwrap());
+    assert!(accept([203, 0, 113, 8]).is_some());
+    assert_eq!(accept([203, 0, 113, 7]), None);
+
+    // The proxy's own health checks are never throttled.
+    let mut local = PROXY_V2_SIGNATURE.to_vec();
+    local.extend_from_slice(&[0x20, 0x00, 0x00, 0x00]);
+    for _ in 0..3 {
+        let source = rt.block_on(accept_proxied(&mut &local[..], proxy, &buckets));
+        assert_eq!(source, Some(proxy));
+    }
+}
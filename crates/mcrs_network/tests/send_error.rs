@@ -0,0 +1,71 @@
+mod common;
+
+use bytes::Bytes;
+use common::mock_connection::test_runtime;
+use mcrs_network::{EngineConnection, RawConnection, SendError};
+use mcrs_protocol::packets::login::clientbound::ClientboundLoginDisconnect;
+use mcrs_protocol::packets::ping::clientbound::PongResponse;
+use mcrs_protocol::{Bounded, PacketDecoder, WritePacket};
+
+/// Bytes rejected by a full channel are kept and sent ahead of the next blob.
+#[test]
+fn full_channel_keeps_bytes_for_the_next_send() {
+    let (mut raw, mut outgoing_rx, _inbound_tx) =
+        test_runtime().block_on(async { RawConnection::new_for_test_full(1) });
+
+    assert!(raw.try_send_blob(Bytes::from_static(b"a")).is_ok());
+    assert!(matches!(
+        raw.try_send_blob(Bytes::from_static(b"bc")),
+        Err(SendError::Full)
+    ));
+    assert_eq!(raw.queued_bytes(), 2);
+    assert_eq!(raw.stats().bytes_sent, 1);
+
+    assert_eq!(outgoing_rx.try_recv().unwrap(), Bytes::from_static(b"a"));
+    assert!(raw.try_send_blob(Bytes::from_static(b"d")).is_ok());
+    assert_eq!(outgoing_rx.try_recv().unwrap(), Bytes::from_static(b"bcd"));
+    assert_eq!(raw.queued_bytes(), 0);
+    assert_eq!(raw.stats().bytes_sent, 4);
+}
+
+#[test]
+fn closed_channel_is_disconnected() {
+    let (mut raw, outgoing_rx, _inbound_tx) =
+        test_runtime().block_on(async { RawConnection::new_for_test_full(1) });
+    drop(outgoing_rx);
+
+    assert!(matches!(
+        raw.try_send_blob(Bytes::from_static(b"a")),
+        Err(SendError::Disconnected)
+    ));
+    raw.write_packet(&PongResponse { payload: 1 });
+    assert!(matches!(raw.flush(), Err(SendError::Disconnected)));
+}
+
+/// A packet that fails to encode leaves nothing behind, so the packets around
+/// it still decode.
+#[test]
+fn encode_error_writes_nothing() {
+    let (mut raw, mut outgoing_rx, _inbound_tx) =
+        test_runtime().block_on(async { RawConnection::new_for_test_full(1) });
+    let too_long = "a".repeat(40_000);
+
+    raw.append(&PongResponse { payload: 1 }).unwrap();
+    assert!(matches!(
+        raw.append(&ClientboundLoginDisconnect {
+            reason: Bounded(too_long.as_str()),
+        }),
+        Err(SendError::Encode(_))
+    ));
+    raw.append(&PongResponse { payload: 2 }).unwrap();
+    assert_eq!(raw.stats().packets_sent, 2);
+    raw.flush().unwrap();
+
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(outgoing_rx.try_recv().unwrap().into());
+    let mut frames = 0;
+    while decoder.try_next_packet().unwrap().is_some() {
+        frames += 1;
+    }
+    assert_eq!(frames, 2);
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use bytes::Bytes;
+use common::mock_connection::test_runtime;
+use mcrs_network::{OUTBOUND_CHANNEL_CAPACITY, RawConnection, SendError, ServerSideConnection};
+
+/// Pressure rises with each unsent blob, reaches 1.0 when the channel is full,
+/// and falls again as the writer drains it.
+#[test]
+fn pressure_tracks_channel_occupancy() {
+    let (raw, mut outgoing_rx, _inbound_tx) = test_runtime()
+        .block_on(async { RawConnection::new_for_test_full(OUTBOUND_CHANNEL_CAPACITY) });
+    let mut conn = ServerSideConnection { raw: Box::new(raw) };
+    assert_eq!(conn.send_buffer_pressure(), 0.0);
+    let blob = Bytes::from_static(&[0u8; 16]);
+
+    for sent in 1..=OUTBOUND_CHANNEL_CAPACITY {
+        assert!(conn.raw.try_send_blob(blob.clone()).is_ok());
+        let expected = sent as f32 / OUTBOUND_CHANNEL_CAPACITY as f32;
+        assert_eq!(conn.send_buffer_pressure(), expected);
+    }
+    assert_eq!(conn.send_buffer_pressure(), 1.0);
+    assert!(matches!(conn.raw.try_send_blob(blob), Err(SendError::Full)));
+
+    outgoing_rx.try_recv().unwrap();
+    assert!(conn.send_buffer_pressure() < 1.0);
+}
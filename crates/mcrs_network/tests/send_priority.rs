@@ -0,0 +1,63 @@
+mod common;
+
+use common::mock_connection::test_runtime;
+use mcrs_network::{EngineConnection, RawConnection, SendError, SendPriority};
+use mcrs_protocol::packets::ping::clientbound::PongResponse;
+use mcrs_protocol::{PacketDecoder, WritePacket};
+
+fn payloads(blob: bytes::Bytes) -> Vec<u64> {
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(blob.into());
+    let mut payloads = Vec::new();
+    while let Some(frame) = decoder.try_next_packet().unwrap() {
+        payloads.push(frame.decode::<PongResponse>().unwrap().payload);
+    }
+    payloads
+}
+
+/// A high-priority packet written behind a backlog of normal ones is sent
+/// ahead of them, and each lane keeps its own order.
+#[test]
+fn high_priority_packet_is_flushed_before_the_backlog() {
+    let (mut raw, mut outgoing_rx, _inbound_tx) =
+        test_runtime().block_on(async { RawConnection::new_for_test_full(1) });
+
+    // The first flush fills the channel; the next ones back up behind it.
+    raw.write_packet(&PongResponse { payload: 0 });
+    raw.flush().unwrap();
+    for payload in 1..=20 {
+        raw.write_packet(&PongResponse { payload });
+        if payload % 5 == 0 {
+            assert!(matches!(raw.flush(), Err(SendError::Full)));
+        }
+    }
+    raw.write_packet(&PongResponse { payload: 21 });
+    raw.write_packet_with_priority(&PongResponse { payload: 100 }, SendPriority::High);
+    raw.write_packet_with_priority(&PongResponse { payload: 101 }, SendPriority::High);
+
+    assert_eq!(payloads(outgoing_rx.try_recv().unwrap()), [0]);
+    raw.flush().unwrap();
+    let mut expected = vec![100, 101];
+    expected.extend(1..=21);
+    assert_eq!(payloads(outgoing_rx.try_recv().unwrap()), expected);
+}
+
+/// High-priority packets held back by a full channel stay ahead of the ones
+/// written after them.
+#[test]
+fn held_back_high_priority_packets_keep_their_order() {
+    let (mut raw, mut outgoing_rx, _inbound_tx) =
+        test_runtime().block_on(async { RawConnection::new_for_test_full(1) });
+
+    raw.write_packet(&PongResponse { payload: 0 });
+    raw.flush().unwrap();
+    raw.write_packet(&PongResponse { payload: 1 });
+    raw.write_packet_with_priority(&PongResponse { payload: 100 }, SendPriority::High);
+    assert!(matches!(raw.flush(), Err(SendError::Full)));
+    raw.write_packet(&PongResponse { payload: 2 });
+    raw.write_packet_with_priority(&PongResponse { payload: 101 }, SendPriority::High);
+
+    assert_eq!(payloads(outgoing_rx.try_recv().unwrap()), [0]);
+    raw.flush().unwrap();
+    assert_eq!(payloads(outgoing_rx.try_recv().unwrap()), [100, 101, 1, 2]);
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use bevy_app::{App, AppExit, Last};
+use bevy_ecs::message::Messages;
+use common::mock_connection::test_runtime;
+use mcrs_network::{ConnectionState, RawConnection, ServerSideConnection, shutdown_on_exit};
+use mcrs_protocol::packets::game::clientbound::ClientboundDisconnect;
+use mcrs_protocol::{Packet, PacketDecoder, Text};
+use tokio::sync::mpsc::error::TryRecvError;
+
+#[test]
+fn app_exit_kicks_and_closes_every_connection() {
+    let rt = test_runtime();
+    let (raw, mut outgoing_rx, _inbound_tx) =
+        rt.block_on(async { RawConnection::new_for_test_full(16) });
+
+    let mut app = App::new();
+    app.add_message::<AppExit>();
+    app.add_systems(Last, shutdown_on_exit);
+    let entity = app
+        .world_mut()
+        .spawn((
+            ServerSideConnection { raw: Box::new(raw) },
+            ConnectionState::Game,
+        ))
+        .id();
+
+    app.update();
+    assert!(app.world().get_entity(entity).is_ok());
+    assert!(matches!(outgoing_rx.try_recv(), Err(TryRecvError::Empty)));
+
+    app.world_mut()
+        .resource_mut::<Messages<AppExit>>()
+        .write(AppExit::Success);
+    app.update();
+    assert!(app.world().get_entity(entity).is_err());
+
+    let blob = outgoing_rx.try_recv().expect("disconnect was flushed");
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(blob.into());
+    let frame = decoder.try_next_packet().unwrap().expect("one packet");
+    assert_eq!(frame.id, ClientboundDisconnect::ID);
+    let packet = frame.decode::<ClientboundDisconnect>().unwrap();
+    assert_eq!(
+        packet.reason,
+        Text::translate("multiplayer.disconnect.server_shutdown", vec![])
+    );
+    // The connection was dropped, so its writer sees the channel close.
+    assert!(matches!(
+        outgoing_rx.try_recv(),
+        Err(TryRecvError::Disconnected)
+    ));
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::handshake::{handshake_with_protocol, serve};
+use common::mock_connection::test_runtime;
+use mcrs_network::NetworkConfig;
+use mcrs_network::intent::{HandshakeOutcome, incompatible_version_reason};
+use mcrs_protocol::handshake::Intent;
+use mcrs_protocol::packets::intent::serverbound::ServerboundHandshake;
+use mcrs_protocol::packets::login::clientbound::ClientboundLoginDisconnect;
+use mcrs_protocol::packets::ping::serverbound::PingRequest;
+use mcrs_protocol::packets::status::clientbound::StatusResponse;
+use mcrs_protocol::packets::status::serverbound::StatusRequest;
+use mcrs_protocol::{
+    Bounded, Decode, Encode, MINECRAFT_VERSION, PROTOCOL_VERSION, Packet, PacketEncoder, Text,
+    VarInt,
+};
+
+/// Encode a Login-intent handshake and decode it the way the server does.
+fn login_handshake_protocol(protocol_version: i32) -> i32 {
+    let mut bytes = Vec::new();
+    ServerboundHandshake {
+        protocol_version: VarInt(protocol_version),
+        server_address: Bounded("localhost"),
+        server_port: 25565,
+        intent: Intent::Login,
+    }
+    .encode(&mut bytes)
+    .unwrap();
+    let handshake = ServerboundHandshake::decode(&mut bytes.as_slice()).unwrap();
+    assert!(matches!(handshake.intent, Intent::Login));
+    handshake.protocol_version.0
+}
+
+#[test]
+fn matching_protocol_is_accepted() {
+    let protocol = login_handshake_protocol(PROTOCOL_VERSION);
+    assert_eq!(incompatible_version_reason(protocol), None);
+}
+
+#[test]
+fn newer_or_older_protocol_is_incompatible() {
+    for protocol in [PROTOCOL_VERSION - 1, PROTOCOL_VERSION + 1, 754] {
+        let protocol = login_handshake_protocol(protocol);
+        assert_eq!(
+            incompatible_version_reason(protocol),
+            Some(Text::translate(
+                "multiplayer.disconnect.incompatible",
+                vec![Text::text(MINECRAFT_VERSION)]
+            )),
+            "protocol {protocol}"
+        );
+    }
+}
+
+#[test]
+fn pre_1_16_4_client_is_outdated() {
+    let protocol = login_handshake_protocol(753);
+    assert_eq!(
+        incompatible_version_reason(protocol),
+        Some(Text::translate(
+            "multiplayer.disconnect.outdated_client",
+            vec![Text::text(MINECRAFT_VERSION)]
+        ))
+    );
+}
+
+/// The reason the server sent when it refused a Login handshake from a client
+/// speaking `protocol_version`, or `None` if the client may log in.
+async fn login_refusal(protocol_version: i32) -> Option<Text> {
+    let mut encoder = PacketEncoder::new();
+    encoder
+        .append_packet(&handshake_with_protocol(Intent::Login, protocol_version))
+        .unwrap();
+    match serve(&mut encoder, &NetworkConfig::loopback(0)).await {
+        (HandshakeOutcome::Login(_), _) => None,
+        (HandshakeOutcome::Refused, mut decoder) => {
+            let frame = decoder
+                .try_next_packet()
+                .unwrap()
+                .expect("Login Disconnect");
+            assert_eq!(frame.id, ClientboundLoginDisconnect::ID);
+            let disconnect = frame.decode::<ClientboundLoginDisconnect>().unwrap();
+            Some(serde_json::from_str(disconnect.reason.0).unwrap())
+        }
+        (HandshakeOutcome::Status, _) => panic!("a Login handshake was served as Status"),
+    }
+}
+
+#[test]
+fn handshake_refuses_mismatched_protocols_at_login() {
+    test_runtime().block_on(async {
+        assert_eq!(login_refusal(PROTOCOL_VERSION).await, None);
+        assert_eq!(
+            login_refusal(PROTOCOL_VERSION + 1).await,
+            Some(Text::translate(
+                "multiplayer.disconnect.incompatible",
+                vec![Text::text(MINECRAFT_VERSION)]
+            ))
+        );
+        assert_eq!(
+            login_refusal(753).await,
+            Some(Text::translate(
+                "multiplayer.disconnect.outdated_client",
+                vec![Text::text(MINECRAFT_VERSION)]
+            ))
+        );
+    });
+}
+
+#[test]
+fn status_ping_ignores_the_protocol() {
+    test_runtime().block_on(async {
+        let mut encoder = PacketEncoder::new();
+        encoder
+            .append_packet(&handshake_with_protocol(Intent::Status, 753))
+            .unwrap();
+        encoder.append_packet(&StatusRequest).unwrap();
+        encoder.append_packet(&PingRequest { payload: 1 }).unwrap();
+
+        let (outcome, mut decoder) = serve(&mut encoder, &NetworkConfig::loopback(0)).await;
+        assert!(matches!(outcome, HandshakeOutcome::Status));
+        let frame = decoder.try_next_packet().unwrap().expect("Status Response");
+        assert_eq!(frame.id, StatusResponse::ID);
+    });
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::mock_connection::test_runtime;
+use mcrs_network::{EngineConnection, RawConnection};
+use mcrs_protocol::packets::ping::clientbound::PongResponse;
+use mcrs_protocol::{PacketDecoder, WritePacket};
+
+/// Packets written within a tick are coalesced: one flush hands the writer a
+/// single buffer that still decodes back to every packet in order.
+#[test]
+fn flush_coalesces_packets_into_one_blob() {
+    const N: u64 = 32;
+    let rt = test_runtime();
+    let (mut raw, mut outgoing_rx, _inbound_tx) =
+        rt.block_on(async { RawConnection::new_for_test_full(16) });
+
+    for payload in 0..N {
+        raw.write_packet(&PongResponse { payload });
+    }
+    assert!(
+        outgoing_rx.try_recv().is_err(),
+        "nothing is sent before flush"
+    );
+
+    raw.flush().expect("flush");
+    let blob = outgoing_rx.try_recv().expect("one combined blob");
+    assert!(
+        outgoing_rx.try_recv().is_err(),
+        "flush sends exactly one blob"
+    );
+
+    let mut decoder = PacketDecoder::new();
+    decoder.queue_bytes(blob.into());
+    let mut payloads = Vec::new();
+    while let Some(frame) = decoder.try_next_packet().unwrap() {
+        payloads.push(frame.decode::<PongResponse>().unwrap().payload);
+    }
+    assert_eq!(payloads, (0..N).collect::<Vec<_>>());
+
+    // Nothing written since, so the next flush is a no-op.
+    raw.flush().expect("flush");
+    assert!(outgoing_rx.try_recv().is_err());
+}
@@ -1,9 +1,13 @@
 use crate::{Decode, Encode, VarInt};
 use anyhow::Context;
 use derive_more::{Deref, From, Into};
+use serde::{Deserialize, Serialize};
 use std::io::Write;
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash, Debug, From, Into, Deref)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash, Debug, From, Into, Deref, Serialize,
+    Deserialize,
+)]
 pub struct BlockStateId(pub u16);
 
 impl BlockStateId {
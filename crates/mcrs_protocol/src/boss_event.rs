@@ -0,0 +1,60 @@
+//! Boss bars: the Boss Event packet's operations and the bar's style.
+
+use bitfield_struct::bitfield;
+use mcrs_protocol_macros::{Decode, Encode};
+
+use crate::Text;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Encode, Decode)]
+pub enum BossBarColor {
+    Pink,
+    Blue,
+    Red,
+    Green,
+    Yellow,
+    #[default]
+    Purple,
+    White,
+}
+
+/// How many notches the bar is split into.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Encode, Decode)]
+pub enum BossBarDivision {
+    #[default]
+    Progress,
+    Notched6,
+    Notched10,
+    Notched12,
+    Notched20,
+}
+
+#[bitfield(u8)]
+#[derive(PartialEq, Eq, Encode, Decode)]
+pub struct BossBarFlags {
+    pub darken_screen: bool,
+    pub play_music: bool,
+    pub create_world_fog: bool,
+    #[bits(5)]
+    _pad: u8,
+}
+
+/// What a Boss Event does to the bar it names. `Add` carries the whole bar;
+/// the others change one aspect of a bar the client already shows.
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub enum BossEventOperation {
+    Add {
+        title: Text,
+        progress: f32,
+        color: BossBarColor,
+        division: BossBarDivision,
+        flags: BossBarFlags,
+    },
+    Remove,
+    UpdateProgress(f32),
+    UpdateTitle(Text),
+    UpdateStyle {
+        color: BossBarColor,
+        division: BossBarDivision,
+    },
+    UpdateProperties(BossBarFlags),
+}
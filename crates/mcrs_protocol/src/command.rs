@@ -0,0 +1,210 @@
+//! The command graph of the Commands packet: the tree of literal and argument
+//! nodes the client parses, highlights and tab-completes chat commands with.
+
+use std::io::Write;
+
+use anyhow::{bail, ensure};
+
+use crate::{Decode, Encode, Ident, VarInt};
+
+const TYPE_MASK: u8 = 0x03;
+const FLAG_EXECUTABLE: u8 = 0x04;
+const FLAG_REDIRECT: u8 = 0x08;
+const FLAG_SUGGESTIONS: u8 = 0x10;
+const FLAG_RESTRICTED: u8 = 0x20;
+
+/// One node of the graph. Nodes refer to each other by their index in the
+/// packet's node list.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CommandNode {
+    pub kind: CommandNodeKind,
+    pub children: Vec<VarInt>,
+    /// The node parsing continues from once this one is matched, e.g. the
+    /// root for `execute ... run`.
+    pub redirect: Option<VarInt>,
+    /// Whether the input up to and including this node is a whole command.
+    pub executable: bool,
+    /// Whether running the command from a click event needs confirmation.
+    pub restricted: bool,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum CommandNodeKind {
+    Root,
+    Literal(String),
+    Argument {
+        name: String,
+        parser: ArgumentParser,
+        /// Asks the server for suggestions, e.g. `minecraft:ask_server`.
+        suggestions: Option<Ident<String>>,
+    },
+}
+
+/// The argument types the client needs to know how to parse. The encoded id
+/// is the parser's index in the `command_argument_type` registry.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ArgumentParser {
+    Integer { min: Option<i32>, max: Option<i32> },
+    String(StringKind),
+    Entity { single: bool, players_only: bool },
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub enum StringKind {
+    /// One word of unquoted characters.
+    SingleWord,
+    /// One word, or a quoted phrase.
+    QuotablePhrase,
+    /// The rest of the input.
+    GreedyPhrase,
+}
+
+impl ArgumentParser {
+    const INTEGER: i32 = 3;
+    const STRING: i32 = 5;
+    const ENTITY: i32 = 6;
+}
+
+impl Encode for ArgumentParser {
+    fn encode(&self, mut w: impl Write) -> anyhow::Result<()> {
+        match *self {
+            ArgumentParser::Integer { min, max } => {
+                VarInt(Self::INTEGER).encode(&mut w)?;
+                let flags = min.is_some() as u8 | (max.is_some() as u8) << 1;
+                flags.encode(&mut w)?;
+                if let Some(min) = min {
+                    min.encode(&mut w)?;
+                }
+                if let Some(max) = max {
+                    max.encode(&mut w)?;
+                }
+                Ok(())
+            }
+            ArgumentParser::String(kind) => {
+                VarInt(Self::STRING).encode(&mut w)?;
+                kind.encode(w)
+            }
+            ArgumentParser::Entity {
+                single,
+                players_only,
+            } => {
+                VarInt(Self::ENTITY).encode(&mut w)?;
+                (single as u8 | (players_only as u8) << 1).encode(w)
+            }
+        }
+    }
+}
+
+impl Decode<'_> for ArgumentParser {
+    fn decode(r: &mut &[u8]) -> anyhow::Result<Self> {
+        Ok(match VarInt::decode(r)?.0 {
+            Self::INTEGER => {
+                let flags = u8::decode(r)?;
+                let min = if flags & 0x01 != 0 {
+                    Some(i32::decode(r)?)
+                } else {
+                    None
+                };
+                let max = if flags & 0x02 != 0 {
+                    Some(i32::decode(r)?)
+                } else {
+                    None
+                };
+                ArgumentParser::Integer { min, max }
+            }
+            Self::STRING => ArgumentParser::String(StringKind::decode(r)?),
+            Self::ENTITY => {
+                let flags = u8::decode(r)?;
+                ArgumentParser::Entity {
+                    single: flags & 0x01 != 0,
+                    players_only: flags & 0x02 != 0,
+                }
+            }
+            id => bail!("unsupported argument parser {id}"),
+        })
+    }
+}
+
+impl Encode for CommandNode {
+    fn encode(&self, mut w: impl Write) -> anyhow::Result<()> {
+        let mut flags = match &self.kind {
+            CommandNodeKind::Root => 0,
+            CommandNodeKind::Literal(_) => 1,
+            CommandNodeKind::Argument { .. } => 2,
+        };
+        if self.executable {
+            flags |= FLAG_EXECUTABLE;
+        }
+        if self.redirect.is_some() {
+            flags |= FLAG_REDIRECT;
+        }
+        if let CommandNodeKind::Argument {
+            suggestions: Some(_),
+            ..
+        } = &self.kind
+        {
+            flags |= FLAG_SUGGESTIONS;
+        }
+        if self.restricted {
+            flags |= FLAG_RESTRICTED;
+        }
+        flags.encode(&mut w)?;
+        self.children.encode(&mut w)?;
+        if let Some(redirect) = self.redirect {
+            redirect.encode(&mut w)?;
+        }
+        match &self.kind {
+            CommandNodeKind::Root => {}
+            CommandNodeKind::Literal(name) => name.encode(&mut w)?,
+            CommandNodeKind::Argument {
+                name,
+                parser,
+                suggestions,
+            } => {
+                name.encode(&mut w)?;
+                parser.encode(&mut w)?;
+                if let Some(suggestions) = suggestions {
+                    suggestions.encode(&mut w)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Decode<'_> for CommandNode {
+    fn decode(r: &mut &[u8]) -> anyhow::Result<Self> {
+        let flags = u8::decode(r)?;
+        let children = Vec::<VarInt>::decode(r)?;
+        let redirect = if flags & FLAG_REDIRECT != 0 {
+            Some(VarInt::decode(r)?)
+        } else {
+            None
+        };
+        let kind = match flags & TYPE_MASK {
+            0 => CommandNodeKind::Root,
+            1 => CommandNodeKind::Literal(String::decode(r)?),
+            2 => CommandNodeKind::Argument {
+                name: String::decode(r)?,
+                parser: ArgumentParser::decode(r)?,
+                suggestions: if flags & FLAG_SUGGESTIONS != 0 {
+                    Some(Ident::decode(r)?)
+                } else {
+                    None
+                },
+            },
+            n => bail!("invalid command node type {n}"),
+        };
+        ensure!(
+            flags & FLAG_SUGGESTIONS == 0 || matches!(kind, CommandNodeKind::Argument { .. }),
+            "only argument nodes can have suggestions"
+        );
+        Ok(Self {
+            kind,
+            children,
+            redirect,
+            executable: flags & FLAG_EXECUTABLE != 0,
+            restricted: flags & FLAG_RESTRICTED != 0,
+        })
+    }
+}
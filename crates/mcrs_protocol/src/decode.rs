@@ -1,5 +1,5 @@
 #[cfg(feature = "encryption")]
-use aes::cipher::{generic_array::GenericArray, BlockDecryptMut, BlockSizeUser, KeyIvInit};
+use aes::cipher::{Array, BlockModeDecrypt, KeyIvInit};
 use anyhow::{bail, ensure, Context};
 use bytes::{Buf, BytesMut};
 
@@ -163,10 +163,9 @@ impl PacketDecoder {
     /// consuming the cipher.
     #[cfg(feature = "encryption")]
     fn decrypt_bytes(cipher: &mut Cipher, bytes: &mut [u8]) {
-        for chunk in bytes.chunks_mut(Cipher::block_size()) {
-            let gen_arr = GenericArray::from_mut_slice(chunk);
-            cipher.decrypt_block_mut(gen_arr);
-        }
+        // CFB-8 works on single bytes, so there is never a partial block.
+        let (blocks, _) = Array::slice_as_chunks_mut(bytes);
+        cipher.decrypt_blocks(blocks);
     }
 
     pub fn queue_bytes(&mut self, mut bytes: BytesMut) {
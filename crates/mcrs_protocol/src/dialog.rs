@@ -1,7 +1,40 @@
+use std::io::Write;
+
 use mcrs_nbt::compound::NbtCompound;
 
-#[allow(dead_code)]
-enum DialogHolder {
+use crate::var_int::VarInt;
+use crate::{Decode, Encode};
+
+/// A dialog as sent by the Game-state Show Dialog packet: either an inline
+/// definition or an id into the synced `minecraft:dialog` registry.
+#[derive(Clone, PartialEq, Debug)]
+pub enum DialogHolder {
     Direct(NbtCompound),
-    Reference(u32),
+    Reference(VarInt),
+}
+
+impl Encode for DialogHolder {
+    fn encode(&self, mut w: impl Write) -> anyhow::Result<()> {
+        match self {
+            DialogHolder::Direct(dialog) => {
+                VarInt(0).encode(&mut w)?;
+                dialog.encode(&mut w)?;
+            }
+            DialogHolder::Reference(id) => VarInt(id.0 + 1).encode(&mut w)?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Decode<'a> for DialogHolder {
+    fn decode(r: &mut &'a [u8]) -> anyhow::Result<Self> {
+        let i = VarInt::decode(r)?.0;
+
+        if i == 0 {
+            Ok(DialogHolder::Direct(NbtCompound::decode(r)?))
+        } else {
+            Ok(DialogHolder::Reference(VarInt(i - 1)))
+        }
+    }
 }
@@ -1,9 +1,7 @@
 use std::io::Write;
 
 #[cfg(feature = "encryption")]
-use aes::cipher::generic_array::GenericArray;
-#[cfg(feature = "encryption")]
-use aes::cipher::{BlockEncryptMut, BlockSizeUser, KeyIvInit};
+use aes::cipher::{Array, BlockModeEncrypt, KeyIvInit};
 use anyhow::ensure;
 use bytes::{BufMut, BytesMut};
 use tracing::warn;
@@ -16,6 +14,31 @@ use crate::{CompressionThreshold, Encode, MAX_PACKET_SIZE, Packet};
 #[cfg(feature = "encryption")]
 type Cipher = cfb8::Encryptor<aes::Aes128>;
 
+/// Encrypts a byte stream the way the client expects once Encryption
+/// Response has been handled.
+///
+/// [`PacketEncoder`] encrypts what it [takes](PacketEncoder::take) with one
+/// of these. A connection that joins the output of several encoders before
+/// the socket encrypts the joined bytes with a single one instead, so the
+/// stream stays in order.
+#[cfg(feature = "encryption")]
+#[derive(Clone)]
+pub struct PacketEncryptor(Cipher);
+
+#[cfg(feature = "encryption")]
+impl PacketEncryptor {
+    pub fn new(key: &[u8; 16]) -> Self {
+        Self(Cipher::new_from_slices(key, key).expect("invalid key"))
+    }
+
+    /// Encrypts `bytes` in place, continuing the stream.
+    pub fn encrypt(&mut self, bytes: &mut [u8]) {
+        // CFB-8 works on single bytes, so there is never a partial block.
+        let (blocks, _) = Array::slice_as_chunks_mut(bytes);
+        self.0.encrypt_blocks(blocks);
+    }
+}
+
 #[derive(Default)]
 pub struct PacketEncoder {
     buf: BytesMut,
@@ -24,7 +47,7 @@ pub struct PacketEncoder {
     #[cfg(feature = "compression")]
     threshold: CompressionThreshold,
     #[cfg(feature = "encryption")]
-    cipher: Option<Cipher>,
+    cipher: Option<PacketEncryptor>,
 }
 
 impl PacketEncoder {
@@ -58,12 +81,24 @@ impl PacketEncoder {
         Ok(())
     }
 
+    /// Encodes `pkt` onto the end of the buffer. On error nothing is
+    /// appended, so the packets already written stay well-formed.
     pub fn append_packet<P>(&mut self, pkt: &P) -> anyhow::Result<()>
     where
         P: Packet + Encode,
     {
         let start_len = self.buf.len();
+        let result = self.append_packet_at(start_len, pkt);
+        if result.is_err() {
+            self.buf.truncate(start_len);
+        }
+        result
+    }
 
+    fn append_packet_at<P>(&mut self, start_len: usize, pkt: &P) -> anyhow::Result<()>
+    where
+        P: Packet + Encode,
+    {
         pkt.encode_with_id((&mut self.buf).writer())?;
 
         let data_len = self.buf.len() - start_len;
@@ -149,10 +184,7 @@ impl PacketEncoder {
     pub fn take(&mut self) -> BytesMut {
         #[cfg(feature = "encryption")]
         if let Some(cipher) = &mut self.cipher {
-            for chunk in self.buf.chunks_mut(Cipher::block_size()) {
-                let gen_arr = GenericArray::from_mut_slice(chunk);
-                cipher.encrypt_block_mut(gen_arr);
-            }
+            cipher.encrypt(&mut self.buf);
         }
 
         self.buf.split()
@@ -178,7 +210,7 @@ impl PacketEncoder {
     #[cfg(feature = "encryption")]
     pub fn enable_encryption(&mut self, key: &[u8; 16]) {
         assert!(self.cipher.is_none(), "encryption is already enabled");
-        self.cipher = Some(Cipher::new_from_slices(key, key).expect("invalid key"));
+        self.cipher = Some(PacketEncryptor::new(key));
     }
 }
 
@@ -388,3 +420,54 @@ where
 
     Ok(())
 }
+
+#[cfg(all(test, feature = "encryption"))]
+mod tests {
+    use super::*;
+    use crate::PacketDecoder;
+    use crate::packets::ping::clientbound::PongResponse;
+
+    const KEY: [u8; 16] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+
+    #[test]
+    fn encrypted_packets_decode_once_the_decoder_has_the_key() {
+        let mut enc = PacketEncoder::new();
+        enc.append_packet(&PongResponse { payload: 1 }).unwrap();
+        let mut stream = enc.take();
+        enc.enable_encryption(&KEY);
+        enc.append_packet(&PongResponse { payload: 2 }).unwrap();
+        enc.append_packet(&PongResponse { payload: 3 }).unwrap();
+        let encrypted = enc.take();
+        stream.extend_from_slice(&encrypted);
+
+        let mut dec = PacketDecoder::new();
+        dec.queue_slice(&stream);
+        let frame = dec.try_next_packet().unwrap().unwrap();
+        assert_eq!(frame.decode::<PongResponse>().unwrap().payload, 1);
+        dec.enable_encryption(&KEY);
+        for payload in [2, 3] {
+            let frame = dec.try_next_packet().unwrap().unwrap();
+            assert_eq!(frame.decode::<PongResponse>().unwrap().payload, payload);
+        }
+        assert!(dec.try_next_packet().unwrap().is_none());
+    }
+
+    #[test]
+    fn encryptor_continues_the_stream_across_calls() {
+        let mut plain = PacketEncoder::new();
+        plain.append_packet(&PongResponse { payload: 2 }).unwrap();
+        plain.append_packet(&PongResponse { payload: 3 }).unwrap();
+        let mut bytes = plain.take();
+
+        let mut enc = PacketEncoder::new();
+        enc.enable_encryption(&KEY);
+        enc.append_packet(&PongResponse { payload: 2 }).unwrap();
+        enc.append_packet(&PongResponse { payload: 3 }).unwrap();
+
+        let mut encryptor = PacketEncryptor::new(&KEY);
+        let (head, tail) = bytes.split_at_mut(5);
+        encryptor.encrypt(head);
+        encryptor.encrypt(tail);
+        assert_eq!(bytes, enc.take());
+    }
+}
@@ -0,0 +1,34 @@
+use crate::VarInt;
+use mcrs_ident::Ident;
+use mcrs_protocol_macros::{Decode, Encode};
+
+/// One attribute of Update Attributes: the client replaces its copy of the
+/// attribute, base value and modifiers alike.
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct AttributeSnapshot {
+    /// Network id in the `minecraft:attribute` registry.
+    pub attribute: VarInt,
+    pub base: f64,
+    pub modifiers: Vec<AttributeModifier>,
+}
+
+/// A modifier on an attribute. An attribute holds at most one modifier per
+/// `id`.
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct AttributeModifier {
+    pub id: Ident<String>,
+    pub amount: f64,
+    pub operation: AttributeOperation,
+}
+
+/// How a modifier's amount is applied. Modifiers are applied grouped by
+/// operation, in declaration order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum AttributeOperation {
+    /// Added to the base value.
+    AddValue,
+    /// The base value, after every `AddValue`, times the amount is added.
+    AddMultipliedBase,
+    /// The value so far is multiplied by one plus the amount.
+    AddMultipliedTotal,
+}
@@ -1,11 +1,14 @@
 use crate::{BlockStateId, Direction, GlobalPos, Slot, VarInt, VarLong};
+use anyhow::ensure;
 use bevy_math::{Vec3, Vec4};
 use mcrs_engine::world::block::BlockPos;
 use mcrs_protocol::entity::player::HumanoidArm;
 use mcrs_protocol_macros::{Decode, Encode};
 use uuid::Uuid;
 use mcrs_text::Text;
+use std::io::Write;
 
+pub mod attribute;
 pub mod minecart;
 pub mod player;
 mod sniffer;
@@ -25,7 +28,8 @@ pub enum MetaDataValue<'a> {
     String(&'a str),
     Text(Text),
     OptionalText(Option<Text>),
-    Slot(Option<Slot>),
+    /// An empty stack travels as count 0, not as an absent option.
+    Slot(Slot),
     Boolean(bool),
     Rotations(Vec3),
     BlockPos(BlockPos),
@@ -59,6 +63,118 @@ pub enum MetaDataValue<'a> {
     HumanoidArm(HumanoidArm),
 }
 
+/// The entries of a Set Entity Metadata packet. Each one is its index, the
+/// serializer id and the value; the list ends with a `0xFF` index instead of
+/// a length prefix. Setting an index twice keeps the last value.
+#[derive(Debug, Clone, Default)]
+pub struct EntityMetadata<'a> {
+    entries: Vec<MetadataEntry<'a>>,
+}
+
+impl<'a> EntityMetadata<'a> {
+    /// Index that ends the entry list on the wire.
+    pub const END: u8 = 0xFF;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_entry(mut self, index: u8, value: MetaDataValue<'a>) -> Self {
+        debug_assert_ne!(index, Self::END, "0xFF ends the metadata list");
+        match self.entries.iter_mut().find(|entry| entry.index == index) {
+            Some(entry) => entry.value = value,
+            None => self.entries.push(MetadataEntry { index, value }),
+        }
+        self
+    }
+
+    pub fn with_byte(self, index: u8, value: i8) -> Self {
+        self.with_entry(index, MetaDataValue::Byte(value))
+    }
+
+    pub fn with_var_int(self, index: u8, value: i32) -> Self {
+        self.with_entry(index, MetaDataValue::VarInt(VarInt(value)))
+    }
+
+    pub fn with_float(self, index: u8, value: f32) -> Self {
+        self.with_entry(index, MetaDataValue::Float(value))
+    }
+
+    pub fn with_string(self, index: u8, value: &'a str) -> Self {
+        self.with_entry(index, MetaDataValue::String(value))
+    }
+
+    pub fn with_optional_text(self, index: u8, value: Option<Text>) -> Self {
+        self.with_entry(index, MetaDataValue::OptionalText(value))
+    }
+
+    pub fn with_boolean(self, index: u8, value: bool) -> Self {
+        self.with_entry(index, MetaDataValue::Boolean(value))
+    }
+
+    /// Pitch, yaw and roll in degrees, as armor stands pose their limbs.
+    pub fn with_rotations(self, index: u8, value: Vec3) -> Self {
+        self.with_entry(index, MetaDataValue::Rotations(value))
+    }
+
+    pub fn with_block_pos(self, index: u8, value: BlockPos) -> Self {
+        self.with_entry(index, MetaDataValue::BlockPos(value))
+    }
+
+    pub fn with_pose(self, index: u8, value: Pose) -> Self {
+        self.with_entry(index, MetaDataValue::Pose(value))
+    }
+
+    pub fn with_slot(self, index: u8, value: Slot) -> Self {
+        self.with_entry(index, MetaDataValue::Slot(value))
+    }
+
+    pub fn get(&self, index: u8) -> Option<&MetaDataValue<'a>> {
+        self.entries
+            .iter()
+            .find(|entry| entry.index == index)
+            .map(|entry| &entry.value)
+    }
+
+    pub fn entries(&self) -> &[MetadataEntry<'a>] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl crate::Encode for EntityMetadata<'_> {
+    fn encode(&self, mut w: impl Write) -> anyhow::Result<()> {
+        for entry in &self.entries {
+            entry.encode(&mut w)?;
+        }
+        Self::END.encode(w)
+    }
+}
+
+impl<'a> crate::Decode<'a> for EntityMetadata<'a> {
+    fn decode(r: &mut &'a [u8]) -> anyhow::Result<Self> {
+        let mut entries = Vec::new();
+        loop {
+            ensure!(
+                !r.is_empty(),
+                "entity metadata is missing its 0xFF terminator"
+            );
+            if r[0] == Self::END {
+                *r = &r[1..];
+                return Ok(Self { entries });
+            }
+            entries.push(MetadataEntry::decode(r)?);
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default, Encode, Decode)]
 pub enum Pose {
     #[default]
@@ -124,3 +240,59 @@ pub struct VillagerData {
     pub profession: VarInt,
     pub level: VarInt,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Decode, Encode};
+
+    fn encoded(metadata: &EntityMetadata) -> Vec<u8> {
+        let mut buf = Vec::new();
+        metadata.encode(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn empty_metadata_is_just_the_terminator() {
+        assert_eq!(encoded(&EntityMetadata::new()), [0xFF]);
+    }
+
+    #[test]
+    fn entries_are_index_serializer_and_value() {
+        let metadata = EntityMetadata::new()
+            .with_byte(0, 0x02)
+            .with_var_int(1, 300)
+            .with_float(9, 20.0)
+            .with_string(2, "hi");
+        assert_eq!(
+            encoded(&metadata),
+            [
+                0, 0, 0x02, // shared flags, byte
+                1, 1, 0xAC, 0x02, // air supply, varint
+                9, 3, 0x41, 0xA0, 0x00, 0x00, // health, float
+                2, 4, 2, b'h', b'i', // string
+                0xFF,
+            ]
+        );
+    }
+
+    #[test]
+    fn setting_an_index_again_replaces_it_and_decodes_back() {
+        let metadata = EntityMetadata::new()
+            .with_boolean(4, true)
+            .with_pose(6, Pose::Standing)
+            .with_pose(6, Pose::Crouching);
+        assert_eq!(metadata.len(), 2);
+
+        let bytes = encoded(&metadata);
+        assert_eq!(bytes, [4, 8, 1, 6, 20, 5, 0xFF]);
+        let mut slice = bytes.as_slice();
+        let decoded = EntityMetadata::decode(&mut slice).unwrap();
+        assert!(slice.is_empty());
+        assert!(matches!(
+            decoded.get(6),
+            Some(MetaDataValue::Pose(Pose::Crouching))
+        ));
+        assert!(EntityMetadata::decode(&mut [4u8, 8, 1].as_slice()).is_err());
+    }
+}
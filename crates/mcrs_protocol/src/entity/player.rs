@@ -1,5 +1,6 @@
 use crate::game_mode::OptGameMode;
 use crate::{Bounded, FixedBitSet, GameMode, GlobalPos, VarInt};
+use bitfield_struct::bitfield;
 use mcrs_protocol_macros::{Decode, Encode};
 use std::borrow::Cow;
 use mcrs_ident::{Ident, ident};
@@ -47,6 +48,42 @@ pub enum PlayerAction {
     Stab,
 }
 
+/// What a Player Command packet reports. Sneaking travels in Player Input
+/// instead, as [`PlayerInputFlags::shift`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum PlayerCommandAction {
+    StopSleeping,
+    StartSprinting,
+    StopSprinting,
+    StartRidingJump,
+    StopRidingJump,
+    OpenInventory,
+    StartFallFlying,
+}
+
+/// What a Client Command packet asks for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum ClientCommandAction {
+    /// Sent from the death screen, and after the credits when leaving the End.
+    PerformRespawn,
+    RequestStats,
+}
+
+/// Movement keys held by the client, the flags byte of the Player Input
+/// packet.
+#[bitfield(u8)]
+#[derive(PartialEq, Eq, Encode, Decode)]
+pub struct PlayerInputFlags {
+    pub forward: bool,
+    pub backward: bool,
+    pub left: bool,
+    pub right: bool,
+    pub jump: bool,
+    pub shift: bool,
+    pub sprint: bool,
+    _pad: bool,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Encode, Decode)]
 pub enum HumanoidArm {
     Left,
@@ -65,3 +102,15 @@ pub struct MessageSignature {
     pub acknowledged: FixedBitSet<20, 3>,
     pub checksum: u8,
 }
+
+/// Flags byte of the Player Abilities packet.
+#[bitfield(u8)]
+#[derive(PartialEq, Eq, Encode, Decode)]
+pub struct PlayerAbilityFlags {
+    pub invulnerable: bool,
+    pub flying: bool,
+    pub may_fly: bool,
+    pub instabuild: bool,
+    #[bits(4)]
+    _pad: u8,
+}
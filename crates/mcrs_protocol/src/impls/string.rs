@@ -1,13 +1,8 @@
 use std::io::{Cursor, Write};
 
-use crate::{Bounded, Decode, Encode, RawBytes, VarInt};
+use crate::{Bounded, Decode, Encode, VarInt};
 use anyhow::ensure;
-use byteorder::WriteBytesExt;
-use mcrs_nbt::deserializer::NbtReadHelper;
-use mcrs_nbt::serializer::WriteAdaptor;
-use mcrs_nbt::tag::NbtTag;
-use mcrs_nbt::{STRING_ID, from_bytes_unnamed, to_bytes_unnamed};
-use mcrs_text::{Text, TextContent};
+use mcrs_text::Text;
 
 const DEFAULT_MAX_STRING_CHARS: usize = 32767;
 
@@ -100,44 +95,66 @@ impl<const MAX_CHARS: usize> Decode<'_> for Bounded<Box<str>, MAX_CHARS> {
 }
 
 impl Encode for Text {
-    fn encode(&self, mut w: impl Write) -> anyhow::Result<()> {
-        if let TextContent::Text { text } = &self.content
-            && self.extra.is_empty()
-            && self.color.is_none()
-            && self.font.is_none()
-            && self.bold.is_none()
-            && self.italic.is_none()
-            && self.underlined.is_none()
-            && self.strikethrough.is_none()
-            && self.obfuscated.is_none()
-            && self.click_event.is_none()
-            && self.hover_event.is_none()
-            && self.insertion.is_none()
-        {
-            w.write_u8(STRING_ID)?;
-            NbtTag::String(text.to_string()).serialize_data(&mut WriteAdaptor::new(w))?;
-            return Ok(());
-        }
-        to_bytes_unnamed(&self, &mut w)?;
-        Ok(())
+    fn encode(&self, w: impl Write) -> anyhow::Result<()> {
+        Ok(self.write_nbt(w)?)
     }
 }
 
 impl Decode<'_> for Text {
     fn decode(r: &mut &[u8]) -> anyhow::Result<Self> {
-        let data = RawBytes::decode(r)?;
-        let b = data.0[0];
-        if b == STRING_ID {
-            let s = NbtTag::deserialize(&mut NbtReadHelper::new(&mut Cursor::new(&data.0)))?;
-            if let NbtTag::String(s) = s {
-                Ok(Self::text(s))
-            } else {
-                anyhow::bail!(
-                    "expected NBT String tag for Text deserialization, got {s:?}"
-                );
-            }
-        } else {
-            Ok(from_bytes_unnamed(&mut Cursor::new(&data.0))?)
-        }
+        let mut cursor = Cursor::new(*r);
+        let text = Text::read_nbt(&mut cursor)?;
+        *r = &r[cursor.position() as usize..];
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcrs_nbt::deserializer::NbtReadHelper;
+    use mcrs_nbt::tag::NbtTag;
+    use mcrs_text::{Color, IntoText};
+
+    fn encoded_tag(text: &Text) -> NbtTag {
+        let mut buf = Vec::new();
+        text.encode(&mut buf).unwrap();
+        NbtTag::deserialize(&mut NbtReadHelper::new(Cursor::new(buf))).unwrap()
+    }
+
+    #[test]
+    fn plain_text_is_a_bare_string_tag() {
+        assert_eq!(
+            encoded_tag(&"Hello".into_text()),
+            NbtTag::String("Hello".to_string())
+        );
+    }
+
+    #[test]
+    fn decoding_stops_at_the_end_of_the_tag() {
+        let text = "Welcome".color(Color::YELLOW).bold();
+        let mut buf = Vec::new();
+        text.encode(&mut buf).unwrap();
+        true.encode(&mut buf).unwrap();
+
+        let mut r = buf.as_slice();
+        assert_eq!(Text::decode(&mut r).unwrap(), text);
+        assert!(bool::decode(&mut r).unwrap());
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn styled_text_is_a_compound_of_its_fields() {
+        let NbtTag::Compound(compound) = encoded_tag(&"Welcome".color(Color::YELLOW).bold()) else {
+            panic!("styled text must be sent as a compound tag");
+        };
+        assert_eq!(
+            compound.child_tags,
+            [
+                ("text".to_string(), NbtTag::String("Welcome".to_string())),
+                ("color".to_string(), NbtTag::String("yellow".to_string())),
+                ("bold".to_string(), NbtTag::Byte(1)),
+            ]
+        );
     }
 }
@@ -1,4 +1,5 @@
 use crate::{Decode, Encode, VarInt};
+use anyhow::Context;
 use bevy_ecs::prelude::Component;
 use derive_more::{From, Into};
 use mcrs_nbt::compound::NbtCompound;
@@ -60,13 +61,16 @@ impl Slot {
     }
 }
 
+/// Vanilla's optional item stack codec: a VarInt count, then the item id and
+/// component patch only for a non-empty stack. Empty stacks, including air,
+/// are a lone zero count.
 impl Encode for Slot {
     fn encode(&self, mut w: impl Write) -> anyhow::Result<()> {
-        self.count.encode(&mut w)?;
-        if self.count == 0 {
-            return Ok(());
+        if self.is_empty() {
+            return VarInt(0).encode(w);
         }
-        VarInt(self.id.0 as i32).encode(&mut w)?;
+        VarInt(self.count as i32).encode(&mut w)?;
+        self.id.encode(&mut w)?;
         self.components.encode(&mut w)?;
         Ok(())
     }
@@ -74,11 +78,12 @@ impl Encode for Slot {
 
 impl Decode<'_> for Slot {
     fn decode(r: &mut &[u8]) -> anyhow::Result<Self> {
-        let count = u8::decode(r)?;
-        if count == 0 {
+        let count = VarInt::decode(r)?.0;
+        if count <= 0 {
             return Ok(Slot::EMPTY);
         }
-        let item = ItemId(u16::decode(r)?);
+        let count = u8::try_from(count).context("item stack count out of range")?;
+        let item = ItemId::decode(r)?;
         let components = ComponentPatch::decode(r)?;
         Ok(Slot {
             id: item,
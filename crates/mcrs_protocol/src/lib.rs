@@ -33,14 +33,16 @@ mod biome_pos;
 mod bit_set;
 mod block;
 pub mod block_pos;
+pub mod boss_event;
 mod bounded;
 mod byte_angle;
 mod cell_pos;
 pub mod chunk;
 pub mod chunk_pos;
+pub mod command;
 pub mod packed_chunk_pos;
 pub mod decode;
-mod dialog;
+pub mod dialog;
 mod difficulty;
 mod direction;
 pub mod encode;
@@ -52,12 +54,14 @@ mod hand;
 pub mod handshake;
 mod impls;
 pub mod item;
+mod lp_vec3;
 pub mod packets;
 mod pos;
 pub mod profile;
 mod raw;
 pub mod registry;
 pub mod resource_pack;
+pub mod scoreboard;
 mod serial;
 pub mod setting;
 pub mod sound;
@@ -83,6 +87,8 @@ pub use decode::PacketDecoder;
 use derive_more::{From, Into};
 pub use difficulty::Difficulty;
 pub use direction::Direction;
+#[cfg(feature = "encryption")]
+pub use encode::PacketEncryptor;
 pub use encode::{PacketEncoder, WritePacket};
 pub use game_event::GameEventKind;
 pub use game_mode::GameMode;
@@ -90,6 +96,7 @@ pub use global_pos::GlobalPos;
 pub use hand::Hand;
 pub use ident::ident;
 pub use item::{ItemId, Slot};
+pub use lp_vec3::LpVec3;
 pub use mcrs_protocol_macros::{Decode, Encode, Packet};
 pub use pos::Look;
 pub use pos::MoveFlags;
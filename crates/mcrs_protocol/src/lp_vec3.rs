@@ -0,0 +1,154 @@
+use std::io::Write;
+
+use anyhow::ensure;
+use bevy_math::DVec3;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use derive_more::{From, Into};
+
+use crate::{Decode, Encode, VarInt};
+
+/// A low-precision vector, as entity velocities (blocks/tick) are sent
+/// (vanilla `LpVec3`).
+///
+/// Each axis is divided by a shared integer scale, the smallest one at least
+/// the largest axis, and quantized to 15 bits across `-1..=1`. The three
+/// axes and the scale's low two bits pack into six bytes; a scale above 3
+/// follows as a VarInt of its remaining bits. A vector too short to
+/// represent is a single zero byte.
+#[derive(Copy, Clone, PartialEq, Debug, Default, From, Into)]
+pub struct LpVec3(pub DVec3);
+
+impl LpVec3 {
+    pub const ZERO: Self = Self(DVec3::ZERO);
+
+    /// Axes this far from zero or further are clamped.
+    const ABS_MAX: f64 = 1.7179869183E10;
+    /// The shortest vector that is not sent as zero.
+    const ABS_MIN: f64 = 3.051944088384301E-5;
+    const MAX_QUANTIZED: f64 = 32766.0;
+    const SCALE_BITS: u32 = 2;
+    const SCALE_MASK: u64 = 0b11;
+    const CONTINUATION_FLAG: u64 = 0b100;
+    const X_OFFSET: u32 = 3;
+    const Y_OFFSET: u32 = 18;
+    const Z_OFFSET: u32 = 33;
+
+    fn sanitize(value: f64) -> f64 {
+        if value.is_nan() {
+            0.0
+        } else {
+            value.clamp(-Self::ABS_MAX, Self::ABS_MAX)
+        }
+    }
+
+    fn pack(value: f64) -> u64 {
+        ((value * 0.5 + 0.5) * Self::MAX_QUANTIZED).round() as u64
+    }
+
+    fn unpack(value: u64) -> f64 {
+        ((value & 0x7FFF) as f64).min(Self::MAX_QUANTIZED) * 2.0 / Self::MAX_QUANTIZED - 1.0
+    }
+}
+
+impl Encode for LpVec3 {
+    fn encode(&self, mut w: impl Write) -> anyhow::Result<()> {
+        let v = self.0.map(Self::sanitize);
+        let max = v.abs().max_element();
+        if max < Self::ABS_MIN {
+            return Ok(w.write_u8(0)?);
+        }
+
+        let scale = max.ceil() as u64;
+        let partial = scale & Self::SCALE_MASK != scale;
+        let markers = if partial {
+            scale & Self::SCALE_MASK | Self::CONTINUATION_FLAG
+        } else {
+            scale
+        };
+        let scale_f = scale as f64;
+        let packed = markers
+            | Self::pack(v.x / scale_f) << Self::X_OFFSET
+            | Self::pack(v.y / scale_f) << Self::Y_OFFSET
+            | Self::pack(v.z / scale_f) << Self::Z_OFFSET;
+        w.write_u8(packed as u8)?;
+        w.write_u8((packed >> 8) as u8)?;
+        w.write_u32::<BigEndian>((packed >> 16) as u32)?;
+        if partial {
+            VarInt((scale >> Self::SCALE_BITS) as i32).encode(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl Decode<'_> for LpVec3 {
+    fn decode(r: &mut &[u8]) -> anyhow::Result<Self> {
+        let lowest = r.read_u8()?;
+        if lowest == 0 {
+            return Ok(Self::ZERO);
+        }
+        let middle = r.read_u8()?;
+        let highest = r.read_u32::<BigEndian>()?;
+        let packed = (highest as u64) << 16 | (middle as u64) << 8 | lowest as u64;
+
+        let mut scale = lowest as u64 & Self::SCALE_MASK;
+        if lowest as u64 & Self::CONTINUATION_FLAG != 0 {
+            let high = VarInt::decode(r)?.0 as u32;
+            scale |= (high as u64) << Self::SCALE_BITS;
+        }
+        ensure!(scale != 0, "low-precision vector with a zero scale");
+        let scale = scale as f64;
+        Ok(Self(DVec3::new(
+            Self::unpack(packed >> Self::X_OFFSET) * scale,
+            Self::unpack(packed >> Self::Y_OFFSET) * scale,
+            Self::unpack(packed >> Self::Z_OFFSET) * scale,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(value: DVec3) -> Vec<u8> {
+        let mut buf = Vec::new();
+        LpVec3(value).encode(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn encodes_like_vanilla() {
+        assert_eq!(encode(DVec3::ZERO), [0]);
+        assert_eq!(encode(DVec3::splat(1e-5)), [0]);
+        // Scale 1: each axis is (v / 2 + 1 / 2) * 32766, rounded.
+        assert_eq!(
+            encode(DVec3::new(0.1, 0.2, -0.1)),
+            [0x29, 0x33, 0x73, 0x33, 0x33, 0x32]
+        );
+        assert_eq!(
+            encode(DVec3::new(0.0, -0.04, 0.0)),
+            [0xF9, 0xFF, 0x7F, 0xFE, 0xF5, 0xC1]
+        );
+        // Scale 5 does not fit two bits: 0b01 inline, 0b1 as a trailing VarInt.
+        assert_eq!(
+            encode(DVec3::new(5.0, 0.0, 0.0)),
+            [0xF5, 0xFF, 0x7F, 0xFE, 0xFF, 0xFF, 0x01]
+        );
+    }
+
+    #[test]
+    fn round_trips_within_precision() {
+        for value in [
+            DVec3::new(0.1, 0.2, -0.1),
+            DVec3::new(-0.75, 0.0, 3.5),
+            DVec3::new(12.25, -40.0, 0.001),
+        ] {
+            let bytes = encode(value);
+            let decoded = LpVec3::decode(&mut bytes.as_slice()).unwrap().0;
+            let tolerance = value.abs().max_element().ceil() / LpVec3::MAX_QUANTIZED;
+            assert!(
+                (decoded - value).abs().max_element() <= tolerance,
+                "{value} decoded as {decoded}"
+            );
+        }
+    }
+}
@@ -1,3 +1,4 @@
+pub use self::clientbound::ClientboundDisconnect;
 pub use self::clientbound::ClientboundFinishConfiguration;
 pub use self::clientbound::ClientboundKeepAlive;
 pub use self::clientbound::ClientboundRegistryData;
@@ -5,13 +6,22 @@ pub use self::clientbound::ClientboundShowDialog;
 pub use self::clientbound::ClientboundUpdateTags;
 
 pub mod clientbound {
-    use crate::packets::common::clientbound::{CustomPayload, Disconnect, KeepAlive, Ping};
+    use crate::packets::common::clientbound::{
+        CustomPayload, Disconnect, KeepAlive, Ping, ResourcePackPop, ResourcePackPush,
+    };
     use crate::packets::cookie::clientbound::CookieRequest;
     use derive_more::From;
     use mcrs_nbt::compound::NbtCompound;
     use mcrs_protocol_macros::{Decode, Encode, Packet};
     use std::borrow::Cow;
     use mcrs_ident::Ident;
+    use mcrs_text::Text;
+
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x02, state=Configuration)]
+    pub struct ClientboundDisconnect {
+        pub reason: Text,
+    }
 
     #[derive(Clone, Debug, Encode, Decode, Packet)]
     #[packet(id=0x03, state=Configuration)]
@@ -28,6 +38,15 @@ pub mod clientbound {
         pub entries: Vec<crate::registry::Entry<'a>>,
     }
 
+    /// Removes the pack `id` from the client, or every server pack if `None`.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x08, state=Configuration)]
+    pub struct ClientboundResourcePackPop(pub ResourcePackPop);
+
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x09, state=Configuration)]
+    pub struct ClientboundResourcePackPush<'a>(pub ResourcePackPush<'a>);
+
     #[derive(Clone, Debug, Encode, Decode, Packet)]
     #[packet(id=0x0E, state=Configuration)]
     pub struct ClientboundSelectKnownPacks<'a> {
@@ -117,7 +136,7 @@ pub mod serverbound {
 
     #[derive(Clone, Debug, Encode, Decode, From, Packet)]
     #[packet(id=0x06, state=Configuration)]
-    pub struct ServerboundResourcePack(ResourcePack);
+    pub struct ServerboundResourcePack(pub ResourcePack);
 
     #[derive(Clone, Debug, Encode, Decode, From, Packet)]
     #[packet(id=0x07, state=Configuration)]
@@ -133,17 +152,18 @@ pub mod serverbound {
     #[packet(id=0x09, state=Configuration)]
     pub struct ServerboundAcceptCodeOfConduct;
 
-    #[derive(Clone, Debug, Encode, Decode, From)]
-    pub enum Packet<'a> {
-        ClientInformation(ServerboundClientInformation<'a>),
-        CookieResponse(ServerboundCookieResponse<'a>),
-        CustomPayload(ServerboundCustomPayload<'a>),
-        FinishConfiguration,
-        KeepAlive(KeepAlive),
-        Pong(Pong),
-        ResourcePack(ResourcePack),
-        SelectKnownPacks(ServerboundSelectKnownPacks<'a>),
-        CustomClickAction(ServerboundCustomClickAction<'a>),
-        AcceptCodeOfConduct,
+    serverbound_packets! {
+        pub enum Packet<'a> {
+            ClientInformation(ServerboundClientInformation<'a>),
+            CookieResponse(ServerboundCookieResponse<'a>),
+            CustomPayload(ServerboundCustomPayload<'a>),
+            FinishConfiguration(ServerboundFinishConfiguration),
+            KeepAlive(ServerboundKeepAlive),
+            Pong(ServerboundPong),
+            ResourcePack(ServerboundResourcePack),
+            SelectKnownPacks(ServerboundSelectKnownPacks<'a>),
+            CustomClickAction(ServerboundCustomClickAction<'a>),
+            AcceptCodeOfConduct(ServerboundAcceptCodeOfConduct),
+        }
     }
 }
@@ -1,11 +1,18 @@
 pub mod clientbound {
+    use crate::boss_event::BossEventOperation;
     use crate::chunk::ChunkBlockUpdateEntry;
+    use crate::command::CommandNode;
+    use crate::dialog::DialogHolder;
+    use crate::entity::EntityMetadata;
+    use crate::entity::attribute::AttributeSnapshot;
     use crate::entity::minecart::MinecartStep;
     use crate::entity::player::*;
     use crate::game_event::GameEventKind;
-    use crate::packets::common::clientbound::KeepAlive;
+    use crate::packets::common::clientbound::{KeepAlive, ResourcePackPop, ResourcePackPush};
     use crate::profile::{PlayerListActions, PlayerListEntry};
-    use crate::{ColumnPos, Look, PositionFlag, Slot, VarInt};
+    use crate::scoreboard::{DisplaySlot, NumberFormat, ObjectiveUpdate};
+    use crate::sound::{SoundCategory, SoundId};
+    use crate::{ColumnPos, GlobalPos, Look, LpVec3, PositionFlag, Slot, VarInt, VarLong};
     use bevy_math::DVec3;
     use mcrs_engine::world::block::BlockPos;
     use mcrs_engine::world::chunk::ChunkPos;
@@ -17,6 +24,12 @@ pub mod clientbound {
     use mcrs_ident::Ident;
     use mcrs_text::Text;
 
+    /// Opens or closes a bundle: the client holds the packets between two
+    /// delimiters and handles them together in one frame.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x00, state=Game)]
+    pub struct ClientboundBundleDelimiter;
+
     #[derive(Clone, Debug, Encode, Decode, Packet)]
     #[packet(id=0x01, state=Game)]
     pub struct ClientboundAddEntity {
@@ -24,13 +37,27 @@ pub mod clientbound {
         pub uuid: Uuid,
         pub kind: VarInt,
         pub pos: DVec3,
-        pub velocity: VarInt,
+        pub velocity: LpVec3,
         pub yaw: ByteAngle,
         pub pitch: ByteAngle,
         pub head_yaw: ByteAngle,
         pub data: VarInt,
     }
 
+    /// Plays an animation on an entity, one of the `SWING_*` constants for
+    /// an arm swing.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x02, state=Game)]
+    pub struct ClientboundAnimate {
+        pub entity_id: VarInt,
+        pub action: u8,
+    }
+
+    impl ClientboundAnimate {
+        pub const SWING_MAIN_HAND: u8 = 0;
+        pub const SWING_OFF_HAND: u8 = 3;
+    }
+
     #[derive(Clone, Debug, Encode, Decode, Packet)]
     #[packet(id=0x05, state=Game)]
     pub struct ClientboundBlockDestruction {
@@ -46,6 +73,23 @@ pub mod clientbound {
         pub block_state_id: BlockStateId,
     }
 
+    /// Adds, removes or changes the boss bar `id` on the client.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x09, state=Game)]
+    pub struct ClientboundBossEvent {
+        pub id: Uuid,
+        pub operation: BossEventOperation,
+    }
+
+    /// The whole command graph. `root_index` is the root node's index in
+    /// `nodes`.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x10, state=Game)]
+    pub struct ClientboundCommands {
+        pub nodes: Vec<CommandNode>,
+        pub root_index: VarInt,
+    }
+
     #[derive(Clone, Debug, Encode, Decode, Packet)]
     #[packet(id=0x12, state=Game)]
     pub struct ClientboundContainerSetContent {
@@ -55,6 +99,15 @@ pub mod clientbound {
         pub carried_item: Slot,
     }
 
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x14, state=Game)]
+    pub struct ClientboundContainerSetSlot {
+        pub container_id: VarInt,
+        pub state_seqno: VarInt,
+        pub slot: i16,
+        pub item: Slot,
+    }
+
     #[derive(Clone, Debug, Encode, Decode, Packet)]
     #[packet(id=0x20, state=Game)]
     pub struct ClientboundDisconnect {
@@ -91,6 +144,21 @@ pub mod clientbound {
         pub game_event: GameEventKind,
     }
 
+    /// Full border state for a player joining or changing dimension.
+    /// `lerp_time` is in milliseconds; 0 means the border isn't moving.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x2B, state=Game)]
+    pub struct ClientboundInitializeBorder {
+        pub center_x: f64,
+        pub center_z: f64,
+        pub old_size: f64,
+        pub new_size: f64,
+        pub lerp_time: VarLong,
+        pub absolute_max_size: VarInt,
+        pub warning_blocks: VarInt,
+        pub warning_time: VarInt,
+    }
+
     #[derive(Clone, Debug, Encode, Decode, Packet)]
     #[packet(id=0x2C, state=Game)]
     pub struct ClientboundKeepAlive(pub KeepAlive);
@@ -161,6 +229,22 @@ pub mod clientbound {
         pub on_ground: bool,
     }
 
+    /// The player's ability flags and movement speeds. Vanilla divides
+    /// `walking_speed` by its default of 0.1 to scale the field of view.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x40, state=Game)]
+    pub struct ClientboundPlayerAbilities {
+        pub flags: PlayerAbilityFlags,
+        pub flying_speed: f32,
+        pub walking_speed: f32,
+    }
+
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x45, state=Game)]
+    pub struct ClientboundPlayerInfoRemove {
+        pub uuids: Vec<Uuid>,
+    }
+
     #[derive(Clone, Debug, Packet)]
     #[packet(id=0x46, state=Game)]
     pub struct ClientboundPlayerInfoUpdate<'a> {
@@ -188,6 +272,27 @@ pub mod clientbound {
         pub entity_ids: Vec<VarInt>,
     }
 
+    /// Removes `owner`'s score for `objective`, or from every objective when
+    /// it is `None`.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x4F, state=Game)]
+    pub struct ClientboundResetScore {
+        pub owner: String,
+        pub objective: Option<String>,
+    }
+
+    /// Removes the pack `id` from the client, or every server pack if `None`.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x50, state=Game)]
+    pub struct ClientboundResourcePackPop(pub ResourcePackPop);
+
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x51, state=Game)]
+    pub struct ClientboundResourcePackPush<'a>(pub ResourcePackPush<'a>);
+
+    /// Moves the client into a new level, for dimension travel and for
+    /// respawning after death. `data_to_keep` is a mask of the `KEEP_*`
+    /// constants; the client resets whatever it doesn't name.
     #[derive(Clone, Debug, Encode, Decode, Packet)]
     #[packet(id=0x52, state=Game)]
     pub struct ClientboundRespawn<'a> {
@@ -195,6 +300,13 @@ pub mod clientbound {
         pub data_to_keep: u8,
     }
 
+    impl ClientboundRespawn<'_> {
+        pub const KEEP_ATTRIBUTE_MODIFIERS: u8 = 0x01;
+        pub const KEEP_ENTITY_DATA: u8 = 0x02;
+        /// What dimension travel keeps; a death respawn keeps nothing.
+        pub const KEEP_ALL_DATA: u8 = 0x03;
+    }
+
     #[derive(Clone, Debug, Encode, Decode, Packet)]
     #[packet(id=0x53, state=Game)]
     pub struct ClientboundRotateHead {
@@ -209,6 +321,42 @@ pub mod clientbound {
         pub blocks: Cow<'a, [ChunkBlockUpdateEntry]>,
     }
 
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x58, state=Game)]
+    pub struct ClientboundSetBorderCenter {
+        pub x: f64,
+        pub z: f64,
+    }
+
+    /// Moves the border from `old_size` to `new_size` over `lerp_time`
+    /// milliseconds.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x59, state=Game)]
+    pub struct ClientboundSetBorderLerpSize {
+        pub old_size: f64,
+        pub new_size: f64,
+        pub lerp_time: VarLong,
+    }
+
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x5A, state=Game)]
+    pub struct ClientboundSetBorderSize {
+        pub size: f64,
+    }
+
+    /// Seconds of warning before a moving border reaches the player.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x5B, state=Game)]
+    pub struct ClientboundSetBorderWarningDelay {
+        pub warning_time: VarInt,
+    }
+
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x5C, state=Game)]
+    pub struct ClientboundSetBorderWarningDistance {
+        pub warning_blocks: VarInt,
+    }
+
     #[derive(Clone, Debug, Encode, Decode, Packet)]
     #[packet(id=0x5E, state=Game)]
     pub struct ClientboundSetChunkCacheCenter {
@@ -222,6 +370,100 @@ pub mod clientbound {
         pub radius: VarInt,
     }
 
+    /// The world spawn: where compasses point, and the fallback respawn
+    /// location.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x61, state=Game)]
+    pub struct ClientboundSetDefaultSpawnPosition<'a> {
+        pub pos: GlobalPos<'a>,
+        pub yaw: f32,
+        pub pitch: f32,
+    }
+
+    /// Shows `objective` in `slot`, or clears the slot when it is empty.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x62, state=Game)]
+    pub struct ClientboundSetDisplayObjective {
+        pub slot: DisplaySlot,
+        pub objective: String,
+    }
+
+    /// Entity metadata changes.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x63, state=Game)]
+    pub struct ClientboundSetEntityData<'a> {
+        pub entity_id: VarInt,
+        pub metadata: EntityMetadata<'a>,
+    }
+
+    /// Sets an entity's velocity, in blocks per tick.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x65, state=Game)]
+    pub struct ClientboundSetEntityMotion {
+        pub entity_id: VarInt,
+        pub velocity: LpVec3,
+    }
+
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x6A, state=Game)]
+    pub struct ClientboundSetObjective {
+        pub objective: String,
+        pub update: ObjectiveUpdate,
+    }
+
+    /// Sets `owner`'s score for `objective`. `display_name` replaces the
+    /// owner's name in the sidebar and `number_format` the objective's
+    /// default format.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x6E, state=Game)]
+    pub struct ClientboundSetScore {
+        pub owner: String,
+        pub objective: String,
+        pub score: VarInt,
+        pub display_name: Option<Text>,
+        pub number_format: Option<NumberFormat>,
+    }
+
+    /// How far from the player, in chunks, the server ticks the world. The
+    /// client simulates no further either.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x6F, state=Game)]
+    pub struct ClientboundSetSimulationDistance {
+        pub simulation_distance: VarInt,
+    }
+
+    /// World age plus the state of each world clock that changed. A clock
+    /// with `rate` 0 is paused on the client until the next update.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x71, state=Game)]
+    pub struct ClientboundSetTime {
+        pub game_time: i64,
+        pub clock_updates: Vec<ClockUpdate>,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Encode, Decode)]
+    pub struct ClockUpdate {
+        /// Network id in the `minecraft:world_clock` registry.
+        pub clock: VarInt,
+        pub total_ticks: VarLong,
+        pub partial_tick: f32,
+        pub rate: f32,
+    }
+
+    /// Position is in fixed point: block coordinates times 8, truncated.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x75, state=Game)]
+    pub struct ClientboundSound<'a> {
+        pub sound: SoundId<'a>,
+        pub category: SoundCategory,
+        pub x: i32,
+        pub y: i32,
+        pub z: i32,
+        pub volume: f32,
+        pub pitch: f32,
+        pub seed: i64,
+    }
+
     #[derive(Clone, Debug, Encode, Decode, Packet)]
     #[packet(id=0x76, state=Game)]
     pub struct ClientboundStartConfiguration;
@@ -319,12 +561,32 @@ pub mod clientbound {
             })
         }
     }
+
+    /// Attributes of an entity that changed, or all of them when the entity
+    /// is first shown. Attributes not listed keep their client-side values.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x83, state=Game)]
+    pub struct ClientboundUpdateAttributes {
+        pub entity_id: VarInt,
+        pub attributes: Vec<AttributeSnapshot>,
+    }
+
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x8C, state=Game)]
+    pub struct ClientboundShowDialog {
+        pub dialog: DialogHolder,
+    }
 }
 
 pub mod serverbound {
-    use crate::entity::player::{CommandArgumentSignature, MessageSignature, PlayerAction};
+    use crate::entity::player::{
+        ClientCommandAction, CommandArgumentSignature, MessageSignature, PlayerAction,
+        PlayerCommandAction, PlayerInputFlags,
+    };
     use crate::item::{ContainerInput, HashedSlot};
-    use crate::packets::common::serverbound::{ClientInformation, KeepAlive};
+    use crate::packets::common::serverbound::{
+        ClientInformation, CustomClickAction, KeepAlive, ResourcePack,
+    };
     use crate::pos::MoveFlags;
     use crate::{Bounded, Difficulty, Direction, GameMode, Look, Position, VarInt};
     use derive_more::From;
@@ -398,9 +660,20 @@ pub mod serverbound {
 
     #[derive(Clone, Debug, Encode, Decode, Packet)]
     #[packet(id=0x0A, state=Game)]
-    pub struct ServerboundChatSessionUpdate {
+    pub struct ServerboundChatSessionUpdate<'a> {
         pub session_id: Uuid,
-        pub public_key: [u8; 32],
+        /// Key expiry, in milliseconds since the Unix epoch.
+        pub expires_at: i64,
+        /// X.509-encoded RSA public key.
+        pub public_key: Bounded<&'a [u8], 512>,
+        /// Mojang's signature over the player UUID, expiry and key.
+        pub key_signature: Bounded<&'a [u8], 4096>,
+    }
+
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x0C, state=Game)]
+    pub struct ServerboundClientCommand {
+        pub action: ClientCommandAction,
     }
 
     #[derive(Clone, Debug, Encode, Decode, From, Packet)]
@@ -464,6 +737,28 @@ pub mod serverbound {
         pub sequence: VarInt,
     }
 
+    /// Sprinting, leaving a bed, a horse jump, opening the inventory or
+    /// starting to glide. `entity_id` is always the sender's own.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x2A, state=Game)]
+    pub struct ServerboundPlayerCommand {
+        pub entity_id: VarInt,
+        pub action: PlayerCommandAction,
+        /// Jump strength for `StartRidingJump`, 0 otherwise.
+        pub data: VarInt,
+    }
+
+    /// The movement keys the client holds, sent whenever they change.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x2B, state=Game)]
+    pub struct ServerboundPlayerInput {
+        pub input: PlayerInputFlags,
+    }
+
+    #[derive(Clone, Debug, Encode, Decode, From, Packet)]
+    #[packet(id=0x31, state=Game)]
+    pub struct ServerboundResourcePack(pub ResourcePack);
+
     #[derive(Clone, Debug, Encode, Decode, Packet)]
     #[packet(id=0x35, state=Game)]
     pub struct ServerboundSetCarriedItem {
@@ -483,4 +778,47 @@ pub mod serverbound {
         pub world_border_hit: bool,
         pub sequence: VarInt,
     }
+
+    /// The client swung an arm.
+    #[derive(Clone, Debug, Encode, Decode, Packet)]
+    #[packet(id=0x3F, state=Game)]
+    pub struct ServerboundSwing {
+        pub hand: crate::Hand,
+    }
+
+    #[derive(Clone, Debug, Encode, Decode, From, Packet)]
+    #[packet(id=0x44, state=Game)]
+    pub struct ServerboundCustomClickAction<'a>(pub CustomClickAction<'a>);
+
+    serverbound_packets! {
+        pub enum Packet<'a> {
+            AcceptTeleportation(ServerboundAcceptTeleportation),
+            BlockEntityTagQuery(ServerboundBlockEntityTagQuery),
+            SelectBundleItem(ServerboundSelectBundleItem),
+            ChangeDifficulty(ServerboundChangeDifficulty),
+            ChangeGameMode(ServerboundChangeGameMode),
+            ChatAck(ServerboundChatAck),
+            ChatCommand(ServerboundChatCommand<'a>),
+            ChatCommandSigned(ServerboundChatCommandSigned<'a>),
+            Chat(ServerboundChat<'a>),
+            ChatSessionUpdate(ServerboundChatSessionUpdate<'a>),
+            ClientCommand(ServerboundClientCommand),
+            ClientInformation(ServerboundClientInformation<'a>),
+            ConfigurationAcknowledged(ServerboundConfigurationAcknowledged),
+            ContainerClick(ServerboundContainerClick),
+            KeepAlive(ServerboundKeepAlive),
+            MovePlayerPos(ServerboundMovePlayerPos),
+            MovePlayerPosRot(ServerboundMovePlayerPosRot),
+            MovePlayerRot(ServerboundMovePlayerRot),
+            MovePlayerStatusOnly(ServerboundMovePlayerStatusOnly),
+            PlayerAction(ServerboundPlayerAction),
+            PlayerCommand(ServerboundPlayerCommand),
+            PlayerInput(ServerboundPlayerInput),
+            ResourcePack(ServerboundResourcePack),
+            SetCarriedItem(ServerboundSetCarriedItem),
+            UseItemOn(ServerboundUseItemOn),
+            Swing(ServerboundSwing),
+            CustomClickAction(ServerboundCustomClickAction<'a>),
+        }
+    }
 }
@@ -19,6 +19,9 @@ pub mod clientbound {
         pub server_id: Bounded<&'a str, 20>,
         pub public_key: &'a [u8],
         pub verify_token: &'a [u8],
+        /// Whether the client has to join through the session server before
+        /// answering.
+        pub should_authenticate: bool,
     }
 
     #[derive(Clone, Debug, Encode, Decode, Packet)]
@@ -27,7 +30,8 @@ pub mod clientbound {
         pub profile: GameProfile<'a>,
     }
 
-    #[derive(Copy, Clone, Debug, Encode, Decode, Into)]
+    #[derive(Copy, Clone, Debug, Encode, Decode, Into, Packet)]
+    #[packet(id=0x03, state=Login)]
     pub struct LoginCompression {
         pub threshold: VarInt,
     }
@@ -53,7 +57,6 @@ pub mod clientbound {
 pub mod serverbound {
     use crate::packets::cookie::serverbound::CookieResponse;
     use crate::{Bounded, RawBytes, VarInt};
-    use derive_more::From;
     use mcrs_protocol_macros::{Decode, Encode, Packet};
     use uuid::Uuid;
 
@@ -86,12 +89,13 @@ pub mod serverbound {
     #[packet(id=0x04, state=Login)]
     pub struct ServerboundCookieResponse<'a>(CookieResponse<'a>);
 
-    #[derive(Clone, Debug, Encode, Decode, From)]
-    pub enum ServerboundPacket<'a> {
-        Hello(ServerboundHello<'a>),
-        Key(ServerboundKey<'a>),
-        CustomQueryAnswer(ServerboundCustomQueryAnswer<'a>),
-        LoginAcknowledged(ServerboundLoginAcknowledged),
-        CookieResponse(ServerboundCookieResponse<'a>),
+    serverbound_packets! {
+        pub enum ServerboundPacket<'a> {
+            Hello(ServerboundHello<'a>),
+            Key(ServerboundKey<'a>),
+            CustomQueryAnswer(ServerboundCustomQueryAnswer<'a>),
+            LoginAcknowledged(ServerboundLoginAcknowledged),
+            CookieResponse(ServerboundCookieResponse<'a>),
+        }
     }
 }
@@ -3,6 +3,45 @@
 //! Packets are grouped in submodules according to the protocol stage they're
 //! used in.
 
+/// Declares a state's serverbound packet enum, one variant per packet type,
+/// together with `decode_body`, which picks the variant by packet id.
+macro_rules! serverbound_packets {
+    (
+        $(#[$attr:meta])*
+        pub enum $name:ident<'a> {
+            $($variant:ident($ty:ty)),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Debug, derive_more::From)]
+        pub enum $name<'a> {
+            $($variant($ty)),*
+        }
+
+        impl<'a> $name<'a> {
+            /// Decode the body of the packet with `id`. Returns `Ok(None)` for
+            /// ids this state has no packet type for; leftover bytes are an
+            /// error.
+            pub fn decode_body(id: i32, mut r: &'a [u8]) -> anyhow::Result<Option<Self>> {
+                use $crate::{Decode, Packet};
+                $(
+                    if id == <$ty as Packet>::ID {
+                        let pkt = <$ty as Decode<'a>>::decode(&mut r)?;
+                        anyhow::ensure!(
+                            r.is_empty(),
+                            "{} bytes left over in {}",
+                            r.len(),
+                            <$ty as Packet>::NAME
+                        );
+                        return Ok(Some(Self::$variant(pkt)));
+                    }
+                )*
+                Ok(None)
+            }
+        }
+    };
+}
+
 pub mod common;
 pub mod configuration;
 pub mod cookie;
@@ -0,0 +1,115 @@
+//! Scoreboard objectives, score formats and display slots.
+
+use std::io::Write;
+
+use anyhow::bail;
+use mcrs_nbt::compound::NbtCompound;
+
+use crate::{Decode, Encode, Text, VarInt};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Encode, Decode)]
+pub enum ObjectiveRenderType {
+    #[default]
+    Integer,
+    Hearts,
+}
+
+/// How the client renders a score's number.
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub enum NumberFormat {
+    /// No number at all.
+    Blank,
+    /// The number in the given style, as the NBT form of a text style.
+    Styled(NbtCompound),
+    /// This text instead of the number.
+    Fixed(Text),
+}
+
+/// What a Set Objective packet does to the objective it names. Sent as a
+/// byte rather than a VarInt, so it is encoded by hand.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ObjectiveUpdate {
+    Add(ObjectiveInfo),
+    Remove,
+    Change(ObjectiveInfo),
+}
+
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct ObjectiveInfo {
+    pub display_name: Text,
+    pub render_type: ObjectiveRenderType,
+    /// Default format for the objective's scores.
+    pub number_format: Option<NumberFormat>,
+}
+
+impl ObjectiveInfo {
+    /// An objective shown as plain integers.
+    pub fn new(display_name: impl Into<Text>) -> Self {
+        Self {
+            display_name: display_name.into(),
+            render_type: ObjectiveRenderType::Integer,
+            number_format: None,
+        }
+    }
+}
+
+impl Encode for ObjectiveUpdate {
+    fn encode(&self, mut w: impl Write) -> anyhow::Result<()> {
+        match self {
+            ObjectiveUpdate::Add(info) => {
+                0i8.encode(&mut w)?;
+                info.encode(&mut w)
+            }
+            ObjectiveUpdate::Remove => 1i8.encode(&mut w),
+            ObjectiveUpdate::Change(info) => {
+                2i8.encode(&mut w)?;
+                info.encode(&mut w)
+            }
+        }
+    }
+}
+
+impl Decode<'_> for ObjectiveUpdate {
+    fn decode(r: &mut &[u8]) -> anyhow::Result<Self> {
+        Ok(match i8::decode(r)? {
+            0 => ObjectiveUpdate::Add(ObjectiveInfo::decode(r)?),
+            1 => ObjectiveUpdate::Remove,
+            2 => ObjectiveUpdate::Change(ObjectiveInfo::decode(r)?),
+            n => bail!("invalid objective method {n}"),
+        })
+    }
+}
+
+/// Where an objective is shown. `TeamSidebar` holds a team color index
+/// (0 to 15) and shows only to players on a team of that color.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum DisplaySlot {
+    List,
+    Sidebar,
+    BelowName,
+    TeamSidebar(u8),
+}
+
+impl Encode for DisplaySlot {
+    fn encode(&self, w: impl Write) -> anyhow::Result<()> {
+        let id = match self {
+            DisplaySlot::List => 0,
+            DisplaySlot::Sidebar => 1,
+            DisplaySlot::BelowName => 2,
+            DisplaySlot::TeamSidebar(color) => 3 + (*color as i32).min(15),
+        };
+        VarInt(id).encode(w)
+    }
+}
+
+impl Decode<'_> for DisplaySlot {
+    fn decode(r: &mut &[u8]) -> anyhow::Result<Self> {
+        Ok(match VarInt::decode(r)?.0 {
+            0 => DisplaySlot::List,
+            1 => DisplaySlot::Sidebar,
+            2 => DisplaySlot::BelowName,
+            id @ 3..=18 => DisplaySlot::TeamSidebar((id - 3) as u8),
+            id => bail!("invalid display slot {id}"),
+        })
+    }
+}
@@ -0,0 +1,555 @@
+//! Round trips for the play packets the server sends: each packet is encoded,
+//! decoded back from exactly those bytes and encoded again, so a field that
+//! is written and read with different widths or framing fails here rather
+//! than on a client.
+
+use bevy_math::DVec3;
+use mcrs_engine::geometry::BlockPos;
+use mcrs_protocol::boss_event::{BossBarColor, BossBarDivision, BossBarFlags, BossEventOperation};
+use mcrs_protocol::command::{ArgumentParser, CommandNode, CommandNodeKind, StringKind};
+use mcrs_protocol::entity::player::{PlayerAbilityFlags, PlayerSpawnInfo};
+use mcrs_protocol::game_mode::OptGameMode;
+use mcrs_protocol::item::ComponentPatch;
+use mcrs_protocol::packets::game::clientbound::{
+    ClientboundBossEvent, ClientboundCommands, ClientboundContainerSetContent,
+    ClientboundContainerSetSlot, ClientboundInitializeBorder, ClientboundLogin,
+    ClientboundPlayerAbilities, ClientboundPlayerInfoRemove, ClientboundResetScore,
+    ClientboundSetBorderCenter, ClientboundSetBorderLerpSize, ClientboundSetBorderSize,
+    ClientboundSetBorderWarningDelay, ClientboundSetBorderWarningDistance,
+    ClientboundSetDefaultSpawnPosition, ClientboundSetDisplayObjective, ClientboundSetEntityMotion,
+    ClientboundSetObjective, ClientboundSetScore, ClientboundSetSimulationDistance,
+    ClientboundSetTime, ClientboundSound, ClientboundSystemChatPacket, ClockUpdate,
+};
+use mcrs_protocol::scoreboard::{
+    DisplaySlot, NumberFormat, ObjectiveInfo, ObjectiveRenderType, ObjectiveUpdate,
+};
+use mcrs_protocol::sound::{SoundCategory, SoundId};
+use mcrs_protocol::uuid::Uuid;
+use mcrs_protocol::{
+    Decode, Encode, GameMode, GlobalPos, ItemId, LpVec3, Slot, Text, VarInt, VarLong, ident,
+};
+
+fn encode<T: Encode>(value: &T) -> Vec<u8> {
+    let mut buf = Vec::new();
+    value.encode(&mut buf).expect("encode");
+    buf
+}
+
+/// Encode `$packet`, decode it as `$ty` consuming every byte, and check the
+/// decoded packet encodes to the same bytes. Every strict prefix of the bytes
+/// must fail to decode, so no field can be silently cut short. Evaluates to
+/// the encoded bytes.
+macro_rules! round_trip {
+    ($ty:ty, $packet:expr) => {{
+        let bytes = encode(&$packet);
+        let mut r = &bytes[..];
+        let decoded = <$ty>::decode(&mut r).expect("decode");
+        assert!(r.is_empty(), "{} left {} bytes", stringify!($ty), r.len());
+        assert_eq!(
+            encode(&decoded),
+            bytes,
+            "{} re-encodes differently",
+            stringify!($ty)
+        );
+        for len in 0..bytes.len() {
+            let mut r = &bytes[..len];
+            assert!(
+                <$ty>::decode(&mut r).is_err(),
+                "{} decoded from its first {len} bytes",
+                stringify!($ty)
+            );
+        }
+        bytes
+    }};
+}
+
+#[test]
+fn boss_event_operations() {
+    let id = Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef);
+    let operations = [
+        BossEventOperation::Add {
+            title: Text::text("Ender Dragon"),
+            progress: 1.0,
+            color: BossBarColor::Pink,
+            division: BossBarDivision::Notched12,
+            flags: BossBarFlags::new().with_darken_screen(true),
+        },
+        BossEventOperation::Remove,
+        BossEventOperation::UpdateProgress(0.5),
+        BossEventOperation::UpdateTitle(Text::text("")),
+        BossEventOperation::UpdateStyle {
+            color: BossBarColor::White,
+            division: BossBarDivision::Progress,
+        },
+        BossEventOperation::UpdateProperties(BossBarFlags::new().with_play_music(true)),
+    ];
+    for operation in operations {
+        let bytes = round_trip!(ClientboundBossEvent, ClientboundBossEvent { id, operation });
+        assert_eq!(bytes[..16], id.as_u128().to_be_bytes());
+    }
+}
+
+#[test]
+fn command_graph() {
+    let nodes = vec![
+        CommandNode {
+            kind: CommandNodeKind::Root,
+            children: vec![VarInt(1), VarInt(3)],
+            redirect: None,
+            executable: false,
+            restricted: false,
+        },
+        CommandNode {
+            kind: CommandNodeKind::Literal("say".to_owned()),
+            children: vec![VarInt(2)],
+            redirect: None,
+            executable: false,
+            restricted: false,
+        },
+        CommandNode {
+            kind: CommandNodeKind::Argument {
+                name: "message".to_owned(),
+                parser: ArgumentParser::String(StringKind::GreedyPhrase),
+                suggestions: Some(ident!("ask_server").into()),
+            },
+            children: vec![],
+            redirect: None,
+            executable: true,
+            restricted: true,
+        },
+        CommandNode {
+            kind: CommandNodeKind::Literal("tp".to_owned()),
+            children: vec![],
+            redirect: Some(VarInt(0)),
+            executable: false,
+            restricted: false,
+        },
+        CommandNode {
+            kind: CommandNodeKind::Argument {
+                name: "count".to_owned(),
+                parser: ArgumentParser::Integer {
+                    min: Some(i32::MIN),
+                    max: None,
+                },
+                suggestions: None,
+            },
+            children: vec![],
+            redirect: None,
+            executable: true,
+            restricted: false,
+        },
+    ];
+    let packet = ClientboundCommands {
+        nodes,
+        root_index: VarInt(0),
+    };
+    round_trip!(ClientboundCommands, packet);
+
+    let mut r = &encode(&packet)[..];
+    let decoded = ClientboundCommands::decode(&mut r).unwrap();
+    assert_eq!(decoded.nodes, packet.nodes);
+}
+
+#[test]
+fn container_content_and_slot() {
+    let stack = Slot::new(ItemId(323), 64, ComponentPatch::EMPTY);
+    round_trip!(
+        ClientboundContainerSetContent,
+        ClientboundContainerSetContent {
+            container_id: VarInt(0),
+            state_seqno: VarInt(0x7FFF),
+            slot_data: vec![Slot::EMPTY, stack.clone(), Slot::EMPTY],
+            carried_item: stack.clone(),
+        }
+    );
+    round_trip!(
+        ClientboundContainerSetContent,
+        ClientboundContainerSetContent {
+            container_id: VarInt(0),
+            state_seqno: VarInt(1),
+            slot_data: vec![],
+            carried_item: Slot::EMPTY,
+        }
+    );
+    round_trip!(
+        ClientboundContainerSetSlot,
+        ClientboundContainerSetSlot {
+            container_id: VarInt(0),
+            state_seqno: VarInt(2),
+            slot: -1,
+            item: stack,
+        }
+    );
+}
+
+#[test]
+fn world_border() {
+    round_trip!(
+        ClientboundInitializeBorder,
+        ClientboundInitializeBorder {
+            center_x: -0.5,
+            center_z: 1e7,
+            old_size: 5.9999968e7,
+            new_size: 16.0,
+            lerp_time: VarLong(i64::MAX),
+            absolute_max_size: VarInt(29_999_984),
+            warning_blocks: VarInt(5),
+            warning_time: VarInt(15),
+        }
+    );
+    round_trip!(
+        ClientboundSetBorderCenter,
+        ClientboundSetBorderCenter { x: 0.0, z: -0.0 }
+    );
+    round_trip!(
+        ClientboundSetBorderLerpSize,
+        ClientboundSetBorderLerpSize {
+            old_size: 100.0,
+            new_size: 10.0,
+            lerp_time: VarLong(0),
+        }
+    );
+    round_trip!(
+        ClientboundSetBorderSize,
+        ClientboundSetBorderSize { size: f64::MAX }
+    );
+    round_trip!(
+        ClientboundSetBorderWarningDistance,
+        ClientboundSetBorderWarningDistance {
+            warning_blocks: VarInt(0),
+        }
+    );
+}
+
+#[test]
+fn var_int_and_var_long_extremes() {
+    let widest = |value| {
+        round_trip!(
+            ClientboundSetBorderWarningDelay,
+            ClientboundSetBorderWarningDelay {
+                warning_time: VarInt(value),
+            }
+        )
+    };
+    assert_eq!(widest(i32::MAX), [0xFF, 0xFF, 0xFF, 0xFF, 0x07]);
+    assert_eq!(widest(-1), [0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+    assert_eq!(widest(i32::MIN), [0x80, 0x80, 0x80, 0x80, 0x08]);
+
+    let bytes = round_trip!(
+        ClientboundSetBorderLerpSize,
+        ClientboundSetBorderLerpSize {
+            old_size: 0.0,
+            new_size: 0.0,
+            lerp_time: VarLong(-1),
+        }
+    );
+    assert_eq!(
+        bytes[16..],
+        [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]
+    );
+
+    // A sixth VarInt byte is never valid, even when the value would fit.
+    let mut r: &[u8] = &[0x80, 0x80, 0x80, 0x80, 0x80, 0x00];
+    assert!(ClientboundSetBorderWarningDelay::decode(&mut r).is_err());
+}
+
+#[test]
+fn player_abilities_and_info_remove() {
+    round_trip!(
+        ClientboundPlayerAbilities,
+        ClientboundPlayerAbilities {
+            flags: PlayerAbilityFlags::new()
+                .with_invulnerable(true)
+                .with_may_fly(true)
+                .with_instabuild(true),
+            flying_speed: 0.05,
+            walking_speed: 0.1,
+        }
+    );
+    let bytes = round_trip!(
+        ClientboundPlayerInfoRemove,
+        ClientboundPlayerInfoRemove { uuids: vec![] }
+    );
+    assert_eq!(bytes, [0x00]);
+    round_trip!(
+        ClientboundPlayerInfoRemove,
+        ClientboundPlayerInfoRemove {
+            uuids: vec![Uuid::nil(), Uuid::from_u128(u128::MAX)],
+        }
+    );
+}
+
+#[test]
+fn scoreboard_with_empty_strings_and_absent_optionals() {
+    let bytes = round_trip!(
+        ClientboundResetScore,
+        ClientboundResetScore {
+            owner: String::new(),
+            objective: None,
+        }
+    );
+    assert_eq!(bytes, [0x00, 0x00]);
+    let bytes = round_trip!(
+        ClientboundResetScore,
+        ClientboundResetScore {
+            owner: String::new(),
+            objective: Some(String::new()),
+        }
+    );
+    assert_eq!(bytes, [0x00, 0x01, 0x00]);
+
+    // An empty objective name clears the slot.
+    for slot in [
+        DisplaySlot::List,
+        DisplaySlot::Sidebar,
+        DisplaySlot::BelowName,
+        DisplaySlot::TeamSidebar(0),
+        DisplaySlot::TeamSidebar(15),
+    ] {
+        round_trip!(
+            ClientboundSetDisplayObjective,
+            ClientboundSetDisplayObjective {
+                slot,
+                objective: String::new(),
+            }
+        );
+    }
+
+    let info = ObjectiveInfo {
+        display_name: Text::text("Deaths"),
+        render_type: ObjectiveRenderType::Hearts,
+        number_format: Some(NumberFormat::Blank),
+    };
+    for update in [
+        ObjectiveUpdate::Add(ObjectiveInfo::new("Kills")),
+        ObjectiveUpdate::Remove,
+        ObjectiveUpdate::Change(info),
+    ] {
+        round_trip!(
+            ClientboundSetObjective,
+            ClientboundSetObjective {
+                objective: "kills".to_owned(),
+                update,
+            }
+        );
+    }
+
+    round_trip!(
+        ClientboundSetScore,
+        ClientboundSetScore {
+            owner: "Steve".to_owned(),
+            objective: "kills".to_owned(),
+            score: VarInt(i32::MIN),
+            display_name: None,
+            number_format: None,
+        }
+    );
+    round_trip!(
+        ClientboundSetScore,
+        ClientboundSetScore {
+            owner: "#hidden".to_owned(),
+            objective: String::new(),
+            score: VarInt(0),
+            display_name: Some(Text::text("")),
+            number_format: Some(NumberFormat::Fixed(Text::text("-"))),
+        }
+    );
+}
+
+#[test]
+fn time_sound_and_system_chat() {
+    let bytes = round_trip!(
+        ClientboundSetTime,
+        ClientboundSetTime {
+            game_time: 0,
+            clock_updates: vec![],
+        }
+    );
+    assert_eq!(bytes.len(), 8 + 1);
+    round_trip!(
+        ClientboundSetTime,
+        ClientboundSetTime {
+            game_time: i64::MAX,
+            clock_updates: vec![ClockUpdate {
+                clock: VarInt(0),
+                total_ticks: VarLong(24_000),
+                partial_tick: 0.5,
+                rate: 1.0,
+            }],
+        }
+    );
+
+    for sound in [
+        SoundId::Reference { id: VarInt(1) },
+        SoundId::Direct {
+            id: ident!("entity.item.pickup").into(),
+            range: None,
+        },
+        SoundId::Direct {
+            id: ident!("entity.item.pickup").into(),
+            range: Some(16.0),
+        },
+    ] {
+        round_trip!(
+            ClientboundSound,
+            ClientboundSound {
+                sound,
+                category: SoundCategory::Voice,
+                x: i32::MIN,
+                y: -1,
+                z: i32::MAX,
+                volume: 1.0,
+                pitch: 2.0,
+                seed: -1,
+            }
+        );
+    }
+
+    for overlay in [false, true] {
+        round_trip!(
+            ClientboundSystemChatPacket,
+            ClientboundSystemChatPacket {
+                content: Text::text(""),
+                overlay,
+            }
+        );
+    }
+}
+
+#[test]
+fn join_game_and_default_spawn_position() {
+    let death = GlobalPos {
+        dimension_name: ident!("the_nether").into(),
+        position: BlockPos::new(1, 2, 3),
+    };
+    let bytes = round_trip!(
+        ClientboundLogin,
+        ClientboundLogin {
+            player_id: 7,
+            hardcore: false,
+            dimensions: vec![ident!("overworld").into(), ident!("the_nether").into()],
+            max_players: VarInt(20),
+            chunk_radius: VarInt(10),
+            simulation_distance: VarInt(10),
+            reduced_debug_info: false,
+            show_death_screen: true,
+            do_limited_crafting: false,
+            player_spawn_info: PlayerSpawnInfo {
+                dimension_type_id: VarInt(0),
+                dimension: ident!("overworld").into(),
+                seed: 0x0123_4567_89ab_cdef,
+                game_mode: GameMode::Creative,
+                prev_game_mode: OptGameMode(None),
+                is_debug: false,
+                is_flat: false,
+                last_depth_location: Some(death.clone()),
+                portal_cooldown: VarInt(0),
+                sea_level: VarInt(63),
+            },
+            enforces_secure_chat: true,
+        }
+    );
+
+    let mut expected = vec![0, 0, 0, 7, 0];
+    // The dimension list: a count, then each name as a string.
+    expected.push(2);
+    expected.push(19);
+    expected.extend_from_slice(b"minecraft:overworld");
+    expected.push(20);
+    expected.extend_from_slice(b"minecraft:the_nether");
+    expected.extend_from_slice(&[20, 10, 10, 0, 1, 0]);
+    expected.push(0);
+    expected.push(19);
+    expected.extend_from_slice(b"minecraft:overworld");
+    expected.extend_from_slice(&0x0123_4567_89ab_cdef_u64.to_be_bytes());
+    // Game mode is one byte, and a missing previous game mode is -1.
+    expected.extend_from_slice(&[1, 0xFF]);
+    expected.extend_from_slice(&[0, 0, 1]);
+    expected.extend_from_slice(&encode(&death));
+    expected.extend_from_slice(&[0, 63, 1]);
+    assert_eq!(bytes, expected);
+
+    for prev_game_mode in [GameMode::Survival, GameMode::Spectator] {
+        let login = ClientboundLogin {
+            player_id: 7,
+            hardcore: true,
+            dimensions: vec![ident!("overworld").into()],
+            max_players: VarInt(1),
+            chunk_radius: VarInt(2),
+            simulation_distance: VarInt(2),
+            reduced_debug_info: true,
+            show_death_screen: false,
+            do_limited_crafting: true,
+            player_spawn_info: PlayerSpawnInfo {
+                game_mode: GameMode::Adventure,
+                prev_game_mode: OptGameMode(Some(prev_game_mode)),
+                ..Default::default()
+            },
+            enforces_secure_chat: false,
+        };
+        let bytes = round_trip!(ClientboundLogin, login);
+        let mut r = &bytes[..];
+        let decoded = ClientboundLogin::decode(&mut r).unwrap();
+        assert_eq!(decoded.player_spawn_info.game_mode, GameMode::Adventure);
+        assert_eq!(
+            decoded.player_spawn_info.prev_game_mode.0,
+            Some(prev_game_mode)
+        );
+    }
+
+    let bytes = round_trip!(
+        ClientboundSetDefaultSpawnPosition,
+        ClientboundSetDefaultSpawnPosition {
+            pos: GlobalPos {
+                dimension_name: ident!("overworld").into(),
+                position: BlockPos::new(-8, 64, 8),
+            },
+            yaw: 90.0,
+            pitch: 0.0,
+        }
+    );
+    assert_eq!(bytes.len(), 1 + 19 + 8 + 4 + 4);
+}
+
+#[test]
+fn set_entity_motion() {
+    // An item popping out of a block: 0.2 blocks/tick up, drifting sideways.
+    let bytes = round_trip!(
+        ClientboundSetEntityMotion,
+        ClientboundSetEntityMotion {
+            entity_id: VarInt(42),
+            velocity: LpVec3(DVec3::new(0.1, 0.2, -0.1)),
+        }
+    );
+    assert_eq!(bytes, [42, 0x29, 0x33, 0x73, 0x33, 0x33, 0x32]);
+
+    // Knockback past 3 blocks/tick carries the rest of its scale in a VarInt.
+    let bytes = round_trip!(
+        ClientboundSetEntityMotion,
+        ClientboundSetEntityMotion {
+            entity_id: VarInt(42),
+            velocity: LpVec3(DVec3::new(5.0, 0.0, 0.0)),
+        }
+    );
+    assert_eq!(bytes, [42, 0xF5, 0xFF, 0x7F, 0xFE, 0xFF, 0xFF, 0x01]);
+
+    let bytes = round_trip!(
+        ClientboundSetEntityMotion,
+        ClientboundSetEntityMotion {
+            entity_id: VarInt(42),
+            velocity: LpVec3::ZERO,
+        }
+    );
+    assert_eq!(bytes, [42, 0]);
+}
+
+#[test]
+fn set_simulation_distance() {
+    let bytes = round_trip!(
+        ClientboundSetSimulationDistance,
+        ClientboundSetSimulationDistance {
+            simulation_distance: VarInt(12),
+        }
+    );
+    assert_eq!(bytes, [12]);
+}
@@ -0,0 +1,52 @@
+use mcrs_protocol::item::ComponentPatch;
+use mcrs_protocol::packets::game::clientbound::ClientboundContainerSetSlot;
+use mcrs_protocol::{Decode, Encode, ItemId, Slot, VarInt};
+
+const STONE: ItemId = ItemId(1);
+
+fn encode<T: Encode>(value: &T) -> Vec<u8> {
+    let mut buf = Vec::new();
+    value.encode(&mut buf).expect("encode");
+    buf
+}
+
+fn decode(bytes: &[u8]) -> Slot {
+    let mut r = bytes;
+    let slot = Slot::decode(&mut r).expect("decode");
+    assert!(r.is_empty(), "decode must consume every byte");
+    slot
+}
+
+#[test]
+fn stack_and_empty_slot_wire_forms() {
+    let stone = Slot::new(STONE, 64, ComponentPatch::EMPTY);
+    // Count, item id, then empty added/removed component lists.
+    assert_eq!(encode(&stone), [0x40, 0x01, 0x00, 0x00]);
+    assert_eq!(decode(&[0x40, 0x01, 0x00, 0x00]), stone);
+
+    // An empty slot is a lone zero count, whatever item it names.
+    assert_eq!(encode(&Slot::EMPTY), [0x00]);
+    assert_eq!(encode(&Slot::new(STONE, 0, ComponentPatch::EMPTY)), [0x00]);
+    assert_eq!(decode(&[0x00]), Slot::EMPTY);
+}
+
+#[test]
+fn item_ids_are_var_ints() {
+    let pickaxe = Slot::new(ItemId(914), 1, ComponentPatch::EMPTY);
+    assert_eq!(encode(&pickaxe), [0x01, 0x92, 0x07, 0x00, 0x00]);
+    assert_eq!(decode(&[0x01, 0x92, 0x07, 0x00, 0x00]), pickaxe);
+}
+
+#[test]
+fn container_set_slot_payload() {
+    let packet = ClientboundContainerSetSlot {
+        container_id: VarInt(0),
+        state_seqno: VarInt(3),
+        slot: 36,
+        item: Slot::new(STONE, 64, ComponentPatch::EMPTY),
+    };
+    assert_eq!(
+        encode(&packet),
+        [0x00, 0x03, 0x00, 0x24, 0x40, 0x01, 0x00, 0x00]
+    );
+}
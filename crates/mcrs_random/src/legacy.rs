@@ -1,4 +1,4 @@
-use crate::{Random, block_pos_seed};
+use crate::{GaussianCache, Random, block_pos_seed};
 use bevy_math::IVec3;
 use md5::{Digest, Md5};
 use rand_xoshiro::rand_core::{Rng, TryRng};
@@ -14,12 +14,14 @@ const F64_MULTIPLIER: f64 = 1.0 / (1u64 << 30) as f64;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LegacyRandom {
     pub seed: u64,
+    pub(crate) gaussian: GaussianCache,
 }
 
 impl LegacyRandom {
     pub fn new(seed: u64) -> Self {
         Self {
             seed: (seed ^ MULTIPLIER) & MODULUS_MASK,
+            gaussian: GaussianCache::default(),
         }
     }
 
@@ -28,7 +30,7 @@ impl LegacyRandom {
         self.seed = self.seed.wrapping_mul(MULTIPLIER).wrapping_add(INCREMENT) & MODULUS_MASK;
     }
 
-    fn next_bits(&mut self, bits: usize) -> u64 {
+    pub(crate) fn next_bits(&mut self, bits: usize) -> u64 {
         self.advance();
         self.seed >> (MODULUS_BITS - bits)
     }
@@ -85,7 +87,6 @@ impl TryRng for LegacyRandom {
     }
 }
 
-
 impl Random for LegacyRandom {
     fn is_legacy(&self) -> bool {
         true
@@ -100,20 +101,12 @@ impl Random for LegacyRandom {
     }
 
     fn next_u32_bound(&mut self, bound: u32) -> u32 {
-        if (bound & (bound - 1)) == 0 {
-            let n = self.next_bits(31);
-            return ((bound as u64).wrapping_mul(n) >> 31) as u32;
-        }
-        let mut a;
-        let mut b;
-        loop {
-            a = self.next_bits(31) as i64;
-            b = a % bound as i64;
-            if a - b + (bound as i64 - 1) >= 0 {
-                break;
-            }
-        }
-        b as u32
+        java_bounded_int(bound, || self.next_bits(31) as u32)
+    }
+
+    /// Java's `nextLong(bound)` (`RandomSupport.boundedNextLong`).
+    fn next_u64_bound(&mut self, bound: u64) -> u64 {
+        java_bounded_long(bound, || self.next_java_long())
     }
 
     fn next_f32(&mut self) -> f32 {
@@ -126,6 +119,13 @@ impl Random for LegacyRandom {
         res
     }
 
+    fn next_gaussian(&mut self) -> f64 {
+        let mut gaussian = self.gaussian;
+        let value = gaussian.next(|| self.next_java_double());
+        self.gaussian = gaussian;
+        value
+    }
+
     fn fork(&mut self) -> Self {
         LegacyRandom::new(self.next_u64())
     }
@@ -134,7 +134,7 @@ impl Random for LegacyRandom {
     where
         T: Into<IVec3>,
     {
-        LegacyRandom::new(self.next_u64() ^ block_pos_seed(pos))
+        LegacyRandom::new((self.next_java_long() ^ block_pos_seed(pos)) as u64)
     }
 
     fn fork_hash(&mut self, seed: impl AsRef<[u8]>) -> Self {
@@ -145,6 +145,45 @@ impl Random for LegacyRandom {
     }
 }
 
+/// Java's `nextInt(bound)` over `next(31)` draws: a multiply for powers of
+/// two, otherwise rejection of the last, partial run of `bound` values.
+pub(crate) fn java_bounded_int(bound: u32, mut next_31: impl FnMut() -> u32) -> u32 {
+    if (bound & (bound - 1)) == 0 {
+        return ((bound as u64).wrapping_mul(next_31() as u64) >> 31) as u32;
+    }
+    let bound = bound as i32;
+    loop {
+        let a = next_31() as i32;
+        let b = a % bound;
+        // Java relies on `int` overflow here: draws from the last, partial
+        // run of `bound` values wrap negative and are rejected.
+        if a.wrapping_sub(b).wrapping_add(bound - 1) >= 0 {
+            return b as u32;
+        }
+    }
+}
+
+/// Java's `nextLong(bound)` (`RandomSupport.boundedNextLong`) over
+/// `nextLong()` draws: masks for powers of two, otherwise rejection-samples
+/// 63-bit values. `bound` must not exceed `i64::MAX`.
+pub(crate) fn java_bounded_long(bound: u64, mut next_long: impl FnMut() -> i64) -> u64 {
+    debug_assert!(bound as i64 > 0, "bound must be in 1..=i64::MAX");
+    let bound = bound as i64;
+    let m = bound - 1;
+    let r = next_long();
+    if bound & m == 0 {
+        return (r & m) as u64;
+    }
+    let mut u = ((r as u64) >> 1) as i64;
+    loop {
+        let r = u % bound;
+        if u.wrapping_add(m).wrapping_sub(r) >= 0 {
+            return r as u64;
+        }
+        u = ((next_long() as u64) >> 1) as i64;
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::Random;
@@ -178,6 +217,61 @@ mod test {
         assert_eq!(random.next_i32_bound(254), 74);
     }
 
+    /// `java.util.Random(12345).nextInt(bound)` for a power of two and two
+    /// rejection-loop bounds.
+    #[test]
+    fn next_u32_bound_matches_java() {
+        let cases: [(u32, [u32; 8]); 3] = [
+            (16, [5, 8, 14, 14, 13, 0, 5, 1]),
+            (10, [1, 0, 1, 8, 5, 4, 5, 2]),
+            (100, [51, 80, 41, 28, 55, 84, 75, 2]),
+        ];
+        for (bound, expected) in cases {
+            let mut random = LegacyRandom::new(12345);
+            for e in expected {
+                assert_eq!(random.next_u32_bound(bound), e, "bound {bound}");
+            }
+        }
+    }
+
+    /// A 31-bit draw of 2147483645 falls in the partial bucket for bound 10, so
+    /// Java rejects it and returns the next draw's remainder instead of 5.
+    #[test]
+    fn next_u32_bound_rejects_partial_bucket() {
+        let mut random = LegacyRandom {
+            seed: 64431386536617,
+            gaussian: Default::default(),
+        };
+        assert_eq!(random.next_u32_bound(10), 7);
+        assert_eq!(random.seed, 218195502956555);
+    }
+
+    #[test]
+    fn next_u64_bound() {
+        let mut random = LegacyRandom::new(123);
+        assert_eq!(random.next_u64_bound(16), 5);
+        assert_eq!(random.next_u64_bound(1000), 533);
+        assert_eq!(random.next_u64_bound(1 << 40), 92660180530);
+        assert_eq!(random.next_u64_bound(3_000_000_000_000), 2287889000349);
+        assert_eq!(random.next_u64_bound(i64::MAX as u64), 7432834261644332613);
+        assert_eq!(random.next_u64_bound((1 << 62) + 1), 663317486552589301);
+    }
+
+    #[test]
+    fn next_i64_bound_stays_in_range() {
+        let mut random = LegacyRandom::new(42);
+        let bound = 1_000_000_007i64;
+        let mut buckets = [0u32; 10];
+        for _ in 0..10_000 {
+            let v = random.next_i64_bound(bound);
+            assert!((0..bound).contains(&v));
+            buckets[(v * 10 / bound) as usize] += 1;
+        }
+        for count in buckets {
+            assert!((800..1200).contains(&count), "bucket count {count}");
+        }
+    }
+
     #[test]
     fn next_f32() {
         let mut random = LegacyRandom::new(123);
@@ -209,4 +303,43 @@ mod test {
             assert_eq!(format!("{:.7}", random.next_f64()), format!("{:.7}", e));
         }
     }
+
+    #[test]
+    fn next_gaussian() {
+        let mut random = LegacyRandom::new(123);
+        let expected = [
+            -1.4380493091409068,
+            0.6341950751776804,
+            0.22606201283216426,
+            0.2774600474034881,
+            0.18431915554393896,
+            -0.36521377741519273,
+            1.3520301643454316,
+            0.359236227002652,
+            -0.20527935071925305,
+            1.0174953185160527,
+            1.3715644272288952,
+            -1.8901985061831905,
+            -0.46712192935671604,
+            -0.6710741967435231,
+            -1.6793972862029432,
+            -0.26861132442483715,
+            -0.30000514126403843,
+            -1.421690929890814,
+            0.09530193550326646,
+            -0.3892962956652293,
+        ];
+        for e in expected {
+            assert_eq!(random.next_gaussian(), e);
+        }
+    }
+
+    #[test]
+    fn next_gaussian_caches_second_value() {
+        let mut random = LegacyRandom::new(123);
+        random.next_gaussian();
+        let seed = random.seed;
+        random.next_gaussian();
+        assert_eq!(random.seed, seed);
+    }
 }
@@ -1,10 +1,13 @@
 pub mod legacy;
+pub mod worldgen;
 pub mod xoroshiro;
 
 use crate::legacy::LegacyRandom;
 use crate::xoroshiro::XoroshiroRandom;
 use bevy_math::IVec3;
+use rand_xoshiro::Xoroshiro128PlusPlus;
 use rand_xoshiro::rand_core::{Rng, TryRng};
+use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
 
 pub trait Random: Rng + Clone {
@@ -26,6 +29,14 @@ pub trait Random: Rng + Clone {
         self.next_u64() as i64
     }
 
+    /// Uniform value in `0..bound`. Each generator consumes bits the way its vanilla
+    /// counterpart does, so sequences stay reproducible across implementations.
+    fn next_u64_bound(&mut self, bound: u64) -> u64;
+
+    fn next_i64_bound(&mut self, bound: i64) -> i64 {
+        self.next_u64_bound(bound as u64) as i64
+    }
+
     /// Java-accurate `nextLong()`: sign-extends both 32-bit halves before combining.
     ///
     /// Java's `java.util.Random.nextLong` computes `((long)(int)upper << 32) + (long)(int)lower`,
@@ -40,8 +51,20 @@ pub trait Random: Rng + Clone {
 
     fn next_f64(&mut self) -> f64;
 
+    /// Vanilla `nextGaussian()`: Marsaglia polar method over the generator's own
+    /// `nextDouble`, caching the second value of each pair for the following call.
+    fn next_gaussian(&mut self) -> f64;
+
     fn fork(&mut self) -> Self;
 
+    /// Vanilla `forkPositional().at(x, y, z)`, mixing in the position seed
+    /// [`block_pos_seed`].
+    ///
+    /// Legacy generators seed the child with `nextLong() ^ seed`; Xoroshiro
+    /// uses `(nextLong() ^ seed, nextLong())` as the low/high halves. The call
+    /// advances `self`, so forking the same parent state at the same position
+    /// always yields the same stream; clone the parent to fork it at several
+    /// positions the way a vanilla positional factory is reused.
     fn fork_at<T>(&mut self, pos: T) -> Self
     where
         T: Into<IVec3>;
@@ -49,27 +72,102 @@ pub trait Random: Rng + Clone {
     fn fork_hash(&mut self, seed: impl AsRef<[u8]>) -> Self;
 }
 
-fn block_pos_seed<T>(pos: T) -> u64
+/// Vanilla `Mth.getSeed(x, y, z)`, the per-position seed behind positional
+/// forks and block-position randomness (e.g. model offsets):
+///
+/// ```text
+/// l = (long)(x * 3129871) ^ (long)z * 116129781L ^ (long)y
+/// l = l * l * 42317861L + l * 11L
+/// seed = l >> 16
+/// ```
+///
+/// This is a hash, not `BlockPos.asLong`'s bit packing: the constants only
+/// scramble the coordinates. Java overflow is reproduced exactly: `x * 3129871`
+/// wraps in 32 bits before widening, everything after wraps in 64 bits, and
+/// the shift is arithmetic.
+pub fn block_pos_seed<T>(pos: T) -> i64
 where
     T: Into<IVec3>,
 {
     let pos = pos.into();
+    // Only the x term multiplies in 32 bits; z is widened first, as in Java.
     let mut l = (pos.x.wrapping_mul(3129871) as i64)
-        ^ (pos.z.wrapping_mul(116129781) as i64)
+        ^ (pos.z as i64).wrapping_mul(116129781)
         ^ (pos.y as i64);
     l = l
         .wrapping_mul(l)
         .wrapping_mul(42317861)
         .wrapping_add(l.wrapping_mul(11));
-    (l >> 16) as u64
+    l >> 16
+}
+
+/// Vanilla `MarsagliaPolarGaussian` state: the cached second value of the last pair
+/// (`nextNextGaussian` / `haveNextNextGaussian`). Kept as raw bits so generators stay `Eq`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct GaussianCache(Option<u64>);
+
+impl GaussianCache {
+    pub(crate) fn from_value(value: Option<f64>) -> Self {
+        Self(value.map(f64::to_bits))
+    }
+
+    pub(crate) fn value(self) -> Option<f64> {
+        self.0.map(f64::from_bits)
+    }
+
+    pub(crate) fn next(&mut self, mut next_f64: impl FnMut() -> f64) -> f64 {
+        if let Some(bits) = self.0.take() {
+            return f64::from_bits(bits);
+        }
+        let (x, y, s) = loop {
+            let x = 2.0 * next_f64() - 1.0;
+            let y = 2.0 * next_f64() - 1.0;
+            let s = x * x + y * y;
+            if s < 1.0 && s != 0.0 {
+                break (x, y, s);
+            }
+        };
+        let multiplier = (-2.0 * s.ln() / s).sqrt();
+        self.0 = Some((y * multiplier).to_bits());
+        x * multiplier
+    }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// Serializes as its [`RandomState`], so a restored source continues the
+/// same stream.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "RandomState", from = "RandomState")]
 pub enum RandomSource {
     Legacy(LegacyRandom),
     Xoroshiro(XoroshiroRandom),
 }
 
+/// Full internal state of a [`RandomSource`], including a pending cached Gaussian.
+/// Restoring it continues the stream exactly where [`RandomSource::snapshot`] left off.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RandomState {
+    Legacy {
+        seed: u64,
+        next_gaussian: Option<f64>,
+    },
+    Xoroshiro {
+        state: Xoroshiro128PlusPlus,
+        next_gaussian: Option<f64>,
+    },
+}
+
+impl From<RandomSource> for RandomState {
+    fn from(source: RandomSource) -> Self {
+        source.snapshot()
+    }
+}
+
+impl From<RandomState> for RandomSource {
+    fn from(state: RandomState) -> Self {
+        RandomSource::restore(state)
+    }
+}
+
 impl RandomSource {
     pub fn new(seed: u64, legacy: bool) -> Self {
         if legacy {
@@ -78,6 +176,44 @@ impl RandomSource {
             RandomSource::Xoroshiro(XoroshiroRandom::new(seed))
         }
     }
+
+    pub fn snapshot(&self) -> RandomState {
+        match self {
+            RandomSource::Legacy(random) => RandomState::Legacy {
+                seed: random.seed,
+                next_gaussian: random.gaussian.value(),
+            },
+            RandomSource::Xoroshiro(random) => RandomState::Xoroshiro {
+                state: random.0.clone(),
+                next_gaussian: random.1.value(),
+            },
+        }
+    }
+
+    pub fn restore(state: RandomState) -> Self {
+        match state {
+            RandomState::Legacy {
+                seed,
+                next_gaussian,
+            } => RandomSource::Legacy(LegacyRandom {
+                seed,
+                gaussian: GaussianCache::from_value(next_gaussian),
+            }),
+            RandomState::Xoroshiro {
+                state,
+                next_gaussian,
+            } => RandomSource::Xoroshiro(XoroshiroRandom(
+                state,
+                GaussianCache::from_value(next_gaussian),
+            )),
+        }
+    }
+
+    /// Reseed in place, like vanilla `setSeed`: a fresh generator of the same
+    /// kind, with any cached Gaussian dropped.
+    pub fn set_seed(&mut self, seed: i64) {
+        *self = RandomSource::new(seed as u64, self.is_legacy());
+    }
 }
 
 impl TryRng for RandomSource {
@@ -106,7 +242,6 @@ impl TryRng for RandomSource {
     }
 }
 
-
 impl Random for RandomSource {
     fn is_legacy(&self) -> bool {
         match self {
@@ -129,6 +264,13 @@ impl Random for RandomSource {
         }
     }
 
+    fn next_u64_bound(&mut self, bound: u64) -> u64 {
+        match self {
+            RandomSource::Legacy(random) => random.next_u64_bound(bound),
+            RandomSource::Xoroshiro(random) => random.next_u64_bound(bound),
+        }
+    }
+
     fn next_java_long(&mut self) -> i64 {
         match self {
             RandomSource::Legacy(random) => random.next_java_long(),
@@ -150,6 +292,13 @@ impl Random for RandomSource {
         }
     }
 
+    fn next_gaussian(&mut self) -> f64 {
+        match self {
+            RandomSource::Legacy(random) => random.next_gaussian(),
+            RandomSource::Xoroshiro(random) => random.next_gaussian(),
+        }
+    }
+
     fn fork(&mut self) -> Self {
         match self {
             RandomSource::Legacy(random) => RandomSource::Legacy(random.fork()),
@@ -174,3 +323,141 @@ impl Random for RandomSource {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{Random, RandomSource, RandomState, block_pos_seed};
+    use bevy_math::IVec3;
+
+    const POSITIONS: [(i32, i32, i32); 5] = [
+        (0, 0, 0),
+        (1, 2, 3),
+        (-5, 64, -300),
+        (100, -64, 100),
+        (-30000000, 319, 29999999),
+    ];
+
+    #[test]
+    fn block_pos_seed_matches_vanilla_get_seed() {
+        let expected: [i64; 5] = [
+            0,
+            -33674130277896,
+            28190198040937,
+            90625551368825,
+            -20892113470306,
+        ];
+        for ((x, y, z), e) in POSITIONS.into_iter().zip(expected) {
+            assert_eq!(block_pos_seed(IVec3::new(x, y, z)), e, "{x} {y} {z}");
+        }
+    }
+
+    /// Coordinates where `x * 3129871` overflows `int`, so the 32-bit wrap
+    /// before widening decides the result.
+    #[test]
+    fn block_pos_seed_wraps_x_in_32_bits() {
+        let cases = [
+            ((1000, 0, 0), -90802465359728),
+            ((-1000, -64, -1000), -63266930620133),
+            ((686, 70, -686), -65025635500790),
+            ((30000000, -64, -30000000), -36386240128100),
+        ];
+        for ((x, y, z), e) in cases {
+            assert_eq!(block_pos_seed(IVec3::new(x, y, z)), e, "{x} {y} {z}");
+        }
+    }
+
+    #[test]
+    fn legacy_fork_at_matches_vanilla() {
+        let expected: [[i32; 3]; 5] = [
+            [-1511962450, -303879429, -1547374546],
+            [-1546736551, 87825912, 90915984],
+            [-1766687615, 1643354034, 2017762692],
+            [134409785, 522303554, 741557163],
+            [1771379192, 1573200482, 1206078866],
+        ];
+        let parent = RandomSource::new(12345, true);
+        for ((x, y, z), values) in POSITIONS.into_iter().zip(expected) {
+            let mut forked = parent.clone().fork_at(IVec3::new(x, y, z));
+            let mut again = parent.clone().fork_at(IVec3::new(x, y, z));
+            for e in values {
+                assert_eq!(forked.next_i32(), e, "{x} {y} {z}");
+                assert_eq!(again.next_i32(), e);
+            }
+        }
+    }
+
+    #[test]
+    fn xoroshiro_fork_at_matches_vanilla() {
+        let expected: [[i64; 3]; 5] = [
+            [
+                782221843147428965,
+                -2977052922289139965,
+                5010184932150837941,
+            ],
+            [
+                -5850280193347195112,
+                4631265565370166692,
+                -2866909841086928401,
+            ],
+            [
+                -2620540984694072068,
+                8292782491322795608,
+                -169783700621661313,
+            ],
+            [
+                8003872026322572779,
+                -4149867323955384050,
+                -7830076094734493160,
+            ],
+            [
+                -6660904467105336710,
+                8159744598111768853,
+                -905949404333655322,
+            ],
+        ];
+        let parent = RandomSource::new(12345, false);
+        for ((x, y, z), values) in POSITIONS.into_iter().zip(expected) {
+            let mut forked = parent.clone().fork_at(IVec3::new(x, y, z));
+            let mut again = parent.clone().fork_at(IVec3::new(x, y, z));
+            for e in values {
+                assert_eq!(forked.next_i64(), e, "{x} {y} {z}");
+                assert_eq!(again.next_i64(), e);
+            }
+        }
+    }
+
+    fn assert_restores_stream(mut random: RandomSource) {
+        random.next_u64();
+        // Leave a cached Gaussian pending so the snapshot has to carry it.
+        random.next_gaussian();
+        let snapshot = random.snapshot();
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let state: RandomState = serde_json::from_str(&json).unwrap();
+        let mut restored = RandomSource::restore(state);
+        assert_eq!(restored, random);
+        // The source serializes through the same state.
+        let direct: RandomSource =
+            serde_json::from_str(&serde_json::to_string(&random).unwrap()).unwrap();
+        assert_eq!(direct, random);
+
+        for _ in 0..8 {
+            assert_eq!(restored.next_gaussian(), random.next_gaussian());
+            assert_eq!(restored.next_u64(), random.next_u64());
+            assert_eq!(
+                restored.fork_hash("minecraft:ore"),
+                random.fork_hash("minecraft:ore")
+            );
+        }
+    }
+
+    #[test]
+    fn legacy_snapshot_round_trip() {
+        assert_restores_stream(RandomSource::new(123, true));
+    }
+
+    #[test]
+    fn xoroshiro_snapshot_round_trip() {
+        assert_restores_stream(RandomSource::new(123, false));
+    }
+}
@@ -0,0 +1,326 @@
+use bevy_math::IVec3;
+use rand_xoshiro::rand_core::{Rng, TryRng};
+use std::convert::Infallible;
+
+use crate::legacy::{java_bounded_int, java_bounded_long};
+use crate::{GaussianCache, Random, RandomSource};
+
+const F32_MULTIPLIER: f32 = 1.0 / (1u64 << 24) as f32;
+const F64_MULTIPLIER: f64 = 1.0 / (1u64 << 53) as f64;
+
+/// Vanilla `WorldgenRandom`: the seedable random behind feature decoration,
+/// carvers and structure placement.
+///
+/// Every draw is built from `next(bits)` the way `java.util.Random` builds
+/// it, whatever the source underneath. A legacy source supplies its own
+/// `next(bits)`; a Xoroshiro source supplies the top `bits` of a full
+/// `nextLong`, so bounded ints, floats and doubles differ from drawing on
+/// the [`RandomSource`] directly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldgenRandom {
+    source: RandomSource,
+    /// Not cleared by [`set_seed`](Self::set_seed): vanilla's override only
+    /// reseeds the source.
+    gaussian: GaussianCache,
+}
+
+impl WorldgenRandom {
+    pub fn new(source: RandomSource) -> Self {
+        Self {
+            source,
+            gaussian: GaussianCache::default(),
+        }
+    }
+
+    pub fn into_source(self) -> RandomSource {
+        self.source
+    }
+
+    fn next_bits(&mut self, bits: usize) -> u64 {
+        match &mut self.source {
+            RandomSource::Legacy(random) => random.next_bits(bits),
+            RandomSource::Xoroshiro(random) => random.next_bits(bits),
+        }
+    }
+
+    /// Reseed the source, like vanilla `setSeed`.
+    pub fn set_seed(&mut self, seed: i64) {
+        self.source.set_seed(seed);
+    }
+
+    /// Vanilla `WorldgenRandom.setDecorationSeed`: seeds the per-chunk
+    /// population random from the level seed and the chunk's minimum block
+    /// coordinates, and returns that seed for [`set_feature_seed`].
+    ///
+    /// [`set_feature_seed`]: Self::set_feature_seed
+    pub fn set_decoration_seed(&mut self, level_seed: i64, min_x: i32, min_z: i32) -> i64 {
+        self.set_seed(level_seed);
+        let a = self.next_java_long() | 1;
+        let b = self.next_java_long() | 1;
+        let seed = (min_x as i64)
+            .wrapping_mul(a)
+            .wrapping_add((min_z as i64).wrapping_mul(b))
+            ^ level_seed;
+        self.set_seed(seed);
+        seed
+    }
+
+    /// Vanilla `WorldgenRandom.setFeatureSeed`: seeds the random for the
+    /// `index`-th feature of decoration step `step`.
+    pub fn set_feature_seed(&mut self, decoration_seed: i64, index: i32, step: i32) {
+        self.set_seed(
+            decoration_seed
+                .wrapping_add(index as i64)
+                .wrapping_add(10000 * step as i64),
+        );
+    }
+
+    /// Vanilla `WorldgenRandom.setLargeFeatureSeed`, used by carvers and
+    /// structure starts, from chunk (not block) coordinates.
+    pub fn set_large_feature_seed(&mut self, level_seed: i64, chunk_x: i32, chunk_z: i32) {
+        self.set_seed(level_seed);
+        let a = self.next_java_long();
+        let b = self.next_java_long();
+        self.set_seed(
+            (chunk_x as i64).wrapping_mul(a) ^ (chunk_z as i64).wrapping_mul(b) ^ level_seed,
+        );
+    }
+
+    /// Vanilla `WorldgenRandom.setLargeFeatureWithSalt`, used by structure
+    /// placement.
+    pub fn set_large_feature_with_salt(&mut self, level_seed: i64, x: i32, z: i32, salt: i32) {
+        self.set_seed(
+            (x as i64)
+                .wrapping_mul(341873128712)
+                .wrapping_add((z as i64).wrapping_mul(132897987541))
+                .wrapping_add(level_seed)
+                .wrapping_add(salt as i64),
+        );
+    }
+}
+
+impl TryRng for WorldgenRandom {
+    type Error = Infallible;
+
+    fn try_next_u32(&mut self) -> Result<u32, Self::Error> {
+        Ok(self.next_bits(32) as u32)
+    }
+
+    fn try_next_u64(&mut self) -> Result<u64, Self::Error> {
+        Ok(self.next_java_long() as u64)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Self::Error> {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+        Ok(())
+    }
+}
+
+impl Random for WorldgenRandom {
+    fn is_legacy(&self) -> bool {
+        self.source.is_legacy()
+    }
+
+    fn next_bool(&mut self) -> bool {
+        self.next_bits(1) != 0
+    }
+
+    fn next_u32_bound(&mut self, bound: u32) -> u32 {
+        java_bounded_int(bound, || self.next_bits(31) as u32)
+    }
+
+    fn next_u64_bound(&mut self, bound: u64) -> u64 {
+        java_bounded_long(bound, || self.next_java_long())
+    }
+
+    /// `((long)next(32) << 32) + next(32)`, both halves sign-extended.
+    fn next_java_long(&mut self) -> i64 {
+        let hi = self.next_bits(32) as i32 as i64;
+        let lo = self.next_bits(32) as i32 as i64;
+        (hi << 32).wrapping_add(lo)
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        self.next_bits(24) as f32 * F32_MULTIPLIER
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        let hi = self.next_bits(26);
+        let lo = self.next_bits(27);
+        ((hi << 27) + lo) as f64 * F64_MULTIPLIER
+    }
+
+    fn next_gaussian(&mut self) -> f64 {
+        let mut gaussian = self.gaussian;
+        let value = gaussian.next(|| self.next_f64());
+        self.gaussian = gaussian;
+        value
+    }
+
+    /// Vanilla hands back the source's own fork; it is wrapped here so the
+    /// child keeps this type. Take [`into_source`](Self::into_source) for the
+    /// source's draws.
+    fn fork(&mut self) -> Self {
+        Self::new(self.source.fork())
+    }
+
+    fn fork_at<T>(&mut self, pos: T) -> Self
+    where
+        T: Into<IVec3>,
+    {
+        Self::new(self.source.fork_at(pos))
+    }
+
+    fn fork_hash(&mut self, seed: impl AsRef<[u8]>) -> Self {
+        Self::new(self.source.fork_hash(seed))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::worldgen::WorldgenRandom;
+    use crate::{Random, RandomSource};
+
+    fn worldgen_random(legacy: bool) -> WorldgenRandom {
+        WorldgenRandom::new(RandomSource::new(0, legacy))
+    }
+
+    /// `(level seed, block x, block z)` and the decoration seeds vanilla's
+    /// `WorldgenRandom` derives from them, for legacy then Xoroshiro sources.
+    const DECORATION_CASES: [(i64, i32, i32, [i64; 2]); 3] = [
+        (12345, -32, 48, [3799801871930699945, -2476831614839651223]),
+        (
+            12345,
+            1600,
+            -2400,
+            [-5522652859438869543, -5285627773984901031],
+        ),
+        (
+            -4172144997902289642,
+            1600,
+            -2400,
+            [5328237895519262838, 6162328475074277622],
+        ),
+    ];
+
+    #[test]
+    fn decoration_seed_matches_vanilla() {
+        for (level_seed, x, z, expected) in DECORATION_CASES {
+            for (legacy, e) in [true, false].into_iter().zip(expected) {
+                let mut random = worldgen_random(legacy);
+                assert_eq!(random.set_decoration_seed(level_seed, x, z), e);
+                assert_eq!(
+                    random.clone().into_source(),
+                    RandomSource::new(e as u64, legacy)
+                );
+            }
+        }
+        // At the origin the coordinate terms vanish.
+        let mut random = worldgen_random(false);
+        assert_eq!(random.set_decoration_seed(12345, 0, 0), 12345);
+    }
+
+    #[test]
+    fn feature_seed_matches_vanilla() {
+        let expected = [
+            [1537931851, 2117319108],
+            [1199419280, 645683701],
+            [-1333088226, -881658257],
+        ];
+        for ((level_seed, x, z, _), values) in DECORATION_CASES.into_iter().zip(expected) {
+            for (legacy, e) in [true, false].into_iter().zip(values) {
+                let mut random = worldgen_random(legacy);
+                let decoration_seed = random.set_decoration_seed(level_seed, x, z);
+                random.set_feature_seed(decoration_seed, 3, 9);
+                assert_eq!(random.next_i32(), e, "{level_seed} {x} {z} {legacy}");
+            }
+        }
+    }
+
+    #[test]
+    fn large_feature_seed_matches_vanilla() {
+        let cases = [
+            (12345, -2, 3, [-8482453820433711153, 5607534668410794126]),
+            (
+                12345,
+                100,
+                -150,
+                [2532207470569476269, -5528985751735188501],
+            ),
+            (
+                -4172144997902289642,
+                100,
+                -150,
+                [-6708132519331038784, 5996567192002662262],
+            ),
+        ];
+        for (level_seed, x, z, expected) in cases {
+            for (legacy, e) in [true, false].into_iter().zip(expected) {
+                let mut random = worldgen_random(legacy);
+                random.set_large_feature_seed(level_seed, x, z);
+                assert_eq!(
+                    random.clone().into_source(),
+                    RandomSource::new(e as u64, legacy)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn large_feature_with_salt_matches_vanilla() {
+        for (legacy, e) in [(true, -360660732), (false, 1223714530)] {
+            let mut random = worldgen_random(legacy);
+            random.set_large_feature_with_salt(12345, 10, -3, 10387312);
+            assert_eq!(
+                random.clone().into_source(),
+                RandomSource::new(3020047724154, legacy)
+            );
+            assert_eq!(random.next_i32(), e);
+        }
+    }
+
+    /// Draws after `set_feature_seed(set_decoration_seed(12345, 1600, -2400), 3, 9)`,
+    /// from `java.util.Random` for the legacy source and from a
+    /// `java.util.Random` whose `next(bits)` takes the top bits of
+    /// Xoroshiro128++ for the Xoroshiro source, as `WorldgenRandom.next` does.
+    #[test]
+    fn draws_are_built_from_next_bits() {
+        for legacy in [true, false] {
+            let mut random = worldgen_random(legacy);
+            let decoration_seed = random.set_decoration_seed(12345, 1600, -2400);
+            random.set_feature_seed(decoration_seed, 3, 9);
+            let ints = [25, 256, 255, 254, 0x7FFFFFFF].map(|bound| random.next_i32_bound(bound));
+            let floats = [(); 3].map(|_| random.next_f32());
+            let doubles = [(); 3].map(|_| random.next_f64());
+            let bools = [(); 3].map(|_| random.next_bool());
+            let long = random.next_java_long();
+            let int = random.next_i32();
+            if legacy {
+                assert_eq!(ints, [15, 142, 194, 12, 932268698]);
+                assert_eq!(floats, [0.3230484, 0.2996598, 0.6555921]);
+                assert_eq!(
+                    doubles,
+                    [0.38943676294655494, 0.44301447242852565, 0.6890717638607112]
+                );
+                assert_eq!(bools, [false, true, true]);
+                assert_eq!((long, int), (8934177745258973227, -300217683));
+            } else {
+                assert_eq!(ints, [0, 6, 147, 146, 2132637098]);
+                assert_eq!(floats, [0.6854363, 0.32561767, 0.4297759]);
+                assert_eq!(
+                    doubles,
+                    [
+                        0.16482411172060596,
+                        0.34055119953643176,
+                        0.024554954634480763
+                    ]
+                );
+                assert_eq!(bools, [true, true, false]);
+                assert_eq!((long, int), (-7900034130879711819, 809098954));
+            }
+        }
+    }
+}
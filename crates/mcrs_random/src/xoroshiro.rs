@@ -4,7 +4,7 @@ use rand_xoshiro::Xoroshiro128PlusPlus;
 use rand_xoshiro::rand_core::{Rng, SeedableRng, TryRng};
 use std::convert::Infallible;
 
-use crate::{Random, block_pos_seed};
+use crate::{GaussianCache, Random, block_pos_seed};
 
 const F32_MULTIPLIER: f32 = 1.0 / (1u64 << 24) as f32;
 const F64_MULTIPLIER: f64 = 1.0 / (1u64 << 53) as f64;
@@ -14,7 +14,7 @@ const SILVER_RATIO: u64 = 0x6a09e667f3bcc909;
 const GOLDEN_RATIO: u64 = 0x9e3779b97f4a7c15;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct XoroshiroRandom(Xoroshiro128PlusPlus);
+pub struct XoroshiroRandom(pub(crate) Xoroshiro128PlusPlus, pub(crate) GaussianCache);
 
 impl XoroshiroRandom {
     pub fn new(seed: u64) -> Self {
@@ -26,10 +26,13 @@ impl XoroshiroRandom {
         let mut array = [0u8; 16];
         array[..8].copy_from_slice(&lo.to_le_bytes());
         array[8..16].copy_from_slice(&hi.to_le_bytes());
-        Self(Xoroshiro128PlusPlus::from_seed(array))
+        Self(
+            Xoroshiro128PlusPlus::from_seed(array),
+            GaussianCache::default(),
+        )
     }
 
-    fn next_bits(&mut self, bits: usize) -> u64 {
+    pub(crate) fn next_bits(&mut self, bits: usize) -> u64 {
         self.next_u64() >> (64 - bits)
     }
 }
@@ -58,6 +61,18 @@ impl Random for XoroshiroRandom {
         (m >> 32) as u32
     }
 
+    /// 64-bit counterpart of `next_u32_bound`: Lemire's multiply-high with rejection.
+    fn next_u64_bound(&mut self, bound: u64) -> u64 {
+        let mut m = self.next_u64() as u128 * bound as u128;
+        if (m as u64) < bound {
+            let threshold = bound.wrapping_neg() % bound;
+            while (m as u64) < threshold {
+                m = self.next_u64() as u128 * bound as u128;
+            }
+        }
+        (m >> 64) as u64
+    }
+
     fn next_f32(&mut self) -> f32 {
         self.next_bits(24) as f32 * F32_MULTIPLIER
     }
@@ -66,6 +81,13 @@ impl Random for XoroshiroRandom {
         self.next_bits(53) as f64 * F64_MULTIPLIER
     }
 
+    fn next_gaussian(&mut self) -> f64 {
+        let mut gaussian = self.1;
+        let value = gaussian.next(|| self.next_f64());
+        self.1 = gaussian;
+        value
+    }
+
     fn fork(&mut self) -> XoroshiroRandom {
         XoroshiroRandom::from_u128_seed(self.next_u64(), self.next_u64())
     }
@@ -74,12 +96,14 @@ impl Random for XoroshiroRandom {
     where
         T: Into<IVec3>,
     {
-        let seed = block_pos_seed(pos);
+        let seed = block_pos_seed(pos) as u64;
         let lo = self.next_u64() ^ seed;
         let hi = self.next_u64();
         XoroshiroRandom::from_u128_seed(lo, hi)
     }
 
+    /// Vanilla's `fromHashOf`: the MD5 digest of `seed`, split into two
+    /// big-endian longs, XORed with the next two longs of this stream.
     fn fork_hash(&mut self, seed: impl AsRef<[u8]>) -> Self {
         let l = self.next_u64();
         let h = self.next_u64();
@@ -112,7 +136,6 @@ impl TryRng for XoroshiroRandom {
     }
 }
 
-
 fn mix_starford_13(mut v: u64) -> u64 {
     v = (v ^ v >> 30).wrapping_mul(STAFFORD_1);
     v = (v ^ v >> 27).wrapping_mul(STAFFORD_2);
@@ -129,6 +152,69 @@ fn upgrade_seed_to_u128(seed: u64) -> (u64, u64) {
 mod test {
     use crate::Random;
     use crate::xoroshiro::XoroshiroRandom;
+    use rand_xoshiro::rand_core::Rng;
+
+    /// Big-endian halves of the MD5 digest of each name.
+    const HASHED_NAMES: [(&str, u64, u64); 3] = [
+        (
+            "minecraft:temperature",
+            0x5c7e6b29735f0d7f,
+            0xf7d86f1bbc734988,
+        ),
+        ("minecraft:terrain", 0x1ee555222ef96f14, 0xe2bedfdbebe43d33),
+        ("minecraft:offset", 0x080518cf6af25384, 0x3f3dfb40a54febd5),
+    ];
+
+    #[test]
+    fn fork_hash_xors_md5_into_parent_longs() {
+        for (name, lo, hi) in HASHED_NAMES {
+            let mut parent = XoroshiroRandom::new(0);
+            let forked = parent.clone().fork_hash(name);
+            let l = parent.next_u64();
+            let h = parent.next_u64();
+            assert_eq!(
+                forked,
+                XoroshiroRandom::from_u128_seed(l ^ lo, h ^ hi),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn fork_hash_next_i64() {
+        let expected: [(&str, [i64; 3]); 3] = [
+            (
+                "minecraft:temperature",
+                [
+                    -5679320831329058961,
+                    8942651225824489666,
+                    2811708535357556344,
+                ],
+            ),
+            (
+                "minecraft:terrain",
+                [
+                    1313131066091701251,
+                    6772908649396509309,
+                    -5153415820515040892,
+                ],
+            ),
+            (
+                "minecraft:offset",
+                [
+                    5613751443713371729,
+                    -6487773709469551859,
+                    2205725929366999122,
+                ],
+            ),
+        ];
+        for (name, values) in expected {
+            let mut random = XoroshiroRandom::new(0).fork_hash(name);
+            for e in values {
+                assert_eq!(random.next_i64(), e, "{name}");
+            }
+        }
+    }
 
     #[test]
     fn next_i64() {
@@ -180,6 +266,35 @@ mod test {
         assert_eq!(random.next_u32_bound(0x7FFFFFFF), 383715241);
     }
 
+    #[test]
+    fn next_u64_bound() {
+        let mut random = XoroshiroRandom::new(1);
+        assert_eq!(random.next_u64_bound(16), 15);
+        assert_eq!(random.next_u64_bound(1000), 349);
+        assert_eq!(random.next_u64_bound(1 << 40), 990918505777);
+        assert_eq!(random.next_u64_bound(3_000_000_000_000), 144755082671);
+        assert_eq!(random.next_u64_bound(i64::MAX as u64), 4047417815372597161);
+        assert_eq!(random.next_u64_bound((1 << 62) + 1), 694854707884546038);
+
+        let mut random = XoroshiroRandom::new(7);
+        assert_eq!(random.next_u64_bound(u64::MAX - 1), 18019197850713215902);
+    }
+
+    #[test]
+    fn next_i64_bound_stays_in_range() {
+        let mut random = XoroshiroRandom::new(42);
+        let bound = 1_000_000_007i64;
+        let mut buckets = [0u32; 10];
+        for _ in 0..10_000 {
+            let v = random.next_i64_bound(bound);
+            assert!((0..bound).contains(&v));
+            buckets[(v * 10 / bound) as usize] += 1;
+        }
+        for count in buckets {
+            assert!((800..1200).contains(&count), "bucket count {count}");
+        }
+    }
+
     #[test]
     fn next_f32() {
         let mut random = XoroshiroRandom::new(1);
@@ -211,4 +326,34 @@ mod test {
             assert_eq!(random.next_f64(), e);
         }
     }
+
+    #[test]
+    fn next_gaussian() {
+        let mut random = XoroshiroRandom::new(1);
+        let expected = [
+            0.48165962333698736,
+            -0.1630114679909305,
+            -0.20220526765580615,
+            -1.154593928550682,
+            -0.7160242977197302,
+            0.3252167507459002,
+            0.5615817327132491,
+            0.10277597707750918,
+            1.5667563254885413,
+            0.30836292491742207,
+            2.1927140477749316,
+            1.941113516664711,
+            2.1530182431295883,
+            -0.21310575682047994,
+            0.3979598977101832,
+            1.011722961977292,
+            1.0669978263288096,
+            0.3519010210935929,
+            -1.308362579181277,
+            0.6968883178096097,
+        ];
+        for &e in &expected {
+            assert_eq!(random.next_gaussian(), e);
+        }
+    }
 }
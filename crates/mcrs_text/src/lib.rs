@@ -1,6 +1,7 @@
 #![doc = include_str!("../README.md")]
 
 use std::borrow::Cow;
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
 use std::{fmt, ops};
@@ -9,6 +10,10 @@ use serde::de::Visitor;
 use serde::{de, Deserialize, Deserializer, Serialize};
 use uuid::Uuid;
 use mcrs_ident::Ident;
+use mcrs_nbt::deserializer::NbtReadHelper;
+use mcrs_nbt::serializer::WriteAdaptor;
+use mcrs_nbt::tag::NbtTag;
+use mcrs_nbt::{STRING_ID, from_bytes_unnamed, get_nbt_string, to_bytes_unnamed};
 
 pub mod color;
 mod into_text;
@@ -65,19 +70,39 @@ pub struct TextInner {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub font: Option<Font>,
 
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "nbt_bool"
+    )]
     pub bold: Option<bool>,
 
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "nbt_bool"
+    )]
     pub italic: Option<bool>,
 
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "nbt_bool"
+    )]
     pub underlined: Option<bool>,
 
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "nbt_bool"
+    )]
     pub strikethrough: Option<bool>,
 
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "nbt_bool"
+    )]
     pub obfuscated: Option<bool>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -139,7 +164,11 @@ pub enum TextContent {
     BlockNbt {
         block: Cow<'static, str>,
         nbt: Cow<'static, str>,
-        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            deserialize_with = "nbt_bool"
+        )]
         interpret: Option<bool>,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         separator: Option<Text>,
@@ -148,7 +177,11 @@ pub enum TextContent {
     EntityNbt {
         entity: Cow<'static, str>,
         nbt: Cow<'static, str>,
-        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            deserialize_with = "nbt_bool"
+        )]
         interpret: Option<bool>,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         separator: Option<Text>,
@@ -157,7 +190,11 @@ pub enum TextContent {
     StorageNbt {
         storage: Ident<Cow<'static, str>>,
         nbt: Cow<'static, str>,
-        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            deserialize_with = "nbt_bool"
+        )]
         interpret: Option<bool>,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         separator: Option<Text>,
@@ -255,6 +292,12 @@ impl Text {
         }))
     }
 
+    /// Constructs a new plain text object, like vanilla's
+    /// `Component.literal`. Same as [`Text::text`].
+    pub fn literal(plain: impl Into<Cow<'static, str>>) -> Self {
+        Self::text(plain)
+    }
+
     /// Create translated text based on the given translation key, with extra
     /// text components to be inserted into the slots of the translation text.
     pub fn translate(key: impl Into<Cow<'static, str>>, with: impl Into<Vec<Text>>) -> Self {
@@ -497,6 +540,72 @@ impl Text {
 
         result
     }
+
+    /// Serializes to JSON, the format used before the play state: status
+    /// responses and login disconnects.
+    pub fn to_json(&self) -> String {
+        self.to_string()
+    }
+
+    /// Parses JSON text. An empty string is an empty text.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        json.parse()
+    }
+
+    /// Serializes to network NBT, the format used in play packets since
+    /// 1.20.3: a bare String tag for unstyled text without children, a
+    /// nameless compound otherwise.
+    pub fn to_nbt(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.write_nbt(&mut buf)
+            .expect("writing NBT to a Vec cannot fail");
+        buf
+    }
+
+    /// Writes the [`Text::to_nbt`] encoding to `w`.
+    pub fn write_nbt(&self, mut w: impl Write) -> Result<(), mcrs_nbt::Error> {
+        match self.plain_text() {
+            Some(text) => {
+                w.write_all(&[STRING_ID])
+                    .map_err(mcrs_nbt::Error::Incomplete)?;
+                NbtTag::String(text.to_owned()).serialize_data(&mut WriteAdaptor::new(w))
+            }
+            None => to_bytes_unnamed(self, w),
+        }
+    }
+
+    /// Reads one network NBT text from `r`, leaving it just past the tag.
+    pub fn read_nbt(mut r: impl Read + Seek) -> Result<Self, mcrs_nbt::Error> {
+        let mut tag_id = [0];
+        r.read_exact(&mut tag_id)
+            .map_err(mcrs_nbt::Error::Incomplete)?;
+        if tag_id[0] == STRING_ID {
+            let text = get_nbt_string(&mut NbtReadHelper::new(r))?;
+            return Ok(Self::text(text));
+        }
+        r.seek(SeekFrom::Current(-1))
+            .map_err(mcrs_nbt::Error::Incomplete)?;
+        from_bytes_unnamed(r)
+    }
+
+    /// The content when this is nothing but unstyled literal text.
+    fn plain_text(&self) -> Option<&str> {
+        let TextContent::Text { text } = &self.content else {
+            return None;
+        };
+        let plain = self.extra.is_empty()
+            && self.color.is_none()
+            && self.font.is_none()
+            && self.bold.is_none()
+            && self.italic.is_none()
+            && self.underlined.is_none()
+            && self.strikethrough.is_none()
+            && self.obfuscated.is_none()
+            && self.click_event.is_none()
+            && self.hover_event.is_none()
+            && self.insertion.is_none();
+        plain.then_some(text)
+    }
 }
 
 impl Deref for Text {
@@ -582,6 +691,33 @@ impl Default for TextContent {
     }
 }
 
+/// A style flag, which NBT stores as a byte rather than a boolean.
+fn nbt_bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<bool>, D::Error> {
+    struct BoolVisitor;
+
+    impl Visitor<'_> for BoolVisitor {
+        type Value = Option<bool>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a boolean or a byte")
+        }
+
+        fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+            Ok(Some(v))
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            Ok(Some(v != 0))
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            Ok(Some(v != 0))
+        }
+    }
+
+    deserializer.deserialize_any(BoolVisitor)
+}
+
 impl<'de> Deserialize<'de> for Text {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         struct TextVisitor;
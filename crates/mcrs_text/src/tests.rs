@@ -134,3 +134,45 @@ fn text_to_legacy_lossy() {
          formatted blue text"
     );
 }
+
+fn nested() -> Text {
+    Text::literal("Welcome, ").color(Color::GOLD).bold()
+        + Text::translate("multiplayer.player.joined", ["Steve".italic()])
+            .color(Color::YELLOW)
+            .not_bold()
+        + "!"
+}
+
+#[test]
+fn nested_json_round_trip() {
+    let before = nested();
+    let json = before.to_json();
+    assert_eq!(
+        json,
+        r#"{"text":"Welcome, ","color":"gold","bold":true,"extra":[{"translate":"multiplayer.player.joined","with":[{"text":"Steve","italic":true}],"color":"yellow","bold":false},{"text":"!"}]}"#
+    );
+    assert_eq!(Text::from_json(&json).unwrap(), before);
+}
+
+#[test]
+fn nested_nbt_round_trip() {
+    let before = nested();
+    let nbt = before.to_nbt();
+    assert_eq!(nbt[0], mcrs_nbt::COMPOUND_ID);
+    let mut cursor = std::io::Cursor::new(&nbt);
+    assert_eq!(Text::read_nbt(&mut cursor).unwrap(), before);
+    assert_eq!(cursor.position() as usize, nbt.len());
+}
+
+#[test]
+fn plain_text_nbt_is_a_bare_string() {
+    let before = Text::literal("hi");
+    let nbt = before.to_nbt();
+    assert_eq!(nbt, [mcrs_nbt::STRING_ID, 0, 2, b'h', b'i']);
+    assert_eq!(Text::read_nbt(std::io::Cursor::new(&nbt)).unwrap(), before);
+}
+
+#[test]
+fn nested_to_legacy() {
+    assert_eq!(nested().to_legacy_lossy(), "§6§lWelcome, §r§e!");
+}
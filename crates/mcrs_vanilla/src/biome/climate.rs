@@ -1,3 +1,4 @@
+use mcrs_minecraft_worldgen::climate::{Param, ParamPoint, QuantizedCoord};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,3 +18,30 @@ pub enum ParameterRange {
     Point(f64),
     Range([f64; 2]),
 }
+
+impl ParameterRange {
+    pub fn to_param(&self) -> Param {
+        match *self {
+            ParameterRange::Point(value) => Param::from(value),
+            ParameterRange::Range([min, max]) => Param {
+                min: QuantizedCoord::from(min),
+                max: QuantizedCoord::from(max),
+            },
+        }
+    }
+}
+
+impl ClimateParameters {
+    /// Quantized parameter point used by the multi-noise biome search.
+    pub fn to_param_point(&self) -> ParamPoint {
+        ParamPoint::new(
+            self.temperature.to_param(),
+            self.humidity.to_param(),
+            self.continentalness.to_param(),
+            self.erosion.to_param(),
+            self.depth.to_param(),
+            self.weirdness.to_param(),
+            self.offset,
+        )
+    }
+}
@@ -20,6 +20,8 @@ use crate::ResourceLocation;
 #[derive(Debug, Clone, Serialize, Deserialize, TypePath)]
 pub struct Biome {
     pub temperature: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature_modifier: Option<String>,
     pub downfall: f32,
     pub has_precipitation: bool,
     pub effects: BiomeEffects,
@@ -43,12 +45,18 @@ impl Biome {
 /// Biome data subset for NETWORK_CODEC — omits server-only generation settings.
 ///
 /// Sent to clients during Configuration; excludes carvers, features,
-/// spawners, and spawn_costs which are irrelevant to the client.
+/// spawners, and spawn_costs which are irrelevant to the client. The sky and
+/// fog colours live in `attributes`, so it has to be forwarded alongside
+/// `effects`.
 #[derive(Debug, Clone, Serialize)]
 pub struct NetworkBiome {
     pub temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_modifier: Option<String>,
     pub downfall: f32,
     pub has_precipitation: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<serde_json::Value>,
     pub effects: BiomeEffects,
 }
 
@@ -56,8 +64,10 @@ impl From<&Biome> for NetworkBiome {
     fn from(biome: &Biome) -> Self {
         NetworkBiome {
             temperature: biome.temperature,
+            temperature_modifier: biome.temperature_modifier.clone(),
             downfall: biome.downfall,
             has_precipitation: biome.has_precipitation,
+            attributes: biome.attributes.clone(),
             effects: biome.effects.clone(),
         }
     }
@@ -272,6 +282,51 @@ mod tests {
         assert_eq!(network.has_precipitation, biome.has_precipitation);
     }
 
+    fn network_nbt(name: &str) -> mcrs_nbt::compound::NbtCompound {
+        let path = assets_dir().join(format!("minecraft/worldgen/biome/{name}.json"));
+        let biome: Biome = serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+        mcrs_nbt::to_nbt_compound(&NetworkBiome::from(&biome)).unwrap()
+    }
+
+    #[test]
+    fn network_biome_nbt_carries_client_fields() {
+        for name in ["plains", "ocean", "desert", "frozen_ocean"] {
+            let nbt = network_nbt(name);
+            assert!(nbt.get_float("temperature").is_some(), "{name}");
+            assert!(nbt.get_float("downfall").is_some(), "{name}");
+            assert!(nbt.get_bool("has_precipitation").is_some(), "{name}");
+            let effects = nbt.get_compound("effects").unwrap();
+            assert!(effects.get_string("water_color").is_some(), "{name}");
+            let attributes = nbt.get_compound("attributes").unwrap();
+            assert!(
+                attributes.get("minecraft:visual/sky_color").is_some(),
+                "{name}: sky colour must reach the client"
+            );
+        }
+
+        let plains = network_nbt("plains");
+        assert_eq!(plains.get_float("temperature"), Some(0.8));
+        assert_eq!(plains.get_bool("has_precipitation"), Some(true));
+        assert!(plains.get("temperature_modifier").is_none());
+        assert_eq!(
+            network_nbt("frozen_ocean").get_string("temperature_modifier"),
+            Some("frozen")
+        );
+    }
+
+    #[test]
+    fn biome_round_trips_through_json() {
+        let bytes =
+            std::fs::read(assets_dir().join("minecraft/worldgen/biome/frozen_ocean.json")).unwrap();
+        let biome: Biome = serde_json::from_slice(&bytes).unwrap();
+        let again: Biome =
+            serde_json::from_value(serde_json::to_value(&biome).unwrap()).unwrap();
+        assert_eq!(again.temperature_modifier.as_deref(), Some("frozen"));
+        assert_eq!(again.attributes, biome.attributes);
+        assert_eq!(again.effects.water_color, biome.effects.water_color);
+        assert_eq!(again.carvers, biome.carvers);
+    }
+
     #[test]
     fn deserialize_plains_biome() {
         let bytes = std::fs::read(
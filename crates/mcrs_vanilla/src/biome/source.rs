@@ -1,10 +1,13 @@
 use std::sync::Arc;
 
 use bevy_asset::{Handle, LoadContext, UntypedAssetId};
+use bevy_math::IVec3;
+use mcrs_minecraft_worldgen::climate::{ParameterList, sample_climate};
+use mcrs_minecraft_worldgen::density_function::{DensityCache, NoiseRouter};
 use serde::Deserialize;
 
-use super::climate::ClimateParameters;
 use super::Biome;
+use super::climate::{ClimateParameters, ParameterRange};
 use crate::ResourceLocation;
 
 // ===========================================================================
@@ -211,12 +214,106 @@ impl BiomeSource {
 pub struct MultiNoiseBiomeSource {
     pub preset: Option<ResourceLocation<Arc<str>>>,
     pub biomes: Option<Vec<MultiNoiseBiomeEntry>>,
+    /// Search tree over `biomes`, mapping each parameter point to its entry index.
+    /// `None` for presets without a built-in list and for empty lists.
+    parameter_list: Option<ParameterList<usize>>,
+}
+
+/// Why [`MultiNoiseBiomeSource::get_noise_biome`] found no biome.
+#[derive(Debug, thiserror::Error)]
+pub enum NoiseBiomeError {
+    #[error("no parameter list for multi-noise preset {0}")]
+    UnsupportedPreset(ResourceLocation<Arc<str>>),
+    #[error("multi-noise biome source has no biomes")]
+    NoBiomes,
 }
 
 #[derive(Debug, Clone)]
 pub struct MultiNoiseBiomeEntry {
     pub parameters: ClimateParameters,
     pub biome: Handle<Biome>,
+    /// Resource location of `biome`, stable across AssetServers (see `BiomeSource::Beta`).
+    pub biome_id: ResourceLocation<Arc<str>>,
+}
+
+impl MultiNoiseBiomeSource {
+    pub fn new(
+        preset: Option<ResourceLocation<Arc<str>>>,
+        biomes: Option<Vec<MultiNoiseBiomeEntry>>,
+    ) -> Self {
+        let parameter_list = biomes.as_ref().and_then(|entries| {
+            ParameterList::new(
+                entries
+                    .iter()
+                    .enumerate()
+                    .map(|(i, entry)| (entry.parameters.to_param_point(), i)),
+            )
+        });
+        MultiNoiseBiomeSource {
+            preset,
+            biomes,
+            parameter_list,
+        }
+    }
+
+    /// Biome at a biome-grid (quart) position: samples the router's six climate
+    /// parameters there and returns the entry nearest in parameter space.
+    ///
+    /// Presets are resolved to their parameter list at load; one without a
+    /// built-in list (see [`preset_parameter_list`]) is an error.
+    pub fn get_noise_biome(
+        &self,
+        quart_pos: IVec3,
+        router: &NoiseRouter,
+        cache: &mut DensityCache,
+    ) -> Result<&MultiNoiseBiomeEntry, NoiseBiomeError> {
+        let (Some(parameter_list), Some(entries)) = (&self.parameter_list, &self.biomes) else {
+            return Err(match &self.preset {
+                Some(preset) if self.biomes.is_none() => {
+                    NoiseBiomeError::UnsupportedPreset(preset.clone())
+                }
+                _ => NoiseBiomeError::NoBiomes,
+            });
+        };
+        let index = *parameter_list.find_value(&sample_climate(router, quart_pos, cache));
+        Ok(&entries[index])
+    }
+}
+
+/// The parameter list a multi-noise `preset` stands for, as vanilla's
+/// `MultiNoiseBiomeSourceParameterList.Preset` builds it in code.
+///
+/// Only `minecraft:nether` is built in. The overworld list comes from
+/// vanilla's `OverworldBiomeBuilder`, which is not ported yet.
+pub fn preset_parameter_list(
+    preset: &ResourceLocation<Arc<str>>,
+) -> Option<Vec<(ClimateParameters, ResourceLocation<Arc<str>>)>> {
+    match preset.as_str() {
+        "minecraft:nether" => Some(
+            [
+                (0.0, 0.0, 0.0, "nether_wastes"),
+                (0.0, -0.5, 0.0, "soul_sand_valley"),
+                (0.4, 0.0, 0.0, "crimson_forest"),
+                (0.0, 0.5, 0.375, "warped_forest"),
+                (-0.5, 0.0, 0.175, "basalt_deltas"),
+            ]
+            .into_iter()
+            .map(|(temperature, humidity, offset, biome)| {
+                let parameters = ClimateParameters {
+                    temperature: ParameterRange::Point(temperature),
+                    humidity: ParameterRange::Point(humidity),
+                    continentalness: ParameterRange::Point(0.0),
+                    erosion: ParameterRange::Point(0.0),
+                    depth: ParameterRange::Point(0.0),
+                    weirdness: ParameterRange::Point(0.0),
+                    offset,
+                };
+                (parameters, ResourceLocation::minecraft(biome))
+            })
+            .collect(),
+        ),
+        _ => None,
+    }
 }
 
 // ===========================================================================
@@ -316,18 +413,28 @@ impl ProtoBiomeSource {
 
 impl ProtoMultiNoiseBiomeSource {
     fn resolve(self, ctx: &mut LoadContext) -> MultiNoiseBiomeSource {
-        MultiNoiseBiomeSource {
-            preset: self.preset,
-            biomes: self.biomes.map(|entries| {
+        let entries: Option<Vec<_>> = match self.biomes {
+            Some(entries) => Some(
+                entries
+                    .into_iter()
+                    .map(|e| (e.parameters, e.biome))
+                    .collect(),
+            ),
+            None => self.preset.as_ref().and_then(preset_parameter_list),
+        };
+        MultiNoiseBiomeSource::new(
+            self.preset,
+            entries.map(|entries| {
                 entries
                     .into_iter()
-                    .map(|e| MultiNoiseBiomeEntry {
-                        parameters: e.parameters,
-                        biome: Biome::load(ctx, &e.biome),
+                    .map(|(parameters, biome_id)| MultiNoiseBiomeEntry {
+                        parameters,
+                        biome: Biome::load(ctx, &biome_id),
+                        biome_id,
                     })
                     .collect()
             }),
-        }
+        )
     }
 }
 
@@ -417,6 +524,24 @@ mod tests {
         assert_eq!(beta_get_biome(0.3, 0.8), BetaLandBiome::Taiga);
     }
 
+    #[test]
+    fn nether_preset_has_a_parameter_list() {
+        let entries = preset_parameter_list(&ResourceLocation::minecraft("nether"))
+            .expect("nether preset is built in");
+        let biomes: Vec<&str> = entries.iter().map(|(_, biome)| biome.as_str()).collect();
+        assert_eq!(
+            biomes,
+            [
+                "minecraft:nether_wastes",
+                "minecraft:soul_sand_valley",
+                "minecraft:crimson_forest",
+                "minecraft:warped_forest",
+                "minecraft:basalt_deltas",
+            ]
+        );
+        assert!(preset_parameter_list(&ResourceLocation::minecraft("overworld")).is_none());
+    }
+
     #[test]
     fn ocean_biome_mapping() {
         assert_eq!(ocean_biome_for(BetaLandBiome::IceDesert), 0);
@@ -253,3 +253,93 @@ pub enum CardinalLight {
     #[serde(rename = "nether")]
     Nether,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcrs_nbt::compound::NbtCompound;
+    use mcrs_nbt::tag::NbtTag;
+    use std::path::PathBuf;
+
+    fn network_nbt(name: &str) -> NbtCompound {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../assets/minecraft/dimension_type")
+            .join(format!("{name}.json"));
+        let bytes = std::fs::read(path).unwrap();
+        let proto: ProtoDimensionType = serde_json::from_slice(&bytes).unwrap();
+        // Mirrors `From<&DimensionType>`, which needs a loaded infiniburn tag.
+        let network = NetworkDimensionType {
+            has_skylight: proto.has_skylight,
+            has_ceiling: proto.has_ceiling,
+            has_ender_dragon_fight: proto.has_ender_dragon_fight,
+            coordinate_scale: proto.coordinate_scale,
+            min_y: proto.min_y,
+            height: proto.height,
+            logical_height: proto.logical_height,
+            infiniburn: proto.infiniburn,
+            ambient_light: proto.ambient_light,
+            monster_spawn_block_light_limit: proto.monster_spawn_block_light_limit,
+            monster_spawn_light_level: proto.monster_spawn_light_level,
+            skybox: proto.skybox,
+            cardinal_light: proto.cardinal_light,
+            has_fixed_time: proto.has_fixed_time,
+            attributes: proto.attributes,
+            timelines: proto.timelines,
+            default_clock: proto.default_clock,
+        };
+        mcrs_nbt::to_nbt_compound(&network).unwrap()
+    }
+
+    #[test]
+    fn overworld_network_nbt_has_client_field_types() {
+        let nbt = network_nbt("overworld");
+        assert_eq!(nbt.get_bool("has_skylight"), Some(true));
+        assert_eq!(nbt.get_bool("has_ceiling"), Some(false));
+        assert_eq!(nbt.get_double("coordinate_scale"), Some(1.0));
+        assert_eq!(nbt.get_int("min_y"), Some(-64));
+        assert_eq!(nbt.get_int("height"), Some(384));
+        assert_eq!(nbt.get_int("logical_height"), Some(384));
+        assert_eq!(nbt.get_float("ambient_light"), Some(0.0));
+        assert_eq!(nbt.get_int("monster_spawn_block_light_limit"), Some(0));
+        assert_eq!(
+            nbt.get_string("infiniburn"),
+            Some("#minecraft:infiniburn_overworld")
+        );
+        assert_eq!(nbt.get_string("skybox"), Some("overworld"));
+        assert_eq!(nbt.get_string("cardinal_light"), Some("default"));
+
+        let light = nbt.get_compound("monster_spawn_light_level").unwrap();
+        assert_eq!(light.get_int("max_inclusive"), Some(7));
+    }
+
+    #[test]
+    fn vanilla_dimension_types_carry_required_fields() {
+        let required: [(&str, fn(&NbtTag) -> bool); 12] = [
+            ("has_skylight", |t| matches!(t, NbtTag::Byte(_))),
+            ("has_ceiling", |t| matches!(t, NbtTag::Byte(_))),
+            ("has_ender_dragon_fight", |t| matches!(t, NbtTag::Byte(_))),
+            ("coordinate_scale", |t| matches!(t, NbtTag::Double(_))),
+            ("min_y", |t| matches!(t, NbtTag::Int(_))),
+            ("height", |t| matches!(t, NbtTag::Int(_))),
+            ("logical_height", |t| matches!(t, NbtTag::Int(_))),
+            ("infiniburn", |t| matches!(t, NbtTag::String(_))),
+            ("ambient_light", |t| matches!(t, NbtTag::Float(_))),
+            ("monster_spawn_block_light_limit", |t| {
+                matches!(t, NbtTag::Int(_))
+            }),
+            ("monster_spawn_light_level", |t| {
+                matches!(t, NbtTag::Int(_) | NbtTag::Compound(_))
+            }),
+            ("skybox", |t| matches!(t, NbtTag::String(_))),
+        ];
+        for name in ["overworld", "overworld_caves", "the_nether", "the_end"] {
+            let nbt = network_nbt(name);
+            for (field, has_type) in &required {
+                let tag = nbt
+                    .get(field)
+                    .unwrap_or_else(|| panic!("{name}: missing {field}"));
+                assert!(has_type(tag), "{name}: {field} has the wrong tag {tag:?}");
+            }
+        }
+    }
+}
@@ -9,6 +9,7 @@ use mcrs_vanilla::biome::{Biome, BiomeEffects, BiomeSpawners, NetworkBiome};
 fn fixture_biome() -> Biome {
     Biome {
         temperature: 0.8,
+        temperature_modifier: None,
         downfall: 0.4,
         has_precipitation: true,
         effects: BiomeEffects {